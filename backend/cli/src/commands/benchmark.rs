@@ -1,30 +1,128 @@
 //! Benchmark command implementation
 
+use std::io::Write;
 use std::path::Path;
-use zkipfs_proof_core::error::Result;
+use zkipfs_proof_core::{
+    error::{ProofError, Result},
+    ipfs::IpfsProcessor,
+    profiling::{BenchmarkConfig, BenchmarkResults, BenchmarkRunner},
+};
+use crate::utils::format_duration;
+
+/// Size of the generated file benchmarked when no file is given
+const SYNTHETIC_FILE_SIZE: usize = 16 * 1024 * 1024;
 
 /// Run performance benchmarks
 pub async fn execute(
     file: Option<&Path>,
     iterations: u32,
     output: Option<&Path>,
+    baseline: Option<&Path>,
+    fail_on_regression: bool,
+    regression_threshold: f64,
 ) -> Result<()> {
+    if iterations == 0 {
+        return Err(ProofError::invalid_input_error("iterations", "At least one iteration is required"));
+    }
+    if !(regression_threshold.is_finite() && regression_threshold >= 0.0) {
+        return Err(ProofError::invalid_input_error(
+            "regression_threshold",
+            "Regression threshold must be a non-negative percentage"
+        ));
+    }
+
     println!("🚀 Running zkIPFS-Proof benchmarks...");
     println!("Iterations: {}", iterations);
-    
-    if let Some(file_path) = file {
-        println!("Test file: {}", file_path.display());
-    } else {
-        println!("Using synthetic test data");
+
+    // Keeps the synthetic file alive until the benchmark finishes
+    let synthetic;
+    let file_path = match file {
+        Some(file_path) => {
+            println!("Test file: {}", file_path.display());
+            file_path
+        }
+        None => {
+            println!("Using synthetic test data");
+            synthetic = synthetic_file()?;
+            synthetic.path()
+        }
+    };
+
+    let mut runner = BenchmarkRunner::new(BenchmarkConfig {
+        iterations: iterations as usize,
+        warmup_iterations: 1,
+        regression_threshold_percent: regression_threshold,
+        ..BenchmarkConfig::default()
+    });
+    if let Some(baseline_path) = baseline {
+        runner.load_baseline(baseline_path)?;
+        println!("Baseline: {}", baseline_path.display());
     }
-    
-    // This would implement actual benchmarking
-    println!("⚠️  Benchmark implementation coming soon!");
-    
+
+    let processor = IpfsProcessor::new().await?;
+    let handle = tokio::runtime::Handle::current();
+    let results = tokio::task::block_in_place(|| {
+        runner.run_benchmark("ipfs_chunking", || {
+            handle.block_on(processor.process_file(file_path))
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+        })
+    });
+
+    if results.successful_iterations == 0 {
+        return Err(ProofError::internal_error("Every benchmark iteration failed", None));
+    }
+    print_results(&results);
+
     if let Some(output_path) = output {
-        println!("Results will be saved to: {}", output_path.display());
+        results.save_baseline(output_path)?;
+        println!("Results saved to: {}", output_path.display());
     }
-    
+
+    if let Some(analysis) = &results.regression_analysis {
+        if analysis.regression_detected && fail_on_regression {
+            eprintln!(
+                "❌ Throughput regressed by {:.1}% (threshold {:.1}%)",
+                -analysis.performance_change,
+                regression_threshold
+            );
+            std::process::exit(1);
+        }
+    }
+
     Ok(())
 }
 
+/// Writes pseudo-random data to a temporary file
+fn synthetic_file() -> Result<tempfile::NamedTempFile> {
+    let data: Vec<u8> = (0..SYNTHETIC_FILE_SIZE as u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 11) as u8)
+        .collect();
+    let mut file = tempfile::NamedTempFile::new()
+        .map_err(|e| ProofError::file_error("Failed to create synthetic test file", Some(e)))?;
+    file.write_all(&data)
+        .map_err(|e| ProofError::file_error("Failed to write synthetic test file", Some(e)))?;
+    Ok(file)
+}
+
+fn print_results(results: &BenchmarkResults) {
+    let ms = |duration: std::time::Duration| format_duration(duration.as_millis() as u64);
+
+    println!();
+    println!("📊 {} ({} iterations, {} failed)", results.operation, results.successful_iterations, results.failed_iterations);
+    println!("   Mean: {}", ms(results.mean_duration));
+    println!("   Median: {}", ms(results.median_duration));
+    println!("   p95: {}", ms(results.p95_duration));
+    println!("   Min / Max: {} / {}", ms(results.min_duration), ms(results.max_duration));
+    println!("   Throughput: {:.2} ops/s", results.throughput);
+
+    if let Some(analysis) = &results.regression_analysis {
+        let verdict = if analysis.regression_detected {
+            "⚠️  regression"
+        } else if analysis.improvement_detected {
+            "✅ improvement"
+        } else {
+            "no significant change"
+        };
+        println!("   Against baseline: {:+.1}% throughput ({})", analysis.performance_change, verdict);
+    }
+}
@@ -0,0 +1,77 @@
+//! Cache command implementation
+//!
+//! Audits the on-disk proof cache and removes entries that can no longer be
+//! read.
+
+use clap::{Args, Subcommand};
+use zkipfs_proof_core::{error::Result, CacheManager, CacheReport};
+use crate::{commands::output, utils::{format_bytes, format_duration}};
+
+#[derive(Debug, Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommand {
+    /// List cache entries with their sizes and ages, flagging corrupt files
+    Inspect,
+
+    /// Remove corrupt cache files and recompute the cache totals
+    Repair,
+}
+
+/// Inspect or repair the proof cache
+pub async fn execute(args: CacheArgs, output_format: &str) -> Result<()> {
+    let mut cache = CacheManager::new().await?;
+    match args.command {
+        CacheCommand::Inspect => {
+            let report = cache.inspect().await?;
+            if output_format == "table" {
+                print_report(&report);
+                Ok(())
+            } else {
+                output::print_output(&report, output_format, true)
+            }
+        }
+        CacheCommand::Repair => {
+            let removed = cache.repair().await?;
+            let stats = cache.get_statistics();
+            if output_format == "table" {
+                println!("🧹 Removed {} corrupt cache entries", removed);
+                println!("  Entries: {}", stats.total_entries);
+                println!("  Disk usage: {}", format_bytes(stats.disk_cache_bytes));
+                Ok(())
+            } else {
+                output::print_output(&serde_json::json!({
+                    "removed": removed,
+                    "statistics": stats,
+                }), output_format, true)
+            }
+        }
+    }
+}
+
+fn print_report(report: &CacheReport) {
+    println!("📦 Cache directory: {}", report.directory.display());
+    println!(
+        "  {} entries, {} on disk, {} expired, {} corrupt",
+        report.entries.len(),
+        format_bytes(report.total_bytes),
+        report.expired_entries,
+        report.corrupt_entries
+    );
+
+    for entry in &report.entries {
+        let age = entry.age_seconds
+            .map(|seconds| format_duration(seconds * 1000))
+            .unwrap_or_else(|| "-".to_string());
+        let status = match &entry.problem {
+            Some(problem) => format!("❌ corrupt ({})", problem),
+            None if entry.expired => "⌛ expired".to_string(),
+            None => "✅".to_string(),
+        };
+        println!("  {:<60} {:>10} {:>10}  {}", entry.key, format_bytes(entry.file_bytes), age, status);
+    }
+}
@@ -0,0 +1,18 @@
+//! Completions command implementation
+
+use clap_complete::Shell;
+use std::io::Write;
+use zkipfs_proof_core::error::{ProofError, Result};
+
+/// Writes the completion script for `shell` to `writer`
+pub fn write_completions<W: Write>(shell: Shell, command: &mut clap::Command, writer: &mut W) -> Result<()> {
+    let bin_name = command.get_name().to_string();
+    clap_complete::generate(shell, command, bin_name, writer);
+    writer.flush()
+        .map_err(|e| ProofError::file_error("Failed to write completion script", Some(e)))
+}
+
+/// Prints the completion script for `shell` to stdout
+pub fn execute(shell: Shell, command: &mut clap::Command) -> Result<()> {
+    write_completions(shell, command, &mut std::io::stdout().lock())
+}
@@ -29,6 +29,7 @@ pub async fn execute(action: crate::ConfigAction, config: &Config) -> Result<()>
                 println!("  worker_threads: {}", threads);
             }
             println!("  chunk_size_bytes: {}", config.performance.chunk_size_bytes);
+            println!("  adaptive_chunking: {}", config.performance.adaptive_chunking);
             println!("  max_in_memory_size_mb: {}", config.performance.max_in_memory_size_mb);
         }
         
@@ -44,6 +45,7 @@ pub async fn execute(action: crate::ConfigAction, config: &Config) -> Result<()>
         crate::ConfigAction::Set { key, value } => {
             let mut new_config = config.clone();
             set_config_value(&mut new_config, &key, &value)?;
+            new_config.validate()?;
             save_config(&new_config, None).await?;
             println!("✅ Configuration updated: {} = {}", key, value);
         }
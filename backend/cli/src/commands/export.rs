@@ -0,0 +1,207 @@
+//! Export command implementation
+//!
+//! Converts a proof between JSON, YAML, the binary envelope and bundles,
+//! checking its structure on the way.
+
+use clap::{Args, ValueEnum};
+use std::path::{Path, PathBuf};
+use zkipfs_proof_core::{
+    bundle::BUNDLE_MAGIC,
+    error::{ProofError, Result},
+    Proof, ProofBundle, ProofConfig, ProofFormat, ProofGenerator, ProofVerifier,
+};
+use crate::{commands::output, utils::{format_bytes, validate_file_path, validate_output_path}};
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Proof to convert, as JSON, YAML, binary or a bundle
+    #[arg(long = "in", value_name = "FILE")]
+    pub input: PathBuf,
+
+    /// File to write the converted proof to
+    #[arg(long, value_name = "FILE")]
+    pub out: PathBuf,
+
+    /// Format to write; inferred from the extension of --out if omitted
+    #[arg(long, value_enum)]
+    pub format: Option<ExportFormat>,
+
+    /// Original file, needed to bundle a proof that is not already in a bundle
+    #[arg(long, value_name = "FILE")]
+    pub file: Option<PathBuf>,
+
+    /// Overwrite --out if it exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Formats a proof can be exported to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Pretty-printed JSON
+    Json,
+    /// YAML
+    Yaml,
+    /// Binary envelope
+    Bin,
+    /// Proof bundle with the blocks backing it
+    Bundle,
+}
+
+impl ExportFormat {
+    /// Format conventionally stored under `path`'s extension
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "json" => Some(ExportFormat::Json),
+            "yaml" | "yml" => Some(ExportFormat::Yaml),
+            "bin" => Some(ExportFormat::Bin),
+            "zkb" => Some(ExportFormat::Bundle),
+            _ => None,
+        }
+    }
+}
+
+/// Convert a proof to another format
+pub async fn execute(args: ExportArgs, output_format: &str) -> Result<()> {
+    let format = match args.format.or_else(|| ExportFormat::from_path(&args.out)) {
+        Some(format) => format,
+        None => return Err(ProofError::invalid_input_error(
+            "format",
+            format!("Cannot infer the format of {}; pass --format", args.out.display())
+        )),
+    };
+    validate_output_path(&args.out, args.force)?;
+
+    let (proof, car) = load(&args.input)?;
+    check_structure(&proof)?;
+
+    let bytes = match format {
+        ExportFormat::Json => proof.encode(ProofFormat::Json)?,
+        ExportFormat::Bin => proof.encode(ProofFormat::Binary)?,
+        ExportFormat::Yaml => to_yaml(&proof)?.into_bytes(),
+        ExportFormat::Bundle => {
+            let car = match (car, &args.file) {
+                (Some(car), _) => car,
+                (None, Some(file)) => {
+                    let generator = ProofGenerator::with_config(ProofConfig { use_cache: false, ..ProofConfig::default() }).await?;
+                    let mut car = Vec::new();
+                    generator.export_car(&proof, file, &mut car).await?;
+                    car
+                }
+                (None, None) => return Err(ProofError::invalid_input_error(
+                    "file",
+                    "Bundling a proof that is not already in a bundle needs the original file; pass --file"
+                )),
+            };
+            let mut bytes = Vec::new();
+            ProofBundle::new(proof.clone(), car).write(&mut bytes)?;
+            bytes
+        }
+    };
+
+    std::fs::write(&args.out, &bytes)
+        .map_err(|e| ProofError::file_error(format!("Failed to write {}", args.out.display()), Some(e)))?;
+
+    if output_format == "table" {
+        output::print_success(&format!(
+            "Exported proof {} to {} ({})",
+            proof.id,
+            args.out.display(),
+            format_bytes(bytes.len() as u64)
+        ));
+        Ok(())
+    } else {
+        output::print_output(&serde_json::json!({
+            "proof_id": proof.id,
+            "output": args.out,
+            "format": format!("{:?}", format).to_lowercase(),
+            "size_bytes": bytes.len(),
+        }), output_format, true)
+    }
+}
+
+/// Reads a proof in any supported format, with the CAR it was bundled with
+fn load(path: &Path) -> Result<(Proof, Option<Vec<u8>>)> {
+    validate_file_path(path)?;
+    let content = std::fs::read(path)
+        .map_err(|e| ProofError::file_error(format!("Failed to read proof file: {}", path.display()), Some(e)))?;
+
+    if content.starts_with(BUNDLE_MAGIC) {
+        let bundle = ProofBundle::read(&content[..])?;
+        return Ok((bundle.proof, Some(bundle.car)));
+    }
+    if ExportFormat::from_path(path) == Some(ExportFormat::Yaml) {
+        return Ok((from_yaml(&content)?, None));
+    }
+    Ok((Proof::decode(&content)?, None))
+}
+
+/// Rejects proofs that are malformed, warning about issues that do not stop
+/// the proof from being converted, such as its age
+fn check_structure(proof: &Proof) -> Result<()> {
+    let report = ProofVerifier::check_structure(proof);
+    if !(report.has_required_fields && report.valid_hashes && report.valid_selection) {
+        return Err(ProofError::verification_error(format!(
+            "Proof is malformed: {}",
+            report.issues.join("; ")
+        )));
+    }
+    for issue in &report.issues {
+        output::print_warning(issue);
+    }
+    Ok(())
+}
+
+/// YAML has no byte strings, so the proof goes through its JSON form, where
+/// bytes are sequences of numbers
+fn to_yaml(proof: &Proof) -> Result<String> {
+    serde_yaml::to_string(&serde_json::to_value(proof)?)
+        .map_err(|e| ProofError::serialization_error("Failed to encode proof as YAML", Some(Box::new(e))))
+}
+
+fn from_yaml(content: &[u8]) -> Result<Proof> {
+    let value: serde_json::Value = serde_yaml::from_slice(content)
+        .map_err(|e| ProofError::serialization_error("Failed to parse proof YAML", Some(Box::new(e))))?;
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zkipfs_proof_core::ContentSelection;
+
+    fn args(input: PathBuf, out: PathBuf) -> ExportArgs {
+        ExportArgs { input, out, format: None, file: None, force: false }
+    }
+
+    #[tokio::test]
+    async fn test_json_to_binary_to_json_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("report.txt");
+        std::fs::write(&file, "The audit found no irregularities in 2023").unwrap();
+        let mut generator = ProofGenerator::with_config(ProofConfig { use_cache: false, ..ProofConfig::default() })
+            .await
+            .unwrap();
+        let proof = generator
+            .generate_proof(&file, ContentSelection::Pattern { content: b"no irregularities".to_vec() })
+            .await
+            .unwrap();
+        let json = dir.path().join("proof.json");
+        std::fs::write(&json, proof.encode(ProofFormat::Json).unwrap()).unwrap();
+
+        let bin = dir.path().join("proof.bin");
+        execute(args(json.clone(), bin.clone()), "json").await.unwrap();
+        assert_eq!(ProofFormat::detect(&std::fs::read(&bin).unwrap()), ProofFormat::Binary);
+
+        let back = dir.path().join("back.json");
+        execute(args(bin, back.clone()), "json").await.unwrap();
+        let (converted, car) = load(&back).unwrap();
+        assert!(car.is_none());
+        assert_eq!(serde_json::to_value(&converted).unwrap(), serde_json::to_value(&proof).unwrap());
+        assert_eq!(converted.fingerprint(), proof.fingerprint());
+
+        let yaml = dir.path().join("proof.yaml");
+        execute(args(json, yaml.clone()), "json").await.unwrap();
+        assert_eq!(load(&yaml).unwrap().0.fingerprint(), proof.fingerprint());
+    }
+}
@@ -6,19 +6,23 @@
 
 use clap::Args;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Instant;
+use tokio::sync::Semaphore;
 use tracing::{info, warn};
 use uuid::Uuid;
 
 use zkipfs_proof_core::{
-    ProofGenerator, ProofConfig, ContentSelection, ProverType, CompressionType,
-    error::Result,
+    ProofGenerator, ProofConfig, ContentSelection, ProverType, CompressionType, Bytes, ChunkingStrategy, Proof, ProofFormat,
+    ProofBundle, RedactionOptions,
+    error::{ProofError, Result},
 };
 use crate::{
     config::Config,
     progress::ProgressTracker,
-    utils::{parse_content_selection, validate_file_path, format_duration, format_bytes},
+    utils::{parse_content_selection, validate_file_path, format_duration, format_bytes, get_file_size},
     commands::{Command, output},
 };
 
@@ -26,23 +30,34 @@ use crate::{
 #[derive(Args, Debug)]
 pub struct GenerateCommand {
     /// Path to the file to generate proof for
-    #[arg(short, long, value_name = "FILE")]
-    pub file: PathBuf,
+    #[arg(short, long, value_name = "FILE", required_unless_present_any = ["batch", "stdin"], conflicts_with = "batch")]
+    pub file: Option<PathBuf>,
+
+    /// Read the content to prove from standard input instead of --file
+    #[arg(long, conflicts_with_all = ["file", "batch"])]
+    pub stdin: bool,
 
     /// Content to prove exists in the file
     /// 
     /// Formats:
     /// - Pattern: "pattern:secret text"
     /// - Regex: "regex:^\d{3}-\d{2}-\d{4}$"
+    /// - JSON path: "json:$.items[0].price"
     /// - Byte range: "range:100:200"  
     /// - Multiple: "pattern:text1,range:50:100"
-    #[arg(short, long, value_name = "SELECTION")]
-    pub content: String,
+    ///
+    /// In batch mode over a directory, the selection is proven for every file.
+    #[arg(short, long, value_name = "SELECTION", required_unless_present = "batch")]
+    pub content: Option<String>,
 
     /// Output file for the generated proof
     #[arg(short, long, value_name = "FILE")]
     pub output: Option<PathBuf>,
 
+    /// Proof file format: json, or bin for the compact binary envelope
+    #[arg(long = "format", value_name = "FORMAT", default_value = "json")]
+    pub proof_format: String,
+
     /// Security level in bits (128, 192, or 256)
     #[arg(long, default_value = "128")]
     pub security_level: u32,
@@ -55,6 +70,11 @@ pub struct GenerateCommand {
     #[arg(long, default_value = "gzip")]
     pub compression: String,
 
+    /// Decompress the file (gzip or zstd) before proving, so the proof binds
+    /// its decompressed content
+    #[arg(long, value_name = "CODEC")]
+    pub decompress: Option<String>,
+
     /// Maximum memory usage in MB
     #[arg(long)]
     pub max_memory: Option<u64>,
@@ -75,10 +95,20 @@ pub struct GenerateCommand {
     #[arg(long)]
     pub save_metadata: Option<PathBuf>,
 
+    /// Also write a proof bundle (.zkb) packaging the proof with the blocks
+    /// needed to verify it offline
+    #[arg(long, value_name = "FILE", conflicts_with = "batch")]
+    pub bundle: Option<PathBuf>,
+
     /// Custom metadata as JSON string
     #[arg(long)]
     pub custom_metadata: Option<String>,
 
+    /// Strip the filename, file size, MIME type and generation environment
+    /// from the proof's metadata
+    #[arg(long)]
+    pub redact_metadata: bool,
+
     /// Force overwrite existing output file
     #[arg(short, long)]
     pub force: bool,
@@ -86,6 +116,40 @@ pub struct GenerateCommand {
     /// Dry run - validate inputs without generating proof
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Estimate cycles, proof size and proving time by executing the guest, without proving
+    #[arg(long, conflicts_with_all = ["batch", "dry_run"])]
+    pub estimate: bool,
+
+    /// Print a QR code of the proof fingerprint to stderr, or save it as PNG to the given path
+    #[arg(long, value_name = "PNG", num_args = 0..=1)]
+    pub qr: Option<Option<PathBuf>>,
+
+    /// Verification URL to embed in the QR code alongside the fingerprint
+    #[arg(long, value_name = "URL", requires = "qr")]
+    pub qr_url: Option<String>,
+
+    /// Batch mode: prove every file in a directory (with --content), or every
+    /// entry of a JSON or YAML manifest listing `path` and `content` pairs
+    #[arg(long, value_name = "PATH")]
+    pub batch: Option<PathBuf>,
+
+    /// Directory to write batch proofs to
+    #[arg(long, value_name = "DIR", requires = "batch", default_value = "proofs")]
+    pub output_dir: PathBuf,
+
+    /// Maximum number of files proven concurrently in batch mode
+    #[arg(long, value_name = "N", requires = "batch", default_value = "1")]
+    pub parallel: usize,
+}
+
+/// A file and the selection to prove in it, as listed in a batch manifest
+///
+/// Relative paths are resolved against the manifest's directory.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct BatchEntry {
+    path: PathBuf,
+    content: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -94,6 +158,8 @@ struct GenerateOutput {
     file_path: String,
     content_selection: String,
     proof_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bundle_file: Option<String>,
     generation_time_ms: u64,
     file_size_bytes: u64,
     proof_size_bytes: u64,
@@ -105,6 +171,45 @@ struct GenerateOutput {
     warnings: Option<Vec<String>>,
 }
 
+#[derive(Serialize, Deserialize)]
+struct EstimateOutput {
+    file_path: String,
+    content_selection: String,
+    file_size_bytes: u64,
+    estimated_cycles: u64,
+    segments: usize,
+    estimated_proof_size_bytes: u64,
+    estimated_peak_memory_bytes: u64,
+    estimated_proving_time_ms: u64,
+    detected_acceleration: String,
+    recommended_prover: String,
+    recommendation: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BatchGenerateOutput {
+    total_files: usize,
+    succeeded: usize,
+    failed: usize,
+    total_generation_time_ms: u64,
+    output_dir: String,
+    results: Vec<BatchEntryOutput>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BatchEntryOutput {
+    file_path: String,
+    content_selection: String,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof_file: Option<String>,
+    generation_time_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
 #[derive(Serialize, Deserialize)]
 struct PerformanceMetrics {
     file_processing_time_ms: u64,
@@ -116,6 +221,23 @@ struct PerformanceMetrics {
 
 impl Command for GenerateCommand {
     async fn execute(&self, config: &Config, output_format: &str) -> Result<()> {
+        if self.batch.is_some() {
+            return self.execute_batch(config, output_format).await;
+        }
+
+        if self.stdin {
+            // Piped content is buffered to a temporary file, removed once the proof is written
+            let max_file_size = self.create_proof_config(config)?.max_file_size_bytes;
+            let input = buffer_input(std::io::stdin().lock(), max_file_size)?;
+            return self.execute_file(config, output_format, input.path()).await;
+        }
+        self.execute_file(config, output_format, self.file()?).await
+    }
+}
+
+impl GenerateCommand {
+    /// Proves the selection in `file`, the input file or buffered standard input
+    async fn execute_file(&self, config: &Config, output_format: &str, file: &Path) -> Result<()> {
         let start_time = Instant::now();
         let mut warnings = Vec::new();
 
@@ -127,9 +249,18 @@ impl Command for GenerateCommand {
             return Ok(());
         }
 
+        if self.estimate {
+            let estimate = self.run_estimate(config, file).await?;
+            match output_format {
+                "table" => self.print_estimate_table_output(&estimate),
+                _ => output::print_output(&estimate, output_format, true)?,
+            }
+            return Ok(());
+        }
+
         // Parse content selection
-        let content_selection = parse_content_selection(&self.content)?;
-        info!("Content selection: {}", content_selection.description());
+        let content_selection = parse_content_selection(self.content()?)?;
+        info!("Content selection: {}", content_selection.description_en());
 
         // Create proof configuration
         let proof_config = self.create_proof_config(config)?;
@@ -142,10 +273,16 @@ impl Command for GenerateCommand {
         let mut generator = ProofGenerator::with_config(proof_config).await?;
         progress.set_progress(10);
 
+        // Fail fast on selections that cannot be proven, before the prover runs
+        progress.set_message("Checking content selection...");
+        generator.preflight(file, &content_selection).await?.ensure_satisfiable()?;
+        progress.set_progress(15);
+
         progress.set_message("Processing file and generating proof...");
         
         // Generate the proof
-        let proof = generator.generate_proof(&self.file, content_selection).await?;
+        let mut proof = generator.generate_proof(file, content_selection).await?;
+        proof.redact(&self.redaction());
         progress.set_progress(90);
 
         progress.set_message("Saving proof...");
@@ -154,8 +291,7 @@ impl Command for GenerateCommand {
         let output_path = self.get_output_path(&proof.id)?;
 
         // Save proof to file
-        let proof_json = serde_json::to_string_pretty(&proof)?;
-        std::fs::write(&output_path, proof_json)?;
+        std::fs::write(&output_path, proof.encode(self.proof_format()?)?)?;
 
         // Save metadata if requested
         if let Some(metadata_path) = &self.save_metadata {
@@ -163,26 +299,36 @@ impl Command for GenerateCommand {
             std::fs::write(metadata_path, metadata_json)?;
         }
 
+        if let Some(bundle_path) = &self.bundle {
+            progress.set_message("Writing proof bundle...");
+            self.write_bundle(&generator, &proof, bundle_path, file).await?;
+        }
+
         progress.finish("Proof generation completed!");
 
+        if let Some(qr_path) = &self.qr {
+            crate::qr::emit(&proof.fingerprint(), self.qr_url.as_deref(), qr_path.as_deref())?;
+        }
+
         let generation_time = start_time.elapsed();
 
         // Create output data
         let output_data = GenerateOutput {
             proof_id: proof.id.clone(),
-            file_path: self.file.display().to_string(),
+            file_path: self.input_name(file),
             content_selection: proof.content_selection.description(),
             proof_file: Some(output_path.display().to_string()),
+            bundle_file: self.bundle.as_ref().map(|path| path.display().to_string()),
             generation_time_ms: generation_time.as_millis() as u64,
-            file_size_bytes: proof.metadata.file_info.size,
-            proof_size_bytes: proof.metadata.performance.proof_size_bytes,
+            file_size_bytes: proof.metadata.file_info.size.get(),
+            proof_size_bytes: proof.metadata.performance.proof_size_bytes.get(),
             security_level: proof.metadata.security.security_level,
             success: true,
             performance_metrics: if self.include_metrics {
                 Some(PerformanceMetrics {
-                    file_processing_time_ms: proof.metadata.performance.file_processing_time_ms,
-                    zk_generation_time_ms: proof.metadata.performance.zk_generation_time_ms,
-                    peak_memory_bytes: proof.metadata.performance.peak_memory_bytes,
+                    file_processing_time_ms: proof.metadata.performance.file_processing_time_ms.get(),
+                    zk_generation_time_ms: proof.metadata.performance.zk_generation_time_ms.get(),
+                    peak_memory_bytes: proof.metadata.performance.peak_memory_bytes.get(),
                     zk_cycles: proof.metadata.performance.zk_cycles,
                     compression_ratio: proof.metadata.performance.compression_ratio,
                 })
@@ -200,14 +346,286 @@ impl Command for GenerateCommand {
 
         Ok(())
     }
-}
 
-impl GenerateCommand {
+    /// Writes `proof` and the blocks backing it to a bundle at `path`
+    async fn write_bundle(&self, generator: &ProofGenerator, proof: &Proof, path: &Path, input: &Path) -> Result<()> {
+        let mut car = Vec::new();
+        generator.export_car(proof, input, &mut car).await?;
+        let file = std::fs::File::create(path)
+            .map_err(|e| ProofError::file_error(format!("Failed to create bundle file: {}", path.display()), Some(e)))?;
+        ProofBundle::new(proof.clone(), car).write(std::io::BufWriter::new(file))
+    }
+
+    /// Returns the codec input files are decompressed with, if any
+    fn decompression(&self) -> Result<Option<CompressionType>> {
+        match self.decompress.as_deref() {
+            None => Ok(None),
+            Some("gzip") => Ok(Some(CompressionType::Gzip)),
+            Some("zstd") => Ok(Some(CompressionType::Zstd)),
+            Some(_) => Err(ProofError::invalid_input_error(
+                "decompress",
+                "Decompression codec must be 'gzip' or 'zstd'"
+            )),
+        }
+    }
+
+    /// Returns the metadata fields to strip from generated proofs
+    fn redaction(&self) -> RedactionOptions {
+        if self.redact_metadata {
+            RedactionOptions::all()
+        } else {
+            RedactionOptions::default()
+        }
+    }
+
+    /// Returns the file to prove outside batch mode
+    fn file(&self) -> Result<&Path> {
+        self.file.as_deref().ok_or_else(|| ProofError::invalid_input_error(
+            "file",
+            "A file is required unless --batch or --stdin is given"
+        ))
+    }
+
+    /// Name of the proven input for output, which is `file` unless read from stdin
+    fn input_name(&self, file: &Path) -> String {
+        if self.stdin {
+            "<stdin>".to_string()
+        } else {
+            file.display().to_string()
+        }
+    }
+
+    /// Returns the format proofs are written in
+    fn proof_format(&self) -> Result<ProofFormat> {
+        self.proof_format.parse()
+    }
+
+    /// Returns the content selection shared by all files
+    fn content(&self) -> Result<&str> {
+        self.content.as_deref().ok_or_else(|| ProofError::invalid_input_error(
+            "content",
+            "A content selection is required unless --batch is given a manifest"
+        ))
+    }
+
+    /// Executes the guest for the selection and projects the cost of proving it
+    async fn run_estimate(&self, config: &Config, file: &Path) -> Result<EstimateOutput> {
+        let content_selection = parse_content_selection(self.content()?)?;
+        let generator = ProofGenerator::with_config(self.create_proof_config(config)?).await?;
+
+        let progress = ProgressTracker::new("Estimating proof cost");
+        progress.set_message("Executing guest program...");
+        let estimate = generator.estimate(file, &content_selection).await?;
+        progress.finish("Estimate completed!");
+
+        Ok(EstimateOutput {
+            file_path: self.input_name(file),
+            content_selection: content_selection.description(),
+            file_size_bytes: get_file_size(file)?,
+            estimated_cycles: estimate.total_cycles,
+            segments: estimate.segment_cycles.len(),
+            estimated_proof_size_bytes: estimate.estimated_proof_size.get(),
+            estimated_peak_memory_bytes: estimate.estimated_peak_memory.get(),
+            estimated_proving_time_ms: estimate.estimated_proving_time_ms.get(),
+            detected_acceleration: format!("{:?}", estimate.detected_acceleration),
+            recommended_prover: format!("{:?}", estimate.recommended_prover),
+            recommendation: estimate.recommendation,
+        })
+    }
+
+    /// Generate proofs for every batch entry, up to `parallel` at a time
+    async fn execute_batch(&self, config: &Config, output_format: &str) -> Result<()> {
+        let mut warnings = Vec::new();
+        self.validate_options(&mut warnings)?;
+        for warning in &warnings {
+            warn!("{}", warning);
+        }
+
+        let entries = self.batch_entries()?;
+        for entry in &entries {
+            validate_file_path(&entry.path)?;
+            parse_content_selection(&entry.content)?;
+        }
+
+        if self.dry_run {
+            output::print_success(&format!("Dry run completed - all {} batch entries are valid", entries.len()));
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.output_dir)
+            .map_err(|e| ProofError::file_error(
+                format!("Failed to create output directory: {}", self.output_dir.display()),
+                Some(e)
+            ))?;
+
+        let proof_config = self.create_proof_config(config)?;
+        let parallel = self.parallel.max(1);
+        let total = entries.len();
+
+        let progress = Arc::new(ProgressTracker::new("Batch proof generation"));
+        progress.set_message(&format!("Proving {} files, up to {} at a time", total, parallel));
+
+        let start_time = Instant::now();
+        let semaphore = Arc::new(Semaphore::new(parallel));
+        let completed = Arc::new(AtomicUsize::new(0));
+        let mut tasks = Vec::with_capacity(total);
+
+        for entry in entries {
+            let semaphore = semaphore.clone();
+            let completed = completed.clone();
+            let progress = progress.clone();
+            let proof_config = proof_config.clone();
+            let output_dir = self.output_dir.clone();
+            let proof_format = self.proof_format()?;
+            let redaction = self.redaction();
+
+            tasks.push(tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await
+                    .expect("batch semaphore is never closed");
+
+                let entry_start = Instant::now();
+                let result = prove_batch_entry(&entry, proof_config, &output_dir, proof_format, &redaction).await;
+                let generation_time_ms = entry_start.elapsed().as_millis() as u64;
+
+                let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
+                progress.set_progress((done * 100 / total) as u64);
+                progress.set_message(&format!(
+                    "Proved {} ({}/{})",
+                    entry.path.file_name().unwrap_or_default().to_string_lossy(),
+                    done,
+                    total
+                ));
+
+                match result {
+                    Ok((proof, proof_file)) => BatchEntryOutput {
+                        file_path: entry.path.display().to_string(),
+                        content_selection: proof.content_selection.description(),
+                        success: true,
+                        proof_id: Some(proof.id),
+                        proof_file: Some(proof_file.display().to_string()),
+                        generation_time_ms,
+                        error: None,
+                    },
+                    Err(e) => {
+                        warn!("Failed to prove {}: {}", entry.path.display(), e);
+                        BatchEntryOutput {
+                            file_path: entry.path.display().to_string(),
+                            content_selection: entry.content.clone(),
+                            success: false,
+                            proof_id: None,
+                            proof_file: None,
+                            generation_time_ms,
+                            error: Some(e.to_string()),
+                        }
+                    }
+                }
+            }));
+        }
+
+        let mut results = Vec::with_capacity(total);
+        for task in tasks {
+            results.push(task.await.map_err(|e| ProofError::internal_error(
+                "Batch proof task failed",
+                Some(Box::new(e))
+            ))?);
+        }
+
+        progress.finish("Batch proof generation completed!");
+
+        let succeeded = results.iter().filter(|r| r.success).count();
+        let batch_output = BatchGenerateOutput {
+            total_files: total,
+            succeeded,
+            failed: total - succeeded,
+            total_generation_time_ms: start_time.elapsed().as_millis() as u64,
+            output_dir: self.output_dir.display().to_string(),
+            results,
+        };
+
+        match output_format {
+            "table" => self.print_batch_table_output(&batch_output),
+            _ => output::print_output(&batch_output, output_format, true)?,
+        }
+
+        Ok(())
+    }
+
+    /// Lists the batch entries from a directory or manifest
+    fn batch_entries(&self) -> Result<Vec<BatchEntry>> {
+        let batch = self.batch.as_deref().ok_or_else(|| ProofError::invalid_input_error(
+            "batch",
+            "Batch mode requires a directory or manifest"
+        ))?;
+
+        let entries = if batch.is_dir() {
+            let content = self.content()?;
+            let mut files = Vec::new();
+            let dir_entries = std::fs::read_dir(batch)
+                .map_err(|e| ProofError::file_error(
+                    format!("Failed to read batch directory: {}", batch.display()),
+                    Some(e)
+                ))?;
+            for entry in dir_entries {
+                let path = entry
+                    .map_err(|e| ProofError::file_error("Failed to read directory entry", Some(e)))?
+                    .path();
+                if path.is_file() {
+                    files.push(path);
+                }
+            }
+            files.sort();
+            files.into_iter()
+                .map(|path| BatchEntry { path, content: content.to_string() })
+                .collect()
+        } else {
+            read_batch_manifest(batch)?
+        };
+
+        if entries.is_empty() {
+            return Err(ProofError::invalid_input_error(
+                "batch",
+                format!("No files to prove in {}", batch.display())
+            ));
+        }
+
+        Ok(entries)
+    }
+
     /// Validate command inputs
     fn validate_inputs(&self, warnings: &mut Vec<String>) -> Result<()> {
         // Validate file path
-        validate_file_path(&self.file)?;
+        if !self.stdin {
+            validate_file_path(self.file()?)?;
+        }
+
+        self.validate_options(warnings)?;
+
+        // Check if output file exists
+        if let Some(output_path) = self.get_output_path("temp")? {
+            if output_path.exists() && !self.force {
+                return Err(zkipfs_proof_core::error::ProofError::invalid_input_error(
+                    "output",
+                    format!("Output file already exists: {}. Use --force to overwrite", 
+                           output_path.display())
+                ));
+            }
+        }
 
+        if let Some(bundle_path) = &self.bundle {
+            if bundle_path.exists() && !self.force {
+                return Err(zkipfs_proof_core::error::ProofError::invalid_input_error(
+                    "bundle",
+                    format!("Bundle file already exists: {}. Use --force to overwrite",
+                           bundle_path.display())
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate the proving options shared by single and batch generation
+    fn validate_options(&self, warnings: &mut Vec<String>) -> Result<()> {
         // Validate security level
         if ![128, 192, 256].contains(&self.security_level) {
             return Err(zkipfs_proof_core::error::ProofError::invalid_input_error(
@@ -238,16 +656,8 @@ impl GenerateCommand {
             }
         }
 
-        // Check if output file exists
-        if let Some(output_path) = self.get_output_path("temp")? {
-            if output_path.exists() && !self.force {
-                return Err(zkipfs_proof_core::error::ProofError::invalid_input_error(
-                    "output",
-                    format!("Output file already exists: {}. Use --force to overwrite", 
-                           output_path.display())
-                ));
-            }
-        }
+        self.proof_format()?;
+        self.decompression()?;
 
         // Validate custom metadata JSON
         if let Some(metadata) = &self.custom_metadata {
@@ -295,12 +705,20 @@ impl GenerateCommand {
             security_level: self.security_level,
             use_hardware_acceleration: !self.no_hardware_acceleration,
             prover_type,
-            max_memory_bytes: self.max_memory.map(|mb| mb * 1024 * 1024),
+            max_memory_bytes: self.max_memory.map(|mb| Bytes(mb * 1024 * 1024)),
             timeout_seconds: self.timeout,
             compression,
             custom_metadata,
             include_performance_metrics: self.include_metrics,
             include_verification_steps: false,
+            worker_threads: config.performance.worker_threads,
+            chunking_strategy: if config.performance.adaptive_chunking {
+                ChunkingStrategy::Adaptive
+            } else {
+                ChunkingStrategy::default()
+            },
+            decompress_input: self.decompression()?,
+            ..ProofConfig::default()
         })
     }
 
@@ -310,7 +728,7 @@ impl GenerateCommand {
             Ok(output.clone())
         } else {
             // Generate default output filename
-            let filename = format!("proof_{}.json", &proof_id[..8]);
+            let filename = format!("proof_{}.{}", &proof_id[..8], self.proof_format()?.extension());
             Ok(PathBuf::from(filename))
         }
     }
@@ -344,6 +762,9 @@ impl GenerateCommand {
         if let Some(proof_file) = &data.proof_file {
             println!("💾 Proof saved to: {}", proof_file);
         }
+        if let Some(bundle_file) = &data.bundle_file {
+            println!("📦 Bundle saved to: {}", bundle_file);
+        }
 
         if let Some(warnings) = &data.warnings {
             if !warnings.is_empty() {
@@ -355,6 +776,127 @@ impl GenerateCommand {
             }
         }
     }
+
+    /// Print table-formatted output for a cost estimate
+    fn print_estimate_table_output(&self, data: &EstimateOutput) {
+        println!("🧮 Proof Cost Estimate");
+        println!();
+        println!("📋 Input:");
+        println!("   File: {}", data.file_path);
+        println!("   Content: {}", data.content_selection);
+        println!("   File Size: {}", format_bytes(data.file_size_bytes));
+        println!();
+        println!("📊 Estimate:");
+        println!("   ZK Cycles: {} in {} segments", data.estimated_cycles, data.segments);
+        println!("   Proof Size: ~{}", format_bytes(data.estimated_proof_size_bytes));
+        println!("   Peak Memory: ~{}", format_bytes(data.estimated_peak_memory_bytes));
+        println!("   Proving Time: ~{}", format_duration(data.estimated_proving_time_ms));
+        println!();
+        println!("💡 Recommendation ({} detected): {}", data.detected_acceleration, data.recommendation);
+    }
+
+    /// Print table-formatted output for batch generation
+    fn print_batch_table_output(&self, data: &BatchGenerateOutput) {
+        println!("📊 Batch Proof Generation Results");
+        println!();
+        println!("📈 Summary:");
+        println!("   Total Files: {}", data.total_files);
+        println!("   Succeeded: {}", data.succeeded);
+        println!("   Failed: {}", data.failed);
+        println!("   Total Time: {}", format_duration(data.total_generation_time_ms));
+        println!("   Output Directory: {}", data.output_dir);
+
+        println!();
+        println!("📋 Individual Results:");
+        for result in &data.results {
+            let status = if result.success { "✅" } else { "❌" };
+            let detail = match (&result.proof_file, &result.error) {
+                (Some(proof_file), _) => proof_file.as_str(),
+                (None, Some(error)) => error.as_str(),
+                (None, None) => "",
+            };
+            println!("   {} {} - {} ({})",
+                status,
+                result.file_path,
+                detail,
+                format_duration(result.generation_time_ms)
+            );
+        }
+    }
+}
+
+/// Reads a JSON or YAML (`.yaml`/`.yml`) batch manifest
+fn read_batch_manifest(path: &Path) -> Result<Vec<BatchEntry>> {
+    let manifest = std::fs::read_to_string(path)
+        .map_err(|e| ProofError::file_error(
+            format!("Failed to read batch manifest: {}", path.display()),
+            Some(e)
+        ))?;
+
+    let is_yaml = path.extension().map_or(false, |ext| ext == "yaml" || ext == "yml");
+    let mut entries: Vec<BatchEntry> = if is_yaml {
+        serde_yaml::from_str(&manifest)
+            .map_err(|e| ProofError::serialization_error(
+                format!("Invalid batch manifest: {}", path.display()),
+                Some(Box::new(e))
+            ))?
+    } else {
+        serde_json::from_str(&manifest)
+            .map_err(|e| ProofError::serialization_error(
+                format!("Invalid batch manifest: {}", path.display()),
+                Some(Box::new(e))
+            ))?
+    };
+
+    let base = path.parent().unwrap_or_else(|| Path::new("."));
+    for entry in &mut entries {
+        if entry.path.is_relative() {
+            entry.path = base.join(&entry.path);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Copies piped input to a temporary file, failing once it exceeds `max_bytes`
+fn buffer_input(reader: impl std::io::Read, max_bytes: Option<Bytes>) -> Result<tempfile::NamedTempFile> {
+    use std::io::{Read, Write};
+
+    let mut file = tempfile::NamedTempFile::new()
+        .map_err(|e| ProofError::file_error("Failed to create a temporary file for standard input", Some(e)))?;
+    let limit = max_bytes.map_or(u64::MAX, |max| max.get());
+    let copied = std::io::copy(&mut reader.take(limit.saturating_add(1)), &mut file)
+        .map_err(|e| ProofError::file_error("Failed to read standard input", Some(e)))?;
+    if copied > limit {
+        return Err(ProofError::resource_limit_error(
+            "file_size",
+            format!("Standard input exceeds the maximum file size of {}", format_bytes(limit))
+        ));
+    }
+    file.flush()
+        .map_err(|e| ProofError::file_error("Failed to buffer standard input", Some(e)))?;
+    Ok(file)
+}
+
+/// Proves one batch entry and writes the proof to `output_dir`
+async fn prove_batch_entry(
+    entry: &BatchEntry,
+    proof_config: ProofConfig,
+    output_dir: &Path,
+    proof_format: ProofFormat,
+    redaction: &RedactionOptions,
+) -> Result<(Proof, PathBuf)> {
+    let content_selection = parse_content_selection(&entry.content)?;
+    let mut generator = ProofGenerator::with_config(proof_config).await?;
+    generator.preflight(&entry.path, &content_selection).await?.ensure_satisfiable()?;
+    let mut proof = generator.generate_proof(&entry.path, content_selection).await?;
+    proof.redact(redaction);
+
+    let stem = entry.path.file_stem().unwrap_or_default().to_string_lossy();
+    let proof_file = output_dir.join(format!("{}_{}.{}", stem, &proof.id[..8], proof_format.extension()));
+    std::fs::write(&proof_file, proof.encode(proof_format)?)?;
+
+    Ok((proof, proof_file))
 }
 
 #[cfg(test)]
@@ -366,20 +908,31 @@ mod tests {
     #[test]
     fn test_validate_security_level() {
         let mut cmd = GenerateCommand {
-            file: PathBuf::from("test.txt"),
-            content: "pattern:test".to_string(),
+            file: Some(PathBuf::from("test.txt")),
+            stdin: false,
+            content: Some("pattern:test".to_string()),
             output: None,
+            proof_format: "json".to_string(),
             security_level: 64, // Invalid
             prover: "local".to_string(),
             compression: "gzip".to_string(),
+            decompress: None,
             max_memory: None,
             timeout: None,
             no_hardware_acceleration: false,
             include_metrics: false,
             save_metadata: None,
+            bundle: None,
             custom_metadata: None,
+            redact_metadata: false,
             force: false,
             dry_run: true,
+            estimate: false,
+            qr: None,
+            qr_url: None,
+            batch: None,
+            output_dir: PathBuf::from("proofs"),
+            parallel: 1,
         };
 
         let mut warnings = Vec::new();
@@ -395,20 +948,31 @@ mod tests {
     #[test]
     fn test_prover_validation() {
         let cmd = GenerateCommand {
-            file: PathBuf::from("test.txt"),
-            content: "pattern:test".to_string(),
+            file: Some(PathBuf::from("test.txt")),
+            stdin: false,
+            content: Some("pattern:test".to_string()),
             output: None,
+            proof_format: "json".to_string(),
             security_level: 128,
             prover: "invalid".to_string(),
             compression: "gzip".to_string(),
+            decompress: None,
             max_memory: None,
             timeout: None,
             no_hardware_acceleration: false,
             include_metrics: false,
             save_metadata: None,
+            bundle: None,
             custom_metadata: None,
+            redact_metadata: false,
             force: false,
             dry_run: true,
+            estimate: false,
+            qr: None,
+            qr_url: None,
+            batch: None,
+            output_dir: PathBuf::from("proofs"),
+            parallel: 1,
         };
 
         let mut warnings = Vec::new();
@@ -419,25 +983,197 @@ mod tests {
     #[test]
     fn test_custom_metadata_validation() {
         let cmd = GenerateCommand {
-            file: PathBuf::from("test.txt"),
-            content: "pattern:test".to_string(),
+            file: Some(PathBuf::from("test.txt")),
+            stdin: false,
+            content: Some("pattern:test".to_string()),
             output: None,
+            proof_format: "json".to_string(),
             security_level: 128,
             prover: "local".to_string(),
             compression: "gzip".to_string(),
+            decompress: None,
             max_memory: None,
             timeout: None,
             no_hardware_acceleration: false,
             include_metrics: false,
             save_metadata: None,
+            bundle: None,
             custom_metadata: Some("invalid json".to_string()),
+            redact_metadata: false,
             force: false,
             dry_run: true,
+            estimate: false,
+            qr: None,
+            qr_url: None,
+            batch: None,
+            output_dir: PathBuf::from("proofs"),
+            parallel: 1,
         };
 
         let mut warnings = Vec::new();
         let result = cmd.validate_inputs(&mut warnings);
         assert!(result.is_err());
     }
-}
 
+    #[tokio::test]
+    async fn test_batch_manifest_writes_one_proof_per_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "first file with secret alpha").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "second file with secret beta").unwrap();
+        std::fs::write(dir.path().join("c.txt"), "third file, bytes 0..5 proven").unwrap();
+
+        let manifest = dir.path().join("manifest.json");
+        std::fs::write(&manifest, r#"[
+            {"path": "a.txt", "content": "pattern:secret alpha"},
+            {"path": "b.txt", "content": "pattern:secret beta"},
+            {"path": "c.txt", "content": "range:0:5"}
+        ]"#).unwrap();
+        let output_dir = dir.path().join("proofs");
+
+        let cmd = GenerateCommand {
+            file: None,
+            stdin: false,
+            content: None,
+            output: None,
+            proof_format: "json".to_string(),
+            security_level: 128,
+            prover: "local".to_string(),
+            compression: "gzip".to_string(),
+            decompress: None,
+            max_memory: None,
+            timeout: None,
+            no_hardware_acceleration: false,
+            include_metrics: false,
+            save_metadata: None,
+            bundle: None,
+            custom_metadata: None,
+            redact_metadata: false,
+            force: false,
+            dry_run: false,
+            estimate: false,
+            qr: None,
+            qr_url: None,
+            batch: Some(manifest),
+            output_dir: output_dir.clone(),
+            parallel: 2,
+        };
+
+        let entries = cmd.batch_entries().unwrap();
+        assert_eq!(entries[0].path, dir.path().join("a.txt"));
+
+        cmd.execute(&Config::default(), "json").await.unwrap();
+
+        let proof_files = std::fs::read_dir(&output_dir).unwrap()
+            .filter(|entry| entry.as_ref().unwrap().path().extension().map_or(false, |ext| ext == "json"))
+            .count();
+        assert_eq!(proof_files, 3);
+    }
+
+    #[tokio::test]
+    async fn test_batch_writes_selected_proof_format() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "batch proofs follow --proof-format").unwrap();
+        let manifest = dir.path().join("manifest.json");
+        std::fs::write(&manifest, r#"[{"path": "a.txt", "content": "pattern:proof-format"}]"#).unwrap();
+        let output_dir = dir.path().join("proofs");
+
+        let cmd = GenerateCommand {
+            file: None,
+            stdin: false,
+            content: None,
+            output: None,
+            proof_format: "bin".to_string(),
+            security_level: 128,
+            prover: "local".to_string(),
+            compression: "gzip".to_string(),
+            decompress: None,
+            max_memory: None,
+            timeout: None,
+            no_hardware_acceleration: false,
+            include_metrics: false,
+            save_metadata: None,
+            bundle: None,
+            custom_metadata: None,
+            redact_metadata: false,
+            force: false,
+            dry_run: false,
+            estimate: false,
+            qr: None,
+            qr_url: None,
+            batch: Some(manifest),
+            output_dir: output_dir.clone(),
+            parallel: 1,
+        };
+        cmd.execute(&Config::default(), "json").await.unwrap();
+
+        let proof_files: Vec<PathBuf> = std::fs::read_dir(&output_dir).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(proof_files.len(), 1);
+        assert_eq!(proof_files[0].extension().unwrap(), "bin");
+        let data = std::fs::read(&proof_files[0]).unwrap();
+        assert_eq!(ProofFormat::detect(&data), ProofFormat::Binary);
+        assert!(Proof::decode(&data).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_estimate_reports_cycles() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"a small file whose proof cost is estimated").unwrap();
+
+        let cmd = GenerateCommand {
+            file: Some(file.path().to_path_buf()),
+            stdin: false,
+            content: Some("pattern:proof cost".to_string()),
+            output: None,
+            proof_format: "json".to_string(),
+            security_level: 128,
+            prover: "local".to_string(),
+            compression: "gzip".to_string(),
+            decompress: None,
+            max_memory: None,
+            timeout: None,
+            no_hardware_acceleration: false,
+            include_metrics: false,
+            save_metadata: None,
+            bundle: None,
+            custom_metadata: None,
+            redact_metadata: false,
+            force: false,
+            dry_run: false,
+            estimate: true,
+            qr: None,
+            qr_url: None,
+            batch: None,
+            output_dir: PathBuf::from("proofs"),
+            parallel: 1,
+        };
+
+        cmd.execute(&Config::default(), "json").await.unwrap();
+
+        let estimate = cmd.run_estimate(&Config::default(), file.path()).await.unwrap();
+        assert!(estimate.estimated_cycles > 0);
+        assert!(estimate.segments > 0);
+    }
+
+    #[tokio::test]
+    async fn test_stdin_proof_matches_file_proof() {
+        let data = b"piped content with a secret in the middle of it";
+        let piped = buffer_input(std::io::Cursor::new(&data[..]), Some(Bytes(1024))).unwrap();
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(data).unwrap();
+
+        let config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let selection = || ContentSelection::Pattern { content: b"secret".to_vec() };
+        let from_stdin = generator.generate_proof(piped.path(), selection()).await.unwrap();
+        let from_file = generator.generate_proof(file.path(), selection()).await.unwrap();
+
+        assert_eq!(from_stdin.content_hash, from_file.content_hash);
+        assert_eq!(from_stdin.root_hash, from_file.root_hash);
+        assert_eq!(from_stdin.metadata.file_info.file_hash, from_file.metadata.file_info.file_hash);
+
+        // Input over the size limit is refused rather than truncated
+        assert!(buffer_input(std::io::Cursor::new(&data[..]), Some(Bytes(10))).is_err());
+    }
+}
@@ -5,10 +5,10 @@
 
 use clap::Args;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::info;
 
-use zkipfs_proof_core::{Proof, error::Result};
+use zkipfs_proof_core::{Proof, ProofDiff, ProofFormat, ProofGenerator, ProofVerifier, error::Result, types::SegmentCycles};
 use crate::{
     config::Config,
     utils::{validate_file_path, format_bytes, format_duration, format_hash, get_system_info, get_file_size},
@@ -49,6 +49,18 @@ pub struct InfoCommand {
     /// Verify proof integrity (quick check)
     #[arg(long)]
     pub verify_integrity: bool,
+
+    /// Print a QR code of the proof fingerprint to stderr, or save it as PNG to the given path
+    #[arg(long, value_name = "PNG", num_args = 0..=1, requires = "proof")]
+    pub qr: Option<Option<PathBuf>>,
+
+    /// Verification URL to embed in the QR code alongside the fingerprint
+    #[arg(long, value_name = "URL", requires = "qr")]
+    pub qr_url: Option<String>,
+
+    /// Compare two proof files field by field
+    #[arg(long, num_args = 2, value_names = ["A", "B"])]
+    pub compare: Option<Vec<PathBuf>>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -61,16 +73,27 @@ struct InfoOutput {
     system_info: Option<SystemInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     config_info: Option<ConfigInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comparison: Option<ProofComparison>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProofComparison {
+    left: String,
+    right: String,
+    diff: ProofDiff,
 }
 
 #[derive(Serialize, Deserialize)]
 struct ProofInfo {
     id: String,
     version: String,
+    format: String,
     created_at: String,
     content_selection: String,
     content_hash: String,
     root_hash: String,
+    fingerprint: String,
     file_info: ProofFileInfo,
     security: SecurityInfo,
     performance: PerformanceInfo,
@@ -106,6 +129,8 @@ struct PerformanceInfo {
     zk_cycles: u64,
     proof_size_bytes: u64,
     compression_ratio: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    segment_cycles: Vec<SegmentCycles>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -132,6 +157,7 @@ struct SystemInfo {
     available_memory_human: Option<String>,
     zkipfs_version: String,
     risc0_available: bool,
+    risc0_version: String,
     hardware_acceleration: HardwareAcceleration,
     ipfs_available: bool,
 }
@@ -157,7 +183,7 @@ struct ConfigInfo {
 #[derive(Serialize, Deserialize)]
 struct IntegrityCheck {
     valid_structure: bool,
-    valid_json: bool,
+    valid_encoding: bool,
     has_required_fields: bool,
     proof_size_reasonable: bool,
     timestamp_reasonable: bool,
@@ -173,6 +199,7 @@ impl Command for InfoCommand {
             file_info: None,
             system_info: None,
             config_info: None,
+            comparison: None,
         };
 
         // Gather proof information
@@ -195,11 +222,17 @@ impl Command for InfoCommand {
             output_data.config_info = Some(self.gather_config_info(config).await?);
         }
 
+        // Compare two proofs
+        if let Some([left, right]) = self.compare.as_deref() {
+            output_data.comparison = Some(self.compare_proofs(left, right)?);
+        }
+
         // If no specific info requested, show system info by default
         if output_data.proof_info.is_none() 
             && output_data.file_info.is_none() 
             && output_data.system_info.is_none() 
-            && output_data.config_info.is_none() {
+            && output_data.config_info.is_none()
+            && output_data.comparison.is_none() {
             output_data.system_info = Some(self.gather_system_info().await?);
         }
 
@@ -209,27 +242,32 @@ impl Command for InfoCommand {
             _ => output::print_output(&output_data, output_format, true)?,
         }
 
+        if let (Some(qr_path), Some(proof_info)) = (&self.qr, &output_data.proof_info) {
+            crate::qr::emit(&proof_info.fingerprint, self.qr_url.as_deref(), qr_path.as_deref())?;
+        }
+
         Ok(())
     }
 }
 
 impl InfoCommand {
-    /// Gather information about a proof file
-    async fn gather_proof_info(&self, proof_path: &PathBuf) -> Result<ProofInfo> {
+    /// Reads a proof file in either format, returning its bytes and the proof
+    fn load_proof(proof_path: &Path) -> Result<(Vec<u8>, Proof)> {
         validate_file_path(proof_path)?;
 
-        // Load proof
-        let content = std::fs::read_to_string(proof_path)
+        let content = std::fs::read(proof_path)
             .map_err(|e| zkipfs_proof_core::error::ProofError::file_error(
                 format!("Failed to read proof file: {}", proof_path.display()),
                 Some(e)
             ))?;
+        let proof = Proof::decode(&content)?;
+        Ok((content, proof))
+    }
 
-        let proof: Proof = serde_json::from_str(&content)
-            .map_err(|e| zkipfs_proof_core::error::ProofError::serialization_error(
-                "Failed to parse proof file",
-                Some(Box::new(e))
-            ))?;
+    /// Gather information about a proof file
+    async fn gather_proof_info(&self, proof_path: &PathBuf) -> Result<ProofInfo> {
+        let (content, proof) = Self::load_proof(proof_path)?;
+        let format = ProofFormat::detect(&content);
 
         // Perform integrity check if requested
         let integrity_check = if self.verify_integrity {
@@ -241,17 +279,22 @@ impl InfoCommand {
         Ok(ProofInfo {
             id: proof.id.clone(),
             version: proof.version.clone(),
+            format: match format {
+                ProofFormat::Json => "json".to_string(),
+                ProofFormat::Binary => "binary".to_string(),
+            },
             created_at: proof.created_at.to_rfc3339(),
             content_selection: proof.content_selection.description(),
             content_hash: format_hash(&proof.content_hash, Some(16)),
             root_hash: format_hash(&proof.root_hash, Some(16)),
+            fingerprint: proof.fingerprint(),
             file_info: ProofFileInfo {
                 filename: proof.metadata.file_info.filename.clone(),
-                size_bytes: proof.metadata.file_info.size,
+                size_bytes: proof.metadata.file_info.size.get(),
                 mime_type: proof.metadata.file_info.mime_type.clone(),
                 ipfs_cid: proof.metadata.file_info.ipfs_cid.clone(),
                 block_count: proof.metadata.file_info.block_count,
-                avg_block_size: proof.metadata.file_info.avg_block_size,
+                avg_block_size: proof.metadata.file_info.avg_block_size.get(),
             },
             security: SecurityInfo {
                 security_level: proof.metadata.security.security_level,
@@ -261,18 +304,31 @@ impl InfoCommand {
                 formal_verification: proof.metadata.security.formal_verification,
             },
             performance: PerformanceInfo {
-                generation_time_ms: proof.metadata.performance.generation_time_ms,
-                file_processing_time_ms: proof.metadata.performance.file_processing_time_ms,
-                zk_generation_time_ms: proof.metadata.performance.zk_generation_time_ms,
-                peak_memory_bytes: proof.metadata.performance.peak_memory_bytes,
+                generation_time_ms: proof.metadata.performance.generation_time_ms.get(),
+                file_processing_time_ms: proof.metadata.performance.file_processing_time_ms.get(),
+                zk_generation_time_ms: proof.metadata.performance.zk_generation_time_ms.get(),
+                peak_memory_bytes: proof.metadata.performance.peak_memory_bytes.get(),
                 zk_cycles: proof.metadata.performance.zk_cycles,
-                proof_size_bytes: proof.metadata.performance.proof_size_bytes,
+                proof_size_bytes: proof.metadata.performance.proof_size_bytes.get(),
                 compression_ratio: proof.metadata.performance.compression_ratio,
+                segment_cycles: proof.metadata.performance.segment_cycles.clone(),
             },
             integrity_check,
         })
     }
 
+    /// Compare two proof files
+    fn compare_proofs(&self, left: &Path, right: &Path) -> Result<ProofComparison> {
+        let (_, left_proof) = Self::load_proof(left)?;
+        let (_, right_proof) = Self::load_proof(right)?;
+
+        Ok(ProofComparison {
+            left: left.display().to_string(),
+            right: right.display().to_string(),
+            diff: left_proof.diff(&right_proof),
+        })
+    }
+
     /// Gather information about a file
     async fn gather_file_info(&self, file_path: &PathBuf) -> Result<FileInfo> {
         let exists = file_path.exists();
@@ -289,37 +345,33 @@ impl InfoCommand {
         };
 
         let hash_sha256 = if exists && readable && size_bytes < 100 * 1024 * 1024 { // Only hash files < 100MB
-            let content = std::fs::read(file_path).ok();
-            content.map(|data| {
-                use sha2::{Digest, Sha256};
-                let hash = Sha256::digest(&data);
-                format_hash(hash.as_slice(), Some(16))
-            })
+            // Stream the file through the hasher rather than reading it into memory
+            use sha2::{Digest, Sha256};
+            let mut hasher = Sha256::new();
+            std::fs::File::open(file_path)
+                .and_then(|mut file| std::io::copy(&mut file, &mut hasher))
+                .ok()
+                .map(|_| format_hash(hasher.finalize().as_slice(), Some(16)))
         } else {
             None
         };
 
-        let mime_type = if exists {
-            // Simple MIME type detection based on extension
-            file_path.extension()
-                .and_then(|ext| ext.to_str())
-                .map(|ext| match ext.to_lowercase().as_str() {
-                    "txt" => "text/plain",
-                    "json" => "application/json",
-                    "pdf" => "application/pdf",
-                    "png" => "image/png",
-                    "jpg" | "jpeg" => "image/jpeg",
-                    "mp4" => "video/mp4",
-                    "csv" => "text/csv",
-                    _ => "application/octet-stream",
-                }.to_string())
+        let mime_type = if exists && readable {
+            // Only the first bytes are needed to recognise the format
+            use std::io::Read;
+            let mut head = Vec::with_capacity(zkipfs_proof_core::mime::SNIFF_LEN);
+            std::fs::File::open(file_path)
+                .and_then(|file| file.take(zkipfs_proof_core::mime::SNIFF_LEN as u64).read_to_end(&mut head))
+                .ok()
+                .map(|_| zkipfs_proof_core::mime::detect_mime_type(file_path, &head))
         } else {
             None
         };
 
         let estimated_ipfs_blocks = if size_bytes > 0 {
-            // IPFS default block size is 256KB
-            Some((size_bytes + 262143) / 262144) // Round up
+            // Estimate using the average block size of the default chunking strategy
+            let block_size = zkipfs_proof_core::ChunkingStrategy::default().average_block_size() as u64;
+            Some(size_bytes.div_ceil(block_size))
         } else {
             None
         };
@@ -340,12 +392,8 @@ impl InfoCommand {
     async fn gather_system_info(&self) -> Result<SystemInfo> {
         let sys_info = get_system_info();
         
-        // Check for Risc0 availability
-        let risc0_available = std::process::Command::new("cargo")
-            .args(&["--version"])
-            .output()
-            .map(|output| output.status.success())
-            .unwrap_or(false);
+        // Check that the zkVM prover initializes
+        let risc0_available = ProofGenerator::risc0_available();
 
         // Check hardware acceleration
         let cuda_available = std::env::var("CUDA_PATH").is_ok() || 
@@ -380,6 +428,7 @@ impl InfoCommand {
             available_memory_human: sys_info.available_memory.map(format_bytes),
             zkipfs_version: env!("CARGO_PKG_VERSION").to_string(),
             risc0_available,
+            risc0_version: ProofGenerator::risc0_version().to_string(),
             hardware_acceleration: HardwareAcceleration {
                 cuda_available,
                 metal_available,
@@ -408,71 +457,74 @@ impl InfoCommand {
     }
 
     /// Check proof integrity
-    fn check_proof_integrity(&self, proof: &Proof, json_content: &str) -> IntegrityCheck {
+    fn check_proof_integrity(&self, proof: &Proof, raw: &[u8]) -> IntegrityCheck {
         let mut issues = Vec::new();
 
-        // Check JSON validity
-        let valid_json = serde_json::from_str::<serde_json::Value>(json_content).is_ok();
-        if !valid_json {
-            issues.push("Invalid JSON format".to_string());
-        }
-
-        // Check required fields
-        let has_required_fields = !proof.id.is_empty() 
-            && !proof.version.is_empty()
-            && !proof.zk_proof.receipt.is_empty()
-            && proof.content_hash.len() == 32
-            && proof.root_hash.len() == 32;
-        
-        if !has_required_fields {
-            issues.push("Missing required fields".to_string());
-        }
-
-        // Check proof size reasonableness (should be between 1KB and 100MB)
-        let proof_size_reasonable = proof.metadata.performance.proof_size_bytes >= 1024 
-            && proof.metadata.performance.proof_size_bytes <= 100 * 1024 * 1024;
-        
-        if !proof_size_reasonable {
-            issues.push("Proof size seems unreasonable".to_string());
-        }
-
-        // Check timestamp reasonableness (not in future, not too old)
-        let now = chrono::Utc::now();
-        let timestamp_reasonable = proof.created_at <= now 
-            && (now - proof.created_at).num_days() <= 365; // Not older than 1 year
-        
-        if !timestamp_reasonable {
-            issues.push("Timestamp seems unreasonable".to_string());
-        }
-
-        // Check security level
-        if proof.metadata.security.security_level < 128 {
-            issues.push("Security level below recommended minimum".to_string());
+        // Check the encoding of the file as written
+        let valid_encoding = match ProofFormat::detect(raw) {
+            ProofFormat::Json => serde_json::from_slice::<serde_json::Value>(raw).is_ok(),
+            ProofFormat::Binary => Proof::from_bytes(raw).is_ok(),
+        };
+        if !valid_encoding {
+            issues.push("Invalid proof encoding".to_string());
         }
 
-        let valid_structure = has_required_fields && proof_size_reasonable && timestamp_reasonable;
+        // Check the fields, hashes, selection and metadata of the decoded proof
+        let structure = ProofVerifier::check_structure(proof);
+        issues.extend(structure.issues);
 
         IntegrityCheck {
-            valid_structure,
-            valid_json,
-            has_required_fields,
-            proof_size_reasonable,
-            timestamp_reasonable,
+            valid_structure: structure.valid_structure,
+            valid_encoding,
+            has_required_fields: structure.has_required_fields,
+            proof_size_reasonable: structure.proof_size_reasonable,
+            timestamp_reasonable: structure.timestamp_reasonable,
             issues,
         }
     }
 
     /// Print table-formatted output
     fn print_table_output(&self, data: &InfoOutput) {
+        if let Some(comparison) = &data.comparison {
+            println!("🔀 Proof Comparison");
+            println!("═══════════════════");
+            println!("A: {}", comparison.left);
+            println!("B: {}", comparison.right);
+            println!();
+
+            if comparison.diff.is_empty() {
+                println!("✅ No differences in compared fields");
+            } else {
+                let width = comparison.diff.differences.iter()
+                    .map(|diff| diff.field.len())
+                    .max()
+                    .unwrap_or(0)
+                    .max("Field".len());
+                println!("{:<width$}  {:<34}  {:<34}", "Field", "A", "B", width = width);
+                println!("{}", "─".repeat(width + 72));
+                for diff in &comparison.diff.differences {
+                    println!(
+                        "{:<width$}  {:<34}  {:<34}",
+                        diff.field,
+                        truncate(&diff.left, 34),
+                        truncate(&diff.right, 34),
+                        width = width
+                    );
+                }
+            }
+        }
+
         if let Some(proof_info) = &data.proof_info {
             println!("🔍 Proof Information");
             println!("═══════════════════");
             println!("ID: {}", proof_info.id);
             println!("Version: {}", proof_info.version);
+            println!("Format: {}", proof_info.format);
             println!("Created: {}", proof_info.created_at);
             println!("Content Selection: {}", proof_info.content_selection);
             println!("Content Hash: {}", proof_info.content_hash);
             println!("Root Hash: {}", proof_info.root_hash);
+            println!("Fingerprint: {}", proof_info.fingerprint);
             
             println!();
             println!("📁 File Information:");
@@ -505,6 +557,12 @@ impl InfoCommand {
                 println!("   ZK Generation: {}", format_duration(proof_info.performance.zk_generation_time_ms));
                 println!("   Peak Memory: {}", format_bytes(proof_info.performance.peak_memory_bytes));
                 println!("   ZK Cycles: {}", proof_info.performance.zk_cycles);
+                if !proof_info.performance.segment_cycles.is_empty() {
+                    println!("   Segments: {}", proof_info.performance.segment_cycles.len());
+                    for (index, segment) in proof_info.performance.segment_cycles.iter().enumerate() {
+                        println!("     #{}: {} cycles (2^{} padded)", index, segment.cycles, segment.po2);
+                    }
+                }
                 println!("   Proof Size: {}", format_bytes(proof_info.performance.proof_size_bytes));
                 if let Some(ratio) = proof_info.performance.compression_ratio {
                     println!("   Compression Ratio: {:.2}x", ratio);
@@ -515,7 +573,7 @@ impl InfoCommand {
                 println!();
                 println!("🔍 Integrity Check:");
                 println!("   Valid Structure: {}", if integrity.valid_structure { "✅" } else { "❌" });
-                println!("   Valid JSON: {}", if integrity.valid_json { "✅" } else { "❌" });
+                println!("   Valid Encoding: {}", if integrity.valid_encoding { "✅" } else { "❌" });
                 println!("   Required Fields: {}", if integrity.has_required_fields { "✅" } else { "❌" });
                 println!("   Reasonable Size: {}", if integrity.proof_size_reasonable { "✅" } else { "❌" });
                 println!("   Reasonable Timestamp: {}", if integrity.timestamp_reasonable { "✅" } else { "❌" });
@@ -564,6 +622,7 @@ impl InfoCommand {
             
             println!("zkIPFS-Proof Version: {}", system_info.zkipfs_version);
             println!("Risc0 Available: {}", if system_info.risc0_available { "✅" } else { "❌" });
+            println!("Risc0 Version: {}", system_info.risc0_version);
             println!("IPFS Available: {}", if system_info.ipfs_available { "✅" } else { "❌" });
             
             println!();
@@ -600,6 +659,16 @@ impl InfoCommand {
     }
 }
 
+/// Shortens a value to `max` characters for a table cell
+fn truncate(value: &str, max: usize) -> String {
+    if value.chars().count() <= max {
+        value.to_string()
+    } else {
+        let kept: String = value.chars().take(max - 1).collect();
+        format!("{}…", kept)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -620,6 +689,9 @@ mod tests {
             metrics: false,
             security: false,
             verify_integrity: false,
+            qr: None,
+            qr_url: None,
+            compare: None,
         };
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -642,6 +714,9 @@ mod tests {
             metrics: false,
             security: false,
             verify_integrity: false,
+            qr: None,
+            qr_url: None,
+            compare: None,
         };
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -651,6 +726,8 @@ mod tests {
         assert!(!system_info.arch.is_empty());
         assert!(system_info.cpu_count > 0);
         assert!(!system_info.zkipfs_version.is_empty());
+        assert!(!system_info.risc0_version.is_empty());
+        assert_ne!(system_info.risc0_version, "1.2.0");
     }
 
     #[test]
@@ -664,16 +741,23 @@ mod tests {
             metrics: false,
             security: false,
             verify_integrity: true,
+            qr: None,
+            qr_url: None,
+            compare: None,
         };
 
         // Create a minimal valid proof structure for testing
         let proof = Proof {
             id: "test-proof-id".to_string(),
             version: "0.1.0".to_string(),
+            proof_type: Default::default(),
+            image_id: None,
+            signature: None,
+            timestamp: None,
             created_at: chrono::Utc::now(),
             zk_proof: zkipfs_proof_core::ZkProofData {
                 receipt: vec![1, 2, 3, 4],
-                public_inputs: vec![],
+                public_inputs: vec![0; 32],
                 format_version: "1.0".to_string(),
                 compression: None,
             },
@@ -688,25 +772,29 @@ mod tests {
                 },
                 file_info: zkipfs_proof_core::FileInfo {
                     filename: Some("test.txt".to_string()),
-                    size: 100,
+                    size: zkipfs_proof_core::Bytes(100),
                     mime_type: Some("text/plain".to_string()),
                     file_hash: [0; 32],
                     ipfs_cid: "QmTest".to_string(),
                     block_count: 1,
-                    avg_block_size: 100,
+                    avg_block_size: zkipfs_proof_core::Bytes(100),
+                    source_compression: None,
+                    chunk_size: None,
                 },
                 performance: zkipfs_proof_core::PerformanceMetrics {
-                    generation_time_ms: 1000,
-                    file_processing_time_ms: 100,
-                    zk_generation_time_ms: 900,
-                    peak_memory_bytes: 1024 * 1024,
+                    generation_time_ms: zkipfs_proof_core::Millis(1000),
+                    file_processing_time_ms: zkipfs_proof_core::Millis(100),
+                    zk_generation_time_ms: zkipfs_proof_core::Millis(900),
+                    peak_memory_bytes: zkipfs_proof_core::Bytes(1024 * 1024),
                     zk_cycles: 10000,
-                    proof_size_bytes: 2048, // Reasonable size
+                    proof_size_bytes: zkipfs_proof_core::Bytes(2048), // Reasonable size
                     compression_ratio: None,
+                    segment_cycles: Vec::new(),
                 },
                 security: zkipfs_proof_core::SecurityParameters {
                     security_level: 128,
                     hash_function: "SHA-256".to_string(),
+                    hash_algorithm: zkipfs_proof_core::HashAlgorithm::Sha256,
                     proof_system: "Risc0".to_string(),
                     risc0_version: "1.2".to_string(),
                     formal_verification: false,
@@ -720,17 +808,26 @@ mod tests {
                     git_commit: None,
                 },
                 custom: std::collections::HashMap::new(),
+                valid_until: None,
+                renewed_from: Vec::new(),
+                extends: None,
+                reproducible: false,
+                content_committed: false,
             },
         };
 
         let json_content = serde_json::to_string(&proof).unwrap();
-        let integrity = cmd.check_proof_integrity(&proof, &json_content);
+        let integrity = cmd.check_proof_integrity(&proof, json_content.as_bytes());
         
-        assert!(integrity.valid_json);
+        assert!(integrity.valid_encoding);
         assert!(integrity.has_required_fields);
         assert!(integrity.proof_size_reasonable);
         assert!(integrity.timestamp_reasonable);
         assert!(integrity.valid_structure);
+
+        let binary = proof.to_bytes().unwrap();
+        assert!(cmd.check_proof_integrity(&proof, &binary).valid_encoding);
+        assert!(!cmd.check_proof_integrity(&proof, &binary[..binary.len() / 2]).valid_encoding);
     }
 }
 
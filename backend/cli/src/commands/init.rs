@@ -1,14 +1,121 @@
 //! Init command implementation
 //!
-//! This module implements the `init` command which initializes the configuration
-//! and sets up the zkIPFS-Proof environment.
+//! This module implements the `init` command, which probes the machine for an
+//! IPFS daemon, GPU acceleration and memory, and writes a commented
+//! `config.toml` tailored to what it finds.
 
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 use zkipfs_proof_core::error::{ProofError, Result};
-use crate::{config::{Config, save_config}, commands::output};
+use zkipfs_proof_core::ipfs_client::{IpfsClient, IpfsConfig};
+use crate::config::Config;
+
+/// IPFS API addresses a local daemon listens on by default
+const IPFS_API_CANDIDATES: &[&str] = &["http://127.0.0.1:5001", "http://localhost:5001"];
+
+/// Seconds to wait for an IPFS daemon to answer the probe
+const IPFS_PROBE_TIMEOUT_SECS: u64 = 2;
+
+/// Explanations written above each field of the generated config, by table and key
+const FIELD_COMMENTS: &[(&str, &str, &str)] = &[
+    ("", "default_security_level", "Security level of generated proofs in bits: 128, 192 or 256"),
+    ("", "default_prover", "Where proofs are generated: local, or bonsai for the remote prover"),
+    ("", "default_compression", "Compression applied to proofs: none, gzip or zstd"),
+    ("", "max_memory_mb", "Memory the prover may use, suggested from the memory available at init"),
+    ("", "default_timeout_seconds", "Seconds a proof may take before generation is abandoned"),
+    ("", "use_hardware_acceleration", "Use CUDA or Metal when available; enabled if either was detected at init"),
+    ("", "include_metrics_by_default", "Record performance metrics in every proof"),
+    ("api", "ipfs_endpoint", "IPFS API the prover talks to"),
+    ("api", "request_timeout_seconds", "Seconds before an API request is abandoned"),
+    ("api", "max_retries", "Times a failed API request is retried"),
+    ("ipfs", "api_url", "IPFS daemon API, detected at init if one was running"),
+    ("ipfs", "api_urls", "Further IPFS APIs to fail over to"),
+    ("ipfs", "round_robin", "Spread requests across all IPFS APIs in turn"),
+    ("ipfs", "gateway_url", "IPFS gateway, read from the daemon's Addresses.Gateway at init"),
+    ("ipfs", "gateway_fallback", "Fetch content from the gateway, checked against its CID, when the API fails"),
+    ("ipfs", "timeout", "Seconds before an IPFS request is abandoned"),
+    ("ipfs", "auto_pin", "Pin uploaded files so the daemon keeps them"),
+    ("logging", "level", "Log level: trace, debug, info, warn or error"),
+    ("logging", "log_to_file", "Write logs to log_file as well as the terminal"),
+    ("logging", "max_log_size_mb", "Size at which the log file is rotated"),
+    ("logging", "log_file_count", "Rotated log files to keep"),
+    ("performance", "chunk_size_bytes", "Size of the blocks files are split into"),
+    ("performance", "adaptive_chunking", "Size blocks from each file's length instead of the fixed default"),
+    ("performance", "max_in_memory_size_mb", "Files larger than this are streamed rather than read into memory"),
+    ("performance", "enable_profiling", "Record a profile of each proof generation"),
+];
+
+/// What `init` found about the machine
+#[derive(Debug, Default)]
+struct Environment {
+    /// API URL of a running IPFS daemon
+    ipfs_api_url: Option<String>,
+    /// Gateway URL the daemon serves
+    ipfs_gateway_url: Option<String>,
+    /// Detected GPU acceleration, `cuda` or `metal`
+    acceleration: Option<&'static str>,
+    /// Available memory in bytes
+    available_memory: Option<u64>,
+}
+
+impl Environment {
+    /// Probes for an IPFS daemon at each of `ipfs_api_urls`, GPU acceleration
+    /// and available memory
+    async fn probe(ipfs_api_urls: &[&str]) -> Self {
+        let mut environment = Self {
+            acceleration: if cuda_available() {
+                Some("cuda")
+            } else if cfg!(target_os = "macos") {
+                Some("metal")
+            } else {
+                None
+            },
+            available_memory: get_available_memory(),
+            ..Self::default()
+        };
+
+        for url in ipfs_api_urls {
+            let Ok(client) = IpfsClient::with_config(IpfsConfig {
+                api_url: url.to_string(),
+                timeout: IPFS_PROBE_TIMEOUT_SECS,
+                ..IpfsConfig::default()
+            }) else {
+                continue;
+            };
+            if client.is_online().await {
+                environment.ipfs_api_url = Some(url.to_string());
+                environment.ipfs_gateway_url = client.node_gateway_url().await.ok().flatten();
+                break;
+            }
+        }
+        environment
+    }
+
+    /// Default configuration with the detected settings applied
+    fn config(&self) -> Config {
+        let mut config = Config::default();
+        if let Some(api_url) = &self.ipfs_api_url {
+            config.ipfs.api_url = api_url.clone();
+            config.api.ipfs_endpoint = Some(api_url.clone());
+        }
+        if let Some(gateway_url) = &self.ipfs_gateway_url {
+            config.ipfs.gateway_url = gateway_url.clone();
+        }
+        config.use_hardware_acceleration = self.acceleration.is_some();
+        // Leave a quarter of the memory to the rest of the system
+        config.max_memory_mb = self.available_memory.map(|bytes| bytes / (1024 * 1024) * 3 / 4);
+        config
+    }
+}
 
 /// Initialize configuration and setup
 pub async fn execute(force: bool, config_dir: Option<&Path>) -> Result<()> {
+    init(force, config_dir, IPFS_API_CANDIDATES, std::io::stdin().is_terminal()).await
+}
+
+/// Writes a configuration tailored to the machine, asking before overwriting
+/// an existing one when `interactive`
+async fn init(force: bool, config_dir: Option<&Path>, ipfs_api_urls: &[&str], interactive: bool) -> Result<()> {
     println!("🚀 Initializing zkIPFS-Proof...");
     
     // Determine config directory
@@ -19,28 +126,36 @@ pub async fn execute(force: bool, config_dir: Option<&Path>) -> Result<()> {
     };
 
     // Check if config already exists
-    if config_path.exists() && !force {
+    if config_path.exists() && !force && !(interactive && confirm_overwrite(&config_path)?) {
         return Err(ProofError::configuration_error(
             format!("Configuration already exists at {}. Use --force to overwrite.", config_path.display())
         ));
     }
 
-    // Create default configuration
-    let config = Config::default();
-
-    // Save configuration
-    save_config(&config, Some(&config_path)).await?;
+    println!("🔍 Probing environment...");
+    let environment = Environment::probe(ipfs_api_urls).await;
+    let config = environment.config();
+    write_commented_config(&config, &config_path).await?;
 
     println!("✅ Configuration initialized at: {}", config_path.display());
     println!();
-    println!("📋 Default Settings:");
+    println!("📋 Detected Settings:");
     println!("   Security Level: {} bits", config.default_security_level);
     println!("   Prover: {}", config.default_prover);
     println!("   Compression: {}", config.default_compression);
-    println!("   Hardware Acceleration: {}", if config.use_hardware_acceleration { "Enabled" } else { "Disabled" });
-    
-    if let Some(ipfs_endpoint) = &config.api.ipfs_endpoint {
-        println!("   IPFS Endpoint: {}", ipfs_endpoint);
+    match environment.acceleration {
+        Some(acceleration) => println!("   Hardware Acceleration: Enabled ({})", acceleration),
+        None => println!("   Hardware Acceleration: Disabled (no CUDA or Metal found)"),
+    }
+    if let Some(max_memory_mb) = config.max_memory_mb {
+        println!("   Max Memory: {}MB", max_memory_mb);
+    }
+    match &environment.ipfs_api_url {
+        Some(api_url) => {
+            println!("   IPFS API: {} (daemon running)", api_url);
+            println!("   IPFS Gateway: {}", config.ipfs.gateway_url);
+        }
+        None => println!("   IPFS API: {} (no daemon found; start one with `ipfs daemon`)", config.ipfs.api_url),
     }
 
     println!();
@@ -58,6 +173,42 @@ pub async fn execute(force: bool, config_dir: Option<&Path>) -> Result<()> {
     Ok(())
 }
 
+/// Asks whether to overwrite the configuration at `path`
+fn confirm_overwrite(path: &Path) -> Result<bool> {
+    dialoguer::Confirm::new()
+        .with_prompt(format!("Configuration already exists at {}. Overwrite it?", path.display()))
+        .default(false)
+        .interact()
+        .map_err(|e| ProofError::internal_error("Failed to read confirmation", Some(Box::new(e))))
+}
+
+/// Writes `config` to `path` as TOML, with a comment explaining each field
+async fn write_commented_config(config: &Config, path: &Path) -> Result<()> {
+    let toml = toml::to_string_pretty(config)
+        .map_err(|e| ProofError::serialization_error("Failed to serialize config", Some(Box::new(e))))?;
+
+    let mut content = String::from("# zkIPFS-Proof configuration, generated by `zkipfs-proof init`\n\n");
+    let mut table = "";
+    for line in toml.lines() {
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            table = name;
+        } else if let Some((key, _)) = line.split_once(" = ") {
+            if let Some((_, _, comment)) = FIELD_COMMENTS.iter().find(|(t, k, _)| *t == table && *k == key) {
+                content.push_str(&format!("# {}\n", comment));
+            }
+        }
+        content.push_str(line);
+        content.push('\n');
+    }
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await
+            .map_err(|e| ProofError::file_error(format!("Failed to create config directory: {}", parent.display()), Some(e)))?;
+    }
+    tokio::fs::write(path, content).await
+        .map_err(|e| ProofError::file_error(format!("Failed to write config file: {}", path.display()), Some(e)))
+}
+
 /// Get the default configuration file path
 fn get_default_config_path() -> Result<PathBuf> {
     let config_dir = dirs::config_dir()
@@ -115,12 +266,7 @@ async fn check_system_requirements() -> Result<()> {
     }
 
     // Check hardware acceleration
-    let cuda_available = std::env::var("CUDA_PATH").is_ok() || 
-        std::process::Command::new("nvcc")
-            .arg("--version")
-            .output()
-            .map(|output| output.status.success())
-            .unwrap_or(false);
+    let cuda_available = cuda_available();
 
     if cuda_available {
         println!("   ✅ CUDA available (hardware acceleration)");
@@ -174,6 +320,16 @@ async fn check_system_requirements() -> Result<()> {
     Ok(())
 }
 
+/// Whether a CUDA toolkit is installed
+fn cuda_available() -> bool {
+    std::env::var("CUDA_PATH").is_ok() ||
+        std::process::Command::new("nvcc")
+            .arg("--version")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+}
+
 /// Get available system memory in bytes (simplified implementation)
 fn get_available_memory() -> Option<u64> {
     #[cfg(target_os = "linux")]
@@ -229,7 +385,7 @@ mod tests {
         // Create existing config
         std::fs::write(&config_path, "test").unwrap();
         
-        let result = execute(false, Some(temp_dir.path())).await;
+        let result = init(false, Some(temp_dir.path()), &[], false).await;
         assert!(result.is_err());
     }
 
@@ -249,6 +405,61 @@ mod tests {
         assert!(content.contains("default_security_level"));
     }
 
+    /// IPFS API answering `version`, and `config` with a gateway on port 8088
+    async fn spawn_mock_ipfs() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = stream.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    let head = String::from_utf8_lossy(&request).to_string();
+                    let body = if head.starts_with("POST /api/v0/version") {
+                        r#"{"Version":"0.30.0"}"#
+                    } else if head.starts_with("POST /api/v0/config") {
+                        r#"{"Key":"Addresses.Gateway","Value":"/ip4/0.0.0.0/tcp/8088"}"#
+                    } else {
+                        "{}"
+                    };
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_init_writes_detected_ipfs_endpoint() {
+        let temp_dir = tempdir().unwrap();
+        let ipfs_url = spawn_mock_ipfs().await;
+
+        init(false, Some(temp_dir.path()), &["http://127.0.0.1:1", &ipfs_url], false).await.unwrap();
+
+        let content = std::fs::read_to_string(temp_dir.path().join("config.toml")).unwrap();
+        assert!(content.contains(&format!("api_url = \"{}\"", ipfs_url)), "{}", content);
+        assert!(content.contains("gateway_url = \"http://127.0.0.1:8088\""), "{}", content);
+        assert!(content.contains("# IPFS daemon API, detected at init"));
+
+        let config: Config = toml::from_str(&content).unwrap();
+        assert_eq!(config.api.ipfs_endpoint.as_deref(), Some(ipfs_url.as_str()));
+    }
+
     #[test]
     fn test_get_default_config_path() {
         let path = get_default_config_path();
@@ -4,7 +4,7 @@
 //! uploading files, and managing decentralized storage.
 
 use clap::{Args, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use zkipfs_proof_core::ipfs_client::{IpfsClient, IpfsConfig, Cid};
 use crate::{config::Config, progress::ProgressTracker, utils::format_bytes};
 use anyhow::{Result, Context};
@@ -263,13 +263,10 @@ async fn download_file(
         anyhow::bail!("IPFS node is not accessible. Please ensure IPFS is running.");
     }
 
-    let mut progress = ProgressTracker::new("Downloading from IPFS");
-    progress.set_message(&format!("Retrieving file with CID: {}", cid.as_str()));
-
-    let content = client.get_file(&cid).await
-        .context("Failed to download file from IPFS")?;
-
-    progress.set_message("Writing file to disk...");
+    // The size only scales the progress bar, so a failed stat is not fatal
+    let total_size = client.stat_file(&cid).await.map(|stat| stat.size).unwrap_or(0);
+    let progress = ProgressTracker::file_progress(total_size, "Downloading from IPFS");
+    progress.set_message(&format!("CID: {}", cid.as_str()));
 
     // Create parent directories if they don't exist
     if let Some(parent) = output_path.parent() {
@@ -277,19 +274,54 @@ async fn download_file(
             .context("Failed to create output directory")?;
     }
 
-    std::fs::write(&output_path, content)
-        .context("Failed to write file to disk")?;
+    let size = tokio::select! {
+        result = download_with_progress(&client, &cid, &output_path, &progress) => result?,
+        _ = tokio::signal::ctrl_c() => {
+            // Dropping the download closes the file before it is removed
+            let _ = tokio::fs::remove_file(&output_path).await;
+            progress.finish_with_error("Download cancelled");
+            anyhow::bail!("Download cancelled, removed partial file {}", output_path.display());
+        }
+    };
 
-    progress.finish_with_message("Download completed successfully!");
+    progress.finish("Download completed successfully!");
 
     println!("✅ File downloaded successfully!");
     println!("   CID: {}", cid.as_str());
     println!("   Output: {}", output_path.display());
-    println!("   Size: {}", format_bytes(std::fs::metadata(&output_path)?.len()));
+    println!("   Size: {}", format_bytes(size));
 
     Ok(())
 }
 
+/// Streams `cid` into `output_path`, advancing `progress` by the bytes received
+///
+/// The partial file is removed if the download fails. On success the bar is
+/// completed at the downloaded size, which can be below the stat size since
+/// that includes the DAG's own encoding.
+async fn download_with_progress(
+    client: &IpfsClient,
+    cid: &Cid,
+    output_path: &Path,
+    progress: &ProgressTracker,
+) -> Result<u64> {
+    let mut file = tokio::fs::File::create(output_path).await
+        .context("Failed to create output file")?;
+
+    match client.download_to(cid, &mut file, |received| progress.set_file_progress(received)).await {
+        Ok(size) => {
+            progress.set_length(size);
+            progress.set_file_progress(size);
+            Ok(size)
+        }
+        Err(e) => {
+            drop(file);
+            let _ = tokio::fs::remove_file(output_path).await;
+            Err(e).context("Failed to download file from IPFS")
+        }
+    }
+}
+
 async fn pin_file(
     cid_str: String,
     api_url: Option<String>,
@@ -535,10 +567,15 @@ async fn handle_ipfs_config(
 fn create_ipfs_config(api_url: Option<String>, config: &Config) -> Result<IpfsConfig> {
     let mut ipfs_config = IpfsConfig {
         api_url: api_url.unwrap_or_else(|| config.ipfs.api_url.clone()),
+        api_urls: config.ipfs.api_urls.clone(),
+        round_robin: config.ipfs.round_robin,
         gateway_url: config.ipfs.gateway_url.clone(),
+        gateway_fallback: config.ipfs.gateway_fallback,
         timeout: config.ipfs.timeout,
         auto_pin: config.ipfs.auto_pin,
         headers: std::collections::HashMap::new(),
+        max_retries: config.api.max_retries,
+        ..IpfsConfig::default()
     };
 
     // Add any custom headers from config
@@ -549,3 +586,78 @@ fn create_ipfs_config(api_url: Option<String>, config: &Config) -> Result<IpfsCo
     Ok(ipfs_config)
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const CID: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+
+    /// Serves `object/stat` with the size of `content`, and `cat` with `content` in small writes
+    async fn spawn_node(content: Vec<u8>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let content = content.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = stream.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                    }
+
+                    let head = String::from_utf8_lossy(&request);
+                    let body = if head.starts_with("POST /api/v0/object/stat") {
+                        format!(
+                            "{{\"Hash\":\"{}\",\"NumLinks\":0,\"DataSize\":{},\"CumulativeSize\":{},\"LinksSize\":0}}",
+                            CID, content.len(), content.len()
+                        ).into_bytes()
+                    } else {
+                        content
+                    };
+                    let reply = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+                    let _ = stream.write_all(reply.as_bytes()).await;
+                    for chunk in body.chunks(16 * 1024) {
+                        let _ = stream.write_all(chunk).await;
+                        let _ = stream.flush().await;
+                    }
+                });
+            }
+        });
+
+        url
+    }
+
+    #[tokio::test]
+    async fn test_download_reports_byte_progress() {
+        let content: Vec<u8> = (0..256 * 1024u32).map(|i| (i % 253) as u8).collect();
+        let client = IpfsClient::with_config(IpfsConfig {
+            api_url: spawn_node(content.clone()).await,
+            timeout: 10,
+            ..IpfsConfig::default()
+        }).unwrap();
+        let cid = Cid::new(CID.to_string());
+
+        let total_size = client.stat_file(&cid).await.unwrap().size;
+        assert_eq!(total_size, content.len() as u64);
+
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("download.bin");
+        let progress = ProgressTracker::file_progress(total_size, "Downloading from IPFS");
+        let size = download_with_progress(&client, &cid, &output_path, &progress).await.unwrap();
+
+        assert_eq!(size, content.len() as u64);
+        assert_eq!(progress.length(), Some(total_size));
+        assert_eq!(progress.position(), total_size);
+        assert_eq!(std::fs::read(&output_path).unwrap(), content);
+    }
+}
@@ -12,6 +12,9 @@ pub mod version;
 pub mod benchmark;
 pub mod config;
 pub mod ipfs;
+pub mod cache;
+pub mod export;
+pub mod completions;
 
 use zkipfs_proof_core::error::Result;
 
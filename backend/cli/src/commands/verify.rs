@@ -4,15 +4,20 @@
 //! against claimed content. It provides detailed verification results and supports
 //! various verification modes.
 
+use chrono::{DateTime, Utc};
 use clap::Args;
+use notify::{EventKind, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::time::Instant;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
 use zkipfs_proof_core::{
-    ProofVerifier, VerificationConfig, Proof,
-    error::Result,
+    ProofVerifier, VerificationConfig, VerificationFailure, Preset, Proof, ProofFormat,
+    ProofBundle, ProofGenerator, ProofConfig,
+    error::{ProofError, Result},
 };
 use crate::{
     config::Config,
@@ -21,12 +26,27 @@ use crate::{
     commands::{Command, output},
 };
 
+/// Quiet period after a change before re-verifying in watch mode
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Step named in a `StepTimeout` failure when an on-chain call is too slow
+const ON_CHAIN_STEP: &str = "On-chain Verification";
+
 /// Verify a zero-knowledge proof
 #[derive(Args, Debug)]
 pub struct VerifyCommand {
     /// Path to the proof file to verify
-    #[arg(short, long, value_name = "FILE")]
-    pub proof: PathBuf,
+    #[arg(short, long, value_name = "FILE", required_unless_present = "bundle")]
+    pub proof: Option<PathBuf>,
+
+    /// Proof bundle (.zkb) to verify, checking its proof against the blocks
+    /// packaged with it
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["proof", "batch", "on_chain"])]
+    pub bundle: Option<PathBuf>,
+
+    /// Expected proof file format (json or bin); detected from the file by default
+    #[arg(long = "format", value_name = "FORMAT")]
+    pub proof_format: Option<String>,
 
     /// Path to the original file (optional, for content verification)
     #[arg(short, long, value_name = "FILE")]
@@ -36,6 +56,11 @@ pub struct VerifyCommand {
     #[arg(short, long, value_name = "CONTENT")]
     pub content: Option<String>,
 
+    /// Verification strictness preset (lenient, standard or paranoid),
+    /// adjusted by the other verification flags
+    #[arg(long, value_name = "PRESET")]
+    pub preset: Option<Preset>,
+
     /// Enable strict verification mode
     #[arg(long)]
     pub strict: bool,
@@ -48,6 +73,11 @@ pub struct VerifyCommand {
     #[arg(long)]
     pub max_age_days: Option<u64>,
 
+    /// Fail verification if the receipt check, custom rules or an on-chain call
+    /// takes longer than this many seconds
+    #[arg(long, value_name = "SECONDS")]
+    pub step_timeout: Option<u64>,
+
     /// Minimum required security level
     #[arg(long)]
     pub min_security_level: Option<u32>,
@@ -79,19 +109,45 @@ pub struct VerifyCommand {
     /// Directory containing proof files for batch verification
     #[arg(long)]
     pub batch_dir: Option<PathBuf>,
+
+    /// Only verify batch proofs created at or after this time (RFC 3339)
+    #[arg(long, value_name = "TIME", requires = "batch", value_parser = parse_rfc3339)]
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only verify batch proofs created at or before this time (RFC 3339)
+    #[arg(long, value_name = "TIME", requires = "batch", value_parser = parse_rfc3339)]
+    pub until: Option<DateTime<Utc>>,
+
+    /// Re-verify whenever the proof or content file changes, until Ctrl-C
+    #[arg(long, conflicts_with_all = ["batch", "on_chain"])]
+    pub watch: bool,
+
+    /// Verify without any network access, refusing checks such as --on-chain
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Explain in plain language what the proof shows
+    #[arg(long)]
+    pub explain: bool,
 }
 
 #[derive(Serialize, Deserialize)]
 struct VerifyOutput {
     proof_id: String,
     is_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<VerificationFailure>,
     verification_time_ms: u64,
     verifier_version: String,
     verification_method: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     proof_metadata: Option<ProofMetadataSummary>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    explanation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     verification_steps: Option<Vec<VerificationStepSummary>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    timings: HashMap<String, u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     warnings: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -121,6 +177,8 @@ struct BatchVerifyOutput {
     total_proofs: usize,
     valid_proofs: usize,
     invalid_proofs: usize,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    skipped_proofs: usize,
     total_verification_time_ms: u64,
     results: Vec<VerifyOutput>,
     summary: BatchSummary,
@@ -137,7 +195,9 @@ struct BatchSummary {
 
 impl Command for VerifyCommand {
     async fn execute(&self, config: &Config, output_format: &str) -> Result<()> {
-        if self.batch {
+        if self.watch {
+            self.execute_watch(config).await
+        } else if self.batch {
             self.execute_batch_verification(config, output_format).await
         } else {
             self.execute_single_verification(config, output_format).await
@@ -159,7 +219,11 @@ impl VerifyCommand {
         let mut progress = ProgressTracker::new("Verifying proof");
         progress.set_message("Loading proof file...");
         
-        let proof = self.load_proof_file()?;
+        let bundle = self.bundle.as_deref().map(load_bundle).transpose()?;
+        let proof = match &bundle {
+            Some(bundle) => bundle.proof.clone(),
+            None => self.load_proof_file()?,
+        };
         progress.set_progress(20);
 
         // Prepare content for verification
@@ -169,18 +233,31 @@ impl VerifyCommand {
 
         // Create verification configuration
         let verification_config = self.create_verification_config(config)?;
+        if self.on_chain {
+            verification_config.ensure_online("On-chain verification")?;
+        }
 
         // Create verifier
+        let step_timeout = verification_config.step_timeout;
         let mut verifier = ProofVerifier::with_config(verification_config);
         progress.set_progress(50);
 
         // Perform verification
         progress.set_message("Performing cryptographic verification...");
-        let verification_result = if self.on_chain {
-            self.verify_on_chain(&proof, &content).await?
+        let mut verification_result = if self.on_chain {
+            self.verify_on_chain_within(&proof, &content, step_timeout).await?
         } else {
             verifier.verify_detailed(&proof, &content).await?
         };
+
+        if let Some(bundle) = &bundle {
+            progress.set_message("Checking bundled blocks...");
+            if let Some(failure) = bundle_block_failure(bundle).await? {
+                verification_result.is_valid = false;
+                errors.push(format!("Bundled blocks do not back the proof ({})", failure));
+                verification_result.failure.get_or_insert(failure);
+            }
+        }
         progress.set_progress(90);
 
         // Save report if requested
@@ -197,17 +274,20 @@ impl VerifyCommand {
         let output_data = VerifyOutput {
             proof_id: proof.id.clone(),
             is_valid: verification_result.is_valid,
+            failure: verification_result.failure.clone(),
             verification_time_ms: verification_time.as_millis() as u64,
             verifier_version: env!("CARGO_PKG_VERSION").to_string(),
             verification_method: if self.on_chain { "on-chain".to_string() } else { "local".to_string() },
             proof_metadata: Some(ProofMetadataSummary {
                 security_level: proof.metadata.security.security_level,
                 proof_system: proof.metadata.security.proof_system.clone(),
-                file_size_bytes: proof.metadata.file_info.size,
-                proof_size_bytes: proof.metadata.performance.proof_size_bytes,
-                generation_time_ms: proof.metadata.performance.generation_time_ms,
+                file_size_bytes: proof.metadata.file_info.size.get(),
+                proof_size_bytes: proof.metadata.performance.proof_size_bytes.get(),
+                generation_time_ms: proof.metadata.performance.generation_time_ms.get(),
                 created_at: proof.created_at.to_rfc3339(),
             }),
+            explanation: self.explain.then(|| proof.explanation()),
+            timings: verification_result.timings,
             verification_steps: if self.detailed {
                 Some(verification_result.verification_steps.into_iter().map(|step| {
                     VerificationStepSummary {
@@ -256,6 +336,19 @@ impl VerifyCommand {
             ));
         }
 
+        let found = proof_files.len();
+        let proof_files = self.filter_by_created_at(proof_files);
+        let skipped_count = found - proof_files.len();
+        if skipped_count > 0 {
+            info!("Skipping {} proofs created outside the --since/--until window", skipped_count);
+        }
+        if proof_files.is_empty() {
+            return Err(zkipfs_proof_core::error::ProofError::invalid_input_error(
+                "since",
+                format!("None of the {} proof files were created in the --since/--until window", found)
+            ));
+        }
+
         let mut progress = ProgressTracker::new("Batch verification");
         progress.set_message(&format!("Found {} proof files", proof_files.len()));
 
@@ -276,7 +369,7 @@ impl VerifyCommand {
             
             // Create a temporary verify command for this proof
             let mut single_verify = self.clone();
-            single_verify.proof = proof_file.clone();
+            single_verify.proof = Some(proof_file.clone());
             single_verify.batch = false;
             
             match single_verify.execute_single_verification(config, "json").await {
@@ -288,11 +381,14 @@ impl VerifyCommand {
                     results.push(VerifyOutput {
                         proof_id: format!("proof_{}", i),
                         is_valid: true,
+                        failure: None,
                         verification_time_ms: verification_time,
                         verifier_version: env!("CARGO_PKG_VERSION").to_string(),
                         verification_method: "local".to_string(),
                         proof_metadata: None,
+                        explanation: None,
                         verification_steps: None,
+                        timings: HashMap::new(),
                         warnings: None,
                         errors: None,
                     });
@@ -304,11 +400,14 @@ impl VerifyCommand {
                     results.push(VerifyOutput {
                         proof_id: format!("proof_{}", i),
                         is_valid: false,
+                        failure: None,
                         verification_time_ms: verification_time,
                         verifier_version: env!("CARGO_PKG_VERSION").to_string(),
                         verification_method: "local".to_string(),
                         proof_metadata: None,
+                        explanation: None,
                         verification_steps: None,
+                        timings: HashMap::new(),
                         warnings: None,
                         errors: Some(vec!["Verification failed".to_string()]),
                     });
@@ -335,6 +434,7 @@ impl VerifyCommand {
             total_proofs: results.len(),
             valid_proofs: valid_count,
             invalid_proofs: invalid_count,
+            skipped_proofs: skipped_count,
             total_verification_time_ms: total_time.as_millis() as u64,
             results,
             summary,
@@ -349,10 +449,45 @@ impl VerifyCommand {
         Ok(())
     }
 
+    /// Re-verify the proof each time it or the content file changes
+    async fn execute_watch(&self, config: &Config) -> Result<()> {
+        let mut warnings = Vec::new();
+        self.validate_inputs(&mut warnings)?;
+        for warning in &warnings {
+            warn!("{}", warning);
+        }
+
+        let mut paths = vec![self.proof_path()?.to_path_buf()];
+        paths.extend(self.file.clone());
+        let names: Vec<String> = paths.iter().map(|path| path.display().to_string()).collect();
+        println!("👀 Watching {} for changes (Ctrl-C to stop)", names.join(", "));
+
+        let shutdown = async {
+            let _ = tokio::signal::ctrl_c().await;
+        };
+        watch_and_verify(
+            &paths,
+            WATCH_DEBOUNCE,
+            shutdown,
+            || self.verify_current(config),
+            |line| println!("{}", line),
+        ).await
+    }
+
+    /// Reads the proof and content as they are now and verifies them, returning
+    /// whether the proof is valid and why not
+    async fn verify_current(&self, config: &Config) -> Result<(bool, Option<VerificationFailure>)> {
+        let proof = self.load_proof_file()?;
+        let content = self.prepare_verification_content(&proof)?;
+        let mut verifier = ProofVerifier::with_config(self.create_verification_config(config)?);
+        let result = verifier.verify_detailed(&proof, &content).await?;
+        Ok((result.is_valid, result.failure))
+    }
+
     /// Validate command inputs
     fn validate_inputs(&self, warnings: &mut Vec<String>) -> Result<()> {
         // Validate proof file
-        validate_file_path(&self.proof)?;
+        validate_file_path(self.proof_path()?)?;
 
         // Validate content source
         if self.file.is_none() && self.content.is_none() {
@@ -391,19 +526,42 @@ impl VerifyCommand {
         Ok(())
     }
 
+    /// Returns the proof file, or the bundle holding the proof
+    fn proof_path(&self) -> Result<&Path> {
+        self.bundle.as_deref()
+            .or(self.proof.as_deref())
+            .ok_or_else(|| ProofError::invalid_input_error("proof", "A proof file or --bundle is required"))
+    }
+
     /// Load proof from file
     fn load_proof_file(&self) -> Result<Proof> {
-        let content = std::fs::read_to_string(&self.proof)
+        if let Some(bundle_path) = &self.bundle {
+            return Ok(load_bundle(bundle_path)?.proof);
+        }
+
+        self.decode_proof_file(self.proof_path()?)
+    }
+
+    /// Reads and decodes a proof file, checking its format if one was given
+    fn decode_proof_file(&self, proof_path: &Path) -> Result<Proof> {
+        let content = std::fs::read(proof_path)
             .map_err(|e| zkipfs_proof_core::error::ProofError::file_error(
-                format!("Failed to read proof file: {}", self.proof.display()),
+                format!("Failed to read proof file: {}", proof_path.display()),
                 Some(e)
             ))?;
 
-        serde_json::from_str(&content)
-            .map_err(|e| zkipfs_proof_core::error::ProofError::serialization_error(
-                "Failed to parse proof file",
-                Some(Box::new(e))
-            ))
+        if let Some(expected) = &self.proof_format {
+            let expected: ProofFormat = expected.parse()?;
+            let detected = ProofFormat::detect(&content);
+            if detected != expected {
+                return Err(zkipfs_proof_core::error::ProofError::invalid_input_error(
+                    "format",
+                    format!("Proof file is in {:?} format, not {:?}", detected, expected)
+                ));
+            }
+        }
+
+        Proof::decode(&content)
     }
 
     /// Prepare content for verification
@@ -424,14 +582,22 @@ impl VerifyCommand {
 
     /// Create verification configuration
     fn create_verification_config(&self, config: &Config) -> Result<VerificationConfig> {
-        let mut verification_config = VerificationConfig::default();
-        
-        verification_config.strict_verification = self.strict;
+        let mut verification_config = match self.preset {
+            Some(preset) => VerificationConfig::preset(preset),
+            // Without a preset, strict checks are only made with --strict
+            None => VerificationConfig { strict_verification: false, ..VerificationConfig::default() },
+        };
+
+        verification_config.strict_verification |= self.strict;
         verification_config.include_verification_steps = self.detailed;
         
         if let Some(max_age_days) = self.max_age_days {
             verification_config.max_proof_age_seconds = Some(max_age_days * 24 * 60 * 60);
         }
+        if let Some(seconds) = self.step_timeout {
+            verification_config.step_timeout = Some(Duration::from_secs(seconds));
+        }
+        verification_config.offline = self.offline;
 
         // Add custom rules based on command line options
         if let Some(min_security) = self.min_security_level {
@@ -457,14 +623,43 @@ impl VerifyCommand {
         Ok(verification_config)
     }
 
+    /// Verifies a proof on-chain, failing with `StepTimeout` if the call takes longer than `step_timeout`
+    async fn verify_on_chain_within(
+        &self,
+        proof: &Proof,
+        content: &[u8],
+        step_timeout: Option<Duration>,
+    ) -> Result<zkipfs_proof_core::VerificationResult> {
+        let Some(step_timeout) = step_timeout else {
+            return self.verify_on_chain(proof, content).await;
+        };
+        match tokio::time::timeout(step_timeout, self.verify_on_chain(proof, content)).await {
+            Ok(result) => result,
+            Err(_) => {
+                let mut result = Self::on_chain_result(proof, vec![format!(
+                    "On-chain verification did not finish within {}",
+                    format_duration(step_timeout.as_millis() as u64)
+                )]);
+                result.failure = Some(VerificationFailure::StepTimeout(ON_CHAIN_STEP.to_string()));
+                Ok(result)
+            }
+        }
+    }
+
     /// Verify proof on-chain
     async fn verify_on_chain(&self, proof: &Proof, content: &[u8]) -> Result<zkipfs_proof_core::VerificationResult> {
         // This would implement actual on-chain verification
         // For now, return a placeholder
         warn!("On-chain verification not yet implemented");
         
-        Ok(zkipfs_proof_core::VerificationResult {
+        Ok(Self::on_chain_result(proof, vec!["On-chain verification not yet implemented".to_string()]))
+    }
+
+    /// Builds a failing on-chain verification result for `proof`
+    fn on_chain_result(proof: &Proof, warnings: Vec<String>) -> zkipfs_proof_core::VerificationResult {
+        zkipfs_proof_core::VerificationResult {
             is_valid: false,
+            failure: None,
             verified_at: chrono::Utc::now(),
             verification_time_ms: 0,
             verifier_info: zkipfs_proof_core::VerifierInfo {
@@ -472,9 +667,17 @@ impl VerifyCommand {
                 method: zkipfs_proof_core::VerificationMethod::OnChain,
                 environment: "blockchain".to_string(),
             },
-            warnings: vec!["On-chain verification not yet implemented".to_string()],
+            warnings,
             verification_steps: vec![],
-        })
+            checks_passed: 0,
+            checks_run: 0,
+            security_level: Some(proof.metadata.security.security_level),
+            proof_created_at: Some(proof.created_at),
+            signature_verified: false,
+            transparency_verified: false,
+            from_cache: false,
+            timings: HashMap::new(),
+        }
     }
 
     /// Save verification report to file
@@ -515,7 +718,7 @@ impl VerifyCommand {
             ))?;
             
             let path = entry.path();
-            if path.is_file() && path.extension().map_or(false, |ext| ext == "json") {
+            if path.is_file() && path.extension().map_or(false, |ext| ext == "json" || ext == "bin") {
                 proof_files.push(path);
             }
         }
@@ -524,6 +727,23 @@ impl VerifyCommand {
         Ok(proof_files)
     }
 
+    /// Keeps the proof files created within `--since`..=`--until`
+    ///
+    /// Files that cannot be decoded are kept, so that verifying them reports the error.
+    fn filter_by_created_at(&self, proof_files: Vec<PathBuf>) -> Vec<PathBuf> {
+        if self.since.is_none() && self.until.is_none() {
+            return proof_files;
+        }
+
+        proof_files.into_iter()
+            .filter(|path| match self.decode_proof_file(path) {
+                Ok(proof) => self.since.map_or(true, |since| proof.created_at >= since)
+                    && self.until.map_or(true, |until| proof.created_at <= until),
+                Err(_) => true,
+            })
+            .collect()
+    }
+
     /// Print table-formatted output for single verification
     fn print_table_output(&self, data: &VerifyOutput) {
         if data.is_valid {
@@ -536,9 +756,20 @@ impl VerifyCommand {
         println!("📋 Verification Details:");
         println!("   Proof ID: {}", data.proof_id);
         println!("   Valid: {}", data.is_valid);
+        if let Some(failure) = &data.failure {
+            println!("   Failure: {}", failure);
+        }
         println!("   Verification Time: {}", format_duration(data.verification_time_ms));
         println!("   Method: {}", data.verification_method);
         println!("   Verifier Version: {}", data.verifier_version);
+        if data.verification_steps.is_none() && !data.timings.is_empty() {
+            let mut timings: Vec<_> = data.timings.iter().collect();
+            timings.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+            let line: Vec<String> = timings.iter()
+                .map(|(name, ms)| format!("{} {}", name, format_duration(**ms)))
+                .collect();
+            println!("   Step Timings: {}", line.join(" · "));
+        }
 
         if let Some(metadata) = &data.proof_metadata {
             println!();
@@ -551,6 +782,12 @@ impl VerifyCommand {
             println!("   Created: {}", metadata.created_at);
         }
 
+        if let Some(explanation) = &data.explanation {
+            println!();
+            println!("📖 What This Proof Shows:");
+            println!("   {}", explanation);
+        }
+
         if let Some(steps) = &data.verification_steps {
             println!();
             println!("🔍 Verification Steps:");
@@ -592,6 +829,9 @@ impl VerifyCommand {
         println!("   Total Proofs: {}", data.total_proofs);
         println!("   Valid Proofs: {} ({:.1}%)", data.valid_proofs, data.summary.success_rate);
         println!("   Invalid Proofs: {}", data.invalid_proofs);
+        if data.skipped_proofs > 0 {
+            println!("   Skipped (outside date window): {}", data.skipped_proofs);
+        }
         println!("   Total Time: {}", format_duration(data.total_verification_time_ms));
         println!("   Average Time: {}", format_duration(data.summary.avg_verification_time_ms as u64));
         println!("   Fastest: {}", format_duration(data.summary.fastest_verification_ms));
@@ -624,8 +864,12 @@ impl Clone for VerifyCommand {
     fn clone(&self) -> Self {
         Self {
             proof: self.proof.clone(),
+            bundle: self.bundle.clone(),
+            proof_format: self.proof_format.clone(),
             file: self.file.clone(),
             content: self.content.clone(),
+            preset: self.preset,
+            step_timeout: self.step_timeout,
             strict: self.strict,
             detailed: self.detailed,
             max_age_days: self.max_age_days,
@@ -637,10 +881,125 @@ impl Clone for VerifyCommand {
             report: self.report.clone(),
             batch: self.batch,
             batch_dir: self.batch_dir.clone(),
+            since: self.since,
+            until: self.until,
+            watch: self.watch,
+            offline: self.offline,
+            explain: self.explain,
         }
     }
 }
 
+/// Parses an RFC 3339 timestamp such as `2024-01-31T12:00:00Z`
+fn parse_rfc3339(value: &str) -> std::result::Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| format!("expected an RFC 3339 time such as 2024-01-31T12:00:00Z: {}", e))
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+/// Reads a proof bundle, checking its entries and signature
+fn load_bundle(path: &Path) -> Result<ProofBundle> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| ProofError::file_error(format!("Failed to read bundle file: {}", path.display()), Some(e)))?;
+    ProofBundle::read(std::io::BufReader::new(file))
+}
+
+/// Returns why a bundle's blocks do not reproduce its proof's root hash and
+/// inclusion path, or `None` if they do
+async fn bundle_block_failure(bundle: &ProofBundle) -> Result<Option<VerificationFailure>> {
+    let blocks = ProofGenerator::import_car(&bundle.car[..])?;
+    let generator = ProofGenerator::with_config(ProofConfig { use_cache: false, ..ProofConfig::default() }).await?;
+    generator.check_blocks(&bundle.proof, &blocks)
+}
+
+/// Runs `verify` once, then again each time one of `paths` changes, until
+/// `shutdown` completes; each outcome is passed to `report` as a timestamped line
+///
+/// Changes are debounced: verification waits until nothing has changed for
+/// `debounce`, so a burst of writes from an editor triggers a single run.
+async fn watch_and_verify<F, Fut>(
+    paths: &[PathBuf],
+    debounce: Duration,
+    shutdown: impl Future<Output = ()>,
+    mut verify: F,
+    mut report: impl FnMut(String),
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(bool, Option<VerificationFailure>)>>,
+{
+    let watched = paths.iter()
+        .map(|path| std::fs::canonicalize(path).map_err(|e| ProofError::file_error(
+            format!("Failed to resolve watched file: {}", path.display()),
+            Some(e)
+        )))
+        .collect::<Result<Vec<_>>>()?;
+
+    let (sender, mut events) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            let _ = sender.send(event);
+        }
+    }).map_err(watch_error)?;
+
+    // Editors often replace a file rather than write to it, which a watch on
+    // the file itself would not survive, so the directories are watched instead
+    let mut directories: Vec<&Path> = watched.iter().filter_map(|path| path.parent()).collect();
+    directories.sort();
+    directories.dedup();
+    for directory in directories {
+        watcher.watch(directory, RecursiveMode::NonRecursive).map_err(watch_error)?;
+    }
+
+    report(watch_line(&verify().await));
+
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            event = events.recv() => {
+                let Some(event) = event else { break };
+                if !is_content_change(&event, &watched) {
+                    continue;
+                }
+                while let Ok(Some(_)) = tokio::time::timeout(debounce, events.recv()).await {}
+                report(watch_line(&verify().await));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `event` changes the contents of one of the watched files; reads and
+/// metadata changes, including the ones verification itself causes, do not count
+fn is_content_change(event: &notify::Event, watched: &[PathBuf]) -> bool {
+    let changes = match event.kind {
+        EventKind::Modify(notify::event::ModifyKind::Metadata(_)) => false,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => true,
+        _ => false,
+    };
+    changes && event.paths.iter().any(|path| watched.contains(path))
+}
+
+fn watch_line(outcome: &Result<(bool, Option<VerificationFailure>)>) -> String {
+    let time = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+    match outcome {
+        Ok((true, _)) => format!("[{}] ✅ valid", time),
+        Ok((false, Some(failure))) => format!("[{}] ❌ invalid ({})", time, failure),
+        Ok((false, None)) => format!("[{}] ❌ invalid", time),
+        Err(e) => format!("[{}] ⚠️  error: {}", time, e),
+    }
+}
+
+fn watch_error(error: notify::Error) -> ProofError {
+    ProofError::file_error(format!("Failed to watch for changes: {}", error), None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -652,9 +1011,13 @@ mod tests {
         let temp_file = NamedTempFile::new().unwrap();
         
         let cmd = VerifyCommand {
-            proof: temp_file.path().to_path_buf(),
+            proof: Some(temp_file.path().to_path_buf()),
+            bundle: None,
+            proof_format: None,
             file: None,
             content: None,
+            preset: None,
+            step_timeout: None,
             strict: false,
             detailed: false,
             max_age_days: None,
@@ -666,6 +1029,11 @@ mod tests {
             report: None,
             batch: false,
             batch_dir: None,
+            since: None,
+            until: None,
+            watch: false,
+            offline: false,
+            explain: false,
         };
 
         let mut warnings = Vec::new();
@@ -679,9 +1047,13 @@ mod tests {
         let temp_file = NamedTempFile::new().unwrap();
         
         let cmd = VerifyCommand {
-            proof: temp_file.path().to_path_buf(),
+            proof: Some(temp_file.path().to_path_buf()),
+            bundle: None,
+            proof_format: None,
             file: None,
             content: None,
+            preset: None,
+            step_timeout: None,
             strict: false,
             detailed: false,
             max_age_days: None,
@@ -693,6 +1065,11 @@ mod tests {
             report: None,
             batch: false,
             batch_dir: None,
+            since: None,
+            until: None,
+            watch: false,
+            offline: false,
+            explain: false,
         };
 
         let mut warnings = Vec::new();
@@ -714,9 +1091,13 @@ mod tests {
         std::fs::write(&not_proof, "not a proof").unwrap();
 
         let cmd = VerifyCommand {
-            proof: PathBuf::new(),
+            proof: None,
+            bundle: None,
+            proof_format: None,
             file: None,
             content: None,
+            preset: None,
+            step_timeout: None,
             strict: false,
             detailed: false,
             max_age_days: None,
@@ -728,6 +1109,11 @@ mod tests {
             report: None,
             batch: true,
             batch_dir: Some(temp_dir.path().to_path_buf()),
+            since: None,
+            until: None,
+            watch: false,
+            offline: false,
+            explain: false,
         };
 
         let proof_files = cmd.find_proof_files(temp_dir.path()).unwrap();
@@ -736,5 +1122,181 @@ mod tests {
         assert!(proof_files.contains(&proof2));
         assert!(!proof_files.iter().any(|p| p == &not_proof));
     }
-}
 
+    fn proof_created_at(created_at: &str) -> Proof {
+        Proof {
+            id: format!("proof-{}", created_at),
+            version: "0.1.0".to_string(),
+            proof_type: Default::default(),
+            image_id: None,
+            signature: None,
+            timestamp: None,
+            created_at: parse_rfc3339(created_at).unwrap(),
+            zk_proof: zkipfs_proof_core::ZkProofData {
+                receipt: vec![1, 2, 3, 4],
+                public_inputs: vec![],
+                format_version: "1.0".to_string(),
+                compression: None,
+            },
+            content_hash: [0; 32],
+            root_hash: [1; 32],
+            content_selection: zkipfs_proof_core::ContentSelection::Pattern { content: b"test".to_vec() },
+            metadata: zkipfs_proof_core::ProofMetadata {
+                guest_metadata: zkipfs_proof_core::guest_types::ProofMetadata {
+                    block_count: 1,
+                    content_size: 4,
+                    timestamp: 0,
+                },
+                file_info: zkipfs_proof_core::FileInfo {
+                    filename: Some("test.txt".to_string()),
+                    size: zkipfs_proof_core::Bytes(4),
+                    mime_type: None,
+                    file_hash: [0; 32],
+                    ipfs_cid: "QmTest".to_string(),
+                    block_count: 1,
+                    avg_block_size: zkipfs_proof_core::Bytes(4),
+                    source_compression: None,
+                    chunk_size: None,
+                },
+                performance: zkipfs_proof_core::PerformanceMetrics {
+                    generation_time_ms: zkipfs_proof_core::Millis(0),
+                    file_processing_time_ms: zkipfs_proof_core::Millis(0),
+                    zk_generation_time_ms: zkipfs_proof_core::Millis(0),
+                    peak_memory_bytes: zkipfs_proof_core::Bytes(0),
+                    zk_cycles: 0,
+                    proof_size_bytes: zkipfs_proof_core::Bytes(4),
+                    compression_ratio: None,
+                    segment_cycles: Vec::new(),
+                },
+                security: zkipfs_proof_core::SecurityParameters {
+                    security_level: 128,
+                    hash_function: "SHA-256".to_string(),
+                    hash_algorithm: zkipfs_proof_core::HashAlgorithm::Sha256,
+                    proof_system: "Risc0".to_string(),
+                    risc0_version: "1.2".to_string(),
+                    formal_verification: false,
+                },
+                environment: zkipfs_proof_core::GenerationEnvironment {
+                    os: "linux".to_string(),
+                    arch: "x86_64".to_string(),
+                    hardware_acceleration: None,
+                    prover_type: zkipfs_proof_core::ProverType::Local,
+                    library_version: "0.1.0".to_string(),
+                    git_commit: None,
+                },
+                custom: HashMap::new(),
+                valid_until: None,
+                renewed_from: Vec::new(),
+                extends: None,
+                reproducible: false,
+                content_committed: false,
+            },
+        }
+    }
+
+    #[test]
+    fn test_explanation_names_selection_and_security() {
+        let mut proof = proof_created_at("2024-03-05T10:00:00Z");
+        proof.content_selection = zkipfs_proof_core::ContentSelection::ByteRange { start: 100, end: 150 };
+
+        let i18n = zkipfs_proof_core::i18n::I18nManager::new();
+        let explanation = proof.explain(&i18n);
+        assert!(explanation.contains("Bytes 100-150"), "{}", explanation);
+        assert!(explanation.contains("test.txt") && explanation.contains("QmTest"), "{}", explanation);
+        assert!(explanation.contains("128-bit security"), "{}", explanation);
+
+        i18n.set_language(zkipfs_proof_core::i18n::Language::German);
+        let explanation = proof.explain(&i18n);
+        assert!(explanation.contains("128-Bit-Sicherheit"), "{}", explanation);
+    }
+
+    #[test]
+    fn test_filter_by_created_at() {
+        let temp_dir = TempDir::new().unwrap();
+        for created_at in ["2024-01-01T00:00:00Z", "2024-02-15T08:30:00Z", "2024-03-31T23:59:59Z", "2024-04-10T12:00:00Z"] {
+            let path = temp_dir.path().join(format!("{}.json", &created_at[..10]));
+            std::fs::write(&path, serde_json::to_vec(&proof_created_at(created_at)).unwrap()).unwrap();
+        }
+
+        let mut cmd = VerifyCommand {
+            proof: None,
+            bundle: None,
+            proof_format: None,
+            file: None,
+            content: None,
+            preset: None,
+            step_timeout: None,
+            strict: false,
+            detailed: false,
+            max_age_days: None,
+            min_security_level: None,
+            expected_proof_system: None,
+            on_chain: false,
+            rpc_endpoint: None,
+            contract_address: None,
+            report: None,
+            batch: true,
+            batch_dir: Some(temp_dir.path().to_path_buf()),
+            since: Some(parse_rfc3339("2024-02-01T00:00:00Z").unwrap()),
+            until: Some(parse_rfc3339("2024-03-31T23:59:59Z").unwrap()),
+            watch: false,
+            offline: false,
+            explain: false,
+        };
+
+        let names = |files: Vec<PathBuf>| -> Vec<String> {
+            files.iter().map(|path| path.file_stem().unwrap().to_string_lossy().into_owned()).collect()
+        };
+        let proof_files = cmd.find_proof_files(temp_dir.path()).unwrap();
+        assert_eq!(names(cmd.filter_by_created_at(proof_files.clone())), ["2024-02-15", "2024-03-31"]);
+
+        cmd.since = None;
+        assert_eq!(names(cmd.filter_by_created_at(proof_files.clone())), ["2024-01-01", "2024-02-15", "2024-03-31"]);
+
+        cmd.until = None;
+        assert_eq!(cmd.filter_by_created_at(proof_files.clone()), proof_files);
+
+        assert!(parse_rfc3339("2024-02-01").is_err());
+    }
+
+    async fn next_line(lines: &mut tokio::sync::mpsc::UnboundedReceiver<String>) -> String {
+        tokio::time::timeout(Duration::from_secs(10), lines.recv()).await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_watch_reverifies_on_content_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let content_path = temp_dir.path().join("content.txt");
+        std::fs::write(&content_path, "original").unwrap();
+
+        let (line_sender, mut lines) = tokio::sync::mpsc::unbounded_channel();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let paths = vec![content_path.clone()];
+        let watch = tokio::spawn(async move {
+            let content_path = paths[0].clone();
+            watch_and_verify(
+                &paths,
+                Duration::from_millis(50),
+                async { let _ = stopped.await; },
+                || {
+                    let matches = std::fs::read(&content_path).map(|content| content == b"original");
+                    async move {
+                        let matches = matches.map_err(|e| ProofError::file_error("Failed to read content", Some(e)))?;
+                        Ok((matches, (!matches).then_some(VerificationFailure::ContentHashMismatch)))
+                    }
+                },
+                move |line| { let _ = line_sender.send(line); },
+            ).await
+        });
+
+        let first = next_line(&mut lines).await;
+        assert!(first.ends_with("valid") && !first.contains("invalid"), "{}", first);
+
+        std::fs::write(&content_path, "changed").unwrap();
+        let second = next_line(&mut lines).await;
+        assert!(second.contains("invalid (ContentHashMismatch)"), "{}", second);
+
+        stop.send(()).unwrap();
+        watch.await.unwrap().unwrap();
+    }
+}
@@ -77,9 +77,21 @@ pub struct IpfsConfig {
     /// IPFS API URL
     pub api_url: String,
     
+    /// Further IPFS API URLs to fail over to
+    #[serde(default)]
+    pub api_urls: Vec<String>,
+    
+    /// Spread requests across all IPFS API URLs in turn
+    #[serde(default)]
+    pub round_robin: bool,
+    
     /// IPFS Gateway URL
     pub gateway_url: String,
     
+    /// Fetch content from the gateway, checked against its CID, when the API fails
+    #[serde(default)]
+    pub gateway_fallback: bool,
+    
     /// Request timeout in seconds
     pub timeout: u64,
     
@@ -117,6 +129,10 @@ pub struct PerformanceConfig {
     
     /// Chunk size for file processing
     pub chunk_size_bytes: usize,
+
+    /// Size blocks from each file's length instead of the fixed default
+    #[serde(default)]
+    pub adaptive_chunking: bool,
     
     /// Maximum file size to process in memory
     pub max_in_memory_size_mb: u64,
@@ -160,7 +176,10 @@ impl Default for IpfsConfig {
     fn default() -> Self {
         Self {
             api_url: "http://127.0.0.1:5001".to_string(),
+            api_urls: Vec::new(),
+            round_robin: false,
             gateway_url: "http://127.0.0.1:8080".to_string(),
+            gateway_fallback: false,
             timeout: 300, // 5 minutes
             auto_pin: true,
             headers: HashMap::new(),
@@ -185,12 +204,89 @@ impl Default for PerformanceConfig {
         Self {
             worker_threads: None, // Use system default
             chunk_size_bytes: 64 * 1024, // 64KB
+            adaptive_chunking: false,
             max_in_memory_size_mb: 100,
             enable_profiling: false,
         }
     }
 }
 
+/// Accepted values of `default_prover`
+const PROVERS: &[&str] = &["local", "bonsai"];
+
+/// Accepted values of `default_compression`
+const COMPRESSIONS: &[&str] = &["none", "gzip", "zstd"];
+
+/// Accepted values of `logging.level`
+const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"];
+
+impl Config {
+    /// Checks value ranges and string options, reporting every problem found
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        let mut check = |valid: bool, problem: String| {
+            if !valid {
+                problems.push(problem);
+            }
+        };
+        let one_of = |value: &str, allowed: &[&str]| allowed.contains(&value.to_lowercase().as_str());
+        let is_url = |url: &str| url.starts_with("http://") || url.starts_with("https://");
+
+        check(
+            (80..=256).contains(&self.default_security_level),
+            format!("default_security_level must be between 80 and 256 bits, got {}", self.default_security_level),
+        );
+        check(
+            one_of(&self.default_prover, PROVERS),
+            format!("default_prover must be one of {}, got '{}'", PROVERS.join(", "), self.default_prover),
+        );
+        check(
+            one_of(&self.default_compression, COMPRESSIONS),
+            format!("default_compression must be one of {}, got '{}'", COMPRESSIONS.join(", "), self.default_compression),
+        );
+        check(self.max_memory_mb != Some(0), "max_memory_mb must be positive".to_string());
+        check(self.default_timeout_seconds != Some(0), "default_timeout_seconds must be positive".to_string());
+
+        for (key, endpoint) in [("api.bonsai_endpoint", &self.api.bonsai_endpoint), ("api.ipfs_endpoint", &self.api.ipfs_endpoint)] {
+            if let Some(endpoint) = endpoint {
+                check(is_url(endpoint), format!("{} must be an http(s) URL, got '{}'", key, endpoint));
+            }
+        }
+        check(self.api.request_timeout_seconds > 0, "api.request_timeout_seconds must be positive".to_string());
+
+        for url in std::iter::once(&self.ipfs.api_url).chain(&self.ipfs.api_urls) {
+            check(is_url(url), format!("ipfs API URL must be an http(s) URL, got '{}'", url));
+        }
+        check(
+            is_url(&self.ipfs.gateway_url),
+            format!("ipfs.gateway_url must be an http(s) URL, got '{}'", self.ipfs.gateway_url),
+        );
+        check(self.ipfs.timeout > 0, "ipfs.timeout must be positive".to_string());
+
+        check(
+            one_of(&self.logging.level, LOG_LEVELS),
+            format!("logging.level must be one of {}, got '{}'", LOG_LEVELS.join(", "), self.logging.level),
+        );
+        if self.logging.log_to_file {
+            check(self.logging.max_log_size_mb > 0, "logging.max_log_size_mb must be positive".to_string());
+            check(self.logging.log_file_count > 0, "logging.log_file_count must be positive".to_string());
+        }
+
+        check(self.performance.worker_threads != Some(0), "performance.worker_threads must be positive".to_string());
+        check(self.performance.chunk_size_bytes > 0, "performance.chunk_size_bytes must be positive".to_string());
+        check(self.performance.max_in_memory_size_mb > 0, "performance.max_in_memory_size_mb must be positive".to_string());
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ProofError::configuration_error(format!(
+                "Invalid configuration:\n  - {}",
+                problems.join("\n  - ")
+            )))
+        }
+    }
+}
+
 /// Load configuration from file or create default
 pub async fn load_config(config_path: Option<&Path>) -> Result<Config> {
     let config_file = if let Some(path) = config_path {
@@ -215,6 +311,7 @@ pub async fn load_config(config_path: Option<&Path>) -> Result<Config> {
 
         // Override with environment variables
         override_with_env(&mut config)?;
+        config.validate()?;
 
         debug!("Configuration loaded successfully");
         Ok(config)
@@ -222,6 +319,7 @@ pub async fn load_config(config_path: Option<&Path>) -> Result<Config> {
         debug!("Config file not found, using defaults");
         let mut config = Config::default();
         override_with_env(&mut config)?;
+        config.validate()?;
         Ok(config)
     }
 }
@@ -353,6 +451,7 @@ pub fn get_config_value(config: &Config, key: &str) -> Option<String> {
         "log_file" => config.logging.log_file.as_ref().map(|p| p.display().to_string()),
         "worker_threads" => config.performance.worker_threads.map(|t| t.to_string()),
         "chunk_size_bytes" => Some(config.performance.chunk_size_bytes.to_string()),
+        "adaptive_chunking" => Some(config.performance.adaptive_chunking.to_string()),
         "max_in_memory_size_mb" => Some(config.performance.max_in_memory_size_mb.to_string()),
         "enable_profiling" => Some(config.performance.enable_profiling.to_string()),
         _ => None,
@@ -436,6 +535,9 @@ pub fn set_config_value(config: &mut Config, key: &str, value: &str) -> Result<(
             config.performance.chunk_size_bytes = value.parse()
                 .map_err(|_| ProofError::invalid_input_error(key, "Invalid chunk size"))?;
         }
+        "adaptive_chunking" => {
+            config.performance.adaptive_chunking = value.to_lowercase() == "true";
+        }
         "max_in_memory_size_mb" => {
             config.performance.max_in_memory_size_mb = value.parse()
                 .map_err(|_| ProofError::invalid_input_error(key, "Invalid memory size"))?;
@@ -498,6 +600,26 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_default_config_is_valid() {
+        Config::default().validate().unwrap();
+    }
+
+    #[test]
+    fn test_invalid_fields_reported_together() {
+        let mut config = Config::default();
+        config.default_security_level = 0;
+        config.default_prover = "quantum".to_string();
+        config.logging.level = "loud".to_string();
+        config.performance.chunk_size_bytes = 0;
+
+        let message = config.validate().unwrap_err().to_string();
+        for field in ["default_security_level", "default_prover", "logging.level", "performance.chunk_size_bytes"] {
+            assert!(message.contains(field), "{} missing from: {}", field, message);
+        }
+        assert!(!message.contains("default_compression"));
+    }
+
     #[test]
     fn test_env_override() {
         std::env::set_var("ZKIPFS_SECURITY_LEVEL", "256");
@@ -7,12 +7,13 @@
 mod commands;
 mod config;
 mod progress;
+mod qr;
 mod utils;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 use tracing::{error, info};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{fmt::MakeWriter, layer::SubscriberExt, util::SubscriberInitExt, Layer};
 
 use commands::{generate::GenerateCommand, verify::VerifyCommand, info::InfoCommand};
 
@@ -47,18 +48,31 @@ struct Cli {
     #[arg(long, global = true)]
     no_color: bool,
 
+    /// Log format; `json` emits one object per line with the fields of the enclosing spans
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    log_format: LogFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// How log lines are written
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// JSON objects, for log aggregators
+    Json,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Generate a zero-knowledge proof for file content
-    #[command(alias = "gen")]
+    #[command(visible_alias = "gen")]
     Generate(GenerateCommand),
 
     /// Verify a zero-knowledge proof
-    #[command(alias = "ver")]
+    #[command(visible_alias = "ver")]
     Verify(VerifyCommand),
 
     /// Display information about proofs, files, or system status
@@ -95,6 +109,18 @@ enum Commands {
         /// Output benchmark results to file
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Compare against results saved earlier with --output
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+
+        /// Exit with a non-zero status if throughput regressed against the baseline
+        #[arg(long, requires = "baseline")]
+        fail_on_regression: bool,
+
+        /// Throughput drop, in percent, that counts as a regression
+        #[arg(long, value_name = "PERCENT", default_value = "5.0")]
+        regression_threshold: f64,
     },
 
     /// Manage configuration settings
@@ -105,6 +131,21 @@ enum Commands {
 
     /// Interact with IPFS for file storage and retrieval
     Ipfs(commands::ipfs::IpfsArgs),
+
+    /// Inspect or repair the on-disk proof cache
+    Cache(commands::cache::CacheArgs),
+
+    /// Convert a proof between JSON, YAML, binary and bundle formats
+    Export(commands::export::ExportArgs),
+
+    /// Print a shell completion script to stdout
+    ///
+    /// For example, `zkipfs-proof completions bash > /etc/bash_completion.d/zkipfs-proof`
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
 }
 
 #[derive(Subcommand)]
@@ -138,8 +179,17 @@ enum ConfigAction {
 async fn main() {
     let cli = Cli::parse();
     
+    // Completion scripts are printed before logging or configuration can interfere
+    if let Commands::Completions { shell } = cli.command {
+        if let Err(e) = commands::completions::execute(shell, &mut Cli::command()) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Initialize logging
-    init_logging(cli.verbose, cli.debug, cli.no_color);
+    init_logging(cli.verbose, cli.debug, cli.no_color, cli.log_format);
     
     // Load configuration
     let config = match config::load_config(cli.config.as_deref()).await {
@@ -163,8 +213,15 @@ async fn main() {
         Commands::Version { detailed } => {
             commands::version::execute(detailed).await
         }
-        Commands::Benchmark { file, iterations, output } => {
-            commands::benchmark::execute(file.as_deref(), iterations, output.as_deref()).await
+        Commands::Benchmark { file, iterations, output, baseline, fail_on_regression, regression_threshold } => {
+            commands::benchmark::execute(
+                file.as_deref(),
+                iterations,
+                output.as_deref(),
+                baseline.as_deref(),
+                fail_on_regression,
+                regression_threshold,
+            ).await
         }
         Commands::Config { action } => {
             commands::config::execute(action, &config).await
@@ -172,6 +229,9 @@ async fn main() {
         Commands::Ipfs(args) => {
             commands::ipfs::handle_ipfs_command(args, &config).await
         }
+        Commands::Cache(args) => commands::cache::execute(args, &cli.output).await,
+        Commands::Export(args) => commands::export::execute(args, &cli.output).await,
+        Commands::Completions { .. } => unreachable!("completions are handled before configuration is loaded"),
     };
     
     match result {
@@ -196,7 +256,7 @@ async fn main() {
 }
 
 /// Initialize logging based on verbosity level
-fn init_logging(verbose: bool, debug: bool, no_color: bool) {
+fn init_logging(verbose: bool, debug: bool, no_color: bool, format: LogFormat) {
     let level = if debug {
         tracing::Level::DEBUG
     } else if verbose {
@@ -205,9 +265,11 @@ fn init_logging(verbose: bool, debug: bool, no_color: bool) {
         tracing::Level::WARN
     };
     
-    let fmt_layer = tracing_subscriber::fmt::layer()
-        .with_target(false)
-        .with_ansi(!no_color);
+    let text_layer = (format == LogFormat::Text).then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_target(false)
+            .with_ansi(!no_color)
+    });
     
     tracing_subscriber::registry()
         .with(
@@ -215,14 +277,28 @@ fn init_logging(verbose: bool, debug: bool, no_color: bool) {
                 .with_default_directive(level.into())
                 .from_env_lossy()
         )
-        .with(fmt_layer)
+        .with(text_layer)
+        .with((format == LogFormat::Json).then(|| json_layer(std::io::stdout)))
         .init();
 }
 
+/// JSON log layer recording the fields of the current span, such as
+/// `proof_id`, and of every span enclosing it
+fn json_layer<S, W>(writer: W) -> impl Layer<S>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt::layer()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_writer(writer)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use clap::CommandFactory;
 
     #[test]
     fn verify_cli() {
@@ -239,5 +315,54 @@ mod tests {
         let cli = Cli::try_parse_from(&["zkipfs-proof", "version"]);
         assert!(cli.is_ok());
     }
-}
 
+    #[test]
+    fn test_json_logs_carry_span_fields() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        #[tracing::instrument(skip(id), fields(proof_id = %id))]
+        fn verify(id: &str) {
+            info!("verifying proof");
+        }
+
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::registry().with(json_layer(move || writer.clone()));
+        tracing::subscriber::with_default(subscriber, || verify("3f2a9c"));
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        let line: serde_json::Value = serde_json::from_str(output.lines().next().unwrap()).unwrap();
+        assert_eq!(line["fields"]["message"], "verifying proof");
+        assert_eq!(line["span"]["proof_id"], "3f2a9c");
+        assert_eq!(line["spans"][0]["proof_id"], "3f2a9c");
+    }
+
+    #[test]
+    fn test_bash_completions() {
+        let mut script = Vec::new();
+        commands::completions::write_completions(
+            clap_complete::Shell::Bash,
+            &mut Cli::command(),
+            &mut script,
+        ).unwrap();
+
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("generate"));
+        assert!(script.contains("verify"));
+        assert!(script.contains("--content"));
+    }
+}
@@ -90,6 +90,21 @@ impl ProgressTracker {
     pub fn set_file_progress(&self, bytes_processed: u64) {
         self.bar.set_position(bytes_processed);
     }
+
+    /// Replace the total the progress is measured against
+    pub fn set_length(&self, length: u64) {
+        self.bar.set_length(length);
+    }
+
+    /// Current position of the progress bar
+    pub fn position(&self) -> u64 {
+        self.bar.position()
+    }
+
+    /// Total the progress is measured against, if known
+    pub fn length(&self) -> Option<u64> {
+        self.bar.length()
+    }
 }
 
 impl Drop for ProgressTracker {
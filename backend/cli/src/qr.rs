@@ -0,0 +1,112 @@
+//! QR code output for proof fingerprints
+//!
+//! This module renders a proof's fingerprint (optionally embedded in a
+//! verification URL) as a QR code, either to the terminal or to a PNG file,
+//! so proofs can be shared in print and scanned back for verification.
+
+use std::path::Path;
+use qrcode::{render::unicode, QrCode};
+use zkipfs_proof_core::error::{ProofError, Result};
+
+/// Minimum edge length of generated PNG images in pixels
+const PNG_MIN_DIMENSION: u32 = 256;
+
+/// Builds the QR payload for a fingerprint and optional verification URL
+pub fn qr_payload(fingerprint: &str, verify_url: Option<&str>) -> String {
+    match verify_url {
+        Some(url) => {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            format!("{}{}fingerprint={}", url, separator, fingerprint)
+        }
+        None => fingerprint.to_string(),
+    }
+}
+
+/// Renders the payload as a QR code using Unicode half blocks
+pub fn render_terminal(payload: &str) -> Result<String> {
+    let code = encode(payload)?;
+    Ok(code.render::<unicode::Dense1x2>()
+        .quiet_zone(true)
+        .build())
+}
+
+/// Writes the payload as a QR code PNG image
+pub fn write_png(payload: &str, path: &Path) -> Result<()> {
+    let code = encode(payload)?;
+    let image = code.render::<image::Luma<u8>>()
+        .min_dimensions(PNG_MIN_DIMENSION, PNG_MIN_DIMENSION)
+        .build();
+
+    image.save(path)
+        .map_err(|e| ProofError::internal_error(
+            format!("Failed to write QR code image: {}", path.display()),
+            Some(Box::new(e))
+        ))
+}
+
+/// Emits a QR code for the fingerprint, to `png_path` if given or to the terminal otherwise
+///
+/// Terminal output goes to stderr, so stdout still holds only the command's
+/// own output, such as a JSON report, when `--qr` is combined with it.
+pub fn emit(fingerprint: &str, verify_url: Option<&str>, png_path: Option<&Path>) -> Result<()> {
+    let payload = qr_payload(fingerprint, verify_url);
+
+    match png_path {
+        Some(path) => {
+            write_png(&payload, path)?;
+            eprintln!("🔳 QR code saved to: {}", path.display());
+        }
+        None => {
+            eprintln!("{}", render_terminal(&payload)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Encodes the payload into a QR code
+fn encode(payload: &str) -> Result<QrCode> {
+    QrCode::new(payload.as_bytes())
+        .map_err(|e| ProofError::internal_error(
+            "Failed to encode QR code",
+            Some(Box::new(e))
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const FINGERPRINT: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    #[test]
+    fn test_qr_payload() {
+        assert_eq!(qr_payload(FINGERPRINT, None), FINGERPRINT);
+        assert_eq!(
+            qr_payload(FINGERPRINT, Some("https://verify.example/p")),
+            format!("https://verify.example/p?fingerprint={}", FINGERPRINT)
+        );
+    }
+
+    #[test]
+    fn test_png_decodes_to_fingerprint() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("proof.png");
+        write_png(FINGERPRINT, &path).unwrap();
+
+        let image = image::open(&path).unwrap().to_luma8();
+        let mut prepared = rqrr::PreparedImage::prepare(image);
+        let grids = prepared.detect_grids();
+        assert_eq!(grids.len(), 1);
+
+        let (_meta, content) = grids[0].decode().unwrap();
+        assert_eq!(content, FINGERPRINT);
+    }
+
+    #[test]
+    fn test_render_terminal() {
+        let rendered = render_terminal(FINGERPRINT).unwrap();
+        assert!(rendered.lines().count() > 10);
+    }
+}
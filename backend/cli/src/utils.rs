@@ -35,6 +35,10 @@ fn parse_single_content_selection(input: &str) -> Result<ContentSelection> {
         Ok(ContentSelection::XPath {
             selector: xpath_selector.to_string(),
         })
+    } else if let Some(json_path) = input.strip_prefix("json:") {
+        Ok(ContentSelection::JsonPath {
+            path: json_path.to_string(),
+        })
     } else if let Some(range_spec) = input.strip_prefix("range:") {
         let parts: Vec<&str> = range_spec.split(':').collect();
         if parts.len() != 2 {
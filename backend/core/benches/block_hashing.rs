@@ -0,0 +1,38 @@
+//! Compares sequential and multi-core hashing of IPFS blocks
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::io::Write;
+use zkipfs_proof_core::{ipfs::IpfsProcessor, ChunkingStrategy};
+
+const FILE_SIZE: usize = 64 * 1024 * 1024;
+
+fn block_hashing(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    let content: Vec<u8> = (0..FILE_SIZE as u32).map(|i| (i.wrapping_mul(2654435761) >> 11) as u8).collect();
+    file.write_all(&content).unwrap();
+
+    let mut group = c.benchmark_group("process_file");
+    group.throughput(Throughput::Bytes(FILE_SIZE as u64));
+    group.sample_size(10);
+
+    for threads in [1, num_threads()] {
+        let mut processor = runtime
+            .block_on(IpfsProcessor::with_chunking(ChunkingStrategy::FixedSize(256 * 1024)))
+            .unwrap();
+        processor.set_worker_threads(Some(threads)).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("threads", threads), &processor, |b, processor| {
+            b.iter(|| runtime.block_on(processor.process_file(file.path())).unwrap());
+        });
+    }
+
+    group.finish();
+}
+
+fn num_threads() -> usize {
+    std::thread::available_parallelism().map_or(4, |n| n.get())
+}
+
+criterion_group!(benches, block_hashing);
+criterion_main!(benches);
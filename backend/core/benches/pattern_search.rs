@@ -0,0 +1,39 @@
+//! Compares pattern search on ordinary content and on input crafted to make
+//! a naive search quadratic; a linear search keeps the two close
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::io::Write;
+use zkipfs_proof_core::{ContentSelection, ProofConfig, ProofGenerator};
+
+const FILE_SIZE: usize = 4 * 1024 * 1024;
+const PATTERN_SIZE: usize = 4096;
+
+fn pattern_search(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let generator = runtime
+        .block_on(ProofGenerator::with_config(ProofConfig { use_cache: false, ..ProofConfig::default() }))
+        .unwrap();
+
+    // Every alignment of the worst case matches all but the last byte of the pattern
+    let worst_case = [vec![b'a'; FILE_SIZE - 1], b"b".to_vec()].concat();
+    let ordinary: Vec<u8> = (0..FILE_SIZE as u32).map(|i| (i.wrapping_mul(2654435761) >> 11) as u8).collect();
+
+    let mut group = c.benchmark_group("preflight_pattern");
+    group.throughput(Throughput::Bytes(FILE_SIZE as u64));
+    group.sample_size(10);
+
+    for (name, content) in [("ordinary", ordinary), ("worst_case", worst_case)] {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&content).unwrap();
+        let selection = ContentSelection::Pattern { content: content[FILE_SIZE - PATTERN_SIZE..].to_vec() };
+
+        group.bench_with_input(BenchmarkId::new("content", name), &selection, |b, selection| {
+            b.iter(|| runtime.block_on(generator.preflight(file.path(), selection)).unwrap());
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, pattern_search);
+criterion_main!(benches);
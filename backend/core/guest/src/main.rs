@@ -1,6 +1,9 @@
 #![no_main]
 #![no_std]
 
+extern crate alloc;
+
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
 use risc0_zkvm::guest::env;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -10,15 +13,48 @@ risc0_zkvm::guest::entry!(main);
 /// IPFS content identifier prefix for SHA-256 hashes
 const SHA256_PREFIX: [u8; 2] = [0x12, 0x20]; // multihash prefix for SHA-256
 
+/// Rolling hash multiplier, must match `PATTERN_FINGERPRINT_BASE` on the host
+const PATTERN_FINGERPRINT_BASE: u64 = 0x0100_0000_01b3;
+
 /// Input data structure for the ZK circuit
 #[derive(Serialize, Deserialize)]
 pub struct ProofInput {
-    /// IPFS blocks that form the complete file structure
+    /// IPFS blocks that form the complete file structure, in any order
     pub blocks: Vec<IpfsBlock>,
     /// Specification of which content to prove exists
     pub content_selection: ContentSelection,
     /// Expected content hash for verification
     pub expected_content_hash: [u8; 32],
+    /// Hash function for the content hash
+    pub hash_algorithm: HashAlgorithm,
+    /// Secret blinding; when set, the content hash is replaced by a commitment to it
+    pub commitment_blinding: Option<[u8; 32]>,
+    /// Set when `blocks` is only the root block and some of its leaves
+    pub materialization: Option<Materialization>,
+}
+
+/// Which leaves of a partly materialized DAG were supplied
+#[derive(Serialize, Deserialize)]
+pub struct Materialization {
+    /// Positions in the root block's links of the leaves following it in `blocks`, ascending
+    pub leaf_indices: Vec<u32>,
+}
+
+/// How much of the DAG the proof was generated from
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct Coverage {
+    pub materialized_blocks: u32,
+    pub total_blocks: u32,
+    pub materialized_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Hash function used for content hashes (block CIDs always use SHA-256)
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha512_256,
+    Blake3,
 }
 
 /// Represents an IPFS block with its data and metadata
@@ -50,8 +86,28 @@ pub enum ContentSelection {
     ByteRange { start: usize, end: usize },
     /// Prove specific content pattern exists
     Pattern { content: Vec<u8> },
+    /// Regular expression selection (resolved on the host, kept for variant order)
+    Regex { pattern: String },
+    /// XPath selection (resolved on the host, kept for variant order)
+    XPath { selector: String },
     /// Prove multiple content selections
     Multiple(Vec<ContentSelection>),
+    /// Prove a large pattern exists given only its hash, length and fingerprint
+    PatternHash { hash: [u8; 32], len: usize, fingerprint: u64 },
+    /// JSONPath selection (resolved to a `JsonSpan` on the host, kept for variant order)
+    JsonPath { path: String },
+    /// Prove the bytes `start..end` hold a JSON value, committing its canonical form
+    JsonSpan { start: usize, end: usize },
+    /// Prove ascending, disjoint byte ranges with one inclusion proof, committing the ranges
+    MultiRange(Vec<(usize, usize)>),
+    /// PDF page selection (resolved to a `ByteRange` on the host, kept for variant order)
+    PdfPage { page: usize },
+    /// CSV cell selection (resolved to a `ByteRange` on the host, kept for variant order)
+    CsvCell { row: usize, column: usize },
+    /// EXIF field selection (resolved to a `ByteRange` on the host, kept for variant order)
+    ExifField { tag: u16 },
+    /// Percentage range selection (resolved to a `ByteRange` on the host, kept for variant order)
+    PercentRange { start_pct: f64, end_pct: f64 },
 }
 
 /// Output data structure from the ZK circuit
@@ -61,10 +117,20 @@ pub struct ProofOutput {
     pub root_hash: [u8; 32],
     /// Hash of the proven content
     pub content_hash: [u8; 32],
-    /// Merkle proof demonstrating content inclusion
-    pub inclusion_proof: Vec<[u8; 32]>,
+    /// Inclusion path of each selection: one per sub-selection of a `Multiple`, otherwise one
+    pub inclusion_paths: Vec<Vec<[u8; 32]>>,
     /// Metadata about the proof
     pub metadata: ProofMetadata,
+    /// Hash function the content hash was computed with
+    pub hash_algorithm: HashAlgorithm,
+    /// Total length of the block data, which every selected byte range lies within
+    pub content_length: u64,
+    /// Hash of the content selection that was proven
+    pub selection_hash: [u8; 32],
+    /// Part of the DAG the proof was generated from
+    pub coverage: Coverage,
+    /// SHA-256 digest of the file's raw CID (of the root block's for a partial DAG)
+    pub cid_digest: [u8; 32],
 }
 
 /// Metadata about the generated proof
@@ -80,16 +146,29 @@ pub struct ProofMetadata {
 
 fn main() {
     // Read input data from the host
-    let input: ProofInput = env::read();
-    
-    // Verify the IPFS block structure and compute root hash
-    let root_hash = verify_ipfs_structure(&input.blocks);
+    let mut input: ProofInput = env::read();
+
+    // A whole DAG is folded in canonical order; a partial one is laid out by its leaf positions
+    if input.materialization.is_none() {
+        input.blocks = canonical_order(core::mem::take(&mut input.blocks));
+    }
     
-    // Extract and verify the specified content
-    let (content_hash, inclusion_proof) = extract_and_prove_content(
-        &input.blocks,
-        &input.content_selection,
-    );
+    // Verify the IPFS block structure and compute root hash, then extract the
+    // specified content
+    let (root_hash, coverage, (content_hash, inclusion_paths)) = match &input.materialization {
+        None => (
+            verify_ipfs_structure(&input.blocks),
+            full_coverage(&input.blocks),
+            extract_and_prove_paths(&input.blocks, &input.content_selection, input.hash_algorithm),
+        ),
+        Some(materialization) => {
+            let (root_hash, segments, coverage) =
+                verify_partial_structure(&input.blocks, &materialization.leaf_indices);
+            let (content_hash, inclusion_proof) =
+                extract_partial_content(&segments, &input.content_selection, input.hash_algorithm);
+            (root_hash, coverage, (content_hash, Vec::from([inclusion_proof])))
+        }
+    };
     
     // Verify that the content hash matches expectations
     assert_eq!(
@@ -97,10 +176,16 @@ fn main() {
         input.expected_content_hash,
         "Content hash mismatch - content may have been tampered with"
     );
+
+    // A committed proof reveals only a commitment to the content hash
+    let content_hash = match &input.commitment_blinding {
+        Some(blinding) => commit_content_hash(&content_hash, blinding),
+        None => content_hash,
+    };
     
     // Create proof metadata
     let metadata = ProofMetadata {
-        block_count: input.blocks.len() as u32,
+        block_count: coverage.total_blocks,
         content_size: calculate_content_size(&input.content_selection, &input.blocks),
         timestamp: env::cycle_count() as u64, // Use cycle count as timestamp
     };
@@ -109,19 +194,112 @@ fn main() {
     let output = ProofOutput {
         root_hash,
         content_hash,
-        inclusion_proof,
+        inclusion_paths,
         metadata,
+        hash_algorithm: input.hash_algorithm,
+        content_length: coverage.total_bytes,
+        selection_hash: selection_hash(&input.content_selection),
+        coverage,
+        cid_digest: cid_digest(&input.blocks, input.materialization.is_some()),
     };
     
     // Commit the proof output to the journal
     env::commit(&output);
 }
 
+/// SHA-256 digest of the raw CID the file is recorded under: of the leaves'
+/// data in order for a whole DAG, of the root block for a partial one
+fn cid_digest(blocks: &[IpfsBlock], partial: bool) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    if partial {
+        hasher.update(&blocks[0].data);
+    } else {
+        for block in blocks.iter().filter(|block| block.links.is_empty()) {
+            hasher.update(&block.data);
+        }
+    }
+    hasher.finalize().into()
+}
+
+/// Prefix of the hashed selection, must match `SELECTION_DOMAIN` on the host
+const SELECTION_DOMAIN: &[u8] = b"zkipfs-proof/selection/v1\0";
+
+/// Hashes the selection's serde words, so the journal binds what was proven
+fn selection_hash(selection: &ContentSelection) -> [u8; 32] {
+    let words = risc0_zkvm::serde::to_vec(selection).expect("a content selection serializes");
+    let mut hasher = Sha256::new();
+    hasher.update(SELECTION_DOMAIN);
+    for word in words {
+        hasher.update(word.to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Prefix of a content commitment, must match `COMMITMENT_DOMAIN` on the host
+const COMMITMENT_DOMAIN: &[u8] = b"zkipfs-proof/commitment/v1\0";
+
+/// Commits to a content hash under a blinding: SHA-256(domain || blinding || hash)
+fn commit_content_hash(content_hash: &[u8; 32], blinding: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(COMMITMENT_DOMAIN);
+    hasher.update(blinding);
+    hasher.update(content_hash);
+    hasher.finalize().into()
+}
+
+/// Puts blocks in canonical order, so the root hash and block offsets do not
+/// depend on the order the host supplied them in
+///
+/// The canonical order is a depth-first pre-order walk of the DAG, matching
+/// `IpfsProcessor::canonical_order` on the host: the root, the one block no
+/// other block links to, comes first, and each block is followed by the
+/// blocks it links to, in link order. Blocks with the same CID fill the links
+/// to it in turn. Every block must be reachable from the root and linked once.
+fn canonical_order(blocks: Vec<IpfsBlock>) -> Vec<IpfsBlock> {
+    if blocks.is_empty() {
+        return blocks;
+    }
+
+    let linked: BTreeSet<&[u8]> = blocks.iter()
+        .flat_map(|block| block.links.iter().map(|link| &link.cid[..]))
+        .collect();
+    let mut roots = (0..blocks.len()).filter(|&index| !linked.contains(&blocks[index].cid[..]));
+    let root = roots.next().expect("Blocks have no root");
+    assert!(roots.next().is_none(), "Blocks have more than one root");
+
+    let mut unused: BTreeMap<&[u8], VecDeque<usize>> = BTreeMap::new();
+    for (index, block) in blocks.iter().enumerate() {
+        if index != root {
+            unused.entry(&block.cid[..]).or_default().push_back(index);
+        }
+    }
+
+    let mut order = Vec::with_capacity(blocks.len());
+    let mut pending = Vec::from([root]);
+    while let Some(index) = pending.pop() {
+        order.push(index);
+        let children: Vec<usize> = blocks[index].links.iter()
+            .map(|link| unused.get_mut(&link.cid[..])
+                .and_then(|indices| indices.pop_front())
+                .expect("A link names a block that was not supplied"))
+            .collect();
+        pending.extend(children.into_iter().rev());
+    }
+    assert_eq!(order.len(), blocks.len(), "Some blocks are not reachable from the root");
+
+    let mut slots: Vec<Option<IpfsBlock>> = blocks.into_iter().map(Some).collect();
+    order.into_iter()
+        .map(|index| slots[index].take().expect("each block is placed once"))
+        .collect()
+}
+
 /// Verifies the IPFS block structure and computes the root hash
+///
+/// `blocks` must be in canonical order.
 fn verify_ipfs_structure(blocks: &[IpfsBlock]) -> [u8; 32] {
     let mut hasher = Sha256::new();
     
-    // Process blocks in topological order to build the DAG
+    // Process blocks in canonical order to build the DAG
     for block in blocks {
         // Verify block integrity
         let computed_cid = compute_block_cid(&block.data);
@@ -145,20 +323,195 @@ fn verify_ipfs_structure(blocks: &[IpfsBlock]) -> [u8; 32] {
     hasher.finalize().into()
 }
 
+/// Coverage of a DAG whose every block was supplied
+fn full_coverage(blocks: &[IpfsBlock]) -> Coverage {
+    let bytes = blocks.iter().map(|block| block.data.len() as u64).sum();
+    Coverage {
+        materialized_blocks: blocks.len() as u32,
+        total_blocks: blocks.len() as u32,
+        materialized_bytes: bytes,
+        total_bytes: bytes,
+    }
+}
+
+/// A block of a partly materialized DAG at its offset in the block stream
+struct Segment<'a> {
+    offset: u64,
+    len: u64,
+    /// `None` for a leaf that was not supplied
+    block: Option<&'a IpfsBlock>,
+}
+
+/// Verifies a partly materialized DAG and lays out its blocks
+///
+/// `blocks` is the root block followed by the leaves at `leaf_indices` in its
+/// links. Absent leaves are hashed by the CID in their link, just as
+/// `verify_ipfs_structure` hashes a leaf, so the root hash is the one of the
+/// whole DAG.
+fn verify_partial_structure<'a>(
+    blocks: &'a [IpfsBlock],
+    leaf_indices: &[u32],
+) -> ([u8; 32], Vec<Segment<'a>>, Coverage) {
+    let (root, leaves) = blocks.split_first().expect("A partial DAG needs its root block");
+    assert_eq!(leaves.len(), leaf_indices.len(), "Every supplied leaf needs its position");
+    assert_eq!(
+        compute_block_cid(&root.data),
+        root.cid,
+        "Block CID mismatch - block may be corrupted"
+    );
+
+    let mut hasher = Sha256::new();
+    hasher.update(&root.cid);
+    for link in &root.links {
+        hasher.update(&link.cid);
+        hasher.update(link.name.as_bytes());
+        hasher.update(&link.size.to_le_bytes());
+    }
+
+    let mut segments = Vec::with_capacity(1 + root.links.len());
+    segments.push(Segment { offset: 0, len: root.data.len() as u64, block: Some(root) });
+    let mut offset = root.data.len() as u64;
+    let mut materialized_bytes = offset;
+    let mut supplied = leaves.iter().zip(leaf_indices).peekable();
+
+    for (index, link) in root.links.iter().enumerate() {
+        hasher.update(&link.cid);
+        let block = supplied.next_if(|(_, position)| **position as usize == index).map(|(leaf, _)| {
+            assert_eq!(leaf.cid, link.cid, "Leaf does not match its link in the root block");
+            assert_eq!(
+                compute_block_cid(&leaf.data),
+                leaf.cid,
+                "Block CID mismatch - block may be corrupted"
+            );
+            assert_eq!(leaf.data.len() as u64, link.size, "Leaf size does not match its link");
+            assert!(leaf.links.is_empty(), "A partial DAG must be one root block linking leaves");
+            materialized_bytes += link.size;
+            leaf
+        });
+        segments.push(Segment { offset, len: link.size, block });
+        offset = offset.checked_add(link.size).expect("Block offset overflow");
+    }
+    assert!(supplied.next().is_none(), "Leaf positions must be ascending and linked from the root block");
+
+    let coverage = Coverage {
+        materialized_blocks: 1 + leaves.len() as u32,
+        total_blocks: 1 + root.links.len() as u32,
+        materialized_bytes,
+        total_bytes: offset,
+    };
+    (hasher.finalize().into(), segments, coverage)
+}
+
+/// Extracts byte ranges from a partly materialized DAG and hashes the blocks they span
+///
+/// Every block a range spans must have been supplied.
+fn extract_partial_content(
+    segments: &[Segment],
+    selection: &ContentSelection,
+    algorithm: HashAlgorithm,
+) -> ([u8; 32], Vec<[u8; 32]>) {
+    let single;
+    let ranges: &[(usize, usize)] = match selection {
+        ContentSelection::ByteRange { start, end } => {
+            single = [(*start, *end)];
+            &single
+        }
+        ContentSelection::MultiRange(ranges) => ranges,
+        _ => panic!("Only byte range selections can be proven from a partial DAG"),
+    };
+
+    let total_len = segments.last().map_or(0, |segment| segment.offset + segment.len);
+    assert!(!ranges.is_empty(), "No byte ranges selected");
+    for (index, &(start, end)) in ranges.iter().enumerate() {
+        assert!(start < end, "Empty byte range");
+        assert!(end as u64 <= total_len, "Byte range extends past the end of the content");
+        if index > 0 {
+            assert!(ranges[index - 1].1 <= start, "Byte ranges must be ascending and disjoint");
+        }
+    }
+
+    let mut content = Vec::new();
+    let mut inclusion_proof = Vec::new();
+    for segment in segments {
+        let (block_start, block_end) = (segment.offset, segment.offset + segment.len);
+        let mut spanned = None;
+        for &(start, end) in ranges {
+            let (start, end) = (start as u64, end as u64);
+            if start < block_end && end > block_start {
+                let block = segment.block.expect("Byte range spans a leaf that was not supplied");
+                let extract_start = start.saturating_sub(block_start) as usize;
+                let extract_end = (end.min(block_end) - block_start) as usize;
+                content.extend_from_slice(&block.data[extract_start..extract_end]);
+                spanned = Some(block);
+            }
+        }
+        if let Some(block) = spanned {
+            inclusion_proof.push(Sha256::digest(&block.data).into());
+        }
+    }
+
+    let content_hash = match selection {
+        ContentSelection::MultiRange(ranges) => hash_content(algorithm, &multi_range_message(ranges, &content)),
+        _ => hash_content(algorithm, &content),
+    };
+    (content_hash, inclusion_proof)
+}
+
+/// Extracts a selection with the inclusion path of each of its parts, one per
+/// sub-selection of a `Multiple` and otherwise one
+fn extract_and_prove_paths(
+    blocks: &[IpfsBlock],
+    selection: &ContentSelection,
+    algorithm: HashAlgorithm,
+) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    match selection {
+        ContentSelection::Multiple(selections) => extract_multiple_content(blocks, selections, algorithm),
+        _ => {
+            let (content_hash, inclusion_proof) = extract_and_prove_content(blocks, selection, algorithm);
+            (content_hash, Vec::from([inclusion_proof]))
+        }
+    }
+}
+
 /// Extracts content according to the selection criteria and generates inclusion proof
 fn extract_and_prove_content(
     blocks: &[IpfsBlock],
     selection: &ContentSelection,
+    algorithm: HashAlgorithm,
 ) -> ([u8; 32], Vec<[u8; 32]>) {
     match selection {
         ContentSelection::ByteRange { start, end } => {
-            extract_byte_range_content(blocks, *start, *end)
+            extract_byte_range_content(blocks, *start, *end, algorithm)
         }
         ContentSelection::Pattern { content } => {
-            extract_pattern_content(blocks, content)
+            extract_pattern_content(blocks, content, algorithm)
         }
         ContentSelection::Multiple(selections) => {
-            extract_multiple_content(blocks, selections)
+            // Nested inside another selection, the parts share one path
+            let (content_hash, paths) = extract_multiple_content(blocks, selections, algorithm);
+            (content_hash, dedup_path(paths.into_iter().flatten()))
+        }
+        ContentSelection::PatternHash { hash, len, fingerprint } => {
+            extract_pattern_hash_content(blocks, hash, *len, *fingerprint, algorithm)
+        }
+        ContentSelection::JsonSpan { start, end } => {
+            extract_json_span_content(blocks, *start, *end, algorithm)
+        }
+        ContentSelection::MultiRange(ranges) => {
+            extract_multi_range_content(blocks, ranges, algorithm)
+        }
+        ContentSelection::Regex { .. }
+        | ContentSelection::XPath { .. }
+        | ContentSelection::JsonPath { .. } => {
+            panic!("Regex, XPath and JSONPath selections are not supported by the guest")
+        }
+        ContentSelection::PdfPage { .. }
+        | ContentSelection::CsvCell { .. }
+        | ContentSelection::ExifField { .. } => {
+            panic!("File format presets must be resolved to byte ranges on the host")
+        }
+        ContentSelection::PercentRange { .. } => {
+            panic!("Percentage ranges must be resolved to byte ranges on the host")
         }
     }
 }
@@ -168,19 +521,43 @@ fn extract_byte_range_content(
     blocks: &[IpfsBlock],
     start: usize,
     end: usize,
+    algorithm: HashAlgorithm,
 ) -> ([u8; 32], Vec<[u8; 32]>) {
+    let (content, inclusion_proof) = collect_byte_range(blocks, start, end);
+    
+    // Compute content hash
+    (hash_content(algorithm, &content), inclusion_proof)
+}
+
+/// Collects the bytes of a range and the hashes of the blocks it spans
+fn collect_byte_range(
+    blocks: &[IpfsBlock],
+    start: usize,
+    end: usize,
+) -> (Vec<u8>, Vec<[u8; 32]>) {
+    let (start, end) = (start as u64, end as u64);
+    let total_len: u64 = blocks.iter().map(|block| block.data.len() as u64).sum();
+    assert!(
+        start < end && end <= total_len,
+        "Byte range {}..{} is not within the content length {}",
+        start, end, total_len
+    );
+
     let mut content = Vec::new();
     let mut inclusion_proof = Vec::new();
-    let mut current_offset = 0;
+    let mut current_offset = 0u64;
     
     for block in blocks {
         let block_start = current_offset;
-        let block_end = current_offset + block.data.len();
+        let block_end = block_start
+            .checked_add(block.data.len() as u64)
+            .expect("Block offset overflow");
         
         // Check if this block contains part of our target range
         if block_start < end && block_end > start {
-            let extract_start = if start > block_start { start - block_start } else { 0 };
-            let extract_end = if end < block_end { end - block_start } else { block.data.len() };
+            // Both bounds lie within the block, so they fit in usize
+            let extract_start = start.saturating_sub(block_start) as usize;
+            let extract_end = (end.min(block_end) - block_start) as usize;
             
             // Extract the relevant portion
             content.extend_from_slice(&block.data[extract_start..extract_end]);
@@ -198,16 +575,125 @@ fn extract_byte_range_content(
         }
     }
     
-    // Compute content hash
-    let content_hash = Sha256::digest(&content);
-    
-    (content_hash.into(), inclusion_proof)
+    (content, inclusion_proof)
+}
+
+/// Extracts several byte ranges in one pass, hashing each spanned block once
+///
+/// The inclusion proof lists the spanned blocks in file order, and the content
+/// hash covers the ranges themselves (see `multi_range_message`).
+fn extract_multi_range_content(
+    blocks: &[IpfsBlock],
+    ranges: &[(usize, usize)],
+    algorithm: HashAlgorithm,
+) -> ([u8; 32], Vec<[u8; 32]>) {
+    assert!(!ranges.is_empty(), "No byte ranges selected");
+    for (index, &(start, end)) in ranges.iter().enumerate() {
+        assert!(start < end, "Empty byte range");
+        if index > 0 {
+            assert!(ranges[index - 1].1 <= start, "Byte ranges must be ascending and disjoint");
+        }
+    }
+
+    let mut content = Vec::new();
+    let mut inclusion_proof = Vec::new();
+    let mut next_range = 0;
+    let mut current_offset = 0u64;
+
+    for block in blocks {
+        let block_start = current_offset;
+        let block_end = block_start
+            .checked_add(block.data.len() as u64)
+            .expect("Block offset overflow");
+        current_offset = block_end;
+
+        // Ranges are ascending, so those ending in this block are done after it
+        let mut spanned = false;
+        let mut range = next_range;
+        while range < ranges.len() && (ranges[range].0 as u64) < block_end {
+            let (start, end) = (ranges[range].0 as u64, ranges[range].1 as u64);
+            if end > block_start {
+                let extract_start = start.saturating_sub(block_start) as usize;
+                let extract_end = (end.min(block_end) - block_start) as usize;
+                content.extend_from_slice(&block.data[extract_start..extract_end]);
+                spanned = true;
+            }
+            if end <= block_end {
+                next_range = range + 1;
+            }
+            range += 1;
+        }
+
+        if spanned {
+            inclusion_proof.push(Sha256::digest(&block.data).into());
+        }
+        if next_range == ranges.len() {
+            break;
+        }
+    }
+    assert_eq!(next_range, ranges.len(), "Byte range extends past the end of the content");
+
+    (hash_content(algorithm, &multi_range_message(ranges, &content)), inclusion_proof)
+}
+
+/// Encodes ranges ahead of their content, matching `types::multi_range_message` on the host
+fn multi_range_message(ranges: &[(usize, usize)], content: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(8 + ranges.len() * 16 + content.len());
+    message.extend_from_slice(&(ranges.len() as u64).to_le_bytes());
+    for &(start, end) in ranges {
+        message.extend_from_slice(&(start as u64).to_le_bytes());
+        message.extend_from_slice(&(end as u64).to_le_bytes());
+    }
+    message.extend_from_slice(content);
+    message
+}
+
+/// Extracts a JSON value from a byte span and hashes its canonical form
+fn extract_json_span_content(
+    blocks: &[IpfsBlock],
+    start: usize,
+    end: usize,
+    algorithm: HashAlgorithm,
+) -> ([u8; 32], Vec<[u8; 32]>) {
+    let (value, inclusion_proof) = collect_byte_range(blocks, start, end);
+    assert_eq!(value.len(), end - start, "JSON span out of range");
+
+    serde_json::from_slice::<serde_json::Value>(&value)
+        .expect("Selected bytes are not a JSON value");
+
+    (hash_content(algorithm, &canonicalize_json(&value)), inclusion_proof)
+}
+
+/// Removes whitespace outside of strings, matching `json_path::canonicalize` on the host
+fn canonicalize_json(value: &[u8]) -> Vec<u8> {
+    let mut canonical = Vec::with_capacity(value.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for &byte in value {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+            }
+            canonical.push(byte);
+        } else if byte == b'"' {
+            in_string = true;
+            canonical.push(byte);
+        } else if !matches!(byte, b' ' | b'\t' | b'\n' | b'\r') {
+            canonical.push(byte);
+        }
+    }
+    canonical
 }
 
 /// Extracts content matching a specific pattern
 fn extract_pattern_content(
     blocks: &[IpfsBlock],
     pattern: &[u8],
+    algorithm: HashAlgorithm,
 ) -> ([u8; 32], Vec<[u8; 32]>) {
     let mut found_content = Vec::new();
     let mut inclusion_proof = Vec::new();
@@ -221,49 +707,123 @@ fn extract_pattern_content(
     // Search for pattern occurrences
     if let Some(pos) = find_pattern(&all_data, pattern) {
         found_content.extend_from_slice(&all_data[pos..pos + pattern.len()]);
-        
-        // Build inclusion proof for blocks containing the pattern
-        let mut current_offset = 0;
-        for block in blocks {
-            let block_end = current_offset + block.data.len();
-            
-            if current_offset <= pos && block_end > pos {
-                let block_hash = Sha256::digest(&block.data);
-                inclusion_proof.push(block_hash.into());
-            }
-            
-            current_offset = block_end;
-        }
+        inclusion_proof = span_inclusion_proof(blocks, pos, pos + pattern.len());
     }
     
     // Verify pattern was found
     assert!(!found_content.is_empty(), "Pattern not found in content");
     assert_eq!(found_content, pattern, "Extracted content doesn't match pattern");
     
-    let content_hash = Sha256::digest(&found_content);
-    (content_hash.into(), inclusion_proof)
+    (hash_content(algorithm, &found_content), inclusion_proof)
+}
+
+/// Hashes of every block the span `start..end` of the block stream touches,
+/// in file order, so a match straddling a block boundary covers both blocks
+fn span_inclusion_proof(blocks: &[IpfsBlock], start: usize, end: usize) -> Vec<[u8; 32]> {
+    let mut inclusion_proof = Vec::new();
+    let mut block_start = 0;
+    for block in blocks {
+        let block_end = block_start + block.data.len();
+        if block_start < end && block_end > start {
+            inclusion_proof.push(Sha256::digest(&block.data).into());
+        }
+        block_start = block_end;
+    }
+    inclusion_proof
+}
+
+/// Finds a large pattern by rolling fingerprint without holding the pattern itself
+fn extract_pattern_hash_content(
+    blocks: &[IpfsBlock],
+    hash: &[u8; 32],
+    len: usize,
+    fingerprint: u64,
+    algorithm: HashAlgorithm,
+) -> ([u8; 32], Vec<[u8; 32]>) {
+    let mut all_data = Vec::new();
+    for block in blocks {
+        all_data.extend_from_slice(&block.data);
+    }
+    assert!(len > 0 && len <= all_data.len(), "Hashed pattern length out of range");
+
+    // Weight of the outgoing byte: BASE^(len - 1)
+    let mut high = 1u64;
+    for _ in 1..len {
+        high = high.wrapping_mul(PATTERN_FINGERPRINT_BASE);
+    }
+
+    let mut rolling = all_data[..len].iter().fold(0u64, |acc, &byte| {
+        acc.wrapping_mul(PATTERN_FINGERPRINT_BASE).wrapping_add(byte as u64)
+    });
+
+    // Fingerprint hits are only candidates; the exact SHA-256 check rules out collisions
+    let mut found = None;
+    let mut pos = 0;
+    loop {
+        if rolling == fingerprint {
+            let window_hash: [u8; 32] = Sha256::digest(&all_data[pos..pos + len]).into();
+            if &window_hash == hash {
+                found = Some(pos);
+                break;
+            }
+        }
+        if pos + len >= all_data.len() {
+            break;
+        }
+        rolling = rolling
+            .wrapping_sub((all_data[pos] as u64).wrapping_mul(high))
+            .wrapping_mul(PATTERN_FINGERPRINT_BASE)
+            .wrapping_add(all_data[pos + len] as u64);
+        pos += 1;
+    }
+
+    let pos = found.expect("Hashed pattern not found in content");
+    let inclusion_proof = span_inclusion_proof(blocks, pos, pos + len);
+
+    // The selection identifies the pattern by its SHA-256 digest
+    let content_hash = match algorithm {
+        HashAlgorithm::Sha256 => *hash,
+        other => hash_content(other, &all_data[pos..pos + len]),
+    };
+    (content_hash, inclusion_proof)
 }
 
-/// Extracts content for multiple selections
+/// Extracts content for multiple selections, keeping each selection's inclusion path
 fn extract_multiple_content(
     blocks: &[IpfsBlock],
     selections: &[ContentSelection],
-) -> ([u8; 32], Vec<[u8; 32]>) {
+    algorithm: HashAlgorithm,
+) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
     let mut combined_content = Vec::new();
-    let mut combined_proof = Vec::new();
+    let mut paths = Vec::with_capacity(selections.len());
     
     for selection in selections {
-        let (content_hash, mut proof) = extract_and_prove_content(blocks, selection);
+        let (content_hash, proof) = extract_and_prove_content(blocks, selection, algorithm);
         combined_content.extend_from_slice(&content_hash);
-        combined_proof.append(&mut proof);
+        paths.push(dedup_path(proof));
     }
     
-    // Remove duplicate proof elements
-    combined_proof.sort();
-    combined_proof.dedup();
-    
-    let final_hash = Sha256::digest(&combined_content);
-    (final_hash.into(), combined_proof)
+    (hash_content(algorithm, &combined_content), paths)
+}
+
+/// Drops repeated block hashes from one inclusion path, keeping file order
+fn dedup_path(hashes: impl IntoIterator<Item = [u8; 32]>) -> Vec<[u8; 32]> {
+    let mut path: Vec<[u8; 32]> = Vec::new();
+    for hash in hashes {
+        if !path.contains(&hash) {
+            path.push(hash);
+        }
+    }
+    path
+}
+
+/// Hashes proven content, matching `HashAlgorithm::digest` on the host
+fn hash_content(algorithm: HashAlgorithm, content: &[u8]) -> [u8; 32] {
+    match algorithm {
+        HashAlgorithm::Sha256 => Sha256::digest(content).into(),
+        HashAlgorithm::Sha512_256 => sha2::Sha512_256::digest(content).into(),
+        HashAlgorithm::Blake3 => blake3::hash(content).into(),
+    }
 }
 
 /// Computes the CID for an IPFS block
@@ -281,17 +841,41 @@ fn compute_block_cid(data: &[u8]) -> Vec<u8> {
 }
 
 /// Finds the first occurrence of a pattern in data
+///
+/// Knuth-Morris-Pratt search, O(n + m) however repetitive the data and pattern.
 fn find_pattern(data: &[u8], pattern: &[u8]) -> Option<usize> {
     if pattern.is_empty() || pattern.len() > data.len() {
         return None;
     }
-    
-    for i in 0..=(data.len() - pattern.len()) {
-        if &data[i..i + pattern.len()] == pattern {
-            return Some(i);
+
+    // Length of the longest proper prefix of pattern[..=i] that is also its suffix
+    let mut failure = Vec::with_capacity(pattern.len());
+    failure.push(0usize);
+    let mut matched = 0;
+    for &byte in &pattern[1..] {
+        while matched > 0 && byte != pattern[matched] {
+            matched = failure[matched - 1];
         }
+        if byte == pattern[matched] {
+            matched += 1;
+        }
+        failure.push(matched);
     }
-    
+
+    // Linear scan: the data position never moves backwards
+    matched = 0;
+    for (i, &byte) in data.iter().enumerate() {
+        while matched > 0 && byte != pattern[matched] {
+            matched = failure[matched - 1];
+        }
+        if byte == pattern[matched] {
+            matched += 1;
+            if matched == pattern.len() {
+                return Some(i + 1 - matched);
+            }
+        }
+    }
+
     None
 }
 
@@ -300,6 +884,18 @@ fn calculate_content_size(selection: &ContentSelection, blocks: &[IpfsBlock]) ->
     match selection {
         ContentSelection::ByteRange { start, end } => (end - start) as u64,
         ContentSelection::Pattern { content } => content.len() as u64,
+        ContentSelection::PatternHash { len, .. } => *len as u64,
+        ContentSelection::JsonSpan { start, end } => (end - start) as u64,
+        ContentSelection::MultiRange(ranges) => {
+            ranges.iter().map(|(start, end)| (end - start) as u64).sum()
+        }
+        ContentSelection::Regex { .. }
+        | ContentSelection::XPath { .. }
+        | ContentSelection::JsonPath { .. }
+        | ContentSelection::PdfPage { .. }
+        | ContentSelection::CsvCell { .. }
+        | ContentSelection::ExifField { .. }
+        | ContentSelection::PercentRange { .. } => 0,
         ContentSelection::Multiple(selections) => {
             selections.iter()
                 .map(|s| calculate_content_size(s, blocks))
@@ -14,12 +14,42 @@ risc0_zkvm::guest::entry!(main);
 /// Input data structure for the ZK circuit
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ProofInput {
-    /// IPFS blocks that form the complete file structure
+    /// IPFS blocks that form the complete file structure, in any order
     pub blocks: Vec<IpfsBlock>,
     /// Specification of which content to prove exists
     pub content_selection: ContentSelection,
     /// Expected content hash for verification
     pub expected_content_hash: [u8; 32],
+    /// Hash function for the content hash
+    pub hash_algorithm: HashAlgorithm,
+    /// Secret blinding; when set, the content hash is replaced by a commitment to it
+    pub commitment_blinding: Option<[u8; 32]>,
+    /// Set when `blocks` is only the root block and some of its leaves
+    pub materialization: Option<Materialization>,
+}
+
+/// Which leaves of a partly materialized DAG were supplied
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Materialization {
+    /// Positions in the root block's links of the leaves following it in `blocks`, ascending
+    pub leaf_indices: Vec<u32>,
+}
+
+/// How much of the DAG the proof was generated from
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Coverage {
+    pub materialized_blocks: u32,
+    pub total_blocks: u32,
+    pub materialized_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Hash function used for content hashes
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha512_256,
+    Blake3,
 }
 
 /// Represents an IPFS block with its data and metadata
@@ -57,6 +87,22 @@ pub enum ContentSelection {
     XPath { selector: String },
     /// Prove multiple content selections
     Multiple(Vec<ContentSelection>),
+    /// Prove a large pattern exists given only its hash, length and fingerprint
+    PatternHash { hash: [u8; 32], len: usize, fingerprint: u64 },
+    /// Prove the value at a JSONPath exists (resolved on the host)
+    JsonPath { path: String },
+    /// Prove a byte span holds a JSON value
+    JsonSpan { start: usize, end: usize },
+    /// Prove ascending, disjoint byte ranges, committing the ranges
+    MultiRange(Vec<(usize, usize)>),
+    /// Prove a PDF page object (resolved on the host)
+    PdfPage { page: usize },
+    /// Prove a CSV cell (resolved on the host)
+    CsvCell { row: usize, column: usize },
+    /// Prove an EXIF field value (resolved on the host)
+    ExifField { tag: u16 },
+    /// Prove a percentage of the file (resolved on the host)
+    PercentRange { start_pct: f64, end_pct: f64 },
 }
 
 /// Output data structure from the ZK circuit
@@ -66,10 +112,20 @@ pub struct ProofOutput {
     pub root_hash: [u8; 32],
     /// Hash of the proven content
     pub content_hash: [u8; 32],
-    /// Merkle proof demonstrating content inclusion
-    pub inclusion_proof: Vec<[u8; 32]>,
+    /// Inclusion path of each selection: one per sub-selection of a `Multiple`, otherwise one
+    pub inclusion_paths: Vec<Vec<[u8; 32]>>,
     /// Metadata about the proof
     pub metadata: ProofMetadata,
+    /// Hash function the content hash was computed with
+    pub hash_algorithm: HashAlgorithm,
+    /// Total length of the block data, which every selected byte range lies within
+    pub content_length: u64,
+    /// Hash of the content selection that was proven
+    pub selection_hash: [u8; 32],
+    /// Part of the DAG the proof was generated from
+    pub coverage: Coverage,
+    /// SHA-256 digest of the file's raw CID (of the root block's for a partial DAG)
+    pub cid_digest: [u8; 32],
 }
 
 /// Metadata about the generated proof
@@ -89,18 +145,46 @@ pub struct ProofMetadata {
 
 fn main() {
     // Read input from the host
-    let input: ProofInput = env::read();
+    let mut input: ProofInput = env::read();
+
+    // A whole DAG is read in canonical order, whatever order the host supplied it in
+    if input.materialization.is_none() {
+        input.blocks = canonical_order(std::mem::take(&mut input.blocks));
+    }
     
+    // A partly materialized DAG is laid out with its absent leaves as gaps
+    let (segments, coverage) = match &input.materialization {
+        None => (None, full_coverage(&input.blocks)),
+        Some(materialization) => {
+            let (segments, coverage) = partial_segments(&input.blocks, &materialization.leaf_indices);
+            (Some(segments), coverage)
+        }
+    };
+
     // Extract content based on selection (optimized to avoid full concatenation if possible)
-    let extracted_content = extract_content(&input.blocks, &input.content_selection);
+    let extracted_content = match &segments {
+        None => extract_content(&input.blocks, &input.content_selection),
+        Some(segments) => extract_partial_content(segments, &input.content_selection),
+    };
     
-    // Calculate content hash
-    let content_hash: [u8; 32] = sha256_hash(&extracted_content);
+    // Calculate content hash; a multi-range hash also covers the ranges
+    let content_hash = match &input.content_selection {
+        ContentSelection::MultiRange(ranges) => {
+            hash_content(input.hash_algorithm, &multi_range_message(ranges, &extracted_content))
+        }
+        _ => hash_content(input.hash_algorithm, &extracted_content),
+    };
     
     // Verify hash matches expected
     if content_hash != input.expected_content_hash {
         panic!("Content hash mismatch! Expected {:?}, got {:?}", input.expected_content_hash, content_hash);
     }
+
+    // A committed proof reveals only a commitment to the content hash
+    let content_hash = match &input.commitment_blinding {
+        Some(blinding) => commit_content_hash(&content_hash, blinding),
+        None => content_hash,
+    };
     
     // Determine root hash (simplified for now, using first block's CID or mock)
     // In a real IPFS verification, we'd verify the DAG/Merkle root.
@@ -111,18 +195,254 @@ fn main() {
     let output = ProofOutput {
         root_hash,
         content_hash,
-        inclusion_proof: vec![], // TODO: Generate inclusion proof
+        inclusion_paths: match &segments {
+            None => inclusion_paths(&input.blocks, &input.content_selection),
+            Some(segments) => vec![partial_inclusion_proof(segments, &input.content_selection)],
+        },
         metadata: ProofMetadata {
-            block_count: input.blocks.len() as u32,
+            block_count: coverage.total_blocks,
             content_size: extracted_content.len() as u64,
             timestamp: 0, 
         },
+        hash_algorithm: input.hash_algorithm,
+        content_length: coverage.total_bytes,
+        selection_hash: selection_hash(&input.content_selection),
+        coverage,
+        cid_digest: cid_digest(&input.blocks, segments.is_some()),
     };
     
     // Commit the result
     env::commit(&output);
 }
 
+/// Inclusion path of each part of a selection: one per sub-selection of a
+/// `Multiple`, otherwise one
+fn inclusion_paths(blocks: &[IpfsBlock], selection: &ContentSelection) -> Vec<Vec<[u8; 32]>> {
+    match selection {
+        ContentSelection::Multiple(selections) => {
+            selections.iter().map(|selection| inclusion_proof(blocks, selection)).collect()
+        }
+        _ => vec![inclusion_proof(blocks, selection)],
+    }
+}
+
+/// Hashes of the blocks a selection spans, in file order, each block once
+///
+/// Only multi-range and pattern selections produce an inclusion proof so far;
+/// a pattern covers every block its first match touches, and a nested
+/// `Multiple` shares one path between its parts.
+fn inclusion_proof(blocks: &[IpfsBlock], selection: &ContentSelection) -> Vec<[u8; 32]> {
+    let ranges = match selection {
+        ContentSelection::Multiple(selections) => {
+            let mut hashes = Vec::new();
+            for hash in selections.iter().flat_map(|selection| inclusion_proof(blocks, selection)) {
+                if !hashes.contains(&hash) {
+                    hashes.push(hash);
+                }
+            }
+            return hashes;
+        }
+        ContentSelection::MultiRange(ranges) => ranges.clone(),
+        ContentSelection::Pattern { content } => match find_pattern(&concatenate_blocks(blocks), content) {
+            Some(pos) => vec![(pos, pos + content.len())],
+            None => return vec![],
+        },
+        _ => return vec![], // TODO: Generate inclusion proofs for other selections
+    };
+
+    let mut hashes = Vec::new();
+    let mut block_start = 0;
+    for block in blocks {
+        let block_end = block_start + block.data.len();
+        if ranges.iter().any(|&(start, end)| start < block_end && end > block_start) {
+            hashes.push(sha256_hash(&block.data));
+        }
+        block_start = block_end;
+    }
+    hashes
+}
+
+/// Puts blocks in canonical order, matching `IpfsProcessor::canonical_order` on the host
+///
+/// The canonical order is a depth-first pre-order walk of the DAG: the root,
+/// the one block no other block links to, comes first, and each block is
+/// followed by the blocks it links to, in link order. Blocks with the same CID
+/// fill the links to it in turn. Every block must be reachable from the root
+/// and linked once.
+fn canonical_order(blocks: Vec<IpfsBlock>) -> Vec<IpfsBlock> {
+    use std::collections::{HashMap, HashSet, VecDeque};
+
+    if blocks.is_empty() {
+        return blocks;
+    }
+
+    let linked: HashSet<&[u8]> = blocks.iter()
+        .flat_map(|block| block.links.iter().map(|link| &link.cid[..]))
+        .collect();
+    let mut roots = (0..blocks.len()).filter(|&index| !linked.contains(&blocks[index].cid[..]));
+    let root = roots.next().expect("Blocks have no root");
+    assert!(roots.next().is_none(), "Blocks have more than one root");
+
+    let mut unused: HashMap<&[u8], VecDeque<usize>> = HashMap::new();
+    for (index, block) in blocks.iter().enumerate() {
+        if index != root {
+            unused.entry(&block.cid[..]).or_default().push_back(index);
+        }
+    }
+
+    let mut order = Vec::with_capacity(blocks.len());
+    let mut pending = vec![root];
+    while let Some(index) = pending.pop() {
+        order.push(index);
+        let children: Vec<usize> = blocks[index].links.iter()
+            .map(|link| unused.get_mut(&link.cid[..])
+                .and_then(|indices| indices.pop_front())
+                .expect("A link names a block that was not supplied"))
+            .collect();
+        pending.extend(children.into_iter().rev());
+    }
+    assert_eq!(order.len(), blocks.len(), "Some blocks are not reachable from the root");
+
+    let mut slots: Vec<Option<IpfsBlock>> = blocks.into_iter().map(Some).collect();
+    order.into_iter()
+        .map(|index| slots[index].take().expect("each block is placed once"))
+        .collect()
+}
+
+/// Coverage of a DAG whose every block was supplied
+fn full_coverage(blocks: &[IpfsBlock]) -> Coverage {
+    let bytes = blocks.iter().map(|block| block.data.len() as u64).sum();
+    Coverage {
+        materialized_blocks: blocks.len() as u32,
+        total_blocks: blocks.len() as u32,
+        materialized_bytes: bytes,
+        total_bytes: bytes,
+    }
+}
+
+/// A block of a partly materialized DAG at its offset in the block stream
+struct Segment<'a> {
+    offset: usize,
+    len: usize,
+    /// `None` for a leaf that was not supplied
+    block: Option<&'a IpfsBlock>,
+}
+
+/// Lays out a root block followed by the leaves at `leaf_indices` in its links,
+/// checking each leaf against its link
+fn partial_segments<'a>(blocks: &'a [IpfsBlock], leaf_indices: &[u32]) -> (Vec<Segment<'a>>, Coverage) {
+    let (root, leaves) = blocks.split_first().expect("A partial DAG needs its root block");
+    assert_eq!(leaves.len(), leaf_indices.len(), "Every supplied leaf needs its position");
+
+    let mut segments = vec![Segment { offset: 0, len: root.data.len(), block: Some(root) }];
+    let mut offset = root.data.len();
+    let mut materialized_bytes = offset as u64;
+    let mut supplied = leaves.iter().zip(leaf_indices).peekable();
+    for (index, link) in root.links.iter().enumerate() {
+        let len = link.size as usize;
+        let block = supplied.next_if(|(_, position)| **position as usize == index).map(|(leaf, _)| {
+            assert_eq!(leaf.cid, link.cid, "Leaf does not match its link in the root block");
+            assert_eq!(leaf.data.len(), len, "Leaf size does not match its link");
+            materialized_bytes += link.size;
+            leaf
+        });
+        segments.push(Segment { offset, len, block });
+        offset += len;
+    }
+    assert!(supplied.next().is_none(), "Leaf positions must be ascending and linked from the root block");
+
+    let coverage = Coverage {
+        materialized_blocks: 1 + leaves.len() as u32,
+        total_blocks: 1 + root.links.len() as u32,
+        materialized_bytes,
+        total_bytes: offset as u64,
+    };
+    (segments, coverage)
+}
+
+/// Byte ranges of a selection provable from a partial DAG
+fn partial_ranges(selection: &ContentSelection) -> Vec<(usize, usize)> {
+    match selection {
+        ContentSelection::ByteRange { start, end } => vec![(*start, *end)],
+        ContentSelection::MultiRange(ranges) => ranges.clone(),
+        _ => panic!("Only byte range selections can be proven from a partial DAG"),
+    }
+}
+
+/// Extracts byte ranges from a partial DAG; every block they span must have been supplied
+fn extract_partial_content(segments: &[Segment], selection: &ContentSelection) -> Vec<u8> {
+    let ranges = partial_ranges(selection);
+    let total_len = segments.last().map_or(0, |segment| segment.offset + segment.len);
+    let mut result = Vec::new();
+    let mut previous_end = 0;
+    for (start, end) in ranges {
+        if start >= end || end > total_len || start < previous_end {
+            panic!("Byte ranges must be non-empty, ascending, disjoint and within the content");
+        }
+        for segment in segments {
+            let segment_end = segment.offset + segment.len;
+            if segment.offset < end && segment_end > start {
+                let block = segment.block.expect("Byte range spans a leaf that was not supplied");
+                let slice_start = start.saturating_sub(segment.offset);
+                let slice_end = end.min(segment_end) - segment.offset;
+                result.extend_from_slice(&block.data[slice_start..slice_end]);
+            }
+        }
+        previous_end = end;
+    }
+    result
+}
+
+/// Hashes of the supplied blocks a partial selection spans, in file order
+fn partial_inclusion_proof(segments: &[Segment], selection: &ContentSelection) -> Vec<[u8; 32]> {
+    let ranges = partial_ranges(selection);
+    segments.iter()
+        .filter(|segment| ranges.iter().any(|&(start, end)| start < segment.offset + segment.len && end > segment.offset))
+        .filter_map(|segment| segment.block)
+        .map(|block| sha256_hash(&block.data))
+        .collect()
+}
+
+/// Prefix of the hashed selection, must match `SELECTION_DOMAIN` on the host
+const SELECTION_DOMAIN: &[u8] = b"zkipfs-proof/selection/v1\0";
+
+/// Hashes the selection's serde words, so the journal binds what was proven
+fn selection_hash(selection: &ContentSelection) -> [u8; 32] {
+    let words = risc0_zkvm::serde::to_vec(selection).expect("a content selection serializes");
+    let mut hasher = Sha256::new();
+    hasher.update(SELECTION_DOMAIN);
+    for word in words {
+        hasher.update(word.to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Prefix of a content commitment, must match `COMMITMENT_DOMAIN` on the host
+const COMMITMENT_DOMAIN: &[u8] = b"zkipfs-proof/commitment/v1\0";
+
+/// Commits to a content hash under a blinding: SHA-256(domain || blinding || hash)
+fn commit_content_hash(content_hash: &[u8; 32], blinding: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(COMMITMENT_DOMAIN);
+    hasher.update(blinding);
+    hasher.update(content_hash);
+    hasher.finalize().into()
+}
+
+/// SHA-256 digest of the raw CID the file is recorded under: of the leaves'
+/// data in order for a whole DAG, of the root block for a partial one
+fn cid_digest(blocks: &[IpfsBlock], partial: bool) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    if partial {
+        hasher.update(&blocks[0].data);
+    } else {
+        for block in blocks.iter().filter(|block| block.links.is_empty()) {
+            hasher.update(&block.data);
+        }
+    }
+    hasher.finalize().into()
+}
+
 fn concatenate_blocks(blocks: &[IpfsBlock]) -> Vec<u8> {
     let mut data = Vec::new();
     for block in blocks {
@@ -135,8 +455,8 @@ fn extract_content(blocks: &[IpfsBlock], selection: &ContentSelection) -> Vec<u8
     match selection {
         ContentSelection::ByteRange { start, end } => {
             let total_len: usize = blocks.iter().map(|b| b.data.len()).sum();
-            if *start >= total_len || *end > total_len || start >= end {
-                panic!("Invalid byte range: {}..{} (len: {})", start, end, total_len);
+            if start >= end || *end > total_len {
+                panic!("Byte range {}..{} is not within the content length {}", start, end, total_len);
             }
 
             let mut result = Vec::with_capacity(end - start);
@@ -168,6 +488,18 @@ fn extract_content(blocks: &[IpfsBlock], selection: &ContentSelection) -> Vec<u8
             }
             result
         }
+        ContentSelection::MultiRange(ranges) => {
+            let mut result = Vec::new();
+            let mut previous_end = 0;
+            for &(start, end) in ranges {
+                if start < previous_end {
+                    panic!("Byte ranges must be ascending and disjoint");
+                }
+                result.extend(extract_content(blocks, &ContentSelection::ByteRange { start, end }));
+                previous_end = end;
+            }
+            result
+        }
         // For other types, we currently need the full data
         _ => {
             let data = concatenate_blocks(blocks);
@@ -213,6 +545,20 @@ fn extract_content(blocks: &[IpfsBlock], selection: &ContentSelection) -> Vec<u8
                         Value::Boolean(b) => b.to_string().into_bytes(),
                     }
                 }
+                ContentSelection::PatternHash { .. } => {
+                    panic!("Hashed patterns are only supported by the core guest");
+                }
+                ContentSelection::JsonPath { .. } | ContentSelection::JsonSpan { .. } => {
+                    panic!("JSON selections are only supported by the core guest");
+                }
+                ContentSelection::PdfPage { .. }
+                | ContentSelection::CsvCell { .. }
+                | ContentSelection::ExifField { .. } => {
+                    panic!("File format presets must be resolved to byte ranges on the host");
+                }
+                ContentSelection::PercentRange { .. } => {
+                    panic!("Percentage ranges must be resolved to byte ranges on the host");
+                }
                 _ => panic!("Unreachable: handled above"),
             }
         }
@@ -224,14 +570,57 @@ fn find_pattern(data: &[u8], pattern: &[u8]) -> Option<usize> {
         return None;
     }
 
-    for i in 0..=(data.len() - pattern.len()) {
-        if &data[i..i + pattern.len()] == pattern {
-            return Some(i);
+    // Length of the longest proper prefix of pattern[..=i] that is also its suffix
+    let mut failure = Vec::with_capacity(pattern.len());
+    failure.push(0usize);
+    let mut matched = 0;
+    for &byte in &pattern[1..] {
+        while matched > 0 && byte != pattern[matched] {
+            matched = failure[matched - 1];
         }
+        if byte == pattern[matched] {
+            matched += 1;
+        }
+        failure.push(matched);
     }
+
+    // Linear scan: the data position never moves backwards
+    matched = 0;
+    for (i, &byte) in data.iter().enumerate() {
+        while matched > 0 && byte != pattern[matched] {
+            matched = failure[matched - 1];
+        }
+        if byte == pattern[matched] {
+            matched += 1;
+            if matched == pattern.len() {
+                return Some(i + 1 - matched);
+            }
+        }
+    }
+
     None
 }
 
+fn hash_content(algorithm: HashAlgorithm, data: &[u8]) -> [u8; 32] {
+    match algorithm {
+        HashAlgorithm::Sha256 => sha256_hash(data),
+        HashAlgorithm::Sha512_256 => sha2::Sha512_256::digest(data).into(),
+        HashAlgorithm::Blake3 => blake3::hash(data).into(),
+    }
+}
+
+/// Encodes ranges ahead of their content, matching `types::multi_range_message` on the host
+fn multi_range_message(ranges: &[(usize, usize)], content: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(8 + ranges.len() * 16 + content.len());
+    message.extend_from_slice(&(ranges.len() as u64).to_le_bytes());
+    for &(start, end) in ranges {
+        message.extend_from_slice(&(start as u64).to_le_bytes());
+        message.extend_from_slice(&(end as u64).to_le_bytes());
+    }
+    message.extend_from_slice(content);
+    message
+}
+
 fn sha256_hash(data: &[u8]) -> [u8; 32] {
     let mut hasher = Sha256::new();
     hasher.update(data);
@@ -2,9 +2,24 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::error::ZkIPFSError;
-use crate::types::{ProofResult, FileInfo};
-use crate::proof_types::{ProofType, ProofTypeConfig};
+use std::path::PathBuf;
+use crate::error::{ProofError, Result};
+use crate::signing::SigningKey;
+use crate::types::Proof;
+use crate::verifier::{ProofVerifier, VerificationConfig};
+use futures::stream::{FuturesUnordered, StreamExt};
+use risc0_zkvm::{sha::{self, Digestible}, Receipt, ReceiptClaim, VerifierContext};
+use std::time::Duration;
+use tracing::warn;
+
+/// How long a node has to answer a verification request by default
+const DEFAULT_VOTE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Fraction of the distance to 0 or 1 a node's reputation moves per round
+const REPUTATION_LEARNING_RATE: f64 = 0.1;
+
+/// Prefix of the message a node signs, so votes cannot be replayed elsewhere
+const VOTE_DOMAIN: &[u8] = b"zkipfs-proof/vote/v1\0";
 
 /// Advanced verification engine with multiple verification strategies
 pub struct AdvancedVerificationEngine {
@@ -12,6 +27,7 @@ pub struct AdvancedVerificationEngine {
     pub batch_processor: BatchProcessor,
     pub verification_cache: VerificationCache,
     pub consensus_engine: ConsensusEngine,
+    node_client: NodeClient,
 }
 
 /// Different verification strategies
@@ -31,6 +47,33 @@ pub enum VerificationStrategy {
     Consensus { algorithm: ConsensusAlgorithm },
 }
 
+impl VerificationStrategy {
+    /// Checks that the strategy's parameters can be used
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            VerificationStrategy::MultiVerifier { count, threshold } if *count == 0 || threshold > count => {
+                Err(ProofError::invalid_input_error(
+                    "threshold",
+                    format!("Threshold {} must be between 1 and the verifier count {}", threshold, count)
+                ))
+            }
+            VerificationStrategy::Probabilistic { sample_rate, .. } if !(*sample_rate > 0.0 && *sample_rate <= 1.0) => {
+                Err(ProofError::invalid_input_error(
+                    "sample_rate",
+                    format!("Sample rate {} must be in (0, 1]", sample_rate)
+                ))
+            }
+            VerificationStrategy::Incremental { chunk_size: 0 } => {
+                Err(ProofError::invalid_input_error("chunk_size", "Chunk size must be at least 1 byte"))
+            }
+            VerificationStrategy::Distributed { node_count: 0 } => {
+                Err(ProofError::invalid_input_error("node_count", "Distributed verification needs at least one node"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Consensus algorithms for verification
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ConsensusAlgorithm {
@@ -67,6 +110,60 @@ pub struct ConsensusEngine {
     pub nodes: Vec<VerificationNode>,
     pub algorithm: ConsensusAlgorithm,
     pub minimum_nodes: u32,
+    /// JSON file node reputations are saved to after each update
+    pub reputation_file: Option<PathBuf>,
+}
+
+impl ConsensusEngine {
+    /// Moves the reputation of every node that voted in `result` towards 1 if
+    /// it sided with the consensus and towards 0 if it dissented, then saves
+    /// the reputations to `reputation_file`
+    ///
+    /// Each update covers a tenth of the remaining distance, so a reputation stays within 0 to 1 and a single round never
+    /// makes or breaks a node.
+    pub fn apply_reputation_update(&mut self, result: &ConsensusResult) -> Result<()> {
+        for node in &mut self.nodes {
+            let Some(&vote) = result.node_votes.get(&node.id) else {
+                continue;
+            };
+            let reputation = node.reputation.clamp(0.0, 1.0);
+            node.reputation = if vote == result.consensus_reached {
+                reputation + REPUTATION_LEARNING_RATE * (1.0 - reputation)
+            } else {
+                reputation - REPUTATION_LEARNING_RATE * reputation
+            };
+        }
+        self.save_reputations()
+    }
+
+    /// Restores the reputations saved in `reputation_file` onto the registered
+    /// nodes, leaving nodes without a saved reputation unchanged
+    pub fn load_reputations(&mut self) -> Result<()> {
+        let Some(path) = &self.reputation_file else {
+            return Ok(());
+        };
+        if !path.exists() {
+            return Ok(());
+        }
+        let saved: HashMap<String, f64> = serde_json::from_slice(&std::fs::read(path)?)?;
+        for node in &mut self.nodes {
+            if let Some(&reputation) = saved.get(&node.id) {
+                node.reputation = reputation;
+            }
+        }
+        Ok(())
+    }
+
+    fn save_reputations(&self) -> Result<()> {
+        let Some(path) = &self.reputation_file else {
+            return Ok(());
+        };
+        let reputations: HashMap<&str, f64> = self.nodes.iter()
+            .map(|node| (node.id.as_str(), node.reputation))
+            .collect();
+        std::fs::write(path, serde_json::to_vec_pretty(&reputations)?)?;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,7 +187,9 @@ pub struct BatchVerificationRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProofVerificationItem {
     pub proof_id: String,
+    /// Proof encoded with [`Proof::encode`]
     pub proof_data: Vec<u8>,
+    /// Claimed content the proof is verified against
     pub public_inputs: Vec<u8>,
     pub expected_result: Option<bool>,
     pub metadata: HashMap<String, String>,
@@ -159,35 +258,60 @@ impl AdvancedVerificationEngine {
                 nodes: Vec::new(),
                 algorithm: ConsensusAlgorithm::MajorityVote,
                 minimum_nodes: 3,
+                reputation_file: None,
             },
+            node_client: NodeClient::new(DEFAULT_VOTE_TIMEOUT),
         }
     }
 
+    /// Sets how long each node has to answer a verification request
+    pub fn set_vote_timeout(&mut self, timeout: Duration) {
+        self.node_client = NodeClient::new(timeout);
+    }
+
     /// Verify a single proof with advanced strategies
     pub async fn verify_proof_advanced(
         &mut self,
         proof_data: &[u8],
         public_inputs: &[u8],
         strategy: &VerificationStrategy,
-    ) -> Result<IndividualVerificationResult, ZkIPFSError> {
+    ) -> Result<IndividualVerificationResult> {
+        strategy.validate()?;
         let start_time = std::time::Instant::now();
         let proof_hash = self.calculate_proof_hash(proof_data, public_inputs);
 
         // Check cache first
-        if let Some(cached) = self.verification_cache.get(&proof_hash) {
-            if !self.is_cache_expired(&cached) {
-                return Ok(IndividualVerificationResult {
-                    proof_id: proof_hash.clone(),
-                    verified: cached.result,
-                    confidence_score: 1.0,
-                    verification_time_ms: 0,
-                    verifier_nodes: vec!["cache".to_string()],
-                    error_message: None,
-                });
-            }
+        if let Some(cached) = self.cached_result(&proof_hash) {
+            return Ok(cached);
         }
 
-        let result = match strategy {
+        let result = self.run_strategy(proof_data, public_inputs, strategy).await;
+        let verification_time = start_time.elapsed().as_millis() as u64;
+        Ok(self.record_result(proof_hash, result, verification_time))
+    }
+
+    fn cached_result(&self, proof_hash: &str) -> Option<IndividualVerificationResult> {
+        let cached = self.verification_cache.get(proof_hash)?;
+        if self.is_cache_expired(cached) {
+            return None;
+        }
+        Some(IndividualVerificationResult {
+            proof_id: proof_hash.to_string(),
+            verified: cached.result,
+            confidence_score: 1.0,
+            verification_time_ms: 0,
+            verifier_nodes: vec!["cache".to_string()],
+            error_message: None,
+        })
+    }
+
+    async fn run_strategy(
+        &self,
+        proof_data: &[u8],
+        public_inputs: &[u8],
+        strategy: &VerificationStrategy,
+    ) -> Result<(bool, f64, Vec<String>)> {
+        match strategy {
             VerificationStrategy::Single => {
                 self.single_verification(proof_data, public_inputs).await
             },
@@ -197,8 +321,8 @@ impl AdvancedVerificationEngine {
             VerificationStrategy::Probabilistic { sample_rate, confidence } => {
                 self.probabilistic_verification(proof_data, public_inputs, *sample_rate, *confidence).await
             },
-            VerificationStrategy::Incremental { chunk_size } => {
-                self.incremental_verification(proof_data, public_inputs, *chunk_size).await
+            VerificationStrategy::Incremental { .. } => {
+                self.incremental_verification(proof_data, public_inputs).await
             },
             VerificationStrategy::Distributed { node_count } => {
                 self.distributed_verification(proof_data, public_inputs, *node_count).await
@@ -206,10 +330,17 @@ impl AdvancedVerificationEngine {
             VerificationStrategy::Consensus { algorithm } => {
                 self.consensus_verification(proof_data, public_inputs, algorithm).await
             },
-        };
-
-        let verification_time = start_time.elapsed().as_millis() as u64;
+        }
+    }
 
+    /// Caches a successful verification and turns `result` into the
+    /// per-proof report
+    fn record_result(
+        &mut self,
+        proof_hash: String,
+        result: Result<(bool, f64, Vec<String>)>,
+        verification_time: u64,
+    ) -> IndividualVerificationResult {
         match result {
             Ok((verified, confidence, nodes)) => {
                 // Cache the result
@@ -222,23 +353,23 @@ impl AdvancedVerificationEngine {
                     verification_count: 1,
                 });
 
-                Ok(IndividualVerificationResult {
+                IndividualVerificationResult {
                     proof_id: proof_hash,
                     verified,
                     confidence_score: confidence,
                     verification_time_ms: verification_time,
                     verifier_nodes: nodes,
                     error_message: None,
-                })
+                }
             },
-            Err(e) => Ok(IndividualVerificationResult {
+            Err(e) => IndividualVerificationResult {
                 proof_id: proof_hash,
                 verified: false,
                 confidence_score: 0.0,
                 verification_time_ms: verification_time,
                 verifier_nodes: vec![],
                 error_message: Some(e.to_string()),
-            }),
+            },
         }
     }
 
@@ -246,7 +377,12 @@ impl AdvancedVerificationEngine {
     pub async fn verify_batch(
         &mut self,
         request: BatchVerificationRequest,
-    ) -> Result<BatchVerificationResult, ZkIPFSError> {
+    ) -> Result<BatchVerificationResult> {
+        if request.proofs.is_empty() {
+            return Err(ProofError::invalid_input_error("proofs", "Batch verification request has no proofs"));
+        }
+        request.strategy.validate()?;
+
         let start_time = std::time::Instant::now();
         let batch_id = uuid::Uuid::new_v4().to_string();
         let total_proofs = request.proofs.len();
@@ -295,27 +431,35 @@ impl AdvancedVerificationEngine {
         &self,
         proof_data: &[u8],
         public_inputs: &[u8],
-    ) -> Result<(bool, f64, Vec<String>), ZkIPFSError> {
-        // Implementation would call the actual verification function
-        // This is a placeholder
-        let verified = !proof_data.is_empty() && !public_inputs.is_empty();
+    ) -> Result<(bool, f64, Vec<String>)> {
+        let verified = Self::verify_independently(proof_data, public_inputs).await?;
         Ok((verified, 1.0, vec!["single_verifier".to_string()]))
     }
 
+    /// Decodes and fully verifies a proof with a verifier of its own, sharing
+    /// no decoded state or cached results with other checks
+    async fn verify_independently(proof_data: &[u8], public_inputs: &[u8]) -> Result<bool> {
+        let proof = Proof::decode(proof_data)?;
+        let mut verifier = ProofVerifier::with_config(VerificationConfig {
+            bypass_cache: true,
+            ..VerificationConfig::default()
+        });
+        Ok(verifier.verify_detailed(&proof, public_inputs).await?.is_valid)
+    }
+
     async fn multi_verifier_verification(
         &self,
         proof_data: &[u8],
         public_inputs: &[u8],
         count: u32,
         threshold: u32,
-    ) -> Result<(bool, f64, Vec<String>), ZkIPFSError> {
+    ) -> Result<(bool, f64, Vec<String>)> {
         let mut verifications = Vec::new();
         let mut verifier_nodes = Vec::new();
 
         for i in 0..count {
             let node_id = format!("verifier_{}", i);
-            // Each verifier would independently verify the proof
-            let verified = self.single_verification(proof_data, public_inputs).await?.0;
+            let verified = Self::verify_independently(proof_data, public_inputs).await?;
             verifications.push(verified);
             verifier_nodes.push(node_id);
         }
@@ -333,37 +477,69 @@ impl AdvancedVerificationEngine {
         public_inputs: &[u8],
         sample_rate: f64,
         target_confidence: f64,
-    ) -> Result<(bool, f64, Vec<String>), ZkIPFSError> {
-        // Implement probabilistic verification with sampling
-        let sample_size = (proof_data.len() as f64 * sample_rate) as usize;
-        let samples_verified = sample_size; // Placeholder
-        let confidence = samples_verified as f64 / sample_size as f64;
-        
+    ) -> Result<(bool, f64, Vec<String>)> {
+        let proof = Proof::decode(proof_data)?;
+        let verifier = ProofVerifier::new();
+        let rejected = (false, 0.0, vec!["probabilistic_verifier".to_string()]);
+
+        // Checks that do not touch the seal are cheap enough to always run
+        let structure_valid = ProofVerifier::check_structure(&proof).valid_structure;
+        let content_valid = proof.claimed_content_hash(public_inputs) == Some(proof.content_hash);
+        if !structure_valid || !content_valid || proof.chain().any(|proof| !verifier.trusts_image_id(proof)) {
+            return Ok(rejected);
+        }
+        // A recorded image ID is now known to be trusted
+        let image_id = proof.image_id
+            .or_else(|| verifier.scheme_for(&proof).map(|scheme| scheme.image_id))
+            .ok_or_else(|| ProofError::verification_error(
+                format!("No proof scheme registered for {:?}", proof.proof_type)
+            ))?;
+
+        // The claim must be that of the trusted image committing this journal,
+        // and the journal must match the proof's hashes
+        let receipt: Receipt = bincode::deserialize(&proof.zk_proof.receipt_bytes()?)?;
+        let expected_claim = ReceiptClaim::ok(image_id, receipt.journal.bytes.clone()).digest::<sha::Impl>();
+        let claim_valid = receipt.claim().is_ok_and(|claim| claim.digest::<sha::Impl>() == expected_claim);
+        if !claim_valid || verifier.verify_journal_hashes(&proof)?.is_some() {
+            return Ok(rejected);
+        }
+
+        // A composite receipt's segments are checked on a sample, each for its
+        // seal and for starting where the previous segment, or the trusted
+        // image, left off; any other receipt is a single check of the whole seal
+        let (sampled, passed) = match receipt.inner.composite() {
+            Ok(composite) if !composite.segments.is_empty() => {
+                let context = VerifierContext::default();
+                let segments = &composite.segments;
+                let segment_valid = |index: usize| {
+                    let expected_pre = match index {
+                        0 => sha::Digest::from(image_id),
+                        _ => segments[index - 1].claim.post.digest::<sha::Impl>(),
+                    };
+                    segments[index].claim.pre.digest::<sha::Impl>() == expected_pre
+                        && segments[index].verify_integrity_with_context(&context).is_ok()
+                };
+                let sample = sample_indices(&proof.id, segments.len(), sample_rate);
+                let passed = sample.iter().filter(|&&index| segment_valid(index)).count();
+                (sample.len(), passed)
+            }
+            _ => (1, usize::from(receipt.verify(image_id).is_ok())),
+        };
+
+        let confidence = passed as f64 / sampled as f64;
         let verified = confidence >= target_confidence;
         Ok((verified, confidence, vec!["probabilistic_verifier".to_string()]))
     }
 
+    /// A receipt's seal cannot be checked piece by piece, so incremental
+    /// verification checks the whole proof; `chunk_size` is only validated
     async fn incremental_verification(
         &self,
         proof_data: &[u8],
         public_inputs: &[u8],
-        chunk_size: usize,
-    ) -> Result<(bool, f64, Vec<String>), ZkIPFSError> {
-        let chunks = proof_data.chunks(chunk_size);
-        let total_chunks = chunks.len();
-        let mut verified_chunks = 0;
-
-        for (i, _chunk) in chunks.enumerate() {
-            // Verify each chunk incrementally
-            let chunk_verified = true; // Placeholder
-            if chunk_verified {
-                verified_chunks += 1;
-            }
-        }
-
-        let confidence = verified_chunks as f64 / total_chunks as f64;
-        let verified = confidence > 0.95; // 95% of chunks must verify
-
+    ) -> Result<(bool, f64, Vec<String>)> {
+        let verified = Self::verify_independently(proof_data, public_inputs).await?;
+        let confidence = if verified { 1.0 } else { 0.0 };
         Ok((verified, confidence, vec!["incremental_verifier".to_string()]))
     }
 
@@ -372,177 +548,159 @@ impl AdvancedVerificationEngine {
         proof_data: &[u8],
         public_inputs: &[u8],
         node_count: u32,
-    ) -> Result<(bool, f64, Vec<String>), ZkIPFSError> {
-        // Distribute verification across multiple nodes
-        let available_nodes = self.consensus_engine.nodes.len().min(node_count as usize);
-        let mut results = Vec::new();
-        let mut node_ids = Vec::new();
-
-        for i in 0..available_nodes {
-            let node = &self.consensus_engine.nodes[i];
-            // Send verification request to node
-            let verified = true; // Placeholder for actual network call
-            results.push(verified);
-            node_ids.push(node.id.clone());
-        }
+    ) -> Result<(bool, f64, Vec<String>)> {
+        let nodes = &self.consensus_engine.nodes[..self.consensus_engine.nodes.len().min(node_count as usize)];
+        let votes = self.collect_votes(nodes, proof_data, public_inputs).await?;
 
-        let successful = results.iter().filter(|&&v| v).count();
-        let confidence = successful as f64 / available_nodes as f64;
-        let verified = confidence > 0.5; // Majority consensus
-
-        Ok((verified, confidence, node_ids))
-    }
+        // Nodes that did not cast a valid vote count against the proof
+        let successful = votes.values().filter(|&&vote| vote).count();
+        let confidence = successful as f64 / nodes.len() as f64;
+        let verified = confidence > 0.5;
 
-    async fn consensus_verification(
-        &self,
-        proof_data: &[u8],
-        public_inputs: &[u8],
-        algorithm: &ConsensusAlgorithm,
-    ) -> Result<(bool, f64, Vec<String>), ZkIPFSError> {
-        match algorithm {
-            ConsensusAlgorithm::MajorityVote => {
-                self.majority_vote_verification(proof_data, public_inputs).await
-            },
-            ConsensusAlgorithm::WeightedVote => {
-                self.weighted_vote_verification(proof_data, public_inputs).await
-            },
-            ConsensusAlgorithm::ByzantineFaultTolerant => {
-                self.bft_verification(proof_data, public_inputs).await
-            },
-            ConsensusAlgorithm::ProofOfStake => {
-                self.pos_verification(proof_data, public_inputs).await
-            },
-        }
+        Ok((verified, confidence, voted_nodes(nodes, &votes)))
     }
 
-    async fn majority_vote_verification(
+    /// Asks each of `nodes` to verify the proof, keeping only the votes whose
+    /// signature verifies against the node's public key
+    async fn collect_votes(
         &self,
+        nodes: &[VerificationNode],
         proof_data: &[u8],
         public_inputs: &[u8],
-    ) -> Result<(bool, f64, Vec<String>), ZkIPFSError> {
-        let node_count = self.consensus_engine.nodes.len();
-        let mut votes = Vec::new();
-        let mut node_ids = Vec::new();
-
-        for node in &self.consensus_engine.nodes {
-            // Get vote from each node
-            let vote = true; // Placeholder
-            votes.push(vote);
-            node_ids.push(node.id.clone());
+    ) -> Result<HashMap<String, bool>> {
+        if nodes.is_empty() {
+            return Err(ProofError::invalid_input_error("nodes", "No verification nodes are registered"));
         }
 
-        let positive_votes = votes.iter().filter(|&&v| v).count();
-        let verified = positive_votes > node_count / 2;
-        let confidence = positive_votes as f64 / node_count as f64;
-
-        Ok((verified, confidence, node_ids))
-    }
+        let item = ProofVerificationItem {
+            proof_id: self.calculate_proof_hash(proof_data, public_inputs),
+            proof_data: proof_data.to_vec(),
+            public_inputs: public_inputs.to_vec(),
+            expected_result: None,
+            metadata: HashMap::new(),
+        };
+        let proof_hash = proof_item_hash(proof_data, public_inputs);
 
-    async fn weighted_vote_verification(
-        &self,
-        proof_data: &[u8],
-        public_inputs: &[u8],
-    ) -> Result<(bool, f64, Vec<String>), ZkIPFSError> {
-        let mut total_weight = 0.0;
-        let mut positive_weight = 0.0;
-        let mut node_ids = Vec::new();
-
-        for node in &self.consensus_engine.nodes {
-            let weight = node.stake as f64 * node.reputation;
-            total_weight += weight;
-            
-            // Get weighted vote from node
-            let vote = true; // Placeholder
-            if vote {
-                positive_weight += weight;
+        let mut votes = HashMap::new();
+        for node in nodes {
+            match self.node_client.request_vote(node, &item).await {
+                Ok(vote) if vote.is_signed_by(node, &proof_hash) => {
+                    votes.insert(node.id.clone(), vote.verified);
+                }
+                Ok(_) => warn!("Discarding vote from node {} with an invalid signature", node.id),
+                Err(e) => warn!("Node {} did not vote: {}", node.id, e),
             }
-            node_ids.push(node.id.clone());
         }
-
-        let confidence = positive_weight / total_weight;
-        let verified = confidence > 0.5;
-
-        Ok((verified, confidence, node_ids))
+        Ok(votes)
     }
 
-    async fn bft_verification(
+    async fn consensus_verification(
         &self,
         proof_data: &[u8],
         public_inputs: &[u8],
-    ) -> Result<(bool, f64, Vec<String>), ZkIPFSError> {
-        // Byzantine Fault Tolerant verification
-        let node_count = self.consensus_engine.nodes.len();
-        let required_honest = (2 * node_count + 2) / 3; // 2/3 + 1 majority
-        
-        let mut honest_votes = 0;
-        let mut node_ids = Vec::new();
-
-        for node in &self.consensus_engine.nodes {
-            // Assume node is honest and vote is correct
-            let vote = true; // Placeholder
-            if vote {
-                honest_votes += 1;
-            }
-            node_ids.push(node.id.clone());
-        }
-
-        let verified = honest_votes >= required_honest;
-        let confidence = honest_votes as f64 / node_count as f64;
-
-        Ok((verified, confidence, node_ids))
+        algorithm: &ConsensusAlgorithm,
+    ) -> Result<(bool, f64, Vec<String>)> {
+        let round = self.consensus_round(proof_data, public_inputs, algorithm).await?;
+        let nodes = voted_nodes(&self.consensus_engine.nodes, &round.node_votes);
+        Ok((round.consensus_reached, round.consensus_confidence, nodes))
     }
 
-    async fn pos_verification(
+    /// Collects the registered nodes' signed votes on a proof and tallies them
+    /// with `algorithm`
+    ///
+    /// `node_votes` holds each node's own verdict, so the result can be passed
+    /// to [`ConsensusEngine::apply_reputation_update`].
+    pub async fn consensus_round(
         &self,
         proof_data: &[u8],
         public_inputs: &[u8],
-    ) -> Result<(bool, f64, Vec<String>), ZkIPFSError> {
-        // Proof of Stake based verification
-        let total_stake: u64 = self.consensus_engine.nodes.iter().map(|n| n.stake).sum();
-        let mut supporting_stake = 0u64;
-        let mut node_ids = Vec::new();
-
-        for node in &self.consensus_engine.nodes {
-            // Stake-weighted verification
-            let vote = true; // Placeholder
-            if vote {
-                supporting_stake += node.stake;
-            }
-            node_ids.push(node.id.clone());
-        }
-
-        let confidence = supporting_stake as f64 / total_stake as f64;
-        let verified = confidence > 0.5;
-
-        Ok((verified, confidence, node_ids))
+        algorithm: &ConsensusAlgorithm,
+    ) -> Result<ConsensusResult> {
+        let nodes = &self.consensus_engine.nodes;
+        let votes = self.collect_votes(nodes, proof_data, public_inputs).await?;
+        let (consensus_reached, consensus_confidence) = match algorithm {
+            ConsensusAlgorithm::MajorityVote => majority_vote(nodes, &votes),
+            ConsensusAlgorithm::WeightedVote => weighted_vote(nodes, &votes),
+            ConsensusAlgorithm::ByzantineFaultTolerant => bft_vote(nodes, &votes),
+            ConsensusAlgorithm::ProofOfStake => stake_vote(nodes, &votes),
+        };
+        Ok(ConsensusResult {
+            algorithm_used: algorithm.clone(),
+            participating_nodes: nodes.len() as u32,
+            consensus_reached,
+            consensus_confidence,
+            node_votes: votes,
+        })
     }
 
+    /// Verifies a chunk's proofs concurrently, running at most
+    /// `parallel_workers` of them at a time
     async fn process_proof_chunk(
         &mut self,
         chunk: &[ProofVerificationItem],
         strategy: &VerificationStrategy,
-    ) -> Result<Vec<IndividualVerificationResult>, ZkIPFSError> {
-        let mut results = Vec::new();
-
-        // Process chunk items in parallel
-        for item in chunk {
-            let result = self.verify_proof_advanced(
-                &item.proof_data,
-                &item.public_inputs,
-                strategy,
-            ).await?;
-            results.push(result);
+    ) -> Result<Vec<IndividualVerificationResult>> {
+        let mut results: Vec<_> = chunk.iter()
+            .map(|item| self.cached_result(&self.calculate_proof_hash(&item.proof_data, &item.public_inputs)))
+            .collect();
+        let mut pending = (0..chunk.len()).filter(|&index| results[index].is_none()).collect::<Vec<_>>().into_iter();
+
+        let mut outcomes = Vec::new();
+        {
+            let engine = &*self;
+            let mut in_flight = FuturesUnordered::new();
+            for index in pending.by_ref().take(self.batch_processor.parallel_workers.max(1)) {
+                in_flight.push(engine.verify_item(index, &chunk[index], strategy));
+            }
+            while let Some(outcome) = in_flight.next().await {
+                outcomes.push(outcome);
+                if let Some(index) = pending.next() {
+                    in_flight.push(engine.verify_item(index, &chunk[index], strategy));
+                }
+            }
+        }
+
+        for (index, proof_hash, result, verification_time) in outcomes {
+            results[index] = Some(self.record_result(proof_hash, result, verification_time));
         }
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Runs `strategy` on one batch item, giving each attempt
+    /// `timeout_seconds` and retrying retryable failures up to
+    /// `retry_attempts` times
+    async fn verify_item(
+        &self,
+        index: usize,
+        item: &ProofVerificationItem,
+        strategy: &VerificationStrategy,
+    ) -> (usize, String, Result<(bool, f64, Vec<String>)>, u64) {
+        let start_time = std::time::Instant::now();
+        let timeout = Duration::from_secs(self.batch_processor.timeout_seconds);
+
+        let mut attempt = 0;
+        let result = loop {
+            let result = tokio::time::timeout(timeout, self.run_strategy(&item.proof_data, &item.public_inputs, strategy))
+                .await
+                .unwrap_or_else(|_| Err(ProofError::timeout_error(
+                    format!("verification of {}", item.proof_id),
+                    timeout.as_millis() as u64
+                )));
+            match &result {
+                Err(e) if e.is_retryable() && attempt < self.batch_processor.retry_attempts => {
+                    attempt += 1;
+                    warn!("Retrying verification of {} after attempt {} failed: {}", item.proof_id, attempt, e);
+                }
+                _ => break result,
+            }
+        };
 
-        Ok(results)
+        let proof_hash = self.calculate_proof_hash(&item.proof_data, &item.public_inputs);
+        (index, proof_hash, result, start_time.elapsed().as_millis() as u64)
     }
 
     fn calculate_proof_hash(&self, proof_data: &[u8], public_inputs: &[u8]) -> String {
-        use sha2::{Digest, Sha256};
-        let mut hasher = Sha256::new();
-        hasher.update(proof_data);
-        hasher.update(public_inputs);
-        hex::encode(hasher.finalize())
+        hex::encode(proof_item_hash(proof_data, public_inputs))
     }
 
     fn is_cache_expired(&self, cached: &CachedVerification) -> bool {
@@ -581,15 +739,188 @@ impl AdvancedVerificationEngine {
     }
 }
 
+/// A node's signed answer to a verification request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeVote {
+    pub node_id: String,
+    /// SHA-256 of the proof data and public inputs voted on
+    pub proof_hash: [u8; 32],
+    pub verified: bool,
+    /// Ed25519 signature over [`NodeVote::message`]
+    pub signature: Vec<u8>,
+}
+
+impl NodeVote {
+    /// Signs a vote as `node_id` with the node's key
+    pub fn sign(node_id: impl Into<String>, proof_hash: [u8; 32], verified: bool, key: &SigningKey) -> Self {
+        use ed25519_dalek::Signer;
+
+        let node_id = node_id.into();
+        let signature = key.sign(&Self::message(&node_id, &proof_hash, verified)).to_bytes().to_vec();
+        Self { node_id, proof_hash, verified, signature }
+    }
+
+    /// Returns the bytes a vote's signature covers
+    pub fn message(node_id: &str, proof_hash: &[u8; 32], verified: bool) -> Vec<u8> {
+        let mut message = VOTE_DOMAIN.to_vec();
+        message.extend_from_slice(node_id.as_bytes());
+        message.push(0);
+        message.extend_from_slice(proof_hash);
+        message.push(u8::from(verified));
+        message
+    }
+
+    /// Checks that this is `node`'s vote on `proof_hash`, signed with its public key
+    pub fn is_signed_by(&self, node: &VerificationNode, proof_hash: &[u8; 32]) -> bool {
+        let Ok(public_key) = <[u8; 32]>::try_from(node.public_key.as_slice()) else {
+            return false;
+        };
+        let Ok(public_key) = crate::signing::VerifyingKey::from_bytes(&public_key) else {
+            return false;
+        };
+        let Ok(signature) = ed25519_dalek::Signature::from_slice(&self.signature) else {
+            return false;
+        };
+        self.node_id == node.id
+            && &self.proof_hash == proof_hash
+            && public_key
+                .verify_strict(&Self::message(&self.node_id, &self.proof_hash, self.verified), &signature)
+                .is_ok()
+    }
+}
+
+/// Sends verification requests to nodes over HTTP
+///
+/// Each request POSTs a [`ProofVerificationItem`] as JSON to the node's
+/// endpoint, which answers with a [`NodeVote`].
+#[derive(Debug, Clone)]
+pub struct NodeClient {
+    http: reqwest::Client,
+    timeout: Duration,
+}
+
+impl NodeClient {
+    pub fn new(timeout: Duration) -> Self {
+        Self { http: reqwest::Client::new(), timeout }
+    }
+
+    /// Asks `node` to verify `item`, failing if it cannot be reached in time
+    pub async fn request_vote(&self, node: &VerificationNode, item: &ProofVerificationItem) -> Result<NodeVote> {
+        let response = self.http
+            .post(&node.endpoint)
+            .timeout(self.timeout)
+            .json(item)
+            .send()
+            .await
+            .map_err(|e| if e.is_timeout() {
+                ProofError::timeout_error(format!("vote from node {}", node.id), self.timeout.as_millis() as u64)
+            } else {
+                ProofError::network_error("request_vote", format!("Node {} is unreachable: {}", node.id, e), Some(e))
+            })?;
+
+        if !response.status().is_success() {
+            return Err(ProofError::network_error(
+                "request_vote",
+                format!("Node {} returned {}", node.id, response.status()),
+                None
+            ));
+        }
+        response.json().await.map_err(|e| ProofError::network_error(
+            "request_vote",
+            format!("Node {} returned an invalid vote: {}", node.id, e),
+            Some(e)
+        ))
+    }
+}
+
+/// SHA-256 of a proof and its public inputs, identifying what a vote is for
+pub fn proof_item_hash(proof_data: &[u8], public_inputs: &[u8]) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(proof_data);
+    hasher.update(public_inputs);
+    hasher.finalize().into()
+}
+
+/// Ids of the nodes that cast a valid vote, in registration order
+fn voted_nodes(nodes: &[VerificationNode], votes: &HashMap<String, bool>) -> Vec<String> {
+    nodes.iter()
+        .filter(|node| votes.contains_key(&node.id))
+        .map(|node| node.id.clone())
+        .collect()
+}
+
+fn approves(node: &VerificationNode, votes: &HashMap<String, bool>) -> bool {
+    votes.get(&node.id) == Some(&true)
+}
+
+/// More than half of all nodes approve
+fn majority_vote(nodes: &[VerificationNode], votes: &HashMap<String, bool>) -> (bool, f64) {
+    let positive_votes = nodes.iter().filter(|node| approves(node, votes)).count();
+    (positive_votes > nodes.len() / 2, positive_votes as f64 / nodes.len() as f64)
+}
+
+/// Nodes holding more than half of the stake-times-reputation weight approve
+///
+/// Only nodes that cast a validly signed vote carry weight, so a forged vote
+/// cannot sway the tally in either direction however much stake it claims.
+fn weighted_vote(nodes: &[VerificationNode], votes: &HashMap<String, bool>) -> (bool, f64) {
+    let weight = |node: &VerificationNode| node.stake as f64 * node.reputation;
+    let voters = || nodes.iter().filter(|node| votes.contains_key(&node.id));
+    let total_weight: f64 = voters().map(weight).sum();
+    let positive_weight: f64 = voters().filter(|node| approves(node, votes)).map(weight).sum();
+    let confidence = if total_weight > 0.0 { positive_weight / total_weight } else { 0.0 };
+    (confidence > 0.5, confidence)
+}
+
+/// At least two thirds of all nodes approve, tolerating up to a third being faulty
+fn bft_vote(nodes: &[VerificationNode], votes: &HashMap<String, bool>) -> (bool, f64) {
+    let required_honest = (2 * nodes.len() + 2) / 3;
+    let honest_votes = nodes.iter().filter(|node| approves(node, votes)).count();
+    (honest_votes >= required_honest, honest_votes as f64 / nodes.len() as f64)
+}
+
+/// Nodes holding more than half of the stake approve
+fn stake_vote(nodes: &[VerificationNode], votes: &HashMap<String, bool>) -> (bool, f64) {
+    let total_stake: u64 = nodes.iter().map(|node| node.stake).sum();
+    let supporting_stake: u64 = nodes.iter().filter(|node| approves(node, votes)).map(|node| node.stake).sum();
+    let confidence = if total_stake > 0 { supporting_stake as f64 / total_stake as f64 } else { 0.0 };
+    (confidence > 0.5, confidence)
+}
+
+/// Picks `ceil(len * sample_rate)` of the indices `0..len`, at least one
+///
+/// The choice is derived from `seed`, so the same proof is always sampled the
+/// same way and results can be reproduced.
+fn sample_indices(seed: &str, len: usize, sample_rate: f64) -> Vec<usize> {
+    use sha2::{Digest, Sha256};
+
+    let count = ((len as f64 * sample_rate).ceil() as usize).clamp(1, len);
+    let mut indices: Vec<usize> = (0..len).collect();
+    indices.sort_by_cached_key(|index| {
+        let mut hasher = Sha256::new();
+        hasher.update(seed.as_bytes());
+        hasher.update((*index as u64).to_le_bytes());
+        <[u8; 32]>::from(hasher.finalize())
+    });
+    indices.truncate(count);
+    indices.sort_unstable();
+    indices
+}
+
 impl VerificationCache {
     fn get(&self, key: &str) -> Option<&CachedVerification> {
         self.cache.get(key)
     }
 
     fn insert(&mut self, key: String, value: CachedVerification) {
-        if self.cache.len() >= self.max_entries {
-            // Simple LRU eviction - remove oldest entry
-            if let Some(oldest_key) = self.cache.keys().next().cloned() {
+        if self.cache.len() >= self.max_entries && !self.cache.contains_key(&key) {
+            // Evict the entry cached longest ago
+            let oldest_key = self.cache.iter()
+                .min_by_key(|(_, cached)| cached.timestamp)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest_key) = oldest_key {
                 self.cache.remove(&oldest_key);
             }
         }
@@ -597,3 +928,323 @@ impl VerificationCache {
     }
 }
 
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn request(strategy: VerificationStrategy) -> BatchVerificationRequest {
+        BatchVerificationRequest {
+            proofs: vec![ProofVerificationItem {
+                proof_id: "proof-1".to_string(),
+                proof_data: vec![1, 2, 3],
+                public_inputs: vec![4],
+                expected_result: Some(true),
+                metadata: HashMap::new(),
+            }],
+            strategy,
+            priority: VerificationPriority::Normal,
+            callback_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_strategy_surfaces_as_proof_error() {
+        let mut engine = AdvancedVerificationEngine::new();
+
+        let error = engine.verify_batch(request(VerificationStrategy::Incremental { chunk_size: 0 }))
+            .await
+            .unwrap_err();
+        match &error {
+            ProofError::InvalidInputError { field, message } => {
+                assert_eq!(field, "chunk_size");
+                assert!(message.contains("at least 1 byte"));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(error.is_user_error());
+
+        let error = engine.verify_proof_advanced(
+            &[1],
+            &[2],
+            &VerificationStrategy::MultiVerifier { count: 2, threshold: 3 },
+        ).await.unwrap_err();
+        assert!(error.to_string().contains("Threshold 3"), "{}", error);
+
+        let result = engine.verify_batch(request(VerificationStrategy::Single)).await.unwrap();
+        assert_eq!(result.successful_verifications, 0);
+        assert!(result.individual_results[0].error_message.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_multi_verifier_rejects_tampered_proof() {
+        use crate::{ContentSelection, ProofConfig, ProofFormat, ProofGenerator};
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"Quarterly figures were restated in March").unwrap();
+        let mut generator = ProofGenerator::with_config(ProofConfig { use_cache: false, ..ProofConfig::default() })
+            .await
+            .unwrap();
+        let proof = generator
+            .generate_proof(file.path(), ContentSelection::Pattern { content: b"restated".to_vec() })
+            .await
+            .unwrap();
+        let strategy = VerificationStrategy::MultiVerifier { count: 3, threshold: 2 };
+
+        let mut engine = AdvancedVerificationEngine::new();
+        let genuine = proof.encode(ProofFormat::Json).unwrap();
+        let result = engine.verify_proof_advanced(&genuine, b"restated", &strategy).await.unwrap();
+        assert!(result.verified, "{:?}", result.error_message);
+        assert_eq!(result.confidence_score, 1.0);
+
+        let mut tampered = proof.clone();
+        tampered.root_hash[0] ^= 1;
+        let tampered = tampered.encode(ProofFormat::Json).unwrap();
+        let result = engine.verify_proof_advanced(&tampered, b"restated", &strategy).await.unwrap();
+        assert!(!result.verified);
+        assert_eq!(result.confidence_score, 0.0);
+        assert_eq!(result.verifier_nodes.len(), 3);
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest_entry() {
+        let mut cache = VerificationCache { cache: HashMap::new(), max_entries: 2, ttl_seconds: 3600 };
+        let cached = |timestamp| CachedVerification { result: true, timestamp, verification_count: 1 };
+        cache.insert("newer".to_string(), cached(20));
+        cache.insert("older".to_string(), cached(10));
+        cache.insert("newest".to_string(), cached(30));
+
+        assert!(cache.get("older").is_none());
+        assert!(cache.get("newer").is_some());
+        assert!(cache.get("newest").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_sampled_strategies_reject_foreign_image() {
+        use crate::{ContentSelection, ProofConfig, ProofFormat, ProofGenerator};
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"Shipments resumed after the audit").unwrap();
+        let mut generator = ProofGenerator::with_config(ProofConfig { use_cache: false, ..ProofConfig::default() })
+            .await
+            .unwrap();
+        let proof = generator
+            .generate_proof(file.path(), ContentSelection::Pattern { content: b"audit".to_vec() })
+            .await
+            .unwrap();
+        let mut foreign = proof.clone();
+        foreign.image_id = Some([3u32; 8]);
+        let (genuine, foreign) = (proof.encode(ProofFormat::Json).unwrap(), foreign.encode(ProofFormat::Json).unwrap());
+
+        for strategy in [
+            VerificationStrategy::Probabilistic { sample_rate: 1.0, confidence: 1.0 },
+            VerificationStrategy::Incremental { chunk_size: 1024 },
+        ] {
+            // Results are cached per proof, so each strategy gets its own engine
+            let mut engine = AdvancedVerificationEngine::new();
+            let result = engine.verify_proof_advanced(&genuine, b"audit", &strategy).await.unwrap();
+            assert!(result.verified, "{:?}: {:?}", strategy, result.error_message);
+
+            let result = engine.verify_proof_advanced(&foreign, b"audit", &strategy).await.unwrap();
+            assert!(!result.verified, "{:?}", strategy);
+        }
+    }
+
+    /// Verifier node answering every request with `verified`, signed with `key`
+    async fn spawn_verifier(id: &'static str, key: SigningKey, verified: bool) -> String {
+        spawn_slow_verifier(id, key, verified, Duration::ZERO).await
+    }
+
+    /// Verifier node that takes `delay` to answer each request
+    async fn spawn_slow_verifier(id: &'static str, key: SigningKey, verified: bool, delay: Duration) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/verify", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let key = key.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 8192];
+                    let item: ProofVerificationItem = loop {
+                        let n = stream.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                        let Some(header_end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+                            continue;
+                        };
+                        let head = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+                        let content_length: usize = head.lines()
+                            .find_map(|line| line.strip_prefix("content-length:"))
+                            .map(|value| value.trim().parse().unwrap())
+                            .unwrap_or(0);
+                        let body = &request[header_end + 4..];
+                        if body.len() >= content_length {
+                            break serde_json::from_slice(&body[..content_length]).unwrap();
+                        }
+                    };
+
+                    tokio::time::sleep(delay).await;
+                    let proof_hash = proof_item_hash(&item.proof_data, &item.public_inputs);
+                    let vote = serde_json::to_vec(&NodeVote::sign(id, proof_hash, verified, &key)).unwrap();
+                    let head = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        vote.len()
+                    );
+                    stream.write_all(head.as_bytes()).await.unwrap();
+                    stream.write_all(&vote).await.unwrap();
+                });
+            }
+        });
+        url
+    }
+
+    fn node(id: &str, endpoint: String, key: &SigningKey) -> VerificationNode {
+        VerificationNode {
+            id: id.to_string(),
+            endpoint,
+            public_key: key.verifying_key().to_bytes().to_vec(),
+            stake: 100,
+            reputation: 1.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_majority_counts_only_signed_votes() {
+        let yes_key = SigningKey::from_bytes(&[1; 32]);
+        let no_key = SigningKey::from_bytes(&[2; 32]);
+        let forger_key = SigningKey::from_bytes(&[3; 32]);
+        let strategy = VerificationStrategy::Consensus { algorithm: ConsensusAlgorithm::MajorityVote };
+
+        // The forger signs "yes" with a key other than the one it registered
+        let mut engine = AdvancedVerificationEngine::new();
+        engine.set_vote_timeout(Duration::from_secs(5));
+        engine.consensus_engine.nodes = vec![
+            node("yes", spawn_verifier("yes", yes_key.clone(), true).await, &yes_key),
+            node("no", spawn_verifier("no", no_key.clone(), false).await, &no_key),
+            node("forger", spawn_verifier("forger", forger_key.clone(), true).await, &yes_key),
+            node("offline", "http://127.0.0.1:1/verify".to_string(), &yes_key),
+        ];
+
+        let result = engine.verify_proof_advanced(b"proof", b"inputs", &strategy).await.unwrap();
+        assert!(!result.verified);
+        assert_eq!(result.confidence_score, 0.25);
+        assert_eq!(result.verifier_nodes, vec!["yes", "no"]);
+
+        let second_yes_key = SigningKey::from_bytes(&[4; 32]);
+        engine.consensus_engine.nodes = vec![
+            node("yes", spawn_verifier("yes", yes_key.clone(), true).await, &yes_key),
+            node("no", spawn_verifier("no", no_key.clone(), false).await, &no_key),
+            node("yes-2", spawn_verifier("yes-2", second_yes_key.clone(), true).await, &second_yes_key),
+        ];
+        let result = engine.verify_proof_advanced(b"proof", b"other inputs", &strategy).await.unwrap();
+        assert!(result.verified);
+        assert!((result.confidence_score - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(result.verifier_nodes.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_weighted_vote_excludes_forged_stake() {
+        let yes_key = SigningKey::from_bytes(&[5; 32]);
+        let no_key = SigningKey::from_bytes(&[6; 32]);
+        let whale_key = SigningKey::from_bytes(&[7; 32]);
+        let strategy = VerificationStrategy::Consensus { algorithm: ConsensusAlgorithm::WeightedVote };
+
+        let mut engine = AdvancedVerificationEngine::new();
+        let mut yes = node("yes", spawn_verifier("yes", yes_key.clone(), true).await, &yes_key);
+        yes.stake = 300;
+        let no = node("no", spawn_verifier("no", no_key.clone(), false).await, &no_key);
+        // Claims most of the stake but signs its "no" with a key it never registered
+        let mut whale = node("whale", spawn_verifier("whale", SigningKey::from_bytes(&[8; 32]), false).await, &whale_key);
+        whale.stake = 1_000_000;
+        engine.consensus_engine.nodes = vec![yes, no, whale];
+
+        let result = engine.verify_proof_advanced(b"proof", b"inputs", &strategy).await.unwrap();
+        assert!(result.verified);
+        assert_eq!(result.confidence_score, 0.75);
+        assert_eq!(result.verifier_nodes, vec!["yes", "no"]);
+    }
+
+    #[tokio::test]
+    async fn test_dissenting_node_loses_reputation() {
+        let keys: Vec<_> = (10..13).map(|seed| SigningKey::from_bytes(&[seed; 32])).collect();
+        let dir = tempfile::tempdir().unwrap();
+        let reputation_file = dir.path().join("reputations.json");
+
+        let mut engine = AdvancedVerificationEngine::new();
+        engine.consensus_engine.reputation_file = Some(reputation_file.clone());
+        engine.consensus_engine.nodes = vec![
+            node("honest-1", spawn_verifier("honest-1", keys[0].clone(), true).await, &keys[0]),
+            node("honest-2", spawn_verifier("honest-2", keys[1].clone(), true).await, &keys[1]),
+            node("wrong", spawn_verifier("wrong", keys[2].clone(), false).await, &keys[2]),
+        ];
+        for node in &mut engine.consensus_engine.nodes {
+            node.reputation = 0.8;
+        }
+
+        for round in 0..5 {
+            let result = engine
+                .consensus_round(b"proof", format!("round {}", round).as_bytes(), &ConsensusAlgorithm::WeightedVote)
+                .await
+                .unwrap();
+            assert!(result.consensus_reached);
+            engine.consensus_engine.apply_reputation_update(&result).unwrap();
+        }
+
+        let nodes = &engine.consensus_engine.nodes;
+        assert!(nodes[0].reputation > 0.8 && nodes[0].reputation < 1.0);
+        assert!(nodes[2].reputation < 0.5, "{}", nodes[2].reputation);
+
+        let mut restored = ConsensusEngine {
+            nodes: engine.consensus_engine.nodes.iter()
+                .map(|node| VerificationNode { reputation: 1.0, ..node.clone() })
+                .collect(),
+            algorithm: ConsensusAlgorithm::WeightedVote,
+            minimum_nodes: 3,
+            reputation_file: Some(reputation_file),
+        };
+        restored.load_reputations().unwrap();
+        assert_eq!(restored.nodes[2].reputation, engine.consensus_engine.nodes[2].reputation);
+    }
+
+    #[tokio::test]
+    async fn test_batch_verifies_proofs_concurrently() {
+        let key = SigningKey::from_bytes(&[20; 32]);
+        let delay = Duration::from_millis(50);
+        let mut engine = AdvancedVerificationEngine::new();
+        engine.batch_processor.parallel_workers = 25;
+        engine.consensus_engine.nodes = vec![
+            node("slow", spawn_slow_verifier("slow", key.clone(), true, delay).await, &key),
+        ];
+
+        let proofs = (0..100)
+            .map(|i| ProofVerificationItem {
+                proof_id: format!("proof-{}", i),
+                proof_data: vec![1, 2, 3],
+                public_inputs: format!("inputs {}", i).into_bytes(),
+                expected_result: Some(true),
+                metadata: HashMap::new(),
+            })
+            .collect();
+        let request = BatchVerificationRequest {
+            proofs,
+            strategy: VerificationStrategy::Distributed { node_count: 1 },
+            priority: VerificationPriority::Normal,
+            callback_url: None,
+        };
+
+        let start = std::time::Instant::now();
+        let result = engine.verify_batch(request).await.unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(result.successful_verifications, 100);
+        assert!(elapsed < delay * 100 / 4, "batch took {:?}", elapsed);
+        assert_eq!(result.individual_results.len(), 100);
+    }
+}
@@ -0,0 +1,411 @@
+//! Proving on Bonsai with credentials that can be rotated while running
+//!
+//! `risc0_zkvm::default_prover` reads the Bonsai URL and API key from the
+//! environment, so changing them means restarting the process. A
+//! [`BonsaiCredentialStore`] holds them instead and [`BonsaiProver`] reads it
+//! on every request, so new credentials apply from the next upload or status
+//! poll, including those of a session already running.
+//!
+//! A key Bonsai rejects is reported as [`ProofError::AuthenticationError`];
+//! failing to reach Bonsai at all is a retryable [`ProofError::NetworkError`].
+
+use crate::{
+    error::{ProofError, Result},
+    secret::Secret,
+};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use risc0_zkvm::{sha::Digest, Receipt};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+use tracing::debug;
+
+/// How often a running session's status is polled by default
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// URL and API key of a Bonsai deployment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BonsaiCredentials {
+    /// Base URL of the Bonsai REST API
+    pub api_url: String,
+    /// API key sent with every request
+    pub api_key: Secret<String>,
+}
+
+impl BonsaiCredentials {
+    pub fn new(api_url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into(),
+            api_key: Secret::new(api_key.into()),
+        }
+    }
+
+    /// Reads `BONSAI_API_URL` and `BONSAI_API_KEY`, if both are set
+    pub fn from_env() -> Option<Self> {
+        let api_url = std::env::var("BONSAI_API_URL").ok()?;
+        let api_key = std::env::var("BONSAI_API_KEY").ok()?;
+        Some(Self::new(api_url, api_key))
+    }
+}
+
+/// Shared Bonsai credentials that can be replaced at any time
+///
+/// Clones share the same credentials, so a store handed to a generator can
+/// be updated from elsewhere, such as an admin endpoint.
+#[derive(Clone, Debug, Default)]
+pub struct BonsaiCredentialStore {
+    current: Arc<RwLock<Option<BonsaiCredentials>>>,
+}
+
+impl BonsaiCredentialStore {
+    /// Creates a store holding `credentials`
+    pub fn new(credentials: BonsaiCredentials) -> Self {
+        let store = Self::default();
+        store.set(credentials);
+        store
+    }
+
+    /// Creates a store holding the credentials in the environment, if any
+    pub fn from_env() -> Self {
+        let store = Self::default();
+        if let Some(credentials) = BonsaiCredentials::from_env() {
+            store.set(credentials);
+        }
+        store
+    }
+
+    /// Replaces the credentials used from the next request on
+    pub fn set(&self, credentials: BonsaiCredentials) {
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = Some(credentials);
+    }
+
+    /// Removes the credentials, failing later requests until new ones are set
+    pub fn clear(&self) {
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Whether credentials are set
+    pub fn is_set(&self) -> bool {
+        self.current.read().unwrap_or_else(PoisonError::into_inner).is_some()
+    }
+
+    /// Returns the credentials to use for a request
+    pub fn current(&self) -> Result<BonsaiCredentials> {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .ok_or_else(|| ProofError::configuration_error(
+                "No Bonsai credentials are set; set BONSAI_API_URL and BONSAI_API_KEY"
+            ))
+    }
+}
+
+/// Upload location Bonsai returns for an image or input
+#[derive(Deserialize)]
+struct UploadRes {
+    url: String,
+    #[serde(default)]
+    uuid: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SessionRes {
+    uuid: String,
+}
+
+#[derive(Deserialize)]
+struct SessionStatusRes {
+    status: String,
+    receipt_url: Option<String>,
+    error_msg: Option<String>,
+    #[serde(default)]
+    stats: Option<SessionStats>,
+}
+
+/// Cycle counts a prover reports for a proving session
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SessionStats {
+    /// Number of segments the execution was split into
+    pub segments: u32,
+    /// Cycles executed, before each segment is padded to a power of two
+    pub cycles: u64,
+}
+
+/// Proves guest programs on Bonsai's REST API
+///
+/// Bonsai sessions produce succinct receipts.
+#[derive(Clone, Debug)]
+pub struct BonsaiProver {
+    credentials: BonsaiCredentialStore,
+    http: reqwest::Client,
+    poll_interval: Duration,
+}
+
+impl BonsaiProver {
+    /// Creates a prover that authenticates with whatever `credentials` holds
+    /// at the time of each request
+    pub fn new(credentials: BonsaiCredentialStore) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .map_err(|e| ProofError::network_error("bonsai_client", "Failed to create HTTP client", Some(e)))?;
+        Ok(Self { credentials, http, poll_interval: DEFAULT_POLL_INTERVAL })
+    }
+
+    /// Credentials the prover reads on every request
+    pub fn credentials(&self) -> &BonsaiCredentialStore {
+        &self.credentials
+    }
+
+    /// Sets how often a running session's status is polled
+    pub fn set_poll_interval(&mut self, poll_interval: Duration) {
+        self.poll_interval = poll_interval;
+    }
+
+    /// Proves `elf` with `input` as the guest's stdin, waiting for the session
+    /// to finish
+    ///
+    /// Returns the receipt with the session's cycle counts, if Bonsai reported them.
+    pub async fn prove(&self, elf: &[u8], image_id: [u32; 8], input: Vec<u8>) -> Result<(Receipt, Option<SessionStats>)> {
+        let image_id = Digest::from(image_id).to_string();
+        self.upload_image(&image_id, elf).await?;
+        let input_id = self.upload_input(input).await?;
+
+        let session = self.send("bonsai_session", self.request(Method::POST, "sessions/create")?
+            .json(&serde_json::json!({
+                "img": image_id,
+                "input": input_id,
+                "assumptions": [],
+                "execute_only": false,
+            })))
+            .await?;
+        let session: SessionRes = parse("bonsai_session", session).await?;
+        debug!("Started Bonsai session {}", session.uuid);
+
+        loop {
+            let status = self.send(
+                "bonsai_status",
+                self.request(Method::GET, &format!("sessions/status/{}", session.uuid))?,
+            ).await?;
+            let status: SessionStatusRes = parse("bonsai_status", status).await?;
+            match status.status.as_str() {
+                "RUNNING" => tokio::time::sleep(self.poll_interval).await,
+                "SUCCEEDED" => {
+                    let receipt_url = status.receipt_url.ok_or_else(|| ProofError::zk_proof_error(
+                        "bonsai_receipt",
+                        format!("Bonsai session {} succeeded without a receipt", session.uuid),
+                        None,
+                    ))?;
+                    let receipt = self.send("bonsai_receipt", self.http.get(receipt_url)).await?
+                        .bytes()
+                        .await
+                        .map_err(|e| ProofError::network_error("bonsai_receipt", "Failed to download receipt", Some(e)))?;
+                    let receipt = bincode::deserialize(&receipt).map_err(|e| ProofError::serialization_error(
+                        "Failed to decode Bonsai receipt",
+                        Some(Box::new(e)),
+                    ))?;
+                    return Ok((receipt, status.stats));
+                }
+                other => {
+                    return Err(ProofError::zk_proof_error(
+                        "bonsai_session",
+                        format!(
+                            "Bonsai session {} ended {}: {}",
+                            session.uuid,
+                            other,
+                            status.error_msg.as_deref().unwrap_or("no error message")
+                        ),
+                        None,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Uploads the guest ELF unless Bonsai already has `image_id`
+    async fn upload_image(&self, image_id: &str, elf: &[u8]) -> Result<()> {
+        let response = self.send(
+            "bonsai_image_upload",
+            self.request(Method::GET, &format!("images/upload/{}", image_id))?,
+        ).await?;
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(());
+        }
+        let upload: UploadRes = parse("bonsai_image_upload", response).await?;
+        self.send("bonsai_image_upload", self.http.put(upload.url).body(elf.to_vec())).await?;
+        Ok(())
+    }
+
+    /// Uploads the guest input, returning its ID
+    async fn upload_input(&self, input: Vec<u8>) -> Result<String> {
+        let response = self.send("bonsai_input_upload", self.request(Method::GET, "inputs/upload")?).await?;
+        let upload: UploadRes = parse("bonsai_input_upload", response).await?;
+        self.send("bonsai_input_upload", self.http.put(upload.url).body(input)).await?;
+        upload.uuid.ok_or_else(|| ProofError::zk_proof_error(
+            "bonsai_input_upload",
+            "Bonsai did not return an input ID",
+            None,
+        ))
+    }
+
+    /// Builds a request to the Bonsai API with the current credentials
+    fn request(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        let credentials = self.credentials.current()?;
+        Ok(self.http
+            .request(method, format!("{}/{}", credentials.api_url.trim_end_matches('/'), path))
+            .header("x-api-key", credentials.api_key.expose())
+            .header("x-risc0-version", risc0_zkvm::VERSION))
+    }
+
+    /// Sends `request`, separating rejected credentials from other failures
+    async fn send(&self, operation: &str, request: RequestBuilder) -> Result<Response> {
+        let response = request.send().await.map_err(|e| ProofError::network_error(
+            operation,
+            format!("Could not reach Bonsai: {}", e),
+            Some(e),
+        ))?;
+
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(ProofError::authentication_error(
+                "bonsai",
+                format!("Bonsai rejected the API key ({})", status),
+            ));
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = format!("Bonsai returned {}: {}", status, body);
+            return Err(if status.is_server_error() {
+                ProofError::network_error(operation, message, None)
+            } else {
+                ProofError::zk_proof_error(operation, message, None)
+            });
+        }
+        Ok(response)
+    }
+}
+
+async fn parse<T: DeserializeOwned>(operation: &str, response: Response) -> Result<T> {
+    response.json().await.map_err(|e| ProofError::network_error(
+        operation,
+        format!("Unexpected response from Bonsai: {}", e),
+        Some(e),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves just enough of the Bonsai API to start a session, recording the
+    /// API key of each request. Sessions run until polled with `stop_key`, or
+    /// any key is `revoked`.
+    async fn mock_bonsai(keys: Arc<Mutex<Vec<String>>>, stop_key: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let base = url.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let keys = keys.clone();
+                let base = base.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 16 * 1024];
+                    let n = stream.read(&mut buf).await.unwrap();
+                    let head = String::from_utf8_lossy(&buf[..n]).to_string();
+                    let path = head.split_whitespace().nth(1).unwrap_or("/").to_string();
+                    let key = head.lines()
+                        .find_map(|line| line.strip_prefix("x-api-key: "))
+                        .map(str::to_string);
+
+                    let (status, body) = match (&key, path.as_str()) {
+                        (Some(key), _) if key == "revoked" => ("401 Unauthorized", String::new()),
+                        (None, "/upload/input") => ("200 OK", String::new()),
+                        (_, path) if path.starts_with("/images/upload/") => ("204 No Content", String::new()),
+                        (_, "/inputs/upload") => (
+                            "200 OK",
+                            format!(r#"{{"uuid":"input-1","url":"{}/upload/input"}}"#, base),
+                        ),
+                        (_, "/sessions/create") => ("200 OK", r#"{"uuid":"session-1"}"#.to_string()),
+                        (Some(key), _) if key == stop_key => (
+                            "200 OK",
+                            r#"{"status":"FAILED","error_msg":"stopped by test"}"#.to_string(),
+                        ),
+                        _ => ("200 OK", r#"{"status":"RUNNING"}"#.to_string()),
+                    };
+                    if let Some(key) = key {
+                        keys.lock().unwrap().push(key);
+                    }
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+
+        url
+    }
+
+    #[tokio::test]
+    async fn test_rotated_credentials_used_mid_session() {
+        let keys = Arc::new(Mutex::new(Vec::new()));
+        let url = mock_bonsai(keys.clone(), "rotated").await;
+        let store = BonsaiCredentialStore::new(BonsaiCredentials::new(url.clone(), "original"));
+        let mut prover = BonsaiProver::new(store.clone()).unwrap();
+        prover.set_poll_interval(Duration::from_millis(10));
+
+        let session = tokio::spawn(async move { prover.prove(b"elf", [7; 8], vec![1, 2, 3]).await });
+        while keys.lock().unwrap().len() < 5 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        store.set(BonsaiCredentials::new(url, "rotated"));
+
+        let error = session.await.unwrap().unwrap_err();
+        assert!(error.to_string().contains("stopped by test"), "{}", error);
+        let keys = keys.lock().unwrap();
+        // Image check, input upload, session creation and the first polls
+        assert!(keys[..5].iter().all(|key| key == "original"));
+        assert_eq!(keys.last().unwrap(), "rotated");
+    }
+
+    #[tokio::test]
+    async fn test_rejected_key_is_not_a_network_error() {
+        let url = mock_bonsai(Arc::new(Mutex::new(Vec::new())), "unused").await;
+        let store = BonsaiCredentialStore::new(BonsaiCredentials::new(url, "revoked"));
+        let prover = BonsaiProver::new(store.clone()).unwrap();
+
+        let error = prover.prove(b"elf", [7; 8], Vec::new()).await.unwrap_err();
+        assert!(matches!(error, ProofError::AuthenticationError { .. }), "{}", error);
+        assert!(!error.is_retryable());
+
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        store.set(BonsaiCredentials::new(format!("http://{}", closed.local_addr().unwrap()), "valid"));
+        drop(closed);
+        let error = prover.prove(b"elf", [7; 8], Vec::new()).await.unwrap_err();
+        assert!(matches!(error, ProofError::NetworkError { .. }), "{}", error);
+
+        store.clear();
+        assert!(matches!(prover.prove(b"elf", [7; 8], Vec::new()).await, Err(ProofError::ConfigurationError { .. })));
+    }
+
+    #[test]
+    fn test_session_stats_parsed() {
+        let status: SessionStatusRes = serde_json::from_str(
+            r#"{"status":"SUCCEEDED","receipt_url":"https://bonsai/receipt","error_msg":null,
+                "stats":{"segments":3,"total_cycles":3145728,"cycles":2801203}}"#
+        ).unwrap();
+        assert_eq!(status.stats, Some(SessionStats { segments: 3, cycles: 2_801_203 }));
+
+        let status: SessionStatusRes = serde_json::from_str(r#"{"status":"RUNNING"}"#).unwrap();
+        assert_eq!(status.stats, None);
+    }
+}
@@ -0,0 +1,342 @@
+//! Proof bundles: a proof and the blocks backing it, in one file
+//!
+//! A bundle (conventionally `.zkb`) is the magic `ZKBUNDLE`, a version byte
+//! and a sequence of named entries, each a little-endian `u16` name length, the
+//! name, a little-endian `u64` data length and the data:
+//!
+//! - `manifest.json`: a [`BundleManifest`] listing the other entries with their
+//!   sizes and SHA-256 digests
+//! - `proof.json`: the proof
+//! - `blocks.car`: a CARv1 archive of the blocks on the proof's inclusion path,
+//!   as written by `ProofGenerator::export_car`
+//! - `signature.json` (optional): an Ed25519 [`ProofSignature`] over the
+//!   manifest, identifying who packaged the bundle
+//!
+//! [`ProofBundle::read`] checks every entry against the manifest and the
+//! signature against the manifest, so a bundle that reads successfully is
+//! intact. The proof itself still has to be verified.
+
+use crate::{
+    error::{ProofError, Result},
+    signing::{SigningKey, VerifyingKey},
+    types::{Proof, ProofSignature},
+};
+use ed25519_dalek::{Signature, Signer};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+
+/// Leading bytes of every bundle
+pub const BUNDLE_MAGIC: &[u8; 8] = b"ZKBUNDLE";
+
+/// Container version written and accepted by this module
+const BUNDLE_VERSION: u8 = 1;
+
+/// Prefix of the signed manifest, so bundle signatures cannot be replayed elsewhere
+const BUNDLE_SIGNING_DOMAIN: &[u8] = b"zkipfs-proof/bundle/v1\0";
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const PROOF_ENTRY: &str = "proof.json";
+const CAR_ENTRY: &str = "blocks.car";
+const SIGNATURE_ENTRY: &str = "signature.json";
+
+/// Contents of a bundle, other than the manifest and signature
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BundleManifest {
+    /// Container version
+    pub version: u8,
+    /// ID of the bundled proof
+    pub proof_id: String,
+    /// Version of the library that wrote the bundle
+    pub library_version: String,
+    /// The proof and CAR entries
+    pub entries: Vec<BundleEntry>,
+}
+
+/// One entry listed in a [`BundleManifest`]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BundleEntry {
+    /// Entry name, such as `proof.json`
+    pub name: String,
+    /// Size of the entry in bytes
+    pub size: u64,
+    /// Hex-encoded SHA-256 of the entry
+    pub sha256: String,
+}
+
+impl BundleEntry {
+    fn new(name: &str, data: &[u8]) -> Self {
+        Self {
+            name: name.to_string(),
+            size: data.len() as u64,
+            sha256: hex::encode(Sha256::digest(data)),
+        }
+    }
+}
+
+/// A proof packaged with the CAR of its backing blocks
+#[derive(Clone, Debug)]
+pub struct ProofBundle {
+    /// The bundled proof
+    pub proof: Proof,
+    /// CARv1 archive of the blocks the proof is verified against
+    pub car: Vec<u8>,
+    /// Signature over the manifest, if the bundle is signed
+    signature: Option<ProofSignature>,
+}
+
+impl ProofBundle {
+    /// Creates an unsigned bundle
+    pub fn new(proof: Proof, car: Vec<u8>) -> Self {
+        Self { proof, car, signature: None }
+    }
+
+    /// Signs the bundle's manifest with `key`, replacing any signature
+    ///
+    /// Changing the proof or the CAR afterwards invalidates the signature, and
+    /// [`write`](Self::write) refuses to write it.
+    pub fn sign(&mut self, key: &SigningKey) -> Result<()> {
+        let message = Self::signing_message(&self.manifest_bytes()?);
+        self.signature = Some(ProofSignature {
+            signature: key.sign(&message).to_bytes().to_vec(),
+            signer_public_key: key.verifying_key().to_bytes(),
+        });
+        Ok(())
+    }
+
+    /// Key that signed the bundle, if signed
+    pub fn signer(&self) -> Option<VerifyingKey> {
+        let signature = self.signature.as_ref()?;
+        VerifyingKey::from_bytes(&signature.signer_public_key).ok()
+    }
+
+    /// Builds the manifest of the bundle's current contents
+    pub fn manifest(&self) -> Result<BundleManifest> {
+        Ok(Self::manifest_for(&self.proof.id, &self.proof_bytes()?, &self.car))
+    }
+
+    /// Writes the bundle
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<()> {
+        let proof = self.proof_bytes()?;
+        let manifest = encode_json(&Self::manifest_for(&self.proof.id, &proof, &self.car), "bundle manifest")?;
+        if let Some(signature) = &self.signature {
+            if !Self::signature_covers(signature, &manifest) {
+                return Err(ProofError::verification_error(
+                    "Bundle signature does not cover its contents; sign the bundle again"
+                ));
+            }
+        }
+
+        let mut header = BUNDLE_MAGIC.to_vec();
+        header.push(BUNDLE_VERSION);
+        write_all(&mut writer, &header)?;
+        write_entry(&mut writer, MANIFEST_ENTRY, &manifest)?;
+        write_entry(&mut writer, PROOF_ENTRY, &proof)?;
+        write_entry(&mut writer, CAR_ENTRY, &self.car)?;
+        if let Some(signature) = &self.signature {
+            write_entry(&mut writer, SIGNATURE_ENTRY, &encode_json(signature, "bundle signature")?)?;
+        }
+
+        writer.flush()
+            .map_err(|e| ProofError::file_error("Failed to flush proof bundle", Some(e)))
+    }
+
+    /// Reads a bundle, checking its entries and signature against the manifest
+    pub fn read<R: Read>(mut reader: R) -> Result<Self> {
+        let mut header = [0u8; BUNDLE_MAGIC.len() + 1];
+        read_exact(&mut reader, &mut header)?;
+        if &header[..BUNDLE_MAGIC.len()] != BUNDLE_MAGIC {
+            return Err(bundle_error("Not a proof bundle"));
+        }
+        if header[BUNDLE_MAGIC.len()] != BUNDLE_VERSION {
+            return Err(bundle_error(format!("Unsupported bundle version {}", header[BUNDLE_MAGIC.len()])));
+        }
+
+        let (mut manifest, mut proof, mut car, mut signature) = (None, None, None, None);
+        while let Some((name, data)) = read_entry(&mut reader)? {
+            let slot = match name.as_str() {
+                MANIFEST_ENTRY => &mut manifest,
+                PROOF_ENTRY => &mut proof,
+                CAR_ENTRY => &mut car,
+                SIGNATURE_ENTRY => &mut signature,
+                other => return Err(bundle_error(format!("Unexpected bundle entry: {}", other))),
+            };
+            if slot.replace(data).is_some() {
+                return Err(bundle_error(format!("Duplicate bundle entry: {}", name)));
+            }
+        }
+
+        let missing = |name: &str| bundle_error(format!("Bundle is missing {}", name));
+        let manifest_bytes = manifest.ok_or_else(|| missing(MANIFEST_ENTRY))?;
+        let proof_bytes = proof.ok_or_else(|| missing(PROOF_ENTRY))?;
+        let car = car.ok_or_else(|| missing(CAR_ENTRY))?;
+
+        let manifest: BundleManifest = serde_json::from_slice(&manifest_bytes)
+            .map_err(|e| ProofError::serialization_error("Failed to decode bundle manifest", Some(Box::new(e))))?;
+        let expected = [BundleEntry::new(PROOF_ENTRY, &proof_bytes), BundleEntry::new(CAR_ENTRY, &car)];
+        if manifest.entries != expected {
+            return Err(ProofError::verification_error("Bundle entries do not match its manifest"));
+        }
+
+        let signature = signature
+            .map(|bytes| serde_json::from_slice::<ProofSignature>(&bytes)
+                .map_err(|e| ProofError::serialization_error("Failed to decode bundle signature", Some(Box::new(e)))))
+            .transpose()?;
+        if let Some(signature) = &signature {
+            if !Self::signature_covers(signature, &manifest_bytes) {
+                return Err(ProofError::verification_error("Bundle signature is invalid"));
+            }
+        }
+
+        let proof: Proof = serde_json::from_slice(&proof_bytes)
+            .map_err(|e| ProofError::serialization_error("Failed to decode bundled proof", Some(Box::new(e))))?;
+        if proof.id != manifest.proof_id {
+            return Err(ProofError::verification_error("Bundled proof does not match its manifest"));
+        }
+
+        Ok(Self { proof, car, signature })
+    }
+
+    fn proof_bytes(&self) -> Result<Vec<u8>> {
+        encode_json(&self.proof, "bundled proof")
+    }
+
+    fn manifest_bytes(&self) -> Result<Vec<u8>> {
+        encode_json(&self.manifest()?, "bundle manifest")
+    }
+
+    fn manifest_for(proof_id: &str, proof: &[u8], car: &[u8]) -> BundleManifest {
+        BundleManifest {
+            version: BUNDLE_VERSION,
+            proof_id: proof_id.to_string(),
+            library_version: env!("CARGO_PKG_VERSION").to_string(),
+            entries: vec![BundleEntry::new(PROOF_ENTRY, proof), BundleEntry::new(CAR_ENTRY, car)],
+        }
+    }
+
+    fn signing_message(manifest: &[u8]) -> Vec<u8> {
+        let mut message = BUNDLE_SIGNING_DOMAIN.to_vec();
+        message.extend_from_slice(manifest);
+        message
+    }
+
+    fn signature_covers(signature: &ProofSignature, manifest: &[u8]) -> bool {
+        let Ok(signer) = VerifyingKey::from_bytes(&signature.signer_public_key) else {
+            return false;
+        };
+        let Ok(signature) = Signature::from_slice(&signature.signature) else {
+            return false;
+        };
+        signer.verify_strict(&Self::signing_message(manifest), &signature).is_ok()
+    }
+}
+
+fn encode_json<T: Serialize>(value: &T, what: &str) -> Result<Vec<u8>> {
+    serde_json::to_vec(value)
+        .map_err(|e| ProofError::serialization_error(format!("Failed to encode {}", what), Some(Box::new(e))))
+}
+
+fn write_entry<W: Write>(writer: &mut W, name: &str, data: &[u8]) -> Result<()> {
+    let name_len = u16::try_from(name.len())
+        .map_err(|_| bundle_error(format!("Bundle entry name too long: {}", name)))?;
+    write_all(writer, &name_len.to_le_bytes())?;
+    write_all(writer, name.as_bytes())?;
+    write_all(writer, &(data.len() as u64).to_le_bytes())?;
+    write_all(writer, data)
+}
+
+/// Reads the next entry, or `None` at the end of the bundle
+fn read_entry<R: Read>(reader: &mut R) -> Result<Option<(String, Vec<u8>)>> {
+    let mut name_len = [0u8; 2];
+    match reader.read(&mut name_len[..1]) {
+        Ok(0) => return Ok(None),
+        Ok(_) => read_exact(reader, &mut name_len[1..])?,
+        Err(e) => return Err(ProofError::file_error("Failed to read proof bundle", Some(e))),
+    }
+
+    let mut name = vec![0u8; u16::from_le_bytes(name_len) as usize];
+    read_exact(reader, &mut name)?;
+    let name = String::from_utf8(name)
+        .map_err(|_| bundle_error("Bundle entry name is not UTF-8"))?;
+
+    let mut data_len = [0u8; 8];
+    read_exact(reader, &mut data_len)?;
+    let data_len = u64::from_le_bytes(data_len);
+
+    // Read through `take` so a corrupt length cannot force a huge allocation
+    let mut data = Vec::new();
+    reader.by_ref().take(data_len).read_to_end(&mut data)
+        .map_err(|e| ProofError::file_error("Failed to read proof bundle", Some(e)))?;
+    if data.len() as u64 != data_len {
+        return Err(bundle_error(format!("Bundle entry {} is truncated", name)));
+    }
+
+    Ok(Some((name, data)))
+}
+
+fn read_exact<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<()> {
+    reader.read_exact(buffer)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => bundle_error("Proof bundle is truncated"),
+            _ => ProofError::file_error("Failed to read proof bundle", Some(e)),
+        })
+}
+
+fn write_all<W: Write>(writer: &mut W, data: &[u8]) -> Result<()> {
+    writer.write_all(data)
+        .map_err(|e| ProofError::file_error("Failed to write proof bundle", Some(e)))
+}
+
+fn bundle_error(message: impl Into<String>) -> ProofError {
+    ProofError::invalid_input_error("bundle", message)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::proof::ProofGenerator;
+    use crate::verifier::tests::create_test_proof;
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let mut bundle = ProofBundle::new(create_test_proof(), b"car bytes".to_vec());
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        bundle.sign(&key).unwrap();
+
+        let mut encoded = Vec::new();
+        bundle.write(&mut encoded).unwrap();
+        assert!(encoded.starts_with(BUNDLE_MAGIC));
+
+        let read = ProofBundle::read(&encoded[..]).unwrap();
+        assert_eq!(read.proof.id, bundle.proof.id);
+        assert_eq!(read.car, bundle.car);
+        assert_eq!(read.signer(), Some(key.verifying_key()));
+
+        // Flipping a byte of the CAR breaks the manifest digest
+        let car_start = encoded.windows(9).position(|window| window == b"car bytes").unwrap();
+        encoded[car_start] ^= 1;
+        assert!(ProofBundle::read(&encoded[..]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generate_and_verify_from_bundle() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"a file with a secret inside").unwrap();
+        let config = crate::ProofConfig { use_cache: false, ..Default::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.generate_proof(
+            file.path(),
+            crate::ContentSelection::Pattern { content: b"secret".to_vec() },
+        ).await.unwrap();
+
+        let mut car = Vec::new();
+        generator.export_car(&proof, file.path(), &mut car).await.unwrap();
+        let mut encoded = Vec::new();
+        ProofBundle::new(proof, car).write(&mut encoded).unwrap();
+        drop(file);
+
+        let bundle = ProofBundle::read(&encoded[..]).unwrap();
+        let blocks = ProofGenerator::import_car(&bundle.car[..]).unwrap();
+        assert!(generator.verify_proof_with_blocks(&bundle.proof, &blocks, b"secret").await.unwrap());
+    }
+}
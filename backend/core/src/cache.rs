@@ -82,6 +82,42 @@ pub struct CacheStatistics {
     pub avg_entry_size_bytes: u64,
 }
 
+/// Contents of the disk cache, from [`CacheManager::inspect`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheReport {
+    /// Directory holding the disk cache
+    pub directory: PathBuf,
+    /// Each `.cache` file, ordered by key
+    pub entries: Vec<CacheFileReport>,
+    /// Size of all cache files on disk
+    pub total_bytes: u64,
+    /// Files that could not be read back as cache entries
+    pub corrupt_entries: usize,
+    /// Entries older than the configured TTL
+    pub expired_entries: usize,
+    /// Entries currently held in memory
+    pub memory_entries: usize,
+}
+
+/// One disk cache file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheFileReport {
+    /// Cache key the file is stored under
+    pub key: String,
+    /// Size of the file on disk
+    pub file_bytes: u64,
+    /// Cached data size the entry records, absent for corrupt files
+    pub data_bytes: Option<u64>,
+    /// Seconds since the entry was created
+    pub age_seconds: Option<u64>,
+    /// Times the entry was read
+    pub access_count: Option<u64>,
+    /// Whether the entry is older than the configured TTL
+    pub expired: bool,
+    /// Why the file is corrupt, if it is
+    pub problem: Option<String>,
+}
+
 /// Types of cacheable data
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum CacheKey {
@@ -92,7 +128,9 @@ pub enum CacheKey {
     /// Content selection result
     ContentSelection(String), // Selection hash
     /// Verification result
-    Verification(String), // Proof ID
+    Verification(String), // Proof ID, claimed content hash and proof/config digest
+    /// Guest input persisted before proving
+    Checkpoint(String), // File hash, selection and config digest
 }
 
 impl CacheManager {
@@ -104,8 +142,13 @@ impl CacheManager {
 
     /// Creates a new cache manager with custom configuration
     pub async fn with_config(config: CacheConfig) -> Result<Self> {
-        let cache_dir = std::env::temp_dir().join("zkipfs-proof-cache");
-        
+        Self::with_dir(config, std::env::temp_dir().join("zkipfs-proof-cache")).await
+    }
+
+    /// Creates a cache manager keeping its disk cache in `cache_dir`
+    pub async fn with_dir(config: CacheConfig, cache_dir: impl Into<PathBuf>) -> Result<Self> {
+        let cache_dir = cache_dir.into();
+
         // Create cache directory if it doesn't exist
         if config.disk_cache_enabled {
             fs::create_dir_all(&cache_dir).await?;
@@ -187,6 +230,14 @@ impl CacheManager {
 
         // Check memory cache first
         if self.config.memory_cache_enabled {
+            let expired = self.memory_cache.get(&key_str)
+                .is_some_and(|entry| self.is_entry_expired(entry));
+            if expired {
+                if let Some(entry) = self.memory_cache.remove(&key_str) {
+                    self.stats.memory_cache_bytes -= entry.size_bytes;
+                }
+            }
+
             if let Some(entry) = self.memory_cache.get_mut(&key_str) {
                 // Update access statistics
                 entry.last_accessed = SystemTime::now();
@@ -340,6 +391,90 @@ impl CacheManager {
         Ok(removed_count)
     }
 
+    /// Lists the disk cache's entries with their sizes and ages, flagging
+    /// files that cannot be read back as cache entries
+    pub async fn inspect(&self) -> Result<CacheReport> {
+        let mut report = CacheReport {
+            directory: self.disk_cache_dir.clone(),
+            entries: Vec::new(),
+            total_bytes: 0,
+            corrupt_entries: 0,
+            expired_entries: 0,
+            memory_entries: self.memory_cache.len(),
+        };
+        if !self.disk_cache_dir.exists() {
+            return Ok(report);
+        }
+
+        let mut files = fs::read_dir(&self.disk_cache_dir).await?;
+        while let Some(file) = files.next_entry().await? {
+            let path = file.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("cache") {
+                continue;
+            }
+            let Some(key) = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()) else {
+                continue;
+            };
+
+            let file_bytes = file.metadata().await?.len();
+            let (entry, problem) = match self.read_entry(&path).await {
+                Ok(entry) => (Some(entry), None),
+                Err(problem) => (None, Some(problem)),
+            };
+            let age_seconds = entry.as_ref()
+                .and_then(|entry| entry.created_at.elapsed().ok())
+                .map(|age| age.as_secs());
+            let expired = entry.as_ref().is_some_and(|entry| self.is_entry_expired(entry));
+
+            report.total_bytes += file_bytes;
+            report.corrupt_entries += usize::from(problem.is_some());
+            report.expired_entries += usize::from(expired);
+            report.entries.push(CacheFileReport {
+                key,
+                file_bytes,
+                data_bytes: entry.as_ref().map(|entry| entry.size_bytes),
+                age_seconds,
+                access_count: entry.as_ref().map(|entry| entry.access_count),
+                expired,
+                problem,
+            });
+        }
+
+        report.entries.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(report)
+    }
+
+    /// Removes corrupt disk cache files and recomputes the statistics from
+    /// what remains, returning the number of files removed
+    pub async fn repair(&mut self) -> Result<u64> {
+        let report = self.inspect().await?;
+        let mut removed = 0;
+        for entry in report.entries.iter().filter(|entry| entry.problem.is_some()) {
+            let path = self.disk_cache_dir.join(format!("{}.cache", entry.key));
+            fs::remove_file(&path).await?;
+            warn!("Removed corrupt cache file {}: {}", path.display(), entry.problem.as_deref().unwrap_or_default());
+            removed += 1;
+        }
+
+        let disk: HashMap<&str, &CacheFileReport> = report.entries.iter()
+            .filter(|entry| entry.problem.is_none())
+            .map(|entry| (entry.key.as_str(), entry))
+            .collect();
+        let memory_only = self.memory_cache.iter().filter(|(key, _)| !disk.contains_key(key.as_str()));
+
+        self.stats.disk_cache_bytes = disk.values().map(|entry| entry.file_bytes).sum();
+        self.stats.memory_cache_bytes = self.memory_cache.values().map(|entry| entry.size_bytes).sum();
+        self.stats.total_bytes_cached = disk.values().filter_map(|entry| entry.data_bytes).sum::<u64>()
+            + memory_only.clone().map(|(_, entry)| entry.size_bytes).sum::<u64>();
+        self.stats.total_entries = (disk.len() + memory_only.count()) as u64;
+        self.update_cache_stats();
+
+        if removed > 0 {
+            info!("Cache repair removed {} corrupt entries", removed);
+        }
+        Ok(removed)
+    }
+
     /// Gets cache statistics
     pub fn get_statistics(&self) -> &CacheStatistics {
         &self.stats
@@ -358,6 +493,7 @@ impl CacheManager {
             CacheKey::Proof(hash) => format!("proof_{}", hash),
             CacheKey::ContentSelection(hash) => format!("content_selection_{}", hash),
             CacheKey::Verification(id) => format!("verification_{}", id),
+            CacheKey::Checkpoint(hash) => format!("checkpoint_{}", hash),
         }
     }
 
@@ -451,6 +587,24 @@ impl CacheManager {
         Ok(Some(entry))
     }
 
+    /// Reads a disk cache file, describing why it is unusable if it is
+    async fn read_entry(&self, path: &Path) -> std::result::Result<CacheEntry, String> {
+        let data = fs::read(path).await.map_err(|e| format!("unreadable: {}", e))?;
+        let entry: CacheEntry = bincode::deserialize(&data)
+            .map_err(|e| format!("not a cache entry: {}", e))?;
+        if entry.size_bytes != entry.data.len() as u64 {
+            return Err(format!(
+                "records {} bytes of data but holds {}",
+                entry.size_bytes,
+                entry.data.len()
+            ));
+        }
+        if entry.compressed {
+            self.decompress_data(&entry.data).map_err(|e| format!("data does not decompress: {}", e))?;
+        }
+        Ok(entry)
+    }
+
     /// Removes entry from disk cache
     async fn remove_disk_cache(&mut self, key: &str) -> Result<bool> {
         let file_path = self.disk_cache_dir.join(format!("{}.cache", key));
@@ -605,5 +759,37 @@ mod tests {
         assert_eq!(stats.hits, 0);
         assert_eq!(stats.misses, 1);
     }
+
+    #[tokio::test]
+    async fn test_repair_removes_corrupt_file() {
+        let dir = TempDir::new().unwrap();
+        let mut manager = CacheManager::with_dir(CacheConfig::default(), dir.path()).await.unwrap();
+        manager.store(CacheKey::Proof("a".to_string()), &vec![1u8; 100]).await.unwrap();
+        manager.store(CacheKey::Proof("b".to_string()), &vec![2u8; 200]).await.unwrap();
+        let valid_bytes: u64 = ["proof_a.cache", "proof_b.cache"].iter()
+            .map(|name| std::fs::metadata(dir.path().join(name)).unwrap().len())
+            .sum();
+        std::fs::write(dir.path().join("proof_c.cache"), b"not a cache entry").unwrap();
+
+        let mut manager = CacheManager::with_dir(CacheConfig::default(), dir.path()).await.unwrap();
+        assert_eq!(manager.get_statistics().disk_cache_bytes, valid_bytes + 17);
+
+        let report = manager.inspect().await.unwrap();
+        assert_eq!(report.entries.len(), 3);
+        assert_eq!(report.corrupt_entries, 1);
+        assert_eq!(report.entries[2].key, "proof_c");
+        assert!(report.entries[2].problem.is_some());
+        assert!(report.entries[..2].iter().all(|entry| entry.problem.is_none() && entry.age_seconds.is_some()));
+
+        assert_eq!(manager.repair().await.unwrap(), 1);
+        assert!(!dir.path().join("proof_c.cache").exists());
+        let stats = manager.get_statistics();
+        assert_eq!(stats.disk_cache_bytes, valid_bytes);
+        assert_eq!(stats.total_entries, 2);
+        assert_eq!(manager.inspect().await.unwrap().corrupt_entries, 0);
+
+        let retrieved: Option<Vec<u8>> = manager.retrieve(&CacheKey::Proof("b".to_string())).await.unwrap();
+        assert_eq!(retrieved, Some(vec![2u8; 200]));
+    }
 }
 
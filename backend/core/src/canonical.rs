@@ -0,0 +1,113 @@
+//! Canonical form of a proof
+//!
+//! Two machines proving the same file and selection produce proofs that differ
+//! in their ID, timestamps, performance metrics, environment and receipt seal,
+//! even though they make the same claim. [`CanonicalProof`] keeps only the
+//! security-relevant fields, so its bytes are identical across such runs.
+//!
+//! Proofs generated with `ProofConfig::reproducible` are signed over their
+//! canonical bytes (see [`Proof::signing_bytes`]). The receipt is left out of the
+//! canonical form; its journal is still checked against the canonical root and
+//! content hashes when the proof is verified. The validity window, renewal
+//! chain and content commitment flag change how a proof is accepted, so they
+//! are kept; informational fields such as `metadata.custom` are not covered by
+//! the signature of a reproducible proof.
+
+use crate::{
+    error::{ProofError, Result},
+    proof_types::ProofType,
+    types::{ContentSelection, HashAlgorithm, Proof},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The fields of a proof that are the same on every machine proving the same content
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CanonicalProof {
+    /// Version of the proof format
+    pub version: String,
+    /// Receipt format version, which names the receipt kind
+    pub format_version: String,
+    /// Content selection that was proven
+    pub content_selection: ContentSelection,
+    /// Hash of the proven content
+    pub content_hash: [u8; 32],
+    /// Root hash of the IPFS structure
+    pub root_hash: [u8; 32],
+    /// Hash function the content hash was computed with
+    pub hash_algorithm: HashAlgorithm,
+    /// Security level in bits
+    pub security_level: u32,
+    /// ZK proof system used
+    pub proof_system: String,
+    /// SHA-256 of the whole file
+    pub file_hash: [u8; 32],
+    /// File size in bytes
+    pub file_size: u64,
+    /// IPFS CID of the file
+    pub ipfs_cid: String,
+    /// Number of IPFS blocks, which depends on the chunking strategy
+    pub block_count: u32,
+    /// Size of the proven content, as committed by the guest
+    pub content_size: u64,
+    /// Canonical hash of the proof this one extends
+    pub extends: Option<[u8; 32]>,
+    /// Kind of claim the proof makes; omitted for content existence proofs
+    #[serde(default, skip_serializing_if = "ProofType::is_default")]
+    pub proof_type: ProofType,
+    /// Image ID of the guest that produced the receipt, if recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_id: Option<[u32; 8]>,
+    /// Time after which the proof should no longer be accepted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<DateTime<Utc>>,
+    /// IDs of the proofs this one renews, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub renewed_from: Vec<String>,
+    /// Whether the content hash is a commitment opened separately
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub content_committed: bool,
+}
+
+impl Proof {
+    /// Returns the security-relevant portion of this proof
+    pub fn canonical(&self) -> Result<CanonicalProof> {
+        let extends = self.metadata.extends.as_ref()
+            .map(|extension| extension.prior.canonical_hash())
+            .transpose()?;
+
+        Ok(CanonicalProof {
+            version: self.version.clone(),
+            format_version: self.zk_proof.format_version.clone(),
+            content_selection: self.content_selection.clone(),
+            content_hash: self.content_hash,
+            root_hash: self.root_hash,
+            hash_algorithm: self.metadata.security.hash_algorithm,
+            security_level: self.metadata.security.security_level,
+            proof_system: self.metadata.security.proof_system.clone(),
+            file_hash: self.metadata.file_info.file_hash,
+            file_size: self.metadata.file_info.size.get(),
+            ipfs_cid: self.metadata.file_info.ipfs_cid.clone(),
+            block_count: self.metadata.file_info.block_count,
+            content_size: self.metadata.guest_metadata.content_size,
+            extends,
+            proof_type: self.proof_type.clone(),
+            image_id: self.image_id,
+            valid_until: self.metadata.valid_until,
+            renewed_from: self.metadata.renewed_from.clone(),
+            content_committed: self.metadata.content_committed,
+        })
+    }
+
+    /// Encodes the canonical form as JSON, with fields in declaration order
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(&self.canonical()?)
+            .map_err(|e| ProofError::serialization_error("Failed to encode canonical proof", Some(Box::new(e))))
+    }
+
+    /// SHA-256 of the canonical bytes, identifying the claim a proof makes
+    pub fn canonical_hash(&self) -> Result<[u8; 32]> {
+        Ok(Sha256::digest(self.canonical_bytes()?).into())
+    }
+}
@@ -0,0 +1,286 @@
+//! CARv1 archive support for zkIPFS-Proof
+//!
+//! Content-addressable archives (CAR) bundle IPFS blocks into a single stream.
+//! They are used to ship the blocks a proof depends on alongside the proof, so
+//! verification works without access to an IPFS node.
+
+use crate::error::{ProofError, Result};
+use cid::Cid;
+use multihash::{Code, MultihashDigest};
+use std::io::{Read, Write};
+
+/// CAR format version written and accepted by this module
+const CAR_VERSION: u64 = 1;
+
+/// CBOR tag used by DAG-CBOR for CIDs
+const CBOR_CID_TAG: u64 = 42;
+
+/// Upper bound for a single header or block section, to reject corrupt lengths
+const MAX_SECTION_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Multihash code for SHA2-256
+const SHA2_256_CODE: u64 = 0x12;
+
+/// Writes a CARv1 stream with the given roots and blocks
+pub fn write_car<W: Write>(mut writer: W, roots: &[Cid], blocks: &[(Cid, &[u8])]) -> Result<()> {
+    let header = encode_header(roots);
+    let mut buffer = Vec::with_capacity(header.len() + 10);
+    encode_varint(header.len() as u64, &mut buffer);
+    buffer.extend_from_slice(&header);
+    write_all(&mut writer, &buffer)?;
+
+    for (cid, data) in blocks {
+        let cid_bytes = cid.to_bytes();
+        let mut prefix = Vec::with_capacity(cid_bytes.len() + 10);
+        encode_varint((cid_bytes.len() + data.len()) as u64, &mut prefix);
+        prefix.extend_from_slice(&cid_bytes);
+        write_all(&mut writer, &prefix)?;
+        write_all(&mut writer, data)?;
+    }
+
+    writer.flush()
+        .map_err(|e| ProofError::file_error("Failed to flush CAR archive", Some(e)))
+}
+
+/// Reads a CARv1 stream, returning its roots and blocks in stream order.
+///
+/// Blocks hashed with SHA2-256 are checked against their CID.
+pub fn read_car<R: Read>(mut reader: R) -> Result<(Vec<Cid>, Vec<(Cid, Vec<u8>)>)> {
+    let header_len = read_varint(&mut reader)?
+        .ok_or_else(|| car_error("CAR archive is empty"))?;
+    let header = read_section(&mut reader, header_len)?;
+    let roots = decode_header(&header)?;
+
+    let mut blocks = Vec::new();
+    while let Some(section_len) = read_varint(&mut reader)? {
+        let section = read_section(&mut reader, section_len)?;
+        let mut cursor = std::io::Cursor::new(&section[..]);
+        let cid = Cid::read_bytes(&mut cursor)
+            .map_err(|e| ProofError::serialization_error(
+                "Invalid CID in CAR block section",
+                Some(Box::new(e))
+            ))?;
+        let data = section[cursor.position() as usize..].to_vec();
+
+        if cid.hash().code() == SHA2_256_CODE
+            && Code::Sha2_256.digest(&data).digest() != cid.hash().digest()
+        {
+            return Err(car_error(format!("Block data does not match its CID: {}", cid)));
+        }
+
+        blocks.push((cid, data));
+    }
+
+    Ok((roots, blocks))
+}
+
+/// Encodes the DAG-CBOR header `{"roots": [..], "version": 1}`
+fn encode_header(roots: &[Cid]) -> Vec<u8> {
+    let mut header = Vec::new();
+    encode_cbor_head(5, 2, &mut header);
+    encode_cbor_text("roots", &mut header);
+    encode_cbor_head(4, roots.len() as u64, &mut header);
+    for root in roots {
+        // DAG-CBOR CIDs are tagged byte strings with a leading multibase identity byte
+        let bytes = root.to_bytes();
+        encode_cbor_head(6, CBOR_CID_TAG, &mut header);
+        encode_cbor_head(2, bytes.len() as u64 + 1, &mut header);
+        header.push(0x00);
+        header.extend_from_slice(&bytes);
+    }
+    encode_cbor_text("version", &mut header);
+    encode_cbor_head(0, CAR_VERSION, &mut header);
+    header
+}
+
+/// Decodes the roots from a CARv1 header, checking its version
+fn decode_header(header: &[u8]) -> Result<Vec<Cid>> {
+    let mut decoder = CborDecoder { data: header, pos: 0 };
+    let entries = decoder.expect(5)?;
+
+    let mut roots = None;
+    let mut version = None;
+    for _ in 0..entries {
+        match decoder.text()?.as_str() {
+            "roots" => {
+                let count = decoder.expect(4)?;
+                let mut cids = Vec::new();
+                for _ in 0..count {
+                    if decoder.expect(6)? != CBOR_CID_TAG {
+                        return Err(car_error("CAR root is not a CID"));
+                    }
+                    let bytes = decoder.bytes()?;
+                    let cid = bytes.split_first()
+                        .filter(|(prefix, _)| **prefix == 0x00)
+                        .and_then(|(_, cid)| Cid::try_from(cid).ok())
+                        .ok_or_else(|| car_error("Invalid CID in CAR header"))?;
+                    cids.push(cid);
+                }
+                roots = Some(cids);
+            }
+            "version" => version = Some(decoder.expect(0)?),
+            other => return Err(car_error(format!("Unexpected CAR header field: {}", other))),
+        }
+    }
+
+    if version != Some(CAR_VERSION) {
+        return Err(car_error(format!("Unsupported CAR version: {:?}", version)));
+    }
+    roots.ok_or_else(|| car_error("CAR header has no roots"))
+}
+
+/// Minimal CBOR decoder covering the types used in CAR headers
+struct CborDecoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl CborDecoder<'_> {
+    /// Reads an item head of the expected major type, returning its argument
+    fn expect(&mut self, major: u8) -> Result<u64> {
+        let initial = self.take(1)?[0];
+        if initial >> 5 != major {
+            return Err(car_error("Unexpected CBOR type in CAR header"));
+        }
+        let argument = match initial & 0x1f {
+            n @ 0..=23 => n as u64,
+            24 => self.take(1)?[0] as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            _ => return Err(car_error("Indefinite-length CBOR is not allowed in CAR headers")),
+        };
+        Ok(argument)
+    }
+
+    fn bytes(&mut self) -> Result<&[u8]> {
+        let len = self.expect(2)? as usize;
+        self.take(len)
+    }
+
+    fn text(&mut self) -> Result<String> {
+        let len = self.expect(3)? as usize;
+        String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|e| ProofError::serialization_error("Invalid text in CAR header", Some(Box::new(e))))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8]> {
+        let end = self.pos.checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| car_error("Truncated CAR header"))?;
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+}
+
+fn encode_cbor_head(major: u8, argument: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    match argument {
+        0..=23 => out.push(major | argument as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, argument as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(argument as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(argument as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&argument.to_be_bytes());
+        }
+    }
+}
+
+fn encode_cbor_text(text: &str, out: &mut Vec<u8>) {
+    encode_cbor_head(3, text.len() as u64, out);
+    out.extend_from_slice(text.as_bytes());
+}
+
+fn encode_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Reads an unsigned varint, returning `None` on a clean end of stream
+fn read_varint<R: Read>(reader: &mut R) -> Result<Option<u64>> {
+    let mut value = 0u64;
+    for i in 0..10 {
+        let mut byte = [0u8; 1];
+        let read = reader.read(&mut byte)
+            .map_err(|e| ProofError::file_error("Failed to read CAR archive", Some(e)))?;
+        if read == 0 {
+            return if i == 0 {
+                Ok(None)
+            } else {
+                Err(car_error("Truncated varint in CAR archive"))
+            };
+        }
+        value |= ((byte[0] & 0x7f) as u64) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+    Err(car_error("Varint too long in CAR archive"))
+}
+
+fn read_section<R: Read>(reader: &mut R, len: u64) -> Result<Vec<u8>> {
+    if len > MAX_SECTION_SIZE {
+        return Err(car_error(format!("CAR section of {} bytes exceeds limit", len)));
+    }
+    let mut section = vec![0u8; len as usize];
+    reader.read_exact(&mut section)
+        .map_err(|e| ProofError::file_error("Truncated CAR archive", Some(e)))?;
+    Ok(section)
+}
+
+fn write_all<W: Write>(writer: &mut W, data: &[u8]) -> Result<()> {
+    writer.write_all(data)
+        .map_err(|e| ProofError::file_error("Failed to write CAR archive", Some(e)))
+}
+
+fn car_error(message: impl Into<String>) -> ProofError {
+    ProofError::serialization_error(message, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_cid(data: &[u8]) -> Cid {
+        Cid::new_v1(0x55, Code::Sha2_256.digest(data))
+    }
+
+    #[test]
+    fn test_car_round_trip() {
+        let first = b"first block".to_vec();
+        let second = b"second block".to_vec();
+        let blocks = [(raw_cid(&first), &first[..]), (raw_cid(&second), &second[..])];
+
+        let mut archive = Vec::new();
+        write_car(&mut archive, &[blocks[0].0], &blocks).unwrap();
+
+        let (roots, decoded) = read_car(&archive[..]).unwrap();
+        assert_eq!(roots, vec![raw_cid(&first)]);
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[1], (raw_cid(&second), second));
+    }
+
+    #[test]
+    fn test_car_rejects_tampered_block() {
+        let data = b"original".to_vec();
+        let cid = raw_cid(&data);
+
+        let mut archive = Vec::new();
+        write_car(&mut archive, &[cid], &[(cid, &data[..])]).unwrap();
+        let last = archive.len() - 1;
+        archive[last] ^= 0xff;
+
+        assert!(read_car(&archive[..]).is_err());
+    }
+}
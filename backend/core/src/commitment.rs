@@ -0,0 +1,74 @@
+//! Commitments to proven content, for selective disclosure
+//!
+//! A committed proof (see `ProofGenerator::generate_committed_proof`) reveals
+//! a commitment to the content hash in place of the content hash itself:
+//!
+//! ```text
+//! commitment = SHA-256(domain || blinding || content_hash)
+//! ```
+//!
+//! where `blinding` is 32 random bytes chosen by the prover and `content_hash`
+//! is the selection's hash of the content, as in an ordinary proof. The guest
+//! computes the commitment from the content it found, so the receipt proves
+//! that the file with the proof's root hash contains content satisfying the
+//! selection, and that the commitment opens to that content.
+//!
+//! # Trust model
+//!
+//! - `ProofVerifier::verify_commitment` checks the receipt and the commitment
+//!   without the content. The verifier learns the selection, the root hash, the
+//!   file information in the metadata and the content size, but nothing else
+//!   about the content.
+//! - The commitment hides the content only as long as the blinding stays
+//!   secret. Anyone holding a [`ContentOpening`] can check it against the proof
+//!   and learns the content; keep openings off channels the content should not
+//!   reach.
+//! - The commitment binds the prover to one content hash, so a proof cannot be
+//!   opened to other content without a SHA-256 collision.
+//! - Selections that embed the content, such as `Pattern`, disclose it
+//!   regardless. Commit to byte ranges, regular expressions or JSON paths
+//!   instead.
+
+use crate::types::Proof;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Prefix of the committed message, so commitments cannot be mistaken for
+/// other hashes; must match the guest
+pub const COMMITMENT_DOMAIN: &[u8] = b"zkipfs-proof/commitment/v1\0";
+
+/// Commits to `content_hash` under `blinding`, as the guest does
+pub fn commit_content_hash(content_hash: &[u8; 32], blinding: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(COMMITMENT_DOMAIN);
+    hasher.update(blinding);
+    hasher.update(content_hash);
+    hasher.finalize().into()
+}
+
+/// The secret that opens a committed proof: the blinding and the proven content
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ContentOpening {
+    /// Random bytes the commitment was made under
+    pub blinding: [u8; 32],
+    /// The content the commitment is to
+    #[serde(with = "serde_bytes")]
+    pub content: Vec<u8>,
+}
+
+impl ContentOpening {
+    /// The commitment this opening yields for `proof`'s selection and hash function
+    pub fn commitment(&self, proof: &Proof) -> [u8; 32] {
+        let content_hash = proof.content_selection
+            .content_hash(proof.metadata.security.hash_algorithm, &self.content);
+        commit_content_hash(&content_hash, &self.blinding)
+    }
+
+    /// Checks that this opening opens `proof`'s commitment
+    ///
+    /// This checks the opening only; the proof itself is checked by
+    /// `ProofVerifier::verify_commitment`.
+    pub fn opens(&self, proof: &Proof) -> bool {
+        proof.metadata.content_committed && self.commitment(proof) == proof.content_hash
+    }
+}
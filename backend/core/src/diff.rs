@@ -0,0 +1,122 @@
+//! Field-level comparison of proofs
+//!
+//! [`Proof::diff`] reports which of the fields that decide what a proof claims
+//! differ between two proofs: the hashes, the selection, the security
+//! parameters and the chunking-derived file information. Fields that differ
+//! between any two proof runs, such as the ID, timestamps and performance
+//! metrics, are not compared.
+
+use crate::types::Proof;
+use serde::{Deserialize, Serialize};
+
+/// One field that differs between two proofs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FieldDiff {
+    /// Dotted path of the field, such as `metadata.file_info.block_count`
+    pub field: String,
+    /// Value in the proof `diff` was called on
+    pub left: String,
+    /// Value in the other proof
+    pub right: String,
+}
+
+/// Differences between two proofs, in field order
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProofDiff {
+    pub differences: Vec<FieldDiff>,
+}
+
+impl ProofDiff {
+    /// Returns true if the proofs agree on every compared field
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Paths of the differing fields
+    pub fn fields(&self) -> Vec<&str> {
+        self.differences.iter().map(|diff| diff.field.as_str()).collect()
+    }
+
+    /// Returns the difference in `field`, if it differs
+    pub fn get(&self, field: &str) -> Option<&FieldDiff> {
+        self.differences.iter().find(|diff| diff.field == field)
+    }
+
+    fn compare<T: PartialEq>(&mut self, field: &str, left: &T, right: &T, show: impl Fn(&T) -> String) {
+        if left != right {
+            self.differences.push(FieldDiff {
+                field: field.to_string(),
+                left: show(left),
+                right: show(right),
+            });
+        }
+    }
+}
+
+impl Proof {
+    /// Compares the fields that decide what this proof and `other` claim
+    pub fn diff(&self, other: &Proof) -> ProofDiff {
+        let mut diff = ProofDiff::default();
+        let hash = |hash: &[u8; 32]| hex::encode(hash);
+        let text = |value: &String| value.clone();
+        let number = |value: &u64| value.to_string();
+
+        diff.compare("root_hash", &self.root_hash, &other.root_hash, hash);
+        diff.compare("content_hash", &self.content_hash, &other.content_hash, hash);
+
+        // Selections compare by their serialized form, which covers every field
+        let selection = |proof: &Proof| serde_json::to_string(&proof.content_selection).unwrap_or_default();
+        if selection(self) != selection(other) {
+            diff.differences.push(FieldDiff {
+                field: "content_selection".to_string(),
+                left: self.content_selection.description(),
+                right: other.content_selection.description(),
+            });
+        }
+
+        diff.compare("version", &self.version, &other.version, text);
+        diff.compare("proof_type", &self.proof_type, &other.proof_type, |proof_type| format!("{:?}", proof_type));
+        diff.compare("zk_proof.format_version", &self.zk_proof.format_version, &other.zk_proof.format_version, text);
+
+        let (left, right) = (&self.metadata.security, &other.metadata.security);
+        diff.compare("metadata.security.security_level", &left.security_level, &right.security_level, |level| level.to_string());
+        diff.compare("metadata.security.hash_algorithm", &left.hash_algorithm, &right.hash_algorithm, |algorithm| format!("{:?}", algorithm));
+        diff.compare("metadata.security.hash_function", &left.hash_function, &right.hash_function, text);
+        diff.compare("metadata.security.proof_system", &left.proof_system, &right.proof_system, text);
+        diff.compare("metadata.security.risc0_version", &left.risc0_version, &right.risc0_version, text);
+        diff.compare("metadata.security.formal_verification", &left.formal_verification, &right.formal_verification, |value| value.to_string());
+
+        let (left, right) = (&self.metadata.file_info, &other.metadata.file_info);
+        diff.compare("metadata.file_info.file_hash", &left.file_hash, &right.file_hash, hash);
+        diff.compare("metadata.file_info.size", &left.size.get(), &right.size.get(), number);
+        diff.compare("metadata.file_info.ipfs_cid", &left.ipfs_cid, &right.ipfs_cid, text);
+        diff.compare("metadata.file_info.block_count", &left.block_count, &right.block_count, |count| count.to_string());
+        diff.compare("metadata.file_info.avg_block_size", &left.avg_block_size.get(), &right.avg_block_size.get(), number);
+
+        let (left, right) = (&self.metadata.guest_metadata, &other.metadata.guest_metadata);
+        diff.compare("metadata.guest_metadata.block_count", &left.block_count, &right.block_count, |count| count.to_string());
+        diff.compare("metadata.guest_metadata.content_size", &left.content_size, &right.content_size, number);
+
+        diff
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::verifier::tests::create_test_proof;
+
+    #[test]
+    fn test_diff_flags_only_block_count() {
+        let left = create_test_proof();
+        let mut right = left.clone();
+        right.id = "another-run".to_string();
+        right.metadata.file_info.block_count = 4;
+
+        assert!(left.diff(&left).is_empty());
+
+        let diff = left.diff(&right);
+        assert_eq!(diff.fields(), vec!["metadata.file_info.block_count"]);
+        let block_count = diff.get("metadata.file_info.block_count").unwrap();
+        assert_eq!((block_count.left.as_str(), block_count.right.as_str()), ("1", "4"));
+    }
+}
@@ -2,8 +2,9 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::error::ZkIPFSError;
-use crate::types::{ProofResult, FileInfo};
+use crate::error::{ProofError, Result};
+use crate::proof_types::ProofResult;
+use crate::types::FileInfo;
 
 /// Integration with various ZK proof systems
 #[derive(Debug, Clone)]
@@ -28,8 +29,8 @@ pub enum ZkSystem {
 /// Adapter trait for different ZK systems
 pub trait ZkAdapter: Send + Sync {
     fn system_name(&self) -> &str;
-    fn generate_proof(&self, input: &ProofInput) -> Result<Vec<u8>, ZkIPFSError>;
-    fn verify_proof(&self, proof: &[u8], public_inputs: &[u8]) -> Result<bool, ZkIPFSError>;
+    fn generate_proof(&self, input: &ProofInput) -> Result<Vec<u8>>;
+    fn verify_proof(&self, proof: &[u8], public_inputs: &[u8]) -> Result<bool>;
     fn proof_size_estimate(&self, input_size: usize) -> usize;
     fn generation_time_estimate(&self, input_size: usize) -> std::time::Duration;
 }
@@ -54,13 +55,13 @@ impl ZkAdapter for Risc0Adapter {
         "Risc0"
     }
 
-    fn generate_proof(&self, input: &ProofInput) -> Result<Vec<u8>, ZkIPFSError> {
+    fn generate_proof(&self, input: &ProofInput) -> Result<Vec<u8>> {
         // Implementation would use Risc0 SDK
         // This is a placeholder for the actual implementation
         Ok(vec![0u8; 1024]) // Mock proof data
     }
 
-    fn verify_proof(&self, proof: &[u8], _public_inputs: &[u8]) -> Result<bool, ZkIPFSError> {
+    fn verify_proof(&self, proof: &[u8], _public_inputs: &[u8]) -> Result<bool> {
         // Implementation would use Risc0 verification
         Ok(!proof.is_empty())
     }
@@ -85,12 +86,12 @@ impl ZkAdapter for CircomAdapter {
         "Circom"
     }
 
-    fn generate_proof(&self, input: &ProofInput) -> Result<Vec<u8>, ZkIPFSError> {
+    fn generate_proof(&self, input: &ProofInput) -> Result<Vec<u8>> {
         // Implementation would use Circom toolchain
         Ok(vec![0u8; 256]) // Mock proof data
     }
 
-    fn verify_proof(&self, proof: &[u8], _public_inputs: &[u8]) -> Result<bool, ZkIPFSError> {
+    fn verify_proof(&self, proof: &[u8], _public_inputs: &[u8]) -> Result<bool> {
         Ok(!proof.is_empty())
     }
 
@@ -171,9 +172,9 @@ pub struct ContractInfo {
 
 /// Cross-chain proof verification
 pub trait CrossChainVerifier {
-    fn deploy_verifier(&self, chain: &BlockchainNetwork) -> Result<String, ZkIPFSError>;
-    fn verify_on_chain(&self, chain: &BlockchainNetwork, proof: &[u8]) -> Result<bool, ZkIPFSError>;
-    fn get_verification_cost(&self, chain: &BlockchainNetwork) -> Result<u64, ZkIPFSError>;
+    fn deploy_verifier(&self, chain: &BlockchainNetwork) -> Result<String>;
+    fn verify_on_chain(&self, chain: &BlockchainNetwork, proof: &[u8]) -> Result<bool>;
+    fn get_verification_cost(&self, chain: &BlockchainNetwork) -> Result<u64>;
 }
 
 /// Integration with decentralized storage networks
@@ -301,9 +302,9 @@ impl EcosystemManager {
         &self,
         system: &str,
         input: &ProofInput,
-    ) -> Result<Vec<u8>, ZkIPFSError> {
+    ) -> Result<Vec<u8>> {
         let adapter = self.zk_integration.adapters.get(system)
-            .ok_or_else(|| ZkIPFSError::UnsupportedSystem(system.to_string()))?;
+            .ok_or_else(|| unsupported_system(system))?;
         
         adapter.generate_proof(input)
     }
@@ -314,9 +315,9 @@ impl EcosystemManager {
         system: &str,
         proof: &[u8],
         public_inputs: &[u8],
-    ) -> Result<bool, ZkIPFSError> {
+    ) -> Result<bool> {
         let adapter = self.zk_integration.adapters.get(system)
-            .ok_or_else(|| ZkIPFSError::UnsupportedSystem(system.to_string()))?;
+            .ok_or_else(|| unsupported_system(system))?;
         
         adapter.verify_proof(proof, public_inputs)
     }
@@ -366,12 +367,11 @@ impl EcosystemManager {
         &self,
         proof: &ProofResult,
         replication_factor: Option<u32>,
-    ) -> Result<Vec<String>, ZkIPFSError> {
+    ) -> Result<Vec<String>> {
         let factor = replication_factor.unwrap_or(self.storage_integration.replication_factor);
         let mut storage_hashes = Vec::new();
 
-        let proof_data = serde_json::to_vec(proof)
-            .map_err(|e| ZkIPFSError::SerializationError(e.to_string()))?;
+        let proof_data = serde_json::to_vec(proof)?;
 
         for provider in self.storage_integration.storage_providers.iter().take(factor as usize) {
             match self.store_on_provider(provider, &proof_data).await {
@@ -381,7 +381,11 @@ impl EcosystemManager {
         }
 
         if storage_hashes.is_empty() {
-            return Err(ZkIPFSError::StorageError("Failed to store on any provider".to_string()));
+            return Err(ProofError::network_error(
+                "store_proof_distributed",
+                "Failed to store on any provider",
+                None
+            ));
         }
 
         Ok(storage_hashes)
@@ -391,13 +395,17 @@ impl EcosystemManager {
         &self,
         provider: &StorageProvider,
         data: &[u8],
-    ) -> Result<String, ZkIPFSError> {
+    ) -> Result<String> {
         // Implementation would depend on the specific storage provider
         // This is a placeholder
         Ok(format!("{}:{}", provider.name, hex::encode(&data[..8])))
     }
 }
 
+fn unsupported_system(system: &str) -> ProofError {
+    ProofError::invalid_input_error("system", format!("Unsupported ZK system: {}", system))
+}
+
 /// Requirements for ZK system selection
 #[derive(Debug, Clone)]
 pub struct ZkRequirements {
@@ -412,7 +420,7 @@ pub struct ZkRequirements {
 /// Plugin system for extending ecosystem integration
 pub trait EcosystemPlugin {
     fn name(&self) -> &str;
-    fn initialize(&mut self, manager: &mut EcosystemManager) -> Result<(), ZkIPFSError>;
+    fn initialize(&mut self, manager: &mut EcosystemManager) -> Result<()>;
     fn supported_operations(&self) -> Vec<String>;
 }
 
@@ -428,13 +436,13 @@ impl PluginRegistry {
         }
     }
 
-    pub fn register_plugin(&mut self, plugin: Box<dyn EcosystemPlugin>) -> Result<(), ZkIPFSError> {
+    pub fn register_plugin(&mut self, plugin: Box<dyn EcosystemPlugin>) -> Result<()> {
         let name = plugin.name().to_string();
         self.plugins.insert(name, plugin);
         Ok(())
     }
 
-    pub fn initialize_all(&mut self, manager: &mut EcosystemManager) -> Result<(), ZkIPFSError> {
+    pub fn initialize_all(&mut self, manager: &mut EcosystemManager) -> Result<()> {
         for plugin in self.plugins.values_mut() {
             plugin.initialize(manager)?;
         }
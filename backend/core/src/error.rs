@@ -29,6 +29,15 @@ pub enum ProofError {
         source: Option<Box<dyn std::error::Error + Send + Sync>>,
     },
 
+    /// IPFS failures that may succeed on retry: timeouts, 5xx responses and dropped connections
+    #[error("Transient IPFS failure: {operation} - {message}")]
+    IpfsTransient {
+        operation: String,
+        message: String,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+
     /// Errors related to zero-knowledge proof operations
     #[error("ZK proof operation failed: {operation} - {message}")]
     ZkProofError {
@@ -80,6 +89,13 @@ pub enum ProofError {
         source: Option<reqwest::Error>,
     },
 
+    /// A service rejected the credentials it was given
+    #[error("Authentication failed: {service} - {message}")]
+    AuthenticationError {
+        service: String,
+        message: String,
+    },
+
     /// Errors related to resource limits and constraints
     #[error("Resource limit exceeded: {resource} - {message}")]
     ResourceLimitError {
@@ -132,6 +148,19 @@ impl ProofError {
         }
     }
 
+    /// Creates a new transient IPFS error
+    pub fn ipfs_transient_error(
+        operation: impl Into<String>,
+        message: impl Into<String>,
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self::IpfsTransient {
+            operation: operation.into(),
+            message: message.into(),
+            source,
+        }
+    }
+
     /// Creates a new ZK proof error
     pub fn zk_proof_error(
         operation: impl Into<String>,
@@ -201,6 +230,14 @@ impl ProofError {
         }
     }
 
+    /// Creates a new authentication error
+    pub fn authentication_error(service: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::AuthenticationError {
+            service: service.into(),
+            message: message.into(),
+        }
+    }
+
     /// Creates a new resource limit error
     pub fn resource_limit_error(
         resource: impl Into<String>,
@@ -248,7 +285,7 @@ impl ProofError {
             self,
             ProofError::NetworkError { .. } | 
             ProofError::TimeoutError { .. } |
-            ProofError::IpfsError { .. }
+            ProofError::IpfsTransient { .. }
         )
     }
 
@@ -258,7 +295,8 @@ impl ProofError {
             self,
             ProofError::InvalidInputError { .. } |
             ProofError::ContentSelectionError { .. } |
-            ProofError::ConfigurationError { .. }
+            ProofError::ConfigurationError { .. } |
+            ProofError::AuthenticationError { .. }
         )
     }
 
@@ -323,6 +361,7 @@ impl From<hex::FromHexError> for ProofError {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl From<tokio::time::error::Elapsed> for ProofError {
     fn from(err: tokio::time::error::Elapsed) -> Self {
         ProofError::timeout_error("async operation", 0) // Duration not available from Elapsed
@@ -379,6 +418,9 @@ mod tests {
         let network_err = ProofError::network_error("GET", "timeout", None);
         assert!(network_err.is_retryable());
         assert!(!network_err.is_user_error());
+
+        assert!(ProofError::ipfs_transient_error("cat", "503 Service Unavailable", None).is_retryable());
+        assert!(!ProofError::ipfs_error("cat", "404 Not Found", None).is_retryable());
     }
 
     #[test]
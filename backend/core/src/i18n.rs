@@ -4,8 +4,11 @@
 //! including message translation, locale detection, and formatting.
 
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
 use serde::{Deserialize, Serialize};
+use tracing::warn;
+use crate::error::{ProofError, Result};
 
 /// Supported languages
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -212,6 +215,142 @@ impl TranslationCatalog {
     }
 }
 
+impl TranslationCatalog {
+    /// Parses a JSON catalog mapping each key to a message string, or to an
+    /// object with `singular` and optional `plural` and `context` fields
+    pub fn from_json(language: Language, source: &str) -> Result<Self> {
+        let entries: HashMap<String, serde_json::Value> = serde_json::from_str(source)
+            .map_err(|e| ProofError::serialization_error(
+                "Invalid JSON translation catalog",
+                Some(Box::new(e))
+            ))?;
+
+        let mut catalog = Self::new(language);
+        for (key, value) in entries {
+            let message = match value {
+                serde_json::Value::String(text) => TranslationMessage::simple(&text),
+                other => serde_json::from_value(other)
+                    .map_err(|e| ProofError::serialization_error(
+                        format!("Invalid translation for key '{}'", key),
+                        Some(Box::new(e))
+                    ))?,
+            };
+            catalog.messages.insert(key, message);
+        }
+        Ok(catalog)
+    }
+
+    /// Parses a Fluent (`.ftl`) catalog
+    ///
+    /// Covers the subset of Fluent used for this catalog: one message per
+    /// `id = value` line with indented continuation lines, `{ $name }`
+    /// placeables and a `{ $count -> [one] ... *[other] ... }` select for
+    /// plurals. Fluent IDs cannot contain dots, so `-` in an ID stands for `.`
+    /// in the key: `cli-file-not_found` is `cli.file.not_found`.
+    pub fn from_fluent(language: Language, source: &str) -> Result<Self> {
+        let mut catalog = Self::new(language);
+        let mut current: Option<(String, Vec<String>)> = None;
+
+        for (line_number, line) in source.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let is_continuation = line.starts_with([' ', '\t']) || trimmed == "}";
+            if is_continuation {
+                match current.as_mut() {
+                    Some((_, lines)) => lines.push(trimmed.to_string()),
+                    None => return Err(fluent_error(line_number, "indented line outside a message")),
+                }
+                continue;
+            }
+
+            if let Some((id, lines)) = current.take() {
+                catalog.add_fluent_message(&id, &lines);
+            }
+            if trimmed.starts_with('#') {
+                continue;
+            }
+
+            let (id, value) = trimmed.split_once('=')
+                .ok_or_else(|| fluent_error(line_number, "expected 'id = value'"))?;
+            let id = id.trim();
+            if id.is_empty() || id.starts_with('-') {
+                return Err(fluent_error(line_number, "expected a message ID"));
+            }
+            let value = value.trim();
+            let lines = if value.is_empty() { Vec::new() } else { vec![value.to_string()] };
+            current = Some((id.to_string(), lines));
+        }
+
+        if let Some((id, lines)) = current {
+            catalog.add_fluent_message(&id, &lines);
+        }
+        Ok(catalog)
+    }
+
+    fn add_fluent_message(&mut self, id: &str, lines: &[String]) {
+        let key = id.replace('-', ".");
+        let is_select = lines.first().map_or(false, |first| first.starts_with('{') && first.ends_with("->"));
+
+        let message = if is_select {
+            let mut one = None;
+            let mut other = None;
+            for line in &lines[1..] {
+                let (is_default, variant) = match line.strip_prefix('*') {
+                    Some(rest) => (true, rest),
+                    None => (false, line.as_str()),
+                };
+                let Some((name, text)) = variant.strip_prefix('[').and_then(|rest| rest.split_once(']')) else {
+                    continue;
+                };
+                let text = fluent_placeables(text.trim());
+                if name.trim() == "one" {
+                    one = Some(text.clone());
+                }
+                if is_default || name.trim() == "other" {
+                    other = Some(text);
+                }
+            }
+            match (one, other) {
+                (Some(one), Some(other)) => TranslationMessage::with_plural(&one, &other),
+                (Some(text), None) | (None, Some(text)) => TranslationMessage::simple(&text),
+                (None, None) => TranslationMessage::simple(""),
+            }
+        } else {
+            TranslationMessage::simple(&fluent_placeables(&lines.join("\n")))
+        };
+
+        self.messages.insert(key, message);
+    }
+}
+
+/// Converts Fluent `{ $name }` placeables to catalog `{name}` placeholders
+fn fluent_placeables(text: &str) -> String {
+    let placeable = regex::Regex::new(r"\{\s*\$([A-Za-z][A-Za-z0-9_-]*)\s*\}")
+        .expect("placeable pattern is valid");
+    placeable.replace_all(text, "{$1}").into_owned()
+}
+
+fn fluent_error(line_number: usize, message: &str) -> ProofError {
+    ProofError::serialization_error(
+        format!("Invalid Fluent catalog at line {}: {}", line_number + 1, message),
+        None
+    )
+}
+
+/// Result of loading a translation catalog from a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogReport {
+    /// Language of the loaded catalog
+    pub language: Language,
+    /// Keys of the English reference catalog that the catalog does not translate
+    pub missing_keys: Vec<String>,
+    /// Keys in the catalog that the English reference catalog does not have
+    pub extra_keys: Vec<String>,
+}
+
 /// Internationalization manager
 pub struct I18nManager {
     /// Current language
@@ -255,6 +394,102 @@ impl I18nManager {
         }
     }
 
+    /// Loads a JSON (`.json`) or Fluent (`.ftl`) catalog file
+    ///
+    /// The language is taken from the file name, such as `es.json` or
+    /// `pt_BR.ftl`. Keys are checked against the English reference catalog;
+    /// missing and extra keys are logged as warnings and reported, and missing
+    /// keys fall back to English when translating.
+    pub fn load_catalog_from_path(&self, path: &Path) -> Result<CatalogReport> {
+        let language = path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| Language::from_code(stem.split(['_', '-']).next().unwrap_or(stem)))
+            .ok_or_else(|| ProofError::invalid_input_error(
+                "catalog_path",
+                format!("Catalog file name is not a supported language code: {}", path.display())
+            ))?;
+
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| ProofError::file_error(
+                format!("Failed to read translation catalog: {}", path.display()),
+                Some(e)
+            ))?;
+
+        let catalog = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => TranslationCatalog::from_json(language, &source)?,
+            Some("ftl") => TranslationCatalog::from_fluent(language, &source)?,
+            _ => return Err(ProofError::invalid_input_error(
+                "catalog_path",
+                format!("Unsupported catalog format (expected .json or .ftl): {}", path.display())
+            )),
+        };
+
+        let report = self.check_catalog(&catalog);
+        if !report.missing_keys.is_empty() {
+            warn!(
+                "Catalog {} is missing {} keys: {}",
+                path.display(),
+                report.missing_keys.len(),
+                report.missing_keys.join(", ")
+            );
+        }
+        if !report.extra_keys.is_empty() {
+            warn!(
+                "Catalog {} has {} unknown keys: {}",
+                path.display(),
+                report.extra_keys.len(),
+                report.extra_keys.join(", ")
+            );
+        }
+
+        self.add_catalog(catalog);
+        Ok(report)
+    }
+
+    /// Loads every `.json` and `.ftl` catalog in a locale directory
+    pub fn load_catalogs_from_dir(&self, dir: &Path) -> Result<Vec<CatalogReport>> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| ProofError::file_error(
+                format!("Failed to read locale directory: {}", dir.display()),
+                Some(e)
+            ))?;
+
+        let mut paths = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|e| ProofError::file_error("Failed to read directory entry", Some(e)))?
+                .path();
+            let is_catalog = path.extension().map_or(false, |ext| ext == "json" || ext == "ftl");
+            if path.is_file() && is_catalog {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        paths.iter().map(|path| self.load_catalog_from_path(path)).collect()
+    }
+
+    /// Compares a catalog's keys with the English reference catalog
+    fn check_catalog(&self, catalog: &TranslationCatalog) -> CatalogReport {
+        let catalogs = self.catalogs.read().unwrap_or_else(|e| e.into_inner());
+        let reference = catalogs.get(&self.fallback_language);
+
+        let mut missing_keys: Vec<String> = reference
+            .map(|reference| reference.messages.keys()
+                .filter(|key| !catalog.messages.contains_key(*key))
+                .cloned()
+                .collect())
+            .unwrap_or_default();
+        let mut extra_keys: Vec<String> = catalog.messages.keys()
+            .filter(|key| reference.map_or(false, |reference| !reference.messages.contains_key(*key)))
+            .cloned()
+            .collect();
+        missing_keys.sort();
+        extra_keys.sort();
+
+        CatalogReport { language: catalog.language, missing_keys, extra_keys }
+    }
+
     /// Translate a message
     pub fn translate(&self, key: &str) -> String {
         self.translate_with_count(key, None)
@@ -380,8 +615,55 @@ impl I18nManager {
         // Pluralization examples
         catalog.add_plural_translation("files.count", "{count} file", "{count} files");
         catalog.add_plural_translation("proofs.generated", "{count} proof generated", "{count} proofs generated");
+
+        // Content selection descriptions
+        catalog.add_translation("selection.byte_range", "Bytes {start}-{end} ({len} bytes)");
+        catalog.add_translation("selection.pattern", "Pattern: {preview} ({len} bytes)");
+        catalog.add_translation("selection.pattern_hash", "Pattern hash: {hash}... ({len} bytes)");
+        catalog.add_translation("selection.regex", "Regex: {pattern}");
+        catalog.add_translation("selection.xpath", "XPath: {selector}");
+        catalog.add_translation("selection.json_path", "JSON path: {path}");
+        catalog.add_translation("selection.json_span", "JSON value at bytes {start}-{end} ({len} bytes)");
+        catalog.add_translation("selection.multi_range", "{count} byte ranges ({len} bytes)");
+        catalog.add_translation("selection.pdf_page", "PDF page {page}");
+        catalog.add_translation("selection.csv_cell", "CSV cell at row {row}, column {column}");
+        catalog.add_translation("selection.exif_field", "EXIF tag {tag}");
+        catalog.add_translation("selection.percent_range", "Bytes {start}%-{end}% of the file");
+        catalog.add_translation("selection.multiple", "Multiple selections ({count})");
+
+        // Proof explanations
+        catalog.add_translation(
+            "proof.explanation",
+            "This proof shows that {selection} of {file}, a {size} file (CID {cid}), contain content \
+             whose {hash_function} hash is {content_hash}; it was generated on {date} with \
+             {security_level}-bit security."
+        );
+        catalog.add_translation("proof.unnamed_file", "an unnamed file");
         
         self.add_catalog(catalog);
+
+        let mut german = TranslationCatalog::new(Language::German);
+        german.add_translation("selection.byte_range", "Bytes {start}-{end} ({len} Bytes)");
+        german.add_translation("selection.pattern", "Muster: {preview} ({len} Bytes)");
+        german.add_translation("selection.pattern_hash", "Muster-Hash: {hash}... ({len} Bytes)");
+        german.add_translation("selection.regex", "Regulärer Ausdruck: {pattern}");
+        german.add_translation("selection.xpath", "XPath: {selector}");
+        german.add_translation("selection.json_path", "JSON-Pfad: {path}");
+        german.add_translation("selection.json_span", "JSON-Wert in Bytes {start}-{end} ({len} Bytes)");
+        german.add_translation("selection.multi_range", "{count} Bytebereiche ({len} Bytes)");
+        german.add_translation("selection.pdf_page", "PDF-Seite {page}");
+        german.add_translation("selection.csv_cell", "CSV-Zelle in Zeile {row}, Spalte {column}");
+        german.add_translation("selection.exif_field", "EXIF-Tag {tag}");
+        german.add_translation("selection.percent_range", "Bytes {start}%-{end}% der Datei");
+        german.add_translation("selection.multiple", "Mehrere Auswahlen ({count})");
+        german.add_translation(
+            "proof.explanation",
+            "Dieser Nachweis zeigt, dass {selection} von {file}, einer Datei mit {size} (CID {cid}), \
+             Inhalt enthalten, dessen {hash_function}-Hash {content_hash} ist; er wurde am {date} \
+             mit {security_level}-Bit-Sicherheit erzeugt."
+        );
+        german.add_translation("proof.unnamed_file", "einer unbenannten Datei");
+        self.add_catalog(german);
     }
 }
 
@@ -392,24 +674,16 @@ impl Default for I18nManager {
 }
 
 /// Global i18n instance
-static mut GLOBAL_I18N: Option<I18nManager> = None;
-static INIT: std::sync::Once = std::sync::Once::new();
+static GLOBAL_I18N: OnceLock<I18nManager> = OnceLock::new();
 
 /// Initialize global i18n
 pub fn init_i18n() -> &'static I18nManager {
-    unsafe {
-        INIT.call_once(|| {
-            GLOBAL_I18N = Some(I18nManager::new());
-        });
-        GLOBAL_I18N.as_ref().unwrap()
-    }
+    GLOBAL_I18N.get_or_init(I18nManager::new)
 }
 
 /// Get global i18n instance
 pub fn get_i18n() -> &'static I18nManager {
-    unsafe {
-        GLOBAL_I18N.as_ref().expect("I18n not initialized. Call init_i18n() first.")
-    }
+    GLOBAL_I18N.get().expect("I18n not initialized. Call init_i18n() first.")
 }
 
 /// Convenience macro for translation
@@ -534,6 +808,76 @@ mod tests {
         assert_eq!(missing, "missing.key");
     }
 
+    #[test]
+    fn test_global_i18n_concurrent_access() {
+        let manager = init_i18n();
+        assert!(std::ptr::eq(manager, init_i18n()));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| std::thread::spawn(|| {
+                (0..100)
+                    .map(|_| get_i18n().translate("cli.generate.success"))
+                    .all(|message| message == "Proof generated successfully")
+            }))
+            .collect();
+
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+        assert_eq!(t!("files.count", 2), "{count} files");
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_load_spanish_catalog() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("es.json");
+        std::fs::write(&path, r#"{
+            "cli.generate.success": "Prueba generada correctamente",
+            "files.count": { "singular": "{count} archivo", "plural": "{count} archivos" },
+            "cli.unknown": "Clave desconocida"
+        }"#).unwrap();
+
+        let manager = I18nManager::new();
+        let report = manager.load_catalog_from_path(&path).unwrap();
+        assert_eq!(report.language, Language::Spanish);
+        assert!(report.missing_keys.contains(&"cli.verify.success".to_string()));
+        assert_eq!(report.extra_keys, vec!["cli.unknown".to_string()]);
+
+        manager.set_language(Language::Spanish);
+        assert_eq!(manager.translate("cli.generate.success"), "Prueba generada correctamente");
+        assert_eq!(manager.translate_with_count("files.count", Some(2)), "{count} archivos");
+        // Missing keys fall back to English
+        assert_eq!(manager.translate("cli.verify.success"), "Proof verified successfully");
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_load_fluent_catalog() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("fr.ftl"), "\
+# Messages de la CLI
+cli-generate-success = Preuve générée avec succès
+cli-file-not_found = Fichier introuvable : { $path }
+files-count = { $count ->
+    [one] {$count} fichier
+   *[other] {$count} fichiers
+}
+").unwrap();
+        std::fs::write(dir.path().join("README.md"), "not a catalog").unwrap();
+
+        let manager = I18nManager::new();
+        let reports = manager.load_catalogs_from_dir(dir.path()).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].extra_keys.is_empty());
+
+        manager.set_language(Language::French);
+        assert_eq!(manager.translate("cli.generate.success"), "Preuve générée avec succès");
+        assert_eq!(manager.translate("cli.file.not_found"), "Fichier introuvable : {path}");
+        assert_eq!(manager.translate_with_count("files.count", Some(1)), "{count} fichier");
+        assert_eq!(manager.translate_with_count("files.count", Some(3)), "{count} fichiers");
+    }
+
     #[test]
     fn test_locale_formatter() {
         let formatter = LocaleFormatter::new(Language::English);
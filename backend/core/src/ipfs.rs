@@ -5,37 +5,161 @@
 
 use crate::{
     error::{ProofError, Result, ResultExt},
-    types::{IpfsBlock, BlockLink, FileInfo},
+    types::{IpfsBlock, BlockLink, FileInfo, Bytes, ChunkingStrategy, CompressionType, ADAPTIVE_MIN_BLOCK_SIZE},
 };
+use memmap2::Mmap;
+use rayon::prelude::*;
 use sha2::{Digest, Sha256};
+use std::ops::Deref;
 use std::path::Path;
 use tokio::fs;
 use tracing::{debug, instrument};
 use cid::{Cid, Version};
 use multihash::{Code, MultihashDigest};
 
+/// Size of the sliding window hashed by the Rabin chunker
+const RABIN_WINDOW_SIZE: usize = 48;
+
+/// Multiplier of the Rabin rolling fingerprint; the value of go-ipfs's
+/// polynomial is reused, but the fingerprint is a plain polynomial hash
+/// rather than its GF(2) reduction, so the boundaries are not go-ipfs's
+const RABIN_BASE: u64 = 0x3DA3_358B_4DC1_73;
+
 /// IPFS processor for converting files to IPFS block structures
 pub struct IpfsProcessor {
-    /// Maximum block size for IPFS blocks (default: 256KB)
-    max_block_size: usize,
+    /// How content is split into blocks (default: fixed 256KB)
+    chunking: ChunkingStrategy,
     /// Chunk size for reading large files
     chunk_size: usize,
+    /// Files at least this large are memory-mapped (`None` disables mapping)
+    mmap_threshold: Option<u64>,
+    /// Threads that hash blocks (`None` uses rayon's global pool)
+    hashing_pool: Option<rayon::ThreadPool>,
+    /// Codec files are decompressed with as they are read (`None` reads them as stored)
+    decompression: Option<CompressionType>,
+    /// Largest size a file may decompress to (`None` leaves it unbounded)
+    max_decompressed_size: Option<u64>,
+}
+
+/// Content of a file being processed, either mapped or read into memory
+enum FileContent {
+    Mapped(Mmap),
+    Buffered(Vec<u8>),
+}
+
+impl Deref for FileContent {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileContent::Mapped(map) => map,
+            FileContent::Buffered(data) => data,
+        }
+    }
 }
 
 impl IpfsProcessor {
     /// Creates a new IPFS processor with default settings
     pub async fn new() -> Result<Self> {
-        Ok(Self {
-            max_block_size: 256 * 1024, // 256KB
+        Self::with_chunking(ChunkingStrategy::default()).await
+    }
+
+    /// Creates a new IPFS processor using the given chunking strategy
+    pub async fn with_chunking(chunking: ChunkingStrategy) -> Result<Self> {
+        let mut processor = Self {
+            chunking: ChunkingStrategy::default(),
             chunk_size: 64 * 1024,      // 64KB
-        })
+            mmap_threshold: None,
+            hashing_pool: None,
+            decompression: None,
+            max_decompressed_size: None,
+        };
+        processor.set_chunking(chunking)?;
+        Ok(processor)
+    }
+
+    /// Changes the chunking strategy used for subsequently processed files
+    pub fn set_chunking(&mut self, chunking: ChunkingStrategy) -> Result<()> {
+        if !chunking.is_valid() {
+            return Err(ProofError::configuration_error(
+                format!("Invalid chunking strategy: {:?}", chunking)
+            ));
+        }
+        self.chunking = chunking;
+        Ok(())
+    }
+
+    /// Returns the chunking strategy in use
+    pub fn chunking(&self) -> &ChunkingStrategy {
+        &self.chunking
+    }
+
+    /// Sets the size from which files are memory-mapped instead of read (`None` disables mapping)
+    pub fn set_mmap_threshold(&mut self, threshold: Option<Bytes>) {
+        self.mmap_threshold = threshold.map(Bytes::get);
+    }
+
+    /// Sets the codec files are decompressed with before chunking (`None` chunks the stored bytes)
+    pub fn set_decompression(&mut self, codec: Option<CompressionType>) {
+        self.decompression = codec.filter(|codec| *codec != CompressionType::None);
+    }
+
+    /// Sets the largest size a file may decompress to (`None` leaves it unbounded)
+    pub fn set_max_decompressed_size(&mut self, max: Option<Bytes>) {
+        self.max_decompressed_size = max.map(Bytes::get);
+    }
+
+    /// Sets how many threads hash blocks (`None` uses one per core, `Some(1)` hashes sequentially)
+    pub fn set_worker_threads(&mut self, threads: Option<usize>) -> Result<()> {
+        self.hashing_pool = match threads {
+            Some(0) => {
+                return Err(ProofError::configuration_error("Worker thread count must be at least 1"));
+            }
+            Some(threads) => Some(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .thread_name(|i| format!("zkipfs-hash-{}", i))
+                    .build()
+                    .map_err(|e| ProofError::configuration_error(
+                        format!("Failed to start block hashing threads: {}", e)
+                    ))?,
+            ),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Returns how many threads hash blocks
+    pub fn worker_threads(&self) -> usize {
+        self.hashing_pool.as_ref()
+            .map_or_else(rayon::current_num_threads, rayon::ThreadPool::current_num_threads)
+    }
+
+    /// Resolves adaptive chunking to the fixed block size picked for `len` bytes
+    fn resolve_chunking(chunking: &ChunkingStrategy, len: usize) -> ChunkingStrategy {
+        match chunking {
+            ChunkingStrategy::Adaptive => ChunkingStrategy::FixedSize(
+                ChunkingStrategy::adaptive_block_size(len as u64)
+            ),
+            other => other.clone(),
+        }
     }
 
     /// Processes a file into IPFS blocks and returns file information
-    #[instrument(skip(self), fields(file = %file_path.display()))]
     pub async fn process_file(
         &self,
         file_path: &Path,
+    ) -> Result<(Vec<IpfsBlock>, FileInfo)> {
+        self.process_file_with(file_path, &self.chunking).await
+    }
+
+    /// Processes a file into IPFS blocks under the given chunking strategy
+    /// instead of the processor's own
+    #[instrument(skip(self), fields(file = %file_path.display()))]
+    pub async fn process_file_with(
+        &self,
+        file_path: &Path,
+        chunking: &ChunkingStrategy,
     ) -> Result<(Vec<IpfsBlock>, FileInfo)> {
         debug!("Processing file into IPFS blocks: {}", file_path.display());
 
@@ -43,17 +167,20 @@ impl IpfsProcessor {
         let metadata = fs::metadata(file_path).await
             .context("Failed to read file metadata")?;
         
-        let file_size = metadata.len();
-        
-        // Read file content
-        let content = fs::read(file_path).await
-            .context("Failed to read file content")?;
+        // Map or read file content; a decompressed file's size is that of its content
+        let content = self.read_content(file_path, metadata.len()).await?;
+        let file_size = content.len() as u64;
         
         // Calculate file hash
         let file_hash = Sha256::digest(&content);
         
-        // Split content into blocks
-        let blocks = self.create_blocks(&content)?;
+        // Split content into blocks, recording the block size adaptive chunking picked
+        let resolved = Self::resolve_chunking(chunking, content.len());
+        let chunk_size = match (chunking, &resolved) {
+            (ChunkingStrategy::Adaptive, ChunkingStrategy::FixedSize(size)) => Some(Bytes(*size as u64)),
+            _ => None,
+        };
+        let blocks = self.create_blocks_with(&content, &resolved)?;
         
         // Calculate IPFS CID for the entire file
         let file_cid = self.calculate_file_cid(&content)?;
@@ -72,12 +199,14 @@ impl IpfsProcessor {
             filename: file_path.file_name()
                 .and_then(|n| n.to_str())
                 .map(|s| s.to_string()),
-            size: file_size,
+            size: Bytes(file_size),
             mime_type,
             file_hash: file_hash.into(),
             ipfs_cid: file_cid.to_string(),
             block_count: blocks.len() as u32,
-            avg_block_size,
+            avg_block_size: Bytes(avg_block_size),
+            source_compression: self.decompression.clone(),
+            chunk_size,
         };
         
         debug!(
@@ -89,28 +218,74 @@ impl IpfsProcessor {
         Ok((blocks, file_info))
     }
 
+    /// Memory-maps files above the threshold, falling back to reading them into memory
+    ///
+    /// Files are decompressed instead when a codec is set.
+    async fn read_content(&self, file_path: &Path, file_size: u64) -> Result<FileContent> {
+        if let Some(codec) = self.decompression.clone() {
+            let path = file_path.to_path_buf();
+            let max_size = self.max_decompressed_size;
+            let content = tokio::task::spawn_blocking(move || decompress_file(&path, &codec, max_size))
+                .await
+                .map_err(|e| ProofError::internal_error("Decompression task failed", Some(Box::new(e))))??;
+            debug!("Decompressed {} bytes into {} bytes", file_size, content.len());
+            return Ok(FileContent::Buffered(content));
+        }
+
+        if self.mmap_threshold.is_some_and(|threshold| file_size >= threshold) {
+            let mapped = std::fs::File::open(file_path).and_then(|file| {
+                // SAFETY: the mapping is read-only and dropped before `process_file`
+                // returns. If another process truncates the file meanwhile, reads
+                // may fault, as with any mmap of a shared file.
+                unsafe { Mmap::map(&file) }
+            });
+            match mapped {
+                Ok(map) => {
+                    debug!("Memory-mapped {} bytes", map.len());
+                    return Ok(FileContent::Mapped(map));
+                }
+                Err(e) => debug!("Memory mapping failed, reading file instead: {}", e),
+            }
+        }
+
+        let data = fs::read(file_path).await
+            .context("Failed to read file content")?;
+        Ok(FileContent::Buffered(data))
+    }
+
     /// Creates IPFS blocks from file content
-    fn create_blocks(&self, content: &[u8]) -> Result<Vec<IpfsBlock>> {
-        let mut blocks = Vec::new();
+    ///
+    /// Block boundaries are found sequentially, since Rabin boundaries depend on
+    /// the previous one; the blocks are then hashed in parallel, in order.
+    pub(crate) fn create_blocks(&self, content: &[u8]) -> Result<Vec<IpfsBlock>> {
+        self.create_blocks_with(content, &self.chunking)
+    }
+
+    /// Creates IPFS blocks from file content under the given chunking strategy
+    pub(crate) fn create_blocks_with(&self, content: &[u8], chunking: &ChunkingStrategy) -> Result<Vec<IpfsBlock>> {
+        let chunking = Self::resolve_chunking(chunking, content.len());
+        let mut ranges = Vec::new();
         let mut offset = 0;
         
         while offset < content.len() {
-            let end = std::cmp::min(offset + self.max_block_size, content.len());
-            let block_data = &content[offset..end];
-            
-            // Create block CID
-            let cid = self.calculate_block_cid(block_data)?;
-            
-            // Create block with no links for now (simple chunking)
-            let block = IpfsBlock {
-                data: block_data.to_vec(),
-                cid: cid.to_bytes(),
-                links: Vec::new(),
-            };
-            
-            blocks.push(block);
+            let end = Self::next_block_end(&chunking, content, offset);
+            ranges.push(offset..end);
             offset = end;
         }
+
+        let mut blocks = if self.worker_threads() > 1 && ranges.len() > 1 {
+            let hash_all = || ranges.par_iter()
+                .map(|range| self.create_content_block(&content[range.clone()]))
+                .collect::<Result<Vec<_>>>();
+            match &self.hashing_pool {
+                Some(pool) => pool.install(hash_all)?,
+                None => hash_all()?,
+            }
+        } else {
+            ranges.iter()
+                .map(|range| self.create_content_block(&content[range.clone()]))
+                .collect::<Result<Vec<_>>>()?
+        };
         
         // If we have multiple blocks, create a root block that links to all chunks
         if blocks.len() > 1 {
@@ -121,6 +296,59 @@ impl IpfsProcessor {
         Ok(blocks)
     }
 
+    /// Creates a leaf block holding `data`
+    fn create_content_block(&self, data: &[u8]) -> Result<IpfsBlock> {
+        let cid = self.calculate_block_cid(data)?;
+
+        // Create block with no links for now (simple chunking)
+        Ok(IpfsBlock {
+            data: data.to_vec(),
+            cid: cid.to_bytes(),
+            links: Vec::new(),
+        })
+    }
+
+    /// Finds where the block starting at `offset` ends under the chunking strategy
+    fn next_block_end(chunking: &ChunkingStrategy, content: &[u8], offset: usize) -> usize {
+        match *chunking {
+            ChunkingStrategy::FixedSize(size) => {
+                std::cmp::min(offset.saturating_add(size), content.len())
+            }
+            // Resolved to a fixed size before blocks are cut
+            ChunkingStrategy::Adaptive => {
+                std::cmp::min(offset.saturating_add(ADAPTIVE_MIN_BLOCK_SIZE), content.len())
+            }
+            ChunkingStrategy::Rabin { min, avg, max } => {
+                let end = std::cmp::min(offset.saturating_add(max), content.len());
+                let scan_start = offset.saturating_add(min);
+                if scan_start >= end {
+                    return end;
+                }
+
+                let window_start = scan_start.saturating_sub(RABIN_WINDOW_SIZE).max(offset);
+                let mut fingerprint = 0u64;
+                for &byte in &content[window_start..scan_start] {
+                    fingerprint = fingerprint.wrapping_mul(RABIN_BASE).wrapping_add(byte as u64);
+                }
+                let out_factor = RABIN_BASE.wrapping_pow(RABIN_WINDOW_SIZE as u32);
+                let avg = avg as u64;
+
+                for pos in scan_start..end {
+                    fingerprint = fingerprint.wrapping_mul(RABIN_BASE).wrapping_add(content[pos] as u64);
+                    if pos >= window_start + RABIN_WINDOW_SIZE {
+                        let outgoing = content[pos - RABIN_WINDOW_SIZE] as u64;
+                        fingerprint = fingerprint.wrapping_sub(outgoing.wrapping_mul(out_factor));
+                    }
+                    if fingerprint % avg == avg - 1 {
+                        return pos + 1;
+                    }
+                }
+
+                end
+            }
+        }
+    }
+
     /// Creates a root block that links to all content blocks
     fn create_root_block(&self, content_blocks: &[IpfsBlock]) -> Result<IpfsBlock> {
         let mut links = Vec::new();
@@ -148,6 +376,107 @@ impl IpfsProcessor {
         })
     }
 
+    /// Decodes the links stored in a root block's data by `create_root_block`
+    pub fn decode_root_links(data: &[u8]) -> Result<Vec<BlockLink>> {
+        let mut cursor = std::io::Cursor::new(data);
+        let mut links = Vec::new();
+
+        while (cursor.position() as usize) < data.len() {
+            let cid = Cid::read_bytes(&mut cursor)
+                .map_err(|e| ProofError::ipfs_error(
+                    "root_block_decoding",
+                    format!("Invalid CID in root block link {}: {}", links.len(), e),
+                    Some(Box::new(e)),
+                ))?;
+            let mut size = [0u8; 8];
+            std::io::Read::read_exact(&mut cursor, &mut size)
+                .map_err(|e| ProofError::ipfs_error(
+                    "root_block_decoding",
+                    format!("Truncated size in root block link {}", links.len()),
+                    Some(Box::new(e)),
+                ))?;
+
+            links.push(BlockLink {
+                name: format!("chunk_{}", links.len()),
+                cid: cid.to_bytes(),
+                size: u64::from_le_bytes(size),
+            });
+        }
+
+        Ok(links)
+    }
+
+    /// Computes the DAG structure hash the guest commits as the proof's root hash
+    ///
+    /// Only block CIDs and links are hashed, so blocks whose data is unavailable
+    /// may be represented by their CID alone. Blocks are hashed in
+    /// [canonical order](Self::canonical_order), so the hash does not depend on
+    /// the order they are supplied in; blocks that do not form a DAG from a
+    /// single root are hashed as given.
+    pub fn structure_hash(blocks: &[IpfsBlock]) -> [u8; 32] {
+        let order = Self::canonical_order(blocks).unwrap_or_else(|_| (0..blocks.len()).collect());
+        let mut hasher = Sha256::new();
+        for block in order.into_iter().map(|index| &blocks[index]) {
+            hasher.update(&block.cid);
+            for link in &block.links {
+                hasher.update(&link.cid);
+                hasher.update(link.name.as_bytes());
+                hasher.update(link.size.to_le_bytes());
+            }
+        }
+        hasher.finalize().into()
+    }
+
+    /// Returns the positions of `blocks` in canonical order, as the guest folds them
+    ///
+    /// The canonical order is a depth-first pre-order walk of the DAG: the root,
+    /// the one block no other block links to, comes first, and each block is
+    /// followed by the blocks it links to, in link order. This is the order of
+    /// the file's content, with each block's data preceding its children's.
+    /// Several blocks with the same CID fill the links to that CID in turn.
+    /// Every block must be reachable from the root and linked exactly once.
+    pub fn canonical_order(blocks: &[IpfsBlock]) -> Result<Vec<usize>> {
+        let invalid = |message: &str| ProofError::ipfs_error("canonical_order", message, None);
+        if blocks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Unused blocks by CID, lowest position first
+        let mut by_cid: std::collections::HashMap<&[u8], std::collections::VecDeque<usize>> =
+            std::collections::HashMap::new();
+        for (index, block) in blocks.iter().enumerate() {
+            by_cid.entry(&block.cid[..]).or_default().push_back(index);
+        }
+
+        let linked: std::collections::HashSet<&[u8]> = blocks.iter()
+            .flat_map(|block| block.links.iter().map(|link| &link.cid[..]))
+            .collect();
+        let mut roots = blocks.iter().enumerate().filter(|(_, block)| !linked.contains(&block.cid[..]));
+        let root = match (roots.next(), roots.next()) {
+            (Some((index, _)), None) => index,
+            (None, _) => return Err(invalid("Blocks have no root: every block is linked from another")),
+            (Some(_), Some(_)) => return Err(invalid("Blocks have more than one root")),
+        };
+        by_cid.get_mut(&blocks[root].cid[..]).and_then(|unused| unused.pop_front());
+
+        let mut order = Vec::with_capacity(blocks.len());
+        let mut pending = vec![root];
+        while let Some(index) = pending.pop() {
+            order.push(index);
+            let children = blocks[index].links.iter()
+                .map(|link| by_cid.get_mut(&link.cid[..])
+                    .and_then(|unused| unused.pop_front())
+                    .ok_or_else(|| invalid("A link names a block that was not supplied, or was already linked")))
+                .collect::<Result<Vec<_>>>()?;
+            pending.extend(children.into_iter().rev());
+        }
+
+        if order.len() != blocks.len() {
+            return Err(invalid("Some blocks are not reachable from the root"));
+        }
+        Ok(order)
+    }
+
     /// Calculates the CID for a block of data
     fn calculate_block_cid(&self, data: &[u8]) -> Result<Cid> {
         let hash = Code::Sha2_256.digest(data);
@@ -160,39 +489,9 @@ impl IpfsProcessor {
         self.calculate_block_cid(content)
     }
 
-    /// Detects MIME type of the file
+    /// Detects MIME type of the file from its content, then its extension
     fn detect_mime_type(&self, file_path: &Path, content: &[u8]) -> Option<String> {
-        // Simple MIME type detection based on file extension and content
-        if let Some(extension) = file_path.extension().and_then(|e| e.to_str()) {
-            match extension.to_lowercase().as_str() {
-                "txt" => return Some("text/plain".to_string()),
-                "json" => return Some("application/json".to_string()),
-                "csv" => return Some("text/csv".to_string()),
-                "pdf" => return Some("application/pdf".to_string()),
-                "png" => return Some("image/png".to_string()),
-                "jpg" | "jpeg" => return Some("image/jpeg".to_string()),
-                "mp4" => return Some("video/mp4".to_string()),
-                "zip" => return Some("application/zip".to_string()),
-                _ => {}
-            }
-        }
-        
-        // Content-based detection for common formats
-        if content.starts_with(b"%PDF") {
-            Some("application/pdf".to_string())
-        } else if content.starts_with(b"\x89PNG\r\n\x1a\n") {
-            Some("image/png".to_string())
-        } else if content.starts_with(b"\xFF\xD8\xFF") {
-            Some("image/jpeg".to_string())
-        } else if content.starts_with(b"PK") {
-            Some("application/zip".to_string())
-        } else if content.starts_with(b"{") || content.starts_with(b"[") {
-            Some("application/json".to_string())
-        } else if content.is_ascii() {
-            Some("text/plain".to_string())
-        } else {
-            Some("application/octet-stream".to_string())
-        }
+        Some(crate::mime::detect_mime_type(file_path, content))
     }
 
     /// Reconstructs file content from IPFS blocks
@@ -333,6 +632,38 @@ impl Default for BlockStatistics {
     }
 }
 
+/// Reads a file through a `codec` decoder, without holding the compressed bytes in memory
+///
+/// Decoding stops one byte past `max_size`, so a small file that expands to
+/// more than that fails without the whole output being buffered.
+fn decompress_file(file_path: &Path, codec: &CompressionType, max_size: Option<u64>) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let file = std::fs::File::open(file_path)
+        .map(std::io::BufReader::new)
+        .context("Failed to open compressed file")?;
+    let error = |e: std::io::Error| ProofError::file_error(format!("Failed to decompress {:?} file", codec), Some(e));
+    let mut decoder: Box<dyn Read> = match codec {
+        CompressionType::None => Box::new(file),
+        CompressionType::Gzip => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        CompressionType::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(file).map_err(error)?),
+    };
+
+    let mut content = Vec::new();
+    match max_size {
+        Some(max) => decoder.take(max.saturating_add(1)).read_to_end(&mut content),
+        None => decoder.read_to_end(&mut content),
+    }.map_err(error)?;
+
+    if let Some(max) = max_size.filter(|max| content.len() as u64 > *max) {
+        return Err(ProofError::resource_limit_error(
+            "file_size",
+            format!("Decompressed {:?} file exceeds maximum allowed size ({} bytes)", codec, max)
+        ));
+    }
+    Ok(content)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -359,14 +690,13 @@ mod tests {
         // Small file should result in a single block
         assert_eq!(blocks.len(), 1);
         assert_eq!(blocks[0].data, test_content);
-        assert_eq!(file_info.size, test_content.len() as u64);
+        assert_eq!(file_info.size, Bytes::from_len(test_content.len()));
         assert_eq!(file_info.block_count, 1);
     }
 
     #[tokio::test]
     async fn test_large_file_processing() {
-        let mut processor = IpfsProcessor::new().await.unwrap();
-        processor.max_block_size = 10; // Small block size for testing
+        let processor = IpfsProcessor::with_chunking(ChunkingStrategy::FixedSize(10)).await.unwrap(); // Small block size for testing
         
         // Create a larger test file
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -377,13 +707,144 @@ mod tests {
         
         // Should result in multiple blocks plus a root block
         assert!(blocks.len() > 1);
-        assert_eq!(file_info.size, test_content.len() as u64);
+        assert_eq!(file_info.size, Bytes::from_len(test_content.len()));
         
         // Verify we can reconstruct the original content
         let reconstructed = processor.reconstruct_content(&blocks).unwrap();
         assert_eq!(reconstructed, test_content);
     }
 
+    #[tokio::test]
+    async fn test_parallel_hashing_matches_sequential() {
+        let content: Vec<u8> = (0..1_000_000u32).map(|i| (i.wrapping_mul(2654435761) >> 11) as u8).collect();
+
+        for chunking in [
+            ChunkingStrategy::FixedSize(4 * 1024),
+            ChunkingStrategy::Rabin { min: 1024, avg: 4 * 1024, max: 16 * 1024 },
+        ] {
+            let mut sequential = IpfsProcessor::with_chunking(chunking.clone()).await.unwrap();
+            sequential.set_worker_threads(Some(1)).unwrap();
+            let mut parallel = IpfsProcessor::with_chunking(chunking).await.unwrap();
+            parallel.set_worker_threads(Some(4)).unwrap();
+            assert_eq!(parallel.worker_threads(), 4);
+
+            let expected = sequential.create_blocks(&content).unwrap();
+            let actual = parallel.create_blocks(&content).unwrap();
+            assert!(expected.len() > 2);
+            assert_eq!(bincode::serialize(&actual).unwrap(), bincode::serialize(&expected).unwrap());
+        }
+
+        let mut processor = IpfsProcessor::new().await.unwrap();
+        assert!(processor.set_worker_threads(Some(0)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rabin_chunking_differs_from_fixed_size() {
+        let content: Vec<u8> = (0..200_000u32).map(|i| (i.wrapping_mul(2654435761) >> 13) as u8).collect();
+
+        let fixed = IpfsProcessor::with_chunking(ChunkingStrategy::FixedSize(16 * 1024)).await.unwrap();
+        let rabin = IpfsProcessor::with_chunking(ChunkingStrategy::Rabin {
+            min: 2 * 1024,
+            avg: 8 * 1024,
+            max: 32 * 1024,
+        }).await.unwrap();
+
+        let fixed_blocks = fixed.create_blocks(&content).unwrap();
+        let rabin_blocks = rabin.create_blocks(&content).unwrap();
+        assert_ne!(fixed_blocks.len(), rabin_blocks.len());
+
+        // Rabin blocks stay within bounds and reassemble to the original content
+        for block in &rabin_blocks[1..rabin_blocks.len() - 1] {
+            assert!(block.data.len() >= 2 * 1024 && block.data.len() <= 32 * 1024);
+        }
+        assert_eq!(rabin.reconstruct_content(&rabin_blocks).unwrap(), content);
+    }
+
+    #[tokio::test]
+    async fn test_mmap_matches_buffered_read() {
+        let content: Vec<u8> = (0..600_000u32).map(|i| (i.wrapping_mul(2654435761) >> 11) as u8).collect();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&content).unwrap();
+
+        let buffered = IpfsProcessor::new().await.unwrap();
+        let mut mapped = IpfsProcessor::new().await.unwrap();
+        mapped.set_mmap_threshold(Some(Bytes(0)));
+        assert!(matches!(
+            mapped.read_content(temp_file.path(), content.len() as u64).await.unwrap(),
+            FileContent::Mapped(_)
+        ));
+
+        let (buffered_blocks, buffered_info) = buffered.process_file(temp_file.path()).await.unwrap();
+        let (mapped_blocks, mapped_info) = mapped.process_file(temp_file.path()).await.unwrap();
+        assert_eq!(IpfsProcessor::structure_hash(&mapped_blocks), IpfsProcessor::structure_hash(&buffered_blocks));
+        assert_eq!(mapped_info.file_hash, buffered_info.file_hash);
+        assert_eq!(mapped_info.ipfs_cid, buffered_info.ipfs_cid);
+    }
+
+    #[tokio::test]
+    async fn test_decompressed_size_limited() {
+        let content = vec![0u8; 64 * 1024];
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&CompressionType::Gzip.compress(&content).unwrap()).unwrap();
+
+        let mut processor = IpfsProcessor::new().await.unwrap();
+        processor.set_decompression(Some(CompressionType::Gzip));
+        processor.set_max_decompressed_size(Some(Bytes(content.len() as u64)));
+        let (_, file_info) = processor.process_file(temp_file.path()).await.unwrap();
+        assert_eq!(file_info.size, Bytes(content.len() as u64));
+
+        processor.set_max_decompressed_size(Some(Bytes(content.len() as u64 - 1)));
+        let result = processor.process_file(temp_file.path()).await;
+        assert!(matches!(result, Err(ProofError::ResourceLimitError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_structure_hash_independent_of_block_order() {
+        let processor = IpfsProcessor::with_chunking(ChunkingStrategy::FixedSize(64)).await.unwrap();
+        // Repeated chunks give several leaves with the same CID
+        let content: Vec<u8> = (0..2048u32).map(|i| ((i / 64) % 5 * 31 + i % 64) as u8).collect();
+        let blocks = processor.create_blocks(&content).unwrap();
+        assert_eq!(IpfsProcessor::canonical_order(&blocks).unwrap(), (0..blocks.len()).collect::<Vec<_>>());
+
+        // Deterministic shuffle with a stride coprime to the block count
+        let stride = (2..blocks.len()).find(|stride| gcd(*stride, blocks.len()) == 1).unwrap();
+        let shuffled: Vec<IpfsBlock> = (0..blocks.len())
+            .map(|i| blocks[(i * stride + 3) % blocks.len()].clone())
+            .collect();
+        assert_ne!(shuffled[0].cid, blocks[0].cid);
+        assert_eq!(IpfsProcessor::structure_hash(&shuffled), IpfsProcessor::structure_hash(&blocks));
+
+        let order = IpfsProcessor::canonical_order(&shuffled).unwrap();
+        let reordered: Vec<IpfsBlock> = order.into_iter().map(|index| shuffled[index].clone()).collect();
+        assert_eq!(processor.reconstruct_content(&reordered).unwrap(), content);
+        assert!(IpfsProcessor::canonical_order(&shuffled[1..]).is_err());
+    }
+
+    fn gcd(a: usize, b: usize) -> usize {
+        if b == 0 { a } else { gcd(b, a % b) }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_chunking_rejected() {
+        let result = IpfsProcessor::with_chunking(ChunkingStrategy::Rabin { min: 10, avg: 5, max: 20 }).await;
+        assert!(result.is_err());
+        assert!(IpfsProcessor::with_chunking(ChunkingStrategy::FixedSize(0)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_root_links_decode() {
+        let processor = IpfsProcessor::with_chunking(ChunkingStrategy::FixedSize(8)).await.unwrap();
+        let blocks = processor.create_blocks(b"content split into several blocks").unwrap();
+
+        let links = IpfsProcessor::decode_root_links(&blocks[0].data).unwrap();
+        assert_eq!(links.len(), blocks.len() - 1);
+        for (decoded, original) in links.iter().zip(&blocks[0].links) {
+            assert_eq!(decoded.cid, original.cid);
+            assert_eq!(decoded.name, original.name);
+            assert_eq!(decoded.size, original.size);
+        }
+    }
+
     #[tokio::test]
     async fn test_mime_type_detection() {
         let processor = IpfsProcessor::new().await.unwrap();
@@ -399,6 +860,17 @@ mod tests {
         assert_eq!(pdf_mime, Some("application/pdf".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_renamed_png_detected_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("picture.dat");
+        std::fs::write(&path, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x01\0\0\0\x01\x08\x06\0\0\0\x1f\x15\xc4\x89").unwrap();
+
+        let processor = IpfsProcessor::new().await.unwrap();
+        let (_, file_info) = processor.process_file(&path).await.unwrap();
+        assert_eq!(file_info.mime_type.as_deref(), Some("image/png"));
+    }
+
     #[tokio::test]
     async fn test_block_validation() {
         let processor = IpfsProcessor::new().await.unwrap();
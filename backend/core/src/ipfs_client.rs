@@ -3,11 +3,34 @@
 //! This module provides a comprehensive IPFS client implementation that handles
 //! file uploads, content addressing, and decentralized storage management.
 
-use crate::{Result, ZkIPFSError};
+use crate::{
+    monitoring::{EventBus, MonitorEvent},
+    ProofError, Result, ZkIPFSError,
+};
+use multihash::{Code, MultihashDigest};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
+
+/// Default chunk size for resumable uploads, matching the IPFS default chunker
+pub const DEFAULT_UPLOAD_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Multicodec of raw blocks
+const RAW_CODEC: u64 = 0x55;
+
+/// Multicodec of dag-pb blocks
+const DAG_PB_CODEC: u64 = 0x70;
+
+/// Upper bound on the delay between retries of a transient failure
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
 
 /// IPFS Content Identifier (CID) representation
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -24,14 +47,67 @@ pub struct Cid {
 
 impl Cid {
     /// Create a new CID from a string
+    ///
+    /// The codec, hash and multibase names are filled in from the parsed CID,
+    /// or set to `"unknown"` if the string is not a valid CID. Use
+    /// [`parse`](Self::parse) to reject invalid strings instead.
     pub fn new(cid: String) -> Self {
-        // Parse CID components (simplified implementation)
-        Self {
-            cid: cid.clone(),
-            hash_algorithm: "sha2-256".to_string(),
-            codec: "dag-pb".to_string(),
-            multibase: "base58btc".to_string(),
+        Self::parse(&cid).unwrap_or_else(|_| Self {
+            cid,
+            hash_algorithm: "unknown".to_string(),
+            codec: "unknown".to_string(),
+            multibase: "unknown".to_string(),
+        })
+    }
+
+    /// Parses a CIDv0 (base58btc `Qm...`) or CIDv1 (multibase, multicodec and multihash)
+    ///
+    /// The string must be the canonical encoding of the CID in its multibase,
+    /// and a digest of a known hash function must have that function's length.
+    pub fn parse(cid: &str) -> Result<Self> {
+        let (parsed, base) = Self::decode(cid)?;
+        let hash = parsed.hash();
+        Ok(Self {
+            cid: cid.to_string(),
+            hash_algorithm: hash_name(hash.code()).to_string(),
+            codec: codec_name(parsed.codec()).to_string(),
+            multibase: multibase_name(base),
+        })
+    }
+
+    /// Decodes and checks `cid`, returning it with its multibase
+    fn decode(cid: &str) -> Result<(cid::Cid, cid::multibase::Base)> {
+        let invalid = |reason: String| {
+            ProofError::invalid_input_error("cid", format!("Invalid CID '{}': {}", cid, reason))
+        };
+
+        let parsed = cid::Cid::try_from(cid).map_err(|e| invalid(e.to_string()))?;
+        let base = match parsed.version() {
+            cid::Version::V0 => cid::multibase::Base::Base58Btc,
+            cid::Version::V1 => cid.chars().next()
+                .and_then(|code| cid::multibase::Base::from_code(code).ok())
+                .ok_or_else(|| invalid("unknown multibase prefix".to_string()))?,
+        };
+
+        let canonical = match parsed.version() {
+            cid::Version::V0 => parsed.to_string(),
+            cid::Version::V1 => parsed.to_string_of_base(base).map_err(|e| invalid(e.to_string()))?,
+        };
+        if canonical != cid {
+            return Err(invalid("trailing or non-canonical data".to_string()));
         }
+
+        let hash = parsed.hash();
+        if let Some(expected) = digest_len(hash.code()) {
+            if hash.size() as usize != expected {
+                return Err(invalid(format!(
+                    "{} digest is {} bytes, expected {}",
+                    hash_name(hash.code()), hash.size(), expected
+                )));
+            }
+        }
+
+        Ok((parsed, base))
     }
 
     /// Get the CID as a string
@@ -41,8 +117,87 @@ impl Cid {
 
     /// Validate the CID format
     pub fn is_valid(&self) -> bool {
-        // Basic CID validation (simplified)
-        self.cid.len() >= 46 && (self.cid.starts_with("Qm") || self.cid.starts_with("bafy"))
+        Self::decode(&self.cid).is_ok()
+    }
+
+    /// Returns the CID version (0 or 1), if the CID is valid
+    pub fn version(&self) -> Option<u64> {
+        self.parsed().map(|cid| match cid.version() {
+            cid::Version::V0 => 0,
+            cid::Version::V1 => 1,
+        })
+    }
+
+    /// Returns the multicodec code of the content, such as `0x70` for dag-pb, if the CID is valid
+    pub fn codec(&self) -> Option<u64> {
+        self.parsed().map(|cid| cid.codec())
+    }
+
+    /// Returns the multihash code of the digest, such as `0x12` for sha2-256, if the CID is valid
+    pub fn hash_type(&self) -> Option<u64> {
+        self.parsed().map(|cid| cid.hash().code())
+    }
+
+    fn parsed(&self) -> Option<cid::Cid> {
+        Self::decode(&self.cid).ok().map(|(cid, _)| cid)
+    }
+}
+
+impl std::str::FromStr for Cid {
+    type Err = ProofError;
+
+    fn from_str(cid: &str) -> Result<Self> {
+        Self::parse(cid)
+    }
+}
+
+/// Returns the multicodec name of a content codec
+fn codec_name(code: u64) -> &'static str {
+    match code {
+        0x51 => "cbor",
+        0x55 => "raw",
+        0x70 => "dag-pb",
+        0x71 => "dag-cbor",
+        0x0129 => "dag-json",
+        0x0200 => "json",
+        _ => "unknown",
+    }
+}
+
+/// Returns the multicodec name of a multihash function
+fn hash_name(code: u64) -> &'static str {
+    match code {
+        0x00 => "identity",
+        0x12 => "sha2-256",
+        0x13 => "sha2-512",
+        0x16 => "sha3-256",
+        0x1e => "blake3",
+        0xb220 => "blake2b-256",
+        _ => "unknown",
+    }
+}
+
+/// Returns the multibase name of an encoding
+fn multibase_name(base: cid::multibase::Base) -> String {
+    use cid::multibase::Base;
+
+    match base {
+        Base::Base16Lower => "base16".to_string(),
+        Base::Base32Lower => "base32".to_string(),
+        Base::Base36Lower => "base36".to_string(),
+        Base::Base58Btc => "base58btc".to_string(),
+        Base::Base64 => "base64".to_string(),
+        Base::Base64Url => "base64url".to_string(),
+        other => format!("{:?}", other).to_lowercase(),
+    }
+}
+
+/// Returns the digest length of a fixed-length multihash function
+fn digest_len(code: u64) -> Option<usize> {
+    match code {
+        0x12 | 0x16 | 0xb220 => Some(32),
+        0x13 => Some(64),
+        _ => None,
     }
 }
 
@@ -51,24 +206,87 @@ impl Cid {
 pub struct IpfsConfig {
     /// IPFS API endpoint
     pub api_url: String,
+    /// Further IPFS API endpoints, tried in order when `api_url` cannot be reached
+    #[serde(default)]
+    pub api_urls: Vec<String>,
+    /// Spread requests across all endpoints in turn instead of preferring `api_url`
+    #[serde(default)]
+    pub round_robin: bool,
+    /// Seconds an unreachable endpoint is tried last for, before it is preferred again
+    #[serde(default = "default_node_cooldown_secs")]
+    pub node_cooldown_secs: u64,
     /// Gateway URL for content retrieval
     pub gateway_url: String,
+    /// Fetch content from `gateway_url` when no API endpoint can serve it;
+    /// every block the gateway returns is checked against its CID
+    #[serde(default)]
+    pub gateway_fallback: bool,
     /// Timeout for operations in seconds
     pub timeout: u64,
     /// Whether to pin content by default
     pub auto_pin: bool,
     /// Custom headers for API requests
     pub headers: HashMap<String, String>,
+    /// Times to retry a get, stat or pin after a transient failure
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry in milliseconds, doubled for each further retry
+    #[serde(default = "default_retry_base_delay_ms")]
+    pub retry_base_delay_ms: u64,
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_base_delay_ms() -> u64 {
+    250
+}
+
+fn default_node_cooldown_secs() -> u64 {
+    30
+}
+
+impl IpfsConfig {
+    /// All API endpoints: `api_url` followed by `api_urls`, without duplicates
+    pub fn endpoints(&self) -> Vec<String> {
+        let mut endpoints = vec![self.api_url.clone()];
+        for url in &self.api_urls {
+            if !endpoints.contains(url) {
+                endpoints.push(url.clone());
+            }
+        }
+        endpoints
+    }
+}
+
+/// Configuration for a single IPFS node at the given API URL
+impl From<String> for IpfsConfig {
+    fn from(api_url: String) -> Self {
+        Self { api_url, ..Self::default() }
+    }
+}
+
+impl From<&str> for IpfsConfig {
+    fn from(api_url: &str) -> Self {
+        Self::from(api_url.to_string())
+    }
 }
 
 impl Default for IpfsConfig {
     fn default() -> Self {
         Self {
             api_url: "http://127.0.0.1:5001".to_string(),
+            api_urls: Vec::new(),
+            round_robin: false,
+            node_cooldown_secs: default_node_cooldown_secs(),
             gateway_url: "http://127.0.0.1:8080".to_string(),
+            gateway_fallback: false,
             timeout: 300, // 5 minutes
             auto_pin: true,
             headers: HashMap::new(),
+            max_retries: default_max_retries(),
+            retry_base_delay_ms: default_retry_base_delay_ms(),
         }
     }
 }
@@ -92,11 +310,59 @@ pub struct IpfsFile {
     pub metadata: HashMap<String, String>,
 }
 
+/// API endpoints of a client and when each was last found unreachable
+#[derive(Debug)]
+struct NodeSet {
+    urls: Vec<String>,
+    /// Set when a node fails to connect, cleared when it answers again
+    failed_at: Mutex<Vec<Option<Instant>>>,
+    /// Node the next request starts from in round-robin mode
+    next: AtomicUsize,
+}
+
+impl NodeSet {
+    fn new(urls: Vec<String>) -> Self {
+        let failed_at = Mutex::new(vec![None; urls.len()]);
+        Self { urls, failed_at, next: AtomicUsize::new(0) }
+    }
+
+    /// Order in which to try the nodes for one request
+    ///
+    /// Nodes that failed within `cooldown` go last, so a request only waits on
+    /// a node known to be down when every node is.
+    fn order(&self, round_robin: bool, cooldown: Duration) -> Vec<usize> {
+        let count = self.urls.len();
+        let start = if round_robin {
+            self.next.fetch_add(1, Ordering::Relaxed) % count
+        } else {
+            0
+        };
+        let failed_at = self.failed_at.lock().unwrap();
+        let (healthy, down): (Vec<usize>, Vec<usize>) = (0..count)
+            .map(|offset| (start + offset) % count)
+            .partition(|&index| failed_at[index].map_or(true, |at| at.elapsed() >= cooldown));
+        healthy.into_iter().chain(down).collect()
+    }
+
+    fn set_failed(&self, index: usize, failed: bool) {
+        self.failed_at.lock().unwrap()[index] = failed.then(Instant::now);
+    }
+}
+
 /// IPFS client for interacting with IPFS nodes
+///
+/// Requests go to `api_url` first, failing over to the endpoints in `api_urls`
+/// when a node cannot be reached, or to each endpoint in turn with `round_robin`.
 #[derive(Debug, Clone)]
 pub struct IpfsClient {
     config: IpfsConfig,
     client: reqwest::Client,
+    /// Endpoints and their health, shared by clones of the client
+    nodes: Arc<NodeSet>,
+    /// Retries made after transient failures, shared by clones of the client
+    retries: Arc<AtomicU64>,
+    /// Receives an `IpfsError` when a retried request fails for good
+    events: EventBus,
 }
 
 impl IpfsClient {
@@ -124,12 +390,28 @@ impl IpfsClient {
             .build()
             .map_err(|e| ZkIPFSError::IpfsError(format!("Failed to create HTTP client: {}", e)))?;
 
-        Ok(Self { config, client })
+        let nodes = Arc::new(NodeSet::new(config.endpoints()));
+        Ok(Self { config, client, nodes, retries: Arc::new(AtomicU64::new(0)), events: EventBus::new() })
+    }
+
+    /// Event bus the client reports failed requests to
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Report events to `events`, for sharing one bus with other components
+    pub fn set_events(&mut self, events: EventBus) {
+        self.events = events;
     }
 
-    /// Check if the IPFS node is accessible
+    /// Number of retries made after transient failures since the client was created
+    pub fn retry_count(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    /// Check if any of the IPFS nodes is accessible
     pub async fn is_online(&self) -> bool {
-        match self.client.post(&format!("{}/api/v0/version", self.config.api_url)).send().await {
+        match self.send(|url| self.client.post(format!("{}/api/v0/version", url))).await {
             Ok(response) => response.status().is_success(),
             Err(_) => false,
         }
@@ -150,19 +432,54 @@ impl IpfsClient {
         self.upload_bytes(&content, &file_name).await
     }
 
+    /// Prepare a chunked, resumable upload of a file
+    ///
+    /// No data is sent until [`ResumableUpload::upload`] is called. If an upload
+    /// attempt fails part way through, calling `upload` again on the same handle
+    /// only re-sends the chunks that are not already present on the node.
+    pub async fn upload_file_resumable<P: AsRef<Path>>(
+        &self,
+        file_path: P,
+        chunk_size: usize,
+    ) -> Result<ResumableUpload> {
+        if chunk_size == 0 {
+            return Err(ZkIPFSError::IpfsError("Chunk size must be greater than zero".to_string()));
+        }
+
+        let path = file_path.as_ref().to_path_buf();
+        let file_size = fs::metadata(&path).await
+            .map_err(|e| ZkIPFSError::IoError(format!("Failed to read file metadata: {}", e)))?
+            .len();
+        let name = path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let chunk_count = (file_size.div_ceil(chunk_size as u64) as usize).max(1);
+
+        Ok(ResumableUpload {
+            client: self.clone(),
+            path,
+            name,
+            chunk_size,
+            file_size,
+            chunks: vec![None; chunk_count],
+            completed: None,
+        })
+    }
+
     /// Upload bytes to IPFS
     pub async fn upload_bytes(&self, content: &[u8], name: &str) -> Result<IpfsFile> {
-        // Create multipart form data
-        let form = reqwest::multipart::Form::new()
-            .part("file", reqwest::multipart::Part::bytes(content.to_vec())
-                .file_name(name.to_string()));
-
-        // Upload to IPFS
-        let response = self.client
-            .post(&format!("{}/api/v0/add", self.config.api_url))
-            .query(&[("pin", self.config.auto_pin.to_string())])
-            .multipart(form)
-            .send()
+        // Upload to IPFS, building the multipart form for each node tried
+        let response = self
+            .send(|url| {
+                let form = reqwest::multipart::Form::new()
+                    .part("file", reqwest::multipart::Part::bytes(content.to_vec())
+                        .file_name(name.to_string()));
+                self.client
+                    .post(format!("{}/api/v0/add", url))
+                    .query(&[("pin", self.config.auto_pin.to_string())])
+                    .multipart(form)
+            })
             .await
             .map_err(|e| ZkIPFSError::IpfsError(format!("Upload failed: {}", e)))?;
 
@@ -196,52 +513,161 @@ impl IpfsClient {
     }
 
     /// Retrieve file content from IPFS
+    ///
+    /// Transient failures are retried with exponential backoff, up to
+    /// `max_retries` times. If the API still fails and `gateway_fallback` is
+    /// set, the content is fetched from the gateway instead.
     pub async fn get_file(&self, cid: &Cid) -> Result<Vec<u8>> {
-        let response = self.client
-            .post(&format!("{}/api/v0/cat", self.config.api_url))
-            .query(&[("arg", cid.as_str())])
-            .send()
-            .await
-            .map_err(|e| ZkIPFSError::IpfsError(format!("Failed to retrieve file: {}", e)))?;
-
-        if !response.status().is_success() {
-            return Err(ZkIPFSError::IpfsError(format!(
-                "Failed to retrieve file with status: {}", 
-                response.status()
-            )));
+        validate_cid("cat", cid)?;
+        match self.cat(cid).await {
+            Err(e) if self.config.gateway_fallback => {
+                tracing::warn!("IPFS cat of {} failed ({}), fetching it from the gateway", cid.as_str(), e);
+                self.get_from_gateway(cid).await
+            }
+            result => result,
         }
+    }
 
-        let content = response.bytes().await
-            .map_err(|e| ZkIPFSError::IpfsError(format!("Failed to read file content: {}", e)))?;
+    /// Retrieve file content through the gateway, checking each block against its CID
+    ///
+    /// Blocks are requested in the trustless gateway's raw format. Raw blocks are
+    /// file data; dag-pb blocks are UnixFS nodes whose data precedes that of
+    /// their links.
+    pub async fn get_from_gateway(&self, cid: &Cid) -> Result<Vec<u8>> {
+        validate_cid("gateway", cid)?;
+        let (root, _) = Cid::decode(cid.as_str())?;
+
+        let mut content = Vec::new();
+        let mut pending = vec![root];
+        while let Some(block_cid) = pending.pop() {
+            let block = self.fetch_gateway_block(&block_cid).await?;
+            match block_cid.codec() {
+                RAW_CODEC => content.extend_from_slice(&block),
+                DAG_PB_CODEC => {
+                    let (data, links) = decode_unixfs_node(&block).map_err(|message| ProofError::ipfs_error(
+                        "gateway",
+                        format!("Invalid dag-pb block {}: {}", block_cid, message),
+                        None,
+                    ))?;
+                    content.extend_from_slice(&data);
+                    pending.extend(links.into_iter().rev());
+                }
+                codec => {
+                    return Err(ProofError::ipfs_error(
+                        "gateway",
+                        format!("Cannot read {} block {} from the gateway", codec_name(codec), block_cid),
+                        None,
+                    ));
+                }
+            }
+        }
 
-        Ok(content.to_vec())
+        Ok(content)
     }
 
-    /// Pin a file in IPFS
-    pub async fn pin_file(&self, cid: &Cid) -> Result<()> {
+    /// Fetches one block from the gateway, rejecting it unless it hashes to `cid`
+    async fn fetch_gateway_block(&self, cid: &cid::Cid) -> Result<Vec<u8>> {
         let response = self.client
-            .post(&format!("{}/api/v0/pin/add", self.config.api_url))
-            .query(&[("arg", cid.as_str())])
+            .get(format!("{}/ipfs/{}", self.config.gateway_url, cid))
+            .query(&[("format", "raw")])
+            .header(reqwest::header::ACCEPT, "application/vnd.ipld.raw")
             .send()
             .await
-            .map_err(|e| ZkIPFSError::IpfsError(format!("Failed to pin file: {}", e)))?;
+            .map_err(|e| request_error("gateway", "Failed to fetch block from gateway", e))?;
+        let response = check_status("gateway", "Failed to fetch block from gateway", response).await?;
+        let block = response.bytes().await
+            .map_err(|e| request_error("gateway", "Failed to read block from gateway", e))?;
+
+        let code = cid.hash().code();
+        let hasher = Code::try_from(code).map_err(|_| ProofError::ipfs_error(
+            "gateway",
+            format!("Cannot check block {} hashed with {}", cid, hash_name(code)),
+            None,
+        ))?;
+        if hasher.digest(&block).digest() != cid.hash().digest() {
+            return Err(ProofError::ipfs_error(
+                "gateway",
+                format!("Gateway returned data that does not hash to {}", cid),
+                None,
+            ));
+        }
 
-        if !response.status().is_success() {
-            return Err(ZkIPFSError::IpfsError(format!(
-                "Failed to pin file with status: {}", 
-                response.status()
-            )));
+        Ok(block.to_vec())
+    }
+
+    /// Retrieve file content from the API, with retries
+    async fn cat(&self, cid: &Cid) -> Result<Vec<u8>> {
+        self.with_retry("cat", move || async move {
+            let response = self
+                .send(|url| self.client
+                    .post(format!("{}/api/v0/cat", url))
+                    .query(&[("arg", cid.as_str())]))
+                .await
+                .map_err(|e| request_error("cat", "Failed to retrieve file", e))?;
+            let response = check_status("cat", "Failed to retrieve file", response).await?;
+
+            let content = response.bytes().await
+                .map_err(|e| request_error("cat", "Failed to read file content", e))?;
+            Ok(content.to_vec())
+        }).await
+    }
+
+    /// Streams file content from the API into `writer`, calling `on_progress`
+    /// with the number of bytes received so far after each chunk
+    ///
+    /// Returns the number of bytes written. Unlike [`get_file`](Self::get_file)
+    /// the download is not retried, as part of it may already have been written.
+    pub async fn download_to<W>(&self, cid: &Cid, writer: &mut W, mut on_progress: impl FnMut(u64)) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        validate_cid("cat", cid)?;
+        let response = self
+            .send(|url| self.client
+                .post(format!("{}/api/v0/cat", url))
+                .query(&[("arg", cid.as_str())]))
+            .await
+            .map_err(|e| request_error("cat", "Failed to retrieve file", e))?;
+        let mut response = check_status("cat", "Failed to retrieve file", response).await?;
+
+        let mut received = 0u64;
+        while let Some(chunk) = response.chunk().await
+            .map_err(|e| request_error("cat", "Failed to read file content", e))?
+        {
+            writer.write_all(&chunk).await
+                .map_err(|e| ProofError::file_error("Failed to write downloaded content", Some(e)))?;
+            received += chunk.len() as u64;
+            on_progress(received);
         }
+        writer.flush().await
+            .map_err(|e| ProofError::file_error("Failed to write downloaded content", Some(e)))?;
 
-        Ok(())
+        Ok(received)
+    }
+
+    /// Pin a file in IPFS
+    ///
+    /// Transient failures are retried like [`get_file`](Self::get_file).
+    pub async fn pin_file(&self, cid: &Cid) -> Result<()> {
+        validate_cid("pin/add", cid)?;
+        self.with_retry("pin/add", move || async move {
+            let response = self
+                .send(|url| self.client
+                    .post(format!("{}/api/v0/pin/add", url))
+                    .query(&[("arg", cid.as_str())]))
+                .await
+                .map_err(|e| request_error("pin/add", "Failed to pin file", e))?;
+            check_status("pin/add", "Failed to pin file", response).await?;
+            Ok(())
+        }).await
     }
 
     /// Unpin a file from IPFS
     pub async fn unpin_file(&self, cid: &Cid) -> Result<()> {
-        let response = self.client
-            .post(&format!("{}/api/v0/pin/rm", self.config.api_url))
-            .query(&[("arg", cid.as_str())])
-            .send()
+        let response = self
+            .send(|url| self.client
+                .post(format!("{}/api/v0/pin/rm", url))
+                .query(&[("arg", cid.as_str())]))
             .await
             .map_err(|e| ZkIPFSError::IpfsError(format!("Failed to unpin file: {}", e)))?;
 
@@ -257,9 +683,8 @@ impl IpfsClient {
 
     /// List pinned files
     pub async fn list_pinned(&self) -> Result<Vec<Cid>> {
-        let response = self.client
-            .post(&format!("{}/api/v0/pin/ls", self.config.api_url))
-            .send()
+        let response = self
+            .send(|url| self.client.post(format!("{}/api/v0/pin/ls", url)))
             .await
             .map_err(|e| ZkIPFSError::IpfsError(format!("Failed to list pinned files: {}", e)))?;
 
@@ -279,17 +704,145 @@ impl IpfsClient {
     }
 
     /// Get file statistics
+    ///
+    /// Transient failures are retried like [`get_file`](Self::get_file).
     pub async fn stat_file(&self, cid: &Cid) -> Result<IpfsFileStat> {
-        let response = self.client
-            .post(&format!("{}/api/v0/object/stat", self.config.api_url))
-            .query(&[("arg", cid.as_str())])
-            .send()
+        validate_cid("object/stat", cid)?;
+        let response_text = self.with_retry("object/stat", move || async move {
+            let response = self
+                .send(|url| self.client
+                    .post(format!("{}/api/v0/object/stat", url))
+                    .query(&[("arg", cid.as_str())]))
+                .await
+                .map_err(|e| request_error("object/stat", "Failed to get file stats", e))?;
+            let response = check_status("object/stat", "Failed to get file stats", response).await?;
+
+            response.text().await
+                .map_err(|e| request_error("object/stat", "Failed to read response", e))
+        }).await?;
+
+        // Parse file statistics (simplified implementation)
+        self.parse_file_stat(&response_text)
+    }
+
+    /// Check whether a block is stored locally on the node, without fetching it from the network
+    pub async fn has_block(&self, cid: &Cid) -> Result<bool> {
+        let response = self
+            .send(|url| self.client
+                .post(format!("{}/api/v0/block/stat", url))
+                .query(&[("arg", cid.as_str()), ("offline", "true")]))
+            .await
+            .map_err(|e| ZkIPFSError::IpfsError(format!("Failed to get block stats: {}", e)))?;
+
+        Ok(response.status().is_success())
+    }
+
+    /// HTTP URL of the node's own gateway, from its `Addresses.Gateway`
+    /// setting, or `None` if it serves no gateway over TCP
+    pub async fn node_gateway_url(&self) -> Result<Option<String>> {
+        let response = self
+            .send(|url| self.client
+                .post(format!("{}/api/v0/config", url))
+                .query(&[("arg", "Addresses.Gateway")]))
+            .await
+            .map_err(|e| request_error("config", "Failed to read the gateway address", e))?;
+        let response = check_status("config", "Failed to read the gateway address", response).await?;
+        let setting: serde_json::Value = response.json().await
+            .map_err(|e| request_error("config", "Failed to read response", e))?;
+
+        // The setting is one multiaddr or a list of them
+        let addresses = match &setting["Value"] {
+            serde_json::Value::Array(addresses) => addresses.iter().filter_map(serde_json::Value::as_str).collect(),
+            value => value.as_str().into_iter().collect::<Vec<_>>(),
+        };
+        Ok(addresses.into_iter().find_map(multiaddr_to_http_url))
+    }
+
+    /// Generate a gateway URL for a file
+    pub fn gateway_url(&self, cid: &Cid) -> String {
+        format!("{}/ipfs/{}", self.config.gateway_url, cid.as_str())
+    }
+
+    // Helper methods
+
+    /// Sends the request `build` makes for a node's API URL, moving on to the
+    /// next node when one cannot be reached
+    ///
+    /// Only connection failures and timeouts fail over; a node that answers,
+    /// even with an error status, handles the request.
+    async fn send<F>(&self, build: F) -> std::result::Result<reqwest::Response, reqwest::Error>
+    where
+        F: Fn(&str) -> reqwest::RequestBuilder,
+    {
+        let cooldown = Duration::from_secs(self.config.node_cooldown_secs);
+        let mut last_error = None;
+        for index in self.nodes.order(self.config.round_robin, cooldown) {
+            let url = &self.nodes.urls[index];
+            match build(url).send().await {
+                Ok(response) => {
+                    self.nodes.set_failed(index, false);
+                    return Ok(response);
+                }
+                Err(e) if e.is_connect() || e.is_timeout() => {
+                    tracing::warn!("IPFS node {} is unreachable ({}), trying the next node", url, e);
+                    self.nodes.set_failed(index, true);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.expect("an IPFS client has at least one endpoint"))
+    }
+
+    /// Runs `call`, retrying transient failures with exponential backoff and full jitter
+    async fn with_retry<T, F, Fut>(&self, operation: &str, mut call: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match call().await {
+                Err(e @ ProofError::IpfsTransient { .. }) if attempt < self.config.max_retries => {
+                    let delay = retry_delay(self.config.retry_base_delay_ms, attempt);
+                    attempt += 1;
+                    self.retries.fetch_add(1, Ordering::Relaxed);
+                    tracing::warn!(
+                        "IPFS {} failed ({}), retry {} of {} in {}ms",
+                        operation,
+                        e,
+                        attempt,
+                        self.config.max_retries,
+                        delay.as_millis()
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => {
+                    self.events.emit(MonitorEvent::IpfsError { message: format!("{} failed: {}", operation, e) });
+                    return Err(e);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn upload_raw_chunk(&self, chunk: Vec<u8>, name: &str) -> Result<Cid> {
+        let response = self
+            .send(|url| {
+                let form = reqwest::multipart::Form::new()
+                    .part("file", reqwest::multipart::Part::bytes(chunk.clone())
+                        .file_name(name.to_string()));
+                self.client
+                    .post(format!("{}/api/v0/add", url))
+                    .query(&[("pin", "false"), ("raw-leaves", "true"), ("cid-version", "1")])
+                    .multipart(form)
+            })
             .await
-            .map_err(|e| ZkIPFSError::IpfsError(format!("Failed to get file stats: {}", e)))?;
+            .map_err(|e| ZkIPFSError::IpfsError(format!("Chunk upload failed: {}", e)))?;
 
         if !response.status().is_success() {
             return Err(ZkIPFSError::IpfsError(format!(
-                "Failed to get file stats with status: {}", 
+                "Chunk upload failed with status: {}",
                 response.status()
             )));
         }
@@ -297,16 +850,40 @@ impl IpfsClient {
         let response_text = response.text().await
             .map_err(|e| ZkIPFSError::IpfsError(format!("Failed to read response: {}", e)))?;
 
-        // Parse file statistics (simplified implementation)
-        self.parse_file_stat(&response_text)
+        Ok(Cid::new(self.extract_cid_from_response(&response_text)?))
     }
 
-    /// Generate a gateway URL for a file
-    pub fn gateway_url(&self, cid: &Cid) -> String {
-        format!("{}/ipfs/{}", self.config.gateway_url, cid.as_str())
-    }
+    async fn put_dag_pb_node(&self, node: Vec<u8>) -> Result<Cid> {
+        let response = self
+            .send(|url| {
+                let form = reqwest::multipart::Form::new()
+                    .part("file", reqwest::multipart::Part::bytes(node.clone()));
+                self.client
+                    .post(format!("{}/api/v0/dag/put", url))
+                    .query(&[
+                        ("store-codec", "dag-pb".to_string()),
+                        ("input-codec", "dag-pb".to_string()),
+                        ("pin", self.config.auto_pin.to_string()),
+                    ])
+                    .multipart(form)
+            })
+            .await
+            .map_err(|e| ZkIPFSError::IpfsError(format!("Failed to store file root: {}", e)))?;
 
-    // Helper methods
+        if !response.status().is_success() {
+            return Err(ZkIPFSError::IpfsError(format!(
+                "Failed to store file root with status: {}",
+                response.status()
+            )));
+        }
+
+        let response: serde_json::Value = response.json().await
+            .map_err(|e| ZkIPFSError::IpfsError(format!("Failed to read response: {}", e)))?;
+
+        response["Cid"]["/"].as_str()
+            .map(|cid| Cid::new(cid.to_string()))
+            .ok_or_else(|| ZkIPFSError::IpfsError("Failed to extract CID from response".to_string()))
+    }
 
     fn extract_cid_from_response(&self, response: &str) -> Result<String> {
         // Simplified JSON parsing - in a real implementation, use serde_json
@@ -337,31 +914,7 @@ impl IpfsClient {
     }
 
     fn detect_mime_type(&self, content: &[u8], name: &str) -> String {
-        // Simple MIME type detection based on file extension and content
-        if let Some(ext) = Path::new(name).extension().and_then(|e| e.to_str()) {
-            match ext.to_lowercase().as_str() {
-                "pdf" => "application/pdf".to_string(),
-                "json" => "application/json".to_string(),
-                "txt" => "text/plain".to_string(),
-                "md" => "text/markdown".to_string(),
-                "csv" => "text/csv".to_string(),
-                "jpg" | "jpeg" => "image/jpeg".to_string(),
-                "png" => "image/png".to_string(),
-                "gif" => "image/gif".to_string(),
-                "mp4" => "video/mp4".to_string(),
-                "mp3" => "audio/mpeg".to_string(),
-                _ => "application/octet-stream".to_string(),
-            }
-        } else {
-            // Basic content-based detection
-            if content.starts_with(b"%PDF") {
-                "application/pdf".to_string()
-            } else if content.starts_with(b"{") || content.starts_with(b"[") {
-                "application/json".to_string()
-            } else {
-                "application/octet-stream".to_string()
-            }
-        }
+        crate::mime::detect_mime_type(Path::new(name), content)
     }
 
     fn parse_file_stat(&self, response: &str) -> Result<IpfsFileStat> {
@@ -396,6 +949,312 @@ impl IpfsClient {
     }
 }
 
+/// Returns the delay before retry `attempt` (zero-based): a random duration of up
+/// to `base_ms * 2^attempt`, capped at [`MAX_RETRY_DELAY`]
+fn retry_delay(base_ms: u64, attempt: u32) -> Duration {
+    let ceiling = base_ms
+        .saturating_mul(1u64 << attempt.min(32))
+        .min(MAX_RETRY_DELAY.as_millis() as u64);
+    let random = std::collections::hash_map::RandomState::new().build_hasher().finish();
+    Duration::from_millis(random % (ceiling + 1))
+}
+
+/// Rejects malformed CIDs before they reach the node, since they can never succeed
+fn validate_cid(operation: &str, cid: &Cid) -> Result<()> {
+    Cid::parse(cid.as_str())
+        .map(|_| ())
+        .map_err(|e| ProofError::ipfs_error(
+            operation,
+            format!("Invalid CID {}: {}", cid.as_str(), e),
+            Some(Box::new(e)),
+        ))
+}
+
+/// Classifies a failed request: timeouts and connection failures are transient
+/// Converts a TCP multiaddr such as `/ip4/0.0.0.0/tcp/8080` to the HTTP URL a
+/// local client reaches it at, mapping wildcard addresses to loopback
+fn multiaddr_to_http_url(address: &str) -> Option<String> {
+    let parts: Vec<&str> = address.trim_start_matches('/').split('/').collect();
+    let host = match parts.as_slice() {
+        ["ip4", "0.0.0.0", "tcp", ..] => "127.0.0.1".to_string(),
+        ["ip6", "::", "tcp", ..] => "[::1]".to_string(),
+        ["ip6", host, "tcp", ..] => format!("[{}]", host),
+        ["ip4" | "dns" | "dns4" | "dns6", host, "tcp", ..] => host.to_string(),
+        _ => return None,
+    };
+    let port: u16 = parts.get(3)?.parse().ok()?;
+    Some(format!("http://{}:{}", host, port))
+}
+
+fn request_error(operation: &str, message: &str, error: reqwest::Error) -> ProofError {
+    let message = format!("{}: {}", message, error);
+    if error.is_timeout() || error.is_connect() || error.is_request() || error.is_body() {
+        ProofError::ipfs_transient_error(operation, message, Some(Box::new(error)))
+    } else {
+        ProofError::ipfs_error(operation, message, Some(Box::new(error)))
+    }
+}
+
+/// Passes successful responses through; 5xx, 408 and 429 responses are transient
+///
+/// The node reports a malformed or unknown CID as a 500 with an error message,
+/// which is treated as permanent.
+async fn check_status(operation: &str, message: &str, response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    let node_message = serde_json::from_str::<serde_json::Value>(&body).ok()
+        .and_then(|error| error["Message"].as_str().map(str::to_string))
+        .unwrap_or_default();
+    let message = if node_message.is_empty() {
+        format!("{} with status: {}", message, status)
+    } else {
+        format!("{} with status: {} ({})", message, status, node_message)
+    };
+
+    let permanent_node_error = ["invalid", "not found", "no link named"].iter()
+        .any(|reason| node_message.to_lowercase().contains(reason));
+    let transient = (status.is_server_error() && !permanent_node_error)
+        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+
+    if transient {
+        Err(ProofError::ipfs_transient_error(operation, message, None))
+    } else {
+        Err(ProofError::ipfs_error(operation, message, None))
+    }
+}
+
+/// A chunk of a resumable upload that has been stored on the node
+#[derive(Debug, Clone)]
+struct UploadedChunk {
+    cid: Cid,
+    size: u64,
+}
+
+/// Handle for a chunked upload that can be resumed after a failure
+///
+/// The handle records the CID of every chunk the node has accepted. Each call
+/// to [`upload`](Self::upload) confirms recorded chunks are still present via
+/// `block/stat`, uploads only the missing ones, and then links them under a
+/// UnixFS file root.
+#[derive(Debug, Clone)]
+pub struct ResumableUpload {
+    client: IpfsClient,
+    path: PathBuf,
+    name: String,
+    chunk_size: usize,
+    file_size: u64,
+    chunks: Vec<Option<UploadedChunk>>,
+    completed: Option<IpfsFile>,
+}
+
+impl ResumableUpload {
+    /// Upload all chunks not yet present on the node and assemble the file root
+    pub async fn upload(&mut self) -> Result<IpfsFile> {
+        if let Some(file) = &self.completed {
+            return Ok(file.clone());
+        }
+
+        let mut file = fs::File::open(&self.path).await
+            .map_err(|e| ZkIPFSError::IoError(format!("Failed to open file: {}", e)))?;
+
+        for index in 0..self.chunks.len() {
+            if let Some(chunk) = &self.chunks[index] {
+                if self.client.has_block(&chunk.cid).await? {
+                    continue;
+                }
+                tracing::debug!("Chunk {} of {} is missing from the node, re-uploading", index, self.name);
+            }
+
+            let offset = index as u64 * self.chunk_size as u64;
+            let len = (self.file_size - offset).min(self.chunk_size as u64) as usize;
+            let mut buffer = vec![0u8; len];
+            file.seek(SeekFrom::Start(offset)).await
+                .map_err(|e| ZkIPFSError::IoError(format!("Failed to seek file: {}", e)))?;
+            file.read_exact(&mut buffer).await
+                .map_err(|e| ZkIPFSError::IoError(format!("Failed to read file: {}", e)))?;
+
+            let cid = self.client.upload_raw_chunk(buffer, &self.name).await?;
+            self.chunks[index] = Some(UploadedChunk { cid, size: len as u64 });
+        }
+
+        let links = self.chunks.iter()
+            .flatten()
+            .map(|chunk| {
+                let cid = cid::Cid::try_from(chunk.cid.as_str())
+                    .map_err(|e| ZkIPFSError::IpfsError(format!("Invalid chunk CID {}: {}", chunk.cid.as_str(), e)))?;
+                Ok((cid.to_bytes(), chunk.size))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let root = self.client.put_dag_pb_node(encode_unixfs_file_node(&links, self.file_size)).await?;
+
+        let mut metadata = HashMap::new();
+        metadata.insert("chunk_count".to_string(), self.chunks.len().to_string());
+        metadata.insert("chunk_size".to_string(), self.chunk_size.to_string());
+
+        let file = IpfsFile {
+            cid: root,
+            name: self.name.clone(),
+            size: self.file_size,
+            mime_type: self.client.detect_mime_type(&[], &self.name),
+            uploaded_at: chrono::Utc::now(),
+            pinned: self.client.config.auto_pin,
+            metadata,
+        };
+        self.completed = Some(file.clone());
+        Ok(file)
+    }
+
+    /// Number of chunks the node has accepted so far
+    pub fn uploaded_chunks(&self) -> usize {
+        self.chunks.iter().flatten().count()
+    }
+
+    /// Total number of chunks in the file
+    pub fn total_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Whether the upload has finished and the file root has been stored
+    pub fn is_complete(&self) -> bool {
+        self.completed.is_some()
+    }
+}
+
+/// Encode a dag-pb node holding a UnixFS file made of the given (CID, size) leaves
+fn encode_unixfs_file_node(links: &[(Vec<u8>, u64)], file_size: u64) -> Vec<u8> {
+    // UnixFS Data { Type = File, filesize, blocksizes }
+    let mut data = vec![0x08, 0x02, 0x18];
+    encode_varint(file_size, &mut data);
+    for (_, size) in links {
+        data.push(0x20);
+        encode_varint(*size, &mut data);
+    }
+
+    // dag-pb requires links to be encoded before data
+    let mut node = Vec::new();
+    for (cid, size) in links {
+        let mut link = vec![0x0a];
+        encode_varint(cid.len() as u64, &mut link);
+        link.extend_from_slice(cid);
+        link.extend_from_slice(&[0x12, 0x00, 0x18]);
+        encode_varint(*size, &mut link);
+
+        node.push(0x12);
+        encode_varint(link.len() as u64, &mut node);
+        node.extend_from_slice(&link);
+    }
+    node.push(0x0a);
+    encode_varint(data.len() as u64, &mut node);
+    node.extend_from_slice(&data);
+    node
+}
+
+fn encode_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Decode a dag-pb node into its UnixFS data and the CIDs it links to, in order
+fn decode_unixfs_node(node: &[u8]) -> std::result::Result<(Vec<u8>, Vec<cid::Cid>), String> {
+    let mut links = Vec::new();
+    let mut unixfs = None;
+    for (field, value) in ProtobufFields::new(node) {
+        match (field?, value) {
+            // PBNode.Links: PBLink { Hash = 1, Name = 2, Tsize = 3 }
+            (2, Some(link)) => {
+                let hash = ProtobufFields::new(link)
+                    .find_map(|(field, value)| matches!(field, Ok(1)).then_some(value).flatten())
+                    .ok_or("link without a hash")?;
+                links.push(cid::Cid::try_from(hash).map_err(|e| format!("invalid link CID: {}", e))?);
+            }
+            // PBNode.Data
+            (1, Some(data)) => unixfs = Some(data),
+            _ => {}
+        }
+    }
+
+    // UnixFS Data { Type = 1, Data = 2, ... }
+    let mut data = Vec::new();
+    for (field, value) in ProtobufFields::new(unixfs.unwrap_or_default()) {
+        if let (2, Some(bytes)) = (field?, value) {
+            data.extend_from_slice(bytes);
+        }
+    }
+    Ok((data, links))
+}
+
+/// Iterates over the fields of a protobuf message, yielding each field number
+/// with the bytes of length-delimited fields
+struct ProtobufFields<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ProtobufFields<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn varint(&mut self) -> std::result::Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self.data.split_first().ok_or("truncated varint")?;
+            self.data = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("varint too long".to_string())
+    }
+
+    fn field(&mut self) -> std::result::Result<(u64, Option<&'a [u8]>), String> {
+        let key = self.varint()?;
+        let value = match key & 7 {
+            0 => {
+                self.varint()?;
+                None
+            }
+            2 => {
+                let len = usize::try_from(self.varint()?).map_err(|_| "field too long")?;
+                if len > self.data.len() {
+                    return Err("truncated field".to_string());
+                }
+                let (value, rest) = self.data.split_at(len);
+                self.data = rest;
+                Some(value)
+            }
+            wire_type => return Err(format!("unsupported wire type {}", wire_type)),
+        };
+        Ok((key >> 3, value))
+    }
+}
+
+impl<'a> Iterator for ProtobufFields<'a> {
+    type Item = (std::result::Result<u64, String>, Option<&'a [u8]>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(match self.field() {
+            Ok((field, value)) => (Ok(field), value),
+            Err(e) => {
+                self.data = &[];
+                (Err(e), None)
+            }
+        })
+    }
+}
+
 /// IPFS file statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IpfsFileStat {
@@ -471,6 +1330,59 @@ mod tests {
         assert!(!invalid_cid.is_valid());
     }
 
+    #[test]
+    fn test_cid_v0_components() {
+        let cid: Cid = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".parse().unwrap();
+        assert_eq!(cid.version(), Some(0));
+        assert_eq!(cid.codec(), Some(0x70));
+        assert_eq!(cid.hash_type(), Some(0x12));
+        assert_eq!(cid.codec, "dag-pb");
+        assert_eq!(cid.multibase, "base58btc");
+    }
+
+    #[test]
+    fn test_cid_v1_components() {
+        use multihash::{Code, MultihashDigest};
+
+        let encoded = cid::Cid::new_v1(0x55, Code::Sha2_256.digest(b"hello")).to_string();
+        assert!(encoded.starts_with("bafkrei"));
+
+        let cid = Cid::parse(&encoded).unwrap();
+        assert_eq!(cid.version(), Some(1));
+        assert_eq!(cid.codec(), Some(0x55));
+        assert_eq!(cid.hash_type(), Some(0x12));
+        assert_eq!(cid.codec, "raw");
+        assert_eq!(cid.hash_algorithm, "sha2-256");
+        assert_eq!(cid.multibase, "base32");
+    }
+
+    #[test]
+    fn test_malformed_cids_rejected() {
+        let encoded_v1 = {
+            use multihash::{Code, MultihashDigest};
+            cid::Cid::new_v1(0x55, Code::Sha2_256.digest(b"hello")).to_string()
+        };
+
+        for malformed in [
+            "",
+            "invalid",
+            // Invalid base58 character
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd0",
+            // Truncated digest
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd",
+            // Passed the old prefix and length check
+            "bafyaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            &format!("{}a", encoded_v1),
+            &format!("!{}", &encoded_v1[1..]),
+        ] {
+            let cid = Cid::new(malformed.to_string());
+            assert!(!cid.is_valid(), "{:?} should be rejected", malformed);
+            assert!(Cid::parse(malformed).is_err());
+            assert_eq!(cid.version(), None);
+            assert_eq!(cid.codec, "unknown");
+        }
+    }
+
     #[test]
     fn test_ipfs_config_default() {
         let config = IpfsConfig::default();
@@ -479,5 +1391,357 @@ mod tests {
         assert_eq!(config.timeout, 300);
         assert!(config.auto_pin);
     }
-}
 
+    #[test]
+    fn test_encode_varint() {
+        let mut out = Vec::new();
+        encode_varint(300, &mut out);
+        assert_eq!(out, vec![0xac, 0x02]);
+    }
+
+    mod mock_node {
+        use std::collections::{HashMap, HashSet};
+        use std::sync::{Arc, Mutex};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::{TcpListener, TcpStream};
+
+        /// Requests observed by the mock node
+        #[derive(Default)]
+        pub struct MockState {
+            pub adds: Vec<Vec<u8>>,
+            pub stats: usize,
+            pub dag_puts: usize,
+            pub blocks: HashSet<String>,
+            /// Fail the add request with this (zero-based) index once
+            pub fail_add_at: Option<usize>,
+            pub cats: usize,
+            /// Answer this many cat requests with 503 before serving `CAT_CONTENT`
+            pub cat_failures: usize,
+            /// Blocks served as a gateway at `/ipfs/<cid>`, by CID
+            pub gateway_blocks: HashMap<String, Vec<u8>>,
+            pub gateway_requests: usize,
+        }
+
+        pub const CAT_CONTENT: &str = "content served by the mock node";
+
+        /// Minimal IPFS HTTP API serving add, block/stat, dag/put and cat, and a
+        /// gateway serving `gateway_blocks`
+        pub async fn spawn(state: Arc<Mutex<MockState>>) -> String {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+
+            tokio::spawn(async move {
+                loop {
+                    let (stream, _) = listener.accept().await.unwrap();
+                    tokio::spawn(handle(stream, state.clone()));
+                }
+            });
+
+            url
+        }
+
+        async fn handle(mut stream: TcpStream, state: Arc<Mutex<MockState>>) {
+            let mut request = Vec::new();
+            let mut buf = [0u8; 8192];
+            let header_end = loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    return;
+                }
+                request.extend_from_slice(&buf[..n]);
+                if let Some(pos) = find(&request, b"\r\n\r\n") {
+                    break pos + 4;
+                }
+            };
+
+            let head = String::from_utf8_lossy(&request[..header_end]).to_string();
+            let content_length = head.lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length").then(|| value.trim().parse::<usize>().ok())?
+                })
+                .unwrap_or(0);
+            while request.len() < header_end + content_length {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let body = &request[header_end..header_end + content_length];
+            let target = head.split_whitespace().nth(1).unwrap_or("/").to_string();
+            let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+
+            if let Some(cid) = path.strip_prefix("/ipfs/") {
+                let block = {
+                    let mut state = state.lock().unwrap();
+                    state.gateway_requests += 1;
+                    state.gateway_blocks.get(cid).cloned()
+                };
+                let (status, block) = match block {
+                    Some(block) => ("200 OK", block),
+                    None => ("404 Not Found", Vec::new()),
+                };
+                let head = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/vnd.ipld.raw\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status, block.len()
+                );
+                let _ = stream.write_all(head.as_bytes()).await;
+                let _ = stream.write_all(&block).await;
+                return;
+            }
+
+            let (status, response) = {
+                let mut state = state.lock().unwrap();
+                match path {
+                    "/api/v0/add" => {
+                        let index = state.adds.len();
+                        let data = part_content(body).to_vec();
+                        state.adds.push(data.clone());
+                        if state.fail_add_at == Some(index) {
+                            state.fail_add_at = None;
+                            ("500 Internal Server Error", String::new())
+                        } else {
+                            let cid = raw_cid(&data);
+                            state.blocks.insert(cid.clone());
+                            ("200 OK", format!("{{\"Name\":\"chunk\",\"Hash\":\"{}\",\"Size\":\"{}\"}}", cid, data.len()))
+                        }
+                    }
+                    "/api/v0/block/stat" => {
+                        state.stats += 1;
+                        let cid = query.split('&')
+                            .find_map(|pair| pair.strip_prefix("arg="))
+                            .unwrap_or("");
+                        if state.blocks.contains(cid) {
+                            ("200 OK", format!("{{\"Key\":\"{}\"}}", cid))
+                        } else {
+                            ("500 Internal Server Error", String::new())
+                        }
+                    }
+                    "/api/v0/cat" => {
+                        state.cats += 1;
+                        if state.cat_failures > 0 {
+                            state.cat_failures -= 1;
+                            ("503 Service Unavailable", String::new())
+                        } else {
+                            ("200 OK", CAT_CONTENT.to_string())
+                        }
+                    }
+                    "/api/v0/dag/put" => {
+                        state.dag_puts += 1;
+                        ("200 OK", format!("{{\"Cid\":{{\"/\":\"{}\"}}}}", raw_cid(part_content(body))))
+                    }
+                    _ => ("404 Not Found", String::new()),
+                }
+            };
+
+            let reply = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status, response.len(), response
+            );
+            let _ = stream.write_all(reply.as_bytes()).await;
+        }
+
+        fn raw_cid(data: &[u8]) -> String {
+            use multihash::{Code, MultihashDigest};
+            cid::Cid::new_v1(0x55, Code::Sha2_256.digest(data)).to_string()
+        }
+
+        /// Extract the content of the single part of a multipart body
+        fn part_content(body: &[u8]) -> &[u8] {
+            let start = find(body, b"\r\n\r\n").map(|p| p + 4).unwrap_or(0);
+            let end = body.windows(4).rposition(|w| w == b"\r\n--").unwrap_or(body.len());
+            &body[start..end.max(start)]
+        }
+
+        fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+            haystack.windows(needle.len()).position(|w| w == needle)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resumable_upload_resends_only_missing_chunks() {
+        use std::io::Write;
+        use std::sync::{Arc, Mutex};
+
+        let state = Arc::new(Mutex::new(mock_node::MockState {
+            fail_add_at: Some(2),
+            ..Default::default()
+        }));
+        let api_url = mock_node::spawn(state.clone()).await;
+
+        let mut temp_file = tempfile::NamedTempFile::new().unwrap();
+        let content: Vec<u8> = (0..4096u32).map(|i| (i % 251) as u8).collect();
+        temp_file.write_all(&content).unwrap();
+
+        let client = IpfsClient::with_config(IpfsConfig {
+            api_url,
+            timeout: 10,
+            ..IpfsConfig::default()
+        }).unwrap();
+        let mut upload = client.upload_file_resumable(temp_file.path(), 1024).await.unwrap();
+        assert_eq!(upload.total_chunks(), 4);
+
+        // The third chunk fails, leaving the first two on the node
+        assert!(upload.upload().await.is_err());
+        assert_eq!(upload.uploaded_chunks(), 2);
+        assert!(!upload.is_complete());
+
+        let file = upload.upload().await.unwrap();
+        assert!(upload.is_complete());
+        assert_eq!(file.size, 4096);
+
+        let state = state.lock().unwrap();
+        // Three adds on the first attempt, then only the two missing chunks
+        assert_eq!(state.adds.len(), 5);
+        assert_eq!(&state.adds[3], &content[2048..3072]);
+        assert_eq!(&state.adds[4], &content[3072..]);
+        assert_eq!(state.stats, 2);
+        assert_eq!(state.dag_puts, 1);
+    }
+
+    /// Returns the URL of a port nothing listens on
+    async fn unreachable_url() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        url
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_second_node() {
+        use std::sync::Mutex;
+
+        let state = Arc::new(Mutex::new(mock_node::MockState::default()));
+        let live_url = mock_node::spawn(state.clone()).await;
+
+        let client = IpfsClient::with_config(IpfsConfig {
+            api_urls: vec![live_url],
+            timeout: 10,
+            ..IpfsConfig::from(unreachable_url().await)
+        }).unwrap();
+        let cid = Cid::new("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string());
+
+        for _ in 0..2 {
+            assert_eq!(client.get_file(&cid).await.unwrap(), mock_node::CAT_CONTENT.as_bytes());
+        }
+        assert!(client.is_online().await);
+        // Failing over is not a retry
+        assert_eq!(client.retry_count(), 0);
+        assert_eq!(state.lock().unwrap().cats, 2);
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_spreads_requests() {
+        use std::sync::Mutex;
+
+        let first = Arc::new(Mutex::new(mock_node::MockState::default()));
+        let second = Arc::new(Mutex::new(mock_node::MockState::default()));
+        let client = IpfsClient::with_config(IpfsConfig {
+            api_url: mock_node::spawn(first.clone()).await,
+            api_urls: vec![mock_node::spawn(second.clone()).await],
+            round_robin: true,
+            timeout: 10,
+            ..IpfsConfig::default()
+        }).unwrap();
+        let cid = Cid::new("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string());
+
+        for _ in 0..4 {
+            client.get_file(&cid).await.unwrap();
+        }
+        assert_eq!(first.lock().unwrap().cats, 2);
+        assert_eq!(second.lock().unwrap().cats, 2);
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        use std::sync::Mutex;
+
+        let state = Arc::new(Mutex::new(mock_node::MockState {
+            cat_failures: 2,
+            ..Default::default()
+        }));
+        let api_url = mock_node::spawn(state.clone()).await;
+
+        let client = IpfsClient::with_config(IpfsConfig {
+            api_url,
+            timeout: 10,
+            retry_base_delay_ms: 1,
+            ..IpfsConfig::default()
+        }).unwrap();
+        let cid = Cid::new("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string());
+
+        let content = client.get_file(&cid).await.unwrap();
+        assert_eq!(content, mock_node::CAT_CONTENT.as_bytes());
+        assert_eq!(client.retry_count(), 2);
+        assert_eq!(state.lock().unwrap().cats, 3);
+
+        // A 404 and a malformed CID fail without being retried
+        assert!(matches!(client.pin_file(&cid).await, Err(ProofError::IpfsError { .. })));
+        assert!(client.get_file(&Cid::new("not-a-cid".to_string())).await.is_err());
+        assert_eq!(client.retry_count(), 2);
+        assert_eq!(state.lock().unwrap().cats, 3);
+    }
+
+    fn block_cid(codec: u64, data: &[u8]) -> cid::Cid {
+        cid::Cid::new_v1(codec, Code::Sha2_256.digest(data))
+    }
+
+    #[tokio::test]
+    async fn test_gateway_fallback_when_api_is_down() {
+        use std::sync::Mutex;
+
+        let leaves: [&[u8]; 2] = [b"served by the gateway, ", b"one verified block at a time"];
+        let leaf_cids: Vec<cid::Cid> = leaves.iter().map(|leaf| block_cid(RAW_CODEC, leaf)).collect();
+        let links: Vec<(Vec<u8>, u64)> = leaf_cids.iter().zip(leaves)
+            .map(|(cid, leaf)| (cid.to_bytes(), leaf.len() as u64))
+            .collect();
+        let root = encode_unixfs_file_node(&links, leaves.iter().map(|leaf| leaf.len() as u64).sum());
+        let root_cid = block_cid(DAG_PB_CODEC, &root);
+
+        let state = Arc::new(Mutex::new(mock_node::MockState::default()));
+        {
+            let mut state = state.lock().unwrap();
+            state.gateway_blocks.insert(root_cid.to_string(), root);
+            for (cid, leaf) in leaf_cids.iter().zip(leaves) {
+                state.gateway_blocks.insert(cid.to_string(), leaf.to_vec());
+            }
+        }
+        let gateway_url = mock_node::spawn(state.clone()).await;
+        let config = IpfsConfig {
+            gateway_url,
+            gateway_fallback: true,
+            timeout: 10,
+            max_retries: 0,
+            ..IpfsConfig::from(unreachable_url().await)
+        };
+
+        let client = IpfsClient::with_config(config.clone()).unwrap();
+        let content = client.get_file(&Cid::new(root_cid.to_string())).await.unwrap();
+        assert_eq!(content, b"served by the gateway, one verified block at a time");
+        assert_eq!(state.lock().unwrap().gateway_requests, 3);
+
+        // Without the fallback, the gateway is not asked
+        let client = IpfsClient::with_config(IpfsConfig { gateway_fallback: false, ..config }).unwrap();
+        assert!(client.get_file(&Cid::new(root_cid.to_string())).await.is_err());
+        assert_eq!(state.lock().unwrap().gateway_requests, 3);
+    }
+
+    #[tokio::test]
+    async fn test_gateway_fallback_rejects_wrong_bytes() {
+        use std::sync::Mutex;
+
+        let cid = block_cid(RAW_CODEC, b"the requested content");
+        let state = Arc::new(Mutex::new(mock_node::MockState::default()));
+        state.lock().unwrap().gateway_blocks.insert(cid.to_string(), b"some other content".to_vec());
+
+        let client = IpfsClient::with_config(IpfsConfig {
+            gateway_url: mock_node::spawn(state.clone()).await,
+            gateway_fallback: true,
+            timeout: 10,
+            max_retries: 0,
+            ..IpfsConfig::from(unreachable_url().await)
+        }).unwrap();
+
+        let error = client.get_file(&Cid::new(cid.to_string())).await.unwrap_err();
+        assert!(matches!(error, ProofError::IpfsError { .. }));
+        assert!(error.to_string().contains("does not hash to"));
+    }
+}
@@ -0,0 +1,347 @@
+//! JSONPath selection for structured documents
+//!
+//! `ContentSelection::JsonPath` proves a single value inside a JSON document,
+//! such as one invoice total, without revealing the rest of it. The host
+//! resolves the path to the byte span of the value in the document exactly as
+//! stored, and the guest checks that those bytes form a JSON value.
+//!
+//! The proven content is the value in canonical form: the bytes of the span with
+//! whitespace outside of strings removed. The value is never re-serialized, so
+//! its numbers and string escapes stay exactly as written, while the same value
+//! proves identically from pretty-printed and minified documents.
+//!
+//! Supported paths are `$` followed by any number of `.key`, `['key']`,
+//! `["key"]` and `[index]` segments.
+
+use crate::error::{ProofError, Result};
+use std::ops::Range;
+
+/// A single step of a JSONPath
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    /// Member of an object
+    Key(String),
+    /// Element of an array
+    Index(usize),
+}
+
+/// Parses a JSONPath such as `$.items[0].price` into its segments
+pub fn parse_path(path: &str) -> Result<Vec<PathSegment>> {
+    let invalid = |reason: &str| {
+        ProofError::content_selection_error(format!("Invalid JSON path '{}': {}", path, reason))
+    };
+
+    let mut rest = path.strip_prefix('$').ok_or_else(|| invalid("must start with '$'"))?;
+    let mut segments = Vec::new();
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err(invalid("empty key"));
+            }
+            segments.push(PathSegment::Key(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            match after.chars().next() {
+                Some(quote @ ('\'' | '"')) => {
+                    let body = &after[1..];
+                    let close = body.find(quote).ok_or_else(|| invalid("unterminated key"))?;
+                    segments.push(PathSegment::Key(body[..close].to_string()));
+                    rest = body[close + 1..]
+                        .strip_prefix(']')
+                        .ok_or_else(|| invalid("expected ']' after quoted key"))?;
+                }
+                _ => {
+                    let close = after.find(']').ok_or_else(|| invalid("unterminated index"))?;
+                    let index = after[..close].trim().parse()
+                        .map_err(|_| invalid("index must be a non-negative integer"))?;
+                    segments.push(PathSegment::Index(index));
+                    rest = &after[close + 1..];
+                }
+            }
+        } else {
+            return Err(invalid("expected '.' or '['"));
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Resolves `path` to the byte span of its value in `document`
+///
+/// The span covers the value only, without surrounding whitespace, and is
+/// checked to hold a valid JSON value.
+pub fn resolve_span(document: &[u8], path: &str) -> Result<Range<usize>> {
+    let segments = parse_path(path)?;
+    let not_found = |detail: String| {
+        ProofError::content_selection_error(format!("JSON path '{}' not found: {}", path, detail))
+    };
+
+    let mut scanner = Scanner { data: document, pos: 0 };
+    for segment in &segments {
+        let found = match segment {
+            PathSegment::Key(key) => {
+                if scanner.peek() != Some(b'{') {
+                    return Err(not_found(format!("value before '{}' is not an object", key)));
+                }
+                scanner.pos += 1;
+                scanner.find_member(key)?
+            }
+            PathSegment::Index(index) => {
+                if scanner.peek() != Some(b'[') {
+                    return Err(not_found(format!("value before [{}] is not an array", index)));
+                }
+                scanner.pos += 1;
+                scanner.find_element(*index)?
+            }
+        };
+        if !found {
+            return Err(not_found(match segment {
+                PathSegment::Key(key) => format!("no key '{}'", key),
+                PathSegment::Index(index) => format!("no element {}", index),
+            }));
+        }
+    }
+
+    let span = scanner.skip_value()?;
+    canonicalize(&document[span.clone()])?;
+    Ok(span)
+}
+
+/// Returns the canonical form of a JSON value, failing if `value` is not one
+///
+/// Must match `canonicalize_json` in the guest program.
+pub fn canonicalize(value: &[u8]) -> Result<Vec<u8>> {
+    serde_json::from_slice::<serde_json::Value>(value)
+        .map_err(|e| ProofError::content_selection_error(
+            format!("Selected bytes are not a JSON value: {}", e)
+        ))?;
+
+    let mut canonical = Vec::with_capacity(value.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for &byte in value {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+            }
+            canonical.push(byte);
+        } else if byte == b'"' {
+            in_string = true;
+            canonical.push(byte);
+        } else if !is_whitespace(byte) {
+            canonical.push(byte);
+        }
+    }
+    Ok(canonical)
+}
+
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+/// Walks a JSON document, tracking byte positions
+///
+/// Only structure is checked while walking; the selected value is validated
+/// separately by [`canonicalize`].
+struct Scanner<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    /// Returns the next non-whitespace byte without consuming it
+    fn peek(&mut self) -> Option<u8> {
+        while self.data.get(self.pos).copied().is_some_and(is_whitespace) {
+            self.pos += 1;
+        }
+        self.data.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        if self.peek() != Some(byte) {
+            return Err(self.error(format!("expected '{}'", byte as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Scans the members of an object after its `{`, stopping after the key `key`
+    fn find_member(&mut self, key: &str) -> Result<bool> {
+        if self.peek() == Some(b'}') {
+            return Ok(false);
+        }
+        loop {
+            let key_span = self.skip_string()?;
+            let name: String = serde_json::from_slice(&self.data[key_span])
+                .map_err(|e| self.error(format!("invalid object key: {}", e)))?;
+            self.expect(b':')?;
+            if name == key {
+                return Ok(true);
+            }
+            self.skip_value()?;
+            if !self.next_item(b'}')? {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Scans the elements of an array after its `[`, stopping before element `index`
+    fn find_element(&mut self, index: usize) -> Result<bool> {
+        if self.peek() == Some(b']') {
+            return Ok(false);
+        }
+        for _ in 0..index {
+            self.skip_value()?;
+            if !self.next_item(b']')? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Consumes a `,` (returning true) or the closing bracket `close` (returning false)
+    fn next_item(&mut self, close: u8) -> Result<bool> {
+        match self.peek() {
+            Some(b',') => {
+                self.pos += 1;
+                Ok(true)
+            }
+            Some(byte) if byte == close => {
+                self.pos += 1;
+                Ok(false)
+            }
+            _ => Err(self.error(format!("expected ',' or '{}'", close as char))),
+        }
+    }
+
+    /// Skips one value, returning its span
+    fn skip_value(&mut self) -> Result<Range<usize>> {
+        let start = match self.peek() {
+            Some(_) => self.pos,
+            None => return Err(self.error("unexpected end of document".to_string())),
+        };
+
+        match self.data[start] {
+            b'{' => {
+                self.pos += 1;
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        self.skip_string()?;
+                        self.expect(b':')?;
+                        self.skip_value()?;
+                        if !self.next_item(b'}')? {
+                            break;
+                        }
+                    }
+                }
+            }
+            b'[' => {
+                self.pos += 1;
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        self.skip_value()?;
+                        if !self.next_item(b']')? {
+                            break;
+                        }
+                    }
+                }
+            }
+            b'"' => {
+                self.skip_string()?;
+            }
+            _ => {
+                while self.data.get(self.pos)
+                    .is_some_and(|&byte| !is_whitespace(byte) && !matches!(byte, b',' | b']' | b'}'))
+                {
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return Err(self.error("expected a value".to_string()));
+                }
+            }
+        }
+
+        Ok(start..self.pos)
+    }
+
+    /// Skips a string, returning its span including the quotes
+    fn skip_string(&mut self) -> Result<Range<usize>> {
+        if self.peek() != Some(b'"') {
+            return Err(self.error("expected a string".to_string()));
+        }
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.data.get(self.pos) {
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(start..self.pos);
+                }
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("unterminated string".to_string())),
+            }
+        }
+    }
+
+    fn error(&self, message: String) -> ProofError {
+        ProofError::content_selection_error(
+            format!("Malformed JSON at byte {}: {}", self.pos, message)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("$.items[0]['unit price']").unwrap(),
+            vec![
+                PathSegment::Key("items".to_string()),
+                PathSegment::Index(0),
+                PathSegment::Key("unit price".to_string()),
+            ]
+        );
+        assert!(parse_path("items[0]").is_err());
+        assert!(parse_path("$.items[x]").is_err());
+    }
+
+    #[test]
+    fn test_span_binds_exact_bytes() {
+        let pretty = br#"{
+            "customer": {"name": "A \"quoted\" name"},
+            "items": [
+                { "sku": "A-1", "price": 19.990 },
+                { "sku": "B-2", "price": { "amount": 5, "currency": "EUR" } }
+            ]
+        }"#;
+        let minified = br#"{"customer":{"name":"A \"quoted\" name"},"items":[{"sku":"A-1","price":19.990},{"sku":"B-2","price":{"amount":5,"currency":"EUR"}}]}"#;
+
+        let span = resolve_span(pretty, "$.items[0].price").unwrap();
+        assert_eq!(&pretty[span], b"19.990");
+
+        let pretty_span = resolve_span(pretty, "$.items[1].price").unwrap();
+        let minified_span = resolve_span(minified, "$.items[1].price").unwrap();
+        assert_eq!(
+            canonicalize(&pretty[pretty_span]).unwrap(),
+            canonicalize(&minified[minified_span]).unwrap()
+        );
+
+        assert!(resolve_span(minified, "$.items[2]").is_err());
+        assert!(resolve_span(minified, "$.customer.email").is_err());
+        assert!(resolve_span(minified, "$.items.sku").is_err());
+    }
+}
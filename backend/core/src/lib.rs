@@ -11,6 +11,7 @@
 //! - **Flexible Content Selection**: Prove byte ranges, patterns, or multiple content selections
 //! - **High Performance**: Optimized for large files with streaming processing
 //! - **Security**: Cryptographic guarantees with 128-bit security level
+//! - **Browser Verification**: Verify proofs in WebAssembly with the `wasm` feature
 //!
 //! ## Quick Start
 //!
@@ -41,30 +42,82 @@
 
 pub mod error;
 pub mod types;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod proof;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod ipfs;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod ipfs_client;
 pub mod verifier;
 pub mod error_patterns;
 pub mod monitoring;
 pub mod profiling;
 pub mod i18n;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod performance;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod memory;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod cache;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bonsai;
+pub mod car;
+pub mod json_path;
+pub mod mime;
+pub mod presets;
 pub mod proof_types;
 pub mod ecosystem_integration;
 pub mod advanced_verification;
+pub mod trust;
+pub mod signing;
+pub mod diff;
+pub mod canonical;
+pub mod timestamp;
+pub mod commitment;
+pub mod bundle;
+pub mod partial;
+pub mod redact;
+pub mod secret;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod store;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub mod wasm;
 
 // Re-export main types for convenience
 pub use error::{ProofError, Result};
+#[cfg(not(target_arch = "wasm32"))]
 pub use proof::{ProofGenerator, ProofConfig};
 pub use types::{
     ContentSelection, IpfsBlock, Proof, ProofMetadata, 
-    ProofInput, ProofOutput, BlockLink
+    ProofInput, ProofOutput, BlockLink, ReceiptKind, Bytes, Millis,
+    ChunkingStrategy, ProofExtension, PreflightReport, SelectionSpan,
+    ProofEstimate, HashAlgorithm, ProofFormat, ProofSignature, ContentSelectionBuilder
 };
-pub use verifier::ProofVerifier;
+pub use verifier::{
+    ProofVerifier, VerificationConfig, VerificationRule, VerificationRuleType, ClosureRule, Preset,
+    StructureReport, AcceptedSystem,
+};
+pub use types::{VerificationResult, VerificationFailure, VerifierInfo, VerificationMethod};
+#[cfg(not(target_arch = "wasm32"))]
 pub use performance::{PerformanceMonitor, OptimizationSettings, PerformanceStats};
-pub use cache::{CacheManager, CacheConfig, CacheKey};
+#[cfg(not(target_arch = "wasm32"))]
+pub use cache::{CacheManager, CacheConfig, CacheKey, CacheReport};
+#[cfg(not(target_arch = "wasm32"))]
+pub use bonsai::{BonsaiCredentials, BonsaiCredentialStore, BonsaiProver};
+pub use trust::{TrustScore, TrustWeights, TrustFactor};
+pub use signing::{SigningKey, VerifyingKey};
+pub use diff::{ProofDiff, FieldDiff};
+pub use canonical::CanonicalProof;
+pub use timestamp::TimestampToken;
+pub use commitment::ContentOpening;
+pub use bundle::ProofBundle;
+pub use partial::PartialDag;
+pub use redact::RedactionOptions;
+pub use secret::Secret;
+#[cfg(not(target_arch = "wasm32"))]
+pub use store::{ProofStore, FileSystemStore, HashKind, MemoryStore};
+pub use proof_types::{ProofType, ProofScheme, ProofTypeRegistry};
+pub use monitoring::{EventBus, MonitorEvent, Sink};
 
 // Re-export guest program types for host-guest communication
 pub use crate::guest_types::*;
@@ -76,12 +129,56 @@ mod guest_types {
     /// Input data structure for the ZK circuit
     #[derive(Serialize, Deserialize, Clone, Debug)]
     pub struct ProofInput {
-        /// IPFS blocks that form the complete file structure
+        /// IPFS blocks that form the complete file structure, in any order: the
+        /// guest reads them in the canonical order of `IpfsProcessor::canonical_order`
         pub blocks: Vec<IpfsBlock>,
         /// Specification of which content to prove exists
         pub content_selection: ContentSelection,
         /// Expected content hash for verification
         pub expected_content_hash: [u8; 32],
+        /// Hash function the content hash is computed with
+        pub hash_algorithm: HashAlgorithm,
+        /// Secret blinding; when set, the guest outputs a commitment to the
+        /// content hash instead of the hash (see [`crate::commitment`])
+        pub commitment_blinding: Option<[u8; 32]>,
+        /// Set when `blocks` is only the root block and some of its leaves
+        /// (see [`crate::partial`])
+        pub materialization: Option<Materialization>,
+    }
+
+    /// Which leaves of a partly materialized DAG the guest is given
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+    pub struct Materialization {
+        /// Positions in the root block's links of the leaves following it in
+        /// `blocks`, ascending
+        pub leaf_indices: Vec<u32>,
+    }
+
+    /// How much of the DAG the guest was given to prove from
+    #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Coverage {
+        /// Blocks whose data was supplied
+        pub materialized_blocks: u32,
+        /// Blocks in the DAG
+        pub total_blocks: u32,
+        /// Bytes of block data supplied
+        pub materialized_bytes: u64,
+        /// Bytes of block data in the DAG
+        pub total_bytes: u64,
+    }
+
+    /// Hash function used for content hashes
+    ///
+    /// Block CIDs and the root hash always use SHA-256, as IPFS does.
+    #[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub enum HashAlgorithm {
+        /// SHA-256
+        #[default]
+        Sha256,
+        /// SHA-512/256: SHA-512 with its own initial values, truncated to 32 bytes
+        Sha512_256,
+        /// BLAKE3 with a 32-byte output
+        Blake3,
     }
 
     /// Represents an IPFS block with its data and metadata
@@ -119,6 +216,31 @@ mod guest_types {
         XPath { selector: String },
         /// Prove multiple content selections
         Multiple(Vec<ContentSelection>),
+        /// Prove a large pattern exists, given only its SHA-256 digest, length and
+        /// rolling fingerprint, so the pattern itself never enters the guest
+        PatternHash { hash: [u8; 32], len: usize, fingerprint: u64 },
+        /// Prove the value at a JSONPath (such as `$.items[0].price`) exists in a
+        /// JSON document; resolved to a `JsonSpan` on the host
+        JsonPath { path: String },
+        /// Prove the bytes `start..end` hold a JSON value, whose canonical form
+        /// is the proven content
+        JsonSpan { start: usize, end: usize },
+        /// Prove several ascending, disjoint byte ranges `(start, end)` with one
+        /// inclusion proof; the content hash commits to the ranges as well as
+        /// their bytes (see [`crate::types::multi_range_message`])
+        MultiRange(Vec<(usize, usize)>),
+        /// Prove the object of a PDF page (zero-based, in file order); resolved
+        /// to a `ByteRange` on the host (see [`crate::presets`])
+        PdfPage { page: usize },
+        /// Prove one cell of a CSV document (zero-based row and column);
+        /// resolved to a `ByteRange` on the host
+        CsvCell { row: usize, column: usize },
+        /// Prove the value of an EXIF tag in a JPEG image; resolved to a
+        /// `ByteRange` on the host
+        ExifField { tag: u16 },
+        /// Prove the bytes from `start_pct` to `end_pct` percent of the file's
+        /// content; resolved to a `ByteRange` on the host
+        PercentRange { start_pct: f64, end_pct: f64 },
     }
 
     /// Output data structure from the ZK circuit
@@ -128,10 +250,25 @@ mod guest_types {
         pub root_hash: [u8; 32],
         /// Hash of the proven content
         pub content_hash: [u8; 32],
-        /// Merkle proof demonstrating content inclusion
-        pub inclusion_proof: Vec<[u8; 32]>,
+        /// Inclusion path of each selection, one per sub-selection of a
+        /// `Multiple` and otherwise one; each path holds the hashes of the
+        /// blocks it spans in file order, each once
+        pub inclusion_paths: Vec<Vec<[u8; 32]>>,
         /// Metadata about the proof
         pub metadata: ProofMetadata,
+        /// Hash function the content hash was computed with
+        pub hash_algorithm: HashAlgorithm,
+        /// Total length of the block data, which every selected byte range lies within
+        pub content_length: u64,
+        /// Hash of the content selection that was proven (see
+        /// [`ContentSelection::selection_hash`])
+        pub selection_hash: [u8; 32],
+        /// Part of the DAG the proof was generated from
+        pub coverage: Coverage,
+        /// SHA-256 digest of the file's raw CID, which for a whole DAG is the
+        /// SHA-256 of its leaves' data and for a partial one that of its root
+        /// block (see [`crate::verifier::ProofVerifier::verify_against_cid`])
+        pub cid_digest: [u8; 32],
     }
 
     /// Metadata about the generated proof
@@ -150,7 +287,7 @@ mod guest_types {
 pub use zkipfs_proof_methods::IPFS_CONTENT_VERIFIER_ELF;
 pub use zkipfs_proof_methods::IPFS_CONTENT_VERIFIER_ID;
 
-#[cfg(test)]
+#[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
     use super::*;
     use tempfile::NamedTempFile;
@@ -0,0 +1,228 @@
+//! Process memory measurement for zkIPFS-Proof
+//!
+//! This module reports the resident memory of the current process on Linux,
+//! macOS and Windows, and provides a background sampler that records the peak
+//! resident memory over an operation such as proof generation.
+//!
+//! On Linux, when the process runs alone in a cgroup v2 group (the usual case
+//! for a container entrypoint), the group's `memory.current` and `memory.peak`
+//! are used, since they are what the container's memory limit is enforced on.
+//! Otherwise `VmRSS`/`VmHWM` from `/proc/self/status` are used.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Default interval between memory samples
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Returns the current resident memory of the process in bytes, if it can be measured
+pub fn current_rss() -> Option<u64> {
+    platform::current_rss()
+}
+
+/// Returns the highest resident memory of the process so far in bytes, if the OS reports it
+pub fn peak_rss() -> Option<u64> {
+    platform::peak_rss()
+}
+
+/// Samples resident memory on a background thread and records the peak
+///
+/// The sampler stops when [`finish`](Self::finish) is called or it is dropped.
+pub struct MemorySampler {
+    peak: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MemorySampler {
+    /// Starts sampling with the default interval
+    pub fn start() -> Self {
+        Self::with_interval(DEFAULT_SAMPLE_INTERVAL)
+    }
+
+    /// Starts sampling every `interval`
+    pub fn with_interval(interval: Duration) -> Self {
+        let peak = Arc::new(AtomicU64::new(current_rss().unwrap_or(0)));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let peak = peak.clone();
+            let stop = stop.clone();
+            std::thread::Builder::new()
+                .name("memory-sampler".to_string())
+                .spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        if let Some(rss) = current_rss() {
+                            peak.fetch_max(rss, Ordering::Relaxed);
+                        }
+                        std::thread::sleep(interval);
+                    }
+                })
+                .ok()
+        };
+
+        Self { peak, stop, handle }
+    }
+
+    /// Returns the peak observed so far in bytes
+    pub fn peak(&self) -> u64 {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    /// Stops sampling and returns the peak resident memory in bytes (0 if unmeasurable)
+    pub fn finish(mut self) -> u64 {
+        self.stop_thread();
+        if let Some(rss) = current_rss() {
+            self.peak.fetch_max(rss, Ordering::Relaxed);
+        }
+        self.peak()
+    }
+
+    fn stop_thread(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for MemorySampler {
+    fn drop(&mut self) {
+        self.stop_thread();
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::path::PathBuf;
+
+    pub fn current_rss() -> Option<u64> {
+        exclusive_cgroup()
+            .and_then(|dir| read_bytes(dir.join("memory.current")))
+            .or_else(|| proc_status_kb("VmRSS:"))
+    }
+
+    pub fn peak_rss() -> Option<u64> {
+        exclusive_cgroup()
+            .and_then(|dir| read_bytes(dir.join("memory.peak")))
+            .or_else(|| proc_status_kb("VmHWM:"))
+    }
+
+    /// Returns the cgroup v2 directory of this process if no other process shares it
+    fn exclusive_cgroup() -> Option<PathBuf> {
+        let membership = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+        let path = membership.lines().find_map(|line| line.strip_prefix("0::"))?;
+        let dir = PathBuf::from("/sys/fs/cgroup").join(path.trim_start_matches('/'));
+
+        let procs = std::fs::read_to_string(dir.join("cgroup.procs")).ok()?;
+        let pid = std::process::id().to_string();
+        let mut members = procs.lines().filter(|line| !line.is_empty());
+        match (members.next(), members.next()) {
+            (Some(only), None) if only.trim() == pid => Some(dir),
+            _ => None,
+        }
+    }
+
+    fn read_bytes(path: PathBuf) -> Option<u64> {
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    fn proc_status_kb(field: &str) -> Option<u64> {
+        let contents = std::fs::read_to_string("/proc/self/status").ok()?;
+        contents.lines()
+            .find(|line| line.starts_with(field))
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows_sys::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows_sys::Win32::System::Threading::GetCurrentProcess;
+
+    fn counters() -> Option<PROCESS_MEMORY_COUNTERS> {
+        let mut counters: PROCESS_MEMORY_COUNTERS = unsafe { std::mem::zeroed() };
+        let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        // SAFETY: the pseudo handle from GetCurrentProcess is always valid and
+        // `counters` is a properly sized, writable PROCESS_MEMORY_COUNTERS.
+        let ok = unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, size) };
+        (ok != 0).then_some(counters)
+    }
+
+    pub fn current_rss() -> Option<u64> {
+        counters().map(|c| c.WorkingSetSize as u64)
+    }
+
+    pub fn peak_rss() -> Option<u64> {
+        counters().map(|c| c.PeakWorkingSetSize as u64)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    pub fn current_rss() -> Option<u64> {
+        let mut info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+        let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+        // SAFETY: `info` is a writable proc_taskinfo of `size` bytes, which is
+        // what PROC_PIDTASKINFO fills in for a pid of this process.
+        let written = unsafe {
+            libc::proc_pidinfo(
+                libc::getpid(),
+                libc::PROC_PIDTASKINFO,
+                0,
+                (&mut info as *mut libc::proc_taskinfo).cast(),
+                size,
+            )
+        };
+        (written == size).then_some(info.pti_resident_size)
+    }
+
+    pub fn peak_rss() -> Option<u64> {
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        // SAFETY: `usage` is a writable rusage for getrusage to fill in.
+        let ok = unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } == 0;
+        // ru_maxrss is in bytes on macOS
+        ok.then_some(usage.ru_maxrss as u64)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+mod platform {
+    pub fn current_rss() -> Option<u64> {
+        None
+    }
+
+    pub fn peak_rss() -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+    fn test_sampler_sees_large_allocation() {
+        const BUFFER_SIZE: usize = 256 * 1024 * 1024;
+
+        let sampler = MemorySampler::with_interval(Duration::from_millis(5));
+        let buffer = vec![1u8; BUFFER_SIZE];
+        std::thread::sleep(Duration::from_millis(50));
+        let peak = sampler.finish();
+
+        assert_eq!(buffer[BUFFER_SIZE - 1], 1);
+        assert!(peak > BUFFER_SIZE as u64, "peak {} should exceed {}", peak, BUFFER_SIZE);
+    }
+
+    #[test]
+    fn test_peak_not_below_current() {
+        if let (Some(current), Some(peak)) = (current_rss(), peak_rss()) {
+            assert!(peak >= current);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! MIME type detection for proven files
+//!
+//! Files are identified by their magic bytes first, so a mislabeled or
+//! extensionless file still gets its real type. The extension is only
+//! consulted when the content is not recognised.
+
+use std::path::Path;
+
+/// Bytes of a file that are enough to recognise its type
+pub const SNIFF_LEN: usize = 8192;
+
+/// Detects the MIME type of `content`, read from a file named `path`
+///
+/// Tries the content's magic bytes, then the file extension, then whether
+/// the content looks like JSON or text, and finally falls back to
+/// `application/octet-stream`. Only the first [`SNIFF_LEN`] bytes are used.
+pub fn detect_mime_type(path: &Path, content: &[u8]) -> String {
+    let sample = &content[..content.len().min(SNIFF_LEN)];
+    sniff(sample)
+        .or_else(|| from_extension(path))
+        .or_else(|| from_text(sample))
+        .unwrap_or("application/octet-stream")
+        .to_string()
+}
+
+/// Recognises file formats by their magic bytes
+pub fn sniff(content: &[u8]) -> Option<&'static str> {
+    infer::get(content).map(|kind| kind.mime_type())
+}
+
+/// Maps common file extensions to their MIME type
+pub fn from_extension(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    Some(match extension.as_str() {
+        "txt" => "text/plain",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        "zip" => "application/zip",
+        _ => return None,
+    })
+}
+
+fn from_text(content: &[u8]) -> Option<&'static str> {
+    if content.is_empty() || !content.is_ascii() {
+        None
+    } else if content.starts_with(b"{") || content.starts_with(b"[") {
+        Some("application/json")
+    } else {
+        Some("text/plain")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x01\0\0\0\x01\x08\x06\0\0\0\x1f\x15\xc4\x89";
+
+    #[test]
+    fn test_content_wins_over_extension() {
+        assert_eq!(detect_mime_type(Path::new("image.dat"), PNG), "image/png");
+        assert_eq!(detect_mime_type(Path::new("report.txt"), b"%PDF-1.7\n"), "application/pdf");
+        assert_eq!(detect_mime_type(Path::new("photo"), b"\xFF\xD8\xFF\xE0\0\x10JFIF\0"), "image/jpeg");
+    }
+
+    #[test]
+    fn test_fallbacks() {
+        assert_eq!(detect_mime_type(Path::new("notes.md"), b"# Title"), "text/markdown");
+        assert_eq!(detect_mime_type(Path::new("data"), b"{\"a\": 1}"), "application/json");
+        assert_eq!(detect_mime_type(Path::new("blob"), &[0x00, 0xFE, 0x81, 0x7F]), "application/octet-stream");
+    }
+}
@@ -4,6 +4,8 @@
 //! capabilities for performance tracking and debugging.
 
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
@@ -381,6 +383,186 @@ pub struct PerformanceSummary {
     pub max_duration: Duration,
 }
 
+/// Future returned by a component health check
+pub type HealthCheckFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
+type HealthCheck = Arc<dyn Fn() -> HealthCheckFuture + Send + Sync>;
+
+/// Whether a component, or the service as a whole, can serve requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+/// Outcome of one component's health check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentHealth {
+    /// Name the component registered with
+    pub name: String,
+    /// Component status
+    pub status: HealthStatus,
+    /// Why the check failed, if it did
+    pub error: Option<String>,
+    /// Time the check took, in milliseconds
+    pub duration_ms: u64,
+}
+
+/// Readiness of the service, with the status of every registered component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    /// `Up` only when every component is up
+    pub status: HealthStatus,
+    /// Per-component results, in registration order
+    pub components: Vec<ComponentHealth>,
+}
+
+impl ReadinessReport {
+    /// Returns true if every component is up
+    pub fn is_ready(&self) -> bool {
+        self.status == HealthStatus::Up
+    }
+}
+
+/// Aggregates component health checks into a readiness report
+///
+/// Components such as the database, prover or IPFS node register an async
+/// check under a name; [`check_readiness`](Self::check_readiness) runs them
+/// all. Clones share the same set of checks.
+#[derive(Clone, Default)]
+pub struct HealthChecker {
+    checks: Arc<Mutex<Vec<(String, HealthCheck)>>>,
+}
+
+impl HealthChecker {
+    /// Create a health checker with no components
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a component check, replacing any check with the same name
+    pub fn register<F, Fut>(&self, name: &str, check: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let check: HealthCheck = Arc::new(move || Box::pin(check()) as HealthCheckFuture);
+        let mut checks = self.checks.lock().unwrap_or_else(|e| e.into_inner());
+        match checks.iter_mut().find(|(existing, _)| existing == name) {
+            Some(entry) => entry.1 = check,
+            None => checks.push((name.to_string(), check)),
+        }
+    }
+
+    /// Names of the registered components
+    pub fn components(&self) -> Vec<String> {
+        let checks = self.checks.lock().unwrap_or_else(|e| e.into_inner());
+        checks.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// Run every component check and report overall readiness
+    pub async fn check_readiness(&self) -> ReadinessReport {
+        let checks = self.checks.lock().unwrap_or_else(|e| e.into_inner()).clone();
+
+        let mut components = Vec::with_capacity(checks.len());
+        for (name, check) in checks {
+            let started = Instant::now();
+            let result = check().await;
+            let duration_ms = started.elapsed().as_millis() as u64;
+
+            if let Err(error) = &result {
+                warn!("Health check for {} failed: {}", name, error);
+            }
+            components.push(ComponentHealth {
+                name,
+                status: if result.is_ok() { HealthStatus::Up } else { HealthStatus::Down },
+                error: result.err(),
+                duration_ms,
+            });
+        }
+
+        let status = if components.iter().all(|c| c.status == HealthStatus::Up) {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Down
+        };
+        ReadinessReport { status, components }
+    }
+}
+
+/// Something the core did, delivered to every [`Sink`] registered on an [`EventBus`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonitorEvent {
+    /// Proof generation began
+    ProofStarted,
+    /// A proof was generated, taking `duration` and `cycles` guest cycles
+    ProofCompleted { duration: Duration, cycles: u64 },
+    /// A proof was verified, successfully or not
+    VerificationCompleted { valid: bool },
+    /// A proof or verification result was served from a cache
+    CacheHit,
+    /// A cache was consulted and did not have the entry
+    CacheMiss,
+    /// An IPFS request failed after any retries
+    IpfsError { message: String },
+}
+
+/// Receiver of [`MonitorEvent`]s, such as an adapter to an embedder's telemetry
+///
+/// Sinks are called synchronously on the thread that emitted the event, so
+/// they should hand events off rather than block.
+pub trait Sink: Send + Sync {
+    /// Handles one event
+    fn on_event(&self, event: &MonitorEvent);
+}
+
+impl<F> Sink for F
+where
+    F: Fn(&MonitorEvent) + Send + Sync,
+{
+    fn on_event(&self, event: &MonitorEvent) {
+        self(event)
+    }
+}
+
+/// Delivers events to registered sinks
+///
+/// `ProofGenerator`, `ProofVerifier` and `IpfsClient` each emit to a bus;
+/// pass the same bus to all of them to collect every event in one place.
+/// Clones share the same set of sinks.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    sinks: Arc<Mutex<Vec<Arc<dyn Sink>>>>,
+}
+
+impl EventBus {
+    /// Create a bus with no sinks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a sink to receive every later event
+    pub fn register(&self, sink: impl Sink + 'static) {
+        self.sinks.lock().unwrap_or_else(|e| e.into_inner()).push(Arc::new(sink));
+    }
+
+    /// Deliver `event` to every registered sink, in registration order
+    pub fn emit(&self, event: MonitorEvent) {
+        let sinks = self.sinks.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        for sink in sinks {
+            sink.on_event(&event);
+        }
+    }
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sinks = self.sinks.lock().unwrap_or_else(|e| e.into_inner()).len();
+        f.debug_struct("EventBus").field("sinks", &sinks).finish()
+    }
+}
+
 // System metrics collection functions
 fn get_memory_usage() -> Option<u64> {
     // Implementation would use system APIs to get actual memory usage
@@ -508,5 +690,24 @@ mod tests {
         let summary = monitoring.get_performance_summary("test");
         assert!(summary.is_some());
     }
+
+    #[tokio::test]
+    async fn test_failing_component_fails_readiness() {
+        let health = HealthChecker::new();
+        health.register("prover", || async { Ok(()) });
+        health.register("database", || async { Err("connection refused".to_string()) });
+
+        let report = health.check_readiness().await;
+        assert!(!report.is_ready());
+        assert_eq!(report.components.len(), 2);
+        assert_eq!(report.components[0].status, HealthStatus::Up);
+        assert_eq!(report.components[1].status, HealthStatus::Down);
+        assert_eq!(report.components[1].error.as_deref(), Some("connection refused"));
+
+        // Re-registering a component replaces its check
+        health.register("database", || async { Ok(()) });
+        assert!(health.check_readiness().await.is_ready());
+        assert_eq!(health.components(), vec!["prover", "database"]);
+    }
 }
 
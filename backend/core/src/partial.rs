@@ -0,0 +1,228 @@
+//! Proofs from a partly materialized DAG
+//!
+//! A byte range depends only on the leaves it spans. A [`PartialDag`] holds
+//! the root block and just those leaves; the root's links stand in for the
+//! other leaves by CID and size, which is all the structure hash covers, so
+//! the proof commits the same root hash as one generated from every block.
+//! The guest checks each supplied leaf against its link and reports in
+//! `ProofOutput::coverage` how much of the DAG it was given.
+//!
+//! Only DAGs of one root block linking leaves, as `IpfsProcessor` builds
+//! them, and `ByteRange` and `MultiRange` selections are supported. Offsets
+//! count the root block's data first, as for proofs of the whole DAG.
+
+use crate::{
+    error::{ProofError, Result},
+    types::{ContentSelection, Coverage, IpfsBlock, Materialization},
+};
+
+/// A root block and the subset of its leaves a selection needs
+#[derive(Clone, Debug)]
+pub struct PartialDag {
+    /// Root block, whose links name every leaf of the DAG
+    pub root: IpfsBlock,
+    /// Supplied leaves with their positions in the root's links, ascending
+    pub leaves: Vec<(u32, IpfsBlock)>,
+}
+
+impl PartialDag {
+    /// Keeps the leaves of `blocks`, a root block followed by all of its
+    /// leaves, that `selection` spans
+    pub fn covering(blocks: &[IpfsBlock], selection: &ContentSelection) -> Result<Self> {
+        let ranges = selection_ranges(selection)?;
+        let (root, leaves) = blocks.split_first()
+            .filter(|(root, _)| !root.links.is_empty())
+            .ok_or_else(|| ProofError::invalid_input_error(
+                "blocks",
+                "Partial proofs need a root block linking the file's leaves"
+            ))?;
+        let complete = root.links.len() == leaves.len()
+            && leaves.iter().zip(&root.links).all(|(leaf, link)| leaf.cid == link.cid);
+        if !complete {
+            return Err(ProofError::invalid_input_error(
+                "blocks",
+                "Blocks must be a root block followed by each of its leaves in order"
+            ));
+        }
+
+        let mut offset = root.data.len() as u64;
+        let mut kept = Vec::new();
+        for (index, leaf) in leaves.iter().enumerate() {
+            let end = offset + leaf.data.len() as u64;
+            if ranges.iter().any(|&(start, stop)| (start as u64) < end && (stop as u64) > offset) {
+                kept.push((index as u32, leaf.clone()));
+            }
+            offset = end;
+        }
+
+        Ok(Self { root: root.clone(), leaves: kept })
+    }
+
+    /// Returns how much of the DAG is materialized
+    pub fn coverage(&self) -> Coverage {
+        let root_bytes = self.root.data.len() as u64;
+        Coverage {
+            materialized_blocks: 1 + self.leaves.len() as u32,
+            total_blocks: 1 + self.root.links.len() as u32,
+            materialized_bytes: root_bytes + self.leaves.iter().map(|(_, leaf)| leaf.data.len() as u64).sum::<u64>(),
+            total_bytes: root_bytes + self.root.links.iter().map(|link| link.size).sum::<u64>(),
+        }
+    }
+
+    /// Checks that every supplied leaf is the block its link names
+    pub fn validate(&self) -> Result<()> {
+        let mut previous = None;
+        for (index, leaf) in &self.leaves {
+            if previous.is_some_and(|previous| *index <= previous) {
+                return Err(ProofError::invalid_input_error("leaves", "Leaf positions must be ascending"));
+            }
+            previous = Some(*index);
+
+            let link = self.root.links.get(*index as usize).ok_or_else(|| ProofError::invalid_input_error(
+                "leaves",
+                format!("Leaf {} is not linked from the root block", index)
+            ))?;
+            if leaf.cid != link.cid || leaf.data.len() as u64 != link.size {
+                return Err(ProofError::invalid_input_error(
+                    "leaves",
+                    format!("Leaf {} does not match its link in the root block", index)
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the content a range selection proves, which must lie in supplied blocks
+    pub fn extract_content(&self, selection: &ContentSelection) -> Result<Vec<u8>> {
+        if !selection.is_valid() {
+            return Err(ProofError::content_selection_error("Byte ranges must be non-empty, ascending and disjoint"));
+        }
+        let mut content = Vec::new();
+        for (start, end) in selection_ranges(selection)? {
+            self.extract_range(start as u64, end as u64, &mut content)?;
+        }
+        Ok(content)
+    }
+
+    /// Returns the blocks and materialization the guest is given
+    pub fn guest_input(&self) -> (Vec<IpfsBlock>, Materialization) {
+        let mut blocks = Vec::with_capacity(1 + self.leaves.len());
+        blocks.push(self.root.clone());
+        blocks.extend(self.leaves.iter().map(|(_, leaf)| leaf.clone()));
+        let leaf_indices = self.leaves.iter().map(|(index, _)| *index).collect();
+        (blocks, Materialization { leaf_indices })
+    }
+
+    /// Appends the bytes `start..end` of the block stream to `content`
+    fn extract_range(&self, start: u64, end: u64, content: &mut Vec<u8>) -> Result<()> {
+        let total_bytes = self.coverage().total_bytes;
+        if end > total_bytes {
+            return Err(ProofError::content_selection_error(format!(
+                "Byte range {}..{} extends past the end of the content ({} bytes)",
+                start, end, total_bytes
+            )));
+        }
+
+        let mut append = |data: &[u8], block_start: u64| {
+            let block_end = block_start + data.len() as u64;
+            if block_start < end && block_end > start {
+                let from = start.saturating_sub(block_start) as usize;
+                let to = (end.min(block_end) - block_start) as usize;
+                content.extend_from_slice(&data[from..to]);
+            }
+        };
+
+        append(&self.root.data, 0);
+        let mut offset = self.root.data.len() as u64;
+        let mut supplied = self.leaves.iter().peekable();
+        for (index, link) in self.root.links.iter().enumerate() {
+            let leaf = supplied.next_if(|(position, _)| *position as usize == index);
+            if offset < end && offset + link.size > start {
+                let (_, leaf) = leaf.ok_or_else(|| ProofError::content_selection_error(format!(
+                    "Byte range {}..{} spans leaf {}, which was not supplied",
+                    start, end, index
+                )))?;
+                append(&leaf.data, offset);
+            }
+            offset += link.size;
+        }
+        Ok(())
+    }
+}
+
+impl Coverage {
+    /// Fraction of the DAG's bytes that were materialized, from 0 to 1
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            1.0
+        } else {
+            self.materialized_bytes as f64 / self.total_bytes as f64
+        }
+    }
+
+    /// Whether every block of the DAG was materialized
+    pub fn is_complete(&self) -> bool {
+        self.materialized_blocks == self.total_blocks
+    }
+}
+
+/// Returns the byte ranges of a selection that can be proven from a partial DAG
+fn selection_ranges(selection: &ContentSelection) -> Result<Vec<(usize, usize)>> {
+    match selection {
+        ContentSelection::ByteRange { start, end } => Ok(vec![(*start, *end)]),
+        ContentSelection::MultiRange(ranges) => Ok(ranges.clone()),
+        _ => Err(ProofError::invalid_input_error(
+            "content_selection",
+            "Only byte range selections can be proven from a partial DAG"
+        )),
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::{ipfs::IpfsProcessor, ChunkingStrategy};
+
+    async fn blocks(content: &[u8]) -> Vec<IpfsBlock> {
+        IpfsProcessor::with_chunking(ChunkingStrategy::FixedSize(1024))
+            .await
+            .unwrap()
+            .create_blocks(content)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_covering_keeps_spanned_leaves() {
+        let content: Vec<u8> = (0..64 * 1024u32).map(|i| (i.wrapping_mul(2654435761) >> 7) as u8).collect();
+        let blocks = blocks(&content).await;
+        let offset = blocks[0].data.len();
+        let selection = ContentSelection::ByteRange { start: offset + 5000, end: offset + 6100 };
+
+        let dag = PartialDag::covering(&blocks, &selection).unwrap();
+        dag.validate().unwrap();
+        assert_eq!(dag.leaves.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!(dag.extract_content(&selection).unwrap(), &content[5000..6100]);
+
+        let coverage = dag.coverage();
+        assert_eq!((coverage.materialized_blocks, coverage.total_blocks), (3, 65));
+        assert_eq!(coverage.total_bytes, (offset + content.len()) as u64);
+        assert!(coverage.fraction() < 0.1);
+        assert!(!coverage.is_complete());
+    }
+
+    #[tokio::test]
+    async fn test_range_outside_supplied_leaves_rejected() {
+        let content: Vec<u8> = (0..8 * 1024u32).map(|i| i as u8 ^ (i >> 8) as u8).collect();
+        let blocks = blocks(&content).await;
+        let offset = blocks[0].data.len();
+        let dag = PartialDag::covering(&blocks, &ContentSelection::ByteRange { start: offset, end: offset + 10 }).unwrap();
+
+        let elsewhere = ContentSelection::ByteRange { start: offset + 4096, end: offset + 4106 };
+        assert!(dag.extract_content(&elsewhere).is_err());
+
+        let mut swapped = dag.clone();
+        swapped.leaves[0].0 = 3;
+        assert!(swapped.validate().is_err());
+        assert!(PartialDag::covering(&blocks[..3], &elsewhere).is_err());
+    }
+}
@@ -128,41 +128,9 @@ impl PerformanceMonitor {
         })
     }
 
-    /// Gets current memory usage in bytes
+    /// Gets current memory usage in bytes (0 if it cannot be measured)
     fn get_memory_usage(&self) -> u64 {
-        #[cfg(target_os = "linux")]
-        {
-            if let Ok(contents) = std::fs::read_to_string("/proc/self/status") {
-                for line in contents.lines() {
-                    if line.starts_with("VmRSS:") {
-                        if let Some(kb_str) = line.split_whitespace().nth(1) {
-                            if let Ok(kb) = kb_str.parse::<u64>() {
-                                return kb * 1024;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        #[cfg(target_os = "macos")]
-        {
-            use std::process::Command;
-            if let Ok(output) = Command::new("ps")
-                .args(&["-o", "rss=", "-p"])
-                .arg(std::process::id().to_string())
-                .output()
-            {
-                if let Ok(rss_str) = String::from_utf8(output.stdout) {
-                    if let Ok(rss_kb) = rss_str.trim().parse::<u64>() {
-                        return rss_kb * 1024;
-                    }
-                }
-            }
-        }
-
-        // Fallback estimate
-        256 * 1024 * 1024 // 256MB
+        crate::memory::current_rss().unwrap_or(0)
     }
 
     /// Gets current CPU usage percentage
@@ -0,0 +1,342 @@
+//! File-format-aware selection presets
+//!
+//! Presets name a piece of a document, such as a CSV cell, instead of its byte
+//! offsets. The host parses the document, resolves the preset to the byte span
+//! holding that piece, and proves the span as a `ByteRange`.
+//!
+//! Supported formats:
+//!
+//! - **PDF** (`PdfPage`): the `N G obj ... endobj` bytes of the n-th
+//!   `/Type /Page` object, counted from zero in file order. Pages stored in
+//!   compressed object streams are not found.
+//! - **CSV** (`CsvCell`): comma-separated fields and `\n`, `\r\n` or `\r`
+//!   separated records, as in RFC 4180. Rows and columns count from zero, so
+//!   a header line is row 0. A quoted field resolves to the text between its
+//!   quotes, with doubled quotes left as written.
+//! - **JPEG EXIF** (`ExifField`): the value of a tag in IFD0, the Exif IFD or
+//!   the GPS IFD. ASCII values exclude their NUL terminator.
+//!
+//! A file in another format, or one without the requested page, cell or tag,
+//! fails with a content selection error saying what is missing.
+
+use crate::error::{ProofError, Result};
+use crate::types::ContentSelection;
+use memchr::memmem;
+use regex::bytes::Regex;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// EXIF tag holding the offset of the Exif IFD
+const EXIF_IFD_TAG: u16 = 0x8769;
+/// EXIF tag holding the offset of the GPS IFD
+const GPS_IFD_TAG: u16 = 0x8825;
+
+impl ContentSelection {
+    /// Selects page `page` (zero-based) of a PDF document
+    pub fn pdf_page(page: usize) -> Self {
+        ContentSelection::PdfPage { page }
+    }
+
+    /// Selects the cell at `row` and `column` (both zero-based) of a CSV document
+    pub fn csv_cell(row: usize, column: usize) -> Self {
+        ContentSelection::CsvCell { row, column }
+    }
+
+    /// Selects the value of EXIF tag `tag` (such as `0x010F`, the camera make) in a JPEG image
+    pub fn image_exif_field(tag: u16) -> Self {
+        ContentSelection::ExifField { tag }
+    }
+
+    /// Returns true for selections resolved from the document format
+    pub fn is_preset(&self) -> bool {
+        matches!(
+            self,
+            ContentSelection::PdfPage { .. }
+                | ContentSelection::CsvCell { .. }
+                | ContentSelection::ExifField { .. }
+        )
+    }
+}
+
+/// Resolves a preset selection to the byte span of `document` it names
+pub fn resolve_span(selection: &ContentSelection, document: &[u8]) -> Result<Range<usize>> {
+    match selection {
+        ContentSelection::PdfPage { page } => pdf_page_span(document, *page),
+        ContentSelection::CsvCell { row, column } => csv_cell_span(document, *row, *column),
+        ContentSelection::ExifField { tag } => exif_field_span(document, *tag),
+        other => Err(ProofError::content_selection_error(
+            format!("{} is not a file format preset", other.description_en())
+        )),
+    }
+}
+
+/// Finds the object of page `page` in a PDF document
+pub fn pdf_page_span(document: &[u8], page: usize) -> Result<Range<usize>> {
+    if !document.starts_with(b"%PDF-") {
+        return Err(ProofError::content_selection_error("File is not a PDF document"));
+    }
+
+    let object_header = Regex::new(r"(?-u)\d+\s+\d+\s+obj\b").expect("valid object regex");
+    let page_type = Regex::new(r"(?-u)/Type\s*/Page\b").expect("valid page regex");
+
+    let mut pages_seen = 0;
+    let mut position = 0;
+    while let Some(header) = object_header.find_at(document, position) {
+        let Some(body_len) = memmem::find(&document[header.end()..], b"endobj") else {
+            break;
+        };
+        let object_end = header.end() + body_len + b"endobj".len();
+
+        if page_type.is_match(&document[header.end()..header.end() + body_len]) {
+            if pages_seen == page {
+                return Ok(header.start()..object_end);
+            }
+            pages_seen += 1;
+        }
+        position = object_end;
+    }
+
+    Err(ProofError::content_selection_error(format!(
+        "PDF page {} not found ({} uncompressed page objects in the document)",
+        page, pages_seen
+    )))
+}
+
+/// Finds the cell at `row` and `column` of a CSV document
+pub fn csv_cell_span(document: &[u8], row: usize, column: usize) -> Result<Range<usize>> {
+    let malformed = |reason: &str| {
+        ProofError::content_selection_error(format!("Malformed CSV document: {}", reason))
+    };
+    if memchr::memchr(0, document).is_some() {
+        return Err(ProofError::content_selection_error("File is not a CSV document"));
+    }
+
+    let (mut current_row, mut current_column) = (0, 0);
+    let mut position = 0;
+    loop {
+        let (field, field_end) = if document.get(position) == Some(&b'"') {
+            let start = position + 1;
+            let mut quote = start;
+            loop {
+                quote += memchr::memchr(b'"', &document[quote..])
+                    .ok_or_else(|| malformed("unterminated quoted field"))?;
+                if document.get(quote + 1) != Some(&b'"') {
+                    break;
+                }
+                quote += 2;
+            }
+            (start..quote, quote + 1)
+        } else {
+            let end = memchr::memchr3(b',', b'\n', b'\r', &document[position..])
+                .map_or(document.len(), |len| position + len);
+            (position..end, end)
+        };
+
+        if (current_row, current_column) == (row, column) {
+            if field.is_empty() {
+                return Err(ProofError::content_selection_error(
+                    format!("CSV cell at row {}, column {} is empty", row, column)
+                ));
+            }
+            return Ok(field);
+        }
+
+        match document.get(field_end) {
+            Some(b',') => {
+                current_column += 1;
+                position = field_end + 1;
+            }
+            Some(b'\r') | Some(b'\n') => {
+                let crlf = document[field_end..].starts_with(b"\r\n");
+                current_row += 1;
+                current_column = 0;
+                position = field_end + if crlf { 2 } else { 1 };
+            }
+            None => break,
+            Some(_) => return Err(malformed("unexpected character after a quoted field")),
+        }
+
+        if current_row > row {
+            break;
+        }
+    }
+
+    Err(ProofError::content_selection_error(
+        format!("CSV document has no cell at row {}, column {}", row, column)
+    ))
+}
+
+/// Finds the value of EXIF tag `tag` in a JPEG image
+pub fn exif_field_span(document: &[u8], tag: u16) -> Result<Range<usize>> {
+    let tiff_start = find_exif_segment(document)?;
+    let tiff = Tiff::new(&document[tiff_start.clone()])?;
+    let missing = || ProofError::content_selection_error(format!("EXIF tag 0x{:04X} not found", tag));
+
+    // IFD0, plus the Exif and GPS IFDs it links to
+    let mut pending = vec![tiff.u32(4).ok_or_else(missing)? as usize];
+    let mut visited = HashSet::new();
+    while let Some(ifd) = pending.pop() {
+        if !visited.insert(ifd) {
+            continue;
+        }
+        let Some(count) = tiff.u16(ifd) else {
+            continue;
+        };
+        for index in 0..count as usize {
+            let entry = ifd + 2 + 12 * index;
+            let Some(entry_tag) = tiff.u16(entry) else {
+                break;
+            };
+
+            if entry_tag == tag {
+                let span = tiff.value_span(entry).ok_or_else(|| ProofError::content_selection_error(
+                    format!("EXIF tag 0x{:04X} has no readable value", tag)
+                ))?;
+                return Ok(tiff_start.start + span.start..tiff_start.start + span.end);
+            }
+            if entry_tag == EXIF_IFD_TAG || entry_tag == GPS_IFD_TAG {
+                if let Some(offset) = tiff.u32(entry + 8) {
+                    pending.push(offset as usize);
+                }
+            }
+        }
+    }
+
+    Err(missing())
+}
+
+/// Returns the span of the TIFF data in a JPEG image's EXIF segment
+fn find_exif_segment(document: &[u8]) -> Result<Range<usize>> {
+    if !document.starts_with(&[0xFF, 0xD8]) {
+        return Err(ProofError::content_selection_error("File is not a JPEG image"));
+    }
+
+    let mut position = 2;
+    while position + 4 <= document.len() && document[position] == 0xFF {
+        let marker = document[position + 1];
+        // Start of scan or end of image: no metadata segments follow
+        if marker == 0xDA || marker == 0xD9 {
+            break;
+        }
+        let length = u16::from_be_bytes([document[position + 2], document[position + 3]]) as usize;
+        let segment = position + 4..position + 2 + length;
+        if length < 2 || segment.end > document.len() {
+            break;
+        }
+        if marker == 0xE1 && document[segment.clone()].starts_with(b"Exif\0\0") {
+            return Ok(segment.start + 6..segment.end);
+        }
+        position = segment.end;
+    }
+
+    Err(ProofError::content_selection_error("JPEG image has no EXIF data"))
+}
+
+/// TIFF structure of an EXIF segment
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(data: &'a [u8]) -> Result<Self> {
+        let little_endian = match data.get(..2) {
+            Some(b"II") => true,
+            Some(b"MM") => false,
+            _ => return Err(ProofError::content_selection_error("EXIF data has an invalid byte order")),
+        };
+        Ok(Self { data, little_endian })
+    }
+
+    fn u16(&self, at: usize) -> Option<u16> {
+        let bytes = self.data.get(at..at.checked_add(2)?)?.try_into().ok()?;
+        Some(if self.little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    }
+
+    fn u32(&self, at: usize) -> Option<u32> {
+        let bytes = self.data.get(at..at.checked_add(4)?)?.try_into().ok()?;
+        Some(if self.little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    }
+
+    /// Span of the value of the IFD entry at `entry`, stored inline when it fits in four bytes
+    fn value_span(&self, entry: usize) -> Option<Range<usize>> {
+        let value_type = self.u16(entry + 2)?;
+        let count = self.u32(entry + 4)? as usize;
+        let element_size = match value_type {
+            1 | 2 | 6 | 7 => 1,
+            3 | 8 => 2,
+            4 | 9 | 11 => 4,
+            5 | 10 | 12 => 8,
+            _ => return None,
+        };
+        let size = count.checked_mul(element_size)?;
+        let start = if size <= 4 { entry + 8 } else { self.u32(entry + 8)? as usize };
+        let mut end = start.checked_add(size)?;
+        if end > self.data.len() {
+            return None;
+        }
+
+        // ASCII values end with a NUL
+        if value_type == 2 {
+            while end > start && self.data[end - 1] == 0 {
+                end -= 1;
+            }
+        }
+        (start < end).then_some(start..end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_cell_spans() {
+        let csv = b"name,amount\r\nalice,\"1,200\"\nbob,\"say \"\"hi\"\"\"\n";
+        let cell = |row, column| csv_cell_span(csv, row, column).map(|span| &csv[span]);
+
+        assert_eq!(cell(0, 1).unwrap(), b"amount");
+        assert_eq!(cell(1, 0).unwrap(), b"alice");
+        assert_eq!(cell(1, 1).unwrap(), b"1,200");
+        assert_eq!(cell(2, 1).unwrap(), b"say \"\"hi\"\"");
+        assert!(cell(1, 2).is_err());
+        assert!(cell(5, 0).is_err());
+        assert!(csv_cell_span(b"a,\"open\n", 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_exif_field_span() {
+        // A little-endian TIFF header, then IFD0 with one Make (0x010F) entry
+        let mut tiff = b"II*\0\x08\0\0\0".to_vec();
+        tiff.extend_from_slice(&1u16.to_le_bytes());
+        tiff.extend_from_slice(&0x010Fu16.to_le_bytes());
+        tiff.extend_from_slice(&2u16.to_le_bytes());
+        tiff.extend_from_slice(&6u32.to_le_bytes());
+        tiff.extend_from_slice(&26u32.to_le_bytes());
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        tiff.extend_from_slice(b"Canon\0");
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+        jpeg.extend_from_slice(b"Exif\0\0");
+        jpeg.extend_from_slice(&tiff);
+        jpeg.extend_from_slice(&[0xFF, 0xD9]);
+
+        let span = exif_field_span(&jpeg, 0x010F).unwrap();
+        assert_eq!(&jpeg[span], b"Canon");
+        assert!(exif_field_span(&jpeg, 0x0110).is_err());
+        assert!(exif_field_span(b"not an image", 0x010F).is_err());
+    }
+
+    #[test]
+    fn test_pdf_page_span() {
+        let pdf = b"%PDF-1.4\n1 0 obj << /Type /Pages /Kids [2 0 R 3 0 R] >> endobj\n\
+            2 0 obj << /Type /Page /Contents 4 0 R >> endobj\n\
+            3 0 obj << /Type/Page /Contents 5 0 R >> endobj\n";
+
+        let span = pdf_page_span(pdf, 1).unwrap();
+        assert_eq!(&pdf[span], b"3 0 obj << /Type/Page /Contents 5 0 R >> endobj");
+        assert!(pdf_page_span(pdf, 2).is_err());
+        assert!(pdf_page_span(b"name,amount\n", 0).is_err());
+    }
+}
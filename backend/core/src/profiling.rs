@@ -3,7 +3,9 @@
 //! This module provides comprehensive performance profiling, benchmarking,
 //! and optimization tools for analyzing and improving system performance.
 
+use crate::error::{ProofError, Result};
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
@@ -87,6 +89,14 @@ pub struct BenchmarkConfig {
     pub profile_cpu: bool,
     /// Enable I/O profiling
     pub profile_io: bool,
+    /// Throughput change, in percent of the baseline, beyond which a
+    /// regression or improvement is reported
+    #[serde(default = "default_regression_threshold_percent")]
+    pub regression_threshold_percent: f64,
+}
+
+fn default_regression_threshold_percent() -> f64 {
+    5.0
 }
 
 impl Default for BenchmarkConfig {
@@ -99,6 +109,7 @@ impl Default for BenchmarkConfig {
             profile_memory: true,
             profile_cpu: true,
             profile_io: true,
+            regression_threshold_percent: default_regression_threshold_percent(),
         }
     }
 }
@@ -138,6 +149,19 @@ pub struct BenchmarkResults {
     pub regression_analysis: Option<RegressionAnalysis>,
 }
 
+impl BenchmarkResults {
+    /// Saves these results as JSON, for later runs to compare against
+    pub fn save_baseline(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| ProofError::serialization_error("Failed to encode benchmark baseline", Some(Box::new(e))))?;
+        std::fs::write(path, json)
+            .map_err(|e| ProofError::file_error(
+                format!("Failed to write benchmark baseline: {}", path.display()),
+                Some(e)
+            ))
+    }
+}
+
 /// Memory usage statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryStats {
@@ -337,10 +361,23 @@ impl BenchmarkRunner {
         self.baseline_results = Some(baseline);
     }
 
+    /// Loads baseline results saved by [`BenchmarkResults::save_baseline`]
+    pub fn load_baseline(&mut self, path: &Path) -> Result<()> {
+        let json = std::fs::read(path)
+            .map_err(|e| ProofError::file_error(
+                format!("Failed to read benchmark baseline: {}", path.display()),
+                Some(e)
+            ))?;
+        let baseline = serde_json::from_slice(&json)
+            .map_err(|e| ProofError::serialization_error("Failed to decode benchmark baseline", Some(Box::new(e))))?;
+        self.set_baseline(baseline);
+        Ok(())
+    }
+
     /// Run benchmark for a given operation
     pub fn run_benchmark<F, T>(&self, operation_name: &str, mut operation: F) -> BenchmarkResults
     where
-        F: FnMut() -> Result<T, Box<dyn std::error::Error>>,
+        F: FnMut() -> std::result::Result<T, Box<dyn std::error::Error>>,
     {
         let mut durations = Vec::new();
         let mut successful_iterations = 0;
@@ -402,23 +439,31 @@ impl BenchmarkRunner {
             }
         }
 
-        // Calculate statistics
+        // Calculate statistics (all zero if every iteration failed)
         durations.sort();
-        let mean_duration = durations.iter().sum::<Duration>() / durations.len() as u32;
-        let median_duration = durations[durations.len() / 2];
+        let mean_duration = if durations.is_empty() {
+            Duration::ZERO
+        } else {
+            durations.iter().sum::<Duration>() / durations.len() as u32
+        };
+        let median_duration = percentile(&durations, 0.5);
         let min_duration = durations.first().copied().unwrap_or_default();
         let max_duration = durations.last().copied().unwrap_or_default();
-        let p95_duration = durations[(durations.len() as f64 * 0.95) as usize];
-        let p99_duration = durations[(durations.len() as f64 * 0.99) as usize];
+        let p95_duration = percentile(&durations, 0.95);
+        let p99_duration = percentile(&durations, 0.99);
 
         // Calculate standard deviation
-        let variance: f64 = durations
-            .iter()
-            .map(|d| {
-                let diff = d.as_nanos() as f64 - mean_duration.as_nanos() as f64;
-                diff * diff
-            })
-            .sum::<f64>() / durations.len() as f64;
+        let variance: f64 = if durations.is_empty() {
+            0.0
+        } else {
+            durations
+                .iter()
+                .map(|d| {
+                    let diff = d.as_nanos() as f64 - mean_duration.as_nanos() as f64;
+                    diff * diff
+                })
+                .sum::<f64>() / durations.len() as f64
+        };
         let std_deviation = Duration::from_nanos(variance.sqrt() as u64);
 
         // Calculate throughput
@@ -472,10 +517,15 @@ impl BenchmarkRunner {
         };
 
         // Calculate performance change (positive = improvement, negative = regression)
-        let performance_change = (baseline.throughput - current.throughput) / baseline.throughput * 100.0;
+        let performance_change = if baseline.throughput > 0.0 {
+            (current.throughput - baseline.throughput) / baseline.throughput * 100.0
+        } else {
+            0.0
+        };
         
-        let regression_detected = performance_change < -5.0; // 5% threshold
-        let improvement_detected = performance_change > 5.0;
+        let threshold = self.config.regression_threshold_percent;
+        let regression_detected = performance_change < -threshold;
+        let improvement_detected = performance_change > threshold;
         let confidence_level = 0.95; // Simplified confidence calculation
 
         RegressionAnalysis {
@@ -568,6 +618,15 @@ impl OptimizationRecommendations {
     }
 }
 
+/// Returns the `q` quantile of sorted durations, or zero if there are none
+fn percentile(sorted: &[Duration], q: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let index = ((sorted.len() as f64 * q) as usize).min(sorted.len() - 1);
+    sorted[index]
+}
+
 // Helper functions for system metrics (placeholder implementations)
 fn get_current_memory_usage() -> usize {
     1024 * 1024 * 50 // 50MB placeholder
@@ -623,6 +682,83 @@ mod tests {
         assert!(results.mean_duration >= Duration::from_millis(1));
     }
 
+    #[test]
+    fn test_benchmark_with_every_iteration_failing() {
+        let runner = BenchmarkRunner::new(BenchmarkConfig {
+            iterations: 5,
+            warmup_iterations: 0,
+            ..Default::default()
+        });
+
+        let results = runner.run_benchmark("failing_op", || -> std::result::Result<(), _> {
+            Err("boom".into())
+        });
+
+        assert_eq!(results.successful_iterations, 0);
+        assert_eq!(results.failed_iterations, 5);
+        assert_eq!(results.mean_duration, Duration::ZERO);
+        assert_eq!(results.median_duration, Duration::ZERO);
+        assert_eq!(results.p99_duration, Duration::ZERO);
+        assert_eq!(results.std_deviation, Duration::ZERO);
+        assert_eq!(results.throughput, 0.0);
+    }
+
+    #[test]
+    fn test_benchmark_with_single_iteration() {
+        let runner = BenchmarkRunner::new(BenchmarkConfig {
+            iterations: 1,
+            warmup_iterations: 0,
+            ..Default::default()
+        });
+
+        let results = runner.run_benchmark("single_op", || {
+            std::thread::sleep(Duration::from_millis(1));
+            Ok(())
+        });
+
+        assert_eq!(results.successful_iterations, 1);
+        assert_eq!(results.median_duration, results.mean_duration);
+        assert_eq!(results.p95_duration, results.mean_duration);
+        assert_eq!(results.p99_duration, results.mean_duration);
+        assert_eq!(results.std_deviation, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_regression_against_saved_baseline() {
+        let config = BenchmarkConfig {
+            iterations: 10,
+            warmup_iterations: 1,
+            ..Default::default()
+        };
+        let baseline_file = tempfile::NamedTempFile::new().unwrap();
+
+        let baseline = BenchmarkRunner::new(config.clone()).run_benchmark("op", || {
+            std::thread::sleep(Duration::from_millis(1));
+            Ok(())
+        });
+        baseline.save_baseline(baseline_file.path()).unwrap();
+
+        let mut runner = BenchmarkRunner::new(config.clone());
+        runner.load_baseline(baseline_file.path()).unwrap();
+        let results = runner.run_benchmark("op", || {
+            std::thread::sleep(Duration::from_millis(5));
+            Ok(())
+        });
+        let analysis = results.regression_analysis.unwrap();
+        assert!(analysis.regression_detected);
+        assert!(!analysis.improvement_detected);
+        assert!(analysis.performance_change < -5.0);
+
+        // A threshold above the slowdown tolerates it
+        let mut lenient = BenchmarkRunner::new(BenchmarkConfig { regression_threshold_percent: 1000.0, ..config });
+        lenient.load_baseline(baseline_file.path()).unwrap();
+        let results = lenient.run_benchmark("op", || {
+            std::thread::sleep(Duration::from_millis(5));
+            Ok(())
+        });
+        assert!(!results.regression_analysis.unwrap().regression_detected);
+    }
+
     #[test]
     fn test_optimization_recommendations() {
         let profile = PerformanceProfile {
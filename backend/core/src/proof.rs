@@ -6,19 +6,52 @@
 use crate::{
     error::{ProofError, Result, ResultExt},
     types::*,
+    bonsai::{BonsaiCredentialStore, BonsaiProver},
     ipfs::IpfsProcessor,
-    IPFS_CONTENT_VERIFIER_ELF,
-    IPFS_CONTENT_VERIFIER_ID,
+    cache::{CacheManager, CacheKey, CacheStatistics},
+    car,
+    commitment::{self, ContentOpening},
+    json_path,
+    memory::MemorySampler,
+    monitoring::{EventBus, MonitorEvent},
+    presets,
+    proof_types::{ProofScheme, ProofType, ProofTypeRegistry},
+    signing::SigningKey,
+    partial::PartialDag,
+    secret::Secret,
+    timestamp::TimestampClient,
 };
-use risc0_zkvm::{default_prover, ExecutorEnv, ProverOpts, Receipt};
+use risc0_zkvm::{default_prover, get_prover_server, ExecutorEnv, ExecutorImpl, ProverOpts, Receipt, Session, VerifierContext};
 use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
 use std::path::Path;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 use tokio::time::timeout;
 use tracing::{debug, info, warn, instrument};
 use uuid::Uuid;
 use chrono::Utc;
 use regex::Regex;
+use memchr::memmem;
+
+/// Guest cycles per byte of block data, covering input decoding, block hashing and selection
+const ESTIMATED_CYCLES_PER_BYTE: u64 = 24;
+/// Guest cycles independent of the input size: startup, root checks and the journal
+const ESTIMATED_BASE_CYCLES: u64 = 250_000;
+/// Cycles in a full proving segment
+const SEGMENT_SIZE_CYCLES: u64 = 1 << 20;
+/// Approximate time to prove one segment on the CPU and with GPU acceleration
+const CPU_SEGMENT_PROVING_MS: u64 = 12_000;
+const GPU_SEGMENT_PROVING_MS: u64 = 1_500;
+/// Most match offsets reported for a single pattern or regex by a preflight
+const MAX_REPORTED_MATCHES: usize = 1000;
+/// Approximate receipt sizes: per segment for composite receipts, in total otherwise
+const COMPOSITE_SEGMENT_RECEIPT_BYTES: u64 = 220 * 1024;
+const SUCCINCT_RECEIPT_BYTES: u64 = 220 * 1024;
+const GROTH16_RECEIPT_BYTES: u64 = 512;
+/// Approximate prover memory per padded cycle of the segment being proven
+const PROVER_BYTES_PER_CYCLE: u64 = 8 * 1024;
+/// Segments above which a CPU-only machine is pointed at Bonsai
+const BONSAI_RECOMMENDATION_SEGMENTS: usize = 16;
 
 /// Main proof generator for zkIPFS-Proof
 pub struct ProofGenerator {
@@ -28,6 +61,18 @@ pub struct ProofGenerator {
     ipfs_processor: IpfsProcessor,
     /// Performance statistics
     stats: ProofStatistics,
+    /// Cache for previously generated proofs (if enabled)
+    cache: Option<CacheManager>,
+    /// Key that signs every proof produced (if set)
+    signing_key: Option<SigningKey>,
+    /// Guest programs that prove each proof type
+    registry: ProofTypeRegistry,
+    /// Receives proof and cache events
+    events: EventBus,
+    /// Credentials read by each Bonsai request when proving on Bonsai
+    bonsai_credentials: BonsaiCredentialStore,
+    /// Id of the request being served, recorded on generation spans
+    request_id: Option<String>,
 }
 
 impl ProofGenerator {
@@ -38,12 +83,34 @@ impl ProofGenerator {
 
     /// Creates a new proof generator with custom configuration
     pub async fn with_config(config: ProofConfig) -> Result<Self> {
-        let ipfs_processor = IpfsProcessor::new().await
+        let mut ipfs_processor = IpfsProcessor::with_chunking(config.chunking_strategy.clone()).await
             .context("Failed to initialize IPFS processor")?;
+        ipfs_processor.set_mmap_threshold(config.mmap_threshold);
+        ipfs_processor.set_worker_threads(config.worker_threads)?;
+        ipfs_processor.set_decompression(config.decompress_input.clone());
+        ipfs_processor.set_max_decompressed_size(config.max_file_size_bytes);
+
+        let cache = if config.use_cache {
+            match CacheManager::new().await {
+                Ok(cache) => Some(cache),
+                Err(e) => {
+                    warn!("Proof cache unavailable, continuing without it: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
 
         Ok(Self {
             config,
             ipfs_processor,
+            cache,
+            signing_key: None,
+            registry: ProofTypeRegistry::new(),
+            events: EventBus::new(),
+            bonsai_credentials: BonsaiCredentialStore::from_env(),
+            request_id: None,
             stats: ProofStatistics {
                 total_proofs_generated: 0,
                 total_proofs_verified: 0,
@@ -58,43 +125,139 @@ impl ProofGenerator {
     }
 
     /// Generates a zero-knowledge proof for the specified content selection
-    #[instrument(skip(self, file_path), fields(file = %file_path.display()))]
     pub async fn generate_proof(
         &mut self,
         file_path: &Path,
         content_selection: ContentSelection,
     ) -> Result<Proof> {
+        let (proof, _) = self.generate(file_path, content_selection, None, false).await?;
+        Ok(proof)
+    }
+
+    /// Generates a proof like [`generate_proof`](Self::generate_proof), resuming
+    /// from the checkpoint an interrupted run left for the same unchanged file
+    /// and selection if there is one
+    ///
+    /// Checkpoints are written before proving when [`ProofConfig::checkpoint`]
+    /// is set and the cache is enabled, and removed once the proof is cached.
+    /// Resuming skips file processing and content extraction, so the file is
+    /// not read again.
+    pub async fn resume_or_generate(
+        &mut self,
+        file_path: &Path,
+        content_selection: ContentSelection,
+    ) -> Result<Proof> {
+        let (proof, _) = self.generate(file_path, content_selection, None, true).await?;
+        Ok(proof)
+    }
+
+    /// Generates a proof that commits to the selected content instead of
+    /// revealing its hash, for selective disclosure
+    ///
+    /// Returns the proof and the opening of its commitment, which is the
+    /// prover's secret until the content is disclosed. Committed proofs are
+    /// checked with `ProofVerifier::verify_commitment` and opened with
+    /// `ProofVerifier::verify_opening`; see [`crate::commitment`] for the trust
+    /// model. They are never served from or stored in the proof cache.
+    pub async fn generate_committed_proof(
+        &mut self,
+        file_path: &Path,
+        content_selection: ContentSelection,
+    ) -> Result<(Proof, ContentOpening)> {
+        let mut blinding = [0u8; 32];
+        getrandom::getrandom(&mut blinding)
+            .map_err(|e| ProofError::internal_error("Failed to generate a commitment blinding", Some(Box::new(e))))?;
+
+        let (proof, content) = self.generate(file_path, content_selection, Some(blinding), false).await?;
+        let content = content.expect("committed proofs are never served from the cache");
+        Ok((proof, ContentOpening { blinding, content }))
+    }
+
+    /// Generates a proof, committing to the content under `commitment_blinding`
+    /// if set, and returns it with the proven content unless it came from the cache
+    ///
+    /// With `resume`, a checkpoint left for the file and selection replaces
+    /// file processing and content extraction.
+    #[instrument(
+        skip(self, file_path, commitment_blinding),
+        fields(file = %file_path.display(), request_id = self.request_id.as_deref())
+    )]
+    async fn generate(
+        &mut self,
+        file_path: &Path,
+        content_selection: ContentSelection,
+        commitment_blinding: Option<[u8; 32]>,
+        resume: bool,
+    ) -> Result<(Proof, Option<Vec<u8>>)> {
         let start_time = Instant::now();
         
         info!("Starting proof generation for file: {}", file_path.display());
+        self.events.emit(MonitorEvent::ProofStarted);
         
         // Validate inputs
         self.validate_inputs(file_path, &content_selection)?;
-        
-        // Process the file and extract IPFS blocks
-        let file_processing_start = Instant::now();
-        let (blocks, file_info) = self.ipfs_processor
-            .process_file(file_path)
-            .await
-            .context("Failed to process file into IPFS blocks")?;
-        let file_processing_time = file_processing_start.elapsed();
-        
-        debug!("Processed file into {} IPFS blocks", blocks.len());
-        
-        // Extract and hash the target content
-        let content_hash = self.extract_content_hash(&blocks, &content_selection)?;
-        
-        // Prepare input for the ZK circuit
-        let proof_input = ProofInput {
-            blocks: blocks.clone(),
-            content_selection: content_selection.clone(),
-            expected_content_hash: content_hash,
+
+        // Return a previously generated proof if the file and selection are unchanged
+        let file_cache_key = Self::file_cache_key(file_path)?;
+        let cached_file_hash = match commitment_blinding {
+            Some(_) => None,
+            None => self.cached_file_hash(&file_cache_key).await,
+        };
+        let proof_cache_key = match cached_file_hash {
+            Some(file_hash) => {
+                let key = self.proof_cache_key(&file_hash, &content_selection);
+                if let Some(proof) = self.cached_proof(&key).await {
+                    info!("Returning cached proof (proof_id: {})", &proof.id[..8]);
+                    self.events.emit(MonitorEvent::CacheHit);
+                    return Ok((self.seal(proof).await?, None));
+                }
+                self.events.emit(MonitorEvent::CacheMiss);
+                Some(key)
+            }
+            None => {
+                if self.cache.is_some() && commitment_blinding.is_none() {
+                    self.events.emit(MonitorEvent::CacheMiss);
+                }
+                None
+            }
+        };
+        let original_selection = content_selection.clone();
+        let memory_sampler = MemorySampler::start();
+
+        let resumed = match resume {
+            true => self.cached_checkpoint(&file_cache_key, &original_selection).await,
+            false => None,
+        };
+        let (proof_input, file_info, file_processing_time, content) = match resumed {
+            Some((proof_input, file_info)) => {
+                info!("Resuming proof generation from checkpoint");
+                let content = self.extract_content(&proof_input.blocks, &proof_input.content_selection)?;
+                (proof_input, file_info, Duration::ZERO, content)
+            }
+            None => {
+                let (proof_input, file_info, file_processing_time, content) = self
+                    .prepare_input(file_path, content_selection, commitment_blinding)
+                    .await?;
+                if self.config.checkpoint && commitment_blinding.is_none() {
+                    self.cache_checkpoint(file_cache_key.clone(), &original_selection, &proof_input, &file_info).await;
+                }
+                (proof_input, file_info, file_processing_time, content)
+            }
+        };
+        let content_selection = proof_input.content_selection.clone();
+        let content_hash = proof_input.expected_content_hash;
+
+        // A committed proof records the commitment the guest outputs in place of the hash
+        let content_hash = match &commitment_blinding {
+            Some(blinding) => commitment::commit_content_hash(&content_hash, blinding),
+            None => content_hash,
         };
         
         // Generate the ZK proof
         let zk_generation_start = Instant::now();
-        let (receipt, zk_cycles) = self.generate_zk_proof(proof_input).await?;
+        let (receipt, zk_cycles, segment_cycles) = self.generate_zk_proof(&self.config.proof_type, proof_input).await?;
         let zk_generation_time = zk_generation_start.elapsed();
+        let peak_memory_bytes = memory_sampler.finish();
         
         // Extract proof output from receipt
         let proof_output: ProofOutput = receipt.journal.decode()
@@ -104,44 +267,62 @@ impl ProofGenerator {
             ))?;
         
         // Create proof metadata
+        let file_hash = file_info.file_hash;
         let total_time = start_time.elapsed();
-        let metadata = self.create_proof_metadata(
+        let (receipt_bytes, receipt_len) = self.encode_receipt(&receipt)?;
+        let mut metadata = self.create_proof_metadata(
             proof_output.metadata,
+            proof_output.hash_algorithm,
             file_info,
             total_time,
             file_processing_time,
             zk_generation_time,
             zk_cycles,
-            &receipt,
+            segment_cycles,
+            peak_memory_bytes,
+            &receipt_bytes,
+            receipt_len,
         )?;
+        let created_at = Utc::now();
+        metadata.valid_until = self.validity_deadline(created_at);
+        metadata.content_committed = commitment_blinding.is_some();
         
         // Create the final proof
         let proof = Proof {
             id: Uuid::new_v4().to_string(),
             zk_proof: ZkProofData {
-                receipt: bincode::serialize(&receipt)
-                    .map_err(|e| ProofError::serialization_error(
-                        "Failed to serialize receipt",
-                        Some(Box::new(e))
-                    ))?,
+                receipt: receipt_bytes,
                 public_inputs: bincode::serialize(&content_hash)
                     .map_err(|e| ProofError::serialization_error(
                         "Failed to serialize public inputs",
                         Some(Box::new(e))
                     ))?,
-                format_version: "1.0".to_string(),
+                format_version: self.config.receipt_kind.format_version(),
                 compression: Some(self.config.compression.clone()),
             },
             metadata,
             content_selection,
             content_hash,
             root_hash: proof_output.root_hash,
-            created_at: Utc::now(),
+            created_at,
             version: env!("CARGO_PKG_VERSION").to_string(),
+            proof_type: self.config.proof_type.clone(),
+            image_id: Some(self.registry.scheme(&self.config.proof_type)?.image_id),
+            signature: None,
+            timestamp: None,
         };
         
         // Update statistics
         self.update_generation_stats(&proof, total_time);
+
+        if commitment_blinding.is_none() {
+            let proof_cache_key = proof_cache_key.unwrap_or_else(|| {
+                self.proof_cache_key(&file_hash, &original_selection)
+            });
+            self.cache_proof(file_cache_key, file_hash, proof_cache_key, &proof).await;
+            self.remove_checkpoint(&file_hash, &original_selection).await;
+        }
+        let proof = self.seal(proof).await?;
         
         info!(
             "Proof generation completed in {}ms (proof_id: {})",
@@ -149,593 +330,3037 @@ impl ProofGenerator {
             &proof.id[..8]
         );
         
-        Ok(proof)
+        Ok((proof, Some(content)))
     }
 
-    /// Verifies a zero-knowledge proof against the claimed content
-    #[instrument(skip(self, proof, claimed_content))]
-    pub async fn verify_proof(
-        &mut self,
-        proof: &Proof,
-        claimed_content: &[u8],
-    ) -> Result<bool> {
-        let start_time = Instant::now();
-        
-        info!("Starting proof verification for proof: {}", &proof.id[..8]);
-        
-        // Deserialize the receipt
-        let receipt: Receipt = bincode::deserialize(&proof.zk_proof.receipt)
-            .map_err(|e| ProofError::serialization_error(
-                "Failed to deserialize receipt",
-                Some(Box::new(e))
-            ))?;
-        
-        // Verify the receipt against the expected image ID
-        let verification_result = receipt.verify(IPFS_CONTENT_VERIFIER_ID)
-            .map_err(|e| ProofError::verification_error(
-                format!("Receipt verification failed: {}", e)
-            ))?;
-        
-        // Extract proof output from the receipt
-        let proof_output: ProofOutput = receipt.journal.decode()
-            .map_err(|e| ProofError::serialization_error(
-                "Failed to decode proof output",
-                Some(Box::new(e))
-            ))?;
-        
-        // Verify that the claimed content hash matches the proof
-        let claimed_hash = Sha256::digest(claimed_content);
-        let is_valid = claimed_hash.as_slice() == proof.content_hash.as_slice() &&
-                      proof_output.content_hash == proof.content_hash &&
-                      proof_output.root_hash == proof.root_hash;
-        
-        let verification_time = start_time.elapsed();
-        
-        // Update statistics
-        self.update_verification_stats(is_valid, verification_time);
+    /// Processes a file into blocks and extracts the selected content: the
+    /// phases before proving, whose result a checkpoint persists
+    ///
+    /// Returns the guest input, the file's info, how long processing took and
+    /// the extracted content.
+    async fn prepare_input(
+        &self,
+        file_path: &Path,
+        content_selection: ContentSelection,
+        commitment_blinding: Option<[u8; 32]>,
+    ) -> Result<(ProofInput, FileInfo, Duration, Vec<u8>)> {
+        // Process the file and extract IPFS blocks
+        let file_processing_start = Instant::now();
+        let (blocks, file_info) = self.ipfs_processor
+            .process_file(file_path)
+            .await
+            .context("Failed to process file into IPFS blocks")?;
+        let file_processing_time = file_processing_start.elapsed();
         
-        if is_valid {
-            info!(
-                "Proof verification successful in {}ms",
-                verification_time.as_millis()
-            );
-        } else {
-            warn!(
-                "Proof verification failed in {}ms",
-                verification_time.as_millis()
-            );
-        }
+        debug!("Processed file into {} IPFS blocks", blocks.len());
         
-        Ok(is_valid)
-    }
-
-    /// Returns current proof generation statistics
-    pub fn get_statistics(&self) -> &ProofStatistics {
-        &self.stats
-    }
+        // Large patterns are replaced by their hash so the guest never holds them,
+        // and JSON paths by the byte span of their value
+        let content_selection = Self::resolve_host_selections(
+            self.prepare_guest_selection(content_selection),
+            &blocks,
+        )?;
 
-    /// Updates the configuration
-    pub fn update_config(&mut self, config: ProofConfig) {
-        self.config = config;
+        // Extract and hash the target content
+        let hash_algorithm = self.config.hash_algorithm;
+        let content = self.extract_content(&blocks, &content_selection)?;
+        let content_hash = content_selection.content_hash(hash_algorithm, &content);
+        
+        // Prepare input for the ZK circuit
+        let proof_input = ProofInput {
+            blocks,
+            content_selection,
+            expected_content_hash: content_hash,
+            hash_algorithm,
+            commitment_blinding,
+            materialization: None,
+        };
+        Ok((proof_input, file_info, file_processing_time, content))
     }
 
-    /// Validates input parameters
-    fn validate_inputs(
+    /// Checks that a selection can be proven against a file, without running the prover
+    ///
+    /// The file is chunked and the selection resolved as for
+    /// [`generate_proof`](Self::generate_proof), so a byte range past the end of
+    /// the content or a pattern that does not occur is reported in seconds
+    /// rather than after proving. Cycle and time estimates are rough, derived
+    /// from the size of the block data and the detected hardware.
+    pub async fn preflight(
         &self,
         file_path: &Path,
         content_selection: &ContentSelection,
-    ) -> Result<()> {
-        // Check if file exists and is readable
-        if !file_path.exists() {
-            return Err(ProofError::invalid_input_error(
-                "file_path",
-                format!("File does not exist: {}", file_path.display())
-            ));
-        }
+    ) -> Result<PreflightReport> {
+        self.validate_inputs(file_path, content_selection)?;
 
-        if !file_path.is_file() {
-            return Err(ProofError::invalid_input_error(
-                "file_path",
-                format!("Path is not a file: {}", file_path.display())
-            ));
-        }
+        let (blocks, file_info) = self.ipfs_processor
+            .process_file(file_path)
+            .await
+            .context("Failed to process file into IPFS blocks")?;
+        self.preflight_blocks(&blocks, &file_info, content_selection)
+    }
 
-        // Validate content selection
-        if !content_selection.is_valid() {
-            return Err(ProofError::content_selection_error(
-                "Invalid content selection parameters"
-            ));
+    /// Preflight checks of a selection against a file already split into `blocks`
+    fn preflight_blocks(
+        &self,
+        blocks: &[IpfsBlock],
+        file_info: &FileInfo,
+        content_selection: &ContentSelection,
+    ) -> Result<PreflightReport> {
+        let mut spans = Vec::new();
+        let mut problems = Vec::new();
+        match Self::resolve_host_selections(self.prepare_guest_selection(content_selection.clone()), blocks) {
+            Ok(selection) => self.preflight_selection(blocks, &selection, &mut spans, &mut problems),
+            Err(e) => problems.push(e.to_string()),
         }
 
-        // Check file size limits
-        let metadata = std::fs::metadata(file_path)?;
-        let file_size = metadata.len();
-        
-        const MAX_FILE_SIZE: u64 = 50 * 1024 * 1024 * 1024; // 50GB
-        if file_size > MAX_FILE_SIZE {
+        // Resolved selections know their size even where the request did not
+        let selected_bytes: u64 = spans.iter().map(|span| span.end - span.start).sum();
+        if let Some(max) = self.config.max_total_selected_bytes.filter(|max| selected_bytes > max.get()) {
             return Err(ProofError::resource_limit_error(
-                "file_size",
-                format!("File size ({} bytes) exceeds maximum allowed size ({} bytes)", 
-                       file_size, MAX_FILE_SIZE)
+                "selected_bytes",
+                format!("Selection covers {} bytes, more than the {} allowed", selected_bytes, max.get())
             ));
         }
 
-        Ok(())
+        let data_len: u64 = blocks.iter().map(|block| block.data.len() as u64).sum();
+        let estimated_cycles = ESTIMATED_BASE_CYCLES + data_len * ESTIMATED_CYCLES_PER_BYTE;
+        let segment_ms = self.segment_proving_ms(&self.detect_hardware_acceleration());
+        let segments = estimated_cycles.div_ceil(SEGMENT_SIZE_CYCLES);
+
+        Ok(PreflightReport {
+            satisfiable: problems.is_empty(),
+            problems,
+            file_size: file_info.size,
+            block_count: file_info.block_count,
+            selected_bytes: Bytes(selected_bytes),
+            spans,
+            estimated_cycles,
+            estimated_proving_time_ms: Millis(segments * segment_ms),
+        })
     }
 
-    /// Extracts and hashes the content specified by the selection
-    fn extract_content_hash(
+    /// Estimates the cost of proving a selection by executing the guest without proving
+    ///
+    /// Cycle counts are exact for this input; proof size, memory and time are
+    /// projected from them for the configured receipt kind and the detected
+    /// hardware.
+    pub async fn estimate(
         &self,
-        blocks: &[IpfsBlock],
+        file_path: &Path,
         content_selection: &ContentSelection,
-    ) -> Result<[u8; 32]> {
-        let content = self.extract_content(blocks, content_selection)?;
-        let hash = Sha256::digest(&content);
-        Ok(hash.into())
+    ) -> Result<ProofEstimate> {
+        self.validate_inputs(file_path, content_selection)?;
+
+        // The blocks chunked for the preflight checks are the executor's input
+        let (blocks, file_info) = self.ipfs_processor
+            .process_file(file_path)
+            .await
+            .context("Failed to process file into IPFS blocks")?;
+        self.preflight_blocks(&blocks, &file_info, content_selection)?.ensure_satisfiable()?;
+
+        let content_selection = Self::resolve_host_selections(
+            self.prepare_guest_selection(content_selection.clone()),
+            &blocks,
+        )?;
+        let hash_algorithm = self.config.hash_algorithm;
+        let proof_input = ProofInput {
+            expected_content_hash: self.extract_content_hash(&blocks, &content_selection, hash_algorithm)?,
+            blocks,
+            content_selection,
+            hash_algorithm,
+            commitment_blinding: None,
+            materialization: None,
+        };
+
+        let scheme = self.registry.scheme(&self.config.proof_type)?;
+        let segment_cycles = Self::execute_guest(scheme, &proof_input)?;
+        let total_cycles = segment_cycles.iter().map(|segment| segment.cycles).sum();
+        let padded_cycles: u64 = segment_cycles.iter().map(|segment| 1u64 << segment.po2).sum();
+        let largest_segment = segment_cycles.iter().map(|segment| 1u64 << segment.po2).max().unwrap_or(0);
+
+        let estimated_proof_size = match self.config.receipt_kind {
+            ReceiptKind::Composite => COMPOSITE_SEGMENT_RECEIPT_BYTES * segment_cycles.len() as u64,
+            ReceiptKind::Succinct => SUCCINCT_RECEIPT_BYTES,
+            ReceiptKind::Groth16 => GROTH16_RECEIPT_BYTES,
+        };
+
+        let detected_acceleration = self.detect_hardware_acceleration().unwrap_or(HardwareAcceleration::None);
+        let (recommended_prover, recommendation) = match detected_acceleration {
+            HardwareAcceleration::Cuda => (ProverType::Local, "Prove locally with CUDA acceleration".to_string()),
+            HardwareAcceleration::Metal => (ProverType::Local, "Prove locally with Metal acceleration".to_string()),
+            HardwareAcceleration::None if segment_cycles.len() > BONSAI_RECOMMENDATION_SEGMENTS => (
+                ProverType::Bonsai,
+                format!(
+                    "No GPU detected and {} segments to prove; Bonsai will be considerably faster",
+                    segment_cycles.len()
+                ),
+            ),
+            HardwareAcceleration::None => (ProverType::Local, "Prove locally on the CPU".to_string()),
+        };
+        let segment_ms = self.segment_proving_ms(&Some(detected_acceleration.clone()));
+
+        Ok(ProofEstimate {
+            total_cycles,
+            estimated_proof_size: Bytes(estimated_proof_size),
+            estimated_peak_memory: Bytes(largest_segment * PROVER_BYTES_PER_CYCLE),
+            estimated_proving_time_ms: Millis(padded_cycles * segment_ms / SEGMENT_SIZE_CYCLES),
+            segment_cycles,
+            detected_acceleration,
+            recommended_prover,
+            recommendation,
+        })
     }
 
-    /// Extracts the actual content bytes from IPFS blocks
-    fn extract_content(
+    /// Approximate time to prove one full segment with the given acceleration
+    fn segment_proving_ms(&self, acceleration: &Option<HardwareAcceleration>) -> u64 {
+        match acceleration {
+            Some(HardwareAcceleration::Cuda | HardwareAcceleration::Metal) => GPU_SEGMENT_PROVING_MS,
+            _ => CPU_SEGMENT_PROVING_MS,
+        }
+    }
+
+    /// Resolves one (guest-ready) selection for a preflight, recording its span or why it fails
+    fn preflight_selection(
         &self,
         blocks: &[IpfsBlock],
-        content_selection: &ContentSelection,
-    ) -> Result<Vec<u8>> {
-        match content_selection {
-            ContentSelection::ByteRange { start, end } => {
-                self.extract_byte_range(blocks, *start, *end)
+        selection: &ContentSelection,
+        spans: &mut Vec<SelectionSpan>,
+        problems: &mut Vec<String>,
+    ) {
+        let data: Vec<u8> = blocks.iter().flat_map(|block| block.data.iter().copied()).collect();
+        let mut record = |start: usize, end: usize, matched_offsets: Vec<u64>| {
+            spans.push(SelectionSpan {
+                selection: selection.description(),
+                start: start as u64,
+                end: end as u64,
+                matched_offsets,
+            });
+        };
+
+        match selection {
+            ContentSelection::ByteRange { start, end } | ContentSelection::JsonSpan { start, end } => {
+                if *end > data.len() {
+                    problems.push(format!(
+                        "Byte range {}..{} extends past the end of the content ({} bytes)",
+                        start, end, data.len()
+                    ));
+                } else {
+                    record(*start, *end, Vec::new());
+                }
             }
             ContentSelection::Pattern { content } => {
-                self.extract_pattern(blocks, content)
+                let offsets: Vec<u64> = memmem::find_iter(&data, content)
+                    .map(|offset| offset as u64)
+                    .take(MAX_REPORTED_MATCHES)
+                    .collect();
+                match offsets.first() {
+                    Some(&first) => record(first as usize, first as usize + content.len(), offsets),
+                    None => problems.push(format!(
+                        "Pattern of {} bytes not found in file content",
+                        content.len()
+                    )),
+                }
+            }
+            ContentSelection::PatternHash { hash, len, fingerprint } => {
+                match self.find_pattern_hash(&data, hash, *len, *fingerprint) {
+                    Some(offset) => record(offset, offset + len, vec![offset as u64]),
+                    None => problems.push("Hashed pattern not found in file content".to_string()),
+                }
             }
             ContentSelection::Regex { pattern } => {
-                self.extract_regex(blocks, pattern)
+                let matches = std::str::from_utf8(&data)
+                    .map_err(|_| "File content is not valid UTF-8, cannot apply Regex".to_string())
+                    .and_then(|text| {
+                        let re = Regex::new(pattern).map_err(|e| format!("Invalid regex pattern: {}", e))?;
+                        Ok(re.find_iter(text)
+                            .map(|found| found.range())
+                            .take(MAX_REPORTED_MATCHES)
+                            .collect::<Vec<_>>())
+                    });
+                match matches {
+                    Ok(ranges) if !ranges.is_empty() => {
+                        let offsets = ranges.iter().map(|range| range.start as u64).collect();
+                        record(ranges[0].start, ranges[0].end, offsets);
+                    }
+                    Ok(_) => problems.push(format!("Regex pattern '{}' not found in file content", pattern)),
+                    Err(problem) => problems.push(problem),
+                }
+            }
+            ContentSelection::MultiRange(ranges) => {
+                if !selection.is_valid() {
+                    problems.push("Byte ranges must be non-empty, ascending and disjoint".to_string());
+                }
+                for &(start, end) in ranges {
+                    if end > data.len() {
+                        problems.push(format!(
+                            "Byte range {}..{} extends past the end of the content ({} bytes)",
+                            start, end, data.len()
+                        ));
+                    } else {
+                        record(start, end, Vec::new());
+                    }
+                }
             }
             ContentSelection::Multiple(selections) => {
-                let mut combined = Vec::new();
                 for selection in selections {
-                    let mut content = self.extract_content(blocks, selection)?;
-                    combined.append(&mut content);
+                    self.preflight_selection(blocks, selection, spans, problems);
+                }
+            }
+            other => {
+                if let Err(e) = self.extract_content(blocks, other) {
+                    problems.push(e.to_string());
                 }
-                Ok(combined)
             }
         }
     }
 
-    /// Extracts content matching a regex pattern
-    fn extract_regex(
-        &self,
-        blocks: &[IpfsBlock],
-        pattern: &str,
-    ) -> Result<Vec<u8>> {
-        // Concatenate all block data for pattern searching
-        // Note: For very large files, this approach is memory intensive.
-        // A streaming regex implementation would be better for optimization.
-        let mut all_data = Vec::new();
-        for block in blocks {
-            all_data.extend_from_slice(&block.data);
-        }
-        
-        // Attempt to convert to string (lossy if not UTF-8, but regex usually assumes string)
-        let data_str = match std::str::from_utf8(&all_data) {
-            Ok(s) => s,
-            Err(_) => {
-                return Err(ProofError::content_selection_error(
-                    "File content is not valid UTF-8, cannot apply Regex"
-                ));
-            }
-        };
-
-        let re = Regex::new(pattern).map_err(|e| {
-            ProofError::content_selection_error(format!("Invalid regex pattern: {}", e))
-        })?;
+    /// Renews a proof by generating a fresh ZK receipt for the same, unchanged content
+    ///
+    /// The file at `file_path` must hash to the original proof's file hash. The
+    /// renewed proof keeps the original selection, hashes and file information,
+    /// gets a new ID and validity window, and records the original ID in
+    /// `metadata.renewed_from`. When no validity is configured, the original
+    /// proof's validity duration is reused.
+    #[instrument(skip(self, proof, file_path), fields(proof_id = %proof.id, request_id = self.request_id.as_deref()))]
+    pub async fn renew_proof(&mut self, proof: &Proof, file_path: &Path) -> Result<Proof> {
+        let start_time = Instant::now();
+        self.events.emit(MonitorEvent::ProofStarted);
 
-        if let Some(mat) = re.find(data_str) {
-            Ok(mat.as_str().as_bytes().to_vec())
-        } else {
-            Err(ProofError::content_selection_error(
-                "Regex pattern not found in file content"
-            ))
+        if proof.metadata.content_committed {
+            return Err(ProofError::invalid_input_error("proof", "Committed proofs cannot be renewed"));
         }
-    }
 
-    /// Extracts content from a byte range
-    fn extract_byte_range(
-        &self,
-        blocks: &[IpfsBlock],
-        start: usize,
-        end: usize,
-    ) -> Result<Vec<u8>> {
-        let mut content = Vec::new();
-        let mut current_offset = 0;
+        let file_processing_start = Instant::now();
+        let (blocks, file_info) = self.process_file_for(proof, file_path).await?;
+        let file_processing_time = file_processing_start.elapsed();
 
-        for block in blocks {
-            let block_start = current_offset;
-            let block_end = current_offset + block.data.len();
+        if file_info.file_hash != proof.metadata.file_info.file_hash {
+            return Err(ProofError::invalid_input_error(
+                "file_path",
+                "File content has changed since the proof was generated"
+            ));
+        }
 
-            if block_start < end && block_end > start {
-                let extract_start = if start > block_start { start - block_start } else { 0 };
-                let extract_end = if end < block_end { end - block_start } else { block.data.len() };
-                
-                content.extend_from_slice(&block.data[extract_start..extract_end]);
-            }
+        let hash_algorithm = proof.metadata.security.hash_algorithm;
+        let content_hash = self.extract_content_hash(&blocks, &proof.content_selection, hash_algorithm)?;
+        if content_hash != proof.content_hash {
+            return Err(ProofError::content_selection_error(
+                "Selected content no longer matches the proof's content hash"
+            ));
+        }
 
-            current_offset = block_end;
-            if current_offset >= end {
-                break;
-            }
+        let memory_sampler = MemorySampler::start();
+        let zk_generation_start = Instant::now();
+        let (receipt, zk_cycles, segment_cycles) = self.generate_zk_proof(&proof.proof_type, ProofInput {
+            blocks,
+            content_selection: proof.content_selection.clone(),
+            expected_content_hash: content_hash,
+            hash_algorithm,
+            commitment_blinding: None,
+            materialization: None,
+        }).await?;
+        let zk_generation_time = zk_generation_start.elapsed();
+        let peak_memory_bytes = memory_sampler.finish();
+
+        let proof_output: ProofOutput = receipt.journal.decode()
+            .map_err(|e| ProofError::serialization_error(
+                "Failed to decode proof output from receipt",
+                Some(Box::new(e))
+            ))?;
+        if proof_output.root_hash != proof.root_hash {
+            return Err(ProofError::verification_error(
+                "Renewed receipt does not match the original root hash; check the chunking strategy"
+            ));
         }
 
-        if content.is_empty() {
-            return Err(ProofError::content_selection_error(
-                format!("No content found in byte range {}..{}", start, end)
+        let total_time = start_time.elapsed();
+        let (receipt_bytes, receipt_len) = self.encode_receipt(&receipt)?;
+        let mut metadata = self.create_proof_metadata(
+            proof_output.metadata,
+            proof_output.hash_algorithm,
+            file_info,
+            total_time,
+            file_processing_time,
+            zk_generation_time,
+            zk_cycles,
+            segment_cycles,
+            peak_memory_bytes,
+            &receipt_bytes,
+            receipt_len,
+        )?;
+        metadata.custom = proof.metadata.custom.clone();
+
+        let created_at = Utc::now();
+        metadata.valid_until = self.validity_deadline(created_at).or_else(|| {
+            proof.metadata.valid_until.map(|valid_until| created_at + (valid_until - proof.created_at))
+        });
+        metadata.renewed_from = proof.metadata.renewed_from.clone();
+        metadata.renewed_from.push(proof.id.clone());
+
+        let renewed = Proof {
+            id: Uuid::new_v4().to_string(),
+            zk_proof: ZkProofData {
+                receipt: receipt_bytes,
+                public_inputs: proof.zk_proof.public_inputs.clone(),
+                format_version: self.config.receipt_kind.format_version(),
+                compression: Some(self.config.compression.clone()),
+            },
+            metadata,
+            content_selection: proof.content_selection.clone(),
+            content_hash: proof.content_hash,
+            root_hash: proof.root_hash,
+            created_at,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            proof_type: proof.proof_type.clone(),
+            image_id: Some(self.registry.scheme(&proof.proof_type)?.image_id),
+            signature: None,
+            timestamp: None,
+        };
+
+        self.update_generation_stats(&renewed, total_time);
+        let renewed = self.seal(renewed).await?;
+
+        info!(
+            "Renewed proof {} as {} in {}ms",
+            &proof.id[..8],
+            &renewed.id[..8],
+            total_time.as_millis()
+        );
+
+        Ok(renewed)
+    }
+
+    /// Extends a proof of an append-only file to cover the bytes appended since
+    ///
+    /// The file at `file_path` must start with exactly the content `prior` was
+    /// generated from. Only the appended bytes are proven; the new proof records
+    /// `prior` in `metadata.extends` and chains its root and content hashes to
+    /// the prior ones (see [`ProofExtension`]). The extended proof verifies
+    /// against the prior proof's content followed by all appended bytes.
+    #[instrument(skip(self, prior, file_path), fields(prior_id = %prior.id, request_id = self.request_id.as_deref()))]
+    pub async fn extend_proof(&mut self, prior: &Proof, file_path: &Path) -> Result<Proof> {
+        let start_time = Instant::now();
+        self.events.emit(MonitorEvent::ProofStarted);
+
+        if prior.metadata.content_committed {
+            return Err(ProofError::invalid_input_error("prior", "Committed proofs cannot be extended"));
+        }
+
+        let file_processing_start = Instant::now();
+        let (blocks, file_info) = self.process_file_for(prior, file_path).await?;
+        let content = self.ipfs_processor.reconstruct_content(&blocks)?;
+        drop(blocks);
+
+        let prior_size = prior.metadata.file_info.size.0;
+        let prior_end = usize::try_from(prior_size).ok()
+            .filter(|&end| end < content.len())
+            .ok_or_else(|| ProofError::invalid_input_error(
+                "file_path",
+                "File has no content appended since the prior proof"
+            ))?;
+        let (prefix, suffix) = content.split_at(prior_end);
+        let prefix_hash: [u8; 32] = Sha256::digest(prefix).into();
+        if prefix_hash != prior.metadata.file_info.file_hash {
+            return Err(ProofError::invalid_input_error(
+                "file_path",
+                "File content before the appended bytes has changed since the prior proof"
             ));
         }
+        let prior_content_len = self.proven_content_len(prior, prefix)?;
 
-        Ok(content)
+        let suffix_blocks = self.ipfs_processor.create_blocks_with(suffix, &self.chunking_of(prior))?;
+        let (suffix_offset, _) = content_stream(&suffix_blocks);
+        let suffix_selection = ContentSelection::ByteRange {
+            start: suffix_offset,
+            end: suffix_offset + suffix.len(),
+        };
+        // The whole chain hashes content with the algorithm of its first proof
+        let hash_algorithm = prior.metadata.security.hash_algorithm;
+        let suffix_content_hash = self.extract_content_hash(&suffix_blocks, &suffix_selection, hash_algorithm)?;
+        let file_processing_time = file_processing_start.elapsed();
+
+        debug!(
+            "Proving {} appended bytes in {} blocks",
+            suffix.len(),
+            suffix_blocks.len()
+        );
+
+        let memory_sampler = MemorySampler::start();
+        let zk_generation_start = Instant::now();
+        let (receipt, zk_cycles, segment_cycles) = self.generate_zk_proof(&prior.proof_type, ProofInput {
+            blocks: suffix_blocks,
+            content_selection: suffix_selection.clone(),
+            expected_content_hash: suffix_content_hash,
+            hash_algorithm,
+            commitment_blinding: None,
+            materialization: None,
+        }).await?;
+        let zk_generation_time = zk_generation_start.elapsed();
+        let peak_memory_bytes = memory_sampler.finish();
+
+        let proof_output: ProofOutput = receipt.journal.decode()
+            .map_err(|e| ProofError::serialization_error(
+                "Failed to decode proof output from receipt",
+                Some(Box::new(e))
+            ))?;
+
+        let total_time = start_time.elapsed();
+        let (receipt_bytes, receipt_len) = self.encode_receipt(&receipt)?;
+        let mut metadata = self.create_proof_metadata(
+            proof_output.metadata,
+            proof_output.hash_algorithm,
+            file_info,
+            total_time,
+            file_processing_time,
+            zk_generation_time,
+            zk_cycles,
+            segment_cycles,
+            peak_memory_bytes,
+            &receipt_bytes,
+            receipt_len,
+        )?;
+        let created_at = Utc::now();
+        metadata.valid_until = self.validity_deadline(created_at);
+
+        let extension = ProofExtension {
+            prior: Box::new(prior.clone()),
+            suffix_root_hash: proof_output.root_hash,
+            suffix_content_hash,
+            prior_content_len,
+        };
+        let root_hash = ProofExtension::chain_hash(&prior.root_hash, &extension.suffix_root_hash);
+        let content_hash = ProofExtension::chain_hash(&prior.content_hash, &suffix_content_hash);
+        metadata.extends = Some(extension);
+
+        let extended = Proof {
+            id: Uuid::new_v4().to_string(),
+            zk_proof: ZkProofData {
+                receipt: receipt_bytes,
+                public_inputs: bincode::serialize(&content_hash)
+                    .map_err(|e| ProofError::serialization_error(
+                        "Failed to serialize public inputs",
+                        Some(Box::new(e))
+                    ))?,
+                format_version: self.config.receipt_kind.format_version(),
+                compression: Some(self.config.compression.clone()),
+            },
+            metadata,
+            content_selection: suffix_selection,
+            content_hash,
+            root_hash,
+            created_at,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            proof_type: prior.proof_type.clone(),
+            image_id: Some(self.registry.scheme(&prior.proof_type)?.image_id),
+            signature: None,
+            timestamp: None,
+        };
+
+        self.update_generation_stats(&extended, total_time);
+        let extended = self.seal(extended).await?;
+
+        info!(
+            "Extended proof {} as {} with {} appended bytes in {}ms",
+            &prior.id[..8],
+            &extended.id[..8],
+            suffix.len(),
+            total_time.as_millis()
+        );
+
+        Ok(extended)
     }
 
-    /// Extracts content matching a pattern
-    fn extract_pattern(
-        &self,
-        blocks: &[IpfsBlock],
-        pattern: &[u8],
-    ) -> Result<Vec<u8>> {
-        // Concatenate all block data for pattern searching
-        let mut all_data = Vec::new();
-        for block in blocks {
-            all_data.extend_from_slice(&block.data);
+    /// Generates a proof of a byte range from a partly materialized DAG
+    ///
+    /// Only the root block and the leaves `dag` supplies are given to the
+    /// prover, so large files can be proven without fetching every block. The
+    /// root hash is the same as for a proof of the whole DAG and the guest
+    /// reports what it was given as [`Coverage`], see [`Self::proof_coverage`].
+    /// The file's SHA-256 is not known, so `file_info.file_hash` is all zeroes.
+    #[instrument(skip(self, dag), fields(leaves = dag.leaves.len(), request_id = self.request_id.as_deref()))]
+    pub async fn generate_partial_proof(
+        &mut self,
+        dag: &PartialDag,
+        content_selection: ContentSelection,
+    ) -> Result<Proof> {
+        let start_time = Instant::now();
+        self.events.emit(MonitorEvent::ProofStarted);
+
+        let file_processing_start = Instant::now();
+        dag.validate()?;
+        let hash_algorithm = self.config.hash_algorithm;
+        let content = dag.extract_content(&content_selection)?;
+        let content_hash = content_selection.content_hash(hash_algorithm, &content);
+        let (blocks, materialization) = dag.guest_input();
+        let coverage = dag.coverage();
+        let root_cid = cid::Cid::try_from(&dag.root.cid[..])
+            .map_err(|e| ProofError::ipfs_error(
+                "partial_proof",
+                format!("Invalid root block CID: {}", e),
+                Some(Box::new(e)),
+            ))?;
+        let file_size: u64 = dag.root.links.iter().map(|link| link.size).sum();
+        let file_info = FileInfo {
+            filename: None,
+            size: Bytes(file_size),
+            mime_type: None,
+            file_hash: [0u8; 32],
+            ipfs_cid: root_cid.to_string(),
+            block_count: coverage.total_blocks,
+            avg_block_size: Bytes(file_size / dag.root.links.len().max(1) as u64),
+            source_compression: None,
+            chunk_size: None,
+        };
+        let file_processing_time = file_processing_start.elapsed();
+
+        debug!(
+            "Proving {} bytes from {} of {} blocks",
+            content.len(),
+            coverage.materialized_blocks,
+            coverage.total_blocks
+        );
+
+        let memory_sampler = MemorySampler::start();
+        let zk_generation_start = Instant::now();
+        let (receipt, zk_cycles, segment_cycles) = self.generate_zk_proof(&self.config.proof_type, ProofInput {
+            blocks,
+            content_selection: content_selection.clone(),
+            expected_content_hash: content_hash,
+            hash_algorithm,
+            commitment_blinding: None,
+            materialization: Some(materialization),
+        }).await?;
+        let zk_generation_time = zk_generation_start.elapsed();
+        let peak_memory_bytes = memory_sampler.finish();
+
+        let proof_output: ProofOutput = receipt.journal.decode()
+            .map_err(|e| ProofError::serialization_error(
+                "Failed to decode proof output from receipt",
+                Some(Box::new(e))
+            ))?;
+
+        let total_time = start_time.elapsed();
+        let (receipt_bytes, receipt_len) = self.encode_receipt(&receipt)?;
+        let mut metadata = self.create_proof_metadata(
+            proof_output.metadata,
+            proof_output.hash_algorithm,
+            file_info,
+            total_time,
+            file_processing_time,
+            zk_generation_time,
+            zk_cycles,
+            segment_cycles,
+            peak_memory_bytes,
+            &receipt_bytes,
+            receipt_len,
+        )?;
+        let created_at = Utc::now();
+        metadata.valid_until = self.validity_deadline(created_at);
+
+        let proof = Proof {
+            id: Uuid::new_v4().to_string(),
+            zk_proof: ZkProofData {
+                receipt: receipt_bytes,
+                public_inputs: bincode::serialize(&content_hash)
+                    .map_err(|e| ProofError::serialization_error(
+                        "Failed to serialize public inputs",
+                        Some(Box::new(e))
+                    ))?,
+                format_version: self.config.receipt_kind.format_version(),
+                compression: Some(self.config.compression.clone()),
+            },
+            metadata,
+            content_selection,
+            content_hash,
+            root_hash: proof_output.root_hash,
+            created_at,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            proof_type: self.config.proof_type.clone(),
+            image_id: Some(self.registry.scheme(&self.config.proof_type)?.image_id),
+            signature: None,
+            timestamp: None,
+        };
+
+        self.update_generation_stats(&proof, total_time);
+        let proof = self.seal(proof).await?;
+
+        info!(
+            "Partial proof generated in {}ms from {:.1}% of the DAG (proof_id: {})",
+            total_time.as_millis(),
+            coverage.fraction() * 100.0,
+            &proof.id[..8]
+        );
+
+        Ok(proof)
+    }
+
+    /// Returns the chunking strategy that re-derives the blocks of `proof`
+    ///
+    /// A block size recorded in the proof by adaptive chunking is used
+    /// instead of this generator's strategy, so the blocks match even if this
+    /// generator chunks differently.
+    fn chunking_of(&self, proof: &Proof) -> ChunkingStrategy {
+        match proof.metadata.file_info.chunk_size {
+            Some(size) => ChunkingStrategy::FixedSize(size.0 as usize),
+            None => self.ipfs_processor.chunking().clone(),
         }
+    }
 
-        // Find pattern
-        if let Some(_pos) = self.find_pattern(&all_data, pattern) {
-            Ok(pattern.to_vec())
+    /// Processes `file_path` into the blocks `proof` was generated from, keeping its recorded block size
+    async fn process_file_for(&self, proof: &Proof, file_path: &Path) -> Result<(Vec<IpfsBlock>, FileInfo)> {
+        let (blocks, mut file_info) = self.ipfs_processor
+            .process_file_with(file_path, &self.chunking_of(proof))
+            .await
+            .context("Failed to process file into IPFS blocks")?;
+        file_info.chunk_size = file_info.chunk_size.or(proof.metadata.file_info.chunk_size);
+        Ok((blocks, file_info))
+    }
+
+    /// Returns the length of the content `proof` was generated for, given the file content it covers
+    ///
+    /// Also checks that the content still reproduces the proof's root and content hashes.
+    fn proven_content_len(&self, proof: &Proof, file_content: &[u8]) -> Result<u64> {
+        if let Some(extension) = &proof.metadata.extends {
+            let appended = proof.metadata.file_info.size.0
+                - extension.prior.metadata.file_info.size.0;
+            return Ok(extension.prior_content_len + appended);
+        }
+
+        let blocks = self.ipfs_processor.create_blocks_with(file_content, &self.chunking_of(proof))?;
+        if IpfsProcessor::structure_hash(&blocks) != proof.root_hash {
+            return Err(ProofError::verification_error(
+                "File blocks do not match the prior proof's root hash; check the chunking strategy"
+            ));
+        }
+
+        let content = self.extract_content(&blocks, &proof.content_selection)?;
+        if proof.content_selection.content_hash(proof.metadata.security.hash_algorithm, &content) != proof.content_hash {
+            return Err(ProofError::content_selection_error(
+                "Selected content no longer matches the prior proof's content hash"
+            ));
+        }
+        Ok(content.len() as u64)
+    }
+
+    /// Verifies a zero-knowledge proof against the claimed content
+    #[instrument(skip(self, proof, claimed_content))]
+    pub async fn verify_proof(
+        &mut self,
+        proof: &Proof,
+        claimed_content: impl Into<Secret<Vec<u8>>>,
+    ) -> Result<bool> {
+        let start_time = Instant::now();
+        let claimed_content = claimed_content.into();
+        
+        info!("Starting proof verification for proof: {}", &proof.id[..8]);
+        
+        // Verify the receipt of each proof in an extension chain against its journal hashes
+        let mut is_valid = proof.is_chain_linked();
+        for proof in proof.chain() {
+            let receipt: Receipt = bincode::deserialize(&proof.zk_proof.receipt_bytes()?)
+                .map_err(|e| ProofError::serialization_error(
+                    "Failed to deserialize receipt",
+                    Some(Box::new(e))
+                ))?;
+
+            receipt.verify(self.registry.scheme(&proof.proof_type)?.image_id)
+                .map_err(|e| ProofError::verification_error(
+                    format!("Receipt verification failed: {}", e)
+                ))?;
+
+            let proof_output: ProofOutput = receipt.journal.decode()
+                .map_err(|e| ProofError::serialization_error(
+                    "Failed to decode proof output",
+                    Some(Box::new(e))
+                ))?;
+
+            let (root_hash, content_hash) = proof.journal_hashes();
+            is_valid &= proof_output.content_hash == content_hash
+                && proof_output.root_hash == root_hash
+                && proof_output.hash_algorithm == proof.metadata.security.hash_algorithm
+                && proof_output.selection_hash == proof.content_selection.selection_hash()
+                && proof.content_selection.range_end()
+                    .map_or(true, |end| end as u64 <= proof_output.content_length);
+        }
+
+        // Verify that the claimed content hash matches the proof
+        is_valid &= proof.claimed_content_hash(claimed_content.expose()) == Some(proof.content_hash);
+        
+        let verification_time = start_time.elapsed();
+        
+        // Update statistics
+        self.update_verification_stats(is_valid, verification_time);
+        self.events.emit(MonitorEvent::VerificationCompleted { valid: is_valid });
+        
+        if is_valid {
+            info!(
+                "Proof verification successful in {}ms",
+                verification_time.as_millis()
+            );
         } else {
-            Err(ProofError::content_selection_error(
-                "Pattern not found in file content"
-            ))
+            warn!(
+                "Proof verification failed in {}ms",
+                verification_time.as_millis()
+            );
+        }
+        
+        Ok(is_valid)
+    }
+
+    /// Writes a CARv1 archive with the blocks needed to verify a proof offline
+    ///
+    /// The archive holds the root block and every block on the proof's inclusion
+    /// path. Blocks are re-derived from `file_path`, which must be the file the
+    /// proof was generated from, using the block size recorded in the proof or
+    /// else this generator's chunking strategy.
+    pub async fn export_car<W: Write>(&self, proof: &Proof, file_path: &Path, writer: W) -> Result<()> {
+        let (blocks, _) = self.process_file_for(proof, file_path).await?;
+
+        if IpfsProcessor::structure_hash(&blocks) != proof.root_hash {
+            return Err(ProofError::verification_error(
+                "File blocks do not match the proof's root hash"
+            ));
+        }
+
+        let inclusion_paths = Self::decode_proof_output(proof)?.inclusion_paths;
+        let (root, content_blocks) = blocks.split_first()
+            .ok_or_else(|| ProofError::verification_error("File produced no blocks"))?;
+        let selected = std::iter::once(root).chain(content_blocks.iter().filter(|block| {
+            let hash: [u8; 32] = Sha256::digest(&block.data).into();
+            inclusion_paths.iter().any(|path| path.contains(&hash))
+        }));
+
+        let entries = selected
+            .map(|block| {
+                let cid = cid::Cid::try_from(&block.cid[..])
+                    .map_err(|e| ProofError::ipfs_error(
+                        "car_export",
+                        format!("Invalid block CID: {}", e),
+                        Some(Box::new(e)),
+                    ))?;
+                Ok((cid, &block.data[..]))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        car::write_car(writer, &[entries[0].0], &entries)
+    }
+
+    /// Reads blocks from a CARv1 archive written by [`export_car`](Self::export_car)
+    ///
+    /// The root block comes first, with its links restored.
+    pub fn import_car<R: Read>(reader: R) -> Result<Vec<IpfsBlock>> {
+        let (roots, entries) = car::read_car(reader)?;
+        let root = match roots.as_slice() {
+            [root] => *root,
+            _ => return Err(ProofError::verification_error(
+                format!("Expected a single CAR root, found {}", roots.len())
+            )),
+        };
+
+        let mut blocks = Vec::with_capacity(entries.len());
+        let mut root_block = None;
+        for (cid, data) in entries {
+            let block = IpfsBlock { data, cid: cid.to_bytes(), links: Vec::new() };
+            if cid == root && root_block.is_none() {
+                root_block = Some(block);
+            } else {
+                blocks.push(block);
+            }
+        }
+
+        let mut root_block = root_block
+            .ok_or_else(|| ProofError::verification_error("CAR archive is missing its root block"))?;
+        if !blocks.is_empty() {
+            root_block.links = IpfsProcessor::decode_root_links(&root_block.data)?;
         }
+        blocks.insert(0, root_block);
+
+        Ok(blocks)
+    }
+
+    /// Verifies a proof against blocks imported from a CAR archive, without an IPFS node
+    ///
+    /// Checks that the blocks back the proof (see
+    /// [`blocks_back_proof`](Self::blocks_back_proof)), then verifies the proof
+    /// against the claimed content.
+    pub async fn verify_proof_with_blocks(
+        &mut self,
+        proof: &Proof,
+        blocks: &[IpfsBlock],
+        claimed_content: &[u8],
+    ) -> Result<bool> {
+        if !self.blocks_back_proof(proof, blocks)? {
+            return Ok(false);
+        }
+        self.verify_proof(proof, claimed_content).await
+    }
+
+    /// Checks that blocks imported from a CAR archive reproduce the proof's
+    /// root hash and cover its inclusion path
+    pub fn blocks_back_proof(&self, proof: &Proof, blocks: &[IpfsBlock]) -> Result<bool> {
+        Ok(self.check_blocks(proof, blocks)?.is_none())
+    }
+
+    /// Returns why `blocks` do not back `proof`, or `None` if they do
+    ///
+    /// The blocks must be the proof's root block followed by blocks it links to.
+    /// Each block on the inclusion path must be among them, so a proof is never
+    /// accepted on the strength of hashes alone; the first one that is not is
+    /// reported as [`VerificationFailure::MissingBlock`] with its CID.
+    pub fn check_blocks(&self, proof: &Proof, blocks: &[IpfsBlock]) -> Result<Option<VerificationFailure>> {
+        self.ipfs_processor.validate_blocks(blocks)?;
+
+        let Some(root) = blocks.first() else {
+            return Ok(Some(VerificationFailure::RootHashMismatch));
+        };
+
+        // Blocks outside the inclusion path are absent, so stand in for them by CID
+        let mut structure = vec![root.clone()];
+        structure.extend(root.links.iter().map(|link| IpfsBlock {
+            data: Vec::new(),
+            cid: link.cid.clone(),
+            links: Vec::new(),
+        }));
+        let linked = blocks[1..].iter().all(|block| root.links.iter().any(|link| link.cid == block.cid));
+        if !linked || IpfsProcessor::structure_hash(&structure) != proof.root_hash {
+            warn!("Imported blocks do not match the proof's root hash");
+            return Ok(Some(VerificationFailure::RootHashMismatch));
+        }
+
+        let block_hashes: Vec<[u8; 32]> = blocks.iter()
+            .map(|block| Sha256::digest(&block.data).into())
+            .collect();
+        let inclusion_paths = Self::decode_proof_output(proof)?.inclusion_paths;
+        if let Some(missing) = inclusion_paths.iter().flatten().find(|hash| !block_hashes.contains(hash)) {
+            let cid = missing_block_cid(root, missing);
+            warn!("Imported blocks do not include block {} of the proof's inclusion path", cid);
+            return Ok(Some(VerificationFailure::MissingBlock(cid)));
+        }
+
+        Ok(None)
+    }
+
+    /// Returns how much of the DAG a proof was generated from
+    pub fn proof_coverage(proof: &Proof) -> Result<Coverage> {
+        Ok(Self::decode_proof_output(proof)?.coverage)
+    }
+
+    /// Decodes the guest output committed to a proof's receipt journal
+    fn decode_proof_output(proof: &Proof) -> Result<ProofOutput> {
+        let receipt: Receipt = bincode::deserialize(&proof.zk_proof.receipt_bytes()?)
+            .map_err(|e| ProofError::serialization_error(
+                "Failed to deserialize receipt",
+                Some(Box::new(e))
+            ))?;
+        receipt.journal.decode()
+            .map_err(|e| ProofError::serialization_error(
+                "Failed to decode proof output",
+                Some(Box::new(e))
+            ))
+    }
+
+    /// Returns current proof generation statistics
+    pub fn get_statistics(&self) -> &ProofStatistics {
+        &self.stats
+    }
+
+    /// Updates the configuration
+    pub fn update_config(&mut self, config: ProofConfig) -> Result<()> {
+        self.ipfs_processor.set_chunking(config.chunking_strategy.clone())?;
+        self.ipfs_processor.set_mmap_threshold(config.mmap_threshold);
+        self.ipfs_processor.set_decompression(config.decompress_input.clone());
+        self.ipfs_processor.set_max_decompressed_size(config.max_file_size_bytes);
+        if config.worker_threads != self.config.worker_threads {
+            self.ipfs_processor.set_worker_threads(config.worker_threads)?;
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    /// Sets the key that signs generated, renewed and extended proofs
+    ///
+    /// Proofs are cached unsigned, so cached proofs are signed with the key
+    /// current when they are returned.
+    pub fn set_signing_key(&mut self, key: Option<SigningKey>) {
+        self.signing_key = key;
+    }
+
+    /// Registers the guest that proves `proof_type`, replacing any earlier one
+    ///
+    /// Proofs of that type are generated with it once `ProofConfig::proof_type`
+    /// selects it; renewed and extended proofs keep the type of the proof they
+    /// replace.
+    pub fn register_proof_type(&mut self, proof_type: ProofType, scheme: ProofScheme) {
+        self.registry.register(proof_type, scheme);
+    }
+
+    /// Signs `proof` with the configured signing key and stamps it with the
+    /// configured timestamp authority, if any
+    async fn seal(&self, mut proof: Proof) -> Result<Proof> {
+        if let Some(key) = &self.signing_key {
+            proof.sign(key)?;
+        }
+        if let Some(url) = &self.config.timestamp_authority {
+            TimestampClient::new(url.clone()).timestamp_proof(&mut proof).await?;
+        }
+        Ok(proof)
+    }
+
+    /// Event bus the generator reports proof and cache events to
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Report events to `events`, for sharing one bus with other components
+    pub fn set_events(&mut self, events: EventBus) {
+        self.events = events;
+    }
+
+    /// Credentials used when the prover type is Bonsai, read from
+    /// `BONSAI_API_URL` and `BONSAI_API_KEY` unless replaced
+    pub fn bonsai_credentials(&self) -> &BonsaiCredentialStore {
+        &self.bonsai_credentials
+    }
+
+    /// Prove on Bonsai with the credentials in `credentials`, which can be
+    /// updated while the generator is in use
+    pub fn set_bonsai_credentials(&mut self, credentials: BonsaiCredentialStore) {
+        self.bonsai_credentials = credentials;
+    }
+
+    /// Id of the request proofs are generated for, if set
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// Records `request_id` on the spans of every proof generated from now
+    /// on, so its logs can be matched to the request that caused them
+    pub fn set_request_id(&mut self, request_id: impl Into<String>) {
+        self.request_id = Some(request_id.into());
+    }
+
+    /// Returns proof cache statistics, if caching is enabled
+    pub fn cache_statistics(&self) -> Option<&CacheStatistics> {
+        self.cache.as_ref().map(|cache| cache.get_statistics())
+    }
+
+    /// Builds the cache key for a file's processing result.
+    ///
+    /// The key covers the path, size and modification time, so editing the file
+    /// invalidates every proof cached for it.
+    fn file_cache_key(file_path: &Path) -> Result<CacheKey> {
+        let metadata = std::fs::metadata(file_path)?;
+        let modified_nanos = metadata.modified().ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0);
+        let path = file_path.canonicalize().unwrap_or_else(|_| file_path.to_path_buf());
+
+        let mut hasher = Sha256::new();
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update(metadata.len().to_le_bytes());
+        hasher.update(modified_nanos.to_le_bytes());
+        Ok(CacheKey::IpfsFile(hex::encode(hasher.finalize())))
+    }
+
+    /// Builds the cache key for a proof over `file_hash` with the given selection,
+    /// the config and the image ID of the scheme proving it
+    fn proof_cache_key(
+        &self,
+        file_hash: &[u8; 32],
+        content_selection: &ContentSelection,
+    ) -> CacheKey {
+        CacheKey::Proof(self.generation_digest(file_hash, content_selection))
+    }
+
+    /// Builds the cache key for the checkpoint of a proof, covering what
+    /// [`proof_cache_key`](Self::proof_cache_key) covers
+    fn checkpoint_cache_key(
+        &self,
+        file_hash: &[u8; 32],
+        content_selection: &ContentSelection,
+    ) -> CacheKey {
+        CacheKey::Checkpoint(self.generation_digest(file_hash, content_selection))
+    }
+
+    /// Hex digest of a file hash, selection, the config and the image ID of
+    /// the scheme proving it
+    fn generation_digest(&self, file_hash: &[u8; 32], content_selection: &ContentSelection) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(file_hash);
+        hasher.update(serde_json::to_vec(content_selection).unwrap_or_default());
+        hasher.update(serde_json::to_vec(&self.config).unwrap_or_default());
+        if let Some(scheme) = self.registry.get(&self.config.proof_type) {
+            for word in scheme.image_id {
+                hasher.update(word.to_le_bytes());
+            }
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Looks up the file hash recorded by a previous run over the same file
+    async fn cached_file_hash(&mut self, key: &CacheKey) -> Option<[u8; 32]> {
+        let cache = self.cache.as_mut()?;
+        match cache.retrieve::<[u8; 32]>(key).await {
+            Ok(file_hash) => file_hash,
+            Err(e) => {
+                warn!("Failed to read file hash from cache: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Looks up a cached proof, evicting it once its validity window has passed
+    async fn cached_proof(&mut self, key: &CacheKey) -> Option<Proof> {
+        let cache = self.cache.as_mut()?;
+        // Proofs are cached as JSON because custom metadata values are not bincode-compatible
+        let proof: Proof = match cache.retrieve::<String>(key).await {
+            Ok(Some(json)) => serde_json::from_str(&json)
+                .map_err(|e| warn!("Discarding unreadable cached proof: {}", e))
+                .ok()?,
+            Ok(None) => return None,
+            Err(e) => {
+                warn!("Failed to read proof from cache: {}", e);
+                return None;
+            }
+        };
+
+        if proof.metadata.valid_until.is_some_and(|valid_until| Utc::now() > valid_until) {
+            debug!("Evicting expired cached proof {}", proof.id);
+            if let Err(e) = cache.remove(key).await {
+                warn!("Failed to evict expired proof from cache: {}", e);
+            }
+            return None;
+        }
+        Some(proof)
+    }
+
+    /// Stores a freshly generated proof; cache failures never fail generation
+    async fn cache_proof(
+        &mut self,
+        file_key: CacheKey,
+        file_hash: [u8; 32],
+        proof_key: CacheKey,
+        proof: &Proof,
+    ) {
+        let cache = match self.cache.as_mut() {
+            Some(cache) => cache,
+            None => return,
+        };
+
+        if let Err(e) = cache.store(file_key, &file_hash).await {
+            warn!("Failed to cache file hash: {}", e);
+            return;
+        }
+
+        match serde_json::to_string(proof) {
+            Ok(json) => {
+                if let Err(e) = cache.store(proof_key, &json).await {
+                    warn!("Failed to cache proof: {}", e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize proof for cache: {}", e),
+        }
+    }
+
+    /// Looks up the checkpoint of an interrupted run over the same file and selection
+    async fn cached_checkpoint(
+        &mut self,
+        file_key: &CacheKey,
+        content_selection: &ContentSelection,
+    ) -> Option<(ProofInput, FileInfo)> {
+        let file_hash = self.cached_file_hash(file_key).await?;
+        let key = self.checkpoint_cache_key(&file_hash, content_selection);
+        let cache = self.cache.as_mut()?;
+        let checkpoint = match cache.retrieve::<ProofCheckpoint>(&key).await {
+            Ok(checkpoint) => checkpoint?,
+            Err(e) => {
+                warn!("Failed to read checkpoint from cache: {}", e);
+                return None;
+            }
+        };
+        let file_info = serde_json::from_str(&checkpoint.file_info)
+            .map_err(|e| warn!("Discarding unreadable checkpoint: {}", e))
+            .ok()?;
+        Some((checkpoint.input, file_info))
+    }
+
+    /// Persists the guest input before proving; cache failures never fail generation
+    async fn cache_checkpoint(
+        &mut self,
+        file_key: CacheKey,
+        content_selection: &ContentSelection,
+        proof_input: &ProofInput,
+        file_info: &FileInfo,
+    ) {
+        let key = self.checkpoint_cache_key(&file_info.file_hash, content_selection);
+        let Some(cache) = self.cache.as_mut() else {
+            return;
+        };
+
+        // File info is kept as JSON because it skips absent fields, which bincode cannot read back
+        let checkpoint = match serde_json::to_string(file_info) {
+            Ok(json) => ProofCheckpoint { input: proof_input.clone(), file_info: json },
+            Err(e) => {
+                warn!("Failed to serialize file info for checkpoint: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = cache.store(file_key, &file_info.file_hash).await {
+            warn!("Failed to cache file hash: {}", e);
+            return;
+        }
+        if let Err(e) = cache.store(key, &checkpoint).await {
+            warn!("Failed to cache checkpoint: {}", e);
+        }
+    }
+
+    /// Drops the checkpoint of a proof that has been generated
+    async fn remove_checkpoint(&mut self, file_hash: &[u8; 32], content_selection: &ContentSelection) {
+        let key = self.checkpoint_cache_key(file_hash, content_selection);
+        if let Some(cache) = self.cache.as_mut() {
+            if let Err(e) = cache.remove(&key).await {
+                warn!("Failed to remove checkpoint from cache: {}", e);
+            }
+        }
+    }
+
+    /// Validates input parameters
+    fn validate_inputs(
+        &self,
+        file_path: &Path,
+        content_selection: &ContentSelection,
+    ) -> Result<()> {
+        // Check if file exists and is readable
+        if !file_path.exists() {
+            return Err(ProofError::invalid_input_error(
+                "file_path",
+                format!("File does not exist: {}", file_path.display())
+            ));
+        }
+
+        if !file_path.is_file() {
+            return Err(ProofError::invalid_input_error(
+                "file_path",
+                format!("Path is not a file: {}", file_path.display())
+            ));
+        }
+
+        // Validate content selection, checking its size first so that deep
+        // nesting is rejected before validation recurses into it
+        content_selection.check_limits(
+            self.config.max_selections,
+            self.config.max_total_selected_bytes.map(|max| max.get()),
+        )?;
+        content_selection.validate()?;
+
+        // Check file size limits
+        let metadata = std::fs::metadata(file_path)?;
+        let file_size = metadata.len();
+        
+        if let Some(max_file_size) = self.config.max_file_size_bytes {
+            if file_size > max_file_size.get() {
+                return Err(ProofError::resource_limit_error(
+                    "file_size",
+                    format!("File size ({} bytes) exceeds maximum allowed size ({} bytes)",
+                           file_size, max_file_size.get())
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces patterns above `pattern_hash_threshold` with `PatternHash` selections
+    fn prepare_guest_selection(&self, selection: ContentSelection) -> ContentSelection {
+        let threshold = match self.config.pattern_hash_threshold {
+            Some(threshold) => threshold,
+            None => return selection,
+        };
+
+        match selection {
+            ContentSelection::Pattern { content } if content.len() > threshold => {
+                debug!("Hashing {} byte pattern for guest input", content.len());
+                ContentSelection::pattern_hash(&content)
+            }
+            ContentSelection::Multiple(selections) => ContentSelection::Multiple(
+                selections.into_iter()
+                    .map(|s| self.prepare_guest_selection(s))
+                    .collect()
+            ),
+            other => other,
+        }
+    }
+
+    /// Replaces `JsonPath` selections with the `JsonSpan` of their value, and
+    /// file format presets and percentage ranges with the `ByteRange` they name
+    fn resolve_host_selections(selection: ContentSelection, blocks: &[IpfsBlock]) -> Result<ContentSelection> {
+        match selection {
+            ContentSelection::JsonPath { path } => {
+                let (offset, document) = content_stream(blocks);
+                let span = json_path::resolve_span(&document, &path)?;
+                debug!("Resolved JSON path {} to bytes {}..{}", path, span.start, span.end);
+                Ok(ContentSelection::JsonSpan {
+                    start: offset + span.start,
+                    end: offset + span.end,
+                })
+            }
+            ContentSelection::PercentRange { start_pct, end_pct } => {
+                let (offset, len) = content_extent(blocks);
+                let span = ContentSelection::percent_span(start_pct, end_pct, len);
+                if span.is_empty() {
+                    return Err(ProofError::content_selection_error("Cannot select a percentage of an empty file"));
+                }
+                debug!("Resolved {}%..{}% to bytes {}..{}", start_pct, end_pct, span.start, span.end);
+                Ok(ContentSelection::ByteRange {
+                    start: offset + span.start,
+                    end: offset + span.end,
+                })
+            }
+            preset if preset.is_preset() => {
+                let (offset, document) = content_stream(blocks);
+                let span = presets::resolve_span(&preset, &document)?;
+                debug!("Resolved {} to bytes {}..{}", preset.description_en(), span.start, span.end);
+                Ok(ContentSelection::ByteRange {
+                    start: offset + span.start,
+                    end: offset + span.end,
+                })
+            }
+            ContentSelection::Multiple(selections) => Ok(ContentSelection::Multiple(
+                selections.into_iter()
+                    .map(|s| Self::resolve_host_selections(s, blocks))
+                    .collect::<Result<_>>()?
+            )),
+            other => Ok(other),
+        }
+    }
+
+    /// Extracts and hashes the content specified by the selection
+    fn extract_content_hash(
+        &self,
+        blocks: &[IpfsBlock],
+        content_selection: &ContentSelection,
+        hash_algorithm: HashAlgorithm,
+    ) -> Result<[u8; 32]> {
+        let content = self.extract_content(blocks, content_selection)?;
+        Ok(content_selection.content_hash(hash_algorithm, &content))
+    }
+
+    /// Extracts the actual content bytes from IPFS blocks
+    fn extract_content(
+        &self,
+        blocks: &[IpfsBlock],
+        content_selection: &ContentSelection,
+    ) -> Result<Vec<u8>> {
+        match content_selection {
+            ContentSelection::ByteRange { start, end } => {
+                self.extract_byte_range(blocks, *start, *end)
+            }
+            ContentSelection::Pattern { content } => {
+                self.extract_pattern(blocks, content)
+            }
+            ContentSelection::PatternHash { hash, len, fingerprint } => {
+                self.extract_pattern_hash(blocks, hash, *len, *fingerprint)
+            }
+            ContentSelection::Regex { pattern } => {
+                self.extract_regex(blocks, pattern)
+            }
+            ContentSelection::XPath { .. } => {
+                Err(ProofError::content_selection_error(
+                    "XPath selections are not supported by the proof generator"
+                ))
+            }
+            ContentSelection::JsonPath { path } => {
+                let (_, document) = content_stream(blocks);
+                let span = json_path::resolve_span(&document, path)?;
+                json_path::canonicalize(&document[span])
+            }
+            ContentSelection::JsonSpan { start, end } => {
+                let value = self.extract_byte_range(blocks, *start, *end)?;
+                json_path::canonicalize(&value)
+            }
+            ContentSelection::PdfPage { .. }
+            | ContentSelection::CsvCell { .. }
+            | ContentSelection::ExifField { .. } => {
+                let (_, document) = content_stream(blocks);
+                let span = presets::resolve_span(content_selection, &document)?;
+                Ok(document[span].to_vec())
+            }
+            ContentSelection::PercentRange { start_pct, end_pct } => {
+                let (_, document) = content_stream(blocks);
+                Ok(document[ContentSelection::percent_span(*start_pct, *end_pct, document.len())].to_vec())
+            }
+            ContentSelection::MultiRange(ranges) => {
+                if !content_selection.is_valid() {
+                    return Err(ProofError::content_selection_error(
+                        "Byte ranges must be non-empty, ascending and disjoint"
+                    ));
+                }
+                let mut combined = Vec::new();
+                for &(start, end) in ranges {
+                    let mut content = self.extract_byte_range(blocks, start, end)?;
+                    if content.len() != end - start {
+                        return Err(ProofError::content_selection_error(
+                            format!("Byte range {}..{} extends past the end of the content", start, end)
+                        ));
+                    }
+                    combined.append(&mut content);
+                }
+                Ok(combined)
+            }
+            ContentSelection::Multiple(selections) => {
+                let mut combined = Vec::new();
+                for selection in selections {
+                    let mut content = self.extract_content(blocks, selection)?;
+                    combined.append(&mut content);
+                }
+                Ok(combined)
+            }
+        }
+    }
+
+    /// Extracts content matching a regex pattern
+    fn extract_regex(
+        &self,
+        blocks: &[IpfsBlock],
+        pattern: &str,
+    ) -> Result<Vec<u8>> {
+        // Concatenate all block data for pattern searching
+        // Note: For very large files, this approach is memory intensive.
+        // A streaming regex implementation would be better for optimization.
+        let mut all_data = Vec::new();
+        for block in blocks {
+            all_data.extend_from_slice(&block.data);
+        }
+        
+        // Attempt to convert to string (lossy if not UTF-8, but regex usually assumes string)
+        let data_str = match std::str::from_utf8(&all_data) {
+            Ok(s) => s,
+            Err(_) => {
+                return Err(ProofError::content_selection_error(
+                    "File content is not valid UTF-8, cannot apply Regex"
+                ));
+            }
+        };
+
+        let re = Regex::new(pattern).map_err(|e| {
+            ProofError::content_selection_error(format!("Invalid regex pattern: {}", e))
+        })?;
+
+        if let Some(mat) = re.find(data_str) {
+            Ok(mat.as_str().as_bytes().to_vec())
+        } else {
+            Err(ProofError::content_selection_error(
+                "Regex pattern not found in file content"
+            ))
+        }
+    }
+
+    /// Extracts content from a byte range
+    fn extract_byte_range(
+        &self,
+        blocks: &[IpfsBlock],
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<u8>> {
+        let (start, end) = (start as u64, end as u64);
+        let total_len = blocks.iter().map(|block| block.data.len() as u64).sum::<u64>();
+        if start >= end || end > total_len {
+            return Err(ProofError::content_selection_error(
+                format!("Byte range {}..{} extends past the end of the content ({} bytes)", start, end, total_len)
+            ));
+        }
+
+        let mut content = Vec::new();
+        let mut current_offset = 0u64;
+
+        for block in blocks {
+            let block_start = current_offset;
+            let block_end = checked_block_end(block_start, block.data.len())?;
+
+            if block_start < end && block_end > start {
+                // Both bounds lie within the block, so they fit in usize
+                let extract_start = start.saturating_sub(block_start) as usize;
+                let extract_end = (end.min(block_end) - block_start) as usize;
+                
+                content.extend_from_slice(&block.data[extract_start..extract_end]);
+            }
+
+            current_offset = block_end;
+            if current_offset >= end {
+                break;
+            }
+        }
+
+        if content.is_empty() {
+            return Err(ProofError::content_selection_error(
+                format!("No content found in byte range {}..{}", start, end)
+            ));
+        }
+
+        Ok(content)
+    }
+
+    /// Extracts content matching a pattern
+    fn extract_pattern(
+        &self,
+        blocks: &[IpfsBlock],
+        pattern: &[u8],
+    ) -> Result<Vec<u8>> {
+        // Concatenate all block data for pattern searching
+        let mut all_data = Vec::new();
+        for block in blocks {
+            all_data.extend_from_slice(&block.data);
+        }
+
+        // Find pattern
+        if let Some(_pos) = self.find_pattern(&all_data, pattern) {
+            Ok(pattern.to_vec())
+        } else {
+            Err(ProofError::content_selection_error(
+                "Pattern not found in file content"
+            ))
+        }
+    }
+
+    /// Extracts the first window whose rolling fingerprint and SHA-256 match
+    fn extract_pattern_hash(
+        &self,
+        blocks: &[IpfsBlock],
+        hash: &[u8; 32],
+        len: usize,
+        fingerprint: u64,
+    ) -> Result<Vec<u8>> {
+        let mut all_data = Vec::new();
+        for block in blocks {
+            all_data.extend_from_slice(&block.data);
+        }
+
+        if let Some(pos) = self.find_pattern_hash(&all_data, hash, len, fingerprint) {
+            Ok(all_data[pos..pos + len].to_vec())
+        } else {
+            Err(ProofError::content_selection_error(
+                "Hashed pattern not found in file content"
+            ))
+        }
+    }
+
+    /// Finds the first window of `len` bytes matching the fingerprint and hash.
+    ///
+    /// The rolling fingerprint only filters candidates; every fingerprint hit is
+    /// confirmed with an exact SHA-256 comparison so collisions cannot match.
+    fn find_pattern_hash(
+        &self,
+        data: &[u8],
+        hash: &[u8; 32],
+        len: usize,
+        fingerprint: u64,
+    ) -> Option<usize> {
+        if len == 0 || len > data.len() {
+            return None;
+        }
+
+        // Weight of the outgoing byte: BASE^(len - 1)
+        let mut high = 1u64;
+        for _ in 1..len {
+            high = high.wrapping_mul(PATTERN_FINGERPRINT_BASE);
+        }
+
+        let mut rolling = pattern_fingerprint(&data[..len]);
+        let mut pos = 0;
+        loop {
+            if rolling == fingerprint && Sha256::digest(&data[pos..pos + len]).as_slice() == hash {
+                return Some(pos);
+            }
+            if pos + len >= data.len() {
+                return None;
+            }
+
+            rolling = rolling
+                .wrapping_sub((data[pos] as u64).wrapping_mul(high))
+                .wrapping_mul(PATTERN_FINGERPRINT_BASE)
+                .wrapping_add(data[pos + len] as u64);
+            pos += 1;
+        }
+    }
+
+    /// Finds the first occurrence of a pattern in data
+    ///
+    /// Uses `memchr`'s two-way substring search, linear in the data length
+    /// whatever the pattern, so crafted inputs cannot make it quadratic.
+    fn find_pattern(&self, data: &[u8], pattern: &[u8]) -> Option<usize> {
+        if pattern.is_empty() {
+            return None;
+        }
+        memmem::find(data, pattern)
+    }
+
+    /// Generates the ZK receipt for `input` with the scheme of `proof_type`,
+    /// returning it with the cycles executed in total and in each segment
+    ///
+    /// Bonsai reports only the total, so its proofs have no segment breakdown.
+    async fn generate_zk_proof(
+        &self,
+        proof_type: &ProofType,
+        input: ProofInput,
+    ) -> Result<(Receipt, u64, Vec<SegmentCycles>)> {
+        let scheme = self.registry.scheme(proof_type)?;
+        let bonsai = match self.config.prover_type {
+            ProverType::Bonsai if matches!(self.config.receipt_kind, ReceiptKind::Groth16) => {
+                return Err(ProofError::configuration_error(
+                    "Groth16 receipts cannot be requested from the Bonsai prover"
+                ));
+            }
+            ProverType::Bonsai => Some(BonsaiProver::new(self.bonsai_credentials.clone())?),
+            _ => None,
+        };
+        let opts = match self.config.receipt_kind {
+            ReceiptKind::Composite => ProverOpts::composite(),
+            ReceiptKind::Succinct => ProverOpts::succinct(),
+            ReceiptKind::Groth16 => ProverOpts::groth16(),
+        };
+
+        // Execution and local proving block for as long as they run, so they
+        // run on the blocking pool where the timeout below can still fire; a
+        // timed-out prover thread is abandoned and finishes in the background
+        let scheme = scheme.clone();
+        let prove_future = async move {
+            if let Some(bonsai) = &bonsai {
+                let stdin = scheme.build_input(&input)?
+                    .iter()
+                    .flat_map(|word| word.to_le_bytes())
+                    .collect();
+                let (receipt, stats) = bonsai.prove(&scheme.elf, scheme.image_id, stdin).await?;
+                return Ok((receipt, stats.map_or(0, |stats| stats.cycles), Vec::new()));
+            }
+
+            run_blocking(move || {
+                // The executed session is proven as is, so its segments are
+                // measured without running the guest a second time
+                let session = Self::execute_session(&scheme, &input)?;
+                let segment_cycles = Self::segment_cycles(&session)?;
+                let prove_info = get_prover_server(&opts)
+                    .and_then(|prover| prover.prove_session(&VerifierContext::default(), &session))
+                    .map_err(|e| ProofError::zk_proof_error(
+                        "proof_generation",
+                        "Failed to generate ZK proof",
+                        Some(e)
+                    ))?;
+                let zk_cycles = segment_cycles.iter().map(|segment| segment.cycles).sum();
+                Ok((prove_info.receipt, zk_cycles, segment_cycles))
+            }).await
+        };
+
+        let (receipt, zk_cycles, segment_cycles) = if let Some(timeout_secs) = self.config.timeout_seconds {
+            timeout(Duration::from_secs(timeout_secs), prove_future)
+                .await
+                .map_err(|_| ProofError::timeout_error(
+                    "zk_proof_generation",
+                    timeout_secs * 1000
+                ))?
+        } else {
+            prove_future.await
+        }?;
+
+        debug!("Guest execution used {} cycles in {} segments", zk_cycles, segment_cycles.len());
+
+        Ok((receipt, zk_cycles, segment_cycles))
+    }
+
+    /// Executes the guest without proving, returning the cycles of each segment
+    fn execute_guest(scheme: &ProofScheme, input: &ProofInput) -> Result<Vec<SegmentCycles>> {
+        Self::segment_cycles(&Self::execute_session(scheme, input)?)
+    }
+
+    /// Executes the guest on `input`, keeping the segments for proving
+    fn execute_session(scheme: &ProofScheme, input: &ProofInput) -> Result<Session> {
+        ExecutorImpl::from_elf(Self::executor_env(scheme, input)?, &scheme.elf)
+            .and_then(|mut executor| executor.run())
+            .map_err(|e| ProofError::zk_proof_error(
+                "execution",
+                format!("Failed to execute guest program: {}", e),
+                None
+            ))
+    }
+
+    /// Cycles executed in each segment of `session`, before padding to a power of two
+    fn segment_cycles(session: &Session) -> Result<Vec<SegmentCycles>> {
+        session.segments.iter()
+            .map(|segment| {
+                let segment = segment.resolve().map_err(|e| ProofError::zk_proof_error(
+                    "execution",
+                    "Failed to load an executed segment",
+                    Some(e)
+                ))?;
+                Ok(SegmentCycles {
+                    po2: segment.inner.po2 as u32,
+                    cycles: segment.inner.insn_cycles as u64,
+                })
+            })
+            .collect()
+    }
+
+    /// Builds the executor environment holding the guest input, encoded by `scheme`
+    fn executor_env(scheme: &ProofScheme, input: &ProofInput) -> Result<ExecutorEnv<'static>> {
+        let words = scheme.build_input(input)?;
+        ExecutorEnv::builder()
+            .write_slice(&words)
+            .build()
+            .map_err(|e| ProofError::zk_proof_error(
+                "environment_setup",
+                "Failed to build executor environment",
+                Some(e)
+            ))
+    }
+
+    /// Creates comprehensive proof metadata
+    fn create_proof_metadata(
+        &self,
+        guest_metadata: crate::guest_types::ProofMetadata,
+        hash_algorithm: HashAlgorithm,
+        file_info: FileInfo,
+        total_time: Duration,
+        file_processing_time: Duration,
+        zk_generation_time: Duration,
+        zk_cycles: u64,
+        segment_cycles: Vec<SegmentCycles>,
+        peak_memory_bytes: u64,
+        receipt_bytes: &[u8],
+        receipt_len: usize,
+    ) -> Result<ProofMetadata> {
+        let performance = PerformanceMetrics {
+            generation_time_ms: Millis::from(total_time),
+            file_processing_time_ms: Millis::from(file_processing_time),
+            zk_generation_time_ms: Millis::from(zk_generation_time),
+            peak_memory_bytes: Bytes(peak_memory_bytes),
+            zk_cycles,
+            proof_size_bytes: Bytes::from_len(receipt_bytes.len()),
+            compression_ratio: self.calculate_compression_ratio(receipt_bytes, receipt_len),
+            segment_cycles,
+        };
+
+        let security = SecurityParameters {
+            security_level: self.config.security_level,
+            hash_function: hash_algorithm.name().to_string(),
+            hash_algorithm,
+            proof_system: "Risc0".to_string(),
+            risc0_version: Self::risc0_version().to_string(),
+            formal_verification: false,
+        };
+
+        let environment = GenerationEnvironment {
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            hardware_acceleration: self.detect_hardware_acceleration(),
+            prover_type: self.config.prover_type.clone(),
+            library_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: option_env!("GIT_COMMIT").map(|s| s.to_string()),
+        };
+
+        Ok(ProofMetadata {
+            guest_metadata,
+            file_info,
+            performance,
+            security,
+            environment,
+            custom: self.config.custom_metadata.clone(),
+            valid_until: None,
+            renewed_from: Vec::new(),
+            extends: None,
+            reproducible: self.config.reproducible,
+            content_committed: false,
+        })
+    }
+
+    /// Returns when a proof created at `created_at` expires under the current configuration
+    fn validity_deadline(&self, created_at: chrono::DateTime<Utc>) -> Option<chrono::DateTime<Utc>> {
+        self.config.validity_seconds
+            .map(|seconds| created_at + chrono::Duration::seconds(seconds as i64))
+    }
+
+    /// Detects available hardware acceleration
+    fn detect_hardware_acceleration(&self) -> Option<HardwareAcceleration> {
+        if self.config.use_hardware_acceleration {
+            // Check for CUDA support
+            if self.has_cuda_support() {
+                return Some(HardwareAcceleration::Cuda);
+            }
+            
+            // Check for Metal support (macOS)
+            if self.has_metal_support() {
+                return Some(HardwareAcceleration::Metal);
+            }
+            
+            // Check for other hardware acceleration
+            if self.has_hardware_acceleration() {
+                return Some(HardwareAcceleration::Other("CPU-optimized".to_string()));
+            }
+        }
+        
+        Some(HardwareAcceleration::None)
+    }
+
+    /// Calculates how far the receipt was compressed, if compression is used
+    fn calculate_compression_ratio(&self, receipt_bytes: &[u8], receipt_len: usize) -> Option<f64> {
+        if self.config.compression == CompressionType::None || receipt_len == 0 {
+            return None;
+        }
+        Some(receipt_bytes.len() as f64 / receipt_len as f64)
+    }
+
+    /// Serializes a receipt and compresses it with the configured codec,
+    /// returning the stored bytes and the serialized length
+    fn encode_receipt(&self, receipt: &Receipt) -> Result<(Vec<u8>, usize)> {
+        let serialized = bincode::serialize(receipt)
+            .map_err(|e| ProofError::serialization_error(
+                "Failed to serialize receipt",
+                Some(Box::new(e))
+            ))?;
+        Ok((self.config.compression.compress(&serialized)?, serialized.len()))
+    }
+
+    /// Version of the linked `risc0-zkvm` crate, fixed at compile time
+    pub fn risc0_version() -> &'static str {
+        risc0_zkvm::VERSION
+    }
+
+    /// Checks that the zkVM can prove here: the prover chosen by the
+    /// environment initializes, and the bundled guest program loads with the
+    /// image ID it was built with
+    pub fn risc0_available() -> bool {
+        let prover_ready = std::panic::catch_unwind(|| default_prover().get_name()).is_ok();
+        prover_ready && risc0_zkvm::compute_image_id(crate::IPFS_CONTENT_VERIFIER_ELF)
+            .is_ok_and(|image_id| image_id == risc0_zkvm::sha::Digest::from(crate::IPFS_CONTENT_VERIFIER_ID))
+    }
+
+    /// Checks for CUDA support
+    fn has_cuda_support(&self) -> bool {
+        #[cfg(feature = "cuda")]
+        {
+            // Check if CUDA runtime is available
+            std::process::Command::new("nvidia-smi")
+                .output()
+                .map(|output| output.status.success())
+                .unwrap_or(false)
+        }
+        #[cfg(not(feature = "cuda"))]
+        false
+    }
+
+    /// Checks for Metal support (macOS)
+    fn has_metal_support(&self) -> bool {
+        #[cfg(all(target_os = "macos", feature = "metal"))]
+        {
+            // Check if Metal is available on macOS
+            true // Metal is available on all modern macOS systems
+        }
+        #[cfg(not(all(target_os = "macos", feature = "metal")))]
+        false
+    }
+
+    /// Checks for general hardware acceleration
+    fn has_hardware_acceleration(&self) -> bool {
+        // Check CPU features
+        #[cfg(target_arch = "x86_64")]
+        {
+            is_x86_feature_detected!("avx2") || is_x86_feature_detected!("avx")
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            // ARM64 typically has NEON support
+            true
+        }
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+        false
+    }
+
+    /// Updates generation statistics and reports the completed proof
+    fn update_generation_stats(&mut self, proof: &Proof, duration: Duration) {
+        self.events.emit(MonitorEvent::ProofCompleted {
+            duration,
+            cycles: proof.metadata.performance.zk_cycles,
+        });
+
+        self.stats.total_proofs_generated += 1;
+        self.stats.total_data_processed_bytes += proof.metadata.file_info.size.get();
+        
+        // Update average generation time
+        let new_time = duration.as_millis() as f64;
+        let count = self.stats.total_proofs_generated as f64;
+        self.stats.avg_generation_time_ms = 
+            (self.stats.avg_generation_time_ms * (count - 1.0) + new_time) / count;
+    }
+
+    /// Updates verification statistics
+    fn update_verification_stats(&mut self, is_valid: bool, duration: Duration) {
+        self.stats.total_proofs_verified += 1;
+        
+        // Update average verification time
+        let new_time = duration.as_millis() as f64;
+        let count = self.stats.total_proofs_verified as f64;
+        self.stats.avg_verification_time_ms = 
+            (self.stats.avg_verification_time_ms * (count - 1.0) + new_time) / count;
+        
+        // Update success rate
+        let successful_verifications = if is_valid {
+            (self.stats.verification_success_rate * (count - 1.0)) + 1.0
+        } else {
+            self.stats.verification_success_rate * (count - 1.0)
+        };
+        self.stats.verification_success_rate = successful_verifications / count;
+    }
+}
+
+/// Runs blocking prover work on tokio's blocking pool
+async fn run_blocking<T: Send + 'static>(work: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| ProofError::internal_error("Prover task failed", Some(Box::new(e))))?
+}
+
+/// Computes the end offset of a block, failing instead of wrapping on overflow
+fn checked_block_end(block_start: u64, block_len: usize) -> Result<u64> {
+    u64::try_from(block_len)
+        .ok()
+        .and_then(|len| block_start.checked_add(len))
+        .ok_or_else(|| ProofError::resource_limit_error(
+            "file_size",
+            format!("Block offset overflow: block of {} bytes at offset {}", block_len, block_start)
+        ))
+}
+
+/// Guest input persisted before proving, from which an interrupted run resumes
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ProofCheckpoint {
+    input: ProofInput,
+    /// The processed file's [`FileInfo`], as JSON
+    file_info: String,
+}
+
+/// Names the block of an inclusion path with data hash `hash` by the CID it is
+/// linked under from `root`, or by the hash if no link addresses it
+fn missing_block_cid(root: &IpfsBlock, hash: &[u8; 32]) -> String {
+    std::iter::once(&root.cid)
+        .chain(root.links.iter().map(|link| &link.cid))
+        .filter_map(|cid| cid::Cid::try_from(&cid[..]).ok())
+        .find(|cid| cid.hash().digest() == hash)
+        .map(|cid| cid.to_string())
+        .unwrap_or_else(|| hex::encode(hash))
+}
+
+/// Returns the file content held by `blocks` and its offset in the concatenated
+/// block data, which starts with the root block for multi-block files
+fn content_stream(blocks: &[IpfsBlock]) -> (usize, Vec<u8>) {
+    let skip = content_start(blocks);
+    let offset = blocks[..skip].iter().map(|block| block.data.len()).sum();
+    let content = blocks[skip..].iter()
+        .flat_map(|block| block.data.iter().copied())
+        .collect();
+    (offset, content)
+}
+
+/// Returns the offset and length of the file content held by `blocks`,
+/// without copying it as [`content_stream`] does
+fn content_extent(blocks: &[IpfsBlock]) -> (usize, usize) {
+    let (header, content) = blocks.split_at(content_start(blocks));
+    let len = |blocks: &[IpfsBlock]| blocks.iter().map(|block| block.data.len()).sum();
+    (len(header), len(content))
+}
+
+/// Number of leading blocks that hold no file content: the root block of a
+/// multi-block file
+fn content_start(blocks: &[IpfsBlock]) -> usize {
+    usize::from(blocks.len() > 1 && !blocks[0].links.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+    use std::io::Write;
+
+    #[test]
+    fn test_risc0_version_comes_from_linked_crate() {
+        let version = ProofGenerator::risc0_version();
+        assert!(!version.is_empty());
+        assert_ne!(version, "1.2.0");
+        assert_eq!(version.split('.').count(), 3);
+        assert!(version.split('.').all(|part| part.chars().next().is_some_and(|c| c.is_ascii_digit())));
+    }
+
+    #[tokio::test]
+    async fn test_proof_generator_creation() {
+        let generator = ProofGenerator::new().await;
+        assert!(generator.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_input_validation() {
+        let generator = ProofGenerator::new().await.unwrap();
+        
+        // Test non-existent file
+        let result = generator.validate_inputs(
+            Path::new("/non/existent/file"),
+            &ContentSelection::Pattern { content: b"test".to_vec() }
+        );
+        assert!(result.is_err());
+        
+        // Test invalid content selection
+        let temp_file = NamedTempFile::new().unwrap();
+        let result = generator.validate_inputs(
+            temp_file.path(),
+            &ContentSelection::ByteRange { start: 10, end: 5 }
+        );
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pattern_finding() {
+        let generator = ProofGenerator::new().await.unwrap();
+        let data = b"Hello, world! This is a test.";
+        
+        assert_eq!(generator.find_pattern(data, b"world"), Some(7));
+        assert_eq!(generator.find_pattern(data, b"test"), Some(25));
+        assert_eq!(generator.find_pattern(data, b"missing"), None);
+        assert_eq!(generator.find_pattern(data, b""), None);
+    }
+
+    /// The quadratic search `find_pattern` replaced, kept as a reference
+    fn naive_find(data: &[u8], pattern: &[u8]) -> Option<usize> {
+        if pattern.is_empty() || pattern.len() > data.len() {
+            return None;
+        }
+        (0..=data.len() - pattern.len()).find(|&i| &data[i..i + pattern.len()] == pattern)
+    }
+
+    #[tokio::test]
+    async fn test_pattern_found_on_worst_case_input() {
+        // Search time on this input is measured by the `pattern_search` benchmark
+        let generator = ProofGenerator::new().await.unwrap();
+        // Every alignment matches all but the last byte of the pattern
+        let data = [vec![b'a'; 64 * 1024], b"b".to_vec()].concat();
+        let pattern = [vec![b'a'; 1023], b"b".to_vec()].concat();
+
+        let found = generator.find_pattern(&data, &pattern);
+        assert_eq!(found, naive_find(&data, &pattern));
+        assert_eq!(found, Some(data.len() - pattern.len()));
+    }
+
+    #[tokio::test]
+    async fn test_pattern_search_matches_naive_search() {
+        let generator = ProofGenerator::new().await.unwrap();
+        let data = b"abababcabababcababcabababab";
+        for start in 0..data.len() {
+            for end in start..=data.len().min(start + 8) {
+                let pattern = &data[start..end];
+                assert_eq!(generator.find_pattern(data, pattern), naive_find(data, pattern), "{:?}", pattern);
+            }
+        }
+        assert_eq!(generator.find_pattern(b"ab", b"abc"), None);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_proof_uses_cache() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "Cached content for repeated proofs").unwrap();
+
+        let mut generator = ProofGenerator::new().await.unwrap();
+        let selection = ContentSelection::Pattern { content: b"repeated".to_vec() };
+
+        let first = generator.generate_proof(temp_file.path(), selection.clone()).await.unwrap();
+        let hits_before = generator.cache_statistics().unwrap().hits;
+
+        let second = generator.generate_proof(temp_file.path(), selection).await.unwrap();
+        let hits_after = generator.cache_statistics().unwrap().hits;
+
+        assert_eq!(first.id, second.id);
+        assert!(hits_after > hits_before);
+    }
+
+    #[tokio::test]
+    async fn test_expired_cached_proof_regenerated() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "Cached content that expires after a second").unwrap();
+
+        let config = ProofConfig { validity_seconds: Some(1), ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let selection = ContentSelection::Pattern { content: b"expires".to_vec() };
+
+        let first = generator.generate_proof(temp_file.path(), selection.clone()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        let second = generator.generate_proof(temp_file.path(), selection).await.unwrap();
+
+        assert_ne!(first.id, second.id);
+    }
+
+    #[tokio::test]
+    async fn test_file_cache_key_tracks_modification() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "original").unwrap();
+        let before = ProofGenerator::file_cache_key(temp_file.path()).unwrap();
+
+        writeln!(temp_file, "appended").unwrap();
+        temp_file.flush().unwrap();
+        let after = ProofGenerator::file_cache_key(temp_file.path()).unwrap();
+
+        assert_ne!(before, after);
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_checkpoint_skips_file_processing() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, "Content whose checkpointed input survives a crash").unwrap();
+        temp_file.flush().unwrap();
+        let selection = ContentSelection::Pattern { content: b"checkpointed".to_vec() };
+        let config = ProofConfig { checkpoint: true, ..ProofConfig::default() };
+        let file_key = ProofGenerator::file_cache_key(temp_file.path()).unwrap();
+
+        // A run that built its input and crashed before proving
+        {
+            let mut crashed = ProofGenerator::with_config(config.clone()).await.unwrap();
+            let (input, file_info, _, _) = crashed
+                .prepare_input(temp_file.path(), selection.clone(), None)
+                .await
+                .unwrap();
+            crashed.cache_checkpoint(file_key.clone(), &selection, &input, &file_info).await;
+        }
+
+        // Change the bytes but not the size or modification time, so only a
+        // re-read of the file could notice
+        let modified = std::fs::metadata(temp_file.path()).unwrap().modified().unwrap();
+        std::fs::write(temp_file.path(), "Content whose overwritten! input survives a crash").unwrap();
+        std::fs::File::options().write(true).open(temp_file.path()).unwrap().set_modified(modified).unwrap();
+
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.resume_or_generate(temp_file.path(), selection.clone()).await.unwrap();
+        assert!(generator.verify_proof(&proof, b"checkpointed").await.unwrap());
+
+        // The checkpoint is dropped once the proof is cached
+        assert!(generator.cached_checkpoint(&file_key, &selection).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_groth16_receipt_is_smaller() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "Content proven with two different receipt kinds").unwrap();
+        let selection = ContentSelection::Pattern { content: b"receipt kinds".to_vec() };
+
+        let mut config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config.clone()).await.unwrap();
+        let composite = generator.generate_proof(temp_file.path(), selection.clone()).await.unwrap();
+
+        config.receipt_kind = ReceiptKind::Groth16;
+        generator.update_config(config).unwrap();
+        let groth16 = generator.generate_proof(temp_file.path(), selection).await.unwrap();
+
+        assert_eq!(groth16.zk_proof.receipt_kind(), Some(ReceiptKind::Groth16));
+        assert!(groth16.zk_proof.receipt.len() * 10 < composite.zk_proof.receipt.len());
+    }
+
+    /// Regenerates the Groth16 proof the `wasm` module tests verify in the browser
+    #[tokio::test]
+    #[ignore = "writes testdata/wasm_proof.json"]
+    async fn write_wasm_fixture() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "This content is verified in the browser without a server").unwrap();
+
+        let config = ProofConfig {
+            use_cache: false,
+            receipt_kind: ReceiptKind::Groth16,
+            ..ProofConfig::default()
+        };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::Pattern { content: b"verified in the browser".to_vec() }
+        ).await.unwrap();
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/wasm_proof.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, serde_json::to_string_pretty(&proof).unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_chunking_strategies_round_trip() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let content: Vec<u8> = (0..100_000u32).map(|i| (i.wrapping_mul(2654435761) >> 11) as u8).collect();
+        temp_file.write_all(&content).unwrap();
+        temp_file.flush().unwrap();
+        let pattern = content[70_000..70_032].to_vec();
+
+        let mut block_counts = Vec::new();
+        for chunking_strategy in [
+            ChunkingStrategy::FixedSize(16 * 1024),
+            ChunkingStrategy::Rabin { min: 2 * 1024, avg: 8 * 1024, max: 32 * 1024 },
+        ] {
+            let config = ProofConfig { use_cache: false, chunking_strategy, ..ProofConfig::default() };
+            let mut generator = ProofGenerator::with_config(config).await.unwrap();
+            let proof = generator.generate_proof(
+                temp_file.path(),
+                ContentSelection::Pattern { content: pattern.clone() }
+            ).await.unwrap();
+
+            assert!(generator.verify_proof(&proof, &pattern).await.unwrap());
+            block_counts.push(proof.metadata.file_info.block_count);
+        }
+
+        assert_ne!(block_counts[0], block_counts[1]);
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_chunking_grows_with_file_size() {
+        let config = ProofConfig {
+            use_cache: false,
+            chunking_strategy: ChunkingStrategy::Adaptive,
+            ..ProofConfig::default()
+        };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+
+        let mut chunk_sizes = Vec::new();
+        for size in [64 * 1024u32, 512 * 1024] {
+            let content: Vec<u8> = (0..size).map(|i| (i.wrapping_mul(2654435761) >> 11) as u8).collect();
+            let mut temp_file = NamedTempFile::new().unwrap();
+            temp_file.write_all(&content).unwrap();
+            temp_file.flush().unwrap();
+            let pattern = content[40_000..40_032].to_vec();
+
+            let proof = generator.generate_proof(
+                temp_file.path(),
+                ContentSelection::Pattern { content: pattern.clone() }
+            ).await.unwrap();
+
+            assert!(generator.verify_proof(&proof, &pattern).await.unwrap());
+            let chunk_size = proof.metadata.file_info.chunk_size.unwrap();
+            assert!(proof.metadata.file_info.avg_block_size <= chunk_size);
+            chunk_sizes.push(chunk_size);
+        }
+
+        assert!(chunk_sizes[1] > chunk_sizes[0]);
+    }
+
+    #[tokio::test]
+    async fn test_mmap_proof_matches_buffered_proof() {
+        let content: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&content).unwrap();
+        temp_file.flush().unwrap();
+        let selection = ContentSelection::ByteRange { start: 1_000, end: 2_000 };
+
+        let mut proofs = Vec::new();
+        for mmap_threshold in [None, Some(Bytes(0))] {
+            let config = ProofConfig { use_cache: false, mmap_threshold, ..ProofConfig::default() };
+            let mut generator = ProofGenerator::with_config(config).await.unwrap();
+            proofs.push(generator.generate_proof(temp_file.path(), selection.clone()).await.unwrap());
+        }
+
+        assert_eq!(proofs[0].root_hash, proofs[1].root_hash);
+        assert_eq!(proofs[0].content_hash, proofs[1].content_hash);
+        assert_eq!(proofs[0].metadata.file_info.ipfs_cid, proofs[1].metadata.file_info.ipfs_cid);
+    }
+
+    #[tokio::test]
+    async fn test_pattern_proven_in_decompressed_gzip_file() {
+        let log: Vec<u8> = (0..5_000u32)
+            .flat_map(|i| format!("{} GET /index.html 200\n", i).into_bytes())
+            .chain(b"9999 POST /admin 403\n".iter().copied())
+            .collect();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&CompressionType::Gzip.compress(&log).unwrap()).unwrap();
+        temp_file.flush().unwrap();
+        let selection = ContentSelection::Pattern { content: b"POST /admin 403".to_vec() };
+
+        let mut stored = ProofGenerator::with_config(ProofConfig { use_cache: false, ..ProofConfig::default() })
+            .await
+            .unwrap();
+        assert!(stored.preflight(temp_file.path(), &selection).await.unwrap().ensure_satisfiable().is_err());
+
+        let config = ProofConfig {
+            use_cache: false,
+            decompress_input: Some(CompressionType::Gzip),
+            ..ProofConfig::default()
+        };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.generate_proof(temp_file.path(), selection).await.unwrap();
+
+        let file_info = &proof.metadata.file_info;
+        assert_eq!(file_info.source_compression, Some(CompressionType::Gzip));
+        assert_eq!(file_info.size, Bytes(log.len() as u64));
+        assert_eq!(file_info.file_hash, <[u8; 32]>::from(Sha256::digest(&log)));
+        assert!(generator.verify_proof(&proof, b"POST /admin 403").await.unwrap());
+    }
+
+    #[test]
+    fn test_block_offset_overflow_detected() {
+        assert_eq!(checked_block_end(1024, 512).unwrap(), 1536);
+        assert_eq!(checked_block_end(u64::MAX - 10, 10).unwrap(), u64::MAX);
+
+        let result = checked_block_end(u64::MAX - 10, 11);
+        assert!(matches!(result, Err(ProofError::ResourceLimitError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_car_export_import_round_trip() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let content: Vec<u8> = (0..40_000u32).map(|i| (i % 241) as u8).collect();
+        temp_file.write_all(&content).unwrap();
+        temp_file.flush().unwrap();
+        let pattern = content[25_000..25_020].to_vec();
+
+        let config = ProofConfig {
+            use_cache: false,
+            chunking_strategy: ChunkingStrategy::FixedSize(8 * 1024),
+            ..ProofConfig::default()
+        };
+        let mut generator = ProofGenerator::with_config(config.clone()).await.unwrap();
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::Pattern { content: pattern.clone() }
+        ).await.unwrap();
+
+        let mut archive = Vec::new();
+        generator.export_car(&proof, temp_file.path(), &mut archive).await.unwrap();
+
+        // Verify from the archive alone, after the source file is gone
+        drop(temp_file);
+        let blocks = ProofGenerator::import_car(&archive[..]).unwrap();
+        assert!(blocks.len() < 6);
+
+        let mut verifier = ProofGenerator::with_config(config).await.unwrap();
+        assert!(verifier.verify_proof_with_blocks(&proof, &blocks, &pattern).await.unwrap());
+        assert!(!verifier.verify_proof_with_blocks(&proof, &blocks[..1], &pattern).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_inclusion_block_reported_by_cid() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let content: Vec<u8> = (0..40_000u32).map(|i| (i % 241) as u8).collect();
+        temp_file.write_all(&content).unwrap();
+        temp_file.flush().unwrap();
+
+        let config = ProofConfig {
+            use_cache: false,
+            chunking_strategy: ChunkingStrategy::FixedSize(8 * 1024),
+            ..ProofConfig::default()
+        };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        // Spans the second and third leaves
+        let offset = generator.ipfs_processor.create_blocks(&content).unwrap()[0].data.len();
+        let selection = ContentSelection::ByteRange { start: offset + 16_000, end: offset + 17_000 };
+        let proof = generator.generate_proof(temp_file.path(), selection).await.unwrap();
+
+        let mut archive = Vec::new();
+        generator.export_car(&proof, temp_file.path(), &mut archive).await.unwrap();
+        let blocks = ProofGenerator::import_car(&archive[..]).unwrap();
+        assert_eq!(generator.check_blocks(&proof, &blocks).unwrap(), None);
+
+        let dropped = blocks.last().unwrap();
+        let dropped_cid = cid::Cid::try_from(&dropped.cid[..]).unwrap().to_string();
+        let remaining: Vec<IpfsBlock> = blocks[..blocks.len() - 1].to_vec();
+        assert_eq!(
+            generator.check_blocks(&proof, &remaining).unwrap(),
+            Some(VerificationFailure::MissingBlock(dropped_cid))
+        );
+        assert!(!generator.blocks_back_proof(&proof, &remaining).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_multi_range_single_inclusion_proof() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let content: Vec<u8> = (0..12_000u32).map(|i| (i % 251) as u8).collect();
+        temp_file.write_all(&content).unwrap();
+        temp_file.flush().unwrap();
+
+        let config = ProofConfig {
+            use_cache: false,
+            chunking_strategy: ChunkingStrategy::FixedSize(8 * 1024),
+            ..ProofConfig::default()
+        };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+
+        // A root block followed by two leaves; the last range crosses into the second leaf
+        let blocks = generator.ipfs_processor.create_blocks(&content).unwrap();
+        assert_eq!(blocks.len(), 3);
+        let leaf = blocks[0].data.len();
+        let ranges = vec![(leaf + 100, leaf + 200), (leaf + 5_000, leaf + 5_050), (leaf + 8_100, leaf + 8_300)];
+        let data: Vec<u8> = blocks.iter().flat_map(|block| block.data.iter().copied()).collect();
+        let claimed: Vec<u8> = ranges.iter().flat_map(|&(start, end)| data[start..end].iter().copied()).collect();
+
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::MultiRange(ranges.clone())
+        ).await.unwrap();
+        assert_eq!(proof.metadata.guest_metadata.content_size, 350);
+
+        // Each spanned leaf is hashed once, in file order
+        let inclusion_paths = ProofGenerator::decode_proof_output(&proof).unwrap().inclusion_paths;
+        let leaf_hashes: Vec<[u8; 32]> = blocks[1..].iter()
+            .map(|block| Sha256::digest(&block.data).into())
+            .collect();
+        assert_eq!(inclusion_paths, vec![leaf_hashes]);
+
+        assert!(generator.verify_proof(&proof, &claimed).await.unwrap());
+
+        // The ranges are committed: the same bytes claimed at other offsets fail
+        let mut shifted = proof.clone();
+        shifted.content_selection = ContentSelection::MultiRange(
+            ranges.iter().map(|&(start, end)| (start + 1, end + 1)).collect()
+        );
+        assert!(!generator.verify_proof(&shifted, &claimed).await.unwrap());
+
+        let overlapping = ContentSelection::MultiRange(vec![(leaf, leaf + 10), (leaf + 5, leaf + 20)]);
+        assert!(generator.generate_proof(temp_file.path(), overlapping).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pattern_spanning_block_boundary() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let mut content = vec![b'a'; 12_000];
+        content[8 * 1024 - 4..8 * 1024 + 4].copy_from_slice(b"STRADDLE");
+        temp_file.write_all(&content).unwrap();
+        temp_file.flush().unwrap();
+
+        let config = ProofConfig {
+            use_cache: false,
+            chunking_strategy: ChunkingStrategy::FixedSize(8 * 1024),
+            ..ProofConfig::default()
+        };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+
+        // The pattern ends in the second leaf, so both leaves are on the inclusion path
+        let blocks = generator.ipfs_processor.create_blocks(&content).unwrap();
+        assert_eq!(blocks.len(), 3);
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::Pattern { content: b"STRADDLE".to_vec() }
+        ).await.unwrap();
+
+        let inclusion_paths = ProofGenerator::decode_proof_output(&proof).unwrap().inclusion_paths;
+        let leaf_hashes: Vec<[u8; 32]> = blocks[1..].iter()
+            .map(|block| Sha256::digest(&block.data).into())
+            .collect();
+        assert_eq!(inclusion_paths, vec![leaf_hashes]);
+        assert!(generator.verify_proof(&proof, b"STRADDLE").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_multiple_keeps_each_inclusion_path() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let mut content = vec![b'a'; 12_000];
+        content[100..105].copy_from_slice(b"alpha");
+        content[200..204].copy_from_slice(b"beta");
+        temp_file.write_all(&content).unwrap();
+        temp_file.flush().unwrap();
+
+        let config = ProofConfig {
+            use_cache: false,
+            chunking_strategy: ChunkingStrategy::FixedSize(8 * 1024),
+            ..ProofConfig::default()
+        };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let blocks = generator.ipfs_processor.create_blocks(&content).unwrap();
+        let first_leaf: [u8; 32] = Sha256::digest(&blocks[1].data).into();
+
+        // Both patterns lie in the first leaf, which each selection's path keeps
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::Multiple(vec![
+                ContentSelection::Pattern { content: b"alpha".to_vec() },
+                ContentSelection::Pattern { content: b"beta".to_vec() },
+            ])
+        ).await.unwrap();
+
+        let inclusion_paths = ProofGenerator::decode_proof_output(&proof).unwrap().inclusion_paths;
+        assert_eq!(inclusion_paths, vec![vec![first_leaf], vec![first_leaf]]);
+        assert!(generator.verify_proof(&proof, b"alphabeta").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_byte_range_bounds() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"0123456789abcdef").unwrap();
+        temp_file.flush().unwrap();
+
+        let config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let blocks = generator.ipfs_processor.create_blocks(b"0123456789abcdef").unwrap();
+        let data: Vec<u8> = blocks.iter().flat_map(|block| block.data.iter().copied()).collect();
+        let total_len = data.len();
+
+        // In range
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::ByteRange { start: 2, end: 6 }
+        ).await.unwrap();
+        assert_eq!(ProofGenerator::decode_proof_output(&proof).unwrap().content_length, total_len as u64);
+        assert!(generator.verify_proof(&proof, &data[2..6]).await.unwrap());
+
+        // Ending exactly at the end of the content
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::ByteRange { start: total_len - 4, end: total_len }
+        ).await.unwrap();
+        assert!(generator.verify_proof(&proof, &data[total_len - 4..]).await.unwrap());
+
+        // One byte past the end
+        let error = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::ByteRange { start: total_len - 4, end: total_len + 1 }
+        ).await.unwrap_err();
+        assert!(error.to_string().contains("extends past the end of the content"));
+    }
+
+    #[tokio::test]
+    async fn test_reproducible_proofs_share_canonical_bytes() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"Quarterly report: revenue grew 12 percent").unwrap();
+        temp_file.flush().unwrap();
+
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let mut proofs = Vec::new();
+        for _ in 0..2 {
+            let config = ProofConfig { use_cache: false, reproducible: true, ..ProofConfig::default() };
+            let mut generator = ProofGenerator::with_config(config).await.unwrap();
+            generator.set_signing_key(Some(key.clone()));
+            proofs.push(generator.generate_proof(
+                temp_file.path(),
+                ContentSelection::Pattern { content: b"revenue grew".to_vec() }
+            ).await.unwrap());
+        }
+
+        let (first, second) = (&proofs[0], &proofs[1]);
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.canonical_bytes().unwrap(), second.canonical_bytes().unwrap());
+        assert_eq!(first.signature, second.signature);
+        assert!(second.verify_signature().unwrap());
+
+        // Fields that change how a proof is accepted stay under the signature
+        let mut extended = second.clone();
+        extended.metadata.valid_until = Some(Utc::now() + chrono::Duration::days(365));
+        assert!(!extended.verify_signature().unwrap());
+        let mut uncommitted = second.clone();
+        uncommitted.metadata.content_committed = true;
+        assert!(!uncommitted.verify_signature().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_committed_proof_verifies_without_content() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"account 4242, balance 1000").unwrap();
+        temp_file.flush().unwrap();
+
+        let config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let selection = ContentSelection::ByteRange { start: 8, end: 12 };
+        let (proof, opening) = generator.generate_committed_proof(temp_file.path(), selection).await.unwrap();
+
+        assert_eq!(opening.content, b"4242");
+        assert!(proof.metadata.content_committed);
+        assert_ne!(proof.content_hash, HashAlgorithm::Sha256.digest(b"4242"));
+
+        let mut verifier = crate::verifier::ProofVerifier::new();
+        assert!(verifier.verify_commitment(&proof).unwrap().is_valid);
+        // The plaintext alone no longer verifies
+        assert!(!verifier.verify_blocking(&proof, b"4242").unwrap().is_valid);
+
+        assert!(verifier.verify_opening(&proof, &opening).unwrap().is_valid);
+        let wrong = ContentOpening { content: b"4243".to_vec(), ..opening };
+        let result = verifier.verify_opening(&proof, &wrong).unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::ContentHashMismatch));
+    }
+
+    #[tokio::test]
+    async fn test_renewed_proof_passes_verification() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "Content whose proof is renewed after expiry").unwrap();
+        let claimed = b"renewed after expiry";
+
+        let config = ProofConfig { use_cache: false, validity_seconds: Some(3600), ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let mut proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::Pattern { content: claimed.to_vec() }
+        ).await.unwrap();
+        assert!(proof.metadata.valid_until.unwrap() > proof.created_at);
+
+        // Simulate the proof having expired
+        proof.metadata.valid_until = Some(Utc::now() - chrono::Duration::seconds(1));
+        let mut verifier = crate::verifier::ProofVerifier::new();
+        assert!(!verifier.verify_simple(&proof, claimed).await.unwrap());
+
+        let renewed = generator.renew_proof(&proof, temp_file.path()).await.unwrap();
+        assert_ne!(renewed.id, proof.id);
+        assert_eq!(renewed.metadata.renewed_from, vec![proof.id.clone()]);
+        assert_eq!(renewed.root_hash, proof.root_hash);
+        assert!(renewed.metadata.valid_until.unwrap() > Utc::now());
+        assert!(verifier.verify_simple(&renewed, claimed).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_extended_proof_binds_full_content() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let original = b"2024-01-01 service started\n".to_vec();
+        temp_file.write_all(&original).unwrap();
+
+        let mut generator = ProofGenerator::new().await.unwrap();
+        let prior = generator
+            .generate_proof(temp_file.path(), ContentSelection::ByteRange { start: 0, end: original.len() })
+            .await
+            .unwrap();
+
+        let appended = b"2024-01-02 request served\n";
+        temp_file.write_all(appended).unwrap();
+        temp_file.flush().unwrap();
+
+        let extended = generator.extend_proof(&prior, temp_file.path()).await.unwrap();
+        let link = extended.metadata.extends.as_ref().unwrap();
+        assert_eq!(link.prior.id, prior.id);
+        assert_eq!(link.prior_content_len, original.len() as u64);
+        assert_eq!(extended.metadata.file_info.size.0, (original.len() + appended.len()) as u64);
+
+        let full_content = [&original[..], &appended[..]].concat();
+        let mut verifier = crate::verifier::ProofVerifier::new();
+        assert!(verifier.verify_simple(&extended, &full_content).await.unwrap());
+        assert!(generator.verify_proof(&extended, &full_content).await.unwrap());
+        assert!(!verifier.verify_simple(&extended, appended).await.unwrap());
+        assert!(!verifier.verify_simple(&extended, &full_content[1..]).await.unwrap());
+
+        let mut unlinked = extended.clone();
+        unlinked.root_hash = [9; 32];
+        let result = verifier.verify_detailed(&unlinked, &full_content).await.unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::ChainLinkMismatch));
+
+        // The prior content must be unchanged
+        std::fs::write(temp_file.path(), [&b"2024-01-01 service stopped\n"[..], &appended[..]].concat()).unwrap();
+        assert!(generator.extend_proof(&prior, temp_file.path()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_partial_proof_from_spanned_leaves() {
+        let content: Vec<u8> = (0..256 * 1024u32).map(|i| (i.wrapping_mul(2654435761) >> 9) as u8).collect();
+        let config = ProofConfig {
+            use_cache: false,
+            chunking_strategy: ChunkingStrategy::FixedSize(4 * 1024),
+            ..ProofConfig::default()
+        };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let blocks = generator.ipfs_processor.create_blocks(&content).unwrap();
+        let offset = blocks[0].data.len();
+        let selection = ContentSelection::ByteRange { start: offset + 100_000, end: offset + 101_000 };
+
+        let dag = PartialDag::covering(&blocks, &selection).unwrap();
+        let proof = generator.generate_partial_proof(&dag, selection).await.unwrap();
+        assert_eq!(proof.root_hash, IpfsProcessor::structure_hash(&blocks));
+
+        let coverage = ProofGenerator::proof_coverage(&proof).unwrap();
+        assert_eq!(coverage, dag.coverage());
+        assert!(!coverage.is_complete());
+        assert!(coverage.fraction() < 0.1);
+
+        let mut verifier = crate::verifier::ProofVerifier::new();
+        assert!(verifier.verify_simple(&proof, &content[100_000..101_000]).await.unwrap());
+        assert!(!verifier.verify_simple(&proof, &content[100_001..101_001]).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_receipt_compression_ratio() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"compressed receipts verify like plain ones").unwrap();
+
+        for compression in [CompressionType::Gzip, CompressionType::Zstd] {
+            let config = ProofConfig { use_cache: false, compression, ..ProofConfig::default() };
+            let mut generator = ProofGenerator::with_config(config).await.unwrap();
+            let proof = generator.generate_proof(
+                temp_file.path(),
+                ContentSelection::Pattern { content: b"receipts".to_vec() }
+            ).await.unwrap();
+
+            let receipt_len = proof.zk_proof.receipt_bytes().unwrap().len();
+            assert!(proof.zk_proof.receipt.len() < receipt_len);
+            let ratio = proof.metadata.performance.compression_ratio.unwrap();
+            assert_eq!(ratio, proof.zk_proof.receipt.len() as f64 / receipt_len as f64);
+            assert_eq!(proof.metadata.performance.proof_size_bytes.get(), proof.zk_proof.receipt.len() as u64);
+
+            let mut verifier = crate::verifier::ProofVerifier::new();
+            assert!(verifier.verify_simple(&proof, b"receipts").await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blake3_content_hash_round_trip() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"interop with a BLAKE3-addressed store").unwrap();
+        let claimed = b"BLAKE3-addressed";
+
+        let config = ProofConfig { use_cache: false, hash_algorithm: HashAlgorithm::Blake3, ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator
+            .generate_proof(temp_file.path(), ContentSelection::Pattern { content: claimed.to_vec() })
+            .await
+            .unwrap();
+
+        assert_eq!(proof.content_hash, <[u8; 32]>::from(blake3::hash(claimed)));
+        assert_eq!(proof.metadata.security.hash_algorithm, HashAlgorithm::Blake3);
+        assert_eq!(proof.metadata.security.hash_function, "BLAKE3");
+
+        let mut verifier = crate::verifier::ProofVerifier::new();
+        assert!(verifier.verify_simple(&proof, claimed).await.unwrap());
+        assert!(generator.verify_proof(&proof, claimed).await.unwrap());
+        assert!(!verifier.verify_simple(&proof, b"SHA256-addressed").await.unwrap());
+
+        // Relabelling the algorithm no longer matches the journal
+        let mut relabelled = proof.clone();
+        relabelled.metadata.security.hash_algorithm = HashAlgorithm::Sha256;
+        assert!(!verifier.verify_simple(&relabelled, claimed).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_tampered_selection_rejected() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"0123456789abcdef").unwrap();
+        let mut generator = ProofGenerator::with_config(ProofConfig { use_cache: false, ..ProofConfig::default() })
+            .await
+            .unwrap();
+        let proof = generator
+            .generate_proof(temp_file.path(), ContentSelection::ByteRange { start: 5, end: 10 })
+            .await
+            .unwrap();
+
+        let mut verifier = crate::verifier::ProofVerifier::new();
+        assert!(verifier.verify_simple(&proof, b"56789").await.unwrap());
+
+        // The same content hash, claimed as a pattern proof instead of a byte range
+        let mut tampered = proof.clone();
+        tampered.content_selection = ContentSelection::Pattern { content: b"56789".to_vec() };
+        assert_eq!(tampered.claimed_content_hash(b"56789"), Some(proof.content_hash));
+
+        let result = verifier.verify_detailed(&tampered, b"56789").await.unwrap();
+        assert!(!result.is_valid);
+        assert_eq!(result.failure, Some(VerificationFailure::SelectionMismatch));
+        assert!(!generator.verify_proof(&tampered, b"56789").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_binary_proof_round_trip() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"stored compactly on disk").unwrap();
+        let mut generator = ProofGenerator::new().await.unwrap();
+        let proof = generator
+            .generate_proof(temp_file.path(), ContentSelection::Pattern { content: b"compactly".to_vec() })
+            .await
+            .unwrap();
+
+        let json = proof.encode(ProofFormat::Json).unwrap();
+        let binary = proof.to_bytes().unwrap();
+        assert!(binary.starts_with(&BINARY_PROOF_MAGIC));
+        assert!(binary.len() * 2 < json.len(), "binary {} bytes, JSON {} bytes", binary.len(), json.len());
+
+        let decoded = Proof::decode(&binary).unwrap();
+        assert_eq!(decoded.fingerprint(), proof.fingerprint());
+        assert_eq!(decoded.zk_proof.receipt, proof.zk_proof.receipt);
+        assert_eq!(decoded.metadata.file_info.ipfs_cid, proof.metadata.file_info.ipfs_cid);
+        assert_eq!(Proof::decode(&json).unwrap().id, proof.id);
+
+        let mut future = binary.clone();
+        future[4] = 9;
+        assert!(Proof::from_bytes(&future).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cycle_count_recorded() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let content: Vec<u8> = (0..64 * 1024u32).map(|i| (i % 251) as u8).collect();
+        temp_file.write_all(&content).unwrap();
+
+        let mut generator = ProofGenerator::new().await.unwrap();
+        let proof = generator
+            .generate_proof(temp_file.path(), ContentSelection::ByteRange { start: 1000, end: 9000 })
+            .await
+            .unwrap();
+
+        let performance = &proof.metadata.performance;
+        assert!(performance.zk_cycles > 0);
+
+        assert_eq!(
+            performance.segment_cycles.iter().map(|segment| segment.cycles).sum::<u64>(),
+            performance.zk_cycles
+        );
+
+        // Proving and estimating count the same executed cycles
+        let selection = ContentSelection::ByteRange { start: 1000, end: 9000 };
+        let estimate = generator.estimate(temp_file.path(), &selection).await.unwrap();
+        assert_eq!(performance.segment_cycles, estimate.segment_cycles);
+        assert_eq!(performance.zk_cycles, estimate.total_cycles);
     }
 
-    /// Finds the first occurrence of a pattern in data
-    fn find_pattern(&self, data: &[u8], pattern: &[u8]) -> Option<usize> {
-        if pattern.is_empty() || pattern.len() > data.len() {
-            return None;
-        }
+    #[tokio::test]
+    async fn test_timeout_fires_while_proving() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"Proving runs off the async worker").unwrap();
 
-        for i in 0..=(data.len() - pattern.len()) {
-            if &data[i..i + pattern.len()] == pattern {
-                return Some(i);
-            }
-        }
+        // On a single-threaded runtime the timer can only fire if proving yields
+        let config = ProofConfig { use_cache: false, timeout_seconds: Some(0), ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let result = generator
+            .generate_proof(temp_file.path(), ContentSelection::Pattern { content: b"async".to_vec() })
+            .await;
 
-        None
+        assert!(matches!(result, Err(ProofError::TimeoutError { .. })));
     }
 
-    /// Generates the ZK proof using Risc0
-    async fn generate_zk_proof(
-        &self,
-        input: ProofInput,
-    ) -> Result<(Receipt, u64)> {
-        let env = ExecutorEnv::builder()
-            .write(&input)
-            .map_err(|e| ProofError::zk_proof_error(
-                "input_preparation",
-                "Failed to prepare input for ZK circuit",
-                Some(e)
-            ))?
-            .build()
-            .map_err(|e| ProofError::zk_proof_error(
-                "environment_setup",
-                "Failed to build executor environment",
-                Some(e)
-            ))?;
+    #[tokio::test]
+    async fn test_sink_receives_proof_completed() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"Events are emitted around every proof").unwrap();
 
-        let prover = default_prover();
-        let opts = ProverOpts::default();
+        let mut generator = ProofGenerator::with_config(ProofConfig { use_cache: false, ..ProofConfig::default() })
+            .await
+            .unwrap();
+        let received = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = received.clone();
+        generator.events().register(move |event: &MonitorEvent| sink.lock().unwrap().push(event.clone()));
 
-        // Apply timeout if configured
-        let prove_future = async {
-            prover.prove_with_opts(env, IPFS_CONTENT_VERIFIER_ELF, &opts)
-                .map_err(|e| ProofError::zk_proof_error(
-                    "proof_generation",
-                    "Failed to generate ZK proof",
-                    Some(e)
-                ))
-        };
+        let proof = generator
+            .generate_proof(temp_file.path(), ContentSelection::Pattern { content: b"emitted".to_vec() })
+            .await
+            .unwrap();
 
-        let receipt = if let Some(timeout_secs) = self.config.timeout_seconds {
-            timeout(Duration::from_secs(timeout_secs), prove_future)
-                .await
-                .map_err(|_| ProofError::timeout_error(
-                    "zk_proof_generation",
-                    timeout_secs * 1000
-                ))?
-        } else {
-            prove_future.await
-        }?;
+        let received = received.lock().unwrap();
+        assert_eq!(received.first(), Some(&MonitorEvent::ProofStarted));
+        let completed = received.iter().find_map(|event| match event {
+            MonitorEvent::ProofCompleted { duration, cycles } => Some((*duration, *cycles)),
+            _ => None,
+        });
+        let (duration, cycles) = completed.expect("a ProofCompleted event");
+        assert_eq!(cycles, proof.metadata.performance.zk_cycles);
+        assert!(duration > Duration::ZERO);
+        assert!(!received.contains(&MonitorEvent::CacheMiss));
+    }
 
-        // Extract cycle count from receipt
-        let cycles = receipt.get_metadata()
-            .map(|m| m.cycles)
-            .unwrap_or(0);
+    #[tokio::test]
+    async fn test_preflight_rejects_range_past_eof() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&[b'x'; 100]).unwrap();
+        let generator = ProofGenerator::new().await.unwrap();
 
-        Ok((receipt, cycles))
-    }
+        let report = generator
+            .preflight(temp_file.path(), &ContentSelection::ByteRange { start: 50, end: 500 })
+            .await
+            .unwrap();
+        assert!(!report.satisfiable);
+        assert!(report.problems[0].contains("past the end"), "{:?}", report.problems);
+        assert!(report.ensure_satisfiable().is_err());
 
-    /// Creates comprehensive proof metadata
-    fn create_proof_metadata(
-        &self,
-        guest_metadata: crate::guest_types::ProofMetadata,
-        file_info: FileInfo,
-        total_time: Duration,
-        file_processing_time: Duration,
-        zk_generation_time: Duration,
-        zk_cycles: u64,
-        receipt: &Receipt,
-    ) -> Result<ProofMetadata> {
-        let receipt_bytes = bincode::serialize(receipt)
-            .map_err(|e| ProofError::serialization_error(
-                "Failed to serialize receipt for size calculation",
-                Some(Box::new(e))
-            ))?;
+        let report = generator
+            .preflight(temp_file.path(), &ContentSelection::ByteRange { start: 50, end: 100 })
+            .await
+            .unwrap();
+        assert!(report.satisfiable);
+        assert_eq!(report.selected_bytes, Bytes(50));
+        assert!(report.estimated_cycles > 0);
+    }
 
-        let performance = PerformanceMetrics {
-            generation_time_ms: total_time.as_millis() as u64,
-            file_processing_time_ms: file_processing_time.as_millis() as u64,
-            zk_generation_time_ms: zk_generation_time.as_millis() as u64,
-            peak_memory_bytes: self.get_peak_memory_usage(),
-            zk_cycles,
-            proof_size_bytes: receipt_bytes.len() as u64,
-            compression_ratio: self.calculate_compression_ratio(&receipt_bytes, &file_info),
-        };
+    #[tokio::test]
+    async fn test_selection_count_limit() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"one two three four five").unwrap();
+        let generator = ProofGenerator::with_config(ProofConfig {
+            max_selections: Some(3),
+            ..ProofConfig::default()
+        }).await.unwrap();
 
-        let security = SecurityParameters {
-            security_level: self.config.security_level,
-            hash_function: "SHA-256".to_string(),
-            proof_system: "Risc0".to_string(),
-            risc0_version: self.get_risc0_version(),
-            formal_verification: false,
-        };
+        let words = |words: &[&str]| ContentSelection::Multiple(
+            words.iter().map(|word| ContentSelection::Pattern { content: word.as_bytes().to_vec() }).collect()
+        );
+        let error = generator.preflight(temp_file.path(), &words(&["one", "two", "three", "four"])).await.unwrap_err();
+        assert!(matches!(&error, ProofError::ResourceLimitError { resource, .. } if resource == "selection_count"), "{}", error);
 
-        let environment = GenerationEnvironment {
-            os: std::env::consts::OS.to_string(),
-            arch: std::env::consts::ARCH.to_string(),
-            hardware_acceleration: self.detect_hardware_acceleration(),
-            prover_type: self.config.prover_type.clone(),
-            library_version: env!("CARGO_PKG_VERSION").to_string(),
-            git_commit: option_env!("GIT_COMMIT").map(|s| s.to_string()),
-        };
+        let report = generator.preflight(temp_file.path(), &words(&["one", "two", "three"])).await.unwrap();
+        assert!(report.satisfiable);
 
-        Ok(ProofMetadata {
-            guest_metadata,
-            file_info,
-            performance,
-            security,
-            environment,
-            custom: self.config.custom_metadata.clone(),
-        })
+        let ranges = ContentSelection::MultiRange(vec![(0, 1), (2, 3), (4, 5), (6, 7)]);
+        assert!(generator.preflight(temp_file.path(), &ranges).await.is_err());
     }
 
-    /// Detects available hardware acceleration
-    fn detect_hardware_acceleration(&self) -> Option<HardwareAcceleration> {
-        if self.config.use_hardware_acceleration {
-            // Check for CUDA support
-            if self.has_cuda_support() {
-                return Some(HardwareAcceleration::Cuda);
-            }
-            
-            // Check for Metal support (macOS)
-            if self.has_metal_support() {
-                return Some(HardwareAcceleration::Metal);
-            }
-            
-            // Check for other hardware acceleration
-            if self.has_hardware_acceleration() {
-                return Some(HardwareAcceleration::Other("CPU-optimized".to_string()));
-            }
-        }
-        
-        Some(HardwareAcceleration::None)
-    }
+    #[tokio::test]
+    async fn test_deeply_nested_selection_rejected() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"nested").unwrap();
+        let generator = ProofGenerator::new().await.unwrap();
 
-    /// Gets peak memory usage during proof generation
-    fn get_peak_memory_usage(&self) -> u64 {
-        // Use system memory information
-        #[cfg(target_os = "linux")]
-        {
-            if let Ok(contents) = std::fs::read_to_string("/proc/self/status") {
-                for line in contents.lines() {
-                    if line.starts_with("VmPeak:") {
-                        if let Some(kb_str) = line.split_whitespace().nth(1) {
-                            if let Ok(kb) = kb_str.parse::<u64>() {
-                                return kb * 1024; // Convert KB to bytes
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        #[cfg(target_os = "macos")]
-        {
-            // Use mach system calls for macOS
-            use std::process::Command;
-            if let Ok(output) = Command::new("ps")
-                .args(&["-o", "rss=", "-p"])
-                .arg(std::process::id().to_string())
-                .output()
-            {
-                if let Ok(rss_str) = String::from_utf8(output.stdout) {
-                    if let Ok(rss_kb) = rss_str.trim().parse::<u64>() {
-                        return rss_kb * 1024; // Convert KB to bytes
-                    }
-                }
-            }
+        let mut selection = ContentSelection::Pattern { content: b"nested".to_vec() };
+        for _ in 0..1000 {
+            selection = ContentSelection::Multiple(vec![selection]);
         }
-        
-        // Fallback: estimate based on proof size and overhead
-        let base_memory = 256 * 1024 * 1024; // 256MB base
-        let proof_overhead = self.stats.total_data_processed_bytes / 10; // 10% overhead estimate
-        base_memory + proof_overhead
-    }
+        let error = generator.preflight(temp_file.path(), &selection).await.unwrap_err();
+        assert!(matches!(&error, ProofError::ResourceLimitError { resource, .. } if resource == "selection_depth"), "{}", error);
 
-    /// Calculates compression ratio if compression is used
-    fn calculate_compression_ratio(&self, proof_bytes: &[u8], file_info: &FileInfo) -> Option<f64> {
-        if self.config.compression.enabled {
-            let original_size = file_info.size as f64;
-            let compressed_size = proof_bytes.len() as f64;
-            
-            if original_size > 0.0 {
-                Some(compressed_size / original_size)
-            } else {
-                None
-            }
-        } else {
-            None
+        let mut selection = ContentSelection::Pattern { content: b"nested".to_vec() };
+        for _ in 0..crate::types::MAX_SELECTION_DEPTH {
+            selection = ContentSelection::Multiple(vec![selection]);
         }
+        assert!(generator.preflight(temp_file.path(), &selection).await.is_ok());
     }
 
-    /// Gets the actual Risc0 version
-    fn get_risc0_version(&self) -> String {
-        // Try to get version from Cargo.toml or environment
-        if let Some(version) = option_env!("RISC0_VERSION") {
-            return version.to_string();
-        }
-        
-        // Parse from Cargo.lock if available
-        if let Ok(cargo_lock) = std::fs::read_to_string("Cargo.lock") {
-            for line in cargo_lock.lines() {
-                if line.contains("risc0-zkvm") {
-                    if let Some(version_line) = cargo_lock.lines()
-                        .skip_while(|l| !l.contains("risc0-zkvm"))
-                        .nth(1)
-                    {
-                        if let Some(version) = version_line.split('"').nth(1) {
-                            return version.to_string();
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Fallback to known version
-        "1.2.0".to_string()
-    }
+    #[tokio::test]
+    async fn test_estimate_measures_cycles_without_proving() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"estimate the cost of proving this line").unwrap();
+        let generator = ProofGenerator::new().await.unwrap();
 
-    /// Checks for CUDA support
-    fn has_cuda_support(&self) -> bool {
-        #[cfg(feature = "cuda")]
-        {
-            // Check if CUDA runtime is available
-            std::process::Command::new("nvidia-smi")
-                .output()
-                .map(|output| output.status.success())
-                .unwrap_or(false)
-        }
-        #[cfg(not(feature = "cuda"))]
-        false
-    }
+        let selection = ContentSelection::Pattern { content: b"cost".to_vec() };
+        let estimate = generator.estimate(temp_file.path(), &selection).await.unwrap();
+        assert!(estimate.total_cycles > 0);
+        assert!(!estimate.segment_cycles.is_empty());
+        assert!(estimate.estimated_proof_size.get() > 0);
 
-    /// Checks for Metal support (macOS)
-    fn has_metal_support(&self) -> bool {
-        #[cfg(all(target_os = "macos", feature = "metal"))]
-        {
-            // Check if Metal is available on macOS
-            true // Metal is available on all modern macOS systems
-        }
-        #[cfg(not(all(target_os = "macos", feature = "metal")))]
-        false
+        let missing = ContentSelection::Pattern { content: b"absent".to_vec() };
+        assert!(generator.estimate(temp_file.path(), &missing).await.is_err());
     }
 
-    /// Checks for general hardware acceleration
-    fn has_hardware_acceleration(&self) -> bool {
-        // Check CPU features
-        #[cfg(target_arch = "x86_64")]
-        {
-            is_x86_feature_detected!("avx2") || is_x86_feature_detected!("avx")
-        }
-        #[cfg(target_arch = "aarch64")]
-        {
-            // ARM64 typically has NEON support
-            true
-        }
-        #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
-        false
-    }
+    #[tokio::test]
+    async fn test_preflight_reports_missing_pattern() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"alpha beta alpha gamma").unwrap();
+        let generator = ProofGenerator::new().await.unwrap();
 
-    /// Updates generation statistics
-    fn update_generation_stats(&mut self, proof: &Proof, duration: Duration) {
-        self.stats.total_proofs_generated += 1;
-        self.stats.total_data_processed_bytes += proof.metadata.file_info.size;
-        
-        // Update average generation time
-        let new_time = duration.as_millis() as f64;
-        let count = self.stats.total_proofs_generated as f64;
-        self.stats.avg_generation_time_ms = 
-            (self.stats.avg_generation_time_ms * (count - 1.0) + new_time) / count;
-    }
+        let missing = ContentSelection::Pattern { content: b"delta".to_vec() };
+        let report = generator.preflight(temp_file.path(), &missing).await.unwrap();
+        assert!(!report.satisfiable);
+        assert!(report.spans.is_empty());
 
-    /// Updates verification statistics
-    fn update_verification_stats(&mut self, is_valid: bool, duration: Duration) {
-        self.stats.total_proofs_verified += 1;
-        
-        // Update average verification time
-        let new_time = duration.as_millis() as f64;
-        let count = self.stats.total_proofs_verified as f64;
-        self.stats.avg_verification_time_ms = 
-            (self.stats.avg_verification_time_ms * (count - 1.0) + new_time) / count;
-        
-        // Update success rate
-        let successful_verifications = if is_valid {
-            (self.stats.verification_success_rate * (count - 1.0)) + 1.0
-        } else {
-            self.stats.verification_success_rate * (count - 1.0)
-        };
-        self.stats.verification_success_rate = successful_verifications / count;
+        let present = ContentSelection::Pattern { content: b"alpha".to_vec() };
+        let report = generator.preflight(temp_file.path(), &present).await.unwrap();
+        assert!(report.satisfiable);
+        assert_eq!(report.spans[0].matched_offsets, vec![0, 11]);
+        assert_eq!((report.spans[0].start, report.spans[0].end), (0, 5));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::NamedTempFile;
-    use std::io::Write;
+    #[tokio::test]
+    async fn test_json_path_proof() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, r#"{{
+  "invoice": "INV-2041",
+  "customer": {{ "name": "Confidential Ltd" }},
+  "items": [
+    {{
+      "sku": "WIDGET-1",
+      "price": {{ "amount": 19.99, "currency": "EUR" }}
+    }},
+    {{ "sku": "WIDGET-2", "price": {{ "amount": 5.00, "currency": "EUR" }} }}
+  ]
+}}"#).unwrap();
+        let claimed = br#"{"amount":19.99,"currency":"EUR"}"#;
+
+        let config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::JsonPath { path: "$.items[0].price".to_string() }
+        ).await.unwrap();
+
+        assert!(matches!(proof.content_selection, ContentSelection::JsonSpan { .. }));
+        assert_eq!(proof.content_hash, <[u8; 32]>::from(Sha256::digest(claimed)));
+
+        let mut verifier = crate::verifier::ProofVerifier::new();
+        assert!(verifier.verify_simple(&proof, claimed).await.unwrap());
+        assert!(!verifier.verify_simple(&proof, br#"{"amount":5.00,"currency":"EUR"}"#).await.unwrap());
+    }
 
     #[tokio::test]
-    async fn test_proof_generator_creation() {
-        let generator = ProofGenerator::new().await;
-        assert!(generator.is_ok());
+    async fn test_csv_cell_preset_proof() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"invoice,customer,total\nINV-1,Acme,120.00\nINV-2,\"Smith, J\",75.50\n").unwrap();
+        temp_file.flush().unwrap();
+
+        let config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::csv_cell(2, 1)
+        ).await.unwrap();
+
+        // The preset is proven as the byte range of the cell
+        assert!(matches!(proof.content_selection, ContentSelection::ByteRange { .. }));
+        assert!(generator.verify_proof(&proof, b"Smith, J").await.unwrap());
+        assert!(!generator.verify_proof(&proof, b"Acme").await.unwrap());
+
+        let missing = generator.generate_proof(temp_file.path(), ContentSelection::csv_cell(2, 5)).await;
+        assert!(missing.is_err());
     }
 
     #[tokio::test]
-    async fn test_input_validation() {
-        let generator = ProofGenerator::new().await.unwrap();
-        
-        // Test non-existent file
-        let result = generator.validate_inputs(
-            Path::new("/non/existent/file"),
-            &ContentSelection::Pattern { content: b"test".to_vec() }
-        );
-        assert!(result.is_err());
-        
-        // Test invalid content selection
-        let temp_file = NamedTempFile::new().unwrap();
-        let result = generator.validate_inputs(
+    async fn test_percent_range_proves_last_tenth() {
+        let data: Vec<u8> = (0..1000u32).map(|i| b'a' + (i % 26) as u8).collect();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&data).unwrap();
+        temp_file.flush().unwrap();
+
+        let config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.generate_proof(
             temp_file.path(),
-            &ContentSelection::ByteRange { start: 10, end: 5 }
-        );
-        assert!(result.is_err());
+            ContentSelection::percent_range(90.0, 100.0)
+        ).await.unwrap();
+
+        // The resolved byte range is what the proof commits to
+        assert!(matches!(proof.content_selection, ContentSelection::ByteRange { start: 900, end: 1000 }));
+        assert!(generator.verify_proof(&proof, &data[900..]).await.unwrap());
+        assert!(!generator.verify_proof(&proof, &data[..100]).await.unwrap());
     }
 
     #[tokio::test]
-    async fn test_pattern_finding() {
+    async fn test_large_pattern_hash_match() {
         let generator = ProofGenerator::new().await.unwrap();
-        let data = b"Hello, world! This is a test.";
-        
-        assert_eq!(generator.find_pattern(data, b"world"), Some(7));
-        assert_eq!(generator.find_pattern(data, b"test"), Some(25));
-        assert_eq!(generator.find_pattern(data, b"missing"), None);
-        assert_eq!(generator.find_pattern(data, b""), None);
+        let data: Vec<u8> = (0..1_000_000u32).map(|i| (i.wrapping_mul(31) % 251) as u8).collect();
+        let pattern = &data[400_000..650_000];
+        let blocks: Vec<IpfsBlock> = data.chunks(64 * 1024)
+            .map(|chunk| IpfsBlock { data: chunk.to_vec(), cid: vec![], links: vec![] })
+            .collect();
+
+        let selection = ContentSelection::pattern_hash(pattern);
+        let extracted = generator.extract_content(&blocks, &selection).unwrap();
+        assert_eq!(extracted, pattern);
+
+        // A matching fingerprint alone is not enough; the SHA-256 must match too
+        if let ContentSelection::PatternHash { len, fingerprint, .. } = selection {
+            let result = generator.find_pattern_hash(&data, &[0u8; 32], len, fingerprint);
+            assert_eq!(result, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_large_pattern_hash_proof_verifies() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let data: Vec<u8> = (0..64 * 1024u32).map(|i| (i.wrapping_mul(31) % 251) as u8).collect();
+        temp_file.write_all(&data).unwrap();
+        let pattern = data[20_000..28_192].to_vec();
+
+        let config = ProofConfig { use_cache: false, pattern_hash_threshold: Some(1024), ..ProofConfig::default() };
+        let mut generator = ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator
+            .generate_proof(temp_file.path(), ContentSelection::Pattern { content: pattern.clone() })
+            .await
+            .unwrap();
+        assert!(matches!(proof.content_selection, ContentSelection::PatternHash { len: 8192, .. }));
+
+        let mut verifier = crate::verifier::ProofVerifier::new();
+        assert!(verifier.verify_simple(&proof, &pattern).await.unwrap());
+        let mut altered = pattern;
+        altered[4096] ^= 1;
+        assert!(!verifier.verify_simple(&proof, &altered).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_pattern_hash_threshold() {
+        let mut config = ProofConfig::default();
+        config.pattern_hash_threshold = Some(4);
+        let generator = ProofGenerator::with_config(config).await.unwrap();
+
+        let short = generator.prepare_guest_selection(ContentSelection::Pattern { content: b"abc".to_vec() });
+        assert!(matches!(short, ContentSelection::Pattern { .. }));
+
+        let long = generator.prepare_guest_selection(ContentSelection::Pattern { content: b"abcdefgh".to_vec() });
+        assert!(matches!(long, ContentSelection::PatternHash { len: 8, .. }));
     }
 }
 
@@ -1,12 +1,23 @@
 //! Extended proof types for various verification scenarios
+//!
+//! Each [`ProofType`] a proof can record is proven by a guest program. The
+//! [`ProofTypeRegistry`] maps proof types to their [`ProofScheme`]: the guest
+//! ELF, its image ID and how its input is encoded. `ProofGenerator` proves with
+//! the scheme of the configured proof type, and `ProofVerifier` checks each
+//! receipt against the image ID registered for the proof's type.
 
+use crate::error::{ProofError, Result};
+use crate::{ProofInput, IPFS_CONTENT_VERIFIER_ELF, IPFS_CONTENT_VERIFIER_ID};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 
 /// Different types of proofs supported by the system
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
 pub enum ProofType {
     /// Basic content existence proof
+    #[default]
     ContentExistence,
     /// Proof of content pattern with position
     ContentPattern,
@@ -28,6 +39,111 @@ pub enum ProofType {
     Composite(Vec<ProofType>),
 }
 
+impl ProofType {
+    /// Returns true for the content existence proofs every release has produced
+    pub fn is_default(&self) -> bool {
+        *self == ProofType::ContentExistence
+    }
+}
+
+/// Encodes a proof input as the words a guest reads from its environment
+pub type InputBuilder = Arc<dyn Fn(&ProofInput) -> Result<Vec<u32>> + Send + Sync>;
+
+/// The guest program that proves one proof type
+#[derive(Clone)]
+pub struct ProofScheme {
+    /// Guest ELF executed by the prover
+    pub elf: Arc<[u8]>,
+    /// Image ID receipts of this scheme are verified against
+    pub image_id: [u32; 8],
+    /// Encodes the guest's input
+    pub input_builder: InputBuilder,
+}
+
+impl ProofScheme {
+    pub fn new(
+        elf: impl Into<Arc<[u8]>>,
+        image_id: [u32; 8],
+        input_builder: impl Fn(&ProofInput) -> Result<Vec<u32>> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            elf: elf.into(),
+            image_id,
+            input_builder: Arc::new(input_builder),
+        }
+    }
+
+    /// The IPFS content verifier guest, which proves content existence
+    pub fn ipfs_content() -> Self {
+        Self::new(IPFS_CONTENT_VERIFIER_ELF, IPFS_CONTENT_VERIFIER_ID, |input| {
+            risc0_zkvm::serde::to_vec(input)
+                .map_err(|e| ProofError::serialization_error("Failed to encode guest input", Some(Box::new(e))))
+        })
+    }
+
+    /// Encodes `input` for this scheme's guest
+    pub fn build_input(&self, input: &ProofInput) -> Result<Vec<u32>> {
+        (self.input_builder)(input)
+    }
+}
+
+impl fmt::Debug for ProofScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProofScheme")
+            .field("elf_len", &self.elf.len())
+            .field("image_id", &self.image_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Proof schemes by proof type
+#[derive(Clone, Debug)]
+pub struct ProofTypeRegistry {
+    schemes: HashMap<ProofType, ProofScheme>,
+}
+
+impl ProofTypeRegistry {
+    /// Creates a registry with the built-in content existence scheme
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register(ProofType::ContentExistence, ProofScheme::ipfs_content());
+        registry
+    }
+
+    /// Creates a registry with no schemes
+    pub fn empty() -> Self {
+        Self { schemes: HashMap::new() }
+    }
+
+    /// Registers `scheme` for `proof_type`, replacing any earlier scheme for it
+    pub fn register(&mut self, proof_type: ProofType, scheme: ProofScheme) {
+        self.schemes.insert(proof_type, scheme);
+    }
+
+    /// Returns the scheme registered for `proof_type`
+    pub fn get(&self, proof_type: &ProofType) -> Option<&ProofScheme> {
+        self.schemes.get(proof_type)
+    }
+
+    /// Returns the scheme registered for `proof_type`, or an error naming it
+    pub fn scheme(&self, proof_type: &ProofType) -> Result<&ProofScheme> {
+        self.get(proof_type).ok_or_else(|| {
+            ProofError::configuration_error(format!("No proof scheme registered for {:?}", proof_type))
+        })
+    }
+
+    /// Proof types with a registered scheme
+    pub fn proof_types(&self) -> impl Iterator<Item = &ProofType> {
+        self.schemes.keys()
+    }
+}
+
+impl Default for ProofTypeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Configuration for different proof types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProofTypeConfig {
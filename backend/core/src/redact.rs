@@ -0,0 +1,110 @@
+//! Redaction of identifying proof metadata
+//!
+//! A proof's metadata records the original filename, the file's size and MIME
+//! type, and the machine it was generated on. The receipt commits to none of
+//! it, so [`Proof::redact`] can strip it before a proof is shared and the proof
+//! still verifies. The file hash, CID, block count and security parameters
+//! are always kept, as verification depends on them.
+//!
+//! Redaction changes the bytes a signature covers, so a redacted proof is left
+//! unsigned; sign it again to vouch for it.
+
+use crate::types::{Bytes, Proof};
+use serde::{Deserialize, Serialize};
+
+/// Metadata fields [`Proof::redact`] removes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionOptions {
+    /// Remove the original filename
+    pub filename: bool,
+    /// Zero the file size and average block size
+    pub file_size: bool,
+    /// Remove the detected MIME type
+    pub mime_type: bool,
+    /// Clear the operating system, architecture, hardware acceleration,
+    /// library version and git commit
+    pub environment: bool,
+}
+
+impl RedactionOptions {
+    /// Redacts every field that can be removed
+    pub fn all() -> Self {
+        Self { filename: true, file_size: true, mime_type: true, environment: true }
+    }
+
+    /// Whether no field is redacted
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Proof {
+    /// Removes the metadata fields selected by `options`, here and in any
+    /// proof this one extends, and drops the signatures they invalidate
+    pub fn redact(&mut self, options: &RedactionOptions) {
+        if options.is_empty() {
+            return;
+        }
+
+        let metadata = &mut self.metadata;
+        if options.filename {
+            metadata.file_info.filename = None;
+        }
+        if options.file_size {
+            metadata.file_info.size = Bytes(0);
+            metadata.file_info.avg_block_size = Bytes(0);
+        }
+        if options.mime_type {
+            metadata.file_info.mime_type = None;
+        }
+        if options.environment {
+            let environment = &mut metadata.environment;
+            environment.os.clear();
+            environment.arch.clear();
+            environment.hardware_acceleration = None;
+            environment.library_version.clear();
+            environment.git_commit = None;
+        }
+        if let Some(extension) = &mut metadata.extends {
+            extension.prior.redact(options);
+        }
+        self.signature = None;
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::{ContentSelection, ProofConfig, ProofGenerator, ProofVerifier};
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[tokio::test]
+    async fn test_redacted_proof_still_verifies() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"The source asked not to be named in this report").unwrap();
+
+        let mut generator = ProofGenerator::with_config(ProofConfig { use_cache: false, ..ProofConfig::default() })
+            .await
+            .unwrap();
+        let mut proof = generator
+            .generate_proof(file.path(), ContentSelection::Pattern { content: b"not to be named".to_vec() })
+            .await
+            .unwrap();
+        assert!(proof.metadata.file_info.filename.is_some());
+
+        proof.redact(&RedactionOptions { filename: true, environment: true, ..RedactionOptions::default() });
+        assert_eq!(proof.metadata.file_info.filename, None);
+        assert!(proof.metadata.environment.os.is_empty());
+        assert!(proof.metadata.environment.arch.is_empty());
+        assert_eq!(proof.metadata.environment.git_commit, None);
+        assert!(proof.metadata.file_info.mime_type.is_some());
+
+        let reloaded = Proof::decode(&proof.encode(crate::ProofFormat::Json).unwrap()).unwrap();
+        let result = ProofVerifier::new()
+            .verify_detailed(&reloaded, b"not to be named")
+            .await
+            .unwrap();
+        assert!(result.is_valid, "{:?}", result.failure);
+    }
+}
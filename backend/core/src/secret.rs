@@ -0,0 +1,63 @@
+//! Guard for content that must not reach logs
+//!
+//! Claimed content is the plaintext a proof keeps private. Wrapping it in a
+//! [`Secret`] means a stray `{:?}`, in a log line or an `#[instrument]` span,
+//! prints only its length. The bytes are reached explicitly through
+//! [`Secret::expose`].
+
+use std::fmt;
+
+/// A value whose `Debug` output is `[REDACTED len=N]` instead of its contents
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    /// Wraps `value`
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Returns the guarded value
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Unwraps the guarded value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: AsRef<[u8]>> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[REDACTED len={}]", self.0.as_ref().len())
+    }
+}
+
+impl From<Vec<u8>> for Secret<Vec<u8>> {
+    fn from(content: Vec<u8>) -> Self {
+        Self(content)
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> From<&T> for Secret<Vec<u8>> {
+    fn from(content: &T) -> Self {
+        Self(content.as_ref().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_never_shows_content() {
+        let secret = Secret::from(b"source: J. Doe".as_slice());
+        let debug = format!("{:?} {:#?}", secret, Some(&secret));
+
+        assert!(debug.contains("[REDACTED len=14]"));
+        assert!(!debug.contains("Doe"));
+        assert!(!debug.contains(&format!("{:?}", b"source: J. Doe".to_vec())[1..10]));
+        assert_eq!(secret.expose(), b"source: J. Doe");
+    }
+}
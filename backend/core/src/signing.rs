@@ -0,0 +1,91 @@
+//! Ed25519 signing of proofs
+//!
+//! A signed proof carries a [`ProofSignature`] naming the key that produced it.
+//! The signature is over [`Proof::signing_bytes`]: a domain prefix followed by
+//! the proof serialized as JSON with its signature removed and object keys
+//! sorted. Those bytes cover the content hash, root hash, receipt and metadata,
+//! so a signature verifies for exactly one proof, and keep verifying after the
+//! proof is saved and reloaded in either [`ProofFormat`](crate::types::ProofFormat).
+//!
+//! Reproducible proofs are instead signed over their
+//! [canonical bytes](crate::canonical), under a separate domain prefix, so the
+//! same claim signed on two machines yields the same signature.
+
+use crate::{
+    error::{ProofError, Result},
+    types::{Proof, ProofSignature},
+};
+use ed25519_dalek::{Signature, Signer};
+
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+/// Prefix of the signed message, so proof signatures cannot be replayed elsewhere
+const SIGNING_DOMAIN: &[u8] = b"zkipfs-proof/signature/v1\0";
+
+/// Prefix of the signed message for reproducible proofs
+const CANONICAL_SIGNING_DOMAIN: &[u8] = b"zkipfs-proof/signature/canonical/v1\0";
+
+impl Proof {
+    /// Returns the bytes a proof signature covers
+    ///
+    /// `serde_json` keeps object keys sorted, which makes the encoding
+    /// independent of the order `metadata.custom` was filled in. Reproducible
+    /// proofs sign their canonical bytes instead.
+    pub fn signing_bytes(&self) -> Result<Vec<u8>> {
+        if self.metadata.reproducible {
+            let mut message = CANONICAL_SIGNING_DOMAIN.to_vec();
+            message.extend_from_slice(&self.canonical_bytes()?);
+            return Ok(message);
+        }
+
+        let serialization_error = |e: serde_json::Error| {
+            ProofError::serialization_error("Failed to encode proof for signing", Some(Box::new(e)))
+        };
+
+        let mut unsigned = serde_json::to_value(self).map_err(serialization_error)?;
+        if let Some(fields) = unsigned.as_object_mut() {
+            fields.remove("signature");
+            fields.remove("timestamp");
+        }
+
+        let mut message = SIGNING_DOMAIN.to_vec();
+        serde_json::to_writer(&mut message, &unsigned).map_err(serialization_error)?;
+        Ok(message)
+    }
+
+    /// Signs this proof with `key`, replacing any existing signature
+    pub fn sign(&mut self, key: &SigningKey) -> Result<()> {
+        let message = self.signing_bytes()?;
+        self.signature = Some(ProofSignature {
+            signature: key.sign(&message).to_bytes().to_vec(),
+            signer_public_key: key.verifying_key().to_bytes(),
+        });
+        Ok(())
+    }
+
+    /// Checks the proof's signature against its recorded signer
+    ///
+    /// Returns `false` for an unsigned proof, a malformed signature or key, or
+    /// a signature that does not cover this proof.
+    pub fn verify_signature(&self) -> Result<bool> {
+        let Some(signature) = &self.signature else {
+            return Ok(false);
+        };
+        let Ok(signer) = VerifyingKey::from_bytes(&signature.signer_public_key) else {
+            return Ok(false);
+        };
+        let Ok(signature) = Signature::from_slice(&signature.signature) else {
+            return Ok(false);
+        };
+
+        Ok(signer.verify_strict(&self.signing_bytes()?, &signature).is_ok())
+    }
+
+    /// Checks that this proof carries a valid signature by `signer`
+    pub fn is_signed_by(&self, signer: &VerifyingKey) -> Result<bool> {
+        match &self.signature {
+            Some(signature) if signature.signer_public_key == signer.to_bytes() => self.verify_signature(),
+            _ => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,503 @@
+//! Storage backends for generated proofs
+//!
+//! [`ProofStore`] keeps proofs by their [`Proof::id`]. The CLI and server
+//! pick a backend and share the rest:
+//!
+//! - [`MemoryStore`] for tests and short-lived processes
+//! - [`FileSystemStore`] for one JSON file per proof in a directory
+//! - `SqliteStore` for a `proofs` table, with the `sqlite` feature
+//! - `S3Store` for objects in an S3 bucket, with the `s3` feature
+//!
+//! Proofs can also be looked up by the content they prove, through
+//! [`ProofStore::find_by_hash`], so a proof for content already proven can be
+//! reused instead of generated again.
+
+use crate::{
+    error::{ProofError, Result},
+    types::Proof,
+};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tokio::fs;
+
+/// A place proofs are kept, keyed by their id
+#[async_trait]
+pub trait ProofStore: Send + Sync {
+    /// Stores `proof`, replacing any proof with the same id
+    async fn put(&self, proof: &Proof) -> Result<()>;
+
+    /// Returns the proof with `id`, if stored
+    async fn get(&self, id: &str) -> Result<Option<Proof>>;
+
+    /// Returns the ids of every stored proof, sorted
+    async fn list(&self) -> Result<Vec<String>>;
+
+    /// Removes the proof with `id`, returning whether it was stored
+    async fn delete(&self, id: &str) -> Result<bool>;
+
+    /// Returns the ids of stored proofs whose `kind` hash is `hash`, sorted
+    ///
+    /// Backends without an index read every stored proof.
+    async fn find_by_hash(&self, kind: HashKind, hash: &[u8; 32]) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        for id in self.list().await? {
+            if let Some(proof) = self.get(&id).await? {
+                if kind.of(&proof) == hash {
+                    ids.push(id);
+                }
+            }
+        }
+        Ok(ids)
+    }
+}
+
+/// Hash of a proof that [`ProofStore::find_by_hash`] matches
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashKind {
+    /// [`Proof::root_hash`], identifying the file
+    Root,
+    /// [`Proof::content_hash`], identifying the proven content
+    Content,
+}
+
+impl HashKind {
+    fn of(self, proof: &Proof) -> &[u8; 32] {
+        match self {
+            HashKind::Root => &proof.root_hash,
+            HashKind::Content => &proof.content_hash,
+        }
+    }
+}
+
+/// Rejects ids that are empty or would escape a directory or key prefix
+fn check_id(id: &str) -> Result<()> {
+    let valid = !id.is_empty()
+        && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ProofError::invalid_input_error(
+            "id",
+            "Proof ids may only contain ASCII letters, digits, '-' and '_'"
+        ))
+    }
+}
+
+fn encode(proof: &Proof) -> Result<Vec<u8>> {
+    check_id(&proof.id)?;
+    serde_json::to_vec_pretty(proof)
+        .map_err(|e| ProofError::serialization_error("Failed to serialize proof", Some(Box::new(e))))
+}
+
+fn decode(data: &[u8]) -> Result<Proof> {
+    serde_json::from_slice(data)
+        .map_err(|e| ProofError::serialization_error("Failed to deserialize stored proof", Some(Box::new(e))))
+}
+
+/// Keeps proofs in memory for the life of the process
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    proofs: RwLock<BTreeMap<String, Proof>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ProofStore for MemoryStore {
+    async fn put(&self, proof: &Proof) -> Result<()> {
+        check_id(&proof.id)?;
+        self.proofs.write().unwrap().insert(proof.id.clone(), proof.clone());
+        Ok(())
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<Proof>> {
+        check_id(id)?;
+        Ok(self.proofs.read().unwrap().get(id).cloned())
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        Ok(self.proofs.read().unwrap().keys().cloned().collect())
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool> {
+        check_id(id)?;
+        Ok(self.proofs.write().unwrap().remove(id).is_some())
+    }
+
+    async fn find_by_hash(&self, kind: HashKind, hash: &[u8; 32]) -> Result<Vec<String>> {
+        Ok(self.proofs.read().unwrap().values()
+            .filter(|proof| kind.of(proof) == hash)
+            .map(|proof| proof.id.clone())
+            .collect())
+    }
+}
+
+/// Keeps each proof as `<id>.json` in a directory
+#[derive(Debug, Clone)]
+pub struct FileSystemStore {
+    dir: PathBuf,
+}
+
+impl FileSystemStore {
+    /// Opens a store in `dir`, creating the directory if needed
+    pub async fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).await
+            .map_err(|e| ProofError::file_error(format!("Failed to create proof store {}", dir.display()), Some(e)))?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, id: &str) -> Result<PathBuf> {
+        check_id(id)?;
+        Ok(self.dir.join(format!("{}.json", id)))
+    }
+}
+
+#[async_trait]
+impl ProofStore for FileSystemStore {
+    async fn put(&self, proof: &Proof) -> Result<()> {
+        let path = self.path(&proof.id)?;
+        let data = encode(proof)?;
+
+        // Written beside the target and renamed, so readers never see a partial proof
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, data).await
+            .map_err(|e| ProofError::file_error(format!("Failed to write {}", temp_path.display()), Some(e)))?;
+        fs::rename(&temp_path, &path).await
+            .map_err(|e| ProofError::file_error(format!("Failed to write {}", path.display()), Some(e)))
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<Proof>> {
+        match fs::read(self.path(id)?).await {
+            Ok(data) => decode(&data).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ProofError::file_error(format!("Failed to read proof {}", id), Some(e))),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        let mut entries = fs::read_dir(&self.dir).await
+            .map_err(|e| ProofError::file_error(format!("Failed to read {}", self.dir.display()), Some(e)))?;
+
+        let mut ids = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    if check_id(id).is_ok() {
+                        ids.push(id.to_string());
+                    }
+                }
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool> {
+        match fs::remove_file(self.path(id)?).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(ProofError::file_error(format!("Failed to delete proof {}", id), Some(e))),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::SqliteStore;
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use sqlx::SqlitePool;
+
+    fn db_error(message: &str, e: sqlx::Error) -> ProofError {
+        ProofError::internal_error(message, Some(Box::new(e)))
+    }
+
+    fn hash_column(kind: HashKind) -> &'static str {
+        match kind {
+            HashKind::Root => "root_hash",
+            HashKind::Content => "content_hash",
+        }
+    }
+
+    /// Keeps proofs as JSON in a `proofs` table, indexed by their root and
+    /// content hashes
+    #[derive(Debug, Clone)]
+    pub struct SqliteStore {
+        pool: SqlitePool,
+    }
+
+    impl SqliteStore {
+        /// Uses `pool`, creating the `proofs` table if it does not exist
+        pub async fn new(pool: SqlitePool) -> Result<Self> {
+            sqlx::query(
+                "CREATE TABLE IF NOT EXISTS proofs (
+                    id TEXT PRIMARY KEY,
+                    proof TEXT NOT NULL,
+                    created_at DATETIME NOT NULL,
+                    root_hash TEXT,
+                    content_hash TEXT
+                )",
+            )
+            .execute(&pool)
+            .await
+            .map_err(|e| db_error("Failed to create proofs table", e))?;
+
+            // Tables created before the hash index lack the columns; the error on newer ones is expected
+            for kind in [HashKind::Root, HashKind::Content] {
+                let column = hash_column(kind);
+                let _ = sqlx::query(&format!("ALTER TABLE proofs ADD COLUMN {} TEXT", column))
+                    .execute(&pool)
+                    .await;
+                sqlx::query(&format!("CREATE INDEX IF NOT EXISTS proofs_{0} ON proofs ({0})", column))
+                    .execute(&pool)
+                    .await
+                    .map_err(|e| db_error("Failed to index proofs", e))?;
+            }
+
+            let store = Self { pool };
+            store.index_unindexed().await?;
+            Ok(store)
+        }
+
+        /// Fills in the hash columns of proofs stored before they existed
+        async fn index_unindexed(&self) -> Result<()> {
+            let rows: Vec<(String, String)> = sqlx::query_as("SELECT id, proof FROM proofs WHERE root_hash IS NULL")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| db_error("Failed to read unindexed proofs", e))?;
+            for (id, data) in rows {
+                let proof = decode(data.as_bytes())?;
+                sqlx::query("UPDATE proofs SET root_hash = ?, content_hash = ? WHERE id = ?")
+                    .bind(hex::encode(proof.root_hash))
+                    .bind(hex::encode(proof.content_hash))
+                    .bind(id)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| db_error("Failed to index proof", e))?;
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ProofStore for SqliteStore {
+        async fn put(&self, proof: &Proof) -> Result<()> {
+            let data = String::from_utf8(encode(proof)?)
+                .map_err(|e| ProofError::serialization_error("Serialized proof is not UTF-8", Some(Box::new(e))))?;
+            sqlx::query(
+                "INSERT INTO proofs (id, proof, created_at, root_hash, content_hash) VALUES (?, ?, ?, ?, ?)
+                 ON CONFLICT (id) DO UPDATE SET proof = excluded.proof, created_at = excluded.created_at,
+                     root_hash = excluded.root_hash, content_hash = excluded.content_hash",
+            )
+            .bind(&proof.id)
+            .bind(data)
+            .bind(proof.created_at)
+            .bind(hex::encode(proof.root_hash))
+            .bind(hex::encode(proof.content_hash))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| db_error("Failed to store proof", e))?;
+            Ok(())
+        }
+
+        async fn get(&self, id: &str) -> Result<Option<Proof>> {
+            check_id(id)?;
+            let data: Option<String> = sqlx::query_scalar("SELECT proof FROM proofs WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| db_error("Failed to read proof", e))?;
+            data.map(|data| decode(data.as_bytes())).transpose()
+        }
+
+        async fn list(&self) -> Result<Vec<String>> {
+            sqlx::query_scalar("SELECT id FROM proofs ORDER BY id")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| db_error("Failed to list proofs", e))
+        }
+
+        async fn delete(&self, id: &str) -> Result<bool> {
+            check_id(id)?;
+            let result = sqlx::query("DELETE FROM proofs WHERE id = ?")
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| db_error("Failed to delete proof", e))?;
+            Ok(result.rows_affected() > 0)
+        }
+
+        async fn find_by_hash(&self, kind: HashKind, hash: &[u8; 32]) -> Result<Vec<String>> {
+            sqlx::query_scalar(&format!("SELECT id FROM proofs WHERE {} = ? ORDER BY id", hash_column(kind)))
+                .bind(hex::encode(hash))
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| db_error("Failed to look up proofs", e))
+        }
+    }
+}
+
+#[cfg(feature = "s3")]
+pub use self::s3_store::S3Store;
+
+#[cfg(feature = "s3")]
+mod s3_store {
+    use super::*;
+    use s3::{creds::Credentials, Bucket, Region};
+
+    fn s3_error(message: &str, e: s3::error::S3Error) -> ProofError {
+        ProofError::internal_error(message, Some(Box::new(e)))
+    }
+
+    fn status_error(message: &str, status: u16) -> ProofError {
+        ProofError::internal_error(format!("{}: S3 returned HTTP {}", message, status), None)
+    }
+
+    /// Keeps each proof as the object `<prefix><id>.json` in an S3 bucket
+    pub struct S3Store {
+        bucket: Box<Bucket>,
+        prefix: String,
+    }
+
+    impl S3Store {
+        /// Uses `bucket` in `region`, storing proofs under `prefix`
+        pub fn new(bucket: &str, region: Region, credentials: Credentials, prefix: impl Into<String>) -> Result<Self> {
+            let bucket = Bucket::new(bucket, region, credentials)
+                .map_err(|e| s3_error("Failed to configure S3 bucket", e))?;
+            Ok(Self { bucket, prefix: prefix.into() })
+        }
+
+        fn key(&self, id: &str) -> Result<String> {
+            check_id(id)?;
+            Ok(format!("{}{}.json", self.prefix, id))
+        }
+    }
+
+    #[async_trait]
+    impl ProofStore for S3Store {
+        async fn put(&self, proof: &Proof) -> Result<()> {
+            let key = self.key(&proof.id)?;
+            let response = self.bucket
+                .put_object_with_content_type(&key, &encode(proof)?, "application/json")
+                .await
+                .map_err(|e| s3_error("Failed to store proof", e))?;
+            match response.status_code() {
+                200..=299 => Ok(()),
+                status => Err(status_error("Failed to store proof", status)),
+            }
+        }
+
+        async fn get(&self, id: &str) -> Result<Option<Proof>> {
+            let response = self.bucket
+                .get_object(self.key(id)?)
+                .await
+                .map_err(|e| s3_error("Failed to read proof", e))?;
+            match response.status_code() {
+                200..=299 => decode(response.bytes()).map(Some),
+                404 => Ok(None),
+                status => Err(status_error("Failed to read proof", status)),
+            }
+        }
+
+        async fn list(&self) -> Result<Vec<String>> {
+            let pages = self.bucket
+                .list(self.prefix.clone(), None)
+                .await
+                .map_err(|e| s3_error("Failed to list proofs", e))?;
+
+            let mut ids: Vec<String> = pages.iter()
+                .flat_map(|page| &page.contents)
+                .filter_map(|object| object.key.strip_prefix(&self.prefix)?.strip_suffix(".json"))
+                .filter(|id| check_id(id).is_ok())
+                .map(str::to_string)
+                .collect();
+            ids.sort();
+            Ok(ids)
+        }
+
+        async fn delete(&self, id: &str) -> Result<bool> {
+            let key = self.key(id)?;
+            // S3 deletes succeed whether or not the object exists, so check first
+            if self.get(id).await?.is_none() {
+                return Ok(false);
+            }
+            let response = self.bucket
+                .delete_object(&key)
+                .await
+                .map_err(|e| s3_error("Failed to delete proof", e))?;
+            match response.status_code() {
+                200..=299 => Ok(true),
+                status => Err(status_error("Failed to delete proof", status)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::tests::create_test_proof;
+
+    fn proof_with_id(id: &str) -> Proof {
+        let mut proof = create_test_proof();
+        proof.id = id.to_string();
+        proof
+    }
+
+    async fn exercise(store: &dyn ProofStore) {
+        assert!(store.list().await.unwrap().is_empty());
+        assert!(store.get("a").await.unwrap().is_none());
+
+        store.put(&proof_with_id("b")).await.unwrap();
+        store.put(&proof_with_id("a")).await.unwrap();
+        assert_eq!(store.list().await.unwrap(), vec!["a", "b"]);
+
+        let mut replaced = proof_with_id("a");
+        replaced.content_hash = [7; 32];
+        store.put(&replaced).await.unwrap();
+        let stored = store.get("a").await.unwrap().unwrap();
+        assert_eq!(stored.content_hash, [7; 32]);
+        assert_eq!(stored.root_hash, replaced.root_hash);
+
+        assert_eq!(store.find_by_hash(HashKind::Content, &[7; 32]).await.unwrap(), vec!["a"]);
+        assert_eq!(store.find_by_hash(HashKind::Root, &replaced.root_hash).await.unwrap(), vec!["a", "b"]);
+        assert!(store.find_by_hash(HashKind::Root, &[7; 32]).await.unwrap().is_empty());
+
+        assert!(store.delete("a").await.unwrap());
+        assert!(!store.delete("a").await.unwrap());
+        assert_eq!(store.list().await.unwrap(), vec!["b"]);
+
+        assert!(store.put(&proof_with_id("../escape")).await.is_err());
+        assert!(store.get("../b").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store() {
+        exercise(&MemoryStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_file_system_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileSystemStore::new(dir.path().join("proofs")).await.unwrap();
+        exercise(&store).await;
+
+        // Files that are not stored proofs are not listed
+        std::fs::write(store.dir().join("notes.txt"), b"not a proof").unwrap();
+        assert_eq!(store.list().await.unwrap(), vec!["b"]);
+        assert!(dir.path().join("proofs/b.json").exists());
+    }
+}
@@ -0,0 +1,532 @@
+//! RFC 3161 timestamps anchoring when a proof existed
+//!
+//! The guest's timestamp is a cycle count, not a time. A proof can instead be
+//! anchored by a timestamp authority (TSA): [`TimestampClient`] sends the
+//! SHA-256 [canonical hash](crate::canonical) of the proof, and the TSA returns
+//! a token signing that hash together with the time it saw it. The token is
+//! kept in [`Proof::timestamp`], and [`Proof::verify_timestamp`] checks that it
+//! binds the proof and was signed by a trusted TSA key, establishing that the
+//! proof existed at the token's time.
+//!
+//! Tokens are CMS `SignedData` over a `TSTInfo`, as RFC 3161 specifies. Only
+//! Ed25519 signatures (RFC 8419) are checked, against a TSA public key the
+//! verifier trusts rather than a certificate chain. The canonical hash leaves
+//! out the token, so a proof can be stamped after it is signed.
+
+use crate::{
+    error::{ProofError, Result},
+    signing::VerifyingKey,
+    types::Proof,
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// DER tags used by timestamp requests and tokens
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+#[cfg(not(target_arch = "wasm32"))]
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+const TAG_CONTEXT_0: u8 = 0xa0;
+const TAG_CONTEXT_1: u8 = 0xa1;
+
+/// Object identifiers, DER-encoded
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+const OID_ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+const OID_SIGNED_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x02];
+const OID_CONTENT_TYPE: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x03];
+const OID_MESSAGE_DIGEST: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x04];
+const OID_TST_INFO: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x10, 0x01, 0x04];
+
+/// An RFC 3161 timestamp token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TimestampToken {
+    /// DER-encoded `ContentInfo` holding the TSA's `SignedData`
+    #[serde(with = "serde_bytes")]
+    pub token: Vec<u8>,
+}
+
+/// The fields of a token's `TSTInfo` that are checked
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimestampInfo {
+    /// Hash the TSA signed
+    pub hashed_message: Vec<u8>,
+    /// Time the TSA saw the hash
+    pub gen_time: DateTime<Utc>,
+    /// Nonce echoed from the request, if any
+    pub nonce: Option<u64>,
+}
+
+/// Parts of a token's `SignedData`
+struct SignedToken<'a> {
+    tst_info: &'a [u8],
+    digest_algorithm: &'a [u8],
+    signed_attributes: Option<&'a [u8]>,
+    signature_algorithm: &'a [u8],
+    signature: &'a [u8],
+}
+
+impl TimestampToken {
+    /// Takes the token out of a DER `TimeStampResp`, failing if the TSA did not
+    /// grant the request
+    pub fn from_response(response: &[u8]) -> Result<Self> {
+        let mut response = Der(Der(response).expect(TAG_SEQUENCE)?);
+        let mut status = Der(response.expect(TAG_SEQUENCE)?);
+        match status.expect(TAG_INTEGER)? {
+            // granted, grantedWithMods
+            [0] | [1] => {}
+            status => return Err(token_error(format!("Timestamp authority refused the request with status {:?}", status))),
+        }
+        let token = response.next_raw()?.to_vec();
+        Self::open(&token)?;
+        Ok(Self { token })
+    }
+
+    /// Reads the `TSTInfo` of the token, without checking its signature
+    pub fn info(&self) -> Result<TimestampInfo> {
+        parse_tst_info(Self::open(&self.token)?.tst_info)
+    }
+
+    /// Checks that the token stamps `hash` and is signed by `tsa_key`,
+    /// returning the time it attests
+    pub fn verify(&self, hash: &[u8; 32], tsa_key: &VerifyingKey) -> Result<DateTime<Utc>> {
+        let signed = Self::open(&self.token)?;
+        if signed.signature_algorithm != OID_ED25519 {
+            return Err(token_error("Only Ed25519-signed timestamp tokens can be verified"));
+        }
+
+        let message = match signed.signed_attributes {
+            Some(attributes) => {
+                if signed.digest_algorithm != OID_SHA256 {
+                    return Err(token_error("Timestamp token digest is not SHA-256"));
+                }
+                let digest = attribute(attributes, OID_MESSAGE_DIGEST)?
+                    .ok_or_else(|| token_error("Timestamp token has no message digest"))?;
+                if Der(digest).expect(TAG_OCTET_STRING)? != Sha256::digest(signed.tst_info).as_slice() {
+                    return Err(token_error("Timestamp token message digest does not match its TSTInfo"));
+                }
+                // Signed attributes are signed as a SET, not with their implicit tag
+                der(TAG_SET, attributes)
+            }
+            None => signed.tst_info.to_vec(),
+        };
+        let signature = ed25519_dalek::Signature::from_slice(signed.signature)
+            .map_err(|_| token_error("Timestamp token signature is malformed"))?;
+        tsa_key.verify_strict(&message, &signature)
+            .map_err(|_| token_error("Timestamp token is not signed by the trusted timestamp authority"))?;
+
+        let info = parse_tst_info(signed.tst_info)?;
+        if info.hashed_message != hash {
+            return Err(token_error("Timestamp token does not stamp this proof"));
+        }
+        Ok(info.gen_time)
+    }
+
+    fn open(token: &[u8]) -> Result<SignedToken<'_>> {
+        let mut content_info = Der(Der(token).expect(TAG_SEQUENCE)?);
+        if content_info.expect(TAG_OID)? != OID_SIGNED_DATA {
+            return Err(token_error("Timestamp token is not CMS SignedData"));
+        }
+        let mut signed_data = Der(Der(content_info.expect(TAG_CONTEXT_0)?).expect(TAG_SEQUENCE)?);
+        signed_data.expect(TAG_INTEGER)?;
+        signed_data.expect(TAG_SET)?;
+
+        let mut content = Der(signed_data.expect(TAG_SEQUENCE)?);
+        if content.expect(TAG_OID)? != OID_TST_INFO {
+            return Err(token_error("Timestamp token does not hold a TSTInfo"));
+        }
+        let tst_info = Der(content.expect(TAG_CONTEXT_0)?).expect(TAG_OCTET_STRING)?;
+
+        // Certificates and CRLs
+        signed_data.next_if(TAG_CONTEXT_0)?;
+        signed_data.next_if(TAG_CONTEXT_1)?;
+        let mut signer = Der(Der(signed_data.expect(TAG_SET)?).expect(TAG_SEQUENCE)?);
+        signer.expect(TAG_INTEGER)?;
+        // Signer identifier
+        signer.read()?;
+        let digest_algorithm = Der(signer.expect(TAG_SEQUENCE)?).expect(TAG_OID)?;
+        let signed_attributes = signer.next_if(TAG_CONTEXT_0)?;
+        let signature_algorithm = Der(signer.expect(TAG_SEQUENCE)?).expect(TAG_OID)?;
+        let signature = signer.expect(TAG_OCTET_STRING)?;
+
+        Ok(SignedToken { tst_info, digest_algorithm, signed_attributes, signature_algorithm, signature })
+    }
+}
+
+impl Proof {
+    /// Checks the proof's timestamp token against `tsa_key`, returning the
+    /// time the proof is attested to have existed at
+    pub fn verify_timestamp(&self, tsa_key: &VerifyingKey) -> Result<DateTime<Utc>> {
+        let token = self.timestamp.as_ref()
+            .ok_or_else(|| token_error("Proof has no timestamp token"))?;
+        token.verify(&self.canonical_hash()?, tsa_key)
+    }
+}
+
+/// Requests timestamp tokens from an RFC 3161 timestamp authority over HTTP
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct TimestampClient {
+    url: String,
+    http: reqwest::Client,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TimestampClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into(), http: reqwest::Client::new() }
+    }
+
+    /// Has the TSA stamp `hash`, checking that the token it returns binds the
+    /// hash and the request's nonce
+    pub async fn timestamp(&self, hash: &[u8; 32]) -> Result<TimestampToken> {
+        let mut nonce = [0u8; 8];
+        getrandom::getrandom(&mut nonce)
+            .map_err(|e| ProofError::cryptographic_error("timestamp", format!("Failed to generate nonce: {}", e)))?;
+        // Kept below 2^63 so it encodes as a positive eight-byte INTEGER
+        let nonce = u64::from_be_bytes(nonce) >> 1;
+
+        let response = self.http
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/timestamp-query")
+            .body(timestamp_request(hash, nonce))
+            .send()
+            .await
+            .map_err(|e| ProofError::network_error(
+                "timestamp",
+                format!("Failed to reach timestamp authority {}: {}", self.url, e),
+                Some(e)
+            ))?;
+        if !response.status().is_success() {
+            return Err(ProofError::network_error(
+                "timestamp",
+                format!("Timestamp authority returned {}", response.status()),
+                None
+            ));
+        }
+        let body = response.bytes().await
+            .map_err(|e| ProofError::network_error("timestamp", "Failed to read timestamp reply", Some(e)))?;
+
+        let token = TimestampToken::from_response(&body)?;
+        let info = token.info()?;
+        if info.hashed_message != hash {
+            return Err(token_error("Timestamp authority stamped a different hash"));
+        }
+        if info.nonce != Some(nonce) {
+            return Err(token_error("Timestamp reply does not echo the request nonce"));
+        }
+        Ok(token)
+    }
+
+    /// Stamps the canonical hash of `proof` and stores the token in it
+    pub async fn timestamp_proof(&self, proof: &mut Proof) -> Result<()> {
+        proof.timestamp = Some(self.timestamp(&proof.canonical_hash()?).await?);
+        Ok(())
+    }
+}
+
+/// DER `TimeStampReq` for a SHA-256 `hash`
+#[cfg(not(target_arch = "wasm32"))]
+fn timestamp_request(hash: &[u8; 32], nonce: u64) -> Vec<u8> {
+    der(TAG_SEQUENCE, &[der_uint(1), message_imprint(hash), der_uint(nonce)].concat())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn message_imprint(hash: &[u8]) -> Vec<u8> {
+    let algorithm = der(TAG_SEQUENCE, &[der(TAG_OID, OID_SHA256), der(TAG_NULL, &[])].concat());
+    der(TAG_SEQUENCE, &[algorithm, der(TAG_OCTET_STRING, hash)].concat())
+}
+
+fn parse_tst_info(tst_info: &[u8]) -> Result<TimestampInfo> {
+    let mut info = Der(Der(tst_info).expect(TAG_SEQUENCE)?);
+    info.expect(TAG_INTEGER)?;
+    // Policy
+    info.expect(TAG_OID)?;
+    let mut imprint = Der(info.expect(TAG_SEQUENCE)?);
+    if Der(imprint.expect(TAG_SEQUENCE)?).expect(TAG_OID)? != OID_SHA256 {
+        return Err(token_error("Timestamp token does not stamp a SHA-256 hash"));
+    }
+    let hashed_message = imprint.expect(TAG_OCTET_STRING)?.to_vec();
+    // Serial number
+    info.expect(TAG_INTEGER)?;
+    let gen_time = parse_generalized_time(info.expect(TAG_GENERALIZED_TIME)?)?;
+
+    // Accuracy and ordering may come before the nonce
+    let mut nonce = None;
+    while let Some(tag) = info.peek() {
+        let contents = info.read()?;
+        if tag == TAG_INTEGER {
+            nonce = parse_uint(contents);
+            break;
+        }
+    }
+    Ok(TimestampInfo { hashed_message, gen_time, nonce })
+}
+
+/// Parses a `GeneralizedTime` such as `20240131120000.5Z`
+fn parse_generalized_time(time: &[u8]) -> Result<DateTime<Utc>> {
+    let invalid = || token_error("Timestamp token time is not a UTC GeneralizedTime");
+    let time = std::str::from_utf8(time).ok().and_then(|time| time.strip_suffix('Z')).ok_or_else(invalid)?;
+    let (seconds, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let time = NaiveDateTime::parse_from_str(seconds, "%Y%m%d%H%M%S").map_err(|_| invalid())?;
+
+    let nanos = match fraction {
+        "" => 0,
+        digits if digits.len() <= 9 && digits.bytes().all(|b| b.is_ascii_digit()) => {
+            format!("{:0<9}", digits).parse::<i64>().map_err(|_| invalid())?
+        }
+        _ => return Err(invalid()),
+    };
+    Ok(time.and_utc() + chrono::Duration::nanoseconds(nanos))
+}
+
+/// Values of the attribute `oid` in a DER set of attributes
+fn attribute<'a>(attributes: &'a [u8], oid: &[u8]) -> Result<Option<&'a [u8]>> {
+    let mut attributes = Der(attributes);
+    while attributes.peek().is_some() {
+        let mut attribute = Der(attributes.expect(TAG_SEQUENCE)?);
+        if attribute.expect(TAG_OID)? == oid {
+            return Ok(Some(attribute.expect(TAG_SET)?));
+        }
+    }
+    Ok(None)
+}
+
+fn parse_uint(contents: &[u8]) -> Option<u64> {
+    let contents = match contents {
+        [0, rest @ ..] => rest,
+        contents => contents,
+    };
+    (contents.len() <= 8).then(|| contents.iter().fold(0u64, |value, &byte| (value << 8) | byte as u64))
+}
+
+/// DER element with `tag` and `contents`
+fn der(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut element = vec![tag];
+    if contents.len() < 0x80 {
+        element.push(contents.len() as u8);
+    } else {
+        let length = contents.len().to_be_bytes();
+        let length = &length[length.iter().take_while(|&&byte| byte == 0).count()..];
+        element.push(0x80 | length.len() as u8);
+        element.extend_from_slice(length);
+    }
+    element.extend_from_slice(contents);
+    element
+}
+
+/// DER INTEGER holding `value`
+#[cfg(not(target_arch = "wasm32"))]
+fn der_uint(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let bytes = &bytes[bytes.iter().take_while(|&&byte| byte == 0).count().min(7)..];
+    let mut contents = Vec::with_capacity(9);
+    if bytes[0] & 0x80 != 0 {
+        contents.push(0);
+    }
+    contents.extend_from_slice(bytes);
+    der(TAG_INTEGER, &contents)
+}
+
+fn token_error(reason: impl Into<String>) -> ProofError {
+    ProofError::verification_error(reason)
+}
+
+/// Reader over a run of DER elements
+struct Der<'a>(&'a [u8]);
+
+impl<'a> Der<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.0.first().copied()
+    }
+
+    /// Reads the next element, returning its contents
+    fn read(&mut self) -> Result<&'a [u8]> {
+        let malformed = || token_error("Timestamp token is not valid DER");
+        let [_, first, rest @ ..] = self.0 else {
+            return Err(malformed());
+        };
+        let (length, rest) = if *first < 0x80 {
+            (*first as usize, rest)
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 || rest.len() < count {
+                return Err(malformed());
+            }
+            let length = rest[..count].iter().fold(0usize, |length, &byte| (length << 8) | byte as usize);
+            (length, &rest[count..])
+        };
+        if rest.len() < length {
+            return Err(malformed());
+        }
+        self.0 = &rest[length..];
+        Ok(&rest[..length])
+    }
+
+    /// Reads the next element, including its tag and length
+    fn next_raw(&mut self) -> Result<&'a [u8]> {
+        let before = self.0;
+        self.read()?;
+        Ok(&before[..before.len() - self.0.len()])
+    }
+
+    /// Reads the next element, which must have `tag`
+    fn expect(&mut self, tag: u8) -> Result<&'a [u8]> {
+        match self.peek() {
+            Some(found) if found == tag => self.read(),
+            found => Err(token_error(format!("Timestamp token has tag {:02x?} where {:02x} was expected", found, tag))),
+        }
+    }
+
+    /// Reads the next element if it has `tag`
+    fn next_if(&mut self, tag: u8) -> Result<Option<&'a [u8]>> {
+        if self.peek() == Some(tag) {
+            self.read().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::signing::SigningKey;
+    use ed25519_dalek::Signer;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Answers each timestamp request with a token signed by `key`, stamped
+    /// with the request's hash and nonce
+    async fn spawn_mock_tsa(key: SigningKey) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/tsa", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let key = key.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    let body = loop {
+                        let n = stream.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                        let Some(header_end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+                            continue;
+                        };
+                        let head = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+                        let length: usize = head.lines()
+                            .find_map(|line| line.strip_prefix("content-length:"))
+                            .map(|value| value.trim().parse().unwrap())
+                            .unwrap_or(0);
+                        if request.len() >= header_end + 4 + length {
+                            break request[header_end + 4..header_end + 4 + length].to_vec();
+                        }
+                    };
+
+                    let mut query = Der(Der(&body).expect(TAG_SEQUENCE).unwrap());
+                    query.expect(TAG_INTEGER).unwrap();
+                    let mut imprint = Der(query.expect(TAG_SEQUENCE).unwrap());
+                    imprint.expect(TAG_SEQUENCE).unwrap();
+                    let hash = imprint.expect(TAG_OCTET_STRING).unwrap();
+                    let nonce = parse_uint(query.expect(TAG_INTEGER).unwrap()).unwrap();
+
+                    let reply = time_stamp_response(&token(&key, hash, nonce));
+                    let head = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/timestamp-reply\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        reply.len()
+                    );
+                    stream.write_all(head.as_bytes()).await.unwrap();
+                    stream.write_all(&reply).await.unwrap();
+                });
+            }
+        });
+        url
+    }
+
+    /// `ContentInfo` with an Ed25519 `SignedData` over a `TSTInfo` for `hash`
+    fn token(key: &SigningKey, hash: &[u8], nonce: u64) -> Vec<u8> {
+        let policy = der(TAG_OID, &[0x2a, 0x03, 0x04]);
+        let tst_info = der(TAG_SEQUENCE, &[
+            der_uint(1),
+            policy,
+            message_imprint(hash),
+            der_uint(42),
+            der(TAG_GENERALIZED_TIME, b"20240131120000.25Z"),
+            der_uint(nonce),
+        ].concat());
+
+        let attributes = [
+            der(TAG_SEQUENCE, &[der(TAG_OID, OID_CONTENT_TYPE), der(TAG_SET, &der(TAG_OID, OID_TST_INFO))].concat()),
+            der(TAG_SEQUENCE, &[
+                der(TAG_OID, OID_MESSAGE_DIGEST),
+                der(TAG_SET, &der(TAG_OCTET_STRING, &Sha256::digest(&tst_info))),
+            ].concat()),
+        ].concat();
+        let signature = key.sign(&der(TAG_SET, &attributes)).to_bytes();
+
+        let sha256 = der(TAG_SEQUENCE, &der(TAG_OID, OID_SHA256));
+        let signer = der(TAG_SEQUENCE, &[
+            der_uint(3),
+            der(0x80, b"tsa-key-id"),
+            sha256.clone(),
+            der(TAG_CONTEXT_0, &attributes),
+            der(TAG_SEQUENCE, &der(TAG_OID, OID_ED25519)),
+            der(TAG_OCTET_STRING, &signature),
+        ].concat());
+        let signed_data = der(TAG_SEQUENCE, &[
+            der_uint(3),
+            der(TAG_SET, &sha256),
+            der(TAG_SEQUENCE, &[
+                der(TAG_OID, OID_TST_INFO),
+                der(TAG_CONTEXT_0, &der(TAG_OCTET_STRING, &tst_info)),
+            ].concat()),
+            der(TAG_SET, &signer),
+        ].concat());
+        der(TAG_SEQUENCE, &[der(TAG_OID, OID_SIGNED_DATA), der(TAG_CONTEXT_0, &signed_data)].concat())
+    }
+
+    fn time_stamp_response(token: &[u8]) -> Vec<u8> {
+        der(TAG_SEQUENCE, &[der(TAG_SEQUENCE, &der_uint(0)), token.to_vec()].concat())
+    }
+
+    #[tokio::test]
+    async fn test_mock_tsa_token_binds_proof_hash() {
+        use crate::{ContentSelection, ProofConfig, ProofFormat, ProofGenerator};
+        use std::io::Write;
+
+        let tsa_key = SigningKey::from_bytes(&[9; 32]);
+        let url = spawn_mock_tsa(tsa_key.clone()).await;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"Minutes of the board meeting held on 30 January").unwrap();
+        let mut generator = ProofGenerator::with_config(ProofConfig {
+            use_cache: false,
+            timestamp_authority: Some(url),
+            ..ProofConfig::default()
+        }).await.unwrap();
+        let proof = generator
+            .generate_proof(file.path(), ContentSelection::Pattern { content: b"board meeting".to_vec() })
+            .await
+            .unwrap();
+
+        let reloaded = Proof::decode(&proof.encode(ProofFormat::Binary).unwrap()).unwrap();
+        let stamped_at = reloaded.verify_timestamp(&tsa_key.verifying_key()).unwrap();
+        assert_eq!(stamped_at.to_rfc3339(), "2024-01-31T12:00:00.250+00:00");
+        assert_eq!(reloaded.timestamp.as_ref().unwrap().info().unwrap().hashed_message, proof.canonical_hash().unwrap());
+
+        let other_key = SigningKey::from_bytes(&[10; 32]);
+        assert!(reloaded.verify_timestamp(&other_key.verifying_key()).is_err());
+
+        // A token for another proof does not carry over
+        let mut other = reloaded.clone();
+        other.content_hash[0] ^= 1;
+        assert!(other.verify_timestamp(&tsa_key.verifying_key()).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+//! Trust scoring for verification results
+//!
+//! A trust score condenses a [`VerificationResult`] into a single number in
+//! `0.0..=1.0` for dashboards and triage. The score is a weighted average of
+//! the factors below, each normalized to `0.0..=1.0`:
+//!
+//! | Factor         | Value                                                               |
+//! |----------------|---------------------------------------------------------------------|
+//! | `security`     | 1.0 at 256 bits, 0.85 at 192, 0.7 at 128, 0.0 below 128 or unknown  |
+//! | `checks`       | fraction of verification checks that passed (0.0 if none ran)       |
+//! | `signature`    | 1.0 if a signature over the proof was verified                      |
+//! | `transparency` | 1.0 if the proof was found in a transparency log                    |
+//! | `freshness`    | falls linearly from 1.0 at generation to 0.0 at the freshness window |
+//!
+//! Freshness is measured at `verified_at`, so the same result always yields the
+//! same score. An invalid result always scores 0.0, with its factors still
+//! reported. For chains of results, [`TrustScore::aggregate`] takes the minimum of
+//! each factor across the chain, since a chain is only as strong as its weakest proof.
+
+use crate::types::VerificationResult;
+use serde::{Deserialize, Serialize};
+
+/// Weights of each factor in the trust score
+///
+/// Weights are relative; they do not need to sum to 1.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TrustWeights {
+    /// Weight of the proof's security level
+    pub security: f64,
+    /// Weight of the fraction of verification checks passed
+    pub checks: f64,
+    /// Weight of a verified signature
+    pub signature: f64,
+    /// Weight of a transparency log entry
+    pub transparency: f64,
+    /// Weight of proof freshness
+    pub freshness: f64,
+    /// Age in seconds at which freshness reaches zero
+    pub freshness_window_seconds: u64,
+}
+
+impl Default for TrustWeights {
+    fn default() -> Self {
+        Self {
+            security: 0.30,
+            checks: 0.30,
+            signature: 0.15,
+            transparency: 0.10,
+            freshness: 0.15,
+            freshness_window_seconds: 30 * 24 * 60 * 60, // 30 days
+        }
+    }
+}
+
+/// A single factor contributing to a trust score
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TrustFactor {
+    /// Factor name
+    pub name: String,
+    /// Normalized factor value (0.0 to 1.0)
+    pub value: f64,
+    /// Weight applied to the factor
+    pub weight: f64,
+    /// Share of the overall score contributed by this factor
+    pub contribution: f64,
+}
+
+/// Trust score with a breakdown of its contributing factors
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TrustScore {
+    /// Overall score (0.0 to 1.0)
+    pub score: f64,
+    /// Whether every verification in the result (or chain) was valid
+    pub is_valid: bool,
+    /// Contributing factors
+    pub factors: Vec<TrustFactor>,
+}
+
+impl TrustScore {
+    /// Combines the scores of a chain of verification results, taking the
+    /// minimum of each factor across the chain
+    pub fn aggregate(results: &[VerificationResult], weights: &TrustWeights) -> TrustScore {
+        let Some((first, rest)) = results.split_first() else {
+            return TrustScore::from_values(false, [0.0; 5], weights);
+        };
+
+        let mut values = factor_values(first, weights);
+        let mut is_valid = first.is_valid;
+        for result in rest {
+            for (value, other) in values.iter_mut().zip(factor_values(result, weights)) {
+                *value = value.min(other);
+            }
+            is_valid &= result.is_valid;
+        }
+
+        TrustScore::from_values(is_valid, values, weights)
+    }
+
+    /// Returns the named factor
+    pub fn factor(&self, name: &str) -> Option<&TrustFactor> {
+        self.factors.iter().find(|factor| factor.name == name)
+    }
+
+    fn from_values(is_valid: bool, values: [f64; 5], weights: &TrustWeights) -> TrustScore {
+        let factor_weights = [
+            weights.security,
+            weights.checks,
+            weights.signature,
+            weights.transparency,
+            weights.freshness,
+        ];
+        let total_weight: f64 = factor_weights.iter().sum();
+
+        let factors: Vec<TrustFactor> = FACTOR_NAMES.iter()
+            .zip(values)
+            .zip(factor_weights)
+            .map(|((name, value), weight)| TrustFactor {
+                name: name.to_string(),
+                value,
+                weight,
+                contribution: if total_weight > 0.0 { value * weight / total_weight } else { 0.0 },
+            })
+            .collect();
+
+        let score = if is_valid {
+            factors.iter().map(|factor| factor.contribution).sum::<f64>().clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        TrustScore { score, is_valid, factors }
+    }
+}
+
+impl VerificationResult {
+    /// Computes the trust score of this result using the default weights
+    pub fn trust_score(&self) -> TrustScore {
+        self.trust_score_with(&TrustWeights::default())
+    }
+
+    /// Computes the trust score of this result using custom weights
+    pub fn trust_score_with(&self, weights: &TrustWeights) -> TrustScore {
+        TrustScore::from_values(self.is_valid, factor_values(self, weights), weights)
+    }
+}
+
+const FACTOR_NAMES: [&str; 5] = ["security", "checks", "signature", "transparency", "freshness"];
+
+fn factor_values(result: &VerificationResult, weights: &TrustWeights) -> [f64; 5] {
+    let security = match result.security_level {
+        Some(level) if level >= 256 => 1.0,
+        Some(level) if level >= 192 => 0.85,
+        Some(level) if level >= 128 => 0.7,
+        _ => 0.0,
+    };
+
+    let checks = if result.checks_run > 0 {
+        result.checks_passed.min(result.checks_run) as f64 / result.checks_run as f64
+    } else {
+        0.0
+    };
+
+    let freshness = match result.proof_created_at {
+        Some(created_at) if weights.freshness_window_seconds > 0 => {
+            let age = (result.verified_at - created_at).num_seconds().max(0) as f64;
+            (1.0 - age / weights.freshness_window_seconds as f64).max(0.0)
+        }
+        _ => 0.0,
+    };
+
+    [
+        security,
+        checks,
+        if result.signature_verified { 1.0 } else { 0.0 },
+        if result.transparency_verified { 1.0 } else { 0.0 },
+        freshness,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{VerificationMethod, VerifierInfo};
+    use chrono::{Duration, Utc};
+    use std::collections::HashMap;
+
+    fn result(security_level: u32, checks_passed: u32, checks_run: u32, age_days: i64) -> VerificationResult {
+        let verified_at = Utc::now();
+        VerificationResult {
+            is_valid: true,
+            failure: None,
+            verified_at,
+            verification_time_ms: 5,
+            verifier_info: VerifierInfo {
+                version: "test".to_string(),
+                method: VerificationMethod::Local,
+                environment: "test".to_string(),
+            },
+            warnings: Vec::new(),
+            verification_steps: Vec::new(),
+            checks_passed,
+            checks_run,
+            security_level: Some(security_level),
+            proof_created_at: Some(verified_at - Duration::days(age_days)),
+            signature_verified: false,
+            transparency_verified: false,
+            from_cache: false,
+            timings: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_high_assurance_score() {
+        let mut high = result(256, 5, 5, 0);
+        high.signature_verified = true;
+        high.transparency_verified = true;
+
+        let score = high.trust_score();
+        assert!((score.score - 1.0).abs() < 1e-9);
+        assert_eq!(score.factors.len(), 5);
+    }
+
+    #[test]
+    fn test_low_assurance_score() {
+        // 0.30 * 0.7 (128-bit) + 0.30 * 0.6 (3 of 5 checks), unsigned and 30 days old
+        let low = result(128, 3, 5, 30);
+        let score = low.trust_score();
+        assert!((score.score - 0.39).abs() < 1e-9);
+        assert_eq!(score.factor("freshness").unwrap().value, 0.0);
+
+        let mut invalid = low.clone();
+        invalid.is_valid = false;
+        assert_eq!(invalid.trust_score().score, 0.0);
+    }
+
+    #[test]
+    fn test_chain_uses_weakest_factors() {
+        let mut strong = result(256, 5, 5, 0);
+        strong.signature_verified = true;
+        let weak = result(128, 4, 4, 0);
+
+        let weights = TrustWeights { transparency: 0.0, ..TrustWeights::default() };
+        let chain = TrustScore::aggregate(&[strong, weak.clone()], &weights);
+        assert_eq!(chain.factor("security").unwrap().value, 0.7);
+        assert_eq!(chain.factor("signature").unwrap().value, 0.0);
+        assert_eq!(chain.score, weak.trust_score_with(&weights).score);
+    }
+}
@@ -6,14 +6,106 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::ops::{Add, AddAssign};
+use std::time::Duration;
 use chrono::{DateTime, Utc};
 
 // Re-export guest types for convenience
 pub use crate::guest_types::{
-    ProofInput, ProofOutput, IpfsBlock, BlockLink, 
-    ContentSelection, ProofMetadata as GuestProofMetadata
+    ProofInput, ProofOutput, IpfsBlock, BlockLink, Materialization, Coverage,
+    ContentSelection, HashAlgorithm, ProofMetadata as GuestProofMetadata
 };
 
+/// A size in bytes.
+///
+/// Serializes as a plain integer, so the JSON form matches the old `u64` fields.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct Bytes(pub u64);
+
+impl Bytes {
+    /// Returns the raw number of bytes
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Creates a size from an in-memory length
+    pub fn from_len(len: usize) -> Self {
+        Bytes(len as u64)
+    }
+}
+
+impl From<u64> for Bytes {
+    fn from(bytes: u64) -> Self {
+        Bytes(bytes)
+    }
+}
+
+impl From<Bytes> for u64 {
+    fn from(bytes: Bytes) -> Self {
+        bytes.0
+    }
+}
+
+impl Add for Bytes {
+    type Output = Bytes;
+
+    fn add(self, other: Bytes) -> Bytes {
+        Bytes(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Bytes {
+    fn add_assign(&mut self, other: Bytes) {
+        self.0 += other.0;
+    }
+}
+
+/// A duration in milliseconds.
+///
+/// Serializes as a plain integer, so the JSON form matches the old `u64` fields.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct Millis(pub u64);
+
+impl Millis {
+    /// Returns the raw number of milliseconds
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Converts to a `Duration`
+    pub fn as_duration(self) -> Duration {
+        Duration::from_millis(self.0)
+    }
+}
+
+impl From<Duration> for Millis {
+    fn from(duration: Duration) -> Self {
+        Millis(duration.as_millis() as u64)
+    }
+}
+
+impl From<u64> for Millis {
+    fn from(ms: u64) -> Self {
+        Millis(ms)
+    }
+}
+
+impl From<Millis> for u64 {
+    fn from(ms: Millis) -> Self {
+        ms.0
+    }
+}
+
+impl Add for Millis {
+    type Output = Millis;
+
+    fn add(self, other: Millis) -> Millis {
+        Millis(self.0 + other.0)
+    }
+}
+
 /// A complete zero-knowledge proof for IPFS content verification
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Proof {
@@ -33,32 +125,276 @@ pub struct Proof {
     pub created_at: DateTime<Utc>,
     /// Version of the proof format
     pub version: String,
+    /// Kind of claim the proof makes, which selects the guest it is verified against
+    #[serde(default, skip_serializing_if = "crate::proof_types::ProofType::is_default")]
+    pub proof_type: crate::proof_types::ProofType,
+    /// Image ID of the guest that produced the receipt; not recorded by older releases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_id: Option<[u32; 8]>,
+    /// Ed25519 signature identifying who produced the proof, if signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ProofSignature>,
+    /// RFC 3161 token from a timestamp authority over the canonical hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<crate::timestamp::TimestampToken>,
+}
+
+/// Ed25519 signature over a proof
+///
+/// The signature covers [`Proof::signing_bytes`]: every field of the proof
+/// except the signature and timestamp token, so it cannot be moved onto another
+/// proof.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProofSignature {
+    /// The 64-byte Ed25519 signature
+    #[serde(with = "serde_bytes")]
+    pub signature: Vec<u8>,
+    /// Public key of the signer
+    pub signer_public_key: [u8; 32],
 }
 
 /// Zero-knowledge proof data containing the cryptographic proof
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ZkProofData {
     /// The receipt from Risc0 containing the proof
+    #[serde(with = "serde_bytes")]
     pub receipt: Vec<u8>,
     /// Public inputs to the proof
+    #[serde(with = "serde_bytes")]
     pub public_inputs: Vec<u8>,
     /// Proof format version
     pub format_version: String,
-    /// Compression algorithm used (if any)
+    /// Compression algorithm the receipt is compressed with (if any)
     pub compression: Option<CompressionType>,
 }
 
+impl ZkProofData {
+    /// Returns the serialized receipt, decompressed with the recorded codec
+    ///
+    /// Proofs written before receipts were compressed record a codec but hold
+    /// the receipt as is. A bincode receipt starts with its variant index,
+    /// never with a gzip or zstd frame magic, so those are passed through.
+    pub fn receipt_bytes(&self) -> crate::error::Result<std::borrow::Cow<'_, [u8]>> {
+        match &self.compression {
+            Some(codec) if codec.is_framed(&self.receipt) => {
+                codec.decompress(&self.receipt).map(std::borrow::Cow::Owned)
+            }
+            _ => Ok(std::borrow::Cow::Borrowed(&self.receipt)),
+        }
+    }
+}
+
+/// Largest size a compressed receipt may decompress to, so that a small
+/// crafted receipt cannot exhaust memory
+pub const MAX_RECEIPT_BYTES: usize = 256 * 1024 * 1024;
+
 /// Supported compression types for proof data
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum CompressionType {
     /// No compression
     None,
     /// Gzip compression
     Gzip,
-    /// Zstd compression
+    /// Zstd compression (not available on wasm32)
     Zstd,
 }
 
+/// First bytes of a gzip member
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// First bytes of a zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+impl CompressionType {
+    /// Compresses `data` with this codec
+    pub fn compress(&self, data: &[u8]) -> crate::error::Result<Vec<u8>> {
+        use std::io::Write;
+
+        let error = |e: std::io::Error| crate::error::ProofError::serialization_error(
+            format!("Failed to compress with {:?}", self),
+            Some(Box::new(e)),
+        );
+        match self {
+            CompressionType::None => Ok(data.to_vec()),
+            CompressionType::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data).map_err(error)?;
+                encoder.finish().map_err(error)
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            CompressionType::Zstd => zstd::stream::encode_all(data, 0).map_err(error),
+            #[cfg(target_arch = "wasm32")]
+            CompressionType::Zstd => Err(Self::zstd_unavailable()),
+        }
+    }
+
+    /// Reverses [`compress`](Self::compress), failing if the output would
+    /// exceed [`MAX_RECEIPT_BYTES`]
+    pub fn decompress(&self, data: &[u8]) -> crate::error::Result<Vec<u8>> {
+        self.decompress_capped(data, MAX_RECEIPT_BYTES)
+    }
+
+    fn decompress_capped(&self, data: &[u8], max_bytes: usize) -> crate::error::Result<Vec<u8>> {
+        use std::io::Read;
+
+        let error = |e: std::io::Error| crate::error::ProofError::serialization_error(
+            format!("Failed to decompress {:?} data", self),
+            Some(Box::new(e)),
+        );
+        // One byte past the cap tells a full-size output from an oversized one
+        let limit = max_bytes as u64 + 1;
+        let mut decompressed = Vec::new();
+        let read = match self {
+            CompressionType::None => return Ok(data.to_vec()),
+            CompressionType::Gzip => {
+                flate2::read::GzDecoder::new(data).take(limit).read_to_end(&mut decompressed)
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            CompressionType::Zstd => zstd::stream::read::Decoder::new(data)
+                .and_then(|decoder| decoder.take(limit).read_to_end(&mut decompressed)),
+            #[cfg(target_arch = "wasm32")]
+            CompressionType::Zstd => return Err(Self::zstd_unavailable()),
+        };
+        read.map_err(error)?;
+
+        if decompressed.len() > max_bytes {
+            return Err(crate::error::ProofError::resource_limit_error(
+                "receipt_size",
+                format!("{:?} data decompresses to more than {} bytes", self, max_bytes),
+            ));
+        }
+        Ok(decompressed)
+    }
+
+    /// Whether `data` starts like this codec's output
+    fn is_framed(&self, data: &[u8]) -> bool {
+        match self {
+            CompressionType::None => false,
+            CompressionType::Gzip => data.starts_with(&GZIP_MAGIC),
+            CompressionType::Zstd => data.starts_with(&ZSTD_MAGIC),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn zstd_unavailable() -> crate::error::ProofError {
+        crate::error::ProofError::configuration_error("Zstd compression is not available on wasm32")
+    }
+}
+
+/// Strategy used to split file content into IPFS blocks
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ChunkingStrategy {
+    /// Fixed-size blocks of the given number of bytes
+    FixedSize(usize),
+    /// Content-defined blocks cut where a Rabin fingerprint of the trailing window
+    /// hits a boundary, bounded by `min` and `max` and averaging about `avg` bytes
+    ///
+    /// The fingerprint is this crate's own rolling hash, not the chunker of
+    /// go-ipfs/Kubo, so blocks and CIDs differ from those of
+    /// `ipfs add --chunker=rabin` with the same bounds.
+    Rabin { min: usize, avg: usize, max: usize },
+    /// Fixed-size blocks sized for each file from its length (see
+    /// [`ChunkingStrategy::adaptive_block_size`]); the chosen size is recorded
+    /// in `FileInfo::chunk_size`
+    Adaptive,
+}
+
+/// Smallest block size adaptive chunking picks
+pub const ADAPTIVE_MIN_BLOCK_SIZE: usize = 16 * 1024;
+
+/// Largest block size adaptive chunking picks
+pub const ADAPTIVE_MAX_BLOCK_SIZE: usize = 1024 * 1024;
+
+/// Number of blocks adaptive chunking aims to split a file into
+const ADAPTIVE_TARGET_BLOCKS: u64 = 16;
+
+impl Default for ChunkingStrategy {
+    fn default() -> Self {
+        ChunkingStrategy::FixedSize(256 * 1024)
+    }
+}
+
+impl ChunkingStrategy {
+    /// Checks that the block size bounds are usable
+    pub fn is_valid(&self) -> bool {
+        match self {
+            ChunkingStrategy::FixedSize(size) => *size > 0,
+            ChunkingStrategy::Rabin { min, avg, max } => *min > 0 && min <= avg && avg <= max,
+            ChunkingStrategy::Adaptive => true,
+        }
+    }
+
+    /// Expected average block size in bytes; for adaptive chunking, that of
+    /// the smallest files
+    pub fn average_block_size(&self) -> usize {
+        match self {
+            ChunkingStrategy::FixedSize(size) => *size,
+            ChunkingStrategy::Rabin { avg, .. } => *avg,
+            ChunkingStrategy::Adaptive => ADAPTIVE_MIN_BLOCK_SIZE,
+        }
+    }
+
+    /// Block size adaptive chunking picks for a file of `file_size` bytes
+    ///
+    /// Each block costs the guest a CID and a link in the root block on top of
+    /// hashing its bytes, so fewer, larger blocks prove in fewer cycles. Blocks
+    /// grow with the file, aiming for about 16 of them so hashing still spreads
+    /// across threads and partial proofs stay small. The size is a power of two
+    /// from [`ADAPTIVE_MIN_BLOCK_SIZE`] to [`ADAPTIVE_MAX_BLOCK_SIZE`], and
+    /// depends on nothing but the file size, so every machine cuts a file into
+    /// the same blocks.
+    pub fn adaptive_block_size(file_size: u64) -> usize {
+        let target = (file_size / ADAPTIVE_TARGET_BLOCKS).max(1).next_power_of_two();
+        usize::try_from(target).unwrap_or(usize::MAX).clamp(ADAPTIVE_MIN_BLOCK_SIZE, ADAPTIVE_MAX_BLOCK_SIZE)
+    }
+}
+
+/// Kind of Risc0 receipt produced by the prover
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ReceiptKind {
+    /// Composite receipt made of per-segment STARKs (largest, fastest to produce)
+    #[default]
+    Composite,
+    /// Single STARK produced by recursively aggregating the segments
+    Succinct,
+    /// Groth16 SNARK wrapping the succinct receipt (smallest, suitable on-chain)
+    Groth16,
+}
+
+impl ReceiptKind {
+    /// Base proof format version that receipt kinds are appended to
+    const BASE_FORMAT_VERSION: &'static str = "1.0";
+
+    /// Returns the `ZkProofData.format_version` string for this receipt kind.
+    ///
+    /// Composite receipts keep the plain base version so older proofs still parse.
+    pub fn format_version(&self) -> String {
+        match self {
+            ReceiptKind::Composite => Self::BASE_FORMAT_VERSION.to_string(),
+            ReceiptKind::Succinct => format!("{}+succinct", Self::BASE_FORMAT_VERSION),
+            ReceiptKind::Groth16 => format!("{}+groth16", Self::BASE_FORMAT_VERSION),
+        }
+    }
+
+    /// Parses the receipt kind stored in a `format_version` string
+    pub fn from_format_version(format_version: &str) -> Option<Self> {
+        match format_version.split_once('+') {
+            None => Some(ReceiptKind::Composite),
+            Some((_, "composite")) => Some(ReceiptKind::Composite),
+            Some((_, "succinct")) => Some(ReceiptKind::Succinct),
+            Some((_, "groth16")) => Some(ReceiptKind::Groth16),
+            Some(_) => None,
+        }
+    }
+}
+
+impl ZkProofData {
+    /// Returns the receipt kind recorded in the format version
+    pub fn receipt_kind(&self) -> Option<ReceiptKind> {
+        ReceiptKind::from_format_version(&self.format_version)
+    }
+}
+
 /// Extended metadata about proof generation and verification
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ProofMetadata {
@@ -74,6 +410,53 @@ pub struct ProofMetadata {
     pub environment: GenerationEnvironment,
     /// Additional custom metadata
     pub custom: HashMap<String, serde_json::Value>,
+    /// Time after which the proof should no longer be accepted
+    #[serde(default)]
+    pub valid_until: Option<DateTime<Utc>>,
+    /// IDs of the proofs this one renews, oldest first
+    #[serde(default)]
+    pub renewed_from: Vec<String>,
+    /// The proof this one extends, for proofs of appended content
+    #[serde(default)]
+    pub extends: Option<ProofExtension>,
+    /// Whether the proof is signed over its canonical form
+    /// (see [`Proof::canonical`](crate::canonical))
+    #[serde(default)]
+    pub reproducible: bool,
+    /// Whether the content hash is a commitment to the content hash, opened
+    /// by a [`ContentOpening`](crate::commitment::ContentOpening)
+    #[serde(default)]
+    pub content_committed: bool,
+}
+
+/// Link from a proof of appended content to the proof of the content before it
+///
+/// The receipt of an extended proof covers only the appended blocks. The
+/// proof's root hash chains the prior root hash with the appended blocks' root
+/// hash, and its content hash chains the prior content hash with the hash of the
+/// appended bytes, so the extended proof commits to both.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProofExtension {
+    /// Proof of the file as it was before the append
+    pub prior: Box<Proof>,
+    /// Root hash of the appended blocks, as committed by the receipt
+    pub suffix_root_hash: [u8; 32],
+    /// Hash of the appended bytes, as committed by the receipt
+    pub suffix_content_hash: [u8; 32],
+    /// Length of the content proven by `prior`, which leads the claimed content
+    pub prior_content_len: u64,
+}
+
+impl ProofExtension {
+    /// Chains a prior hash with the hash of the appended part
+    pub fn chain_hash(prior: &[u8; 32], suffix: &[u8; 32]) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(prior);
+        hasher.update(suffix);
+        hasher.finalize().into()
+    }
 }
 
 /// Information about the file that was proven
@@ -82,7 +465,7 @@ pub struct FileInfo {
     /// Original filename (if available)
     pub filename: Option<String>,
     /// File size in bytes
-    pub size: u64,
+    pub size: Bytes,
     /// MIME type (if detected)
     pub mime_type: Option<String>,
     /// File hash (SHA-256)
@@ -92,26 +475,48 @@ pub struct FileInfo {
     /// Number of IPFS blocks
     pub block_count: u32,
     /// Average block size
-    pub avg_block_size: u64,
+    pub avg_block_size: Bytes,
+    /// Codec the stored file was compressed with, if it was decompressed
+    /// before chunking; the size, hashes and CID are of the decompressed content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_compression: Option<CompressionType>,
+    /// Block size adaptive chunking chose for the file (`None` under a fixed
+    /// or content-defined strategy)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_size: Option<Bytes>,
 }
 
 /// Performance metrics for proof generation
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct PerformanceMetrics {
     /// Total time taken for proof generation (milliseconds)
-    pub generation_time_ms: u64,
+    pub generation_time_ms: Millis,
     /// Time taken for file processing (milliseconds)
-    pub file_processing_time_ms: u64,
+    pub file_processing_time_ms: Millis,
     /// Time taken for ZK proof generation (milliseconds)
-    pub zk_generation_time_ms: u64,
+    pub zk_generation_time_ms: Millis,
     /// Peak memory usage during generation (bytes)
-    pub peak_memory_bytes: u64,
-    /// Number of CPU cycles used in the ZK-VM
+    pub peak_memory_bytes: Bytes,
+    /// Cycles executed in the ZK-VM, before padding each segment to a power of two
     pub zk_cycles: u64,
     /// Proof size in bytes
-    pub proof_size_bytes: u64,
-    /// Compression ratio (if compression was used)
+    pub proof_size_bytes: Bytes,
+    /// Compressed receipt size as a fraction of the serialized receipt size
+    /// (if the receipt is compressed)
     pub compression_ratio: Option<f64>,
+    /// Cycles used by each segment of the guest execution, in execution order
+    /// (empty if the prover reported only the total)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segment_cycles: Vec<SegmentCycles>,
+}
+
+/// Cycles used by one segment of a ZK-VM execution
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SegmentCycles {
+    /// Log2 of the segment's padded size in cycles
+    pub po2: u32,
+    /// Cycles executed in the segment
+    pub cycles: u64,
 }
 
 /// Security parameters used for proof generation
@@ -121,6 +526,9 @@ pub struct SecurityParameters {
     pub security_level: u32,
     /// Hash function used for content hashing
     pub hash_function: String,
+    /// Hash function the content hash was computed with
+    #[serde(default)]
+    pub hash_algorithm: HashAlgorithm,
     /// ZK proof system used
     pub proof_system: String,
     /// Risc0 version used
@@ -168,11 +576,65 @@ pub enum ProverType {
     Custom(String),
 }
 
+/// Machine-readable reason a proof failed verification
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum VerificationFailure {
+    /// The proof is malformed (missing version or receipt, invalid selection or metadata)
+    MalformedProof,
+    /// The receipt could not be decoded or does not verify against the guest image
+    CryptoInvalid,
+    /// The claimed content, or the proof's content hash, does not match the receipt
+    ContentHashMismatch,
+    /// The proof's root hash does not match the receipt
+    RootHashMismatch,
+    /// The proof is past its validity window or the verifier's maximum age
+    Expired,
+    /// The proof's security level is below the required minimum
+    SecurityLevelTooLow,
+    /// The named custom verification rule failed
+    CustomRule(String),
+    /// An extended proof's hashes do not chain from the proof it extends
+    ChainLinkMismatch,
+    /// The proof's signature does not verify for its recorded signer
+    SignatureInvalid,
+    /// The proof's content selection is not the one committed in the receipt
+    SelectionMismatch,
+    /// The proof was generated by a guest image the verifier does not trust
+    UnknownImageId,
+    /// A block on the proof's inclusion path, named by CID, was not supplied
+    MissingBlock(String),
+    /// The named verification step did not finish within the step timeout
+    StepTimeout(String),
+}
+
+impl fmt::Display for VerificationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationFailure::MalformedProof => write!(f, "MalformedProof"),
+            VerificationFailure::CryptoInvalid => write!(f, "CryptoInvalid"),
+            VerificationFailure::ContentHashMismatch => write!(f, "ContentHashMismatch"),
+            VerificationFailure::RootHashMismatch => write!(f, "RootHashMismatch"),
+            VerificationFailure::Expired => write!(f, "Expired"),
+            VerificationFailure::SecurityLevelTooLow => write!(f, "SecurityLevelTooLow"),
+            VerificationFailure::CustomRule(name) => write!(f, "CustomRule({})", name),
+            VerificationFailure::ChainLinkMismatch => write!(f, "ChainLinkMismatch"),
+            VerificationFailure::SignatureInvalid => write!(f, "SignatureInvalid"),
+            VerificationFailure::SelectionMismatch => write!(f, "SelectionMismatch"),
+            VerificationFailure::UnknownImageId => write!(f, "UnknownImageId"),
+            VerificationFailure::MissingBlock(cid) => write!(f, "MissingBlock({})", cid),
+            VerificationFailure::StepTimeout(step) => write!(f, "StepTimeout({})", step),
+        }
+    }
+}
+
 /// Verification result containing detailed information
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct VerificationResult {
     /// Whether the proof is valid
     pub is_valid: bool,
+    /// Why verification failed (`None` when the proof is valid)
+    #[serde(default)]
+    pub failure: Option<VerificationFailure>,
     /// Verification timestamp
     pub verified_at: DateTime<Utc>,
     /// Time taken for verification (milliseconds)
@@ -183,6 +645,31 @@ pub struct VerificationResult {
     pub warnings: Vec<String>,
     /// Detailed verification steps (for debugging)
     pub verification_steps: Vec<VerificationStep>,
+    /// Time taken by each verification step (milliseconds), kept even when the
+    /// detailed steps are not
+    #[serde(default)]
+    pub timings: HashMap<String, u64>,
+    /// Number of verification checks that passed
+    #[serde(default)]
+    pub checks_passed: u32,
+    /// Number of verification checks that ran
+    #[serde(default)]
+    pub checks_run: u32,
+    /// Security level of the verified proof in bits
+    #[serde(default)]
+    pub security_level: Option<u32>,
+    /// When the verified proof was generated
+    #[serde(default)]
+    pub proof_created_at: Option<DateTime<Utc>>,
+    /// Whether a signature over the proof was verified
+    #[serde(default)]
+    pub signature_verified: bool,
+    /// Whether the proof was found in a transparency log
+    #[serde(default)]
+    pub transparency_verified: bool,
+    /// Whether the result was served from the verifier's result cache
+    #[serde(default)]
+    pub from_cache: bool,
 }
 
 /// Information about the verifier
@@ -220,6 +707,79 @@ pub struct VerificationStep {
     pub details: Option<String>,
 }
 
+/// Result of checking a content selection against a file without proving it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PreflightReport {
+    /// Whether the selection can be proven against the file
+    pub satisfiable: bool,
+    /// Why the selection cannot be proven, one entry per failing selection
+    pub problems: Vec<String>,
+    /// Size of the file
+    pub file_size: Bytes,
+    /// Number of IPFS blocks the file is split into
+    pub block_count: u32,
+    /// Where each satisfiable selection resolves
+    pub spans: Vec<SelectionSpan>,
+    /// Total bytes bound by the selection
+    pub selected_bytes: Bytes,
+    /// Estimated number of guest cycles
+    pub estimated_cycles: u64,
+    /// Estimated wall-clock proving time on this machine
+    pub estimated_proving_time_ms: Millis,
+}
+
+impl PreflightReport {
+    /// Returns a content selection error listing the problems if the selection cannot be proven
+    pub fn ensure_satisfiable(&self) -> crate::error::Result<()> {
+        if self.satisfiable {
+            Ok(())
+        } else {
+            Err(crate::error::ProofError::content_selection_error(format!(
+                "Selection cannot be proven: {}",
+                self.problems.join("; ")
+            )))
+        }
+    }
+}
+
+/// Resolved location of a single selection
+///
+/// Offsets are into the concatenated block data, the same offsets byte range
+/// selections use.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SelectionSpan {
+    /// Description of the selection
+    pub selection: String,
+    /// Start of the bytes the proof binds
+    pub start: u64,
+    /// End (exclusive) of the bytes the proof binds
+    pub end: u64,
+    /// Offsets of every match of a pattern or regex, up to a limit; the proof binds the first
+    pub matched_offsets: Vec<u64>,
+}
+
+/// Cost estimate for a proof, measured by executing the guest without proving
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProofEstimate {
+    /// Cycles of each segment the guest execution was split into
+    pub segment_cycles: Vec<SegmentCycles>,
+    /// Total guest cycles executed, before padding; the same measure as
+    /// [`PerformanceMetrics::zk_cycles`]
+    pub total_cycles: u64,
+    /// Projected size of the receipt for the configured receipt kind
+    pub estimated_proof_size: Bytes,
+    /// Projected peak prover memory; segments are proven one at a time
+    pub estimated_peak_memory: Bytes,
+    /// Projected proving time with the recommended backend
+    pub estimated_proving_time_ms: Millis,
+    /// Hardware acceleration detected on this machine
+    pub detected_acceleration: HardwareAcceleration,
+    /// Prover the estimate recommends
+    pub recommended_prover: ProverType,
+    /// Why the recommended prover was chosen
+    pub recommendation: String,
+}
+
 /// Configuration for proof generation
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ProofConfig {
@@ -230,7 +790,7 @@ pub struct ProofConfig {
     /// Prover type preference
     pub prover_type: ProverType,
     /// Maximum memory usage (bytes)
-    pub max_memory_bytes: Option<u64>,
+    pub max_memory_bytes: Option<Bytes>,
     /// Timeout for proof generation (seconds)
     pub timeout_seconds: Option<u64>,
     /// Whether to compress the proof
@@ -241,6 +801,82 @@ pub struct ProofConfig {
     pub include_performance_metrics: bool,
     /// Whether to include detailed verification steps
     pub include_verification_steps: bool,
+    /// Patterns longer than this many bytes are sent to the guest as a hash and
+    /// length instead of the full content (`None` disables hashing)
+    #[serde(default)]
+    pub pattern_hash_threshold: Option<usize>,
+    /// Whether to reuse cached proofs for unchanged files and selections
+    #[serde(default = "default_use_cache")]
+    pub use_cache: bool,
+    /// Kind of receipt to produce
+    #[serde(default)]
+    pub receipt_kind: ReceiptKind,
+    /// How file content is split into IPFS blocks
+    #[serde(default)]
+    pub chunking_strategy: ChunkingStrategy,
+    /// How long generated proofs stay valid (seconds, `None` for no expiry)
+    #[serde(default)]
+    pub validity_seconds: Option<u64>,
+    /// Hash function for the content hash
+    #[serde(default)]
+    pub hash_algorithm: HashAlgorithm,
+    /// Files at least this large are memory-mapped instead of read into memory
+    /// (`None` always reads the whole file)
+    #[serde(default = "default_mmap_threshold")]
+    pub mmap_threshold: Option<Bytes>,
+    /// Whether to sign proofs over their canonical form, which leaves out the
+    /// metadata that differs between machines and runs
+    #[serde(default)]
+    pub reproducible: bool,
+    /// Kind of proof to generate, proven with the scheme registered for it
+    #[serde(default, skip_serializing_if = "crate::proof_types::ProofType::is_default")]
+    pub proof_type: crate::proof_types::ProofType,
+    /// Largest file that will be proven (`None` for no limit)
+    #[serde(default = "default_max_file_size_bytes")]
+    pub max_file_size_bytes: Option<Bytes>,
+    /// Threads hashing IPFS blocks (`None` for one per core)
+    #[serde(default)]
+    pub worker_threads: Option<usize>,
+    /// Codec to decompress files with as they are read, so proofs bind the
+    /// decompressed content (`None` proves the stored bytes)
+    #[serde(default)]
+    pub decompress_input: Option<CompressionType>,
+    /// Most selections, counting each range of a multi-range, one proof may
+    /// make (`None` for no limit)
+    #[serde(default = "default_max_selections")]
+    pub max_selections: Option<usize>,
+    /// Most bytes one proof may select in total (`None` for no limit)
+    #[serde(default = "default_max_total_selected_bytes")]
+    pub max_total_selected_bytes: Option<Bytes>,
+    /// URL of an RFC 3161 timestamp authority to stamp each proof with
+    /// (`None` leaves proofs unstamped)
+    #[serde(default)]
+    pub timestamp_authority: Option<String>,
+    /// Whether to persist the guest input to the cache before proving, so
+    /// [`ProofGenerator::resume_or_generate`](crate::ProofGenerator::resume_or_generate)
+    /// can resume an interrupted run without processing the file again
+    #[serde(default)]
+    pub checkpoint: bool,
+}
+
+fn default_use_cache() -> bool {
+    true
+}
+
+fn default_mmap_threshold() -> Option<Bytes> {
+    Some(Bytes(64 * 1024 * 1024))
+}
+
+fn default_max_file_size_bytes() -> Option<Bytes> {
+    Some(Bytes(50 * 1024 * 1024 * 1024))
+}
+
+fn default_max_selections() -> Option<usize> {
+    Some(1024)
+}
+
+fn default_max_total_selected_bytes() -> Option<Bytes> {
+    Some(Bytes(1024 * 1024 * 1024))
 }
 
 impl Default for ProofConfig {
@@ -255,6 +891,22 @@ impl Default for ProofConfig {
             custom_metadata: HashMap::new(),
             include_performance_metrics: true,
             include_verification_steps: false,
+            pattern_hash_threshold: None,
+            use_cache: true,
+            receipt_kind: ReceiptKind::Composite,
+            chunking_strategy: ChunkingStrategy::default(),
+            validity_seconds: None,
+            hash_algorithm: HashAlgorithm::Sha256,
+            mmap_threshold: default_mmap_threshold(),
+            reproducible: false,
+            proof_type: crate::proof_types::ProofType::ContentExistence,
+            max_file_size_bytes: default_max_file_size_bytes(),
+            worker_threads: None,
+            decompress_input: None,
+            max_selections: default_max_selections(),
+            max_total_selected_bytes: default_max_total_selected_bytes(),
+            timestamp_authority: None,
+            checkpoint: false,
         }
     }
 }
@@ -280,6 +932,247 @@ pub struct ProofStatistics {
     pub common_file_types: HashMap<String, u64>,
 }
 
+/// Deepest nesting of `Multiple` selections accepted for proving
+pub const MAX_SELECTION_DEPTH: usize = 16;
+
+/// Magic bytes opening a binary-encoded proof
+pub const BINARY_PROOF_MAGIC: [u8; 4] = *b"ZKPF";
+
+/// Version of the binary proof envelope
+pub const BINARY_PROOF_VERSION: u16 = 1;
+
+/// Encoding of a stored proof
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProofFormat {
+    /// Pretty-printed JSON
+    #[default]
+    Json,
+    /// Binary envelope: magic, version, then the proof as CBOR
+    Binary,
+}
+
+impl ProofFormat {
+    /// Detects the format of encoded proof bytes from the binary magic header
+    pub fn detect(data: &[u8]) -> ProofFormat {
+        if data.starts_with(&BINARY_PROOF_MAGIC) {
+            ProofFormat::Binary
+        } else {
+            ProofFormat::Json
+        }
+    }
+
+    /// File extension for proofs stored in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ProofFormat::Json => "json",
+            ProofFormat::Binary => "bin",
+        }
+    }
+}
+
+impl std::str::FromStr for ProofFormat {
+    type Err = crate::error::ProofError;
+
+    fn from_str(s: &str) -> crate::error::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(ProofFormat::Json),
+            "bin" | "binary" => Ok(ProofFormat::Binary),
+            _ => Err(crate::error::ProofError::invalid_input_error(
+                "format",
+                format!("Unknown proof format '{}', expected json or bin", s)
+            )),
+        }
+    }
+}
+
+impl Proof {
+    /// Encodes the proof in the binary envelope
+    ///
+    /// The envelope is [`BINARY_PROOF_MAGIC`], [`BINARY_PROOF_VERSION`] as two
+    /// little-endian bytes, then the proof as CBOR. Receipts are stored as raw
+    /// bytes, so the result is a fraction of the size of the JSON form.
+    pub fn to_bytes(&self) -> crate::error::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(self.zk_proof.receipt.len() + 4096);
+        bytes.extend_from_slice(&BINARY_PROOF_MAGIC);
+        bytes.extend_from_slice(&BINARY_PROOF_VERSION.to_le_bytes());
+        ciborium::into_writer(self, &mut bytes)
+            .map_err(|e| crate::error::ProofError::serialization_error(
+                "Failed to encode binary proof",
+                Some(Box::new(e))
+            ))?;
+        Ok(bytes)
+    }
+
+    /// Decodes a proof from the binary envelope written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(data: &[u8]) -> crate::error::Result<Proof> {
+        let payload = data.strip_prefix(&BINARY_PROOF_MAGIC)
+            .ok_or_else(|| crate::error::ProofError::serialization_error(
+                "Not a binary proof: missing magic header",
+                None
+            ))?;
+        let (version, payload) = match payload {
+            [low, high, rest @ ..] => (u16::from_le_bytes([*low, *high]), rest),
+            _ => return Err(crate::error::ProofError::serialization_error(
+                "Binary proof is truncated",
+                None
+            )),
+        };
+        if version != BINARY_PROOF_VERSION {
+            return Err(crate::error::ProofError::serialization_error(
+                format!("Unsupported binary proof version {}", version),
+                None
+            ));
+        }
+
+        ciborium::from_reader(payload)
+            .map_err(|e| crate::error::ProofError::serialization_error(
+                "Failed to decode binary proof",
+                Some(Box::new(e))
+            ))
+    }
+
+    /// Encodes the proof in the given format
+    pub fn encode(&self, format: ProofFormat) -> crate::error::Result<Vec<u8>> {
+        match format {
+            ProofFormat::Json => Ok(serde_json::to_vec_pretty(self)?),
+            ProofFormat::Binary => self.to_bytes(),
+        }
+    }
+
+    /// Decodes a proof stored as either JSON or the binary envelope
+    pub fn decode(data: &[u8]) -> crate::error::Result<Proof> {
+        match ProofFormat::detect(data) {
+            ProofFormat::Binary => Proof::from_bytes(data),
+            ProofFormat::Json => serde_json::from_slice(data)
+                .map_err(|e| crate::error::ProofError::serialization_error(
+                    "Failed to parse proof JSON",
+                    Some(Box::new(e))
+                )),
+        }
+    }
+
+    /// Returns the canonical fingerprint of this proof as a hex string.
+    ///
+    /// The fingerprint commits to the root hash, content hash and receipt, so two
+    /// proofs share a fingerprint only if they carry the same cryptographic claim.
+    pub fn fingerprint(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(self.root_hash);
+        hasher.update(self.content_hash);
+        hasher.update(&self.zk_proof.receipt);
+        hex::encode(hasher.finalize())
+    }
+
+    /// Iterates over this proof and the proofs it extends, newest first
+    pub fn chain(&self) -> impl Iterator<Item = &Proof> {
+        std::iter::successors(Some(self), |proof| {
+            proof.metadata.extends.as_ref().map(|extension| extension.prior.as_ref())
+        })
+    }
+
+    /// Returns the root and content hashes this proof's receipt journal must commit
+    pub fn journal_hashes(&self) -> ([u8; 32], [u8; 32]) {
+        match &self.metadata.extends {
+            Some(extension) => (extension.suffix_root_hash, extension.suffix_content_hash),
+            None => (self.root_hash, self.content_hash),
+        }
+    }
+
+    /// Checks that every extended proof in the chain chains its prior's hashes
+    pub fn is_chain_linked(&self) -> bool {
+        self.chain().all(|proof| match &proof.metadata.extends {
+            Some(extension) => {
+                proof.root_hash == ProofExtension::chain_hash(&extension.prior.root_hash, &extension.suffix_root_hash)
+                    && proof.content_hash
+                        == ProofExtension::chain_hash(&extension.prior.content_hash, &extension.suffix_content_hash)
+            }
+            None => true,
+        })
+    }
+
+    /// Hashes claimed content the way this proof's content hash is derived
+    ///
+    /// For an extended proof, the leading `prior_content_len` bytes are hashed as
+    /// the prior proof's content and the rest as the appended bytes. Returns
+    /// `None` if the content is too short to cover the prior proof's content.
+    pub fn claimed_content_hash(&self, claimed_content: &[u8]) -> Option<[u8; 32]> {
+        match &self.metadata.extends {
+            Some(extension) => {
+                let split = usize::try_from(extension.prior_content_len).ok()
+                    .filter(|&split| split <= claimed_content.len())?;
+                let (prior_content, suffix) = claimed_content.split_at(split);
+                let prior_hash = extension.prior.claimed_content_hash(prior_content)?;
+                let suffix_hash = self.metadata.security.hash_algorithm.digest(suffix);
+                Some(ProofExtension::chain_hash(&prior_hash, &suffix_hash))
+            }
+            None => Some(self.content_selection.content_hash(self.metadata.security.hash_algorithm, claimed_content)),
+        }
+    }
+
+    /// Hashes claimed content read from `reader` the way
+    /// [`claimed_content_hash`](Self::claimed_content_hash) hashes a slice,
+    /// holding only one buffer of it in memory at a time
+    pub fn claimed_content_hash_from_reader(&self, reader: &mut dyn std::io::Read) -> std::io::Result<Option<[u8; 32]>> {
+        use std::io::Read;
+
+        let algorithm = self.metadata.security.hash_algorithm;
+        match &self.metadata.extends {
+            Some(extension) => {
+                let mut prior_content = (&mut *reader).take(extension.prior_content_len);
+                let Some(prior_hash) = extension.prior.claimed_content_hash_from_reader(&mut prior_content)? else {
+                    return Ok(None);
+                };
+                if prior_content.limit() > 0 {
+                    return Ok(None);
+                }
+                let suffix_hash = algorithm.hasher().read_all(reader)?;
+                Ok(Some(ProofExtension::chain_hash(&prior_hash, &suffix_hash)))
+            }
+            None => {
+                let mut hasher = algorithm.hasher();
+                if let ContentSelection::MultiRange(ranges) = &self.content_selection {
+                    hasher.update(&multi_range_message(ranges, &[]));
+                }
+                Ok(Some(hasher.read_all(reader)?))
+            }
+        }
+    }
+
+    /// Returns a plain-language account of what the proof shows, in the
+    /// language of the global [`I18nManager`](crate::i18n::I18nManager)
+    pub fn explanation(&self) -> String {
+        self.explain(crate::i18n::init_i18n())
+    }
+
+    /// Returns a plain-language account of what the proof shows in `i18n`'s
+    /// current language: the selection, the file it was taken from, the
+    /// content hash, when the proof was generated and at what security level
+    pub fn explain(&self, i18n: &crate::i18n::I18nManager) -> String {
+        let formatter = crate::i18n::LocaleFormatter::new(i18n.get_language());
+        let file_info = &self.metadata.file_info;
+        let file = match &file_info.filename {
+            Some(filename) => filename.clone(),
+            None => i18n.translate("proof.unnamed_file"),
+        };
+        let args = [
+            ("selection", self.content_selection.describe(i18n)),
+            ("file", file),
+            ("size", formatter.format_file_size(file_info.size.get())),
+            ("cid", file_info.ipfs_cid.clone()),
+            ("hash_function", self.metadata.security.hash_algorithm.name().to_string()),
+            ("content_hash", hex::encode(self.content_hash)),
+            ("date", formatter.format_date(&self.created_at)),
+            ("security_level", self.metadata.security.security_level.to_string()),
+        ];
+        let args = args.into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        i18n.translate_with_args("proof.explanation", &args)
+    }
+}
+
 impl fmt::Display for Proof {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -304,14 +1197,214 @@ impl fmt::Display for VerificationResult {
     }
 }
 
+/// Multiplier for the polynomial rolling hash used by `ContentSelection::PatternHash`.
+///
+/// Must match the constant used by the guest program.
+pub const PATTERN_FINGERPRINT_BASE: u64 = 0x0100_0000_01b3;
+
+/// Computes the rolling-hash fingerprint of `data` (wrapping arithmetic mod 2^64)
+pub fn pattern_fingerprint(data: &[u8]) -> u64 {
+    data.iter().fold(0u64, |acc, &byte| {
+        acc.wrapping_mul(PATTERN_FINGERPRINT_BASE).wrapping_add(byte as u64)
+    })
+}
+
+/// Encodes the ranges of a `ContentSelection::MultiRange` ahead of their content
+///
+/// The layout is the range count, then each start and end, all as
+/// little-endian `u64`, followed by the ranges' bytes in order. The content
+/// hash of a multi-range proof is taken over this message, so the receipt
+/// journal binds the exact ranges that were proven. Must match
+/// `multi_range_message` in the guest program.
+pub fn multi_range_message(ranges: &[(usize, usize)], content: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(8 + ranges.len() * 16 + content.len());
+    message.extend_from_slice(&(ranges.len() as u64).to_le_bytes());
+    for &(start, end) in ranges {
+        message.extend_from_slice(&(start as u64).to_le_bytes());
+        message.extend_from_slice(&(end as u64).to_le_bytes());
+    }
+    message.extend_from_slice(content);
+    message
+}
+
+/// Prefix of the message hashed by [`ContentSelection::selection_hash`]; must
+/// match the guest
+pub const SELECTION_DOMAIN: &[u8] = b"zkipfs-proof/selection/v1\0";
+
+impl HashAlgorithm {
+    /// Hashes `data` with this algorithm
+    pub fn digest(&self, data: &[u8]) -> [u8; 32] {
+        use sha2::Digest;
+
+        match self {
+            HashAlgorithm::Sha256 => sha2::Sha256::digest(data).into(),
+            HashAlgorithm::Sha512_256 => sha2::Sha512_256::digest(data).into(),
+            HashAlgorithm::Blake3 => blake3::hash(data).into(),
+        }
+    }
+
+    /// Starts an incremental hash with this algorithm
+    pub fn hasher(&self) -> ContentHasher {
+        use sha2::Digest;
+
+        match self {
+            HashAlgorithm::Sha256 => ContentHasher::Sha256(sha2::Sha256::new()),
+            HashAlgorithm::Sha512_256 => ContentHasher::Sha512_256(sha2::Sha512_256::new()),
+            HashAlgorithm::Blake3 => ContentHasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    /// Human-readable name, as recorded in `SecurityParameters::hash_function`
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "SHA-256",
+            HashAlgorithm::Sha512_256 => "SHA-512/256",
+            HashAlgorithm::Blake3 => "BLAKE3",
+        }
+    }
+}
+
+/// Incremental form of [`HashAlgorithm::digest`]
+pub enum ContentHasher {
+    Sha256(sha2::Sha256),
+    Sha512_256(sha2::Sha512_256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl ContentHasher {
+    /// Bytes read from a reader at a time by [`read_all`](Self::read_all)
+    const BUFFER_LEN: usize = 64 * 1024;
+
+    /// Adds `data` to the hash
+    pub fn update(&mut self, data: &[u8]) {
+        use sha2::Digest;
+
+        match self {
+            ContentHasher::Sha256(hasher) => hasher.update(data),
+            ContentHasher::Sha512_256(hasher) => hasher.update(data),
+            ContentHasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    /// Adds everything `reader` yields and returns the hash
+    pub fn read_all(mut self, reader: &mut dyn std::io::Read) -> std::io::Result<[u8; 32]> {
+        let mut buffer = vec![0u8; Self::BUFFER_LEN];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => return Ok(self.finalize()),
+                Ok(n) => self.update(&buffer[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns the hash of everything added
+    pub fn finalize(self) -> [u8; 32] {
+        use sha2::Digest;
+
+        match self {
+            ContentHasher::Sha256(hasher) => hasher.finalize().into(),
+            ContentHasher::Sha512_256(hasher) => hasher.finalize().into(),
+            ContentHasher::Blake3(hasher) => hasher.finalize().into(),
+        }
+    }
+}
+
 impl ContentSelection {
+    /// Creates a hashed pattern selection from the full pattern bytes
+    pub fn pattern_hash(content: &[u8]) -> Self {
+        use sha2::{Digest, Sha256};
+
+        ContentSelection::PatternHash {
+            hash: Sha256::digest(content).into(),
+            len: content.len(),
+            fingerprint: pattern_fingerprint(content),
+        }
+    }
+
+    /// Selects `start_pct` to `end_pct` percent of the file's content, such
+    /// as `90.0..100.0` for its last tenth
+    pub fn percent_range(start_pct: f64, end_pct: f64) -> Self {
+        ContentSelection::PercentRange { start_pct, end_pct }
+    }
+
+    /// Byte span of `start_pct` to `end_pct` percent of `len` bytes, widened
+    /// to whole bytes
+    pub fn percent_span(start_pct: f64, end_pct: f64, len: usize) -> std::ops::Range<usize> {
+        let offset = |pct: f64, round: fn(f64) -> f64| (round(len as f64 * pct / 100.0) as usize).min(len);
+        offset(start_pct, f64::floor)..offset(end_pct, f64::ceil)
+    }
+
+    /// Hashes the content proven by this selection
+    ///
+    /// This is the digest of `content`, except for `MultiRange`, whose hash
+    /// also covers its ranges.
+    pub fn content_hash(&self, algorithm: HashAlgorithm, content: &[u8]) -> [u8; 32] {
+        match self {
+            ContentSelection::MultiRange(ranges) => algorithm.digest(&multi_range_message(ranges, content)),
+            _ => algorithm.digest(content),
+        }
+    }
+
+    /// Hashes this selection as the guest does when committing it to the journal
+    ///
+    /// The hash is taken over the domain and the selection's risc0 serde words,
+    /// as little-endian bytes, so a proof's `content_selection` can be checked
+    /// against the selection its receipt was generated for.
+    pub fn selection_hash(&self) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+
+        let words = risc0_zkvm::serde::to_vec(self).expect("a content selection serializes");
+        let mut hasher = Sha256::new();
+        hasher.update(SELECTION_DOMAIN);
+        for word in words {
+            hasher.update(word.to_le_bytes());
+        }
+        hasher.finalize().into()
+    }
+
+    /// Returns the furthest byte offset this selection addresses, if it names
+    /// byte ranges itself
+    pub fn range_end(&self) -> Option<usize> {
+        match self {
+            ContentSelection::ByteRange { end, .. } | ContentSelection::JsonSpan { end, .. } => Some(*end),
+            ContentSelection::MultiRange(ranges) => ranges.iter().map(|(_, end)| *end).max(),
+            ContentSelection::Multiple(selections) => {
+                selections.iter().filter_map(ContentSelection::range_end).max()
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns how many inclusion paths a proof of this selection commits,
+    /// one per sub-selection of a `Multiple`
+    pub fn inclusion_path_count(&self) -> usize {
+        match self {
+            ContentSelection::Multiple(selections) => selections.len(),
+            _ => 1,
+        }
+    }
+
     /// Returns the estimated size of content that will be proven
     pub fn estimated_size(&self) -> Option<usize> {
         match self {
             ContentSelection::ByteRange { start, end } => Some(end - start),
             ContentSelection::Pattern { content } => Some(content.len()),
+            ContentSelection::PatternHash { len, .. } => Some(*len),
             ContentSelection::Regex { .. } => None,
             ContentSelection::XPath { .. } => None,
+            ContentSelection::JsonPath { .. } => None,
+            ContentSelection::JsonSpan { start, end } => Some(end - start),
+            ContentSelection::MultiRange(ranges) => {
+                Some(ranges.iter().map(|(start, end)| end.saturating_sub(*start)).sum())
+            }
+            ContentSelection::PdfPage { .. }
+            | ContentSelection::CsvCell { .. }
+            | ContentSelection::ExifField { .. }
+            | ContentSelection::PercentRange { .. } => None,
             ContentSelection::Multiple(selections) => {
                 selections.iter()
                     .map(|s| s.estimated_size())
@@ -320,21 +1413,179 @@ impl ContentSelection {
         }
     }
 
+    /// Checks that this selection makes at most `max_selections` selections,
+    /// of at most `max_total_bytes` in total, and nests `Multiple` selections
+    /// at most [`MAX_SELECTION_DEPTH`] deep
+    ///
+    /// The selection is walked without recursion, so this is safe to call
+    /// before [`validate`](Self::validate) on selections of any depth. Sizes
+    /// only known once resolved, such as a regex match, are not counted.
+    pub fn check_limits(&self, max_selections: Option<usize>, max_total_bytes: Option<u64>) -> crate::error::Result<()> {
+        let exceeded = |resource: &str, message: String| Err(crate::error::ProofError::resource_limit_error(resource, message));
+
+        let mut pending = vec![(self, 0)];
+        let (mut selections, mut total_bytes) = (0usize, 0u64);
+        while let Some((selection, depth)) = pending.pop() {
+            if let ContentSelection::Multiple(parts) = selection {
+                if depth >= MAX_SELECTION_DEPTH {
+                    return exceeded(
+                        "selection_depth",
+                        format!("Multiple selections are nested more than {} deep", MAX_SELECTION_DEPTH)
+                    );
+                }
+                pending.extend(parts.iter().map(|part| (part, depth + 1)));
+                continue;
+            }
+
+            selections += match selection {
+                ContentSelection::MultiRange(ranges) => ranges.len(),
+                _ => 1,
+            };
+            if let Some(max) = max_selections.filter(|&max| selections > max) {
+                return exceeded("selection_count", format!("Selection makes more than {} selections", max));
+            }
+            total_bytes += selection.estimated_size().unwrap_or(0) as u64;
+            if let Some(max) = max_total_bytes.filter(|&max| total_bytes > max) {
+                return exceeded("selected_bytes", format!("Selection covers more than {} bytes", max));
+            }
+        }
+        Ok(())
+    }
+
     /// Returns true if this selection is valid
     pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Checks that this selection is well-formed, saying what is wrong if not
+    pub fn validate(&self) -> crate::error::Result<()> {
+        let invalid = |reason: String| Err(crate::error::ProofError::content_selection_error(reason));
+
         match self {
-            ContentSelection::ByteRange { start, end } => start < end,
-            ContentSelection::Pattern { content } => !content.is_empty(),
-            ContentSelection::Regex { pattern } => !pattern.is_empty(),
-            ContentSelection::XPath { selector } => !selector.is_empty(),
+            ContentSelection::ByteRange { start, end } | ContentSelection::JsonSpan { start, end } => {
+                if start >= end {
+                    return invalid(format!("Byte range {}..{} is empty or inverted", start, end));
+                }
+            }
+            ContentSelection::Pattern { content } => {
+                if content.is_empty() {
+                    return invalid("Pattern must not be empty".to_string());
+                }
+            }
+            ContentSelection::PatternHash { len, .. } => {
+                if *len == 0 {
+                    return invalid("Hashed pattern must not be empty".to_string());
+                }
+            }
+            ContentSelection::Regex { pattern } => {
+                if pattern.is_empty() {
+                    return invalid("Regex pattern must not be empty".to_string());
+                }
+                if let Err(e) = regex::Regex::new(pattern) {
+                    return invalid(format!("Invalid regex pattern: {}", e));
+                }
+            }
+            ContentSelection::XPath { selector } => {
+                if selector.is_empty() {
+                    return invalid("XPath selector must not be empty".to_string());
+                }
+            }
+            ContentSelection::JsonPath { path } => {
+                crate::json_path::parse_path(path)?;
+            }
+            ContentSelection::MultiRange(ranges) => {
+                if ranges.is_empty() {
+                    return invalid("Multi-range selection has no ranges".to_string());
+                }
+                if let Some((start, end)) = ranges.iter().find(|(start, end)| start >= end) {
+                    return invalid(format!("Byte range {}..{} is empty or inverted", start, end));
+                }
+                if ranges.windows(2).any(|pair| pair[0].1 > pair[1].0) {
+                    return invalid("Byte ranges must be ascending and disjoint".to_string());
+                }
+            }
+            ContentSelection::PdfPage { .. }
+            | ContentSelection::CsvCell { .. }
+            | ContentSelection::ExifField { .. } => {}
+            ContentSelection::PercentRange { start_pct, end_pct } => {
+                // Written so that NaN fails too
+                let in_bounds = 0.0 <= *start_pct && start_pct < end_pct && *end_pct <= 100.0;
+                if !in_bounds {
+                    return invalid(format!(
+                        "Percentage range {}%..{}% must satisfy 0 <= start < end <= 100",
+                        start_pct, end_pct
+                    ));
+                }
+            }
             ContentSelection::Multiple(selections) => {
-                !selections.is_empty() && selections.iter().all(|s| s.is_valid())
+                if selections.is_empty() {
+                    return invalid("Multiple selection has no selections".to_string());
+                }
+                for selection in selections {
+                    selection.validate()?;
+                }
             }
         }
+        Ok(())
     }
 
-    /// Returns a human-readable description of the selection
+    /// Returns a human-readable description of the selection in the language
+    /// of the global [`I18nManager`](crate::i18n::I18nManager)
     pub fn description(&self) -> String {
+        self.describe(crate::i18n::init_i18n())
+    }
+
+    /// Returns a description of the selection in `i18n`'s current language,
+    /// from the `selection.*` translation keys
+    pub fn describe(&self, i18n: &crate::i18n::I18nManager) -> String {
+        let (key, args) = self.description_args();
+        let args = args.into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        i18n.translate_with_args(key, &args)
+    }
+
+    /// Translation key of the description and the values of its placeholders
+    fn description_args(&self) -> (&'static str, Vec<(&'static str, String)>) {
+        let span = |start: &usize, end: &usize| vec![
+            ("start", start.to_string()),
+            ("end", end.to_string()),
+            ("len", end.saturating_sub(*start).to_string()),
+        ];
+        match self {
+            ContentSelection::ByteRange { start, end } => ("selection.byte_range", span(start, end)),
+            ContentSelection::Pattern { content } => ("selection.pattern", vec![
+                ("preview", String::from_utf8_lossy(&content[..content.len().min(50)]).into_owned()),
+                ("len", content.len().to_string()),
+            ]),
+            ContentSelection::PatternHash { hash, len, .. } => ("selection.pattern_hash", vec![
+                ("hash", hex::encode(&hash[..8])),
+                ("len", len.to_string()),
+            ]),
+            ContentSelection::Regex { pattern } => ("selection.regex", vec![("pattern", pattern.clone())]),
+            ContentSelection::XPath { selector } => ("selection.xpath", vec![("selector", selector.clone())]),
+            ContentSelection::JsonPath { path } => ("selection.json_path", vec![("path", path.clone())]),
+            ContentSelection::JsonSpan { start, end } => ("selection.json_span", span(start, end)),
+            ContentSelection::MultiRange(ranges) => ("selection.multi_range", vec![
+                ("count", ranges.len().to_string()),
+                ("len", ranges.iter().map(|(start, end)| end.saturating_sub(*start)).sum::<usize>().to_string()),
+            ]),
+            ContentSelection::PdfPage { page } => ("selection.pdf_page", vec![("page", page.to_string())]),
+            ContentSelection::CsvCell { row, column } => ("selection.csv_cell", vec![
+                ("row", row.to_string()),
+                ("column", column.to_string()),
+            ]),
+            ContentSelection::ExifField { tag } => ("selection.exif_field", vec![("tag", format!("0x{:04X}", tag))]),
+            ContentSelection::PercentRange { start_pct, end_pct } => ("selection.percent_range", vec![
+                ("start", start_pct.to_string()),
+                ("end", end_pct.to_string()),
+            ]),
+            ContentSelection::Multiple(selections) => ("selection.multiple", vec![("count", selections.len().to_string())]),
+        }
+    }
+
+    /// Returns an English description of the selection, for logs
+    pub fn description_en(&self) -> String {
         match self {
             ContentSelection::ByteRange { start, end } => {
                 format!("Bytes {}-{} ({} bytes)", start, end, end - start)
@@ -345,12 +1596,40 @@ impl ContentSelection {
                     content.len()
                 )
             }
+            ContentSelection::PatternHash { hash, len, .. } => {
+                format!("Pattern hash: {}... ({} bytes)", hex::encode(&hash[..8]), len)
+            }
             ContentSelection::Regex { pattern } => {
                 format!("Regex: {}", pattern)
             }
             ContentSelection::XPath { selector } => {
                 format!("XPath: {}", selector)
             }
+            ContentSelection::JsonPath { path } => {
+                format!("JSON path: {}", path)
+            }
+            ContentSelection::JsonSpan { start, end } => {
+                format!("JSON value at bytes {}-{} ({} bytes)", start, end, end - start)
+            }
+            ContentSelection::MultiRange(ranges) => {
+                format!(
+                    "{} byte ranges ({} bytes)",
+                    ranges.len(),
+                    ranges.iter().map(|(start, end)| end.saturating_sub(*start)).sum::<usize>()
+                )
+            }
+            ContentSelection::PdfPage { page } => {
+                format!("PDF page {}", page)
+            }
+            ContentSelection::CsvCell { row, column } => {
+                format!("CSV cell at row {}, column {}", row, column)
+            }
+            ContentSelection::ExifField { tag } => {
+                format!("EXIF tag 0x{:04X}", tag)
+            }
+            ContentSelection::PercentRange { start_pct, end_pct } => {
+                format!("Bytes {}%-{}% of the file", start_pct, end_pct)
+            }
             ContentSelection::Multiple(selections) => {
                 format!("Multiple selections ({})", selections.len())
             }
@@ -358,6 +1637,68 @@ impl ContentSelection {
     }
 }
 
+/// Builds a [`ContentSelection`] from one or more parts, validating them
+///
+/// A single part builds to itself and several parts to a `Multiple`.
+/// Malformed parts, such as an inverted byte range, are rejected by
+/// [`build`](Self::build) with the reason from [`ContentSelection::validate`].
+///
+/// ```
+/// use zkipfs_proof_core::ContentSelectionBuilder;
+///
+/// let selection = ContentSelectionBuilder::new()
+///     .byte_range(0, 64)
+///     .pattern("total: 120.00")
+///     .build()
+///     .unwrap();
+/// assert!(selection.is_valid());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ContentSelectionBuilder {
+    selections: Vec<ContentSelection>,
+}
+
+impl ContentSelectionBuilder {
+    /// Creates a builder with no parts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the bytes `start..end`
+    pub fn byte_range(self, start: usize, end: usize) -> Self {
+        self.add(ContentSelection::ByteRange { start, end })
+    }
+
+    /// Adds an exact pattern
+    pub fn pattern(self, content: impl Into<Vec<u8>>) -> Self {
+        self.add(ContentSelection::Pattern { content: content.into() })
+    }
+
+    /// Adds the first match of a regular expression
+    pub fn regex(self, pattern: impl Into<String>) -> Self {
+        self.add(ContentSelection::Regex { pattern: pattern.into() })
+    }
+
+    /// Adds any selection, including one built by another builder
+    pub fn add(mut self, selection: ContentSelection) -> Self {
+        self.selections.push(selection);
+        self
+    }
+
+    /// Validates the parts and combines them into one selection
+    pub fn build(mut self) -> crate::error::Result<ContentSelection> {
+        let selection = match self.selections.len() {
+            0 => return Err(crate::error::ProofError::content_selection_error(
+                "Content selection builder has no selections"
+            )),
+            1 => self.selections.remove(0),
+            _ => ContentSelection::Multiple(self.selections),
+        };
+        selection.validate()?;
+        Ok(selection)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -383,6 +1724,56 @@ mod tests {
         assert_eq!(regex.estimated_size(), None);
     }
 
+    #[test]
+    fn test_percent_range_validation() {
+        assert!(ContentSelection::percent_range(90.0, 100.0).is_valid());
+        assert_eq!(ContentSelection::percent_span(90.0, 100.0, 1000), 900..1000);
+        // Partial bytes are included
+        assert_eq!(ContentSelection::percent_span(10.0, 20.0, 33), 3..7);
+
+        assert!(!ContentSelection::percent_range(50.0, 40.0).is_valid());
+        assert!(!ContentSelection::percent_range(40.0, 40.0).is_valid());
+        assert!(!ContentSelection::percent_range(-1.0, 10.0).is_valid());
+        assert!(!ContentSelection::percent_range(90.0, 100.5).is_valid());
+        assert!(!ContentSelection::percent_range(f64::NAN, 10.0).is_valid());
+    }
+
+    #[test]
+    fn test_builder_multi_selection() {
+        let selection = ContentSelectionBuilder::new()
+            .byte_range(0, 16)
+            .pattern("invoice")
+            .regex(r"total: \d+")
+            .add(ContentSelectionBuilder::new().byte_range(32, 40).build().unwrap())
+            .build()
+            .unwrap();
+
+        match &selection {
+            ContentSelection::Multiple(parts) => {
+                assert_eq!(parts.len(), 4);
+                assert!(matches!(parts[3], ContentSelection::ByteRange { start: 32, end: 40 }));
+            }
+            other => panic!("expected a multiple selection, got {:?}", other),
+        }
+        assert!(selection.is_valid());
+
+        let single = ContentSelectionBuilder::new().pattern("x").build().unwrap();
+        assert!(matches!(single, ContentSelection::Pattern { .. }));
+    }
+
+    #[test]
+    fn test_builder_rejects_inverted_range() {
+        let error = ContentSelectionBuilder::new()
+            .pattern("ok")
+            .byte_range(10, 5)
+            .build()
+            .unwrap_err();
+        assert!(error.to_string().contains("10..5"));
+
+        assert!(ContentSelectionBuilder::new().build().is_err());
+        assert!(ContentSelectionBuilder::new().regex("(unclosed").build().is_err());
+    }
+
     #[test]
     fn test_proof_config_default() {
         let config = ProofConfig::default();
@@ -392,6 +1783,43 @@ mod tests {
         assert_eq!(config.timeout_seconds, Some(600));
     }
 
+    #[test]
+    fn test_adaptive_block_size() {
+        assert_eq!(ChunkingStrategy::adaptive_block_size(0), ADAPTIVE_MIN_BLOCK_SIZE);
+        assert_eq!(ChunkingStrategy::adaptive_block_size(64 * 1024), ADAPTIVE_MIN_BLOCK_SIZE);
+        assert_eq!(ChunkingStrategy::adaptive_block_size(4 * 1024 * 1024), 256 * 1024);
+        // Rounded up to a power of two, and capped for large files
+        assert_eq!(ChunkingStrategy::adaptive_block_size(5 * 1024 * 1024), 512 * 1024);
+        assert_eq!(ChunkingStrategy::adaptive_block_size(64 * 1024 * 1024), ADAPTIVE_MAX_BLOCK_SIZE);
+    }
+
+    #[test]
+    fn test_unit_newtypes_keep_json_format() {
+        let json = serde_json::json!({
+            "generation_time_ms": 1000,
+            "file_processing_time_ms": 100,
+            "zk_generation_time_ms": 900,
+            "peak_memory_bytes": 1048576,
+            "zk_cycles": 10000,
+            "proof_size_bytes": 2048,
+            "compression_ratio": null
+        });
+
+        let metrics: PerformanceMetrics = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(metrics.generation_time_ms, Millis(1000));
+        assert_eq!(metrics.peak_memory_bytes, Bytes(1024 * 1024));
+        assert_eq!(serde_json::to_value(&metrics).unwrap(), json);
+    }
+
+    #[test]
+    fn test_receipt_kind_format_version() {
+        for kind in [ReceiptKind::Composite, ReceiptKind::Succinct, ReceiptKind::Groth16] {
+            assert_eq!(ReceiptKind::from_format_version(&kind.format_version()), Some(kind));
+        }
+        assert_eq!(ReceiptKind::Composite.format_version(), "1.0");
+        assert_eq!(ReceiptKind::from_format_version("1.0+unknown"), None);
+    }
+
     #[test]
     fn test_content_selection_description() {
         let range = ContentSelection::ByteRange { start: 100, end: 200 };
@@ -401,5 +1829,55 @@ mod tests {
         assert!(pattern.description().contains("hello world"));
         assert!(pattern.description().contains("11 bytes"));
     }
-}
 
+    #[test]
+    fn test_localized_selection_description() {
+        let i18n = crate::i18n::I18nManager::new();
+        i18n.set_language(crate::i18n::Language::German);
+
+        let range = ContentSelection::ByteRange { start: 5, end: 10 };
+        assert_eq!(range.describe(&i18n), "Bytes 5-10 (5 Bytes)");
+        let cell = ContentSelection::CsvCell { row: 2, column: 1 };
+        assert_eq!(cell.describe(&i18n), "CSV-Zelle in Zeile 2, Spalte 1");
+        assert_eq!(range.description_en(), "Bytes 5-10 (5 bytes)");
+    }
+
+    #[test]
+    fn test_receipt_compression_round_trip() {
+        let receipt: Vec<u8> = (0..4096u32).flat_map(|i| (i % 16).to_le_bytes()).collect();
+
+        for codec in [CompressionType::Gzip, CompressionType::Zstd] {
+            let compressed = codec.compress(&receipt).unwrap();
+            assert!(compressed.len() < receipt.len() / 4, "{:?}", codec);
+            let data = ZkProofData {
+                receipt: compressed,
+                public_inputs: Vec::new(),
+                format_version: "1.0".to_string(),
+                compression: Some(codec),
+            };
+            assert_eq!(&*data.receipt_bytes().unwrap(), &receipt[..]);
+        }
+
+        // Receipts stored before compression was applied are read as is
+        let legacy = ZkProofData {
+            receipt: receipt.clone(),
+            public_inputs: Vec::new(),
+            format_version: "1.0".to_string(),
+            compression: Some(CompressionType::Gzip),
+        };
+        assert_eq!(&*legacy.receipt_bytes().unwrap(), &receipt[..]);
+    }
+
+    #[test]
+    fn test_decompression_capped() {
+        let receipt = vec![0u8; 4096];
+
+        for codec in [CompressionType::Gzip, CompressionType::Zstd] {
+            let compressed = codec.compress(&receipt).unwrap();
+            assert_eq!(codec.decompress_capped(&compressed, 4096).unwrap(), receipt);
+
+            let err = codec.decompress_capped(&compressed, 4095).unwrap_err();
+            assert!(matches!(err, crate::error::ProofError::ResourceLimitError { .. }), "{:?}", codec);
+        }
+    }
+}
@@ -4,26 +4,75 @@
 //! including local verification, on-chain verification, and batch verification.
 
 use crate::{
+    commitment::ContentOpening,
     error::{ProofError, Result, ResultExt},
+    monitoring::{EventBus, MonitorEvent},
+    proof_types::{ProofScheme, ProofType, ProofTypeRegistry},
+    secret::Secret,
+    signing::VerifyingKey,
     types::*,
-    IPFS_CONTENT_VERIFIER_ID,
 };
+#[cfg(not(target_arch = "wasm32"))]
+use crate::cache::{CacheConfig, CacheKey, CacheManager};
+use cid::Cid;
 use risc0_zkvm::Receipt;
-use sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
 use tracing::{debug, info, instrument, warn};
 use chrono::Utc;
 
+/// Name of the verification step that checks a proof's signature
+const SIGNATURE_STEP: &str = "Signature Verification";
+
+/// Name of the verification step that checks the receipt
+const CRYPTO_STEP: &str = "Cryptographic Proof Verification";
+
+/// Name of the verification step that evaluates custom rules
+const CUSTOM_RULES_STEP: &str = "Custom Rules Verification";
+
+/// What verification checks the proven content against
+#[derive(Clone, Copy)]
+enum ContentCheck<'a> {
+    /// Content hash of the claimed content
+    Claimed(&'a [u8]),
+    /// Claimed content already hashed while reading it, `None` if it is too
+    /// short for the proof
+    ClaimedHash(Option<[u8; 32]>),
+    /// Only that the proof commits to its content
+    Committed,
+    /// An opening of the proof's commitment
+    Opening(&'a ContentOpening),
+}
+
+/// Multicodec code of raw binary content
+const RAW_CODEC: u64 = 0x55;
+
+/// Multihash code of SHA-256
+const SHA2_256_CODE: u64 = 0x12;
+
 /// Comprehensive proof verifier for zkIPFS-Proof
 pub struct ProofVerifier {
     /// Configuration for verification
     config: VerificationConfig,
     /// Verification statistics
     stats: VerificationStatistics,
+    /// Guest image IDs receipts are checked against, by proof type
+    registry: ProofTypeRegistry,
+    /// Cache of recent verification results, created on first use
+    #[cfg(not(target_arch = "wasm32"))]
+    cache: Option<CacheManager>,
+    /// Receives verification and cache events
+    events: EventBus,
 }
 
 /// Configuration for proof verification
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct VerificationConfig {
     /// Whether to perform strict verification (includes all checks)
     pub strict_verification: bool,
@@ -35,10 +84,93 @@ pub struct VerificationConfig {
     pub verify_metadata: bool,
     /// Custom verification rules
     pub custom_rules: Vec<VerificationRule>,
+    /// Programmatic rules backed by closures (not serialized)
+    #[serde(skip)]
+    pub closure_rules: Vec<ClosureRule>,
+    /// Seconds a result is served from the result cache (0 disables caching)
+    #[serde(default = "default_result_cache_ttl_seconds")]
+    pub result_cache_ttl_seconds: u64,
+    /// Run every check even if a cached result exists, for security-critical checks
+    #[serde(default)]
+    pub bypass_cache: bool,
+    /// Guest image IDs trusted besides the one registered for each proof type,
+    /// such as those of earlier releases
+    #[serde(default)]
+    pub trusted_image_ids: Vec<[u32; 8]>,
+    /// Longest the cryptographic check, the custom rules or an on-chain call
+    /// may take before verification fails with `StepTimeout` (`None` for no limit)
+    #[serde(default)]
+    pub step_timeout: Option<Duration>,
+    /// Refuse every check that needs the network
+    ///
+    /// Offline verification checks everything the proof carries: the receipt
+    /// against the trusted guest image IDs, the journal against the proof's
+    /// hashes, selection and extension chain, the claimed content, the
+    /// signature, the timestamp token against a trusted TSA key, age, metadata
+    /// and custom rules. It does not fetch the file from IPFS, call a chain or
+    /// contact a timestamp authority; callers asking for such a check get an
+    /// error from [`ensure_online`](Self::ensure_online) instead. Closure rules
+    /// run as written and must keep to this themselves.
+    #[serde(default)]
+    pub offline: bool,
+    /// Proof systems whose proofs pass the metadata check; a proof must match
+    /// an entry's system, version and security level, or the check warns and,
+    /// under strict verification, fails. An empty list accepts every system.
+    #[serde(default = "default_accepted_systems")]
+    pub accepted_systems: Vec<AcceptedSystem>,
+}
+
+fn default_result_cache_ttl_seconds() -> u64 {
+    5 * 60
+}
+
+fn default_accepted_systems() -> Vec<AcceptedSystem> {
+    vec![AcceptedSystem {
+        proof_system: "Risc0".to_string(),
+        min_version: None,
+        min_security: 128,
+    }]
+}
+
+/// A proof system the verifier accepts, from a minimum version and security level
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AcceptedSystem {
+    /// Name recorded as the proof's `proof_system`, such as `Risc0`
+    pub proof_system: String,
+    /// Oldest accepted version of the system, compared by dotted numeric
+    /// component so `1.10` is newer than `1.2` (`None` accepts any version)
+    #[serde(default)]
+    pub min_version: Option<String>,
+    /// Lowest accepted security level in bits
+    pub min_security: u32,
+}
+
+impl AcceptedSystem {
+    /// Whether `version` is at least the minimum version
+    ///
+    /// Pre-release and build suffixes (after `-` or `+`) are ignored, and a
+    /// component that is not a number counts as zero.
+    pub fn accepts_version(&self, version: &str) -> bool {
+        let Some(min_version) = &self.min_version else {
+            return true;
+        };
+        let components = |version: &str| -> Vec<u64> {
+            version.split(['-', '+']).next().unwrap_or_default()
+                .split('.')
+                .map(|component| component.trim().parse().unwrap_or(0))
+                .collect()
+        };
+        let (version, min_version) = (components(version), components(min_version));
+        let len = version.len().max(min_version.len());
+        let padded = |components: &[u64]| -> Vec<u64> {
+            (0..len).map(|i| components.get(i).copied().unwrap_or(0)).collect()
+        };
+        padded(&version) >= padded(&min_version)
+    }
 }
 
 /// Custom verification rule
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct VerificationRule {
     pub name: String,
     pub description: String,
@@ -46,16 +178,141 @@ pub struct VerificationRule {
 }
 
 /// Types of verification rules
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum VerificationRuleType {
     /// Minimum security level required
     MinSecurityLevel(u32),
     /// Maximum proof size allowed
-    MaxProofSize(u64),
+    MaxProofSize(Bytes),
     /// Required proof system
     RequiredProofSystem(String),
-    /// Custom validation function
-    Custom(fn(&Proof) -> Result<bool>),
+    /// Proof must carry a valid signature by this hex-encoded Ed25519 public key
+    RequiredSigner(String),
+    /// Proof must carry a valid signature by any key
+    RequireSignature,
+    /// Proof must carry a timestamp token binding it, signed by this
+    /// hex-encoded Ed25519 timestamp authority key
+    RequiredTimestampAuthority(String),
+}
+
+/// Named verification strictness levels for [`VerificationConfig::preset`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    /// Only the cryptographic checks: no age, metadata or custom rules
+    Lenient,
+    /// The default configuration
+    #[default]
+    Standard,
+    /// Proofs at most 7 days old, of at least 128-bit security, proven with
+    /// Risc0 and signed
+    Paranoid,
+}
+
+impl std::str::FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "lenient" => Ok(Self::Lenient),
+            "standard" => Ok(Self::Standard),
+            "paranoid" => Ok(Self::Paranoid),
+            _ => Err(format!("unknown preset '{}' (expected lenient, standard or paranoid)", s)),
+        }
+    }
+}
+
+/// Check function of a [`ClosureRule`]
+pub type RuleCheck = dyn Fn(&Proof) -> Result<bool> + Send + Sync;
+
+/// Verification rule backed by a closure, which may capture state such as an allowlist
+///
+/// Closure rules cannot be serialized, so they live beside the serializable
+/// `custom_rules` and are skipped when a `VerificationConfig` is saved.
+#[derive(Clone)]
+pub struct ClosureRule {
+    pub name: String,
+    pub description: String,
+    pub check: Arc<RuleCheck>,
+}
+
+impl std::fmt::Debug for ClosureRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClosureRule")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VerificationConfig {
+    /// Fails if the configuration is offline, for callers about to run
+    /// `check`, a check that needs the network
+    pub fn ensure_online(&self, check: &str) -> Result<()> {
+        if self.offline {
+            return Err(ProofError::configuration_error(format!(
+                "{} needs network access, which offline verification refuses",
+                check
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the configuration of a named strictness level
+    ///
+    /// Fields can be changed afterwards to adjust the preset.
+    pub fn preset(preset: Preset) -> Self {
+        match preset {
+            Preset::Lenient => Self {
+                strict_verification: false,
+                max_proof_age_seconds: None,
+                verify_metadata: false,
+                ..Self::default()
+            },
+            Preset::Standard => Self::default(),
+            Preset::Paranoid => Self {
+                strict_verification: true,
+                max_proof_age_seconds: Some(7 * 24 * 60 * 60),
+                verify_metadata: true,
+                custom_rules: vec![
+                    VerificationRule {
+                        name: "min_security_level".to_string(),
+                        description: "Minimum security level: 128 bits".to_string(),
+                        rule_type: VerificationRuleType::MinSecurityLevel(128),
+                    },
+                    VerificationRule {
+                        name: "required_proof_system".to_string(),
+                        description: "Required proof system: Risc0".to_string(),
+                        rule_type: VerificationRuleType::RequiredProofSystem("Risc0".to_string()),
+                    },
+                    VerificationRule {
+                        name: "require_signature".to_string(),
+                        description: "Proof must be signed".to_string(),
+                        rule_type: VerificationRuleType::RequireSignature,
+                    },
+                ],
+                ..Self::default()
+            },
+        }
+    }
+
+    /// Adds a rule that passes when `check` returns `Ok(true)`
+    pub fn add_closure_rule<F>(
+        mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        check: F,
+    ) -> Self
+    where
+        F: Fn(&Proof) -> Result<bool> + Send + Sync + 'static,
+    {
+        self.closure_rules.push(ClosureRule {
+            name: name.into(),
+            description: description.into(),
+            check: Arc::new(check),
+        });
+        self
+    }
 }
 
 /// Statistics for proof verification
@@ -66,6 +323,27 @@ pub struct VerificationStatistics {
     pub failed_verifications: u64,
     pub avg_verification_time_ms: f64,
     pub total_verification_time_ms: u64,
+    pub cache_hits: u64,
+}
+
+/// Outcome of [`ProofVerifier::check_structure`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StructureReport {
+    /// Whether every check below passed
+    pub valid_structure: bool,
+    /// The id, version and receipt are present
+    pub has_required_fields: bool,
+    /// The public inputs are the 32-byte content hash and any signature is a
+    /// 64-byte Ed25519 signature
+    pub valid_hashes: bool,
+    /// The content selection is well-formed
+    pub valid_selection: bool,
+    /// The recorded proof size is between 1KB and 100MB
+    pub proof_size_reasonable: bool,
+    /// The proof was created in the past year, and not in the future
+    pub timestamp_reasonable: bool,
+    /// Every problem found, including ones that do not invalidate the structure
+    pub issues: Vec<String>,
 }
 
 impl Default for VerificationConfig {
@@ -76,6 +354,13 @@ impl Default for VerificationConfig {
             max_proof_age_seconds: Some(30 * 24 * 60 * 60), // 30 days
             verify_metadata: true,
             custom_rules: Vec::new(),
+            closure_rules: Vec::new(),
+            result_cache_ttl_seconds: default_result_cache_ttl_seconds(),
+            bypass_cache: false,
+            trusted_image_ids: Vec::new(),
+            step_timeout: None,
+            offline: false,
+            accepted_systems: default_accepted_systems(),
         }
     }
 }
@@ -91,16 +376,241 @@ impl ProofVerifier {
         Self {
             config,
             stats: VerificationStatistics::default(),
+            registry: ProofTypeRegistry::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            cache: None,
+            events: EventBus::new(),
         }
     }
 
+    /// Event bus the verifier reports verification and cache events to
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Report events to `events`, for sharing one bus with other components
+    pub fn set_events(&mut self, events: EventBus) {
+        self.events = events;
+    }
+
+    /// Registers the scheme whose image ID receipts of `proof_type` proofs are
+    /// verified against, replacing any earlier one
+    pub fn register_proof_type(&mut self, proof_type: ProofType, scheme: ProofScheme) {
+        self.registry.register(proof_type, scheme);
+    }
+
+    /// Returns the scheme `proof` is verified against, if its type is registered
+    pub fn scheme_for(&self, proof: &Proof) -> Option<&ProofScheme> {
+        self.registry.get(&proof.proof_type)
+    }
+
     /// Verifies a proof against claimed content with detailed result
-    #[instrument(skip(self, proof, claimed_content), fields(proof_id = %proof.id))]
+    ///
+    /// Verification is CPU-bound, so it runs on Tokio's blocking thread pool
+    /// through [`verify_blocking`](Self::verify_blocking). On wasm32 it runs on
+    /// the calling thread.
+    #[cfg(target_arch = "wasm32")]
+    pub async fn verify_detailed(
+        &mut self,
+        proof: &Proof,
+        claimed_content: impl Into<Secret<Vec<u8>>>,
+    ) -> Result<VerificationResult> {
+        self.verify_blocking(proof, claimed_content.into().expose())
+    }
+
+    /// Verifies a proof against claimed content with detailed result
+    ///
+    /// Verification is CPU-bound, so it runs on Tokio's blocking thread pool
+    /// through [`verify_blocking`](Self::verify_blocking). On wasm32 it runs on
+    /// the calling thread.
+    ///
+    /// Results are cached in memory for `result_cache_ttl_seconds`, keyed by
+    /// the proof id, the SHA-256 of the claimed content and a digest of the
+    /// whole proof and configuration, so editing the proof or the rules misses
+    /// the cache. A cached valid result is not served once the proof has
+    /// expired. Set `bypass_cache` to always run every check.
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn verify_detailed(
+        &mut self,
+        proof: &Proof,
+        claimed_content: impl Into<Secret<Vec<u8>>>,
+    ) -> Result<VerificationResult> {
+        let start_time = Instant::now();
+        let claimed_content = claimed_content.into();
+        let cache_key = self.result_cache_key(proof, &claimed_content)?;
+        if let Some(key) = &cache_key {
+            if let Some(mut result) = self.cached_result(key, proof).await? {
+                result.from_cache = true;
+                result.verification_time_ms = start_time.elapsed().as_millis() as u64;
+                self.stats.cache_hits += 1;
+                self.update_stats(result.is_valid, result.verification_time_ms);
+                debug!("Serving cached verification result for proof: {}", proof.id);
+                self.events.emit(MonitorEvent::CacheHit);
+                self.events.emit(MonitorEvent::VerificationCompleted { valid: result.is_valid });
+                return Ok(result);
+            }
+            self.events.emit(MonitorEvent::CacheMiss);
+        }
+
+        let mut worker = self.detached();
+        let proof = proof.clone();
+
+        let (worker, result) = tokio::task::spawn_blocking(move || {
+            let result = worker.verify_blocking(&proof, claimed_content.expose());
+            (worker, result)
+        })
+        .await
+        .map_err(|e| ProofError::internal_error("Verification task failed", Some(Box::new(e))))?;
+
+        self.stats = worker.stats;
+        if let (Some(key), Ok(result)) = (cache_key, &result) {
+            self.cache_result(key, result).await;
+        }
+        result
+    }
+
+    /// Builds the result cache key, or `None` if the result must not be cached
+    ///
+    /// Closure rules may depend on state outside the proof, so configurations
+    /// with closure rules are never cached.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn result_cache_key(&self, proof: &Proof, claimed_content: &Secret<Vec<u8>>) -> Result<Option<CacheKey>> {
+        use sha2::{Digest, Sha256};
+
+        if self.config.bypass_cache
+            || self.config.result_cache_ttl_seconds == 0
+            || !self.config.closure_rules.is_empty()
+        {
+            return Ok(None);
+        }
+
+        let config = serde_json::to_vec(&self.config)
+            .map_err(|e| ProofError::serialization_error("Failed to encode verification config", Some(Box::new(e))))?;
+        // The whole proof, receipt included: the signed bytes of a reproducible
+        // proof leave the receipt out. Going through `Value` sorts object keys.
+        let encoded = serde_json::to_value(proof)
+            .and_then(|value| serde_json::to_vec(&value))
+            .map_err(|e| ProofError::serialization_error("Failed to encode proof", Some(Box::new(e))))?;
+        let mut state = Sha256::new();
+        state.update(encoded);
+        state.update(config);
+        if let Some(scheme) = self.scheme_for(proof) {
+            for word in scheme.image_id {
+                state.update(word.to_le_bytes());
+            }
+        }
+
+        Ok(Some(CacheKey::Verification(format!(
+            "{}_{}_{}",
+            proof.id,
+            hex::encode(Sha256::digest(claimed_content.expose())),
+            hex::encode(state.finalize()),
+        ))))
+    }
+
+    /// Looks up a cached result, skipping valid results for proofs that have since expired
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn cached_result(&mut self, key: &CacheKey, proof: &Proof) -> Result<Option<VerificationResult>> {
+        let Some(cache) = self.cache.as_mut() else {
+            return Ok(None);
+        };
+        let result = match cache.retrieve::<VerificationResult>(key).await {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("Verification cache lookup failed: {}", e);
+                None
+            }
+        };
+
+        match result {
+            Some(result) if result.is_valid && !self.verify_proof_structure(proof)? => Ok(None),
+            result => Ok(result),
+        }
+    }
+
+    /// Stores a result in the cache, creating the cache on first use
+    ///
+    /// The cache is kept in memory only, so results cannot be planted on disk
+    /// by another process.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn cache_result(&mut self, key: CacheKey, result: &VerificationResult) {
+        if self.cache.is_none() {
+            let config = CacheConfig {
+                disk_cache_enabled: false,
+                entry_ttl_seconds: self.config.result_cache_ttl_seconds,
+                compression_enabled: false,
+                ..CacheConfig::default()
+            };
+            match CacheManager::with_config(config).await {
+                Ok(cache) => self.cache = Some(cache),
+                Err(e) => {
+                    warn!("Verification cache unavailable: {}", e);
+                    return;
+                }
+            }
+        }
+
+        if let Some(cache) = self.cache.as_mut() {
+            if let Err(e) = cache.store(key, result).await {
+                warn!("Failed to cache verification result: {}", e);
+            }
+        }
+    }
+
+    /// Verifies a proof against claimed content on the current thread
+    ///
+    /// This is the synchronous counterpart of [`verify_detailed`](Self::verify_detailed)
+    /// and needs no async runtime, for CLI tools and WASM hosts.
+    #[instrument(skip(self, proof, claimed_content), fields(proof_id = %proof.id))]
+    pub fn verify_blocking(
         &mut self,
         proof: &Proof,
         claimed_content: &[u8],
     ) -> Result<VerificationResult> {
+        self.run_checks(proof, ContentCheck::Claimed(claimed_content))
+    }
+
+    /// Verifies a proof against claimed content read from `reader`
+    ///
+    /// Runs the same checks as [`verify_blocking`](Self::verify_blocking), but
+    /// hashes the content as it is read, so content too large to hold in
+    /// memory can be verified, for example straight from a file.
+    #[instrument(skip(self, proof, reader), fields(proof_id = %proof.id))]
+    pub fn verify_from_reader(&mut self, proof: &Proof, mut reader: impl std::io::Read) -> Result<VerificationResult> {
+        let claimed_hash = proof.claimed_content_hash_from_reader(&mut reader)
+            .map_err(|e| ProofError::file_error("Failed to read claimed content", Some(e)))?;
+        self.run_checks(proof, ContentCheck::ClaimedHash(claimed_hash))
+    }
+
+    /// Verifies a committed proof without its content
+    ///
+    /// Runs the same checks as [`verify_blocking`](Self::verify_blocking),
+    /// except that the content hash check is replaced by a check that the proof
+    /// commits to its content. The receipt then proves that content satisfying
+    /// the selection exists in the file, without revealing it; see
+    /// [`crate::commitment`] for what the verifier does learn.
+    #[instrument(skip(self, proof), fields(proof_id = %proof.id))]
+    pub fn verify_commitment(&mut self, proof: &Proof) -> Result<VerificationResult> {
+        self.run_checks(proof, ContentCheck::Committed)
+    }
+
+    /// Verifies a committed proof and that `opening` opens its commitment,
+    /// disclosing the content to this verifier
+    #[instrument(skip(self, proof, opening), fields(proof_id = %proof.id))]
+    pub fn verify_opening(&mut self, proof: &Proof, opening: &ContentOpening) -> Result<VerificationResult> {
+        self.run_checks(proof, ContentCheck::Opening(opening))
+    }
+
+    /// Runs every check and reports the outcome to the event bus
+    fn run_checks(&mut self, proof: &Proof, content: ContentCheck<'_>) -> Result<VerificationResult> {
+        let result = self.check_all(proof, content);
+        if let Ok(result) = &result {
+            self.events.emit(MonitorEvent::VerificationCompleted { valid: result.is_valid });
+        }
+        result
+    }
+
+    fn check_all(&mut self, proof: &Proof, content: ContentCheck<'_>) -> Result<VerificationResult> {
         let start_time = Instant::now();
         let mut verification_steps = Vec::new();
         let mut warnings = Vec::new();
@@ -109,7 +619,7 @@ impl ProofVerifier {
         
         // Step 1: Basic proof structure validation
         let step_start = Instant::now();
-        let structure_valid = self.verify_proof_structure(proof).await?;
+        let structure_valid = self.verify_proof_structure(proof)?;
         verification_steps.push(VerificationStep {
             name: "Proof Structure Validation".to_string(),
             passed: structure_valid,
@@ -123,15 +633,31 @@ impl ProofVerifier {
         
         if !structure_valid {
             return Ok(self.create_verification_result(
-                false, start_time, verification_steps, warnings
+                proof, Some(self.structure_failure(proof)), start_time, verification_steps, warnings
             ));
         }
         
-        // Step 2: Cryptographic proof verification
+        // Step 2: Cryptographic proof verification, against a trusted guest image
+        if let Some(untrusted) = proof.chain().find(|proof| !self.trusts_image_id(proof)) {
+            verification_steps.push(VerificationStep {
+                name: "Guest Image Verification".to_string(),
+                passed: false,
+                duration_ms: 0,
+                details: untrusted.image_id.map(|image_id| {
+                    format!("Proof was generated by untrusted guest image {}", risc0_zkvm::sha::Digest::from(image_id))
+                }),
+            });
+            return Ok(self.create_verification_result(
+                proof, Some(VerificationFailure::UnknownImageId), start_time, verification_steps, warnings
+            ));
+        }
+
         let step_start = Instant::now();
-        let crypto_valid = self.verify_cryptographic_proof(proof).await?;
+        let Some(crypto_valid) = self.run_step(proof, Self::verify_cryptographic_proof)? else {
+            return Ok(self.step_timed_out(proof, CRYPTO_STEP, start_time, verification_steps, warnings));
+        };
         verification_steps.push(VerificationStep {
-            name: "Cryptographic Proof Verification".to_string(),
+            name: CRYPTO_STEP.to_string(),
             passed: crypto_valid,
             duration_ms: step_start.elapsed().as_millis() as u64,
             details: if crypto_valid { 
@@ -143,34 +669,117 @@ impl ProofVerifier {
         
         if !crypto_valid {
             return Ok(self.create_verification_result(
-                false, start_time, verification_steps, warnings
+                proof, Some(VerificationFailure::CryptoInvalid), start_time, verification_steps, warnings
             ));
         }
+
+        // Step 3: Journal hash verification
+        let step_start = Instant::now();
+        let journal_failure = self.verify_journal_hashes(proof)?;
+        verification_steps.push(VerificationStep {
+            name: "Journal Hash Verification".to_string(),
+            passed: journal_failure.is_none(),
+            duration_ms: step_start.elapsed().as_millis() as u64,
+            details: journal_failure.as_ref()
+                .map(|_| "Proof hashes or selection do not match the receipt journal".to_string()),
+        });
+
+        if journal_failure.is_some() {
+            return Ok(self.create_verification_result(
+                proof, journal_failure, start_time, verification_steps, warnings
+            ));
+        }
+
+        // Step 3b: Chain linkage verification (extended proofs only)
+        if proof.metadata.extends.is_some() {
+            let step_start = Instant::now();
+            let linked = proof.is_chain_linked();
+            verification_steps.push(VerificationStep {
+                name: "Chain Linkage Verification".to_string(),
+                passed: linked,
+                duration_ms: step_start.elapsed().as_millis() as u64,
+                details: if linked {
+                    None
+                } else {
+                    Some("Proof hashes do not chain from the extended proof".to_string())
+                },
+            });
+
+            if !linked {
+                return Ok(self.create_verification_result(
+                    proof, Some(VerificationFailure::ChainLinkMismatch), start_time, verification_steps, warnings
+                ));
+            }
+        }
+        
+        // Step 3c: Signature verification (signed proofs only)
+        if proof.signature.is_some() {
+            let step_start = Instant::now();
+            let signature_valid = proof.verify_signature()?;
+            verification_steps.push(VerificationStep {
+                name: SIGNATURE_STEP.to_string(),
+                passed: signature_valid,
+                duration_ms: step_start.elapsed().as_millis() as u64,
+                details: if signature_valid {
+                    None
+                } else {
+                    Some("Signature does not cover this proof for its signer".to_string())
+                },
+            });
+
+            if !signature_valid {
+                return Ok(self.create_verification_result(
+                    proof, Some(VerificationFailure::SignatureInvalid), start_time, verification_steps, warnings
+                ));
+            }
+        }
         
-        // Step 3: Content hash verification
+        // Step 4: Content hash verification, or the commitment in its place
         let step_start = Instant::now();
-        let content_valid = self.verify_content_hash(proof, claimed_content)?;
+        let (step_name, content_valid, details, failure) = match content {
+            ContentCheck::Claimed(claimed_content) => (
+                "Content Hash Verification",
+                self.verify_content_hash(proof, claimed_content)?,
+                "Content hash mismatch",
+                VerificationFailure::ContentHashMismatch,
+            ),
+            ContentCheck::ClaimedHash(claimed_hash) => (
+                "Content Hash Verification",
+                claimed_hash == Some(proof.content_hash),
+                "Content hash mismatch",
+                VerificationFailure::ContentHashMismatch,
+            ),
+            ContentCheck::Committed => (
+                "Commitment Verification",
+                proof.metadata.content_committed,
+                "Proof does not commit to its content",
+                VerificationFailure::MalformedProof,
+            ),
+            ContentCheck::Opening(opening) => (
+                "Commitment Opening Verification",
+                opening.opens(proof),
+                "Opening does not match the proof's commitment",
+                VerificationFailure::ContentHashMismatch,
+            ),
+        };
         verification_steps.push(VerificationStep {
-            name: "Content Hash Verification".to_string(),
+            name: step_name.to_string(),
             passed: content_valid,
             duration_ms: step_start.elapsed().as_millis() as u64,
-            details: if content_valid { 
-                None 
-            } else { 
-                Some("Content hash mismatch".to_string()) 
-            },
+            details: if content_valid { None } else { Some(details.to_string()) },
         });
         
         if !content_valid {
             return Ok(self.create_verification_result(
-                false, start_time, verification_steps, warnings
+                proof, Some(failure), start_time, verification_steps, warnings
             ));
         }
         
-        // Step 4: Metadata verification (if enabled)
+        // Step 5: Metadata verification (if enabled)
         if self.config.verify_metadata {
             let step_start = Instant::now();
-            let (metadata_valid, metadata_warnings) = self.verify_metadata(proof)?;
+            let (metadata_failure, metadata_warnings) = self.verify_metadata(proof)?;
+            let metadata_valid = metadata_failure.is_none();
             warnings.extend(metadata_warnings);
             verification_steps.push(VerificationStep {
                 name: "Metadata Verification".to_string(),
@@ -185,18 +794,21 @@ impl ProofVerifier {
             
             if self.config.strict_verification && !metadata_valid {
                 return Ok(self.create_verification_result(
-                    false, start_time, verification_steps, warnings
+                    proof, metadata_failure, start_time, verification_steps, warnings
                 ));
             }
         }
         
-        // Step 5: Custom rules verification
-        if !self.config.custom_rules.is_empty() {
+        // Step 6: Custom rules verification
+        if !self.config.custom_rules.is_empty() || !self.config.closure_rules.is_empty() {
             let step_start = Instant::now();
-            let (rules_valid, rules_warnings) = self.verify_custom_rules(proof)?;
+            let Some((rules_failure, rules_warnings)) = self.run_step(proof, Self::verify_custom_rules)? else {
+                return Ok(self.step_timed_out(proof, CUSTOM_RULES_STEP, start_time, verification_steps, warnings));
+            };
+            let rules_valid = rules_failure.is_none();
             warnings.extend(rules_warnings);
             verification_steps.push(VerificationStep {
-                name: "Custom Rules Verification".to_string(),
+                name: CUSTOM_RULES_STEP.to_string(),
                 passed: rules_valid,
                 duration_ms: step_start.elapsed().as_millis() as u64,
                 details: if rules_valid { 
@@ -208,14 +820,14 @@ impl ProofVerifier {
             
             if self.config.strict_verification && !rules_valid {
                 return Ok(self.create_verification_result(
-                    false, start_time, verification_steps, warnings
+                    proof, rules_failure, start_time, verification_steps, warnings
                 ));
             }
         }
         
         // All verifications passed
         let result = self.create_verification_result(
-            true, start_time, verification_steps, warnings
+            proof, None, start_time, verification_steps, warnings
         );
         
         // Update statistics
@@ -239,6 +851,29 @@ impl ProofVerifier {
         Ok(result.is_valid)
     }
 
+    /// Checks that a proof refers to the content addressed by `cid`, without
+    /// the content itself
+    ///
+    /// The proof must come from a trusted guest image, its receipt must verify
+    /// and `cid` must be the raw SHA-256 CID whose
+    /// digest the guest committed in its journal: that of the file for a whole
+    /// DAG, or of the root block for a partial one. Other CIDs, and extended
+    /// proofs, whose receipts each cover only part of the file, never match.
+    /// The selected content is not checked, so this says nothing about the
+    /// selection's plaintext; use [`ProofVerifier::verify_detailed`] with the
+    /// claimed bytes for that.
+    pub fn verify_against_cid(&self, proof: &Proof, cid: &Cid) -> Result<bool> {
+        if cid.codec() != RAW_CODEC || cid.hash().code() != SHA2_256_CODE || proof.metadata.extends.is_some() {
+            return Ok(false);
+        }
+        // The receipt is only checked against the image ID the proof names, so
+        // a receipt from another guest could commit any digest
+        if proof.chain().any(|proof| !self.trusts_image_id(proof)) || !self.verify_receipt(proof)? {
+            return Ok(false);
+        }
+        Ok(Self::decode_journal(proof)?.cid_digest[..] == *cid.hash().digest())
+    }
+
     /// Verifies multiple proofs in batch
     pub async fn verify_batch(
         &mut self,
@@ -254,8 +889,74 @@ impl ProofVerifier {
         Ok(results)
     }
 
+    /// Checks that a proof is well-formed without verifying its receipt
+    ///
+    /// This is a cheap triage check: a proof that passes may still fail
+    /// verification, but one that fails cannot pass it.
+    pub fn check_structure(proof: &Proof) -> StructureReport {
+        let mut issues = Vec::new();
+
+        let has_required_fields = !proof.id.is_empty()
+            && !proof.version.is_empty()
+            && !proof.zk_proof.receipt.is_empty();
+        if !has_required_fields {
+            issues.push("Missing required fields: id, version or receipt".to_string());
+        }
+
+        let valid_inputs = proof.zk_proof.public_inputs == proof.content_hash;
+        if !valid_inputs {
+            issues.push(format!(
+                "Public inputs are {} bytes, not the 32-byte content hash",
+                proof.zk_proof.public_inputs.len()
+            ));
+        }
+        let valid_signature = proof.signature.as_ref().map_or(true, |signature| signature.signature.len() == 64);
+        if !valid_signature {
+            issues.push("Signature is not a 64-byte Ed25519 signature".to_string());
+        }
+        let valid_hashes = valid_inputs && valid_signature;
+
+        let valid_selection = match proof.content_selection.validate() {
+            Ok(()) => true,
+            Err(e) => {
+                issues.push(format!("Invalid content selection: {}", e));
+                false
+            }
+        };
+
+        let proof_size = proof.metadata.performance.proof_size_bytes.get();
+        let proof_size_reasonable = (1024..=100 * 1024 * 1024).contains(&proof_size);
+        if !proof_size_reasonable {
+            issues.push("Proof size seems unreasonable".to_string());
+        }
+
+        let now = Utc::now();
+        let timestamp_reasonable = proof.created_at <= now && (now - proof.created_at).num_days() <= 365;
+        if !timestamp_reasonable {
+            issues.push("Timestamp seems unreasonable".to_string());
+        }
+
+        if proof.metadata.security.security_level < 128 {
+            issues.push("Security level below recommended minimum".to_string());
+        }
+
+        StructureReport {
+            valid_structure: has_required_fields
+                && valid_hashes
+                && valid_selection
+                && proof_size_reasonable
+                && timestamp_reasonable,
+            has_required_fields,
+            valid_hashes,
+            valid_selection,
+            proof_size_reasonable,
+            timestamp_reasonable,
+            issues,
+        }
+    }
+
     /// Verifies the basic structure of a proof
-    async fn verify_proof_structure(&self, proof: &Proof) -> Result<bool> {
+    fn verify_proof_structure(&self, proof: &Proof) -> Result<bool> {
         // Check proof version compatibility
         if proof.version.is_empty() {
             return Ok(false);
@@ -269,6 +970,14 @@ impl ProofVerifier {
             }
         }
         
+        // Check the proof's own validity window
+        if let Some(valid_until) = proof.metadata.valid_until {
+            if Utc::now() > valid_until {
+                warn!("Proof {} expired at {}", proof.id, valid_until);
+                return Ok(false);
+            }
+        }
+        
         // Check ZK proof data structure
         if proof.zk_proof.receipt.is_empty() {
             return Ok(false);
@@ -287,17 +996,141 @@ impl ProofVerifier {
         Ok(true)
     }
 
-    /// Verifies the cryptographic proof using Risc0
-    async fn verify_cryptographic_proof(&self, proof: &Proof) -> Result<bool> {
-        // Deserialize the receipt
-        let receipt: Receipt = bincode::deserialize(&proof.zk_proof.receipt)
-            .map_err(|e| ProofError::serialization_error(
-                "Failed to deserialize receipt",
-                Some(Box::new(e))
-            ))?;
+    /// Returns a verifier with this one's configuration, statistics and
+    /// registry, for running checks on another thread
+    fn detached(&self) -> ProofVerifier {
+        ProofVerifier {
+            config: self.config.clone(),
+            stats: self.stats.clone(),
+            registry: self.registry.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            cache: None,
+            events: self.events.clone(),
+        }
+    }
+
+    /// Runs a verification step, or returns `None` if it exceeds `step_timeout`
+    ///
+    /// With a timeout the step runs on its own thread. A step that times out
+    /// cannot be interrupted: it finishes in the background and its result is
+    /// discarded. On wasm32 steps always run to completion on the calling thread.
+    fn run_step<T: Send + 'static>(
+        &self,
+        proof: &Proof,
+        step: fn(&ProofVerifier, &Proof) -> Result<T>,
+    ) -> Result<Option<T>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(timeout) = self.config.step_timeout {
+            let worker = self.detached();
+            let proof = proof.clone();
+            let (sender, receiver) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = sender.send(step(&worker, &proof));
+            });
+            return match receiver.recv_timeout(timeout) {
+                Ok(result) => result.map(Some),
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Ok(None),
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => Err(ProofError::internal_error(
+                    "Verification step panicked",
+                    None,
+                )),
+            };
+        }
+        step(self, proof).map(Some)
+    }
+
+    /// Creates the result of a verification whose step `step_name` timed out
+    fn step_timed_out(
+        &self,
+        proof: &Proof,
+        step_name: &str,
+        start_time: Instant,
+        mut verification_steps: Vec<VerificationStep>,
+        warnings: Vec<String>,
+    ) -> VerificationResult {
+        let timeout = self.config.step_timeout.unwrap_or_default();
+        warn!("Verification step '{}' exceeded {}ms", step_name, timeout.as_millis());
+        verification_steps.push(VerificationStep {
+            name: step_name.to_string(),
+            passed: false,
+            duration_ms: timeout.as_millis() as u64,
+            details: Some(format!("Step did not finish within {}ms", timeout.as_millis())),
+        });
+        self.create_verification_result(
+            proof,
+            Some(VerificationFailure::StepTimeout(step_name.to_string())),
+            start_time,
+            verification_steps,
+            warnings,
+        )
+    }
+
+    /// Verifies the cryptographic proof using Risc0, including the receipts of
+    /// every proof an extended proof builds on
+    fn verify_cryptographic_proof(&self, proof: &Proof) -> Result<bool> {
+        for proof in proof.chain() {
+            if !self.verify_receipt(proof)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Whether the guest image a proof records is one this verifier trusts
+    ///
+    /// Proofs that record no image ID are checked against the registered guest.
+    pub(crate) fn trusts_image_id(&self, proof: &Proof) -> bool {
+        match proof.image_id {
+            None => true,
+            Some(image_id) => self.scheme_for(proof).is_some_and(|scheme| scheme.image_id == image_id)
+                || self.config.trusted_image_ids.contains(&image_id),
+        }
+    }
+
+    /// Verifies a single proof's receipt against the image of the guest that produced it
+    fn verify_receipt(&self, proof: &Proof) -> Result<bool> {
+        let image_id = match (proof.image_id, self.scheme_for(proof)) {
+            (Some(image_id), _) => image_id,
+            (None, Some(scheme)) => scheme.image_id,
+            (None, None) => {
+                warn!("No proof scheme registered for {:?}", proof.proof_type);
+                return Ok(false);
+            }
+        };
+
+        // An undecodable receipt is an invalid proof rather than an operational error
+        let receipt_bytes = match proof.zk_proof.receipt_bytes() {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!("Failed to decompress receipt: {}", e);
+                return Ok(false);
+            }
+        };
+        let receipt: Receipt = match bincode::deserialize(&receipt_bytes) {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                warn!("Failed to deserialize receipt: {}", e);
+                return Ok(false);
+            }
+        };
         
+        // The receipt must be of the kind recorded when the proof was generated
+        let kind_matches = match proof.zk_proof.receipt_kind() {
+            Some(ReceiptKind::Composite) => receipt.inner.composite().is_ok(),
+            Some(ReceiptKind::Succinct) => receipt.inner.succinct().is_ok(),
+            Some(ReceiptKind::Groth16) => receipt.inner.groth16().is_ok(),
+            None => false,
+        };
+        if !kind_matches {
+            warn!(
+                "Receipt does not match recorded format version: {}",
+                proof.zk_proof.format_version
+            );
+            return Ok(false);
+        }
+
         // Verify the receipt against the expected image ID
-        match receipt.verify(IPFS_CONTENT_VERIFIER_ID) {
+        match receipt.verify(image_id) {
             Ok(_) => {
                 debug!("Cryptographic proof verification successful");
                 Ok(true)
@@ -309,10 +1142,69 @@ impl ProofVerifier {
         }
     }
 
+    /// Checks the proof's root and content hashes against those committed in the receipt journal
+    ///
+    /// Each proof in an extension chain is checked against its own receipt.
+    pub(crate) fn verify_journal_hashes(&self, proof: &Proof) -> Result<Option<VerificationFailure>> {
+        for proof in proof.chain() {
+            let output = Self::decode_journal(proof)?;
+
+            let (root_hash, content_hash) = proof.journal_hashes();
+            if output.hash_algorithm != proof.metadata.security.hash_algorithm {
+                return Ok(Some(VerificationFailure::ContentHashMismatch));
+            }
+            if output.root_hash != root_hash {
+                return Ok(Some(VerificationFailure::RootHashMismatch));
+            }
+            if output.content_hash != content_hash {
+                return Ok(Some(VerificationFailure::ContentHashMismatch));
+            }
+            if output.selection_hash != proof.content_selection.selection_hash() {
+                return Ok(Some(VerificationFailure::SelectionMismatch));
+            }
+            if output.inclusion_paths.len() != proof.content_selection.inclusion_path_count() {
+                return Ok(Some(VerificationFailure::MalformedProof));
+            }
+            // The guest only proves ranges it found inside the content
+            if proof.content_selection.range_end().is_some_and(|end| end as u64 > output.content_length) {
+                return Ok(Some(VerificationFailure::MalformedProof));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Decodes the guest output committed in a proof's receipt journal
+    fn decode_journal(proof: &Proof) -> Result<ProofOutput> {
+        let receipt: Receipt = bincode::deserialize(&proof.zk_proof.receipt_bytes()?)
+            .map_err(|e| ProofError::serialization_error(
+                "Failed to deserialize receipt",
+                Some(Box::new(e))
+            ))?;
+        receipt.journal.decode()
+            .map_err(|e| ProofError::serialization_error(
+                "Failed to decode proof output",
+                Some(Box::new(e))
+            ))
+    }
+
+    /// Classifies why a proof failed structure validation
+    fn structure_failure(&self, proof: &Proof) -> VerificationFailure {
+        let now = Utc::now();
+        let past_own_expiry = proof.metadata.valid_until.is_some_and(|valid_until| now > valid_until);
+        let past_max_age = self.config.max_proof_age_seconds.is_some_and(|max_age| {
+            now.signed_duration_since(proof.created_at).num_seconds() > max_age as i64
+        });
+
+        if past_own_expiry || past_max_age {
+            VerificationFailure::Expired
+        } else {
+            VerificationFailure::MalformedProof
+        }
+    }
+
     /// Verifies that the claimed content matches the proof
     fn verify_content_hash(&self, proof: &Proof, claimed_content: &[u8]) -> Result<bool> {
-        let claimed_hash = Sha256::digest(claimed_content);
-        let matches = claimed_hash.as_slice() == proof.content_hash.as_slice();
+        let matches = proof.claimed_content_hash(claimed_content) == Some(proof.content_hash);
         
         if matches {
             debug!("Content hash verification successful");
@@ -323,33 +1215,25 @@ impl ProofVerifier {
         Ok(matches)
     }
 
-    /// Verifies proof metadata
-    fn verify_metadata(&self, proof: &Proof) -> Result<(bool, Vec<String>)> {
+    /// Verifies proof metadata, returning the failure (if any) and warnings
+    fn verify_metadata(&self, proof: &Proof) -> Result<(Option<VerificationFailure>, Vec<String>)> {
         let mut warnings = Vec::new();
-        let mut is_valid = true;
+        let mut failure = None;
         
-        // Check security level
-        if proof.metadata.security.security_level < 128 {
-            warnings.push("Security level below recommended minimum (128 bits)".to_string());
-            if self.config.strict_verification {
-                is_valid = false;
-            }
-        }
-        
-        // Check proof system
-        if proof.metadata.security.proof_system != "Risc0" {
-            warnings.push("Unexpected proof system".to_string());
+        // Check the proof system, its version and the security level
+        if let Some((system_failure, warning)) = self.accepted_system_failure(&proof.metadata.security) {
+            warnings.push(warning);
             if self.config.strict_verification {
-                is_valid = false;
+                failure = Some(system_failure);
             }
         }
         
         // Check performance metrics for anomalies
-        if proof.metadata.performance.generation_time_ms > 3600000 { // 1 hour
+        if proof.metadata.performance.generation_time_ms > Millis(3_600_000) { // 1 hour
             warnings.push("Unusually long proof generation time".to_string());
         }
         
-        if proof.metadata.performance.proof_size_bytes > 100 * 1024 * 1024 { // 100MB
+        if proof.metadata.performance.proof_size_bytes > Bytes(100 * 1024 * 1024) { // 100MB
             warnings.push("Unusually large proof size".to_string());
         }
         
@@ -357,17 +1241,55 @@ impl ProofVerifier {
         if proof.metadata.file_info.block_count == 0 {
             warnings.push("No IPFS blocks in file info".to_string());
             if self.config.strict_verification {
-                is_valid = false;
+                failure.get_or_insert(VerificationFailure::MalformedProof);
             }
         }
         
-        Ok((is_valid, warnings))
+        Ok((failure, warnings))
     }
 
-    /// Verifies custom rules
-    fn verify_custom_rules(&self, proof: &Proof) -> Result<(bool, Vec<String>)> {
+    /// Returns why `security` matches no accepted system, with a warning
+    /// naming the problem, or `None` if it matches one
+    fn accepted_system_failure(&self, security: &SecurityParameters) -> Option<(VerificationFailure, String)> {
+        let accepted = &self.config.accepted_systems;
+        if accepted.is_empty() {
+            return None;
+        }
+
+        let same_system: Vec<&AcceptedSystem> = accepted.iter()
+            .filter(|system| system.proof_system == security.proof_system)
+            .collect();
+        if same_system.is_empty() {
+            return Some((
+                VerificationFailure::MalformedProof,
+                format!("Unexpected proof system {}", security.proof_system),
+            ));
+        }
+
+        let current: Vec<&AcceptedSystem> = same_system.into_iter()
+            .filter(|system| system.accepts_version(&security.risc0_version))
+            .collect();
+        if current.is_empty() {
+            return Some((
+                VerificationFailure::MalformedProof,
+                format!("{} version {} is older than accepted", security.proof_system, security.risc0_version),
+            ));
+        }
+
+        let min_security = current.iter().map(|system| system.min_security).min().unwrap_or(0);
+        if security.security_level < min_security {
+            return Some((
+                VerificationFailure::SecurityLevelTooLow,
+                format!("Security level below accepted minimum ({} bits)", min_security),
+            ));
+        }
+        None
+    }
+
+    /// Verifies custom rules, returning the first failure (if any) and warnings
+    fn verify_custom_rules(&self, proof: &Proof) -> Result<(Option<VerificationFailure>, Vec<String>)> {
         let mut warnings = Vec::new();
-        let mut is_valid = true;
+        let mut failure = None;
         
         for rule in &self.config.custom_rules {
             let rule_result = match &rule.rule_type {
@@ -380,11 +1302,27 @@ impl ProofVerifier {
                 VerificationRuleType::RequiredProofSystem(required_system) => {
                     proof.metadata.security.proof_system == *required_system
                 }
-                VerificationRuleType::Custom(validator) => {
-                    match validator(proof) {
-                        Ok(result) => result,
+                VerificationRuleType::RequiredSigner(signer) => {
+                    let signed_by = proof.signature.as_ref()
+                        .is_some_and(|signature| hex::encode(signature.signer_public_key).eq_ignore_ascii_case(signer));
+                    signed_by && proof.verify_signature()?
+                }
+                VerificationRuleType::RequireSignature => proof.verify_signature()?,
+                VerificationRuleType::RequiredTimestampAuthority(authority) => {
+                    let key = hex::decode(authority).ok()
+                        .and_then(|key| <[u8; 32]>::try_from(key).ok())
+                        .and_then(|key| VerifyingKey::from_bytes(&key).ok())
+                        .ok_or_else(|| ProofError::invalid_input_error(
+                            "timestamp_authority",
+                            format!("Invalid timestamp authority key: {}", authority)
+                        ))?;
+                    match proof.verify_timestamp(&key) {
+                        Ok(stamped_at) => {
+                            debug!("Proof {} timestamped at {}", proof.id, stamped_at);
+                            true
+                        }
                         Err(e) => {
-                            warnings.push(format!("Custom rule '{}' failed: {}", rule.name, e));
+                            warnings.push(format!("Timestamp check failed: {}", e));
                             false
                         }
                     }
@@ -394,24 +1332,55 @@ impl ProofVerifier {
             if !rule_result {
                 warnings.push(format!("Custom rule '{}' failed: {}", rule.name, rule.description));
                 if self.config.strict_verification {
-                    is_valid = false;
+                    failure.get_or_insert_with(|| match rule.rule_type {
+                        VerificationRuleType::MinSecurityLevel(_) => VerificationFailure::SecurityLevelTooLow,
+                        _ => VerificationFailure::CustomRule(rule.name.clone()),
+                    });
+                }
+            }
+        }
+
+        for rule in &self.config.closure_rules {
+            let rule_result = match (rule.check)(proof) {
+                Ok(result) => result,
+                Err(e) => {
+                    warnings.push(format!("Custom rule '{}' failed: {}", rule.name, e));
+                    false
+                }
+            };
+
+            if !rule_result {
+                warnings.push(format!("Custom rule '{}' failed: {}", rule.name, rule.description));
+                if self.config.strict_verification {
+                    failure.get_or_insert_with(|| VerificationFailure::CustomRule(rule.name.clone()));
                 }
             }
         }
         
-        Ok((is_valid, warnings))
+        Ok((failure, warnings))
     }
 
     /// Creates a verification result
     fn create_verification_result(
         &self,
-        is_valid: bool,
+        proof: &Proof,
+        failure: Option<VerificationFailure>,
         start_time: Instant,
         verification_steps: Vec<VerificationStep>,
         warnings: Vec<String>,
     ) -> VerificationResult {
+        if let Some(failure) = &failure {
+            debug!("Proof verification failed: {}", failure);
+        }
+
+        let mut timings = HashMap::new();
+        for step in &verification_steps {
+            *timings.entry(step.name.clone()).or_insert(0) += step.duration_ms;
+        }
+
         VerificationResult {
-            is_valid,
+            is_valid: failure.is_none(),
+            failure,
             verified_at: Utc::now(),
             verification_time_ms: start_time.elapsed().as_millis() as u64,
             verifier_info: VerifierInfo {
@@ -420,11 +1389,20 @@ impl ProofVerifier {
                 environment: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
             },
             warnings,
+            checks_passed: verification_steps.iter().filter(|step| step.passed).count() as u32,
+            checks_run: verification_steps.len() as u32,
+            security_level: Some(proof.metadata.security.security_level),
+            proof_created_at: Some(proof.created_at),
+            signature_verified: verification_steps.iter()
+                .any(|step| step.name == SIGNATURE_STEP && step.passed),
+            transparency_verified: false,
+            timings,
             verification_steps: if self.config.include_verification_steps {
                 verification_steps
             } else {
                 Vec::new()
             },
+            from_cache: false,
         }
     }
 
@@ -451,6 +1429,15 @@ impl ProofVerifier {
 
     /// Updates the verification configuration
     pub fn update_config(&mut self, config: VerificationConfig) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(cache) = self.cache.as_mut() {
+            cache.update_config(CacheConfig {
+                disk_cache_enabled: false,
+                entry_ttl_seconds: config.result_cache_ttl_seconds,
+                compression_enabled: false,
+                ..CacheConfig::default()
+            });
+        }
         self.config = config;
     }
 
@@ -465,14 +1452,15 @@ impl ProofVerifier {
     }
 }
 
-#[cfg(test)]
-mod tests {
+#[cfg(all(test, not(target_arch = "wasm32")))]
+pub(crate) mod tests {
     use super::*;
     use crate::types::*;
     use chrono::Utc;
+    use sha2::Digest;
     use uuid::Uuid;
 
-    fn create_test_proof() -> Proof {
+    pub(crate) fn create_test_proof() -> Proof {
         Proof {
             id: Uuid::new_v4().to_string(),
             zk_proof: ZkProofData {
@@ -489,25 +1477,29 @@ mod tests {
                 },
                 file_info: FileInfo {
                     filename: Some("test.txt".to_string()),
-                    size: 100,
+                    size: Bytes(100),
                     mime_type: Some("text/plain".to_string()),
                     file_hash: [0; 32],
                     ipfs_cid: "QmTest".to_string(),
                     block_count: 1,
-                    avg_block_size: 100,
+                    avg_block_size: Bytes(100),
+                    source_compression: None,
+                    chunk_size: None,
                 },
                 performance: PerformanceMetrics {
-                    generation_time_ms: 1000,
-                    file_processing_time_ms: 100,
-                    zk_generation_time_ms: 900,
-                    peak_memory_bytes: 1024 * 1024,
+                    generation_time_ms: Millis(1000),
+                    file_processing_time_ms: Millis(100),
+                    zk_generation_time_ms: Millis(900),
+                    peak_memory_bytes: Bytes(1024 * 1024),
                     zk_cycles: 10000,
-                    proof_size_bytes: 1024,
+                    proof_size_bytes: Bytes(1024),
                     compression_ratio: None,
+                    segment_cycles: Vec::new(),
                 },
                 security: SecurityParameters {
                     security_level: 128,
                     hash_function: "SHA-256".to_string(),
+                    hash_algorithm: HashAlgorithm::Sha256,
                     proof_system: "Risc0".to_string(),
                     risc0_version: "1.2".to_string(),
                     formal_verification: false,
@@ -521,12 +1513,21 @@ mod tests {
                     git_commit: None,
                 },
                 custom: std::collections::HashMap::new(),
+                valid_until: None,
+                renewed_from: Vec::new(),
+                extends: None,
+                reproducible: false,
+                content_committed: false,
             },
             content_selection: ContentSelection::Pattern { content: b"test".to_vec() },
             content_hash: [0; 32],
             root_hash: [1; 32],
             created_at: Utc::now(),
             version: "0.1.0".to_string(),
+            proof_type: ProofType::ContentExistence,
+            image_id: None,
+            signature: None,
+            timestamp: None,
         }
     }
 
@@ -536,19 +1537,68 @@ mod tests {
         assert_eq!(verifier.stats.total_verifications, 0);
     }
 
+    #[test]
+    fn test_dispatch_by_proof_type() {
+        let mut verifier = ProofVerifier::new();
+        let mock_image_id = [7u32; 8];
+        verifier.register_proof_type(
+            ProofType::TimestampProof,
+            ProofScheme::new(Vec::new(), mock_image_id, |_| Ok(Vec::new())),
+        );
+
+        let proof = create_test_proof();
+        assert_eq!(verifier.scheme_for(&proof).unwrap().image_id, crate::IPFS_CONTENT_VERIFIER_ID);
+
+        let mut timestamp_proof = proof.clone();
+        timestamp_proof.proof_type = ProofType::TimestampProof;
+        assert_eq!(verifier.scheme_for(&timestamp_proof).unwrap().image_id, mock_image_id);
+
+        let mut unregistered = proof;
+        unregistered.proof_type = ProofType::DataRange;
+        assert!(verifier.scheme_for(&unregistered).is_none());
+        assert!(!verifier.verify_receipt(&unregistered).unwrap());
+    }
+
     #[test]
     fn test_proof_structure_validation() {
         let verifier = ProofVerifier::new();
         let proof = create_test_proof();
         
         // This should pass basic structure validation
-        // Note: We can't test the full async method in a sync test,
-        // but we can test the structure validation logic
+        assert!(verifier.verify_proof_structure(&proof).unwrap());
         assert!(proof.content_selection.is_valid());
         assert_eq!(proof.content_hash.len(), 32);
         assert_eq!(proof.root_hash.len(), 32);
     }
 
+    #[test]
+    fn test_check_structure_flags_wrong_hash_length() {
+        let mut proof = create_test_proof();
+        proof.zk_proof.public_inputs = proof.content_hash.to_vec();
+        let report = ProofVerifier::check_structure(&proof);
+        assert!(report.valid_structure, "{:?}", report.issues);
+        assert!(report.issues.is_empty());
+
+        proof.zk_proof.public_inputs.pop();
+        let report = ProofVerifier::check_structure(&proof);
+        assert!(!report.valid_structure);
+        assert!(!report.valid_hashes);
+        assert!(report.has_required_fields && report.valid_selection);
+        assert!(report.issues[0].contains("31 bytes"));
+    }
+
+    #[test]
+    fn test_expired_proof_rejected() {
+        let verifier = ProofVerifier::new();
+        let mut proof = create_test_proof();
+
+        proof.metadata.valid_until = Some(Utc::now() + chrono::Duration::hours(1));
+        assert!(verifier.verify_proof_structure(&proof).unwrap());
+
+        proof.metadata.valid_until = Some(Utc::now() - chrono::Duration::seconds(1));
+        assert!(!verifier.verify_proof_structure(&proof).unwrap());
+    }
+
     #[test]
     fn test_content_hash_verification() {
         let verifier = ProofVerifier::new();
@@ -580,8 +1630,8 @@ mod tests {
         verifier.add_custom_rule(rule);
         
         let proof = create_test_proof();
-        let (is_valid, warnings) = verifier.verify_custom_rules(&proof).unwrap();
-        assert!(is_valid);
+        let (failure, warnings) = verifier.verify_custom_rules(&proof).unwrap();
+        assert!(failure.is_none());
         assert!(warnings.is_empty());
         
         // Test with insufficient security level
@@ -592,11 +1642,486 @@ mod tests {
         };
         verifier.add_custom_rule(rule);
         
-        let (is_valid, warnings) = verifier.verify_custom_rules(&proof).unwrap();
-        assert!(!is_valid);
+        let (failure, warnings) = verifier.verify_custom_rules(&proof).unwrap();
+        assert_eq!(failure, Some(VerificationFailure::SecurityLevelTooLow));
         assert!(!warnings.is_empty());
     }
 
+    #[test]
+    fn test_accepted_systems_enforce_min_version() {
+        let verifier = ProofVerifier::with_config(VerificationConfig {
+            accepted_systems: vec![AcceptedSystem {
+                proof_system: "Risc0".to_string(),
+                min_version: Some("1.2".to_string()),
+                min_security: 128,
+            }],
+            ..VerificationConfig::default()
+        });
+
+        let mut proof = create_test_proof();
+        for version in ["1.2", "1.2.0", "1.10.1", "2.0.0-rc.1"] {
+            proof.metadata.security.risc0_version = version.to_string();
+            let (failure, warnings) = verifier.verify_metadata(&proof).unwrap();
+            assert!(failure.is_none(), "version {} was rejected: {:?}", version, warnings);
+        }
+
+        proof.metadata.security.risc0_version = "1.1.9".to_string();
+        let (failure, warnings) = verifier.verify_metadata(&proof).unwrap();
+        assert_eq!(failure, Some(VerificationFailure::MalformedProof));
+        assert!(warnings.iter().any(|warning| warning.contains("older than accepted")));
+    }
+
+    #[test]
+    fn test_accepted_systems_reject_other_systems_and_weak_proofs() {
+        let verifier = ProofVerifier::new();
+        let mut proof = create_test_proof();
+        assert!(verifier.verify_metadata(&proof).unwrap().0.is_none());
+
+        proof.metadata.security.security_level = 96;
+        assert_eq!(verifier.verify_metadata(&proof).unwrap().0, Some(VerificationFailure::SecurityLevelTooLow));
+
+        proof.metadata.security.proof_system = "Plonky2".to_string();
+        assert_eq!(verifier.verify_metadata(&proof).unwrap().0, Some(VerificationFailure::MalformedProof));
+
+        // An empty allowlist accepts any system
+        let permissive = ProofVerifier::with_config(VerificationConfig {
+            accepted_systems: Vec::new(),
+            ..VerificationConfig::default()
+        });
+        assert!(permissive.verify_metadata(&proof).unwrap().0.is_none());
+    }
+
+    fn signer_rule(key: &crate::signing::SigningKey) -> VerificationRule {
+        VerificationRule {
+            name: "trusted_signer".to_string(),
+            description: "Signed by the trusted generator".to_string(),
+            rule_type: VerificationRuleType::RequiredSigner(hex::encode(key.verifying_key().to_bytes())),
+        }
+    }
+
+    #[test]
+    fn test_valid_signature() {
+        let key = crate::signing::SigningKey::from_bytes(&[7; 32]);
+        let mut proof = create_test_proof();
+        proof.metadata.custom.insert("b".to_string(), serde_json::json!(1));
+        proof.metadata.custom.insert("a".to_string(), serde_json::json!(0.1));
+        proof.sign(&key).unwrap();
+
+        assert!(proof.verify_signature().unwrap());
+        assert!(proof.is_signed_by(&key.verifying_key()).unwrap());
+
+        // Saving and reloading the proof keeps the signature valid
+        let reloaded = Proof::decode(serde_json::to_string(&proof).unwrap().as_bytes()).unwrap();
+        assert!(reloaded.verify_signature().unwrap());
+        let reloaded = Proof::decode(&proof.to_bytes().unwrap()).unwrap();
+        assert!(reloaded.verify_signature().unwrap());
+
+        let mut verifier = ProofVerifier::new();
+        verifier.add_custom_rule(signer_rule(&key));
+        let (failure, _) = verifier.verify_custom_rules(&proof).unwrap();
+        assert!(failure.is_none());
+    }
+
+    #[test]
+    fn test_signature_from_wrong_key() {
+        let key = crate::signing::SigningKey::from_bytes(&[7; 32]);
+        let other = crate::signing::SigningKey::from_bytes(&[8; 32]);
+        let mut proof = create_test_proof();
+        proof.sign(&key).unwrap();
+
+        assert!(!proof.is_signed_by(&other.verifying_key()).unwrap());
+        let mut verifier = ProofVerifier::new();
+        verifier.add_custom_rule(signer_rule(&other));
+        let (failure, _) = verifier.verify_custom_rules(&proof).unwrap();
+        assert_eq!(failure, Some(VerificationFailure::CustomRule("trusted_signer".to_string())));
+
+        // Claiming another signer does not make the signature theirs
+        proof.signature.as_mut().unwrap().signer_public_key = other.verifying_key().to_bytes();
+        assert!(!proof.verify_signature().unwrap());
+
+        let unsigned = create_test_proof();
+        assert!(!unsigned.verify_signature().unwrap());
+        let (failure, _) = verifier.verify_custom_rules(&unsigned).unwrap();
+        assert!(failure.is_some());
+    }
+
+    #[test]
+    fn test_tampered_proof_fails_signature() {
+        let key = crate::signing::SigningKey::from_bytes(&[7; 32]);
+        let mut proof = create_test_proof();
+        proof.sign(&key).unwrap();
+
+        let mut tampered = proof.clone();
+        tampered.content_hash[0] ^= 1;
+        assert!(!tampered.verify_signature().unwrap());
+
+        let mut tampered = proof.clone();
+        tampered.root_hash[0] ^= 1;
+        assert!(!tampered.verify_signature().unwrap());
+
+        let mut tampered = proof.clone();
+        tampered.metadata.security.security_level = 256;
+        assert!(!tampered.verify_signature().unwrap());
+
+        // The signature cannot be lifted onto another proof
+        let mut other = create_test_proof();
+        other.signature = proof.signature.clone();
+        assert!(!other.verify_signature().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_structural_failure_reasons() {
+        let mut verifier = ProofVerifier::new();
+
+        let mut expired = create_test_proof();
+        expired.metadata.valid_until = Some(Utc::now() - chrono::Duration::seconds(1));
+        let result = verifier.verify_detailed(&expired, b"test").await.unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::Expired));
+
+        let mut malformed = create_test_proof();
+        malformed.version = String::new();
+        let result = verifier.verify_detailed(&malformed, b"test").await.unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::MalformedProof));
+
+        // The dummy receipt cannot be decoded
+        let result = verifier.verify_detailed(&create_test_proof(), b"test").await.unwrap();
+        assert!(!result.is_valid);
+        assert_eq!(result.failure, Some(VerificationFailure::CryptoInvalid));
+    }
+
+    #[tokio::test]
+    async fn test_timings_collected_without_detailed_steps() {
+        let mut verifier = ProofVerifier::new();
+        let result = verifier.verify_detailed(&create_test_proof(), b"test").await.unwrap();
+
+        assert!(result.verification_steps.is_empty());
+        assert!(result.timings.contains_key("Proof Structure Validation"));
+        assert!(result.timings.contains_key("Cryptographic Proof Verification"));
+    }
+
+    #[tokio::test]
+    async fn test_receipt_failure_reasons() {
+        use std::io::Write;
+
+        let mut temp_file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(temp_file, "Content for failure reason tests").unwrap();
+        let claimed = b"failure reason";
+
+        let config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = crate::ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::Pattern { content: claimed.to_vec() }
+        ).await.unwrap();
+
+        let mut verifier = ProofVerifier::new();
+        let result = verifier.verify_detailed(&proof, claimed).await.unwrap();
+        assert!(result.is_valid);
+        assert_eq!(result.failure, None);
+
+        let result = verifier.verify_detailed(&proof, b"other content").await.unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::ContentHashMismatch));
+
+        let mut tampered = proof.clone();
+        tampered.root_hash = [7; 32];
+        let result = verifier.verify_detailed(&tampered, claimed).await.unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::RootHashMismatch));
+
+        let mut weak = proof.clone();
+        weak.metadata.security.security_level = 64;
+        let result = verifier.verify_detailed(&weak, claimed).await.unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::SecurityLevelTooLow));
+
+        verifier.add_custom_rule(VerificationRule {
+            name: "groth16_only".to_string(),
+            description: "Only Groth16 proof systems are accepted".to_string(),
+            rule_type: VerificationRuleType::RequiredProofSystem("Groth16".to_string()),
+        });
+        let result = verifier.verify_detailed(&proof, claimed).await.unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::CustomRule("groth16_only".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_slow_custom_rule_times_out() {
+        use std::io::Write;
+
+        let mut temp_file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(temp_file, "Content for step timeout tests").unwrap();
+        let claimed = b"step timeout";
+
+        let config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = crate::ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::Pattern { content: claimed.to_vec() }
+        ).await.unwrap();
+
+        let mut verifier = ProofVerifier::new();
+        let start = Instant::now();
+        assert!(verifier.verify_detailed(&proof, claimed).await.unwrap().is_valid);
+        // Long enough for every step but the slow rule
+        let step_timeout = (start.elapsed() * 4).max(Duration::from_secs(1));
+
+        let config = VerificationConfig {
+            step_timeout: Some(step_timeout),
+            ..VerificationConfig::default()
+        }.add_closure_rule("slow", "Takes longer than the step timeout", move |_: &Proof| {
+            std::thread::sleep(step_timeout * 10);
+            Ok(true)
+        });
+        let mut verifier = ProofVerifier::with_config(config);
+        let start = Instant::now();
+        let result = verifier.verify_detailed(&proof, claimed).await.unwrap();
+
+        assert!(!result.is_valid);
+        assert_eq!(result.failure, Some(VerificationFailure::StepTimeout(CUSTOM_RULES_STEP.to_string())));
+        assert!(start.elapsed() < step_timeout * 5);
+    }
+
+    #[tokio::test]
+    async fn test_offline_verification_of_self_contained_proof() {
+        use std::io::Write;
+
+        let mut temp_file = tempfile::NamedTempFile::new().unwrap();
+        temp_file.write_all(b"Shipment 4417 cleared customs on 2 March").unwrap();
+        let config = crate::ProofConfig { use_cache: false, ..crate::ProofConfig::default() };
+        let mut generator = crate::ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::Pattern { content: b"cleared customs".to_vec() }
+        ).await.unwrap();
+
+        let config = VerificationConfig { offline: true, ..VerificationConfig::default() };
+        let mut verifier = ProofVerifier::with_config(config.clone());
+        assert!(verifier.verify_detailed(&proof, b"cleared customs").await.unwrap().is_valid);
+
+        let err = config.ensure_online("On-chain verification").unwrap_err();
+        assert!(matches!(err, ProofError::ConfigurationError { .. }));
+        assert!(VerificationConfig::default().ensure_online("On-chain verification").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_verify_from_file_reader() {
+        use std::io::{Read, Write};
+
+        /// Reads a file, recording the largest read asked of it
+        struct Recording {
+            file: std::fs::File,
+            largest_read: usize,
+        }
+
+        impl Read for Recording {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.largest_read = self.largest_read.max(buf.len());
+                self.file.read(buf)
+            }
+        }
+
+        let content: Vec<u8> = (0..256 * 1024u32).map(|i| (i.wrapping_mul(2654435761) >> 11) as u8).collect();
+        let mut temp_file = tempfile::NamedTempFile::new().unwrap();
+        temp_file.write_all(&content).unwrap();
+        let selection = ContentSelection::ByteRange { start: 1000, end: 201_000 };
+
+        let config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = crate::ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.generate_proof(temp_file.path(), selection).await.unwrap();
+
+        let mut claimed_file = tempfile::NamedTempFile::new().unwrap();
+        claimed_file.write_all(&content[1000..201_000]).unwrap();
+        let mut reader = Recording { file: claimed_file.reopen().unwrap(), largest_read: 0 };
+
+        let mut verifier = ProofVerifier::new();
+        let result = verifier.verify_from_reader(&proof, &mut reader).unwrap();
+        assert!(result.is_valid, "{:?}", result.failure);
+        assert!(reader.largest_read < 200_000);
+
+        let result = verifier.verify_from_reader(&proof, &content[1000..200_999]).unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::ContentHashMismatch));
+    }
+
+    #[tokio::test]
+    async fn test_repeat_verification_served_from_cache() {
+        use std::io::Write;
+
+        let mut temp_file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(temp_file, "Content for result cache tests").unwrap();
+        let claimed = b"result cache";
+
+        let config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = crate::ProofGenerator::with_config(config).await.unwrap();
+        let proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::Pattern { content: claimed.to_vec() }
+        ).await.unwrap();
+
+        let mut verifier = ProofVerifier::new();
+        let first = verifier.verify_detailed(&proof, claimed).await.unwrap();
+        let second = verifier.verify_detailed(&proof, claimed).await.unwrap();
+        assert!(first.is_valid && !first.from_cache);
+        assert!(second.is_valid && second.from_cache);
+        assert!(second.verification_time_ms <= first.verification_time_ms);
+        assert!(second.verification_time_ms < 10, "cache hit took {}ms", second.verification_time_ms);
+        assert_eq!(verifier.get_statistics().cache_hits, 1);
+
+        // Other content, an edited proof and a bypassing configuration all re-verify
+        let other = verifier.verify_detailed(&proof, b"other content").await.unwrap();
+        assert!(!other.from_cache);
+
+        let mut edited = proof.clone();
+        edited.metadata.security.security_level = 64;
+        let result = verifier.verify_detailed(&edited, claimed).await.unwrap();
+        assert!(!result.from_cache);
+        assert_eq!(result.failure, Some(VerificationFailure::SecurityLevelTooLow));
+
+        verifier.update_config(VerificationConfig { bypass_cache: true, ..VerificationConfig::default() });
+        let result = verifier.verify_detailed(&proof, claimed).await.unwrap();
+        assert!(result.is_valid && !result.from_cache);
+        assert_eq!(verifier.get_statistics().cache_hits, 1);
+    }
+
+    #[test]
+    fn test_verify_blocking_without_runtime() {
+        assert!(tokio::runtime::Handle::try_current().is_err());
+
+        let mut verifier = ProofVerifier::new();
+        let result = verifier.verify_blocking(&create_test_proof(), b"test").unwrap();
+
+        // The test receipt is not a real Risc0 receipt
+        assert!(!result.is_valid);
+        assert_eq!(result.failure, Some(VerificationFailure::CryptoInvalid));
+    }
+
+    #[test]
+    fn test_foreign_image_id_rejected() {
+        let foreign_image_id = [3u32; 8];
+        let mut proof = create_test_proof();
+        proof.image_id = Some(foreign_image_id);
+
+        let mut verifier = ProofVerifier::new();
+        let result = verifier.verify_blocking(&proof, b"test").unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::UnknownImageId));
+
+        // The current guest is trusted by default, so the dummy receipt is checked
+        proof.image_id = Some(crate::IPFS_CONTENT_VERIFIER_ID);
+        let result = verifier.verify_blocking(&proof, b"test").unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::CryptoInvalid));
+
+        let mut verifier = ProofVerifier::with_config(VerificationConfig {
+            trusted_image_ids: vec![foreign_image_id],
+            ..VerificationConfig::default()
+        });
+        proof.image_id = Some(foreign_image_id);
+        let result = verifier.verify_blocking(&proof, b"test").unwrap();
+        assert_eq!(result.failure, Some(VerificationFailure::CryptoInvalid));
+    }
+
+    #[test]
+    fn test_closure_rule_cid_allowlist() {
+        let allowed = vec!["QmTest".to_string(), "QmOther".to_string()];
+        let config = VerificationConfig::default().add_closure_rule(
+            "cid_allowlist",
+            "File CID must be on the allowlist",
+            move |proof: &Proof| Ok(allowed.contains(&proof.metadata.file_info.ipfs_cid)),
+        );
+        let verifier = ProofVerifier::with_config(config.clone());
+
+        let mut proof = create_test_proof();
+        let (failure, _) = verifier.verify_custom_rules(&proof).unwrap();
+        assert!(failure.is_none());
+
+        proof.metadata.file_info.ipfs_cid = "QmUnknown".to_string();
+        let (failure, warnings) = verifier.verify_custom_rules(&proof).unwrap();
+        assert_eq!(failure, Some(VerificationFailure::CustomRule("cid_allowlist".to_string())));
+        assert!(!warnings.is_empty());
+
+        // Closure rules are skipped when the config is serialized
+        let json = serde_json::to_string(&config).unwrap();
+        let restored: VerificationConfig = serde_json::from_str(&json).unwrap();
+        assert!(restored.closure_rules.is_empty());
+        assert_eq!(restored.max_proof_age_seconds, config.max_proof_age_seconds);
+    }
+
+    #[test]
+    fn test_preset_fields() {
+        let lenient = VerificationConfig::preset(Preset::Lenient);
+        assert!(!lenient.strict_verification);
+        assert!(!lenient.verify_metadata);
+        assert_eq!(lenient.max_proof_age_seconds, None);
+        assert!(lenient.custom_rules.is_empty());
+
+        let standard = VerificationConfig::preset(Preset::Standard);
+        let default = VerificationConfig::default();
+        assert_eq!(standard.strict_verification, default.strict_verification);
+        assert_eq!(standard.verify_metadata, default.verify_metadata);
+        assert_eq!(standard.max_proof_age_seconds, default.max_proof_age_seconds);
+        assert!(standard.custom_rules.is_empty());
+
+        let paranoid = VerificationConfig::preset(Preset::Paranoid);
+        assert!(paranoid.strict_verification);
+        assert!(paranoid.verify_metadata);
+        assert_eq!(paranoid.max_proof_age_seconds, Some(7 * 24 * 60 * 60));
+        let rules: Vec<_> = paranoid.custom_rules.iter().map(|rule| &rule.rule_type).collect();
+        assert!(matches!(rules[..], [
+            VerificationRuleType::MinSecurityLevel(128),
+            VerificationRuleType::RequiredProofSystem(ref system),
+            VerificationRuleType::RequireSignature,
+        ] if system == "Risc0"));
+
+        assert_eq!("Paranoid".parse::<Preset>().unwrap(), Preset::Paranoid);
+        assert!("loose".parse::<Preset>().is_err());
+    }
+
+    #[test]
+    fn test_paranoid_only_rules() {
+        // Ten days old and unsigned: fine by Standard, rejected by Paranoid
+        let mut proof = create_test_proof();
+        proof.created_at = Utc::now() - chrono::Duration::days(10);
+
+        let standard = ProofVerifier::with_config(VerificationConfig::preset(Preset::Standard));
+        assert!(standard.verify_proof_structure(&proof).unwrap());
+        assert!(standard.verify_custom_rules(&proof).unwrap().0.is_none());
+
+        let paranoid = ProofVerifier::with_config(VerificationConfig::preset(Preset::Paranoid));
+        assert!(!paranoid.verify_proof_structure(&proof).unwrap());
+        proof.created_at = Utc::now();
+        let (failure, _) = paranoid.verify_custom_rules(&proof).unwrap();
+        assert_eq!(failure, Some(VerificationFailure::CustomRule("require_signature".to_string())));
+
+        let key = crate::signing::SigningKey::from_bytes(&[7; 32]);
+        proof.sign(&key).unwrap();
+        assert!(paranoid.verify_custom_rules(&proof).unwrap().0.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_verify_against_cid() {
+        use multihash::{Code, MultihashDigest};
+        use std::io::Write;
+
+        let content = b"public content with a secret inside";
+        let mut temp_file = tempfile::NamedTempFile::new().unwrap();
+        temp_file.write_all(content).unwrap();
+        let config = ProofConfig { use_cache: false, ..ProofConfig::default() };
+        let mut generator = crate::ProofGenerator::with_config(config).await.unwrap();
+        let mut proof = generator.generate_proof(
+            temp_file.path(),
+            ContentSelection::Pattern { content: b"secret".to_vec() }
+        ).await.unwrap();
+
+        let cid = Cid::new_v1(RAW_CODEC, Code::Sha2_256.digest(content));
+        let verifier = ProofVerifier::new();
+        assert!(verifier.verify_against_cid(&proof, &cid).unwrap());
+
+        // The CID is checked against the journal, not the recorded metadata
+        let other = Cid::new_v1(RAW_CODEC, Code::Sha2_256.digest(b"other content"));
+        proof.metadata.file_info.ipfs_cid = other.to_string();
+        assert!(!verifier.verify_against_cid(&proof, &other).unwrap());
+
+        // Proofs naming a guest image the verifier does not trust never match
+        let mut foreign = proof.clone();
+        foreign.image_id = Some([3u32; 8]);
+        assert!(!verifier.verify_against_cid(&foreign, &cid).unwrap());
+    }
+
     #[test]
     fn test_statistics_update() {
         let mut verifier = ProofVerifier::new();
@@ -0,0 +1,132 @@
+//! Browser-side proof verification
+//!
+//! With the `wasm` feature on `wasm32-unknown-unknown`, this module exports
+//! proof verification through `wasm-bindgen`, so a page can check a proof with
+//! no server round-trip. Proof generation, file access and the IPFS client are
+//! not compiled for wasm32; the same [`ProofVerifier`] used natively does the
+//! work through its synchronous [`verify_blocking`](ProofVerifier::verify_blocking).
+//!
+//! Receipts are checked by Risc0's verifier built without the `prove` feature,
+//! which handles every receipt kind. Groth16 receipts are the kind to ship to
+//! browsers: they are a few hundred bytes, where composite and succinct receipts
+//! run to hundreds of kilobytes or more and take far longer to verify.
+//!
+//! ```js
+//! import init, { verify_proof_wasm } from "zkipfs_proof_core";
+//!
+//! await init();
+//! const outcome = verify_proof_wasm(proofJson, new TextEncoder().encode("claimed"));
+//! if (outcome.is_valid) { /* ... */ }
+//! ```
+
+use crate::{
+    types::{Proof, VerificationResult},
+    verifier::{ProofVerifier, VerificationConfig},
+};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Outcome of a browser verification, as returned to JavaScript
+#[derive(Serialize)]
+struct WasmVerification {
+    /// Whether the proof is valid for the claimed content
+    is_valid: bool,
+    /// Detailed result, when verification ran
+    result: Option<VerificationResult>,
+    /// Why verification could not run (unreadable proof or configuration)
+    error: Option<String>,
+}
+
+/// Verifies a JSON-encoded proof against the claimed content with the default configuration
+///
+/// Returns an object with `is_valid`, the detailed `result` and, if the proof
+/// could not be read, an `error` message.
+#[wasm_bindgen]
+pub fn verify_proof_wasm(proof_json: &str, claimed_content: &[u8]) -> JsValue {
+    to_js(verify(proof_json, claimed_content, VerificationConfig::default()))
+}
+
+/// Verifies a JSON-encoded proof with a JSON-encoded `VerificationConfig`
+#[wasm_bindgen]
+pub fn verify_proof_wasm_with_config(proof_json: &str, claimed_content: &[u8], config_json: &str) -> JsValue {
+    let outcome = match serde_json::from_str::<VerificationConfig>(config_json) {
+        Ok(config) => verify(proof_json, claimed_content, config),
+        Err(e) => failed(format!("Invalid verification config: {}", e)),
+    };
+    to_js(outcome)
+}
+
+fn verify(proof_json: &str, claimed_content: &[u8], config: VerificationConfig) -> WasmVerification {
+    let proof: Proof = match serde_json::from_str(proof_json) {
+        Ok(proof) => proof,
+        Err(e) => return failed(format!("Invalid proof JSON: {}", e)),
+    };
+
+    match ProofVerifier::with_config(config).verify_blocking(&proof, claimed_content) {
+        Ok(result) => WasmVerification {
+            is_valid: result.is_valid,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => failed(e.to_string()),
+    }
+}
+
+fn failed(error: String) -> WasmVerification {
+    WasmVerification {
+        is_valid: false,
+        result: None,
+        error: Some(error),
+    }
+}
+
+fn to_js(outcome: WasmVerification) -> JsValue {
+    serde_wasm_bindgen::to_value(&outcome)
+        .unwrap_or_else(|e| JsValue::from_str(&format!("Failed to encode verification result: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Groth16 proof written by `write_wasm_fixture` in `proof.rs`, which
+    /// must be rerun whenever the guest changes its image ID or journal
+    #[cfg(feature = "wasm-fixture")]
+    const PROOF_JSON: &str = include_str!("../testdata/wasm_proof.json");
+    const CLAIMED: &[u8] = b"verified in the browser";
+
+    // The fixture never expires
+    #[cfg(feature = "wasm-fixture")]
+    const CONFIG_JSON: &str = r#"{
+        "strict_verification": true,
+        "include_verification_steps": true,
+        "max_proof_age_seconds": null,
+        "verify_metadata": true,
+        "custom_rules": []
+    }"#;
+
+    fn outcome(value: JsValue) -> serde_json::Value {
+        serde_wasm_bindgen::from_value(value).unwrap()
+    }
+
+    #[cfg(feature = "wasm-fixture")]
+    #[wasm_bindgen_test]
+    fn test_verifies_known_good_proof() {
+        let result = outcome(verify_proof_wasm_with_config(PROOF_JSON, CLAIMED, CONFIG_JSON));
+        assert_eq!(result["is_valid"], true, "{}", result);
+
+        let result = outcome(verify_proof_wasm_with_config(PROOF_JSON, b"other content", CONFIG_JSON));
+        assert_eq!(result["is_valid"], false);
+        assert_eq!(result["result"]["failure"], "ContentHashMismatch");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_rejects_malformed_proof() {
+        let result = outcome(verify_proof_wasm("not a proof", CLAIMED));
+        assert_eq!(result["is_valid"], false);
+        assert!(result["error"].as_str().unwrap().starts_with("Invalid proof JSON"));
+    }
+}
@@ -1,12 +1,12 @@
 use axum::{
     async_trait,
     extract::{FromRequestParts, State},
-    http::{Request, StatusCode},
+    http::{header, HeaderValue, Request, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Json, Response},
 };
 use std::sync::Arc;
-use crate::{state::AppState, db::ApiKey};
+use crate::{state::AppState, db::{ApiKey, QuotaUsage}};
 use tower_governor::{key_extractor::KeyExtractor};
 
 // API Key Extractor
@@ -58,11 +58,60 @@ where
     }
 }
 
-// We will implement the middleware as a function instead of FromRequestParts for easier State access if traits are hard.
-// OR, we impl generic assuming S has a valid `db`.
+/// Scope for creating and revoking API keys
+pub const SCOPE_KEYS_WRITE: &str = "keys:write";
+/// Scope for listing every key and changing quotas
+pub const SCOPE_KEYS_ADMIN: &str = "keys:admin";
+/// Scope for generating proofs
+pub const SCOPE_PROOFS_GENERATE: &str = "proofs:generate";
 
+/// Scopes given to keys created through the API
+pub const DEFAULT_SCOPES: &[&str] = &[SCOPE_PROOFS_GENERATE];
+
+/// Every scope a key may hold
+pub const ALL_SCOPES: &[&str] = &[SCOPE_KEYS_WRITE, SCOPE_KEYS_ADMIN, SCOPE_PROOFS_GENERATE];
+
+/// Environment variable whose value is made a key holding every scope at
+/// startup, so keys can be managed on a fresh database
+pub const ADMIN_KEY_ENV: &str = "ZKIPFS_ADMIN_API_KEY";
+
+/// 403 JSON error naming the scope a request lacked
+pub fn insufficient_scope(scope: &str) -> Response {
+    (
+        StatusCode::FORBIDDEN,
+        Json(serde_json::json!({
+            "error": "insufficient_scope",
+            "required_scope": scope,
+        })),
+    ).into_response()
+}
+
+/// Authenticates the request like `auth_middleware`, rejecting keys that lack
+/// the scope given as state with a 403 JSON error
+///
+/// The scope is checked before the request counts against the key's quota.
+/// Apply it per route with
+/// `middleware::from_fn_with_state((state, SCOPE), require_scope)`.
+pub async fn require_scope(
+    axum::extract::State((state, scope)): axum::extract::State<(AppState, &'static str)>,
+    req: Request<axum::body::Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    authenticate(&state, Some(scope), req, next).await
+}
+
+/// Authenticates the request's API key and counts the request against its quota
 pub async fn auth_middleware(
     axum::extract::State(state): axum::extract::State<AppState>,
+    req: Request<axum::body::Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    authenticate(&state, None, req, next).await
+}
+
+async fn authenticate(
+    state: &AppState,
+    scope: Option<&'static str>,
     mut req: Request<axum::body::Body>,
     next: Next,
 ) -> Result<Response, StatusCode> {
@@ -73,9 +122,25 @@ pub async fn auth_middleware(
         Some(key) => {
             match state.db.validate_api_key(key).await {
                 Ok(Some(api_key)) => {
+                    if let Some(scope) = scope.filter(|scope| !api_key.has_scope(scope)) {
+                        return Ok(insufficient_scope(scope));
+                    }
+
+                    let usage = state.db.consume_quota(&api_key).await
+                        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+                    if !usage.allowed {
+                        let retry_after = (usage.reset_at - chrono::Utc::now()).num_seconds().max(1);
+                        let mut response = (StatusCode::TOO_MANY_REQUESTS, "API key quota exceeded").into_response();
+                        insert_rate_limit_headers(&mut response, &usage);
+                        response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+                        return Ok(response);
+                    }
+
                     // Inject user context if needed
                     req.extensions_mut().insert(api_key);
-                    Ok(next.run(req).await)
+                    let mut response = next.run(req).await;
+                    insert_rate_limit_headers(&mut response, &usage);
+                    Ok(response)
                 }
                 Ok(None) => Err(StatusCode::UNAUTHORIZED),
                 Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
@@ -85,6 +150,15 @@ pub async fn auth_middleware(
     }
 }
 
+/// Adds `X-RateLimit-Limit`, `X-RateLimit-Remaining` and `X-RateLimit-Reset`
+/// (a Unix timestamp) to a response
+fn insert_rate_limit_headers(response: &mut Response, usage: &QuotaUsage) {
+    let headers = response.headers_mut();
+    headers.insert("X-RateLimit-Limit", HeaderValue::from(usage.limit));
+    headers.insert("X-RateLimit-Remaining", HeaderValue::from(usage.remaining));
+    headers.insert("X-RateLimit-Reset", HeaderValue::from(usage.reset_at.timestamp()));
+}
+
 // Key Extractor for Rate Limiting based on IP or API Key
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub struct ApiKeyExtractor;
@@ -114,3 +188,134 @@ impl KeyExtractor for ApiKeyExtractor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+    use crate::db::Db;
+
+    #[tokio::test]
+    async fn test_exhausted_quota_returns_429() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Db::new(dir.path().join("quota.db").to_str().unwrap()).await.unwrap();
+        let (key, raw_key) = db.create_api_key("quota_user", DEFAULT_SCOPES).await.unwrap();
+        db.set_daily_quota(&key.id, Some(2)).await.unwrap();
+
+        let state = AppState::new(db);
+        let app = Router::new()
+            .route("/ping", get(|| async { "pong" }))
+            .layer(middleware::from_fn_with_state(state.clone(), auth_middleware))
+            .with_state(state);
+
+        let mut responses = Vec::new();
+        for _ in 0..3 {
+            let request = Request::builder()
+                .uri("/ping")
+                .header("X-API-Key", &raw_key)
+                .body(Body::empty())
+                .unwrap();
+            responses.push(app.clone().oneshot(request).await.unwrap());
+        }
+        let header = |response: &Response, name: &str| {
+            response.headers().get(name).unwrap().to_str().unwrap().to_string()
+        };
+
+        assert_eq!(responses[0].status(), StatusCode::OK);
+        assert_eq!(header(&responses[0], "X-RateLimit-Limit"), "2");
+        assert_eq!(header(&responses[0], "X-RateLimit-Remaining"), "1");
+        assert_eq!(header(&responses[1], "X-RateLimit-Remaining"), "0");
+
+        let rejected = &responses[2];
+        assert_eq!(rejected.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(header(rejected, "X-RateLimit-Remaining"), "0");
+        let reset: i64 = header(rejected, "X-RateLimit-Reset").parse().unwrap();
+        let retry_after: i64 = header(rejected, "Retry-After").parse().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        assert!(reset > now && reset <= now + 86_400);
+        assert!(retry_after > 0 && retry_after <= reset - now + 1);
+    }
+
+    async fn list_keys_status(state: &AppState, raw_key: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .uri("/keys")
+            .header("X-API-Key", raw_key)
+            .body(Body::empty())
+            .unwrap();
+        let response = crate::handlers::api_v1_router(state.clone())
+            .with_state(state.clone())
+            .oneshot(request)
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_route_requires_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Db::new(dir.path().join("scopes.db").to_str().unwrap()).await.unwrap();
+        let (_, admin_key) = db.create_api_key("admin", &[SCOPE_KEYS_ADMIN]).await.unwrap();
+        let (_, user_key) = db.create_api_key("user", DEFAULT_SCOPES).await.unwrap();
+        let state = AppState::new(db);
+
+        let (status, keys) = list_keys_status(&state, &admin_key).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(keys.as_array().map(Vec::len), Some(2));
+
+        let (status, error) = list_keys_status(&state, &user_key).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(error["error"], "insufficient_scope");
+        assert_eq!(error["required_scope"], SCOPE_KEYS_ADMIN);
+    }
+
+    #[tokio::test]
+    async fn test_forbidden_request_keeps_quota() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Db::new(dir.path().join("forbidden.db").to_str().unwrap()).await.unwrap();
+        let (key, raw_key) = db.create_api_key("user", DEFAULT_SCOPES).await.unwrap();
+        db.set_daily_quota(&key.id, Some(1)).await.unwrap();
+        let state = AppState::new(db);
+
+        for _ in 0..2 {
+            assert_eq!(list_keys_status(&state, &raw_key).await.0, StatusCode::FORBIDDEN);
+        }
+        let usage = state.db.consume_quota(&key).await.unwrap();
+        assert!(usage.allowed);
+        assert_eq!(usage.remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn test_bootstrapped_admin_grants_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Db::new(dir.path().join("bootstrap.db").to_str().unwrap()).await.unwrap();
+        let admin = db.ensure_api_key("admin", "bootstrap-secret", ALL_SCOPES).await.unwrap();
+        assert_eq!(db.ensure_api_key("admin", "bootstrap-secret", ALL_SCOPES).await.unwrap().id, admin.id);
+        let (_, writer_key) = db.create_api_key("writer", &[SCOPE_KEYS_WRITE]).await.unwrap();
+        let state = AppState::new(db);
+
+        let create = |raw_key: &str, body: &str| Request::builder()
+            .method("POST")
+            .uri("/keys")
+            .header("X-API-Key", raw_key)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let router = crate::handlers::api_v1_router(state.clone()).with_state(state.clone());
+        let body = r#"{"owner":"ops","scopes":["keys:admin","proofs:generate"]}"#;
+
+        let response = router.clone().oneshot(create("bootstrap-secret", body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let created = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let created: serde_json::Value = serde_json::from_slice(&created).unwrap();
+        let ops_key = state.db.validate_api_key(created["key"].as_str().unwrap()).await.unwrap().unwrap();
+        assert!(ops_key.has_scope(SCOPE_KEYS_ADMIN) && !ops_key.has_scope(SCOPE_KEYS_WRITE));
+
+        let response = router.clone().oneshot(create(&writer_key, body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = router.oneshot(create("bootstrap-secret", r#"{"scopes":["root"]}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -17,14 +17,45 @@ pub struct Db {
     pub pool: Pool<Sqlite>,
 }
 
-#[derive(sqlx::FromRow, Debug, Clone)]
+#[derive(sqlx::FromRow, serde::Serialize, Debug, Clone)]
 pub struct ApiKey {
     pub id: String,
+    #[serde(skip_serializing)]
     pub key_hash: String,
     pub owner: String,
     pub created_at: DateTime<Utc>,
     pub revoked: bool,
     pub last_used_at: Option<DateTime<Utc>>,
+    /// Requests allowed per UTC day; `None` uses [`DEFAULT_DAILY_QUOTA`]
+    pub daily_quota: Option<i64>,
+    /// Space-separated scopes granting what the key may do
+    pub scopes: String,
+}
+
+/// Daily request quota for keys without one of their own
+pub const DEFAULT_DAILY_QUOTA: i64 = 1000;
+
+/// A key's quota usage for the current UTC day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaUsage {
+    /// Requests allowed per day
+    pub limit: i64,
+    /// Requests left today
+    pub remaining: i64,
+    /// Whether this request was admitted
+    pub allowed: bool,
+    /// When the quota resets (the next UTC midnight)
+    pub reset_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    pub fn quota(&self) -> i64 {
+        self.daily_quota.unwrap_or(DEFAULT_DAILY_QUOTA)
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.split_whitespace().any(|granted| granted == scope)
+    }
 }
 
 impl Db {
@@ -55,7 +86,28 @@ impl Db {
                 owner TEXT NOT NULL,
                 created_at DATETIME NOT NULL,
                 revoked BOOLEAN NOT NULL DEFAULT FALSE,
-                last_used_at DATETIME
+                last_used_at DATETIME,
+                daily_quota INTEGER,
+                scopes TEXT NOT NULL DEFAULT 'proofs:generate'
+            )",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Databases created before quotas lack the column; the error on newer ones is expected
+        let _ = sqlx::query("ALTER TABLE api_keys ADD COLUMN daily_quota INTEGER")
+            .execute(&self.pool)
+            .await;
+        let _ = sqlx::query("ALTER TABLE api_keys ADD COLUMN scopes TEXT NOT NULL DEFAULT 'proofs:generate'")
+            .execute(&self.pool)
+            .await;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS api_key_usage (
+                key_id TEXT NOT NULL,
+                day TEXT NOT NULL,
+                count INTEGER NOT NULL,
+                PRIMARY KEY (key_id, day)
             )",
         )
         .execute(&self.pool)
@@ -76,16 +128,37 @@ impl Db {
         Ok(())
     }
 
-    pub async fn create_api_key(&self, owner: &str) -> Result<(ApiKey, String)> {
+    /// Checks that the database answers queries
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    pub async fn create_api_key(&self, owner: &str, scopes: &[&str]) -> Result<(ApiKey, String)> {
         let salt = SaltString::generate(&mut OsRng);
         let api_key_raw = Uuid::new_v4().to_string().replace("-", "") + &Uuid::new_v4().to_string().replace("-", ""); 
-        
-        let mut hasher = Sha256::new();
-        hasher.update(api_key_raw.as_bytes());
-        let key_hash = hex::encode(hasher.finalize());
-        
+        let api_key = self.insert_api_key(owner, &api_key_raw, scopes).await?;
+        Ok((api_key, api_key_raw))
+    }
+
+    /// Makes `raw_key` a key holding exactly `scopes`, creating it unless it exists and is not revoked
+    pub async fn ensure_api_key(&self, owner: &str, raw_key: &str, scopes: &[&str]) -> Result<ApiKey> {
+        let updated = sqlx::query("UPDATE api_keys SET scopes = ? WHERE key_hash = ? AND revoked = FALSE")
+            .bind(scopes.join(" "))
+            .bind(hash_key(raw_key))
+            .execute(&self.pool)
+            .await?;
+        if updated.rows_affected() == 0 {
+            return self.insert_api_key(owner, raw_key, scopes).await;
+        }
+        self.validate_api_key(raw_key).await?
+            .ok_or_else(|| anyhow::anyhow!("API key disappeared while being updated"))
+    }
+
+    async fn insert_api_key(&self, owner: &str, raw_key: &str, scopes: &[&str]) -> Result<ApiKey> {
         // We use fast hasing (SHA256) for lookup/verification of machine keys. 
         // Argon2 is for passwords. 
+        let key_hash = hash_key(raw_key);
         
         let api_key = ApiKey {
             id: Uuid::new_v4().to_string(),
@@ -94,26 +167,27 @@ impl Db {
             created_at: Utc::now(),
             revoked: false,
             last_used_at: None,
+            daily_quota: None,
+            scopes: scopes.join(" "),
         };
 
         sqlx::query(
-            "INSERT INTO api_keys (id, key_hash, owner, created_at, revoked) VALUES (?, ?, ?, ?, ?)",
+            "INSERT INTO api_keys (id, key_hash, owner, created_at, revoked, scopes) VALUES (?, ?, ?, ?, ?, ?)",
         )
         .bind(&api_key.id)
         .bind(&api_key.key_hash)
         .bind(&api_key.owner)
         .bind(&api_key.created_at)
         .bind(&api_key.revoked)
+        .bind(&api_key.scopes)
         .execute(&self.pool)
         .await?;
 
-        Ok((api_key, api_key_raw))
+        Ok(api_key)
     }
 
     pub async fn validate_api_key(&self, raw_key: &str) -> Result<Option<ApiKey>> {
-        let mut hasher = Sha256::new();
-        hasher.update(raw_key.as_bytes());
-        let hash = hex::encode(hasher.finalize());
+        let hash = hash_key(raw_key);
         
         let key = sqlx::query_as::<_, ApiKey>(
             "SELECT * FROM api_keys WHERE key_hash = ? AND revoked = FALSE"
@@ -140,6 +214,56 @@ impl Db {
         Ok(keys)
     }
 
+    pub async fn set_daily_quota(&self, id: &str, daily_quota: Option<i64>) -> Result<()> {
+        sqlx::query("UPDATE api_keys SET daily_quota = ? WHERE id = ?")
+            .bind(daily_quota)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Counts one request against the key's quota for today, unless it is used up
+    ///
+    /// The check and the increment are one statement, so concurrent requests
+    /// cannot overrun the quota.
+    pub async fn consume_quota(&self, key: &ApiKey) -> Result<QuotaUsage> {
+        let now = Utc::now();
+        let day = now.date_naive();
+        let reset_at = day.succ_opt().unwrap_or(day).and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let limit = key.quota();
+
+        let used: Option<i64> = if limit > 0 {
+            sqlx::query_scalar(
+                "INSERT INTO api_key_usage (key_id, day, count) VALUES (?, ?, 1)
+                 ON CONFLICT (key_id, day) DO UPDATE SET count = count + 1 WHERE count < ?
+                 RETURNING count",
+            )
+            .bind(&key.id)
+            .bind(day.to_string())
+            .bind(limit)
+            .fetch_optional(&self.pool)
+            .await?
+        } else {
+            None
+        };
+
+        Ok(QuotaUsage {
+            limit,
+            remaining: used.map_or(0, |used| (limit - used).max(0)),
+            allowed: used.is_some(),
+            reset_at,
+        })
+    }
+
+    pub async fn get_key(&self, id: &str) -> Result<Option<ApiKey>> {
+        let key = sqlx::query_as::<_, ApiKey>("SELECT * FROM api_keys WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(key)
+    }
+
     pub async fn revoke_key(&self, id: &str) -> Result<()> {
         sqlx::query("UPDATE api_keys SET revoked = TRUE WHERE id = ?")
             .bind(id)
@@ -148,3 +272,10 @@ impl Db {
         Ok(())
     }
 }
+
+/// SHA-256 of a raw API key, as stored in `api_keys.key_hash`
+fn hash_key(raw_key: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(raw_key.as_bytes());
+    hex::encode(hasher.finalize())
+}
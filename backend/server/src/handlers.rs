@@ -1,12 +1,18 @@
 use axum::{
-    extract::{State, Path, Multipart},
-    response::{Json, IntoResponse},
-    http::StatusCode,
+    extract::{State, Path, Query, Multipart, DefaultBodyLimit, Extension, multipart::MultipartError},
+    response::{Json, IntoResponse, Response},
+    http::{header, StatusCode},
+    middleware,
+    routing::{get, post, put, delete},
+    Router,
 };
 use std::path::PathBuf;
+use tower_http::limit::RequestBodyLimitLayer;
+use tracing::Instrument;
 use uuid::Uuid;
-use crate::state::{AppState, Job, JobStatus};
-use zkipfs_proof_core::{ProofGenerator, ProofConfig, ContentSelection};
+use crate::request_id::RequestId;
+use crate::state::{AppState, Job, JobStatus, RequestLimits};
+use zkipfs_proof_core::{ProofGenerator, ProofConfig, ContentSelection, Bytes, HashKind};
 use std::sync::Arc;
 use tokio::io::AsyncWriteExt;
 
@@ -15,10 +21,76 @@ pub struct GenerateRequest {
     // Fields are parsed manually from multipart
 }
 
+/// A `/generate` upload, read in full before its job is enqueued
+pub struct ProofRequest {
+    /// Keeps the uploaded file alive until the job finishes
+    _temp_dir: tempfile::TempDir,
+    pub file_path: PathBuf,
+    pub file_size: u64,
+    content_str: Option<String>,
+    security_level: u32,
+}
+
+/// Applies the body size limit to `router`, answering oversized requests
+/// with a JSON `413 Payload Too Large`
+pub fn with_body_limit(router: Router<AppState>, limits: &RequestLimits) -> Router<AppState> {
+    router
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(limits.max_body_bytes))
+        .layer(middleware::map_response(json_payload_too_large))
+}
+
+/// Replaces the plain-text body of a `413` from the body limit layer with a JSON error
+async fn json_payload_too_large(response: Response) -> Response {
+    let is_json = response.headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE || is_json {
+        return response;
+    }
+    payload_too_large("Request body exceeds the maximum allowed size")
+}
+
+fn payload_too_large(message: impl Into<String>) -> Response {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(serde_json::json!({
+            "error": "payload_too_large",
+            "message": message.into(),
+        })),
+    ).into_response()
+}
+
+fn bad_request(message: impl Into<String>) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({
+            "error": "invalid_request",
+            "message": message.into(),
+        })),
+    ).into_response()
+}
+
+fn multipart_error(e: MultipartError) -> Response {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        payload_too_large(e.body_text())
+    } else {
+        bad_request(e.body_text())
+    }
+}
+
 pub async fn generate_proof(
     State(state): State<AppState>,
+    request_id: Option<Extension<RequestId>>,
     mut multipart: Multipart,
-) -> impl IntoResponse {
+) -> Response {
+    let request_id = request_id.map(|Extension(RequestId(id))| id);
+    // The upload is read and size-checked before a job exists for it
+    let request = match read_proof_request(&mut multipart, state.limits.max_file_size_bytes).await {
+        Ok(request) => request,
+        Err(response) => return response,
+    };
+
     let job_id = Uuid::new_v4().to_string();
     let job_id_clone = job_id.clone();
     
@@ -29,16 +101,23 @@ pub async fn generate_proof(
             id: job_id.clone(),
             status: JobStatus::Pending,
             created_at: chrono::Utc::now().timestamp() as u64,
+            request_id: request_id.clone(),
         });
     }
 
-    // Spawn processing task
+    // Spawn processing task; the span nests under the request's span
+    let span = tracing::info_span!("proof_job", job_id = %job_id, file_size = request.file_size);
+    let job_request_id = request_id.clone();
     tokio::spawn(async move {
-        match process_proof_request(state.clone(), job_id_clone.clone(), multipart).await {
+        match process_proof_request(state.clone(), job_id_clone.clone(), request, job_request_id).await {
             Ok(proof) => {
+                let status = match state.proofs.put(&proof).await {
+                    Ok(()) => JobStatus::Completed(serde_json::to_value(proof).unwrap()),
+                    Err(e) => JobStatus::Failed(format!("Failed to store proof: {}", e)),
+                };
                 let mut jobs = state.jobs.write().unwrap();
                 if let Some(job) = jobs.get_mut(&job_id_clone) {
-                    job.status = JobStatus::Completed(serde_json::to_value(proof).unwrap());
+                    job.status = status;
                 }
             }
             Err(e) => {
@@ -48,9 +127,9 @@ pub async fn generate_proof(
                 }
             }
         }
-    });
+    }.instrument(span));
 
-    Json(serde_json::json!({ "job_id": job_id }))
+    Json(serde_json::json!({ "job_id": job_id, "request_id": request_id })).into_response()
 }
 
 pub async fn get_status(
@@ -65,56 +144,146 @@ pub async fn get_status(
     }
 }
 
-async fn process_proof_request(
-    state: AppState,
-    job_id: String,
-    mut multipart: Multipart,
-) -> anyhow::Result<zkipfs_proof_core::types::Proof> {
-    // Update status to processing
-    {
-        let mut jobs = state.jobs.write().unwrap();
-        if let Some(job) = jobs.get_mut(&job_id) {
-            job.status = JobStatus::Processing;
-        }
+pub async fn get_proof(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Response {
+    match state.proofs.get(&id).await {
+        Ok(Some(proof)) => Json(proof).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "Proof not found").into_response(),
+        Err(e) => store_error(e),
+    }
+}
+
+pub async fn list_proofs(
+    State(state): State<AppState>,
+) -> Response {
+    match state.proofs.list().await {
+        Ok(ids) => Json(serde_json::json!({ "proofs": ids })).into_response(),
+        Err(e) => store_error(e),
     }
+}
+
+#[derive(serde::Deserialize)]
+pub struct ProofLookup {
+    /// Hex-encoded root hash of the file the proof is for
+    pub root_hash: Option<String>,
+    /// Hex-encoded hash of the proven content
+    pub content_hash: Option<String>,
+}
+
+/// Ids of stored proofs with the given root or content hash, so a client can
+/// reuse a proof instead of generating it again
+pub async fn find_proofs(
+    State(state): State<AppState>,
+    Query(lookup): Query<ProofLookup>,
+) -> Response {
+    let (kind, hash) = match (lookup.root_hash, lookup.content_hash) {
+        (Some(hash), None) => (HashKind::Root, hash),
+        (None, Some(hash)) => (HashKind::Content, hash),
+        _ => return bad_request("Pass exactly one of root_hash and content_hash"),
+    };
+    let Some(hash) = hex::decode(&hash).ok().and_then(|hash| <[u8; 32]>::try_from(hash).ok()) else {
+        return bad_request("Hashes must be 64 hex characters");
+    };
+
+    match state.proofs.find_by_hash(kind, &hash).await {
+        Ok(ids) => Json(serde_json::json!({ "proofs": ids })).into_response(),
+        Err(e) => store_error(e),
+    }
+}
+
+fn store_error(e: zkipfs_proof_core::ProofError) -> Response {
+    match e {
+        zkipfs_proof_core::ProofError::InvalidInputError { .. } => bad_request(e.to_string()),
+        e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Reads the multipart fields of a `/generate` request, streaming the file to
+/// a temporary directory and rejecting it once it exceeds `max_file_size_bytes`
+pub async fn read_proof_request(
+    multipart: &mut Multipart,
+    max_file_size_bytes: u64,
+) -> Result<ProofRequest, Response> {
+    let internal_error = |e: std::io::Error| {
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+    };
 
     let mut file_path: Option<PathBuf> = None;
+    let mut file_size = 0u64;
     let mut content_str: Option<String> = None;
     let mut security_level = 128;
     // directory to keep temp file
-    let temp_dir = tempfile::tempdir()?;
+    let temp_dir = tempfile::tempdir().map_err(internal_error)?;
     
-    while let Some(field) = multipart.next_field().await? {
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
         let name = field.name().unwrap_or("").to_string();
         
         if name == "file" {
-            let file_name = field.file_name().unwrap_or("upload.tmp").to_string();
-            let path = temp_dir.path().join(file_name);
-            let mut file = tokio::fs::File::create(&path).await?;
-            let data = field.bytes().await?;
-            file.write_all(&data).await?;
+            // The client's file name could escape the directory, so it is not used
+            let path = temp_dir.path().join("upload");
+            let mut file = tokio::fs::File::create(&path).await.map_err(internal_error)?;
+            file_size = 0;
+            while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+                file_size += chunk.len() as u64;
+                if file_size > max_file_size_bytes {
+                    return Err(payload_too_large(format!(
+                        "File exceeds the maximum allowed size ({} bytes)",
+                        max_file_size_bytes
+                    )));
+                }
+                file.write_all(&chunk).await.map_err(internal_error)?;
+            }
+            // Writes still in flight would otherwise race the prover reading the file
+            file.flush().await.map_err(internal_error)?;
             file_path = Some(path);
         } else if name == "content_selection" {
-             content_str = Some(field.text().await?);
+             content_str = Some(field.text().await.map_err(multipart_error)?);
         } else if name == "security_level" {
-             if let Ok(val) = field.text().await?.parse::<u32>() {
+             if let Ok(val) = field.text().await.map_err(multipart_error)?.parse::<u32>() {
                  security_level = val;
              }
         }
     }
 
-    let file_path = file_path.ok_or_else(|| anyhow::anyhow!("No file uploaded"))?;
-    let content_str = content_str.unwrap_or_else(|| "pattern: ".to_string()); // Default or error?
+    let file_path = file_path.ok_or_else(|| bad_request("No file uploaded"))?;
+    Ok(ProofRequest {
+        _temp_dir: temp_dir,
+        file_path,
+        file_size,
+        content_str,
+        security_level,
+    })
+}
+
+async fn process_proof_request(
+    state: AppState,
+    job_id: String,
+    request: ProofRequest,
+    request_id: Option<String>,
+) -> anyhow::Result<zkipfs_proof_core::types::Proof> {
+    // Update status to processing
+    {
+        let mut jobs = state.jobs.write().unwrap();
+        if let Some(job) = jobs.get_mut(&job_id) {
+            job.status = JobStatus::Processing;
+        }
+    }
+
+    let content_str = request.content_str.unwrap_or_else(|| "pattern: ".to_string()); // Default or error?
 
     // Parse content selection (Basic parsing similar to CLI utils, implemented here for simplicity)
     // In a real implementation, we should expose `parse_content_selection` from CLI or move it to Core.
-    // Assuming standard formats: "pattern:...", "regex:...", "xpath:...", "range:..."
+    // Assuming standard formats: "pattern:...", "regex:...", "xpath:...", "json:...", "range:..."
     let selection = if let Some(stripped) = content_str.strip_prefix("pattern:") {
          ContentSelection::Pattern { content: stripped.as_bytes().to_vec() }
     } else if let Some(stripped) = content_str.strip_prefix("regex:") {
          ContentSelection::Regex { pattern: stripped.to_string() }
     } else if let Some(stripped) = content_str.strip_prefix("xpath:") {
          ContentSelection::XPath { selector: stripped.to_string() }
+    } else if let Some(stripped) = content_str.strip_prefix("json:") {
+         ContentSelection::JsonPath { path: stripped.to_string() }
     } else if let Some(stripped) = content_str.strip_prefix("range:") {
          let parts: Vec<&str> = stripped.split(':').collect();
          if parts.len() == 2 {
@@ -131,32 +300,103 @@ async fn process_proof_request(
     };
 
     let config = ProofConfig {
-        security_level,
+        security_level: request.security_level,
         use_hardware_acceleration: true,
-        prover_type: zkipfs_proof_core::ProverType::Local,
+        prover_type: if state.bonsai.is_set() {
+            zkipfs_proof_core::ProverType::Bonsai
+        } else {
+            zkipfs_proof_core::ProverType::Local
+        },
+        max_file_size_bytes: Some(Bytes(state.limits.max_file_size_bytes)),
         ..ProofConfig::default()
     };
 
+    let proving_timeout = state.limits.proving_timeout;
     let mut generator = ProofGenerator::with_config(config).await?;
-    let proof = generator.generate_proof(&file_path, selection).await?;
+    generator.set_bonsai_credentials(state.bonsai.clone());
+    if let Some(request_id) = request_id {
+        generator.set_request_id(request_id);
+    }
+    let proof = tokio::time::timeout(proving_timeout, generator.generate_proof(&request.file_path, selection))
+        .await
+        .map_err(|_| anyhow::anyhow!("Proving timed out after {} seconds", proving_timeout.as_secs()))??;
 
     Ok(proof)
 }
 
+/// Unversioned proof routes, authenticated and scoped like their `/api/v1`
+/// counterparts
+pub fn legacy_router(state: AppState) -> Router<AppState> {
+    use crate::auth::{require_scope, SCOPE_PROOFS_GENERATE};
+
+    Router::new()
+        .route("/generate", post(generate_proof))
+        .route("/status/:id", get(get_status))
+        .route("/proofs", get(list_proofs))
+        .route("/proofs/:id", get(get_proof))
+        .route_layer(middleware::from_fn_with_state((state, SCOPE_PROOFS_GENERATE), require_scope))
+}
+
 // Enterprise Handlers
 
+/// Routes of the authenticated enterprise API, each requiring its scope
+pub fn api_v1_router(state: AppState) -> Router<AppState> {
+    use crate::auth::{require_scope, SCOPE_KEYS_ADMIN, SCOPE_KEYS_WRITE, SCOPE_PROOFS_GENERATE};
+
+    // Each route authenticates its key, so scopes are checked before quota is used
+    let scoped = |scope: &'static str| middleware::from_fn_with_state((state.clone(), scope), require_scope);
+
+    Router::new()
+        .route("/keys", post(create_api_key)
+            .route_layer(scoped(SCOPE_KEYS_WRITE))
+            .merge(get(list_api_keys)
+                .route_layer(scoped(SCOPE_KEYS_ADMIN))))
+        .route("/keys/:id", delete(revoke_api_key)
+            .route_layer(scoped(SCOPE_KEYS_WRITE)))
+        .route("/keys/:id/quota", put(set_api_key_quota)
+            .route_layer(scoped(SCOPE_KEYS_ADMIN)))
+        .route("/admin/bonsai-credentials", put(set_bonsai_credentials)
+            .route_layer(scoped(SCOPE_KEYS_ADMIN)))
+        .route("/generate", post(generate_proof)
+            .route_layer(scoped(SCOPE_PROOFS_GENERATE)))
+        .route("/proofs", get(find_proofs)
+            .route_layer(scoped(SCOPE_PROOFS_GENERATE)))
+}
+
+#[derive(serde::Deserialize, Default)]
+pub struct CreateKeyRequest {
+    pub owner: Option<String>,
+    /// Scopes of the new key (default: `proofs:generate`); granting any other
+    /// scope needs a `keys:admin` key
+    pub scopes: Option<Vec<String>>,
+}
+
 pub async fn create_api_key(
     State(state): State<AppState>,
+    Extension(caller): Extension<crate::db::ApiKey>,
+    request: Option<Json<CreateKeyRequest>>,
 ) -> impl IntoResponse {
-    // In real world, authenticated user creates a key. Here we simulate "admin" creating it or open creation for demo.
-    // For demo/POC: Allow open creation or require a "master" key?
-    // Let's assume open for now or require a secret hardcoded in env?
-    // For simplicity: Open endpoint for generating keys.
-    match state.db.create_api_key("demo_user").await {
+    use crate::auth::{ALL_SCOPES, DEFAULT_SCOPES, SCOPE_KEYS_ADMIN};
+
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let scopes: Vec<&str> = match &request.scopes {
+        Some(scopes) => scopes.iter().map(String::as_str).collect(),
+        None => DEFAULT_SCOPES.to_vec(),
+    };
+    if let Some(unknown) = scopes.iter().find(|scope| !ALL_SCOPES.contains(scope)) {
+        return (StatusCode::BAD_REQUEST, format!("Unknown scope: {}", unknown)).into_response();
+    }
+    if scopes.iter().any(|scope| !DEFAULT_SCOPES.contains(scope)) && !caller.has_scope(SCOPE_KEYS_ADMIN) {
+        return crate::auth::insufficient_scope(SCOPE_KEYS_ADMIN);
+    }
+
+    let owner = request.owner.as_deref().unwrap_or("demo_user");
+    match state.db.create_api_key(owner, &scopes).await {
         Ok((key, raw)) => Json(serde_json::json!({
             "key": raw,
             "id": key.id,
-            "created_at": key.created_at
+            "created_at": key.created_at,
+            "scopes": key.scopes,
         })).into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
     }
@@ -171,12 +411,302 @@ pub async fn list_api_keys(
     }
 }
 
+/// Revokes a key owned by the caller; `keys:admin` keys may revoke any key
 pub async fn revoke_api_key(
     State(state): State<AppState>,
+    Extension(caller): Extension<crate::db::ApiKey>,
     Path(id): Path<String>,
 ) -> impl IntoResponse {
+    use crate::auth::SCOPE_KEYS_ADMIN;
+
+    match state.db.get_key(&id).await {
+        Ok(Some(key)) if key.owner == caller.owner || caller.has_scope(SCOPE_KEYS_ADMIN) => {}
+        // Keys of other owners are not revealed to exist
+        Ok(_) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
     match state.db.revoke_key(&id).await {
         Ok(_) => StatusCode::OK.into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
     }
 }
+
+#[derive(serde::Deserialize)]
+pub struct QuotaRequest {
+    /// Requests allowed per UTC day; `null` restores the default quota
+    pub daily_quota: Option<i64>,
+}
+
+pub async fn set_api_key_quota(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<QuotaRequest>,
+) -> impl IntoResponse {
+    if request.daily_quota.is_some_and(|quota| quota < 0) {
+        return (StatusCode::BAD_REQUEST, "daily_quota must not be negative").into_response();
+    }
+    match state.db.set_daily_quota(&id, request.daily_quota).await {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct BonsaiCredentialsRequest {
+    pub api_url: String,
+    pub api_key: String,
+}
+
+/// Replaces the Bonsai credentials, taking effect from the next Bonsai request
+/// of every job, including those already proving
+pub async fn set_bonsai_credentials(
+    State(state): State<AppState>,
+    Json(request): Json<BonsaiCredentialsRequest>,
+) -> impl IntoResponse {
+    let is_http = request.api_url.starts_with("https://") || request.api_url.starts_with("http://");
+    if !is_http || request.api_key.is_empty() {
+        return (StatusCode::BAD_REQUEST, "api_url must be an HTTP(S) URL and api_key must not be empty").into_response();
+    }
+    state.bonsai.set(zkipfs_proof_core::BonsaiCredentials::new(request.api_url, request.api_key));
+    tracing::info!("Bonsai credentials rotated");
+    StatusCode::NO_CONTENT.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+    use crate::db::Db;
+
+    const BOUNDARY: &str = "zkipfs-test-boundary";
+
+    fn upload(file: &[u8]) -> Request<Body> {
+        let mut body = format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"content_selection\"\r\n\r\npattern:secret\r\n\
+             --{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"upload.txt\"\r\n\r\n"
+        ).into_bytes();
+        body.extend_from_slice(file);
+        body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+        Request::builder()
+            .method("POST")
+            .uri("/generate")
+            .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={BOUNDARY}"))
+            .header(header::CONTENT_LENGTH, body.len())
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    async fn generate(state: &AppState, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let router = with_body_limit(Router::new().route("/generate", post(generate_proof)), &state.limits)
+            .with_state(state.clone());
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn limited_state(name: &str) -> (tempfile::TempDir, AppState) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Db::new(dir.path().join(name).to_str().unwrap()).await.unwrap();
+        let state = AppState::new(db).with_limits(RequestLimits {
+            max_body_bytes: 1024,
+            ..RequestLimits::default()
+        });
+        (dir, state)
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_rejected_with_413() {
+        let (_dir, state) = limited_state("oversized.db").await;
+
+        let (status, body) = generate(&state, upload(&[b'x'; 4096])).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["error"], "payload_too_large");
+        assert!(state.jobs.read().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_small_upload_enqueued() {
+        let (_dir, state) = limited_state("accepted.db").await;
+
+        let (status, body) = generate(&state, upload(b"some secret content")).await;
+        assert_eq!(status, StatusCode::OK);
+        let job_id = body["job_id"].as_str().unwrap();
+        assert!(state.jobs.read().unwrap().contains_key(job_id));
+    }
+
+    #[tokio::test]
+    async fn test_upload_file_name_not_used_as_path() {
+        use axum::extract::FromRequest;
+
+        let body = format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"../../escaped.txt\"\r\n\r\n\
+             escaping content\r\n--{BOUNDARY}--\r\n"
+        );
+        let request = Request::builder()
+            .method("POST")
+            .uri("/generate")
+            .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={BOUNDARY}"))
+            .body(Body::from(body))
+            .unwrap();
+
+        let mut multipart = Multipart::from_request(request, &()).await.unwrap();
+        let request = read_proof_request(&mut multipart, 1024).await.unwrap();
+        assert_eq!(request.file_path, request._temp_dir.path().join("upload"));
+        assert_eq!(std::fs::read(&request.file_path).unwrap(), b"escaping content");
+    }
+
+    #[tokio::test]
+    async fn test_proofs_served_from_store() {
+        let (_dir, state) = limited_state("proofs.db").await;
+        let router = Router::new()
+            .route("/proofs", get(list_proofs))
+            .route("/proofs/:id", get(get_proof))
+            .with_state(state);
+        let get_uri = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let response = router.clone().oneshot(get_uri("/proofs")).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["proofs"], serde_json::json!([]));
+
+        let response = router.clone().oneshot(get_uri("/proofs/missing")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = router.oneshot(get_uri("/proofs/not..an..id")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_proofs_found_by_root_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Db::new(dir.path().join("lookup.db").to_str().unwrap()).await.unwrap();
+        let (_, key) = db.create_api_key("user", crate::auth::DEFAULT_SCOPES).await.unwrap();
+        let store = zkipfs_proof_core::store::SqliteStore::new(db.pool.clone()).await.unwrap();
+        let state = AppState::new(db).with_proof_store(Arc::new(store));
+
+        let file = dir.path().join("ledger.txt");
+        std::fs::write(&file, "Quarterly ledger: revenue up 4 percent").unwrap();
+        let mut generator = ProofGenerator::with_config(ProofConfig { use_cache: false, ..ProofConfig::default() })
+            .await
+            .unwrap();
+        let proof = generator
+            .generate_proof(&file, ContentSelection::Pattern { content: b"revenue".to_vec() })
+            .await
+            .unwrap();
+        state.proofs.put(&proof).await.unwrap();
+
+        let router = api_v1_router(state.clone()).with_state(state);
+        let find = |query: String| Request::builder()
+            .uri(format!("/proofs?{}", query))
+            .header("X-API-Key", key.as_str())
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.clone().oneshot(find(format!("root_hash={}", hex::encode(proof.root_hash)))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["proofs"], serde_json::json!([proof.id]));
+
+        let response = router.clone().oneshot(find(format!("root_hash={}", hex::encode([0u8; 32])))).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&body).unwrap()["proofs"], serde_json::json!([]));
+
+        let response = router.oneshot(find("root_hash=xyz".to_string())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_routes_require_scoped_key() {
+        let (_dir, state) = limited_state("legacy.db").await;
+        let (_, user_key) = state.db.create_api_key("user", crate::auth::DEFAULT_SCOPES).await.unwrap();
+        let (_, keys_key) = state.db.create_api_key("keys", &[crate::auth::SCOPE_KEYS_WRITE]).await.unwrap();
+        let router = legacy_router(state.clone()).with_state(state);
+        let get_uri = |uri: &str, key: Option<&str>| {
+            let request = Request::builder().uri(uri);
+            match key {
+                Some(key) => request.header("X-API-Key", key),
+                None => request,
+            }.body(Body::empty()).unwrap()
+        };
+
+        for uri in ["/proofs", "/proofs/missing", "/status/missing"] {
+            let response = router.clone().oneshot(get_uri(uri, None)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{}", uri);
+        }
+        let response = router.clone().oneshot(upload(b"unauthenticated")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = router.clone().oneshot(get_uri("/proofs", Some(&keys_key))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = router.oneshot(get_uri("/proofs", Some(&user_key))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_keys_revoked_only_by_owner_or_admin() {
+        use crate::auth::{SCOPE_KEYS_ADMIN, SCOPE_KEYS_WRITE};
+
+        let (_dir, state) = limited_state("revoke.db").await;
+        let (_, alice_key) = state.db.create_api_key("alice", &[SCOPE_KEYS_WRITE]).await.unwrap();
+        let (bob, bob_key) = state.db.create_api_key("bob", &[SCOPE_KEYS_WRITE]).await.unwrap();
+        let (_, admin_key) = state.db.create_api_key("admin", &[SCOPE_KEYS_WRITE, SCOPE_KEYS_ADMIN]).await.unwrap();
+        let (carol, _) = state.db.create_api_key("carol", crate::auth::DEFAULT_SCOPES).await.unwrap();
+        let router = api_v1_router(state.clone()).with_state(state.clone());
+        let revoke = |id: &str, key: &str| Request::builder()
+            .method("DELETE")
+            .uri(format!("/keys/{}", id))
+            .header("X-API-Key", key)
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.clone().oneshot(revoke(&bob.id, &alice_key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(!state.db.get_key(&bob.id).await.unwrap().unwrap().revoked);
+
+        let response = router.clone().oneshot(revoke(&carol.id, &admin_key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.db.get_key(&carol.id).await.unwrap().unwrap().revoked);
+
+        let response = router.oneshot(revoke(&bob.id, &bob_key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.db.get_key(&bob.id).await.unwrap().unwrap().revoked);
+    }
+
+    #[tokio::test]
+    async fn test_admin_rotates_bonsai_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Db::new(dir.path().join("bonsai.db").to_str().unwrap()).await.unwrap();
+        let (_, admin_key) = db.create_api_key("admin", &[crate::auth::SCOPE_KEYS_ADMIN]).await.unwrap();
+        let (_, user_key) = db.create_api_key("user", crate::auth::DEFAULT_SCOPES).await.unwrap();
+        let state = AppState::new(db);
+        state.bonsai.clear();
+
+        let rotate = |key: &str, body: &str| Request::builder()
+            .method("PUT")
+            .uri("/admin/bonsai-credentials")
+            .header("X-API-Key", key)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let router = api_v1_router(state.clone()).with_state(state.clone());
+        let body = r#"{"api_url":"https://api.bonsai.xyz","api_key":"rotated"}"#;
+
+        let response = router.clone().oneshot(rotate(&user_key, body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(!state.bonsai.is_set());
+
+        let response = router.clone().oneshot(rotate(&admin_key, r#"{"api_url":"bonsai","api_key":""}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = router.oneshot(rotate(&admin_key, body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let credentials = state.bonsai.current().unwrap();
+        assert_eq!(credentials.api_url, "https://api.bonsai.xyz");
+        assert_eq!(credentials.api_key.expose(), "rotated");
+    }
+}
@@ -0,0 +1,104 @@
+//! Liveness and readiness probes
+//!
+//! `/healthz` answers as long as the process is serving requests. `/readyz`
+//! runs the component checks registered with the state's `HealthChecker` and
+//! returns 503 with the per-component status when any of them is down.
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json},
+    routing::get,
+    Router,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use zkipfs_proof_core::{ipfs_client::IpfsClient, ProofGenerator};
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+}
+
+/// Registers the database, prover and IPFS checks
+///
+/// The prover is initialized once in the background; it reports down until
+/// that succeeds.
+pub fn register_checks(state: &AppState) {
+    register_database_check(state);
+
+    let prover_ready = Arc::new(AtomicBool::new(false));
+    let ready = prover_ready.clone();
+    tokio::spawn(async move {
+        match ProofGenerator::new().await {
+            Ok(_) => ready.store(true, Ordering::Release),
+            Err(e) => tracing::error!("Failed to initialize prover: {}", e),
+        }
+    });
+    state.health.register("prover", move || {
+        let ready = prover_ready.load(Ordering::Acquire);
+        async move {
+            if ready { Ok(()) } else { Err("Prover is not initialized".to_string()) }
+        }
+    });
+
+    state.health.register("ipfs", || async {
+        let client = IpfsClient::new().map_err(|e| e.to_string())?;
+        if client.is_online().await {
+            Ok(())
+        } else {
+            Err("IPFS node is unreachable".to_string())
+        }
+    });
+}
+
+fn register_database_check(state: &AppState) {
+    let db = state.db.clone();
+    state.health.register("database", move || {
+        let db = db.clone();
+        async move { db.ping().await.map_err(|e| e.to_string()) }
+    });
+}
+
+pub async fn healthz() -> impl IntoResponse {
+    (StatusCode::OK, "OK")
+}
+
+pub async fn readyz(State(state): State<AppState>) -> impl IntoResponse {
+    let report = state.health.check_readiness().await;
+    let status = if report.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+    use crate::db::Db;
+
+    async fn probe(state: &AppState, path: &str) -> StatusCode {
+        let request = Request::builder().uri(path).body(Body::empty()).unwrap();
+        routes().with_state(state.clone()).oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_failing_database_fails_readiness_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Db::new(dir.path().join("health.db").to_str().unwrap()).await.unwrap();
+        let state = AppState::new(db);
+        register_database_check(&state);
+
+        assert_eq!(probe(&state, "/readyz").await, StatusCode::OK);
+
+        state.db.pool.close().await;
+        assert_eq!(probe(&state, "/readyz").await, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(probe(&state, "/healthz").await, StatusCode::OK);
+    }
+}
@@ -0,0 +1,12 @@
+//! HTTP server for generating and serving zkIPFS proofs
+//!
+//! The modules are exposed as a library so that integration tests under
+//! `tests/` can drive the handlers directly; the binary in `main.rs` wires
+//! them into the running server.
+
+pub mod db;
+pub mod state;
+pub mod auth;
+pub mod handlers;
+pub mod health;
+pub mod request_id;
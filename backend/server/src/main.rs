@@ -1,26 +1,34 @@
 use axum::{
-    routing::{get, post, delete},
+    routing::get,
     Router,
     http::Method,
-    middleware,
 };
 use tower_http::cors::{CorsLayer, Any};
-use std::sync::{Arc, RwLock};
-use std::collections::HashMap;
 use tokio::net::TcpListener;
 use tower_governor::{governor::GovernorConfigBuilder, GovernorLayer};
 
-mod db;
-mod state;
-mod auth;
-mod handlers;
+use zkipfs_proof_server::{auth, handlers, health, request_id};
+use zkipfs_proof_server::state::{AppState, RequestLimits};
+use zkipfs_proof_server::db::Db;
+use zkipfs_proof_core::store::SqliteStore;
 
-use crate::state::AppState;
-use crate::db::Db;
+/// Logs as text, or as JSON objects carrying span fields such as `job_id`
+/// when `ZKIPFS_LOG_FORMAT=json`
+fn init_logging() {
+    if std::env::var("ZKIPFS_LOG_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("json")) {
+        tracing_subscriber::fmt()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .init();
+    } else {
+        tracing_subscriber::fmt::init();
+    }
+}
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt::init();
+    init_logging();
     
     // Initialize Database (SQLite)
     // Ensure the data directory exists or use a local file
@@ -34,16 +42,32 @@ async fn main() {
         }
     };
 
-    // Initialize state
-    let state = AppState {
-        jobs: Arc::new(RwLock::new(HashMap::new())),
-        db,
+    if let Ok(admin_key) = std::env::var(auth::ADMIN_KEY_ENV) {
+        if let Err(e) = db.ensure_api_key("admin", &admin_key, auth::ALL_SCOPES).await {
+            tracing::error!("Failed to create the admin API key: {}", e);
+            std::process::exit(1);
+        }
+        tracing::info!("Admin API key from {} holds every scope", auth::ADMIN_KEY_ENV);
+    }
+
+    let proofs = match SqliteStore::new(db.pool.clone()).await {
+        Ok(store) => std::sync::Arc::new(store),
+        Err(e) => {
+            tracing::error!("Failed to open proof store: {}", e);
+            std::process::exit(1);
+        }
     };
 
+    // Initialize state
+    let state = AppState::new(db)
+        .with_limits(RequestLimits::from_env())
+        .with_proof_store(proofs);
+    health::register_checks(&state);
+
     // Setup CORS
     let cors = CorsLayer::new()
         .allow_origin(Any)
-        .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
         .allow_headers(Any);
 
     // Rate Limiter Configuration (Global fallback)
@@ -58,24 +82,19 @@ async fn main() {
     );
 
     // Routes
-    let app = Router::new()
-        // Public / Legacy Routes (Rate limited by IP)
-        .route("/generate", post(handlers::generate_proof)) 
-        .route("/status/:id", get(handlers::get_status))
+    let routes = Router::new()
+        // Legacy Routes (Authenticated like /api/v1)
+        .merge(handlers::legacy_router(state.clone()))
         .route("/health", get(|| async { "OK" }))
+        .merge(health::routes())
         // Enterprise API v1 (Authenticated & Rate Limited)
-        .nest("/api/v1", Router::new() 
-            .route("/keys", post(handlers::create_api_key))
-            .route("/keys", get(handlers::list_api_keys)) // In real app, restrict this!
-            .route("/keys/:id", delete(handlers::revoke_api_key))
-            .route("/generate", post(handlers::generate_proof)) // Authenticated generation
-            // Add Auth Middleware to this nested router
-            .layer(middleware::from_fn_with_state(state.clone(), auth::auth::auth_middleware))
-        )
+        .nest("/api/v1", handlers::api_v1_router(state.clone()));
+    let app = handlers::with_body_limit(routes, &state.limits)
         .layer(GovernorLayer {
             config: Box::leak(governor_conf),
         })
         .layer(cors)
+        .layer(axum::middleware::from_fn(request_id::request_id_middleware))
         .with_state(state);
 
     let addr = "0.0.0.0:3000";
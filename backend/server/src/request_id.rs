@@ -0,0 +1,193 @@
+//! Request ids for correlating logs
+//!
+//! Every request is handled inside a `request` span carrying its id, which
+//! the spans of proof jobs and of the core library's generator nest under.
+//! The id is returned in the `X-Request-Id` header and in JSON error bodies.
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Header a request id is read from and returned in
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Largest error body that has the request id added to it
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// Id correlating a request with the logs it caused, available to handlers
+/// as an extension
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Keeps the caller's id if it is a short token, so ids can be traced
+    /// across services, and generates one otherwise
+    fn for_request(request: &Request) -> Self {
+        let supplied = request.headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|id| {
+                (1..=64).contains(&id.len())
+                    && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+            });
+        Self(supplied.map_or_else(|| Uuid::new_v4().to_string(), str::to_string))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Handles the request inside a `request` span carrying its id, returns the
+/// id in the `X-Request-Id` header and adds it to JSON error bodies
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = RequestId::for_request(&request);
+    request.extensions_mut().insert(request_id.clone());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id.as_str(),
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    let response = async {
+        let response = next.run(request).await;
+        tracing::info!(status = response.status().as_u16(), "Handled request");
+        response
+    }
+    .instrument(span)
+    .await;
+
+    let mut response = with_request_id_in_error_body(response, &request_id).await;
+    if let Ok(value) = HeaderValue::from_str(request_id.as_str()) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+async fn with_request_id_in_error_body(response: Response, request_id: &RequestId) -> Response {
+    let is_json = response.headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    let is_error = response.status().is_client_error() || response.status().is_server_error();
+    if !is_json || !is_error {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_ERROR_BODY_BYTES).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(mut fields)) => {
+            fields.insert("request_id".to_string(), request_id.as_str().into());
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(serde_json::Value::Object(fields).to_string())
+        }
+        _ => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::Db, handlers, state::AppState};
+    use axum::{http::StatusCode, routing::post, Router};
+    use std::sync::{Arc, Mutex};
+    use tower::ServiceExt;
+
+    const BOUNDARY: &str = "zkipfs-request-id-boundary";
+
+    /// Collects the formatted log output
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl CapturedLogs {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).lines().map(str::to_string).collect()
+        }
+    }
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for CapturedLogs {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    fn upload(fields: &str) -> axum::http::Request<Body> {
+        let body = format!("{fields}--{BOUNDARY}--\r\n");
+        axum::http::Request::builder()
+            .method("POST")
+            .uri("/generate")
+            .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={BOUNDARY}"))
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_request_id_in_response_and_logs() {
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::fmt().json().with_writer(logs.clone()).finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Db::new(dir.path().join("request_id.db").to_str().unwrap()).await.unwrap();
+        let router = Router::new()
+            .route("/generate", post(handlers::generate_proof))
+            .layer(axum::middleware::from_fn(request_id_middleware))
+            .with_state(AppState::new(db));
+
+        let response = router.clone().oneshot(upload(&format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"content_selection\"\r\n\r\npattern:secret\r\n\
+             --{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"upload.txt\"\r\n\r\n\
+             some secret content\r\n"
+        ))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let request_id = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        assert_eq!(body_json(response).await["request_id"], request_id.as_str());
+
+        // The proof job logs from the core library under the request's span
+        let mut core_line = None;
+        for _ in 0..300 {
+            core_line = logs.lines().into_iter()
+                .find(|line| line.contains("\"target\":\"zkipfs_proof_core") && line.contains(&request_id));
+            if core_line.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert!(core_line.is_some(), "no core log line carries request id {}", request_id);
+
+        // Error bodies carry the id of their request too
+        let response = router.oneshot(upload("")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error_id = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        assert_ne!(error_id, request_id);
+        assert_eq!(body_json(response).await["request_id"], error_id.as_str());
+    }
+}
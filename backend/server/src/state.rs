@@ -1,12 +1,85 @@
 use std::sync::{Arc, RwLock};
 use std::collections::HashMap;
+use std::time::Duration;
 use serde::{Serialize, Deserialize};
+use zkipfs_proof_core::bonsai::BonsaiCredentialStore;
+use zkipfs_proof_core::monitoring::HealthChecker;
+use zkipfs_proof_core::store::{MemoryStore, ProofStore};
 use crate::db::Db;
 
 #[derive(Clone)]
 pub struct AppState {
     pub jobs: Arc<RwLock<HashMap<String, Job>>>,
     pub db: Db,
+    /// Where completed proofs are kept
+    pub proofs: Arc<dyn ProofStore>,
+    pub health: HealthChecker,
+    pub limits: RequestLimits,
+    /// Bonsai credentials jobs prove with, replaceable through the admin API
+    pub bonsai: BonsaiCredentialStore,
+}
+
+impl AppState {
+    pub fn new(db: Db) -> Self {
+        Self {
+            jobs: Arc::new(RwLock::new(HashMap::new())),
+            db,
+            proofs: Arc::new(MemoryStore::new()),
+            health: HealthChecker::new(),
+            limits: RequestLimits::default(),
+            bonsai: BonsaiCredentialStore::from_env(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: RequestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_proof_store(mut self, proofs: Arc<dyn ProofStore>) -> Self {
+        self.proofs = proofs;
+        self
+    }
+}
+
+/// Bounds on the size of proof requests and the time spent proving them
+#[derive(Clone, Debug)]
+pub struct RequestLimits {
+    /// Largest request body accepted, in bytes
+    pub max_body_bytes: usize,
+    /// Largest uploaded file that is proven, in bytes
+    pub max_file_size_bytes: u64,
+    /// How long a single job may spend proving
+    pub proving_timeout: Duration,
+}
+
+impl RequestLimits {
+    /// Reads `ZKIPFS_MAX_BODY_BYTES`, `ZKIPFS_MAX_FILE_SIZE_BYTES` and
+    /// `ZKIPFS_PROVING_TIMEOUT_SECS`, keeping the default for unset or invalid values
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok()?.parse().ok()
+        }
+
+        let defaults = Self::default();
+        Self {
+            max_body_bytes: var("ZKIPFS_MAX_BODY_BYTES").unwrap_or(defaults.max_body_bytes),
+            max_file_size_bytes: var("ZKIPFS_MAX_FILE_SIZE_BYTES").unwrap_or(defaults.max_file_size_bytes),
+            proving_timeout: var("ZKIPFS_PROVING_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.proving_timeout),
+        }
+    }
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 100 * 1024 * 1024,
+            max_file_size_bytes: 100 * 1024 * 1024,
+            proving_timeout: Duration::from_secs(600),
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -23,4 +96,7 @@ pub struct Job {
     pub id: String,
     pub status: JobStatus,
     pub created_at: u64,
+    /// Id of the request that created the job
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
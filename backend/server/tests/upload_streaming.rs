@@ -0,0 +1,75 @@
+//! Checks that `/generate` uploads are streamed to disk rather than buffered
+//!
+//! The test installs a global allocator that records the largest allocation,
+//! so it runs in its own binary where no other test allocates alongside it.
+
+use axum::{
+    body::Body,
+    extract::{FromRequest, Multipart},
+    http::{header, Request},
+};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use zkipfs_proof_server::handlers::read_proof_request;
+
+const BOUNDARY: &str = "zkipfs-test-boundary";
+
+/// Records the largest single allocation, which is at least the file
+/// size if an upload is ever buffered whole
+struct LargestAllocation;
+
+static LARGEST_ALLOCATION: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for LargestAllocation {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LARGEST_ALLOCATION.fetch_max(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        LARGEST_ALLOCATION.fetch_max(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: LargestAllocation = LargestAllocation;
+
+#[tokio::test]
+async fn test_large_upload_streamed_to_disk() {
+    const FILE_SIZE: usize = 32 * 1024 * 1024;
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    // The body is generated chunk by chunk so the test never holds the whole file either
+    let head = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"large.bin\"\r\n\r\n"
+    );
+    let tail = format!("\r\n--{BOUNDARY}--\r\n");
+    let chunks = std::iter::once(axum::body::Bytes::from(head))
+        .chain((0..FILE_SIZE / CHUNK_SIZE).map(|_| axum::body::Bytes::from(vec![b'x'; CHUNK_SIZE])))
+        .chain(std::iter::once(axum::body::Bytes::from(tail)))
+        .map(Ok::<_, std::io::Error>);
+    let request = Request::builder()
+        .method("POST")
+        .uri("/generate")
+        .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={BOUNDARY}"))
+        .body(Body::from_stream(futures::stream::iter(chunks)))
+        .unwrap();
+
+    LARGEST_ALLOCATION.store(0, Ordering::Relaxed);
+    let mut multipart = Multipart::from_request(request, &()).await.unwrap();
+    let request = read_proof_request(&mut multipart, FILE_SIZE as u64).await.unwrap();
+
+    assert_eq!(request.file_size, FILE_SIZE as u64);
+    assert_eq!(std::fs::metadata(&request.file_path).unwrap().len(), FILE_SIZE as u64);
+    assert!(LARGEST_ALLOCATION.load(Ordering::Relaxed) < FILE_SIZE / 4);
+
+    // The upload goes away with the request
+    let file_path = request.file_path.clone();
+    drop(request);
+    assert!(!file_path.exists());
+}